@@ -19,6 +19,7 @@ export enum FuncKind {
   SchemaVariantDefinition = "SchemaVariantDefinition",
   Unknown = "Unknown",
   Management = "Management",
+  Policy = "Policy",
 }
 
 export enum CustomizableFuncKind {
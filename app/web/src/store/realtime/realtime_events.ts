@@ -15,7 +15,7 @@ import {
   SchemaId,
   SchemaVariantId,
 } from "@/api/sdf/dal/schema";
-import { ActionId } from "@/api/sdf/dal/action";
+import { ActionId, ActionState } from "@/api/sdf/dal/action";
 import { ViewDescription, ViewId } from "@/api/sdf/dal/views";
 import { WorkspacePk } from "../workspaces.store";
 import { StatusUpdate } from "../status.store";
@@ -265,6 +265,10 @@ export type WsEventPayloadMap = {
   ActionsListUpdated: {
     changeSetId: ChangeSetId;
   };
+  ActionStateUpdated: {
+    actionId: ActionId;
+    state: ActionState;
+  };
 
   ActionAdded: {
     componentId: ComponentId;
@@ -372,6 +376,11 @@ export type WsEventPayloadMap = {
     funcRunLogId: FuncRunLogId;
     actionId?: ActionId;
   };
+  FuncRunFinished: {
+    funcRunId: FuncRunId;
+    actionId?: ActionId;
+    succeeded: boolean;
+  };
   ViewUpdated: { view: ViewDescription };
   ViewDeleted: { viewId: ViewId };
   ViewCreated: { view: ViewDescription };
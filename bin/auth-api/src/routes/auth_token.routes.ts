@@ -7,6 +7,7 @@ import { CustomRouteContext } from "../custom-state";
 import {
   createSdfAuthToken,
   decodeSdfAuthToken,
+  SDF_AUTH_TOKEN_PERMISSIONS,
 } from "../services/auth.service";
 import {
   AuthTokenId,
@@ -40,11 +41,16 @@ router.post("/workspaces/:workspaceId/authTokens", async (ctx) => {
 
   // TODO - this should also get an expiration instead of just defaulting to 1d!
   // Get params from body
-  const { name, expiration } = validate(
+  const {
+    name, expiration, permissions, changeSetIds, viewIds,
+  } = validate(
     ctx.request.body,
     z.object({
       name: z.optional(z.string()),
       expiration: z.optional(z.string()),
+      permissions: z.optional(z.array(z.enum(SDF_AUTH_TOKEN_PERMISSIONS))),
+      changeSetIds: z.optional(z.array(z.string())),
+      viewIds: z.optional(z.array(z.string())),
     }),
   );
 
@@ -60,6 +66,9 @@ router.post("/workspaces/:workspaceId/authTokens", async (ctx) => {
     userId,
     workspaceId: workspace.id,
     role: "automation",
+    permissions,
+    changeSetIds,
+    viewIds,
   }, {
     expiresIn,
     jwtid: ulid(),
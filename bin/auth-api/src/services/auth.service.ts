@@ -50,12 +50,24 @@ export async function decodeAuthToken(token: string) {
 // and between that SDF instance and this auth api if necessary
 export type SdfAuthTokenPayload = SdfAuthTokenPayloadV1 | SdfAuthTokenPayloadV2;
 export type SdfAuthTokenRole = "web" | "automation";
+export const SDF_AUTH_TOKEN_PERMISSIONS = [
+  "readOnly",
+  "changeSetWrite",
+  "apply",
+  "actionExecute",
+  "secretRead",
+] as const;
+export type SdfAuthTokenPermission = typeof SDF_AUTH_TOKEN_PERMISSIONS[number];
 
 interface SdfAuthTokenPayloadV2 {
   version: "2";
   userId: UserId;
   workspaceId: WorkspaceId;
   role: SdfAuthTokenRole;
+  // Optional scope for automation tokens; sdf grants everything the role allows when omitted
+  permissions?: SdfAuthTokenPermission[];
+  changeSetIds?: string[];
+  viewIds?: string[];
 }
 
 // Old auth token versions
@@ -83,6 +83,18 @@ pub(crate) struct Args {
     /// Enables the audit logs app
     #[arg(long)]
     pub(crate) enable_audit_logs_app: Option<bool>,
+
+    /// Enables the webhooks app
+    #[arg(long)]
+    pub(crate) enable_webhooks_app: Option<bool>,
+
+    /// The base URL of the web app, used to link to change sets from Slack messages
+    #[arg(long)]
+    pub(crate) web_url: Option<String>,
+
+    /// Symmetric crypto active key as base64 string
+    #[arg(long)]
+    pub(crate) symmetric_crypto_active_key_base64: Option<SensitiveString>,
 }
 
 impl TryFrom<Args> for Config {
@@ -112,6 +124,18 @@ impl TryFrom<Args> for Config {
             if let Some(enable_audit_logs_app) = args.enable_audit_logs_app {
                 config_map.set("enable_audit_logs_app", enable_audit_logs_app);
             }
+            if let Some(enable_webhooks_app) = args.enable_webhooks_app {
+                config_map.set("enable_webhooks_app", enable_webhooks_app);
+            }
+            if let Some(web_url) = args.web_url {
+                config_map.set("web_url", web_url);
+            }
+            if let Some(base64) = args.symmetric_crypto_active_key_base64 {
+                config_map.set(
+                    "symmetric_crypto_service.active_key_base64",
+                    base64.to_string(),
+                );
+            }
         })?
         .try_into()
     }
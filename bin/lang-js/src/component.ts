@@ -13,4 +13,5 @@ export interface Geometry {
 export interface ComponentWithGeometry {
   properties: Record<string, unknown>;
   geometry: { [key: string]: Geometry };
+  tags?: Record<string, string>;
 }
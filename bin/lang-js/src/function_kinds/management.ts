@@ -18,6 +18,9 @@ export interface ManagementFunc extends Func {
   components: {
     [key: string]: ComponentWithGeometry;
   }
+  selectedComponents?: {
+    [key: string]: ComponentWithGeometry;
+  }
 }
 
 export type ManagementFuncResult =
@@ -73,14 +76,14 @@ export interface ManagementFuncResultFailure extends ResultFailure { }
 async function execute(
   vm: NodeVM,
   { executionId }: RequestCtx,
-  { thisComponent, components, currentView }: ManagementFunc,
+  { thisComponent, components, selectedComponents, currentView }: ManagementFunc,
   code: string,
 ): Promise<ManagementFuncResult> {
   let managementResult: Record<string, unknown> | undefined | null;
   try {
     const runner = vm.run(code);
     managementResult = await new Promise((resolve) => {
-      runner({ thisComponent, components, currentView }, (resolution: Record<string, unknown>) => resolve(resolution));
+      runner({ thisComponent, components, selectedComponents: selectedComponents ?? {}, currentView }, (resolution: Record<string, unknown>) => resolve(resolution));
     });
   } catch (err) {
     return failureExecution(err as Error, executionId);
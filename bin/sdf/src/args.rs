@@ -210,6 +210,14 @@ pub(crate) struct Args {
     )]
     pub(crate) create_workspace_allowlist: Vec<WorkspacePermissions>,
 
+    /// Prunes func runs past their workspace's retention policy. Enable on one instance only.
+    #[arg(
+        long = "prune-func-runs",
+        default_value = "false",
+        env = "SI_PRUNE_FUNC_RUNS"
+    )]
+    pub(crate) prune_func_runs: bool,
+
     /// Override for the auth api url
     #[arg(long, env = "SI_AUTH_API_URL")]
     pub(crate) auth_api_url: Option<String>,
@@ -412,6 +420,10 @@ impl TryFrom<Args> for Config {
                 );
             }
 
+            if args.prune_func_runs {
+                config_map.set("prune_func_runs", true);
+            }
+
             config_map.set("nats.connection_name", NAME);
             config_map.set("pg.application_name", NAME);
             config_map.set("layer_db_config.pg_pool_config.application_name", NAME);
@@ -0,0 +1,317 @@
+use std::path::PathBuf;
+
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use sdf_server::{Config, ConfigError, ConfigFile, StandardConfigFile};
+use si_id::{ChangeSetId, FuncRunId, WorkspacePk};
+use si_service::prelude::*;
+
+const NAME: &str = "si-admin";
+
+/// Configuration is loaded the same way as for sdf, so that both connect to the same services.
+const CONFIG_NAME: &str = "sdf";
+
+/// Parse, validate, and return the CLI arguments as a typed struct.
+pub(crate) fn parse() -> Args {
+    Args::parse()
+}
+
+/// The System Initiative operator CLI.
+///
+/// Performs the operations of the admin API directly against PostgreSQL, NATS and the layer db,
+/// using the same configuration as sdf.
+#[derive(Parser, Debug)]
+#[command(name = NAME, max_term_width = 100)]
+pub(crate) struct Args {
+    /// Sets the verbosity mode.
+    ///
+    /// Multiple -v options increase verbosity. The maximum is 6.
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count, global = true)]
+    pub(crate) verbose: u8,
+
+    /// Disables ANSI coloring in log output, even if standard output refers to a terminal/TTY.
+    ///
+    /// For more details, visit: <http://no-color.org/>.
+    #[arg(
+        long = "no-color",
+        default_value = "false",
+        env = "SI_NO_COLOR",
+        hide_env_values = true,
+        conflicts_with = "force_color",
+        global = true
+    )]
+    pub(crate) no_color: bool,
+
+    /// Forces ANSI coloring, even if standard output refers to a terminal/TTY.
+    ///
+    /// For more details, visit: <http://no-color.org/>.
+    #[arg(
+        long = "force-color",
+        default_value = "false",
+        env = "SI_FORCE_COLOR",
+        hide_env_values = true,
+        conflicts_with = "no_color",
+        global = true
+    )]
+    pub(crate) force_color: bool,
+
+    /// Prints telemetry logging as JSON lines.
+    ///
+    /// For more details, visit: <https://jsonlines.org/>.
+    #[arg(
+        long = "log-json",
+        default_value = "false",
+        env = "SI_LOG_JSON",
+        hide_env_values = true,
+        global = true
+    )]
+    pub(crate) log_json: bool,
+
+    /// How results are printed
+    #[arg(long, short = 'o', value_enum, default_value = "table", global = true)]
+    pub(crate) output: Output,
+
+    /// PostgreSQL connection pool dbname [example: myapp]
+    #[arg(long, global = true)]
+    pub(crate) pg_dbname: Option<String>,
+
+    /// PostgreSQL connection pool dbname for layer_db [example: melons]
+    #[arg(long, global = true)]
+    pub(crate) layer_db_pg_dbname: Option<String>,
+
+    /// PostgreSQL connection pool hostname [example: prod.db.example.com]
+    #[arg(long, global = true)]
+    pub(crate) pg_hostname: Option<String>,
+
+    /// PostgreSQL connection pool port [example: 5432]
+    #[arg(long, global = true)]
+    pub(crate) pg_port: Option<u16>,
+
+    /// PostgreSQL connection pool user [example: dbuser]
+    #[arg(long, global = true)]
+    pub(crate) pg_user: Option<String>,
+
+    /// PostgreSQL connection certification path
+    #[arg(long, global = true)]
+    pub(crate) pg_cert_path: Option<PathBuf>,
+
+    /// PostgreSQL connection certification base64 string
+    #[arg(long, global = true)]
+    pub(crate) pg_cert_base64: Option<SensitiveString>,
+
+    /// NATS connection URL [example: demo.nats.io]
+    #[arg(long, global = true)]
+    pub(crate) nats_url: Option<String>,
+
+    /// NATS credentials string
+    #[arg(long, allow_hyphen_values = true, global = true)]
+    pub(crate) nats_creds: Option<SensitiveString>,
+
+    /// NATS credentials file
+    #[arg(long, global = true)]
+    pub(crate) nats_creds_path: Option<PathBuf>,
+
+    /// Veritech encryption key file location [default: /run/sdf/veritech_encryption.key]
+    #[arg(long, global = true)]
+    pub(crate) veritech_encryption_key_path: Option<PathBuf>,
+
+    /// Symmetric crypto active key as base64 string
+    #[arg(long, global = true)]
+    pub(crate) symmetric_crypto_active_key_base64: Option<SensitiveString>,
+
+    /// The path at which the layer db cache is created/used on disk [e.g. /banana/]
+    #[arg(long, global = true)]
+    pub(crate) layer_db_disk_path: Option<String>,
+
+    /// The base URL for the module-index API server
+    #[arg(long, env = "SI_MODULE_INDEX_URL", global = true)]
+    pub(crate) module_index_url: Option<String>,
+
+    #[command(subcommand)]
+    pub(crate) command: Command,
+}
+
+/// How results are printed.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub(crate) enum Output {
+    /// Aligned columns, for people.
+    Table,
+    /// Pretty printed JSON, for scripts.
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Inspects and configures workspaces
+    #[command(subcommand)]
+    Workspace(WorkspaceCommand),
+
+    /// Exports and imports the workspace snapshots of change sets
+    #[command(subcommand)]
+    Snapshot(SnapshotCommand),
+
+    /// Kills a running func execution
+    KillExecution {
+        /// The func run to kill
+        func_run_id: FuncRunId,
+    },
+
+    /// Replays a recorded func run against a local cyclone and prints its output and result
+    /// beside the original's
+    ReplayFuncRun {
+        /// The func run to replay
+        func_run_id: FuncRunId,
+
+        /// Path to the unix domain socket of the cyclone to replay the func run against
+        #[arg(long)]
+        cyclone_socket: PathBuf,
+
+        /// Path to a JSON file mapping secret names to the values given to the func run's before
+        /// functions. Secrets missing from it are redacted
+        #[arg(long)]
+        secrets_path: Option<PathBuf>,
+    },
+
+    /// Caches any new builtin modules from the module index
+    UpdateModuleCache,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum WorkspaceCommand {
+    /// Searches workspaces by id, name, owner or snapshot address, most recently created first
+    Search {
+        /// What to search for. Lists the most recently created workspaces when omitted
+        query: Option<String>,
+
+        /// The most workspaces to list
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Shows a workspace along with all of its change sets
+    Inspect { workspace_id: WorkspacePk },
+
+    /// Sets the component concurrency limit of a workspace
+    SetConcurrencyLimit {
+        workspace_id: WorkspacePk,
+
+        /// The limit to set. Resets the workspace to the default limit when omitted
+        concurrency_limit: Option<i32>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum SnapshotCommand {
+    /// Writes the workspace snapshot of a change set to a file
+    Export {
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+
+        /// The file to write the snapshot to
+        #[arg(long, short = 'f')]
+        file: PathBuf,
+    },
+
+    /// Replaces the workspace snapshot of a change set with one read from a file
+    Import {
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+
+        /// The file to read the snapshot from, as written by `snapshot export`
+        #[arg(long, short = 'f')]
+        file: PathBuf,
+    },
+}
+
+impl TryFrom<&Args> for Config {
+    type Error = ConfigError;
+
+    fn try_from(args: &Args) -> Result<Self, Self::Error> {
+        ConfigFile::layered_load(CONFIG_NAME, |config_map| {
+            if let Some(dbname) = &args.pg_dbname {
+                config_map.set("pg.dbname", dbname.clone());
+            }
+            if let Some(layer_cache_pg_dbname) = &args.layer_db_pg_dbname {
+                config_map.set(
+                    "layer_db_config.pg_pool_config.dbname",
+                    layer_cache_pg_dbname.clone(),
+                );
+            }
+            if let Some(hostname) = &args.pg_hostname {
+                config_map.set("pg.hostname", hostname.clone());
+                config_map.set("layer_db_config.pg_pool_config.hostname", hostname.clone());
+            }
+            if let Some(port) = args.pg_port {
+                config_map.set("pg.port", i64::from(port));
+                config_map.set("layer_db_config.pg_pool_config.port", i64::from(port));
+            }
+            if let Some(user) = &args.pg_user {
+                config_map.set("pg.user", user.clone());
+                config_map.set("layer_db_config.pg_pool_config.user", user.clone());
+            }
+            if let Some(cert_path) = &args.pg_cert_path {
+                config_map.set("pg.certificate_path", cert_path.display().to_string());
+                config_map.set(
+                    "layer_db_config.pg_pool_config.certificate_path",
+                    cert_path.display().to_string(),
+                );
+            }
+            if let Some(cert) = &args.pg_cert_base64 {
+                config_map.set("pg.certificate_base64", cert.to_string());
+                config_map.set(
+                    "layer_db_config.pg_pool_config.certificate_base64",
+                    cert.to_string(),
+                );
+            }
+            if let Some(url) = &args.nats_url {
+                config_map.set("nats.url", url.clone());
+                config_map.set("layer_db_config.nats_config.url", url.clone());
+            }
+            if let Some(creds) = &args.nats_creds {
+                config_map.set("nats.creds", creds.to_string());
+                config_map.set("layer_db_config.nats_config.creds", creds.to_string());
+            }
+            if let Some(creds_file) = &args.nats_creds_path {
+                config_map.set("nats.creds_file", creds_file.display().to_string());
+                config_map.set(
+                    "layer_db_config.nats_config.creds_file",
+                    creds_file.display().to_string(),
+                );
+            }
+            if let Some(veritech_encryption_key_file) = &args.veritech_encryption_key_path {
+                config_map.set(
+                    "crypto.encryption_key_file",
+                    veritech_encryption_key_file.display().to_string(),
+                );
+            }
+            if let Some(base64) = &args.symmetric_crypto_active_key_base64 {
+                config_map.set(
+                    "symmetric_crypto_service.active_key_base64",
+                    base64.to_string(),
+                );
+            }
+            if let Some(layer_cache_disk_path) = &args.layer_db_disk_path {
+                config_map.set("layer_db_config.disk_path", layer_cache_disk_path.clone());
+            }
+            if let Some(module_index_url) = &args.module_index_url {
+                config_map.set("module_index_url", module_index_url.clone());
+            }
+
+            config_map.set("nats.connection_name", NAME);
+            config_map.set("pg.application_name", NAME);
+            config_map.set("layer_db_config.pg_pool_config.application_name", NAME);
+            config_map.set("layer_db_config.nats_config.connection_name", NAME);
+        })
+        .and_then(Config::try_from_for_tool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_command() {
+        use clap::CommandFactory;
+        Args::command().debug_assert()
+    }
+}
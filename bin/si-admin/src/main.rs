@@ -0,0 +1,305 @@
+use std::{io, time::Duration};
+
+use sdf_server::{Config, Operator, OperatorError};
+use serde_json::json;
+use si_service::{color_eyre, prelude::*, rt, shutdown, startup, telemetry_application};
+use thiserror::Error;
+
+use crate::{
+    args::{Command, Output, SnapshotCommand, WorkspaceCommand},
+    output::{or_dash, print_fields, print_table},
+};
+
+mod args;
+mod output;
+
+const BIN_NAME: &str = env!("CARGO_BIN_NAME");
+
+const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+enum AdminError {
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("operator error: {0}")]
+    Operator(#[from] OperatorError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+type AdminResult<T> = std::result::Result<T, AdminError>;
+
+fn main() -> Result<()> {
+    rt::block_on(BIN_NAME, async_main())
+}
+
+async fn async_main() -> Result<()> {
+    let main_tracker = TaskTracker::new();
+    let main_token = CancellationToken::new();
+    let helping_tasks_tracker = TaskTracker::new();
+    let helping_tasks_token = CancellationToken::new();
+    let telemetry_tracker = TaskTracker::new();
+    let telemetry_token = CancellationToken::new();
+
+    color_eyre::install()?;
+    let args = args::parse();
+    let (mut telemetry, telemetry_shutdown) = {
+        let config = TelemetryConfig::builder()
+            .force_color(args.force_color.then_some(true))
+            .no_color(args.no_color.then_some(true))
+            .console_log_format(
+                args.log_json
+                    .then_some(ConsoleLogFormat::Json)
+                    .unwrap_or_default(),
+            )
+            .service_name(BIN_NAME)
+            .service_namespace("si")
+            .log_env_var_prefix("SI")
+            .app_modules(vec!["si_admin", "sdf_server"])
+            .interesting_modules(vec![
+                "dal",
+                "si_data_nats",
+                "si_data_pg",
+                "si_layer_cache",
+                "si_service",
+            ])
+            .build()?;
+
+        telemetry_application::init(config, &telemetry_tracker, telemetry_token.clone())?
+    };
+
+    startup::startup(BIN_NAME).await?;
+
+    if args.verbose > 0 {
+        telemetry
+            .set_verbosity_and_wait(args.verbose.into())
+            .await?;
+    }
+    debug!(arguments =?args, "parsed cli arguments");
+
+    let config = Config::try_from(&args)?;
+    debug!(?config, "computed configuration");
+
+    let operator =
+        Operator::from_config(config, &helping_tasks_tracker, helping_tasks_token.clone()).await?;
+
+    let handle = main_tracker.spawn(run(operator, args.command, args.output));
+
+    shutdown::graceful_with_handle(handle)
+        .group(main_tracker, main_token)
+        .group(helping_tasks_tracker, helping_tasks_token)
+        .group(telemetry_tracker, telemetry_token)
+        .telemetry_guard(telemetry_shutdown.into_future())
+        .timeout(GRACEFUL_SHUTDOWN_TIMEOUT)
+        .wait()
+        .await
+        .map_err(Into::into)
+}
+
+async fn run(operator: Operator, command: Command, output: Output) -> AdminResult<()> {
+    match command {
+        Command::Workspace(WorkspaceCommand::Search { query, limit }) => {
+            let workspaces = operator.search_workspaces(query.as_deref(), limit).await?;
+
+            match output {
+                Output::Json => print_json(&workspaces)?,
+                Output::Table => print_table(
+                    [
+                        "ID",
+                        "NAME",
+                        "DEFAULT CHANGE SET",
+                        "SNAPSHOT VERSION",
+                        "CONCURRENCY LIMIT",
+                        "CREATED AT",
+                    ],
+                    workspaces
+                        .into_iter()
+                        .map(|workspace| {
+                            [
+                                workspace.id.to_string(),
+                                workspace.name,
+                                workspace.default_change_set_id.to_string(),
+                                workspace.snapshot_version.to_string(),
+                                or_dash(workspace.component_concurrency_limit),
+                                workspace.timestamp.created_at.to_rfc3339(),
+                            ]
+                        })
+                        .collect(),
+                ),
+            }
+        }
+        Command::Workspace(WorkspaceCommand::Inspect { workspace_id }) => {
+            let inspection = operator.inspect_workspace(workspace_id).await?;
+
+            match output {
+                Output::Json => print_json(&inspection)?,
+                Output::Table => {
+                    let workspace = inspection.workspace;
+                    print_fields([
+                        ("id", workspace.id.to_string()),
+                        ("name", workspace.name),
+                        (
+                            "default change set",
+                            workspace.default_change_set_id.to_string(),
+                        ),
+                        ("snapshot version", workspace.snapshot_version.to_string()),
+                        (
+                            "concurrency limit",
+                            or_dash(workspace.component_concurrency_limit),
+                        ),
+                        ("created at", workspace.timestamp.created_at.to_rfc3339()),
+                        ("updated at", workspace.timestamp.updated_at.to_rfc3339()),
+                    ]);
+                    println!();
+                    print_table(
+                        [
+                            "CHANGE SET",
+                            "NAME",
+                            "STATUS",
+                            "BASE CHANGE SET",
+                            "SNAPSHOT ADDRESS",
+                            "UPDATED AT",
+                        ],
+                        inspection
+                            .change_sets
+                            .into_iter()
+                            .map(|change_set| {
+                                [
+                                    change_set.id.to_string(),
+                                    change_set.name,
+                                    change_set.status.to_string(),
+                                    or_dash(change_set.base_change_set_id),
+                                    change_set.workspace_snapshot_address.to_string(),
+                                    change_set.updated_at.to_rfc3339(),
+                                ]
+                            })
+                            .collect(),
+                    );
+                }
+            }
+        }
+        Command::Workspace(WorkspaceCommand::SetConcurrencyLimit {
+            workspace_id,
+            concurrency_limit,
+        }) => {
+            let concurrency_limit = operator
+                .set_concurrency_limit(workspace_id, concurrency_limit)
+                .await?;
+
+            match output {
+                Output::Json => print_json(&json!({ "concurrencyLimit": concurrency_limit }))?,
+                Output::Table => print_fields([(
+                    "concurrency limit",
+                    concurrency_limit
+                        .map_or_else(|| "default".to_owned(), |limit| limit.to_string()),
+                )]),
+            }
+        }
+        Command::Snapshot(SnapshotCommand::Export {
+            workspace_id,
+            change_set_id,
+            file,
+        }) => {
+            let bytes = operator
+                .export_snapshot(workspace_id, change_set_id)
+                .await?;
+            tokio::fs::write(&file, &bytes).await?;
+
+            match output {
+                Output::Json => print_json(&json!({
+                    "file": file,
+                    "bytes": bytes.len(),
+                }))?,
+                Output::Table => print_fields([
+                    ("file", file.display().to_string()),
+                    ("bytes", bytes.len().to_string()),
+                ]),
+            }
+        }
+        Command::Snapshot(SnapshotCommand::Import {
+            workspace_id,
+            change_set_id,
+            file,
+        }) => {
+            let bytes = tokio::fs::read(&file).await?;
+            let workspace_snapshot_address = operator
+                .import_snapshot(workspace_id, change_set_id, bytes)
+                .await?;
+
+            match output {
+                Output::Json => print_json(&json!({
+                    "workspaceSnapshotAddress": workspace_snapshot_address,
+                }))?,
+                Output::Table => print_fields([(
+                    "workspace snapshot address",
+                    workspace_snapshot_address.to_string(),
+                )]),
+            }
+        }
+        Command::KillExecution { func_run_id } => {
+            operator.kill_execution(func_run_id).await?;
+
+            match output {
+                Output::Json => print_json(&json!({ "funcRunId": func_run_id, "killed": true }))?,
+                Output::Table => print_fields([("killed func run", func_run_id.to_string())]),
+            }
+        }
+        Command::ReplayFuncRun {
+            func_run_id,
+            cyclone_socket,
+            secrets_path,
+        } => {
+            let report = operator
+                .replay_func_run(func_run_id, cyclone_socket, secrets_path)
+                .await?;
+
+            match output {
+                Output::Json => print_json(&report)?,
+                Output::Table => println!("{report}"),
+            }
+        }
+        Command::UpdateModuleCache => {
+            let new_modules = operator.update_module_cache().await?;
+
+            match output {
+                // Package data is left out, it is only useful to sdf
+                Output::Json => print_json(
+                    &new_modules
+                        .iter()
+                        .map(|module| {
+                            json!({
+                                "id": module.id,
+                                "schemaId": module.schema_id,
+                                "schemaName": module.schema_name,
+                                "latestHash": module.latest_hash,
+                                "createdAt": module.created_at,
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                )?,
+                Output::Table => print_table(
+                    ["SCHEMA", "SCHEMA ID", "LATEST HASH", "CREATED AT"],
+                    new_modules
+                        .into_iter()
+                        .map(|module| {
+                            [
+                                module.schema_name,
+                                module.schema_id.to_string(),
+                                module.latest_hash,
+                                module.created_at.to_rfc3339(),
+                            ]
+                        })
+                        .collect(),
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_json(value: &impl serde::Serialize) -> AdminResult<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
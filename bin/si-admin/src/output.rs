@@ -0,0 +1,44 @@
+use std::fmt::Display;
+
+/// Prints rows under a header, with every column as wide as its widest value.
+pub(crate) fn print_table<const N: usize>(header: [&str; N], rows: Vec<[String; N]>) {
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.len());
+        }
+    }
+
+    print_row(&header, &widths);
+    for row in &rows {
+        print_row(row, &widths);
+    }
+}
+
+/// Prints one `field  value` line per field.
+pub(crate) fn print_fields<const N: usize>(fields: [(&str, String); N]) {
+    let width = fields
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default();
+
+    for (name, value) in fields {
+        println!("{name:<width$}  {value}");
+    }
+}
+
+/// Formats an optional value, or `-` if there is none.
+pub(crate) fn or_dash(value: Option<impl Display>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| value.to_string())
+}
+
+fn print_row<const N: usize>(row: &[impl AsRef<str>; N], widths: &[usize; N]) {
+    let line = row
+        .iter()
+        .zip(widths)
+        .map(|(value, width)| format!("{:<width$}", value.as_ref()))
+        .collect::<Vec<_>>()
+        .join("  ");
+    println!("{}", line.trim_end());
+}
@@ -47,6 +47,7 @@ use thiserror::Error;
 mod config;
 mod context;
 mod migrate;
+mod webhook;
 
 pub use config::default_pg_pool_config;
 pub use config::AuditDatabaseConfig;
@@ -54,6 +55,10 @@ pub use config::DBNAME;
 pub use context::AuditDatabaseContext;
 pub use context::AuditDatabaseContextError;
 pub use migrate::{migrate, AuditDatabaseMigrationError};
+pub use webhook::{
+    is_deliverable_address, EncryptedSigningSecret, WebhookDeliveryRow, WebhookDeliveryStatus,
+    WebhookEndpointRow,
+};
 
 #[allow(missing_docs)]
 #[remain::sorted]
@@ -61,12 +66,22 @@ pub use migrate::{migrate, AuditDatabaseMigrationError};
 pub enum AuditDatabaseError {
     #[error("chrono parse error: {0}")]
     ChronoParse(#[from] chrono::ParseError),
+    #[error("from utf8 error: {0}")]
+    FromUtf8(#[from] std::string::FromUtf8Error),
+    #[error("hash parse error: {0}")]
+    HashParse(#[from] si_hash::HashParseError),
+    #[error("invalid signing secret nonce")]
+    InvalidSigningSecretNonce,
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
     #[error("pg pool error: {0}")]
     PgPool(#[from] PgPoolError),
     #[error("serde json error: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
+    #[error("symmetric crypto error: {0}")]
+    SymmetricCrypto(#[from] si_crypto::SymmetricCryptoError),
     #[error("ulid decode error: {0}")]
     UlidDecode(#[from] ulid::DecodeError),
 }
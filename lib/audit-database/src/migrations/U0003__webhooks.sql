@@ -0,0 +1,43 @@
+CREATE TABLE webhook_endpoints (
+    id text PRIMARY KEY,
+    workspace_id text NOT NULL,
+    created_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    url text NOT NULL,
+    payload_format text NOT NULL,
+    event_kinds text[] NOT NULL,
+    -- Signing secrets are encrypted with the symmetric crypto service
+    signing_secret_crypted bytea,
+    signing_secret_nonce bytea,
+    signing_secret_key_hash text,
+    enabled boolean NOT NULL DEFAULT TRUE,
+    description text,
+    -- The Slack webhook URL of a workspace integration is delivered through an endpoint of its own
+    workspace_integration boolean NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX webhook_endpoints_workspace ON webhook_endpoints (workspace_id);
+
+CREATE UNIQUE INDEX webhook_endpoints_workspace_integration ON webhook_endpoints (workspace_id)
+    WHERE workspace_integration;
+
+CREATE TABLE webhook_deliveries (
+    id text PRIMARY KEY,
+    endpoint_id text NOT NULL REFERENCES webhook_endpoints (id) ON DELETE CASCADE,
+    workspace_id text NOT NULL,
+    created_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    updated_at timestamp with time zone NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    event_kind text NOT NULL,
+    payload jsonb NOT NULL,
+    status text NOT NULL,
+    attempts integer NOT NULL DEFAULT 0,
+    last_response_status integer,
+    last_error text,
+    -- Retries are driven by a sweeper, which claims pending deliveries whose next attempt is due
+    next_attempt_at timestamp with time zone
+);
+
+CREATE INDEX webhook_deliveries_endpoint ON webhook_deliveries (endpoint_id, created_at);
+
+CREATE INDEX webhook_deliveries_due ON webhook_deliveries (next_attempt_at)
+    WHERE status = 'pending';
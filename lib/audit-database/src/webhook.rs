@@ -0,0 +1,834 @@
+//! Contains outbound webhook endpoints and the log of deliveries made to them.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::DateTime;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use si_crypto::SymmetricCryptoService;
+use si_crypto::SymmetricNonce;
+use si_data_pg::PgRow;
+use si_events::WebhookDeliveryId;
+use si_events::WebhookEndpointId;
+use si_events::WebhookEventKind;
+use si_events::WebhookPayloadFormat;
+use si_events::WorkspacePk;
+use si_hash::Hash;
+use strum::AsRefStr;
+use strum::Display;
+use strum::EnumString;
+use telemetry::prelude::*;
+
+use crate::AuditDatabaseContext;
+use crate::AuditDatabaseError;
+use crate::Result;
+
+/// The kinds of events delivered to the endpoint of a workspace integration's Slack webhook URL,
+/// which are the change set events that were posted to it before outbound webhooks existed.
+const WORKSPACE_INTEGRATION_EVENT_KINDS: [WebhookEventKind; 5] = [
+    WebhookEventKind::ChangeSetApplied,
+    WebhookEventKind::ChangeSetApproved,
+    WebhookEventKind::ChangeSetApprovalRequested,
+    WebhookEventKind::ChangeSetApprovalWithdrawn,
+    WebhookEventKind::ChangeSetRejected,
+];
+
+/// Whether or not webhooks may be delivered to an address.
+///
+/// Loopback, private, link-local, shared and other non-routable addresses are refused so that an
+/// endpoint can't be used to reach services on our own network, such as cloud metadata endpoints.
+pub fn is_deliverable_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_deliverable_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_deliverable_ipv4(ip),
+            None => is_deliverable_ipv6(ip),
+        },
+    }
+}
+
+fn is_deliverable_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "this network" (0.0.0.0/8)
+        || first == 0
+        // shared address space (100.64.0.0/10)
+        || (first == 100 && (second & 0b1100_0000) == 64)
+        // reserved (240.0.0.0/4)
+        || first >= 240)
+}
+
+fn is_deliverable_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // unique local (fc00::/7)
+        || (first & 0xfe00) == 0xfc00
+        // link-local (fe80::/10)
+        || (first & 0xffc0) == 0xfe80
+        // documentation (2001:db8::/32)
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// The secret used to sign an endpoint's deliveries, encrypted with the active key of a
+/// [`SymmetricCryptoService`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptedSigningSecret {
+    crypted: Vec<u8>,
+    nonce: SymmetricNonce,
+    key_hash: Hash,
+}
+
+impl fmt::Debug for EncryptedSigningSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedSigningSecret")
+            .field("key_hash", &self.key_hash)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EncryptedSigningSecret {
+    /// Encrypts a signing secret.
+    pub fn encrypt(
+        symmetric_crypto_service: &SymmetricCryptoService,
+        signing_secret: &str,
+    ) -> Self {
+        let (crypted, nonce, key_hash) =
+            symmetric_crypto_service.encrypt(signing_secret.as_bytes());
+        Self {
+            crypted,
+            nonce,
+            key_hash: *key_hash,
+        }
+    }
+
+    /// Decrypts the signing secret.
+    ///
+    /// _Warning:_ the plaintext secret is returned.
+    pub fn decrypt(&self, symmetric_crypto_service: &SymmetricCryptoService) -> Result<String> {
+        let decrypted =
+            symmetric_crypto_service.decrypt(&self.crypted, &self.nonce, &self.key_hash)?;
+        Ok(String::from_utf8(decrypted)?)
+    }
+
+    fn try_from_row(row: &PgRow) -> Result<Option<Self>> {
+        let crypted: Option<Vec<u8>> = row.try_get("signing_secret_crypted")?;
+        let nonce: Option<Vec<u8>> = row.try_get("signing_secret_nonce")?;
+        let key_hash: Option<String> = row.try_get("signing_secret_key_hash")?;
+
+        match (crypted, nonce, key_hash) {
+            (Some(crypted), Some(nonce), Some(key_hash)) => Ok(Some(Self {
+                crypted,
+                nonce: SymmetricNonce::from_slice(&nonce)
+                    .ok_or(AuditDatabaseError::InvalidSigningSecretNonce)?,
+                key_hash: Hash::from_str(&key_hash)?,
+            })),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// A row in the webhook endpoints table of the audit database.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEndpointRow {
+    /// The identifier of the endpoint.
+    pub id: WebhookEndpointId,
+    /// Indicates the workspace that the row belongs to.
+    pub workspace_id: WorkspacePk,
+    /// When the endpoint was created.
+    pub created_at: DateTime<Utc>,
+    /// When the endpoint was last updated.
+    pub updated_at: DateTime<Utc>,
+    /// The URL that events are delivered to.
+    pub url: String,
+    /// The shape of the body delivered to the endpoint.
+    pub payload_format: WebhookPayloadFormat,
+    /// The kinds of events that are delivered to the endpoint.
+    pub event_kinds: Vec<WebhookEventKind>,
+    /// The encrypted secret used to sign [`Json`](WebhookPayloadFormat::Json) payloads. This is
+    /// never serialized.
+    #[serde(skip)]
+    pub signing_secret: Option<EncryptedSigningSecret>,
+    /// Whether or not events are currently delivered to the endpoint.
+    pub enabled: bool,
+    /// An optional, user-provided description of the endpoint.
+    pub description: Option<String>,
+    /// Whether the endpoint delivers to the Slack webhook URL of the workspace's integration.
+    pub workspace_integration: bool,
+}
+
+impl WebhookEndpointRow {
+    /// Inserts a new row into the webhook endpoints table of the audit database.
+    #[instrument(
+        name = "audit_database.webhook_endpoint.insert",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn insert(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        url: String,
+        payload_format: WebhookPayloadFormat,
+        event_kinds: Vec<WebhookEventKind>,
+        signing_secret: Option<EncryptedSigningSecret>,
+        description: Option<String>,
+    ) -> Result<Self> {
+        let row = context
+            .pg_pool()
+            .get()
+            .await?
+            .query_one(
+                "INSERT INTO webhook_endpoints (
+                    id,
+                    workspace_id,
+                    url,
+                    payload_format,
+                    event_kinds,
+                    signing_secret_crypted,
+                    signing_secret_nonce,
+                    signing_secret_key_hash,
+                    description
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6,
+                    $7,
+                    $8,
+                    $9
+                ) RETURNING *",
+                &[
+                    &WebhookEndpointId::new().to_string(),
+                    &workspace_id.to_string(),
+                    &url,
+                    &payload_format.as_ref(),
+                    &event_kinds_to_strings(&event_kinds),
+                    &signing_secret
+                        .as_ref()
+                        .map(|secret| secret.crypted.as_slice()),
+                    &signing_secret.as_ref().map(|secret| secret.nonce.as_ref()),
+                    &signing_secret
+                        .as_ref()
+                        .map(|secret| secret.key_hash.to_string()),
+                    &description,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Creates or updates the [`Slack`](WebhookPayloadFormat::Slack) endpoint that delivers change
+    /// set events to the Slack webhook URL of a workspace's integration.
+    ///
+    /// Only the URL of an existing endpoint is updated, so that the events it is subscribed to can
+    /// be changed like those of any other endpoint.
+    #[instrument(
+        name = "audit_database.webhook_endpoint.upsert_for_workspace_integration",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn upsert_for_workspace_integration(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        url: String,
+    ) -> Result<Self> {
+        let row = context
+            .pg_pool()
+            .get()
+            .await?
+            .query_one(
+                "INSERT INTO webhook_endpoints (
+                    id,
+                    workspace_id,
+                    url,
+                    payload_format,
+                    event_kinds,
+                    description,
+                    workspace_integration
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6,
+                    TRUE
+                )
+                ON CONFLICT (workspace_id) WHERE workspace_integration DO UPDATE SET
+                    url = EXCLUDED.url,
+                    updated_at = CLOCK_TIMESTAMP()
+                RETURNING *",
+                &[
+                    &WebhookEndpointId::new().to_string(),
+                    &workspace_id.to_string(),
+                    &url,
+                    &WebhookPayloadFormat::Slack.as_ref(),
+                    &event_kinds_to_strings(&WORKSPACE_INTEGRATION_EVENT_KINDS),
+                    &"Slack workspace integration",
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Deletes the endpoint of a workspace's integration (along with its deliveries), returning
+    /// whether or not it existed.
+    #[instrument(
+        name = "audit_database.webhook_endpoint.delete_for_workspace_integration",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn delete_for_workspace_integration(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+    ) -> Result<bool> {
+        let deleted = context
+            .pg_pool()
+            .get()
+            .await?
+            .execute(
+                "DELETE FROM webhook_endpoints WHERE workspace_id = $1 AND workspace_integration",
+                &[&workspace_id.to_string()],
+            )
+            .await?;
+
+        Ok(deleted > 0)
+    }
+
+    /// Finds an endpoint by id within a workspace.
+    #[instrument(
+        name = "audit_database.webhook_endpoint.get",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn get(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        id: WebhookEndpointId,
+    ) -> Result<Option<Self>> {
+        let maybe_row = context
+            .pg_pool()
+            .get()
+            .await?
+            .query_opt(
+                "SELECT * FROM webhook_endpoints WHERE workspace_id = $1 AND id = $2",
+                &[&workspace_id.to_string(), &id.to_string()],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Lists all endpoints for a workspace.
+    #[instrument(
+        name = "audit_database.webhook_endpoint.list",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn list(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+    ) -> Result<Vec<Self>> {
+        let rows = context
+            .pg_pool()
+            .get()
+            .await?
+            .query(
+                "SELECT * FROM webhook_endpoints WHERE workspace_id = $1 ORDER BY created_at ASC",
+                &[&workspace_id.to_string()],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Lists the enabled endpoints for a workspace which subscribe to the given kind of event.
+    #[instrument(
+        name = "audit_database.webhook_endpoint.list_for_event_kind",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+            si.webhook.event_kind = %event_kind,
+        ),
+    )]
+    pub async fn list_for_event_kind(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        event_kind: WebhookEventKind,
+    ) -> Result<Vec<Self>> {
+        let rows = context
+            .pg_pool()
+            .get()
+            .await?
+            .query(
+                "SELECT * FROM webhook_endpoints
+                    WHERE workspace_id = $1 AND enabled AND $2 = ANY(event_kinds)",
+                &[&workspace_id.to_string(), &event_kind.as_ref()],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Updates the user-configurable fields of an endpoint, returning the updated row (if the
+    /// endpoint exists).
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(
+        name = "audit_database.webhook_endpoint.update",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn update(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        id: WebhookEndpointId,
+        url: String,
+        payload_format: WebhookPayloadFormat,
+        event_kinds: Vec<WebhookEventKind>,
+        enabled: bool,
+        description: Option<String>,
+    ) -> Result<Option<Self>> {
+        let maybe_row = context
+            .pg_pool()
+            .get()
+            .await?
+            .query_opt(
+                "UPDATE webhook_endpoints SET
+                    url = $3,
+                    payload_format = $4,
+                    event_kinds = $5,
+                    enabled = $6,
+                    description = $7,
+                    updated_at = CLOCK_TIMESTAMP()
+                WHERE workspace_id = $1 AND id = $2
+                RETURNING *",
+                &[
+                    &workspace_id.to_string(),
+                    &id.to_string(),
+                    &url,
+                    &payload_format.as_ref(),
+                    &event_kinds_to_strings(&event_kinds),
+                    &enabled,
+                    &description,
+                ],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Deletes an endpoint (along with its deliveries), returning whether or not it existed.
+    #[instrument(
+        name = "audit_database.webhook_endpoint.delete",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+        ),
+    )]
+    pub async fn delete(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        id: WebhookEndpointId,
+    ) -> Result<bool> {
+        let deleted = context
+            .pg_pool()
+            .get()
+            .await?
+            .execute(
+                "DELETE FROM webhook_endpoints WHERE workspace_id = $1 AND id = $2",
+                &[&workspace_id.to_string(), &id.to_string()],
+            )
+            .await?;
+
+        Ok(deleted > 0)
+    }
+}
+
+impl TryFrom<PgRow> for WebhookEndpointRow {
+    type Error = AuditDatabaseError;
+
+    fn try_from(value: PgRow) -> std::result::Result<Self, Self::Error> {
+        let id = {
+            let inner: String = value.try_get("id")?;
+            WebhookEndpointId::from_str(&inner)?
+        };
+        let workspace_id = {
+            let inner: String = value.try_get("workspace_id")?;
+            WorkspacePk::from_str(&inner)?
+        };
+        let payload_format = {
+            let inner: String = value.try_get("payload_format")?;
+            WebhookPayloadFormat::from_str(&inner)?
+        };
+        let event_kinds = {
+            let inner: Vec<String> = value.try_get("event_kinds")?;
+            inner
+                .iter()
+                .map(|kind| WebhookEventKind::from_str(kind))
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+
+        let signing_secret = EncryptedSigningSecret::try_from_row(&value)?;
+
+        Ok(Self {
+            id,
+            workspace_id,
+            created_at: value.try_get("created_at")?,
+            updated_at: value.try_get("updated_at")?,
+            url: value.try_get("url")?,
+            payload_format,
+            event_kinds,
+            signing_secret,
+            enabled: value.try_get("enabled")?,
+            description: value.try_get("description")?,
+            workspace_integration: value.try_get("workspace_integration")?,
+        })
+    }
+}
+
+/// The status of a [`WebhookDeliveryRow`].
+#[remain::sorted]
+#[derive(
+    AsRefStr, Clone, Copy, Debug, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WebhookDeliveryStatus {
+    /// Every attempt to deliver the event failed.
+    Failed,
+    /// The event has not yet been successfully delivered, but more attempts will be made.
+    Pending,
+    /// The endpoint accepted the event.
+    Succeeded,
+}
+
+/// A row in the webhook deliveries table of the audit database.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryRow {
+    /// The identifier of the delivery.
+    pub id: WebhookDeliveryId,
+    /// The endpoint that the event is being delivered to.
+    pub endpoint_id: WebhookEndpointId,
+    /// Indicates the workspace that the row belongs to.
+    pub workspace_id: WorkspacePk,
+    /// When the delivery was created.
+    pub created_at: DateTime<Utc>,
+    /// When the delivery was last attempted.
+    pub updated_at: DateTime<Utc>,
+    /// The kind of event being delivered.
+    pub event_kind: WebhookEventKind,
+    /// The body delivered to the endpoint.
+    pub payload: serde_json::Value,
+    /// The status of the delivery.
+    pub status: WebhookDeliveryStatus,
+    /// The number of delivery attempts made so far.
+    pub attempts: i32,
+    /// The HTTP status returned by the endpoint for the last attempt, if it responded.
+    pub last_response_status: Option<i32>,
+    /// The error encountered during the last attempt, if any.
+    pub last_error: Option<String>,
+    /// When the next attempt is due, or when the attempt in progress can be assumed to have been
+    /// interrupted. Only [pending](WebhookDeliveryStatus::Pending) deliveries have one.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDeliveryRow {
+    /// Inserts a new, [pending](WebhookDeliveryStatus::Pending) row into the webhook deliveries
+    /// table of the audit database.
+    ///
+    /// The delivery is claimed for `lease` so that the caller can make the first attempt. If the
+    /// attempt is not recorded by then, [`claim_due`](Self::claim_due) hands it out again.
+    #[instrument(
+        name = "audit_database.webhook_delivery.insert",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %endpoint.workspace_id,
+            si.webhook.endpoint.id = %endpoint.id,
+        ),
+    )]
+    pub async fn insert(
+        context: &AuditDatabaseContext,
+        endpoint: &WebhookEndpointRow,
+        event_kind: WebhookEventKind,
+        payload: serde_json::Value,
+        lease: Duration,
+    ) -> Result<Self> {
+        let row = context
+            .pg_pool()
+            .get()
+            .await?
+            .query_one(
+                "INSERT INTO webhook_deliveries (
+                    id,
+                    endpoint_id,
+                    workspace_id,
+                    event_kind,
+                    payload,
+                    status,
+                    next_attempt_at
+                ) VALUES (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6,
+                    CLOCK_TIMESTAMP() + make_interval(secs => $7)
+                ) RETURNING *",
+                &[
+                    &WebhookDeliveryId::new().to_string(),
+                    &endpoint.id.to_string(),
+                    &endpoint.workspace_id.to_string(),
+                    &event_kind.as_ref(),
+                    &payload,
+                    &WebhookDeliveryStatus::Pending.as_ref(),
+                    &lease.as_secs_f64(),
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Claims up to `limit` [pending](WebhookDeliveryStatus::Pending) deliveries whose next
+    /// attempt is due, pushing their next attempt back by `lease` so that no one else claims them
+    /// while they are being attempted.
+    #[instrument(
+        name = "audit_database.webhook_delivery.claim_due",
+        level = "debug",
+        skip_all
+    )]
+    pub async fn claim_due(
+        context: &AuditDatabaseContext,
+        limit: usize,
+        lease: Duration,
+    ) -> Result<Vec<Self>> {
+        let limit = limit as i64;
+        let rows = context
+            .pg_pool()
+            .get()
+            .await?
+            .query(
+                "UPDATE webhook_deliveries SET
+                    next_attempt_at = CLOCK_TIMESTAMP() + make_interval(secs => $3)
+                WHERE id IN (
+                    SELECT id FROM webhook_deliveries
+                        WHERE status = $1 AND next_attempt_at <= CLOCK_TIMESTAMP()
+                        ORDER BY next_attempt_at ASC
+                        LIMIT $2
+                        FOR UPDATE SKIP LOCKED
+                )
+                RETURNING *",
+                &[
+                    &WebhookDeliveryStatus::Pending.as_ref(),
+                    &limit,
+                    &lease.as_secs_f64(),
+                ],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Records the outcome of a delivery attempt.
+    ///
+    /// `retry_in` schedules the next attempt of a delivery that is still
+    /// [pending](WebhookDeliveryStatus::Pending).
+    #[instrument(
+        name = "audit_database.webhook_delivery.record_attempt",
+        level = "debug",
+        skip_all,
+        fields(
+            si.webhook.delivery.id = %id,
+        ),
+    )]
+    pub async fn record_attempt(
+        context: &AuditDatabaseContext,
+        id: WebhookDeliveryId,
+        status: WebhookDeliveryStatus,
+        response_status: Option<u16>,
+        error: Option<String>,
+        retry_in: Option<Duration>,
+    ) -> Result<()> {
+        context
+            .pg_pool()
+            .get()
+            .await?
+            .execute(
+                "UPDATE webhook_deliveries SET
+                    status = $2,
+                    attempts = attempts + 1,
+                    last_response_status = $3,
+                    last_error = $4,
+                    next_attempt_at = CLOCK_TIMESTAMP() + make_interval(secs => $5),
+                    updated_at = CLOCK_TIMESTAMP()
+                WHERE id = $1",
+                &[
+                    &id.to_string(),
+                    &status.as_ref(),
+                    &response_status.map(i32::from),
+                    &error,
+                    &retry_in.map(|retry_in| retry_in.as_secs_f64()),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Marks a delivery as [failed](WebhookDeliveryStatus::Failed) without attempting it, such as
+    /// when its endpoint has been disabled.
+    #[instrument(
+        name = "audit_database.webhook_delivery.abandon",
+        level = "debug",
+        skip_all,
+        fields(
+            si.webhook.delivery.id = %id,
+        ),
+    )]
+    pub async fn abandon(
+        context: &AuditDatabaseContext,
+        id: WebhookDeliveryId,
+        error: String,
+    ) -> Result<()> {
+        context
+            .pg_pool()
+            .get()
+            .await?
+            .execute(
+                "UPDATE webhook_deliveries SET
+                    status = $2,
+                    last_error = $3,
+                    next_attempt_at = NULL,
+                    updated_at = CLOCK_TIMESTAMP()
+                WHERE id = $1",
+                &[
+                    &id.to_string(),
+                    &WebhookDeliveryStatus::Failed.as_ref(),
+                    &error,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lists the most recent deliveries to an endpoint, newest first.
+    #[instrument(
+        name = "audit_database.webhook_delivery.list_for_endpoint",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace.id = %workspace_id,
+            si.webhook.endpoint.id = %endpoint_id,
+        ),
+    )]
+    pub async fn list_for_endpoint(
+        context: &AuditDatabaseContext,
+        workspace_id: WorkspacePk,
+        endpoint_id: WebhookEndpointId,
+        size: usize,
+    ) -> Result<Vec<Self>> {
+        let size = size as i64;
+        let rows = context
+            .pg_pool()
+            .get()
+            .await?
+            .query(
+                "SELECT * FROM webhook_deliveries
+                    WHERE workspace_id = $1 AND endpoint_id = $2
+                    ORDER BY created_at DESC
+                    LIMIT $3",
+                &[&workspace_id.to_string(), &endpoint_id.to_string(), &size],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+}
+
+impl TryFrom<PgRow> for WebhookDeliveryRow {
+    type Error = AuditDatabaseError;
+
+    fn try_from(value: PgRow) -> std::result::Result<Self, Self::Error> {
+        let id = {
+            let inner: String = value.try_get("id")?;
+            WebhookDeliveryId::from_str(&inner)?
+        };
+        let endpoint_id = {
+            let inner: String = value.try_get("endpoint_id")?;
+            WebhookEndpointId::from_str(&inner)?
+        };
+        let workspace_id = {
+            let inner: String = value.try_get("workspace_id")?;
+            WorkspacePk::from_str(&inner)?
+        };
+        let event_kind = {
+            let inner: String = value.try_get("event_kind")?;
+            WebhookEventKind::from_str(&inner)?
+        };
+        let status = {
+            let inner: String = value.try_get("status")?;
+            WebhookDeliveryStatus::from_str(&inner)?
+        };
+
+        Ok(Self {
+            id,
+            endpoint_id,
+            workspace_id,
+            created_at: value.try_get("created_at")?,
+            updated_at: value.try_get("updated_at")?,
+            event_kind,
+            payload: value.try_get("payload")?,
+            status,
+            attempts: value.try_get("attempts")?,
+            last_response_status: value.try_get("last_response_status")?,
+            last_error: value.try_get("last_error")?,
+            next_attempt_at: value.try_get("next_attempt_at")?,
+        })
+    }
+}
+
+fn event_kinds_to_strings(event_kinds: &[WebhookEventKind]) -> Vec<String> {
+    event_kinds.iter().map(ToString::to_string).collect()
+}
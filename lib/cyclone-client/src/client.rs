@@ -1330,8 +1330,10 @@ mod tests {
                 kind: None,
                 properties: serde_json::json!({"it": "is", "a": "principle", "of": "music", "to": "repeat the theme"}),
                 geometry: serde_json::json!({"x": "1", "y": "2"}),
+                tags: Default::default(),
             },
             components: HashMap::new(),
+            selected_components: HashMap::new(),
             code_base64: base64_encode(
                 r#"function manage(input) {
                     console.log('first');
@@ -1415,8 +1417,10 @@ mod tests {
                 kind: None,
                 properties: serde_json::json!({"it": "is", "a": "principle", "of": "music", "to": "repeat the theme"}),
                 geometry: serde_json::json!({"x": "1", "y": "2"}),
+                tags: Default::default(),
             },
             components: HashMap::new(),
+            selected_components: HashMap::new(),
             code_base64: base64_encode(
                 r#"function manage({ thisComponent }) {
                     console.log('first');
@@ -5,10 +5,10 @@ mod watch;
 
 pub use client::{Client, ClientConfig, ClientError, CycloneClient, HttpClient, UdsClient};
 pub use cyclone_core::{
-    ActionRunRequest, ActionRunResultSuccess, CycloneRequest, LivenessStatus,
-    LivenessStatusParseError, ReadinessStatus, ReadinessStatusParseError, ResolverFunctionRequest,
-    ResolverFunctionResultSuccess, SchemaVariantDefinitionRequest,
-    SchemaVariantDefinitionResultSuccess, SensitiveStrings,
+    ActionRunRequest, ActionRunResultSuccess, CycloneRequest, CycloneRequestable, FunctionResult,
+    LivenessStatus, LivenessStatusParseError, OutputStream, ProgressMessage, ReadinessStatus,
+    ReadinessStatusParseError, ResolverFunctionRequest, ResolverFunctionResultSuccess,
+    SchemaVariantDefinitionRequest, SchemaVariantDefinitionResultSuccess, SensitiveStrings,
 };
 pub use execution::{new_unstarted_execution, Execution, ExecutionError};
 pub use hyper::client::connect::Connection;
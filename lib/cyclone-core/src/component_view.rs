@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -38,6 +40,8 @@ pub struct ComponentViewWithGeometry {
     pub kind: Option<String>,
     pub properties: Value,
     pub geometry: Value,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
 }
 
 impl Default for ComponentViewWithGeometry {
@@ -46,6 +50,7 @@ impl Default for ComponentViewWithGeometry {
             kind: None,
             properties: serde_json::json!({}),
             geometry: serde_json::json!({}),
+            tags: BTreeMap::new(),
         }
     }
 }
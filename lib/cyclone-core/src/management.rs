@@ -15,6 +15,10 @@ pub struct ManagementRequest {
     pub current_view: String,
     pub this_component: ComponentViewWithGeometry,
     pub components: HashMap<String, ComponentViewWithGeometry>,
+    /// Components chosen by the prototype's tag selectors. These are inputs only: the function
+    /// cannot operate on them unless they are also managed.
+    #[serde(default)]
+    pub selected_components: HashMap<String, ComponentViewWithGeometry>,
     pub before: Vec<BeforeFunction>,
 }
 
@@ -764,6 +764,7 @@ pub async fn forklift_server(
             config.audit().insert_concurrency_limit,
         )),
         None,
+        None,
         token,
     )
     .await
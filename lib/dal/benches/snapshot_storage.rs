@@ -0,0 +1,129 @@
+//! Compares writing and reading a workspace snapshot stored whole against storing it as chunks.
+//!
+//! Run with `cargo bench -p dal --bench snapshot_storage`.
+
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use dal::{
+    workspace_snapshot::{
+        chunked::{self, SnapshotChunk},
+        content_address::ContentAddress,
+        node_weight::NodeWeight,
+    },
+    EdgeWeight, EdgeWeightKind, WorkspaceSnapshotGraph, WorkspaceSnapshotGraphVCurrent,
+};
+use si_events::{merkle_tree_hash::MerkleTreeHash, ContentHash};
+use si_layer_cache::db::serialize;
+
+const COMPONENTS: usize = 1_000;
+const VALUES_PER_COMPONENT: usize = 20;
+
+/// Builds a graph of components, each with a tree of attribute values, which is roughly the shape
+/// of a large workspace.
+fn build_graph() -> WorkspaceSnapshotGraphVCurrent {
+    let mut graph =
+        WorkspaceSnapshotGraphVCurrent::new_with_categories_only().expect("create graph");
+
+    for component in 0..COMPONENTS {
+        let component_index = graph
+            .add_or_replace_node(NodeWeight::new_component(
+                graph.generate_ulid().expect("generate ulid"),
+                graph.generate_ulid().expect("generate ulid"),
+                ContentHash::new(format!("component-{component}").as_bytes()),
+            ))
+            .expect("add component");
+        graph
+            .add_edge(
+                graph.root(),
+                EdgeWeight::new(EdgeWeightKind::new_use()),
+                component_index,
+            )
+            .expect("add component edge");
+
+        let mut parent_index = component_index;
+        for value in 0..VALUES_PER_COMPONENT {
+            let content_hash =
+                ContentHash::new(format!("component-{component}-value-{value}").as_bytes());
+            let value_index = graph
+                .add_or_replace_node(NodeWeight::new_attribute_value(
+                    graph.generate_ulid().expect("generate ulid"),
+                    graph.generate_ulid().expect("generate ulid"),
+                    Some(ContentAddress::JsonValue(content_hash)),
+                    Some(ContentAddress::JsonValue(content_hash)),
+                ))
+                .expect("add attribute value");
+            graph
+                .add_edge(
+                    parent_index,
+                    EdgeWeight::new(EdgeWeightKind::Contain(None)),
+                    value_index,
+                )
+                .expect("add attribute value edge");
+            parent_index = value_index;
+        }
+    }
+
+    graph
+        .cleanup_and_merkle_tree_hash()
+        .expect("merkle tree hash");
+    graph
+}
+
+/// Splits a graph into serialized chunks keyed the way [`chunked::write`] keys them.
+fn write_chunks(
+    graph: &WorkspaceSnapshotGraphVCurrent,
+) -> (SnapshotChunk, HashMap<MerkleTreeHash, Vec<u8>>) {
+    let mut chunks = HashMap::new();
+    let root_chunk = chunked::split_graph(graph, |chunk| {
+        let (compressed, uncompressed) = serialize::to_vec_with_uncompressed(&chunk)?;
+        let key = MerkleTreeHash::new(&uncompressed);
+        chunks.insert(key, compressed);
+        Ok(key)
+    })
+    .expect("split graph");
+
+    (root_chunk, chunks)
+}
+
+fn snapshot_storage(c: &mut Criterion) {
+    let graph = build_graph();
+    let whole = WorkspaceSnapshotGraph::V4(graph.clone());
+    let (whole_bytes, _) = serialize::to_vec(&whole).expect("serialize snapshot");
+    let (root_chunk, chunk_bytes) = write_chunks(&graph);
+
+    let mut group = c.benchmark_group("snapshot_storage");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(graph.node_count() as u64));
+
+    group.bench_function("whole/write", |b| {
+        b.iter(|| serialize::to_vec(black_box(&whole)).expect("serialize snapshot"))
+    });
+    group.bench_function("whole/read", |b| {
+        b.iter(|| {
+            serialize::from_bytes::<WorkspaceSnapshotGraph>(black_box(&whole_bytes))
+                .expect("deserialize snapshot")
+        })
+    });
+    group.bench_function("chunked/write", |b| {
+        b.iter(|| write_chunks(black_box(&graph)))
+    });
+    group.bench_function("chunked/read", |b| {
+        b.iter_batched(
+            || chunk_bytes.values().cloned().collect::<Vec<_>>(),
+            |chunk_bytes| {
+                let chunks: Vec<SnapshotChunk> = chunk_bytes
+                    .iter()
+                    .map(|bytes| serialize::from_bytes(bytes).expect("deserialize chunk"))
+                    .collect();
+                chunked::assemble_graph(&root_chunk, chunks.iter()).expect("assemble graph")
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, snapshot_storage);
+criterion_main!(benches);
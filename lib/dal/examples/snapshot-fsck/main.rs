@@ -0,0 +1,57 @@
+use std::{env, fs::File, io::prelude::*};
+
+use si_layer_cache::db::serialize;
+
+use dal::WorkspaceSnapshotGraph;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + 'static>>;
+
+const USAGE: &str =
+    "usage: cargo run --example snapshot-fsck <SNAPSHOT_FILE_PATH> [<REPAIRED_SNAPSHOT_FILE_PATH>]";
+
+fn main() -> Result<()> {
+    let mut args = env::args();
+    let snap_path = args.nth(1).expect(USAGE);
+    let repaired_path = args.next();
+
+    let mut snap_file = File::open(snap_path)?;
+    let mut snap_bytes = vec![];
+    snap_file.read_to_end(&mut snap_bytes)?;
+
+    let mut graph: WorkspaceSnapshotGraph = serialize::from_bytes(&snap_bytes)?;
+
+    // Content addresses can only be checked against the CAS by the admin route, since an
+    // exported snapshot does not carry its content.
+    let violations = graph.fsck();
+    for violation in &violations {
+        println!(
+            "{} {}{}: {}\n    at {}",
+            violation.kind,
+            violation.node_id,
+            if violation.repairable {
+                " (repairable)"
+            } else {
+                ""
+            },
+            violation.message,
+            violation.path.join(" -> "),
+        );
+    }
+    println!("{} violations found", violations.len());
+
+    if let Some(repaired_path) = repaired_path {
+        let repaired = graph.inner_mut().repair(&violations)?;
+
+        let mut repaired_file = File::create(&repaired_path)?;
+        let (bytes, _) = serialize::to_vec(&graph)?;
+        repaired_file.write_all(&bytes)?;
+
+        println!(
+            "repaired {} violations, wrote {}",
+            repaired.len(),
+            repaired_path
+        );
+    }
+
+    Ok(())
+}
@@ -22,12 +22,13 @@ use crate::{
     workspace_snapshot::node_weight::{
         category_node_weight::CategoryNodeKind, ActionNodeWeight, NodeWeight, NodeWeightError,
     },
-    AttributeValue, ChangeSetError, ChangeSetId, ComponentError, ComponentId, DalContext,
-    EdgeWeightKind, EdgeWeightKindDiscriminants, HelperError, TransactionsError,
+    AttributeValue, ChangeSetError, ChangeSetId, Component, ComponentError, ComponentId,
+    DalContext, EdgeWeightKind, EdgeWeightKindDiscriminants, HelperError, TransactionsError,
     WorkspaceSnapshotError, WsEvent, WsEventError, WsEventResult, WsPayload,
 };
 
 pub mod dependency_graph;
+pub mod plan;
 pub mod prototype;
 
 #[remain::sorted]
@@ -307,6 +308,12 @@ impl Action {
         ctx.workspace_snapshot()?
             .add_or_replace_node(NodeWeight::Action(new_node_weight))
             .await?;
+
+        WsEvent::action_state_updated(ctx, id, state)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
         Ok(())
     }
 
@@ -325,6 +332,15 @@ impl Action {
         action_prototype_id: ActionPrototypeId,
         maybe_component_id: Option<ComponentId>,
     ) -> ActionResult<Self> {
+        if let Some(component_id) = maybe_component_id {
+            let prototype = ActionPrototype::get_by_id(ctx, action_prototype_id).await?;
+            if prototype.kind == ActionKind::Destroy
+                && Component::is_protected(ctx, component_id).await?
+            {
+                return Err(ComponentError::ComponentProtected(component_id).into());
+            }
+        }
+
         let new_id: ActionId = ctx.workspace_snapshot()?.generate_ulid().await?.into();
         let lineage_id = ctx.workspace_snapshot()?.generate_ulid().await?;
 
@@ -605,8 +621,37 @@ impl Action {
     }
 }
 
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionStateUpdatedPayload {
+    action_id: ActionId,
+    state: ActionState,
+}
+
+impl ActionStateUpdatedPayload {
+    pub fn action_id(&self) -> ActionId {
+        self.action_id
+    }
+
+    pub fn state(&self) -> ActionState {
+        self.state
+    }
+}
+
 impl WsEvent {
     pub async fn action_list_updated(ctx: &DalContext) -> WsEventResult<Self> {
         WsEvent::new(ctx, WsPayload::ActionsListUpdated(ctx.change_set_id())).await
     }
+
+    pub async fn action_state_updated(
+        ctx: &DalContext,
+        action_id: ActionId,
+        state: ActionState,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ActionStateUpdated(ActionStateUpdatedPayload { action_id, state }),
+        )
+        .await
+    }
 }
@@ -0,0 +1,294 @@
+//! Previews which [`Actions`](Action) will be queued on HEAD once a change set is applied, and in
+//! what order they will run.
+//!
+//! The plan is built by applying the change set's updates to an in-memory copy of the HEAD
+//! snapshot, exactly as the rebaser would, and then building the [`ActionDependencyGraph`] for the
+//! result. Nothing is written back.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    action::{
+        dependency_graph::ActionDependencyGraph,
+        prototype::{ActionKind, ActionPrototype},
+        Action, ActionId, ActionResult, ActionState,
+    },
+    ActionPrototypeId, ChangeSet, ChangeSetError, ChangeSetId, Component, ComponentId, DalContext,
+    WorkspaceSnapshot,
+};
+
+/// An [`Action`] as it will exist on HEAD after the apply.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPlanEntry {
+    pub id: ActionId,
+    pub prototype_id: ActionPrototypeId,
+    pub name: String,
+    pub kind: ActionKind,
+    pub state: ActionState,
+    pub component_id: Option<ComponentId>,
+    pub component_name: Option<String>,
+    pub originating_change_set_id: ChangeSetId,
+    /// Whether the action is brought to HEAD by this change set, rather than already queued there.
+    pub from_change_set: bool,
+}
+
+/// `action_id` will not run until `depends_on` has succeeded.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPlanDependency {
+    pub action_id: ActionId,
+    pub depends_on: ActionId,
+}
+
+/// An [`Action`] that is on hold or has failed, and so will not be dispatched, along with what it
+/// holds up.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeldAction {
+    pub action_id: ActionId,
+    pub state: ActionState,
+    /// Every action that waits, directly or not, on this one.
+    pub blocked_action_ids: Vec<ActionId>,
+    /// The components of this action and of every action it blocks.
+    pub affected_component_ids: Vec<ComponentId>,
+}
+
+/// The actions that will be queued on HEAD after applying a change set.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionPlan {
+    pub change_set_id: ChangeSetId,
+    pub base_change_set_id: ChangeSetId,
+    /// Every action, in the order they will be dispatched.
+    pub actions: Vec<ActionPlanEntry>,
+    pub dependencies: Vec<ActionPlanDependency>,
+    /// Groups of actions that can be dispatched together, in order. Each batch is the
+    /// [independent actions](ActionDependencyGraph::independent_actions) left once the previous
+    /// batches have succeeded.
+    pub batches: Vec<Vec<ActionId>>,
+    pub held: Vec<HeldAction>,
+    /// Actions that will never be dispatched because they depend on each other.
+    pub cyclic_action_ids: Vec<ActionId>,
+}
+
+impl ActionPlan {
+    /// Builds the plan for applying the change set in the [`DalContext`] onto its base.
+    #[instrument(name = "action.plan.for_apply", level = "info", skip_all)]
+    pub async fn for_apply(ctx: &DalContext) -> ActionResult<Self> {
+        let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id()).await?;
+        let base_change_set_id = change_set
+            .base_change_set_id
+            .ok_or(ChangeSetError::NoBaseChangeSet(change_set.id))?;
+
+        let mut plan_ctx = ctx.clone_with_base().await?;
+        let head_action_ids: HashSet<ActionId> =
+            Action::all_ids(&plan_ctx).await?.into_iter().collect();
+
+        if let Some(rebase_batch) = change_set.detect_updates_that_will_be_applied(ctx).await? {
+            let snapshot = WorkspaceSnapshot::find_for_change_set(ctx, base_change_set_id).await?;
+            let updates = snapshot
+                .correct_transforms(rebase_batch.updates().to_vec(), false)
+                .await?;
+            snapshot.perform_updates(&updates).await?;
+            plan_ctx.set_workspace_snapshot(snapshot);
+        }
+
+        let graph = ActionDependencyGraph::for_workspace(&plan_ctx).await?;
+        Self::assemble(
+            &plan_ctx,
+            change_set.id,
+            base_change_set_id,
+            &head_action_ids,
+            graph,
+        )
+        .await
+    }
+
+    async fn assemble(
+        ctx: &DalContext,
+        change_set_id: ChangeSetId,
+        base_change_set_id: ChangeSetId,
+        head_action_ids: &HashSet<ActionId>,
+        graph: ActionDependencyGraph,
+    ) -> ActionResult<Self> {
+        let mut all_action_ids = graph.remaining_actions();
+        all_action_ids.sort();
+
+        let mut dependencies = Vec::new();
+        for &action_id in &all_action_ids {
+            let mut depends_on = graph.direct_dependencies_of(action_id);
+            depends_on.sort();
+            dependencies.extend(
+                depends_on
+                    .into_iter()
+                    .map(|depends_on| ActionPlanDependency {
+                        action_id,
+                        depends_on,
+                    }),
+            );
+        }
+
+        let mut entries = Vec::with_capacity(all_action_ids.len());
+        for &action_id in &all_action_ids {
+            let action = Action::get_by_id(ctx, action_id).await?;
+            let prototype_id = Action::prototype_id(ctx, action_id).await?;
+            let prototype = ActionPrototype::get_by_id(ctx, prototype_id).await?;
+            let component_id = Action::component_id(ctx, action_id).await?;
+            let component_name = match component_id {
+                Some(component_id) => Some(Component::name_by_id(ctx, component_id).await?),
+                None => None,
+            };
+
+            entries.push(ActionPlanEntry {
+                id: action_id,
+                prototype_id,
+                name: prototype.name().clone(),
+                kind: prototype.kind,
+                state: action.state(),
+                component_id,
+                component_name,
+                originating_change_set_id: action.originating_changeset_id(),
+                from_change_set: !head_action_ids.contains(&action_id),
+            });
+        }
+
+        let mut held = Vec::new();
+        for entry in &entries {
+            if !matches!(entry.state, ActionState::OnHold | ActionState::Failed) {
+                continue;
+            }
+            let mut blocked_action_ids = graph.get_all_dependencies(entry.id);
+            blocked_action_ids.sort();
+            let affected_component_ids: BTreeSet<ComponentId> = entries
+                .iter()
+                .filter(|other| other.id == entry.id || blocked_action_ids.contains(&other.id))
+                .filter_map(|other| other.component_id)
+                .collect();
+            held.push(HeldAction {
+                action_id: entry.id,
+                state: entry.state,
+                blocked_action_ids,
+                affected_component_ids: affected_component_ids.into_iter().collect(),
+            });
+        }
+
+        // Walk the graph the way the dispatcher does: each round, whatever is independent and not
+        // held runs. Anything still left afterwards is either held up or stuck in a cycle.
+        let runnable: HashSet<ActionId> = entries
+            .iter()
+            .filter(|entry| !matches!(entry.state, ActionState::OnHold | ActionState::Failed))
+            .map(|entry| entry.id)
+            .collect();
+        let mut remaining = graph.clone();
+        let mut batches = Vec::new();
+        loop {
+            let mut batch: Vec<ActionId> = remaining
+                .independent_actions()
+                .into_iter()
+                .filter(|action_id| runnable.contains(action_id))
+                .collect();
+            if batch.is_empty() {
+                break;
+            }
+            batch.sort();
+            for &action_id in &batch {
+                remaining.remove_action(action_id);
+            }
+            batches.push(batch);
+        }
+
+        let held_up: HashSet<ActionId> = held
+            .iter()
+            .flat_map(|held| {
+                std::iter::once(held.action_id).chain(held.blocked_action_ids.iter().copied())
+            })
+            .collect();
+        let mut cyclic_action_ids: Vec<ActionId> = remaining
+            .remaining_actions()
+            .into_iter()
+            .filter(|action_id| !held_up.contains(action_id))
+            .collect();
+        cyclic_action_ids.sort();
+
+        // Order the actions the way they'll be dispatched, with anything that never runs last.
+        let position: HashMap<ActionId, usize> = batches
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(position, action_id)| (*action_id, position))
+            .collect();
+        entries.sort_by_key(|entry| {
+            (
+                position.get(&entry.id).copied().unwrap_or(usize::MAX),
+                entry.id,
+            )
+        });
+
+        Ok(Self {
+            change_set_id,
+            base_change_set_id,
+            actions: entries,
+            dependencies,
+            batches,
+            held,
+            cyclic_action_ids,
+        })
+    }
+
+    /// Renders the plan as a Graphviz DOT digraph. Edges point from an action to the actions that
+    /// wait on it, each batch shares a rank, and held actions are highlighted.
+    pub fn to_dot(&self) -> String {
+        let held_ids: HashSet<ActionId> = self.held.iter().map(|held| held.action_id).collect();
+
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph apply_plan {{");
+        let _ = writeln!(dot, "  rankdir=LR;");
+        let _ = writeln!(dot, "  node [shape=box, style=rounded];");
+
+        for entry in &self.actions {
+            let mut label = format!("{} ({})", dot_escape(&entry.name), entry.kind);
+            if let Some(component_name) = &entry.component_name {
+                label.push_str("\\n");
+                label.push_str(&dot_escape(component_name));
+            }
+            let style = if held_ids.contains(&entry.id) {
+                ", style=\"rounded,filled\", fillcolor=\"#f8d7a9\""
+            } else if self.cyclic_action_ids.contains(&entry.id) {
+                ", style=\"rounded,filled\", fillcolor=\"#f5b7b1\""
+            } else if entry.from_change_set {
+                ", penwidth=2"
+            } else {
+                ""
+            };
+            let _ = writeln!(dot, "  \"{}\" [label=\"{label}\"{style}];", entry.id);
+        }
+
+        for (index, batch) in self.batches.iter().enumerate() {
+            let _ = write!(dot, "  subgraph batch_{index} {{ rank=same;");
+            for action_id in batch {
+                let _ = write!(dot, " \"{action_id}\";");
+            }
+            let _ = writeln!(dot, " }}");
+        }
+
+        for dependency in &self.dependencies {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\";",
+                dependency.depends_on, dependency.action_id
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
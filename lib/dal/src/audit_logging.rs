@@ -13,6 +13,7 @@ use shuttle_server::Shuttle;
 use shuttle_server::ShuttleError;
 use si_events::audit_log::AuditLog;
 use si_events::audit_log::AuditLogKind;
+use si_events::WebhookEventKind;
 use telemetry::prelude::*;
 use thiserror::Error;
 use tokio_util::task::TaskTracker;
@@ -210,18 +211,36 @@ pub(crate) async fn write(
     let destination_change_set_id =
         override_destination_change_set_id.unwrap_or(ctx.change_set_id());
 
+    // Webhooks posted to Slack name the actor, so their email is looked up for those kinds only
+    let actor_email = if WebhookEventKind::for_audit_log_kind(&kind).is_some() {
+        match ctx.history_actor().email(ctx).await {
+            Ok(email) => Some(email),
+            Err(err) => {
+                warn!(si.error.message = ?err, "failed to look up the email of the audit log actor");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut audit_log = AuditLog::new(
+        ctx.events_actor(),
+        kind,
+        entity_name,
+        destination_change_set_id,
+    );
+    if let Some(actor_email) = actor_email {
+        audit_log = audit_log.with_actor_email(actor_email);
+    }
+
     let pending_events_stream = PendingEventsStream::get_or_create(ctx.jetstream_context()).await?;
     pending_events_stream
         .publish_audit_log(
             workspace_id,
             ctx.change_set_id(),
             ctx.event_session_id(),
-            &AuditLog::new(
-                ctx.events_actor(),
-                kind,
-                entity_name,
-                destination_change_set_id,
-            ),
+            &audit_log,
             destination_change_set_id,
         )
         .await?;
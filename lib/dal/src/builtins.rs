@@ -8,6 +8,7 @@ use thiserror::Error;
 
 use si_pkg::{SiPkgError, SpecError};
 
+use crate::change_set::ChangeSetError;
 use crate::func::argument::FuncArgumentError;
 use crate::func::FuncError;
 use crate::module::ModuleError;
@@ -29,6 +30,8 @@ pub enum BuiltinsError {
     AttributeValueNotFound(AttributeValueId),
     #[error("builtin {0} missing func argument {1}")]
     BuiltinMissingFuncArgument(String, String),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] ChangeSetError),
     #[error("func error")]
     Func(#[from] FuncError),
     #[error("func argument error: {0}")]
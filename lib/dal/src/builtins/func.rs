@@ -2,7 +2,9 @@ use si_pkg::SiPkg;
 
 use crate::module::Module;
 use crate::{
-    func::intrinsics::IntrinsicFunc, pkg::import_pkg_from_pkg, BuiltinsResult, DalContext,
+    func::intrinsics::IntrinsicFunc,
+    pkg::{import::import_funcs_missing_by_name, import_pkg_from_pkg},
+    BuiltinsResult, ChangeSet, ChangeSetStatus, DalContext, Tenancy, Visibility,
 };
 use telemetry::prelude::*;
 
@@ -54,3 +56,49 @@ pub async fn migrate_intrinsics_no_commit(ctx: &DalContext) -> BuiltinsResult<()
     import_pkg_from_pkg(ctx, &intrinsics_pkg, None).await?;
     Ok(())
 }
+
+/// Imports the intrinsic funcs that a workspace created before they existed (such as the
+/// transforms) is missing. The intrinsics package is only imported whole when a workspace is
+/// created, so this is how existing workspaces pick up new intrinsics. Returns the number of funcs
+/// imported; does not commit.
+#[instrument(skip_all)]
+pub async fn migrate_missing_intrinsics_no_commit(ctx: &DalContext) -> BuiltinsResult<usize> {
+    let intrinsics_pkg_spec = IntrinsicFunc::pkg_spec()?;
+    let intrinsics_pkg = SiPkg::load_from_spec(intrinsics_pkg_spec)?;
+    let imported = import_funcs_missing_by_name(ctx, intrinsics_pkg.funcs()?).await?;
+    Ok(imported.len())
+}
+
+/// Runs [`migrate_missing_intrinsics_no_commit`] against every open change set, pointing each one
+/// that was missing intrinsics at its updated snapshot. Change sets that already have every
+/// intrinsic are left alone, so this is safe to run on every startup.
+#[instrument(skip_all)]
+pub async fn migrate_missing_intrinsics_for_all_workspaces(ctx: &DalContext) -> BuiltinsResult<()> {
+    let mut migrated = 0;
+    for change_set in ChangeSet::list_open_for_all_workspaces(ctx).await? {
+        let mut change_set = ChangeSet::get_by_id_across_workspaces(ctx, change_set.id).await?;
+        let workspace_pk = match change_set.workspace_id {
+            Some(workspace_pk) if change_set.status != ChangeSetStatus::Failed => workspace_pk,
+            _ => continue,
+        };
+
+        let mut change_set_ctx = ctx.clone_with_new_visibility(Visibility::from(change_set.id));
+        change_set_ctx.update_tenancy(Tenancy::new(workspace_pk));
+        change_set_ctx.update_snapshot_to_visibility().await?;
+
+        if migrate_missing_intrinsics_no_commit(&change_set_ctx).await? == 0 {
+            continue;
+        }
+
+        if let Some(snapshot_address) = change_set_ctx.write_snapshot().await? {
+            change_set
+                .update_pointer(&change_set_ctx, snapshot_address)
+                .await?;
+            migrated += 1;
+        }
+    }
+
+    info!("Imported missing intrinsics into {migrated} change set(s)");
+
+    Ok(())
+}
@@ -14,6 +14,8 @@ use thiserror::Error;
 use tokio::time;
 
 use crate::billing_publish::BillingPublishError;
+use crate::change_set::approval::{ChangeSetApprovalError, ChangeSetApprovals};
+use crate::change_set::policy::{ChangeSetPolicy, ChangeSetPolicyError, PolicyResult};
 use crate::slow_rt::SlowRuntimeError;
 use crate::workspace_snapshot::graph::RebaseBatch;
 use crate::{
@@ -27,7 +29,11 @@ use crate::{
     WorkspaceError,
 };
 
+pub mod approval;
+pub mod comment;
 pub mod event;
+pub mod policy;
+pub mod schedule;
 pub mod status;
 pub mod view;
 
@@ -36,6 +42,10 @@ const FIND_ANCESTORS_QUERY: &str = include_str!("queries/change_set/find_ancesto
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ChangeSetError {
+    #[error("change set approval error: {0}")]
+    Approval(#[from] Box<ChangeSetApprovalError>),
+    #[error("approval rules not satisfied: {}", .0.join(", "))]
+    ApprovalRulesNotSatisfied(Vec<String>),
     #[error("billing publish error: {0}")]
     BillingPublish(#[from] Box<BillingPublishError>),
     #[error("change set not approved for apply. Current state: {0}")]
@@ -46,8 +56,12 @@ pub enum ChangeSetError {
     DefaultChangeSetNoWorkspaceSnapshotPointer(ChangeSetId),
     #[error("dvu roots are not empty for change set: {0}")]
     DvuRootsNotEmpty(ChangeSetId),
+    #[error("change set {0} has been edited since it was approved")]
+    EditedSinceApproval(ChangeSetId),
     #[error("enum parse error: {0}")]
     EnumParse(#[from] strum::ParseError),
+    #[error("change set {0} pointer was moved by a holder of a newer fencing token than {1}")]
+    FencingTokenSuperseded(ChangeSetId, u64),
     #[error("func error: {0}")]
     Func(#[from] Box<FuncError>),
     #[error("history event error: {0}")]
@@ -74,6 +88,10 @@ pub enum ChangeSetError {
     NoWorkspaceSnapshot(ChangeSetId),
     #[error("pg error: {0}")]
     Pg(#[from] PgError),
+    #[error("change set policy error: {0}")]
+    Policy(#[from] Box<ChangeSetPolicyError>),
+    #[error("blocking policies failed: {}", .0.join(", "))]
+    PolicyChecksFailed(Vec<String>),
     #[error("rebaser client error: {0}")]
     RebaserClient(#[from] rebaser_client::ClientError),
     #[error("schema error: {0}")]
@@ -340,6 +358,44 @@ impl ChangeSet {
         Ok(())
     }
 
+    /// Moves the pointer like [`Self::update_pointer`], but only if no write with a newer
+    /// fencing token has moved it since. The rebaser passes the fencing token of its change set
+    /// lease, so that an instance which lost its lease cannot overwrite the work of the instance
+    /// that took over, however long it stalled for.
+    pub async fn update_pointer_fenced(
+        &mut self,
+        ctx: &DalContext,
+        workspace_snapshot_address: WorkspaceSnapshotAddress,
+        fencing_token: u64,
+    ) -> ChangeSetResult<()> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE change_set_pointers
+                   SET workspace_snapshot_address = $2, fencing_token = $3, updated_at = CLOCK_TIMESTAMP()
+                   WHERE id = $1 AND fencing_token <= $3
+                   RETURNING id",
+                &[&self.id, &workspace_snapshot_address, &(fencing_token as i64)],
+            )
+            .await?;
+        if maybe_row.is_none() {
+            return Err(ChangeSetError::FencingTokenSuperseded(
+                self.id,
+                fencing_token,
+            ));
+        }
+
+        self.workspace_snapshot_address = workspace_snapshot_address;
+
+        billing_publish::for_head_change_set_pointer_update(ctx, self)
+            .await
+            .map_err(Box::new)?;
+
+        Ok(())
+    }
+
     pub async fn update_status(
         &mut self,
         ctx: &DalContext,
@@ -378,9 +434,10 @@ impl ChangeSet {
         Ok(())
     }
 
-    /// Set the status to Open, and clear any reviewed/merge requested info
+    /// Set the status to Open, and clear any reviewed/merge requested info and approvals
     pub async fn reopen_change_set(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         let status = ChangeSetStatus::Open;
+        ChangeSetApprovals::clear(ctx).await.map_err(Box::new)?;
         ctx.txns()
             .await?
             .pg()
@@ -415,9 +472,10 @@ impl ChangeSet {
         Self::prepare_for_apply(ctx).await
     }
 
-    /// First, checks if DVU Roots still exist. Next, ensures the [`ChangeSet`] has an
-    /// [`ChangeSetStatus::Approved`]. Finally,
-    /// lock every [`SchemaVariant`] and [`Func`] that is currently unlocked
+    /// First, checks if DVU Roots still exist. Next, ensures the [`ChangeSet`] satisfies every
+    /// [approval rule](approval) that applies to it, has an [`ChangeSetStatus::Approved`] and that no
+    /// [policy](policy) is failing without an override. Finally, lock every [`SchemaVariant`] and
+    /// [`Func`] that is currently unlocked
     pub async fn prepare_for_apply(ctx: &DalContext) -> ChangeSetResult<()> {
         let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id()).await?;
 
@@ -438,6 +496,20 @@ impl ChangeSet {
             return Err(ChangeSetError::DvuRootsNotEmpty(ctx.change_set_id()));
         }
 
+        // The change set may have changed since it was approved, which voids the approvals given
+        // before, so check the rules again
+        let approval_status = ChangeSetApprovals::evaluate(ctx).await.map_err(Box::new)?;
+        if !approval_status.satisfied {
+            let unsatisfied_rule_names = approval_status.unsatisfied_rule_names();
+            if !unsatisfied_rule_names.is_empty() {
+                return Err(ChangeSetError::ApprovalRulesNotSatisfied(
+                    unsatisfied_rule_names,
+                ));
+            } else if change_set.status == ChangeSetStatus::Approved {
+                return Err(ChangeSetError::EditedSinceApproval(change_set.id));
+            }
+        }
+
         // if the change set status isn't approved, we shouldn't go
         // locking stuff
         if change_set.status != ChangeSetStatus::Approved {
@@ -446,6 +518,17 @@ impl ChangeSet {
             ));
         }
 
+        let blocking_policies: Vec<String> = ChangeSetPolicy::evaluate(ctx)
+            .await
+            .map_err(Box::new)?
+            .into_iter()
+            .filter(PolicyResult::is_blocking)
+            .map(|result| result.func_name)
+            .collect();
+        if !blocking_policies.is_empty() {
+            return Err(ChangeSetError::PolicyChecksFailed(blocking_policies));
+        }
+
         // Lock all unlocked variants
         for schema_id in Schema::list_ids(ctx).await.map_err(Box::new)? {
             let schema = Schema::get_by_id_or_error(ctx, schema_id)
@@ -478,9 +561,24 @@ impl ChangeSet {
         Ok(())
     }
 
+    /// Records the current user's approval, then moves the [`ChangeSet`] to
+    /// [`ChangeSetStatus::Approved`] if every [approval rule](approval) that applies to it is
+    /// satisfied. Otherwise it stays in [`ChangeSetStatus::NeedsApproval`] until enough approvers
+    /// have weighed in.
     pub async fn approve_change_set_for_apply(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         let user_pk = Self::extract_userid_from_context_or_error(ctx).await?;
-        let status = ChangeSetStatus::Approved;
+        ChangeSetApprovals::record(ctx, user_pk)
+            .await
+            .map_err(Box::new)?;
+        let status = if ChangeSetApprovals::evaluate(ctx)
+            .await
+            .map_err(Box::new)?
+            .satisfied
+        {
+            ChangeSetStatus::Approved
+        } else {
+            ChangeSetStatus::NeedsApproval
+        };
         ctx.txns()
             .await?
             .pg()
@@ -497,6 +595,9 @@ impl ChangeSet {
 
     pub async fn reject_change_set_for_apply(&mut self, ctx: &DalContext) -> ChangeSetResult<()> {
         let user_pk = Self::extract_userid_from_context_or_error(ctx).await?;
+        ChangeSetApprovals::withdraw(ctx, user_pk)
+            .await
+            .map_err(Box::new)?;
         let status = ChangeSetStatus::Rejected;
         ctx.txns()
             .await?
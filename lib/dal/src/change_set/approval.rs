@@ -0,0 +1,694 @@
+//! Approval rules decide how many approvals a [`ChangeSet`] needs, and from whom, before it can be
+//! applied.
+//!
+//! A rule is scoped by what the change set touches relative to HEAD: components of a given schema,
+//! components in a given view, or any func. A rule with no scope applies to every change set. Each
+//! approval is recorded per user, and a rule is satisfied once enough of those approvals count
+//! towards it, i.e. they come from the rule's approvers (anyone, if it names none) and, unless the
+//! rule allows it, not from the user who requested the approval. Approvers are named individually
+//! or through [approver groups](ApproverGroup).
+//!
+//! An approval is given against the change set as it was at the time. Once the change set is
+//! edited, it no longer counts and has to be given again.
+//!
+//! A workspace with no rules keeps the original behavior: a single approval is enough.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use si_events::WorkspaceSnapshotAddress;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use super::comment::{ChangeSetCommentError, CommentThread};
+use crate::{
+    diagram::{geometry::Geometry, view::ViewId, DiagramError},
+    ChangeSet, ChangeSetError, ChangeSetId, Component, ComponentError, ComponentId, DalContext,
+    Func, FuncError, FuncId, TransactionsError, UserPk,
+};
+
+pub use si_id::{ApprovalRuleId, ApproverGroupId};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ChangeSetApprovalError {
+    #[error("approver group not found: {0}")]
+    ApproverGroupNotFound(ApproverGroupId),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] Box<ChangeSetError>),
+    #[error("comment error: {0}")]
+    Comment(#[from] Box<ChangeSetCommentError>),
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] Box<DiagramError>),
+    #[error("func error: {0}")]
+    Func(#[from] Box<FuncError>),
+    #[error("approval rule must require at least one approval: {0}")]
+    InvalidRequiredApprovals(u32),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("approval rule not found: {0}")]
+    RuleNotFound(ApprovalRuleId),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("ulid decode error: {0}")]
+    UlidDecode(#[from] ulid::DecodeError),
+}
+
+pub type ChangeSetApprovalResult<T> = Result<T, ChangeSetApprovalError>;
+
+/// The fields needed to create an [`ApprovalRule`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRuleSpec {
+    pub name: String,
+    pub required_approvals: u32,
+    /// The rule applies if the change set touches a component of any of these schemas.
+    #[serde(default)]
+    pub schema_names: Vec<String>,
+    /// The rule applies if the change set touches a component in any of these views.
+    #[serde(default)]
+    pub view_ids: Vec<ViewId>,
+    /// The rule applies if the change set creates, changes or removes any func.
+    #[serde(default)]
+    pub match_funcs: bool,
+    /// Only approvals from these users, or from members of the approver groups, count towards the
+    /// rule. If neither names anyone, any approver counts.
+    #[serde(default)]
+    pub approver_user_pks: Vec<UserPk>,
+    #[serde(default)]
+    pub approver_group_ids: Vec<ApproverGroupId>,
+    /// Whether the user who requested approval may count towards the rule.
+    pub allow_self_approval: bool,
+}
+
+/// A workspace-wide rule for approving change sets.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRule {
+    pub id: ApprovalRuleId,
+    pub name: String,
+    pub required_approvals: u32,
+    pub schema_names: Vec<String>,
+    pub view_ids: Vec<ViewId>,
+    pub match_funcs: bool,
+    pub approver_user_pks: Vec<UserPk>,
+    pub approver_group_ids: Vec<ApproverGroupId>,
+    pub allow_self_approval: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for ApprovalRule {
+    type Error = ChangeSetApprovalError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let required_approvals: i32 = row.try_get("required_approvals")?;
+        let view_ids: Vec<String> = row.try_get("view_ids")?;
+        let approver_user_pks: Vec<String> = row.try_get("approver_user_pks")?;
+        let approver_group_ids: Vec<String> = row.try_get("approver_group_ids")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            required_approvals: required_approvals.max(0) as u32,
+            schema_names: row.try_get("schema_names")?,
+            view_ids: view_ids
+                .iter()
+                .map(|id| id.parse())
+                .collect::<Result<_, _>>()?,
+            match_funcs: row.try_get("match_funcs")?,
+            approver_user_pks: approver_user_pks
+                .iter()
+                .map(|pk| pk.parse())
+                .collect::<Result<_, _>>()?,
+            approver_group_ids: approver_group_ids
+                .iter()
+                .map(|id| id.parse())
+                .collect::<Result<_, _>>()?,
+            allow_self_approval: row.try_get("allow_self_approval")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl ApprovalRule {
+    pub async fn new(ctx: &DalContext, spec: ApprovalRuleSpec) -> ChangeSetApprovalResult<Self> {
+        if spec.required_approvals == 0 {
+            return Err(ChangeSetApprovalError::InvalidRequiredApprovals(
+                spec.required_approvals,
+            ));
+        }
+
+        let view_ids: Vec<String> = spec.view_ids.iter().map(ToString::to_string).collect();
+        let approver_user_pks: Vec<String> = spec
+            .approver_user_pks
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let groups = ApproverGroup::list(ctx).await?;
+        if let Some(missing) = spec
+            .approver_group_ids
+            .iter()
+            .find(|group_id| !groups.iter().any(|group| group.id == **group_id))
+        {
+            return Err(ChangeSetApprovalError::ApproverGroupNotFound(*missing));
+        }
+        let approver_group_ids: Vec<String> = spec
+            .approver_group_ids
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO change_set_approval_rules
+                   (workspace_pk, name, required_approvals, schema_names, view_ids, match_funcs,
+                    approver_user_pks, approver_group_ids, allow_self_approval)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                   RETURNING *",
+                &[
+                    &ctx.workspace_pk()?,
+                    &spec.name,
+                    &(spec.required_approvals.min(i32::MAX as u32) as i32),
+                    &spec.schema_names,
+                    &view_ids,
+                    &spec.match_funcs,
+                    &approver_user_pks,
+                    &approver_group_ids,
+                    &spec.allow_self_approval,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Lists the approval rules for the current workspace, oldest first.
+    pub async fn list(ctx: &DalContext) -> ChangeSetApprovalResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_approval_rules WHERE workspace_pk = $1 ORDER BY created_at",
+                &[&ctx.workspace_pk()?],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    pub async fn remove(ctx: &DalContext, id: ApprovalRuleId) -> ChangeSetApprovalResult<()> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "DELETE FROM change_set_approval_rules WHERE workspace_pk = $1 AND id = $2 RETURNING id",
+                &[&ctx.workspace_pk()?, &id],
+            )
+            .await?;
+        if maybe_row.is_none() {
+            return Err(ChangeSetApprovalError::RuleNotFound(id));
+        }
+
+        Ok(())
+    }
+
+    /// A rule with no scope applies to every change set.
+    fn is_scoped(&self) -> bool {
+        !self.schema_names.is_empty() || !self.view_ids.is_empty() || self.match_funcs
+    }
+
+    fn applies_to(&self, touched: &TouchedByChangeSet) -> bool {
+        !self.is_scoped()
+            || self
+                .schema_names
+                .iter()
+                .any(|name| touched.schema_names.contains(name))
+            || self
+                .view_ids
+                .iter()
+                .any(|view_id| touched.view_ids.contains(view_id))
+            || (self.match_funcs && !touched.func_ids.is_empty())
+    }
+
+    /// Whether the given user is one of the rule's approvers. Groups that have since been removed
+    /// contribute no approvers, rather than opening the rule up to anyone.
+    fn is_approver(&self, user_pk: UserPk, groups: &[ApproverGroup]) -> bool {
+        (self.approver_user_pks.is_empty() && self.approver_group_ids.is_empty())
+            || self.approver_user_pks.contains(&user_pk)
+            || groups.iter().any(|group| {
+                self.approver_group_ids.contains(&group.id)
+                    && group.member_user_pks.contains(&user_pk)
+            })
+    }
+
+    fn counts(
+        &self,
+        user_pk: UserPk,
+        requested_by: Option<UserPk>,
+        groups: &[ApproverGroup],
+    ) -> bool {
+        self.is_approver(user_pk, groups)
+            && (self.allow_self_approval || requested_by != Some(user_pk))
+    }
+}
+
+/// The fields needed to create an [`ApproverGroup`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApproverGroupSpec {
+    pub name: String,
+    #[serde(default)]
+    pub member_user_pks: Vec<UserPk>,
+}
+
+/// A named set of users that [approval rules](ApprovalRule) can name as their approvers, so that
+/// who may approve is managed in one place.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApproverGroup {
+    pub id: ApproverGroupId,
+    pub name: String,
+    pub member_user_pks: Vec<UserPk>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for ApproverGroup {
+    type Error = ChangeSetApprovalError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let member_user_pks: Vec<String> = row.try_get("member_user_pks")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            member_user_pks: member_user_pks
+                .iter()
+                .map(|pk| pk.parse())
+                .collect::<Result<_, _>>()?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+impl ApproverGroup {
+    pub async fn new(ctx: &DalContext, spec: ApproverGroupSpec) -> ChangeSetApprovalResult<Self> {
+        let member_user_pks: Vec<String> = spec
+            .member_user_pks
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO change_set_approver_groups (workspace_pk, name, member_user_pks)
+                   VALUES ($1, $2, $3)
+                   RETURNING *",
+                &[&ctx.workspace_pk()?, &spec.name, &member_user_pks],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Lists the approver groups for the current workspace, oldest first.
+    pub async fn list(ctx: &DalContext) -> ChangeSetApprovalResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_approver_groups WHERE workspace_pk = $1 ORDER BY created_at",
+                &[&ctx.workspace_pk()?],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Replaces the members of the group. This takes effect for every rule that names the group,
+    /// including on change sets that are already awaiting approval.
+    pub async fn set_members(
+        ctx: &DalContext,
+        id: ApproverGroupId,
+        member_user_pks: Vec<UserPk>,
+    ) -> ChangeSetApprovalResult<Self> {
+        let member_user_pks: Vec<String> =
+            member_user_pks.iter().map(ToString::to_string).collect();
+
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE change_set_approver_groups SET member_user_pks = $3
+                   WHERE workspace_pk = $1 AND id = $2
+                   RETURNING *",
+                &[&ctx.workspace_pk()?, &id, &member_user_pks],
+            )
+            .await?;
+
+        match maybe_row {
+            Some(row) => Self::try_from(row),
+            None => Err(ChangeSetApprovalError::ApproverGroupNotFound(id)),
+        }
+    }
+
+    pub async fn remove(ctx: &DalContext, id: ApproverGroupId) -> ChangeSetApprovalResult<()> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "DELETE FROM change_set_approver_groups WHERE workspace_pk = $1 AND id = $2 RETURNING id",
+                &[&ctx.workspace_pk()?, &id],
+            )
+            .await?;
+        if maybe_row.is_none() {
+            return Err(ChangeSetApprovalError::ApproverGroupNotFound(id));
+        }
+
+        Ok(())
+    }
+}
+
+/// An approval recorded against a change set.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApproval {
+    pub user_pk: UserPk,
+    pub approved_at: DateTime<Utc>,
+    /// The snapshot of the change set that was approved, if it was recorded.
+    pub approved_snapshot_address: Option<WorkspaceSnapshotAddress>,
+}
+
+impl TryFrom<PgRow> for ChangeSetApproval {
+    type Error = ChangeSetApprovalError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            user_pk: row.try_get("user_pk")?,
+            approved_at: row.try_get("approved_at")?,
+            approved_snapshot_address: row.try_get("approved_snapshot_address")?,
+        })
+    }
+}
+
+impl ChangeSetApproval {
+    /// Whether the approval was given against the change set as it is now.
+    fn is_current(&self, change_set: &ChangeSet) -> bool {
+        self.approved_snapshot_address == Some(change_set.workspace_snapshot_address)
+    }
+}
+
+/// What a change set creates, changes or removes relative to HEAD, as far as approval rules care.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TouchedByChangeSet {
+    pub component_ids: BTreeSet<ComponentId>,
+    pub schema_names: BTreeSet<String>,
+    pub view_ids: BTreeSet<ViewId>,
+    pub func_ids: BTreeSet<FuncId>,
+}
+
+/// How an [`ApprovalRule`] fares against the approvals on a change set.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRuleEvaluation {
+    pub rule: ApprovalRule,
+    pub satisfied: bool,
+    /// Approvals that count towards the rule.
+    pub counted_approvals: Vec<UserPk>,
+    /// Approvals that don't, because the user isn't one of the rule's approvers or requested the
+    /// approval themselves.
+    pub ignored_approvals: Vec<UserPk>,
+}
+
+/// The approval state of a change set, shown to approvers.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetApprovalStatus {
+    pub change_set_id: ChangeSetId,
+    pub requested_by_user_pk: Option<UserPk>,
+    /// Approvals given against the change set as it is now.
+    pub approvals: Vec<ChangeSetApproval>,
+    /// Approvals given before the change set was last edited, which no longer count.
+    pub stale_approvals: Vec<ChangeSetApproval>,
+    pub touched: TouchedByChangeSet,
+    /// Only the rules that apply to this change set.
+    pub rules: Vec<ApprovalRuleEvaluation>,
+    /// Whether every rule that applies is satisfied. With no rules to apply, a single current
+    /// approval is enough.
+    pub satisfied: bool,
+    /// Unresolved comment threads, so approvers see outstanding discussion before approving.
+    /// These don't block approval.
+    pub open_comment_threads: Vec<CommentThread>,
+}
+
+impl ChangeSetApprovalStatus {
+    /// The names of the applicable rules that are not yet satisfied.
+    pub fn unsatisfied_rule_names(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .filter(|evaluation| !evaluation.satisfied)
+            .map(|evaluation| evaluation.rule.name.clone())
+            .collect()
+    }
+}
+
+/// Logic for recording approvals on a change set and checking them against the workspace's
+/// [`ApprovalRules`](ApprovalRule).
+pub struct ChangeSetApprovals;
+
+impl ChangeSetApprovals {
+    /// Records an approval from the given user against the change set as it is now. Approving
+    /// again keeps the original approval, unless the change set has been edited since.
+    pub async fn record(ctx: &DalContext, user_pk: UserPk) -> ChangeSetApprovalResult<()> {
+        let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+            .await
+            .map_err(Box::new)?;
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "INSERT INTO change_set_approvals
+                   (change_set_id, workspace_pk, user_pk, approved_snapshot_address)
+                   VALUES ($1, $2, $3, $4)
+                   ON CONFLICT (change_set_id, user_pk) DO UPDATE
+                   SET approved_at = EXCLUDED.approved_at,
+                       approved_snapshot_address = EXCLUDED.approved_snapshot_address
+                   WHERE change_set_approvals.approved_snapshot_address
+                       IS DISTINCT FROM EXCLUDED.approved_snapshot_address",
+                &[
+                    &change_set.id,
+                    &ctx.workspace_pk()?,
+                    &user_pk,
+                    &change_set.workspace_snapshot_address,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Withdraws the given user's approval, if any.
+    pub async fn withdraw(ctx: &DalContext, user_pk: UserPk) -> ChangeSetApprovalResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "DELETE FROM change_set_approvals WHERE change_set_id = $1 AND user_pk = $2",
+                &[&ctx.change_set_id(), &user_pk],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Removes every approval on the change set.
+    pub async fn clear(ctx: &DalContext) -> ChangeSetApprovalResult<()> {
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "DELETE FROM change_set_approvals WHERE change_set_id = $1",
+                &[&ctx.change_set_id()],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list(ctx: &DalContext) -> ChangeSetApprovalResult<Vec<ChangeSetApproval>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_approvals WHERE change_set_id = $1 ORDER BY approved_at",
+                &[&ctx.change_set_id()],
+            )
+            .await?;
+
+        rows.into_iter().map(ChangeSetApproval::try_from).collect()
+    }
+
+    /// Checks the approvals on the change set in the [`DalContext`] against every rule that
+    /// applies to it. Only approvals given against the change set as it is now count, so this is
+    /// checked again right before applying.
+    #[instrument(name = "change_set.approval.evaluate", level = "info", skip_all)]
+    pub async fn evaluate(ctx: &DalContext) -> ChangeSetApprovalResult<ChangeSetApprovalStatus> {
+        let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+            .await
+            .map_err(Box::new)?;
+        let requested_by = change_set.merge_requested_by_user_id;
+        let (approvals, stale_approvals): (Vec<ChangeSetApproval>, Vec<ChangeSetApproval>) =
+            Self::list(ctx)
+                .await?
+                .into_iter()
+                .partition(|approval| approval.is_current(&change_set));
+        let rules = ApprovalRule::list(ctx).await?;
+        let groups = if rules.iter().any(|rule| !rule.approver_group_ids.is_empty()) {
+            ApproverGroup::list(ctx).await?
+        } else {
+            Vec::new()
+        };
+
+        // Working out what the change set touches means walking every component on both sides,
+        // so only do it when a rule needs it.
+        let touched = if rules.iter().any(ApprovalRule::is_scoped) {
+            Self::touched(ctx).await?
+        } else {
+            TouchedByChangeSet::default()
+        };
+
+        let rules: Vec<ApprovalRuleEvaluation> = rules
+            .into_iter()
+            .filter(|rule| rule.applies_to(&touched))
+            .map(|rule| {
+                let (counted_approvals, ignored_approvals): (Vec<UserPk>, Vec<UserPk>) = approvals
+                    .iter()
+                    .map(|approval| approval.user_pk)
+                    .partition(|user_pk| rule.counts(*user_pk, requested_by, &groups));
+                ApprovalRuleEvaluation {
+                    satisfied: counted_approvals.len() >= rule.required_approvals as usize,
+                    rule,
+                    counted_approvals,
+                    ignored_approvals,
+                }
+            })
+            .collect();
+
+        let satisfied = if rules.is_empty() {
+            !approvals.is_empty()
+        } else {
+            rules.iter().all(|evaluation| evaluation.satisfied)
+        };
+        let open_comment_threads = CommentThread::list_open(ctx).await.map_err(Box::new)?;
+
+        Ok(ChangeSetApprovalStatus {
+            change_set_id: change_set.id,
+            requested_by_user_pk: requested_by,
+            approvals,
+            stale_approvals,
+            touched,
+            rules,
+            satisfied,
+            open_comment_threads,
+        })
+    }
+
+    /// Works out which components and funcs the change set in the [`DalContext`] adds, changes or
+    /// removes relative to HEAD.
+    pub async fn touched(ctx: &DalContext) -> ChangeSetApprovalResult<TouchedByChangeSet> {
+        let head_ctx = ctx.clone_with_head().await?;
+        let mut touched = TouchedByChangeSet::default();
+
+        let mut head_component_ids: BTreeSet<ComponentId> = Component::list_ids(&head_ctx)
+            .await
+            .map_err(Box::new)?
+            .into_iter()
+            .collect();
+        for component in Component::list(ctx).await.map_err(Box::new)? {
+            let component_id = component.id();
+            let in_head = head_component_ids.remove(&component_id);
+
+            let is_touched = if !in_head || component.to_delete() {
+                true
+            } else {
+                let head_component = Component::get_by_id(&head_ctx, component_id)
+                    .await
+                    .map_err(Box::new)?;
+                component.view(ctx).await.map_err(Box::new)?
+                    != head_component.view(&head_ctx).await.map_err(Box::new)?
+            };
+            if !is_touched {
+                continue;
+            }
+
+            Self::add_touched_component(ctx, &mut touched, &component).await?;
+        }
+
+        // Whatever is left in HEAD has been removed outright by the change set.
+        for component_id in head_component_ids {
+            let component = Component::get_by_id(&head_ctx, component_id)
+                .await
+                .map_err(Box::new)?;
+            Self::add_touched_component(&head_ctx, &mut touched, &component).await?;
+        }
+
+        let mut head_funcs: HashMap<FuncId, Func> = Func::list_all(&head_ctx)
+            .await
+            .map_err(Box::new)?
+            .into_iter()
+            .map(|func| (func.id, func))
+            .collect();
+        for func in Func::list_all(ctx).await.map_err(Box::new)? {
+            match head_funcs.remove(&func.id) {
+                Some(head_func)
+                    if head_func.name == func.name && head_func.code_blake3 == func.code_blake3 => {
+                }
+                _ => {
+                    touched.func_ids.insert(func.id);
+                }
+            }
+        }
+        touched.func_ids.extend(head_funcs.into_keys());
+
+        Ok(touched)
+    }
+
+    async fn add_touched_component(
+        ctx: &DalContext,
+        touched: &mut TouchedByChangeSet,
+        component: &Component,
+    ) -> ChangeSetApprovalResult<()> {
+        touched.component_ids.insert(component.id());
+        touched.schema_names.insert(
+            component
+                .schema(ctx)
+                .await
+                .map_err(Box::new)?
+                .name()
+                .to_owned(),
+        );
+        touched.view_ids.extend(
+            Geometry::by_view_for_component_id(ctx, component.id())
+                .await
+                .map_err(Box::new)?
+                .into_keys(),
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,644 @@
+//! Comments let reviewers discuss a [`ChangeSet`] in context.
+//!
+//! Comments are grouped into [threads](CommentThread). A thread is attached to the change set as a
+//! whole, to a component, or to an attribute path on a component. Threads are stored per change
+//! set in the database rather than in the workspace snapshot, so they never reach HEAD. A thread
+//! can be resolved and unresolved by anyone; a comment can only be edited or deleted by its
+//! author. Deleting the last comment in a thread removes the thread.
+//!
+//! Comments can mention workspace members, who are recorded on the comment so the frontend can
+//! notify them. Open threads are shown to approvers alongside the
+//! [approval status](super::approval::ChangeSetApprovalStatus).
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use si_events::audit_log::AuditLogKind;
+use thiserror::Error;
+
+use crate::{
+    ChangeSet, ChangeSetError, Component, ComponentError, ComponentId, DalContext,
+    TransactionsError, User, UserError, UserPk, WsEvent, WsEventError,
+};
+
+pub use si_id::{CommentId, CommentThreadId};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ChangeSetCommentError {
+    #[error("attribute path {0} needs a component to comment on")]
+    AttributePathWithoutComponent(String),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] Box<ChangeSetError>),
+    #[error("comment not found: {0}")]
+    CommentNotFound(CommentId),
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("component not found in change set: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("comment body cannot be empty")]
+    EmptyBody,
+    #[error("only the author of comment {0} can change it")]
+    NotAuthor(CommentId),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("comment thread not found: {0}")]
+    ThreadNotFound(CommentThreadId),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("ulid decode error: {0}")]
+    UlidDecode(#[from] ulid::DecodeError),
+    #[error("mentioned user is not a member of the workspace: {0}")]
+    UnknownMention(UserPk),
+    #[error("user error: {0}")]
+    User(#[from] Box<UserError>),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] Box<WsEventError>),
+}
+
+pub type ChangeSetCommentResult<T> = Result<T, ChangeSetCommentError>;
+
+/// What a [`CommentThread`] is about.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentTarget {
+    /// The thread is about the change set as a whole if this is unset.
+    pub component_id: Option<ComponentId>,
+    /// A path on the component, such as `/domain/region`.
+    pub attribute_path: Option<String>,
+}
+
+/// A single comment in a [`CommentThread`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetComment {
+    pub id: CommentId,
+    pub thread_id: CommentThreadId,
+    pub author_user_pk: UserPk,
+    pub body: String,
+    pub mentioned_user_pks: Vec<UserPk>,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<PgRow> for ChangeSetComment {
+    type Error = ChangeSetCommentError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let mentioned_user_pks: Vec<String> = row.try_get("mentioned_user_pks")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            thread_id: row.try_get("thread_id")?,
+            author_user_pk: row.try_get("author_user_pk")?,
+            body: row.try_get("body")?,
+            mentioned_user_pks: mentioned_user_pks
+                .iter()
+                .map(|pk| pk.parse())
+                .collect::<Result<_, _>>()?,
+            created_at: row.try_get("created_at")?,
+            edited_at: row.try_get("edited_at")?,
+        })
+    }
+}
+
+/// A discussion on a change set, oldest comment first.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThread {
+    pub id: CommentThreadId,
+    #[serde(flatten)]
+    pub target: CommentTarget,
+    pub created_by_user_pk: UserPk,
+    pub created_at: DateTime<Utc>,
+    pub resolved_by_user_pk: Option<UserPk>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub comments: Vec<ChangeSetComment>,
+}
+
+impl CommentThread {
+    fn from_row(row: PgRow, comments: Vec<ChangeSetComment>) -> ChangeSetCommentResult<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            target: CommentTarget {
+                component_id: row.try_get("component_id")?,
+                attribute_path: row.try_get("attribute_path")?,
+            },
+            created_by_user_pk: row.try_get("created_by_user_pk")?,
+            created_at: row.try_get("created_at")?,
+            resolved_by_user_pk: row.try_get("resolved_by_user_pk")?,
+            resolved_at: row.try_get("resolved_at")?,
+            comments,
+        })
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_at.is_some()
+    }
+
+    /// Starts a thread on the change set in the [`DalContext`] with a first comment from the
+    /// current user.
+    pub async fn new(
+        ctx: &DalContext,
+        target: CommentTarget,
+        body: impl Into<String>,
+        mentioned_user_pks: Vec<UserPk>,
+    ) -> ChangeSetCommentResult<Self> {
+        let body = validate_body(body)?;
+        if let Some(component_id) = target.component_id {
+            if Component::try_get_by_id(ctx, component_id)
+                .await
+                .map_err(Box::new)?
+                .is_none()
+            {
+                return Err(ChangeSetCommentError::ComponentNotFound(component_id));
+            }
+        } else if let Some(attribute_path) = &target.attribute_path {
+            return Err(ChangeSetCommentError::AttributePathWithoutComponent(
+                attribute_path.clone(),
+            ));
+        }
+        validate_mentions(ctx, &mentioned_user_pks).await?;
+        let user_pk = ChangeSet::extract_userid_from_context_or_error(ctx)
+            .await
+            .map_err(Box::new)?;
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO change_set_comment_threads
+                   (workspace_pk, change_set_id, component_id, attribute_path, created_by_user_pk)
+                   VALUES ($1, $2, $3, $4, $5)
+                   RETURNING id",
+                &[
+                    &ctx.workspace_pk()?,
+                    &ctx.change_set_id(),
+                    &target.component_id,
+                    &target.attribute_path,
+                    &user_pk,
+                ],
+            )
+            .await?;
+        let thread_id: CommentThreadId = row.try_get("id")?;
+
+        let comment =
+            ChangeSetComment::insert(ctx, thread_id, user_pk, body, &mentioned_user_pks).await?;
+        ctx.write_audit_log(
+            AuditLogKind::CreateComment {
+                thread_id,
+                comment_id: comment.id,
+                component_id: target.component_id,
+                attribute_path: target.attribute_path.clone(),
+                mentioned_user_pks,
+            },
+            entity_name(ctx, target.component_id).await?,
+        )
+        .await?;
+
+        let thread = Self::get(ctx, thread_id).await?;
+        thread.publish_on_commit(ctx).await?;
+
+        Ok(thread)
+    }
+
+    /// Adds a comment from the current user to the thread.
+    pub async fn reply(
+        ctx: &DalContext,
+        thread_id: CommentThreadId,
+        body: impl Into<String>,
+        mentioned_user_pks: Vec<UserPk>,
+    ) -> ChangeSetCommentResult<Self> {
+        let body = validate_body(body)?;
+        let thread = Self::get(ctx, thread_id).await?;
+        validate_mentions(ctx, &mentioned_user_pks).await?;
+        let user_pk = ChangeSet::extract_userid_from_context_or_error(ctx)
+            .await
+            .map_err(Box::new)?;
+
+        let comment =
+            ChangeSetComment::insert(ctx, thread_id, user_pk, body, &mentioned_user_pks).await?;
+        ctx.write_audit_log(
+            AuditLogKind::CreateComment {
+                thread_id,
+                comment_id: comment.id,
+                component_id: thread.target.component_id,
+                attribute_path: thread.target.attribute_path.clone(),
+                mentioned_user_pks,
+            },
+            entity_name(ctx, thread.target.component_id).await?,
+        )
+        .await?;
+
+        let thread = Self::get(ctx, thread_id).await?;
+        thread.publish_on_commit(ctx).await?;
+
+        Ok(thread)
+    }
+
+    pub async fn get(ctx: &DalContext, id: CommentThreadId) -> ChangeSetCommentResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM change_set_comment_threads WHERE change_set_id = $1 AND id = $2",
+                &[&ctx.change_set_id(), &id],
+            )
+            .await?
+            .ok_or(ChangeSetCommentError::ThreadNotFound(id))?;
+        let comments = ChangeSetComment::list_for_thread(ctx, id).await?;
+
+        Self::from_row(row, comments)
+    }
+
+    /// Lists the threads on the change set in the [`DalContext`], oldest first.
+    pub async fn list(ctx: &DalContext) -> ChangeSetCommentResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_comment_threads WHERE change_set_id = $1 ORDER BY created_at",
+                &[&ctx.change_set_id()],
+            )
+            .await?;
+
+        Self::assemble(ctx, rows).await
+    }
+
+    /// Lists the threads on the component, including those on its attribute paths.
+    pub async fn list_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ChangeSetCommentResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_comment_threads
+                   WHERE change_set_id = $1 AND component_id = $2
+                   ORDER BY created_at",
+                &[&ctx.change_set_id(), &component_id],
+            )
+            .await?;
+
+        Self::assemble(ctx, rows).await
+    }
+
+    /// Lists the threads on the change set that haven't been resolved.
+    pub async fn list_open(ctx: &DalContext) -> ChangeSetCommentResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_comment_threads
+                   WHERE change_set_id = $1 AND resolved_at IS NULL
+                   ORDER BY created_at",
+                &[&ctx.change_set_id()],
+            )
+            .await?;
+
+        Self::assemble(ctx, rows).await
+    }
+
+    async fn assemble(ctx: &DalContext, rows: Vec<PgRow>) -> ChangeSetCommentResult<Vec<Self>> {
+        let mut comments = ChangeSetComment::list_by_thread(ctx).await?;
+
+        let mut threads = Vec::with_capacity(rows.len());
+        for row in rows {
+            let thread_id: CommentThreadId = row.try_get("id")?;
+            threads.push(Self::from_row(
+                row,
+                comments.remove(&thread_id).unwrap_or_default(),
+            )?);
+        }
+
+        Ok(threads)
+    }
+
+    /// Marks the thread as resolved by the current user. Resolving a resolved thread does
+    /// nothing.
+    pub async fn resolve(ctx: &DalContext, id: CommentThreadId) -> ChangeSetCommentResult<Self> {
+        let thread = Self::get(ctx, id).await?;
+        if thread.is_resolved() {
+            return Ok(thread);
+        }
+        let user_pk = ChangeSet::extract_userid_from_context_or_error(ctx)
+            .await
+            .map_err(Box::new)?;
+
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "UPDATE change_set_comment_threads
+                   SET resolved_by_user_pk = $3, resolved_at = clock_timestamp()
+                   WHERE change_set_id = $1 AND id = $2",
+                &[&ctx.change_set_id(), &id, &user_pk],
+            )
+            .await?;
+        ctx.write_audit_log(
+            AuditLogKind::ResolveCommentThread { thread_id: id },
+            entity_name(ctx, thread.target.component_id).await?,
+        )
+        .await?;
+
+        let thread = Self::get(ctx, id).await?;
+        thread.publish_on_commit(ctx).await?;
+
+        Ok(thread)
+    }
+
+    /// Reopens a resolved thread. Unresolving an open thread does nothing.
+    pub async fn unresolve(ctx: &DalContext, id: CommentThreadId) -> ChangeSetCommentResult<Self> {
+        let thread = Self::get(ctx, id).await?;
+        if !thread.is_resolved() {
+            return Ok(thread);
+        }
+
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "UPDATE change_set_comment_threads
+                   SET resolved_by_user_pk = NULL, resolved_at = NULL
+                   WHERE change_set_id = $1 AND id = $2",
+                &[&ctx.change_set_id(), &id],
+            )
+            .await?;
+        ctx.write_audit_log(
+            AuditLogKind::UnresolveCommentThread { thread_id: id },
+            entity_name(ctx, thread.target.component_id).await?,
+        )
+        .await?;
+
+        let thread = Self::get(ctx, id).await?;
+        thread.publish_on_commit(ctx).await?;
+
+        Ok(thread)
+    }
+
+    async fn publish_on_commit(&self, ctx: &DalContext) -> ChangeSetCommentResult<()> {
+        WsEvent::change_set_comment_thread_updated(ctx, self.clone())
+            .await
+            .map_err(Box::new)?
+            .publish_on_commit(ctx)
+            .await
+            .map_err(Box::new)?;
+
+        Ok(())
+    }
+}
+
+impl ChangeSetComment {
+    async fn insert(
+        ctx: &DalContext,
+        thread_id: CommentThreadId,
+        author_user_pk: UserPk,
+        body: String,
+        mentioned_user_pks: &[UserPk],
+    ) -> ChangeSetCommentResult<Self> {
+        let mentioned_user_pks: Vec<String> =
+            mentioned_user_pks.iter().map(ToString::to_string).collect();
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO change_set_comments
+                   (thread_id, workspace_pk, change_set_id, author_user_pk, body, mentioned_user_pks)
+                   VALUES ($1, $2, $3, $4, $5, $6)
+                   RETURNING *",
+                &[
+                    &thread_id,
+                    &ctx.workspace_pk()?,
+                    &ctx.change_set_id(),
+                    &author_user_pk,
+                    &body,
+                    &mentioned_user_pks,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    async fn get(ctx: &DalContext, id: CommentId) -> ChangeSetCommentResult<Self> {
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM change_set_comments WHERE change_set_id = $1 AND id = $2",
+                &[&ctx.change_set_id(), &id],
+            )
+            .await?
+            .ok_or(ChangeSetCommentError::CommentNotFound(id))?;
+
+        Self::try_from(row)
+    }
+
+    async fn list_for_thread(
+        ctx: &DalContext,
+        thread_id: CommentThreadId,
+    ) -> ChangeSetCommentResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_comments
+                   WHERE change_set_id = $1 AND thread_id = $2
+                   ORDER BY created_at",
+                &[&ctx.change_set_id(), &thread_id],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Every comment on the change set in the [`DalContext`], grouped by thread.
+    async fn list_by_thread(
+        ctx: &DalContext,
+    ) -> ChangeSetCommentResult<HashMap<CommentThreadId, Vec<Self>>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_comments WHERE change_set_id = $1 ORDER BY created_at",
+                &[&ctx.change_set_id()],
+            )
+            .await?;
+
+        let mut comments: HashMap<CommentThreadId, Vec<Self>> = HashMap::new();
+        for row in rows {
+            let comment = Self::try_from(row)?;
+            comments.entry(comment.thread_id).or_default().push(comment);
+        }
+
+        Ok(comments)
+    }
+
+    /// Replaces the body and mentions of one of the current user's comments.
+    pub async fn edit(
+        ctx: &DalContext,
+        id: CommentId,
+        body: impl Into<String>,
+        mentioned_user_pks: Vec<UserPk>,
+    ) -> ChangeSetCommentResult<CommentThread> {
+        let body = validate_body(body)?;
+        let comment = Self::get_authored(ctx, id).await?;
+        validate_mentions(ctx, &mentioned_user_pks).await?;
+
+        let mentioned_user_pk_strings: Vec<String> =
+            mentioned_user_pks.iter().map(ToString::to_string).collect();
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "UPDATE change_set_comments
+                   SET body = $3, mentioned_user_pks = $4, edited_at = clock_timestamp()
+                   WHERE change_set_id = $1 AND id = $2",
+                &[&ctx.change_set_id(), &id, &body, &mentioned_user_pk_strings],
+            )
+            .await?;
+
+        let thread = CommentThread::get(ctx, comment.thread_id).await?;
+        ctx.write_audit_log(
+            AuditLogKind::EditComment {
+                thread_id: thread.id,
+                comment_id: id,
+                mentioned_user_pks,
+            },
+            entity_name(ctx, thread.target.component_id).await?,
+        )
+        .await?;
+        thread.publish_on_commit(ctx).await?;
+
+        Ok(thread)
+    }
+
+    /// Deletes one of the current user's comments. Returns the thread, or [`None`] if that was
+    /// its last comment and the thread is gone too.
+    pub async fn remove(
+        ctx: &DalContext,
+        id: CommentId,
+    ) -> ChangeSetCommentResult<Option<CommentThread>> {
+        let comment = Self::get_authored(ctx, id).await?;
+        let thread = CommentThread::get(ctx, comment.thread_id).await?;
+
+        let txns = ctx.txns().await?;
+        txns.pg()
+            .query_none(
+                "DELETE FROM change_set_comments WHERE change_set_id = $1 AND id = $2",
+                &[&ctx.change_set_id(), &id],
+            )
+            .await?;
+        let thread_removed = thread.comments.len() <= 1;
+        if thread_removed {
+            txns.pg()
+                .query_none(
+                    "DELETE FROM change_set_comment_threads WHERE change_set_id = $1 AND id = $2",
+                    &[&ctx.change_set_id(), &thread.id],
+                )
+                .await?;
+        }
+
+        ctx.write_audit_log(
+            AuditLogKind::DeleteComment {
+                thread_id: thread.id,
+                comment_id: id,
+            },
+            entity_name(ctx, thread.target.component_id).await?,
+        )
+        .await?;
+
+        if thread_removed {
+            WsEvent::change_set_comment_thread_removed(ctx, thread.id)
+                .await
+                .map_err(Box::new)?
+                .publish_on_commit(ctx)
+                .await
+                .map_err(Box::new)?;
+            return Ok(None);
+        }
+
+        let thread = CommentThread::get(ctx, thread.id).await?;
+        thread.publish_on_commit(ctx).await?;
+
+        Ok(Some(thread))
+    }
+
+    async fn get_authored(ctx: &DalContext, id: CommentId) -> ChangeSetCommentResult<Self> {
+        let comment = Self::get(ctx, id).await?;
+        let user_pk = ChangeSet::extract_userid_from_context_or_error(ctx)
+            .await
+            .map_err(Box::new)?;
+        if comment.author_user_pk != user_pk {
+            return Err(ChangeSetCommentError::NotAuthor(id));
+        }
+
+        Ok(comment)
+    }
+}
+
+fn validate_body(body: impl Into<String>) -> ChangeSetCommentResult<String> {
+    let body = body.into();
+    if body.trim().is_empty() {
+        return Err(ChangeSetCommentError::EmptyBody);
+    }
+
+    Ok(body)
+}
+
+async fn validate_mentions(
+    ctx: &DalContext,
+    mentioned_user_pks: &[UserPk],
+) -> ChangeSetCommentResult<()> {
+    if mentioned_user_pks.is_empty() {
+        return Ok(());
+    }
+
+    let members: Vec<UserPk> =
+        User::list_members_for_workspace(ctx, ctx.workspace_pk()?.to_string())
+            .await
+            .map_err(Box::new)?
+            .iter()
+            .map(User::pk)
+            .collect();
+    if let Some(unknown) = mentioned_user_pks
+        .iter()
+        .find(|user_pk| !members.contains(user_pk))
+    {
+        return Err(ChangeSetCommentError::UnknownMention(*unknown));
+    }
+
+    Ok(())
+}
+
+/// Audit logs name the component a thread is on, or the change set for change set threads.
+async fn entity_name(
+    ctx: &DalContext,
+    component_id: Option<ComponentId>,
+) -> ChangeSetCommentResult<String> {
+    match component_id {
+        // The component may have been removed since the thread was started.
+        Some(component_id) => match Component::try_get_by_id(ctx, component_id)
+            .await
+            .map_err(Box::new)?
+        {
+            Some(component) => Ok(component.name(ctx).await.map_err(Box::new)?),
+            None => Ok(component_id.to_string()),
+        },
+        None => Ok(ChangeSet::get_by_id(ctx, ctx.change_set_id())
+            .await
+            .map_err(Box::new)?
+            .name),
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use super::{
+    comment::{CommentThread, CommentThreadId},
+    schedule::ScheduledApply,
+};
 use crate::{ChangeSetId, ChangeSetStatus, DalContext, UserPk, WsEvent, WsEventResult, WsPayload};
 
 impl WsEvent {
@@ -10,6 +14,20 @@ impl WsEvent {
         WsEvent::new(ctx, WsPayload::ChangeSetWritten(change_set_id)).await
     }
 
+    pub async fn change_set_comment_thread_updated(
+        ctx: &DalContext,
+        thread: CommentThread,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetCommentThreadUpdated(thread)).await
+    }
+
+    pub async fn change_set_comment_thread_removed(
+        ctx: &DalContext,
+        thread_id: CommentThreadId,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(ctx, WsPayload::ChangeSetCommentThreadRemoved(thread_id)).await
+    }
+
     pub async fn change_set_created(
         ctx: &DalContext,
         change_set_id: ChangeSetId,
@@ -17,6 +35,17 @@ impl WsEvent {
         WsEvent::new(ctx, WsPayload::ChangeSetCreated(change_set_id)).await
     }
 
+    pub async fn change_set_scheduled_apply_updated(
+        ctx: &DalContext,
+        scheduled_apply: ScheduledApply,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ChangeSetScheduledApplyUpdated(scheduled_apply),
+        )
+        .await
+    }
+
     pub async fn change_set_status_changed(
         ctx: &DalContext,
         from_status: ChangeSetStatus,
@@ -194,6 +223,16 @@ pub struct ChangeSetStateChangePayload {
     change_set: si_frontend_types::ChangeSet,
 }
 
+impl ChangeSetStateChangePayload {
+    pub fn from_status(&self) -> ChangeSetStatus {
+        self.from_status
+    }
+
+    pub fn change_set(&self) -> &si_frontend_types::ChangeSet {
+        &self.change_set
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ChangeSetAppliedPayload {
@@ -202,6 +241,16 @@ pub struct ChangeSetAppliedPayload {
     user_pk: Option<UserPk>,
 }
 
+impl ChangeSetAppliedPayload {
+    pub fn change_set_id(&self) -> ChangeSetId {
+        self.change_set_id
+    }
+
+    pub fn to_rebase_change_set_id(&self) -> ChangeSetId {
+        self.to_rebase_change_set_id
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ChangeSetMergeVotePayload {
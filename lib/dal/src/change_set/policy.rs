@@ -0,0 +1,430 @@
+//! Workspace policies: [`Funcs`](Func) of kind [`Policy`](FuncKind::Policy) that check a change
+//! set as a whole, rather than one component at a time like qualifications.
+//!
+//! Each policy is run in veritech against a JSON view of every component in the change set and
+//! how it differs from HEAD. A policy returns a qualification-shaped result: `success`, `warning`
+//! or `failure`, an optional message, and optional per-component violations. The latest result
+//! for each policy is stored against the change set, and
+//! [`ChangeSet::prepare_for_apply`](crate::ChangeSet::prepare_for_apply) refuses to apply while
+//! any policy is failing, unless an approver has [overridden](ChangeSetPolicy::override_failures)
+//! that failure.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use si_data_pg::{PgError, PgRow};
+use si_events::{audit_log::AuditLogKind, FuncRunId};
+use si_layer_cache::LayerDbError;
+use strum::{AsRefStr, Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    component::tag::{ComponentTag, ComponentTagError},
+    func::{
+        runner::{FuncRunner, FuncRunnerError},
+        FuncKind,
+    },
+    ChangeSet, ChangeSetError, ChangeSetId, Component, ComponentError, ComponentId, DalContext,
+    Func, FuncError, FuncId, HistoryActor, TransactionsError, UserPk,
+};
+
+/// The TypeScript types given to the editor for policy functions.
+pub const POLICY_FUNC_TYPES: &str = concat!(
+    "type Input = {\n",
+    "  changeSetId: string;\n",
+    "  changeSetName: string;\n",
+    "  components: Array<{\n",
+    "    id: string;\n",
+    "    name: string;\n",
+    "    schema: string;\n",
+    "    variant: string;\n",
+    "    tags: Record<string, string>;\n",
+    "    changeStatus: \"added\" | \"deleted\" | \"modified\" | \"unmodified\";\n",
+    "    properties: any;\n",
+    "    headProperties: any | null;\n",
+    "  }>;\n",
+    "};\n",
+    "\n",
+    "type Output = {\n",
+    "  result: \"success\" | \"warning\" | \"failure\";\n",
+    "  message?: string;\n",
+    "  violations?: Array<{ componentId?: string; message: string }>;\n",
+    "};\n",
+);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ChangeSetPolicyError {
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] Box<ChangeSetError>),
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("component tag error: {0}")]
+    ComponentTag(#[from] Box<ComponentTagError>),
+    #[error("func error: {0}")]
+    Func(#[from] Box<FuncError>),
+    #[error("func runner error: {0}")]
+    FuncRunner(#[from] Box<FuncRunnerError>),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("no failing policies to override for change set {0}")]
+    NothingToOverride(ChangeSetId),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ChangeSetPolicyResult<T> = Result<T, ChangeSetPolicyError>;
+
+#[derive(
+    AsRefStr, Deserialize, Display, EnumString, Serialize, Debug, Eq, PartialEq, Clone, Copy,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum PolicyStatus {
+    Failure,
+    Success,
+    Warning,
+}
+
+/// A single problem reported by a policy, optionally tied to a component.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyViolation {
+    #[serde(default)]
+    pub component_id: Option<ComponentId>,
+    pub message: String,
+}
+
+/// What a policy function returns.
+#[derive(Deserialize, Debug)]
+struct PolicyOutput {
+    result: PolicyStatus,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    violations: Vec<PolicyViolation>,
+}
+
+/// The latest result of running a policy against a change set.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyResult {
+    pub change_set_id: ChangeSetId,
+    pub func_id: FuncId,
+    pub func_name: String,
+    pub status: PolicyStatus,
+    pub message: Option<String>,
+    pub violations: Vec<PolicyViolation>,
+    pub func_run_id: Option<FuncRunId>,
+    pub evaluated_at: DateTime<Utc>,
+    pub overridden_by_user_pk: Option<UserPk>,
+    pub overridden_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<PgRow> for PolicyResult {
+    type Error = ChangeSetPolicyError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let status: String = row.try_get("status")?;
+        let violations: Value = row.try_get("violations")?;
+        Ok(Self {
+            change_set_id: row.try_get("change_set_id")?,
+            func_id: row.try_get("func_id")?,
+            func_name: row.try_get("func_name")?,
+            status: status.parse()?,
+            message: row.try_get("message")?,
+            violations: serde_json::from_value(violations)?,
+            func_run_id: row.try_get("func_run_id")?,
+            evaluated_at: row.try_get("evaluated_at")?,
+            overridden_by_user_pk: row.try_get("overridden_by_user_pk")?,
+            overridden_at: row.try_get("overridden_at")?,
+        })
+    }
+}
+
+impl PolicyResult {
+    /// A failing result blocks apply until it is overridden.
+    pub fn is_blocking(&self) -> bool {
+        self.status == PolicyStatus::Failure && self.overridden_at.is_none()
+    }
+}
+
+/// Logic for running workspace policies against a change set and recording their results.
+pub struct ChangeSetPolicy;
+
+impl ChangeSetPolicy {
+    /// Lists the policy [`Funcs`](Func) in the workspace.
+    pub async fn list_policies(ctx: &DalContext) -> ChangeSetPolicyResult<Vec<Func>> {
+        Ok(Func::list_all(ctx)
+            .await
+            .map_err(Box::new)?
+            .into_iter()
+            .filter(|func| func.kind == FuncKind::Policy)
+            .collect())
+    }
+
+    /// Builds the JSON view of the change set that policies are run against.
+    pub async fn input(ctx: &DalContext) -> ChangeSetPolicyResult<Value> {
+        let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+            .await
+            .map_err(Box::new)?;
+        let head_ctx = ctx.clone_with_head().await?;
+
+        let mut components = Vec::new();
+        for component_id in Component::list_ids(ctx).await.map_err(Box::new)? {
+            let component = Component::get_by_id(ctx, component_id)
+                .await
+                .map_err(Box::new)?;
+            let properties = component
+                .view(ctx)
+                .await
+                .map_err(Box::new)?
+                .unwrap_or(Value::Null);
+
+            let head_properties = match Component::try_get_by_id(&head_ctx, component_id)
+                .await
+                .map_err(Box::new)?
+            {
+                Some(head_component) => Some(
+                    head_component
+                        .view(&head_ctx)
+                        .await
+                        .map_err(Box::new)?
+                        .unwrap_or(Value::Null),
+                ),
+                None => None,
+            };
+            let change_status = match &head_properties {
+                None => "added",
+                Some(_) if component.to_delete() => "deleted",
+                Some(head_properties) if *head_properties != properties => "modified",
+                Some(_) => "unmodified",
+            };
+
+            let tags: BTreeMap<String, String> =
+                ComponentTag::list_for_component(ctx, component_id)
+                    .await
+                    .map_err(Box::new)?;
+
+            components.push(json!({
+                "id": component_id,
+                "name": component.name(ctx).await.map_err(Box::new)?,
+                "schema": component.schema(ctx).await.map_err(Box::new)?.name(),
+                "variant": component.schema_variant(ctx).await.map_err(Box::new)?.display_name(),
+                "tags": tags,
+                "changeStatus": change_status,
+                "properties": properties,
+                "headProperties": head_properties,
+            }));
+        }
+
+        Ok(json!({
+            "changeSetId": change_set.id,
+            "changeSetName": change_set.name,
+            "components": components,
+        }))
+    }
+
+    /// Runs every policy against the change set, stores the results and returns them. An override
+    /// on a failing result is kept only if the policy fails in exactly the same way again.
+    #[instrument(name = "change_set.policy.evaluate", level = "info", skip_all)]
+    pub async fn evaluate(ctx: &DalContext) -> ChangeSetPolicyResult<Vec<PolicyResult>> {
+        let change_set_id = ctx.change_set_id();
+        let workspace_pk = ctx.workspace_pk()?;
+        let policies = Self::list_policies(ctx).await?;
+
+        let func_ids: Vec<String> = policies.iter().map(|func| func.id.to_string()).collect();
+        ctx.txns()
+            .await?
+            .pg()
+            .query_none(
+                "DELETE FROM change_set_policy_results
+                   WHERE change_set_id = $1 AND NOT (func_id = ANY($2))",
+                &[&change_set_id, &func_ids],
+            )
+            .await?;
+
+        if policies.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let input = Self::input(ctx).await?;
+
+        let mut results = Vec::with_capacity(policies.len());
+        for func in policies {
+            let func_id = func.id;
+            let func_name = func.name.clone();
+            let (func_run_id, output) = Self::run(ctx, func, input.clone()).await?;
+
+            let row = ctx
+                .txns()
+                .await?
+                .pg()
+                .query_one(
+                    "INSERT INTO change_set_policy_results
+                       (change_set_id, workspace_pk, func_id, func_name, status, message, violations, func_run_id)
+                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                       ON CONFLICT (change_set_id, func_id) DO UPDATE SET
+                         func_name = EXCLUDED.func_name,
+                         func_run_id = EXCLUDED.func_run_id,
+                         evaluated_at = clock_timestamp(),
+                         overridden_by_user_pk = CASE
+                           WHEN change_set_policy_results.status = EXCLUDED.status
+                             AND change_set_policy_results.message IS NOT DISTINCT FROM EXCLUDED.message
+                             AND change_set_policy_results.violations = EXCLUDED.violations
+                           THEN change_set_policy_results.overridden_by_user_pk
+                           ELSE NULL END,
+                         overridden_at = CASE
+                           WHEN change_set_policy_results.status = EXCLUDED.status
+                             AND change_set_policy_results.message IS NOT DISTINCT FROM EXCLUDED.message
+                             AND change_set_policy_results.violations = EXCLUDED.violations
+                           THEN change_set_policy_results.overridden_at
+                           ELSE NULL END,
+                         status = EXCLUDED.status,
+                         message = EXCLUDED.message,
+                         violations = EXCLUDED.violations
+                       RETURNING *",
+                    &[
+                        &change_set_id,
+                        &workspace_pk,
+                        &func_id,
+                        &func_name,
+                        &output.result.as_ref(),
+                        &output.message,
+                        &serde_json::to_value(&output.violations)?,
+                        &func_run_id,
+                    ],
+                )
+                .await?;
+
+            results.push(PolicyResult::try_from(row)?);
+        }
+
+        Ok(results)
+    }
+
+    /// Lists the stored results for the change set, without running any policies.
+    pub async fn list_results(ctx: &DalContext) -> ChangeSetPolicyResult<Vec<PolicyResult>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM change_set_policy_results WHERE change_set_id = $1 ORDER BY func_name",
+                &[&ctx.change_set_id()],
+            )
+            .await?;
+
+        rows.into_iter().map(PolicyResult::try_from).collect()
+    }
+
+    /// Overrides every failing policy on the change set so that it can be applied, recording who did
+    /// it in the audit log. Callers are responsible for ensuring the user is an approver.
+    pub async fn override_failures(ctx: &DalContext) -> ChangeSetPolicyResult<Vec<PolicyResult>> {
+        let user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        };
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "UPDATE change_set_policy_results
+                   SET overridden_by_user_pk = $2, overridden_at = clock_timestamp()
+                   WHERE change_set_id = $1 AND status = $3 AND overridden_at IS NULL
+                   RETURNING *",
+                &[
+                    &ctx.change_set_id(),
+                    &user_pk,
+                    &PolicyStatus::Failure.as_ref(),
+                ],
+            )
+            .await?;
+        if rows.is_empty() {
+            return Err(ChangeSetPolicyError::NothingToOverride(ctx.change_set_id()));
+        }
+
+        let overridden = rows
+            .into_iter()
+            .map(PolicyResult::try_from)
+            .collect::<ChangeSetPolicyResult<Vec<_>>>()?;
+
+        let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+            .await
+            .map_err(Box::new)?;
+        ctx.write_audit_log(
+            AuditLogKind::OverridePolicyChecks {
+                failed_policies: overridden
+                    .iter()
+                    .map(|result| result.func_name.clone())
+                    .collect(),
+            },
+            change_set.name,
+        )
+        .await?;
+
+        Ok(overridden)
+    }
+
+    /// Runs a single policy. A policy that throws or returns something malformed is recorded as
+    /// failing, since we can't tell whether the change set would have passed.
+    async fn run(
+        ctx: &DalContext,
+        func: Func,
+        input: Value,
+    ) -> ChangeSetPolicyResult<(FuncRunId, PolicyOutput)> {
+        let (func_run_id, result_channel) = FuncRunner::run_policy(ctx, func, input)
+            .await
+            .map_err(Box::new)?;
+
+        let value = match result_channel.await {
+            Ok(Ok(run_value)) => {
+                ctx.layer_db()
+                    .func_run()
+                    .set_state_to_success(func_run_id, ctx.events_tenancy(), ctx.events_actor())
+                    .await?;
+                run_value.value().cloned().unwrap_or(Value::Null)
+            }
+            Ok(Err(err)) => {
+                return Ok((
+                    func_run_id,
+                    PolicyOutput::failure(format!("Policy execution failed: {err}")),
+                ))
+            }
+            Err(_) => {
+                return Ok((
+                    func_run_id,
+                    PolicyOutput::failure("Policy execution was cancelled".to_owned()),
+                ))
+            }
+        };
+
+        Ok((
+            func_run_id,
+            serde_json::from_value(value).unwrap_or_else(|err| {
+                PolicyOutput::failure(format!("Policy returned an invalid result: {err}"))
+            }),
+        ))
+    }
+}
+
+impl PolicyOutput {
+    fn failure(message: String) -> Self {
+        Self {
+            result: PolicyStatus::Failure,
+            message: Some(message),
+            violations: Vec::new(),
+        }
+    }
+}
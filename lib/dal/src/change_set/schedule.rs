@@ -0,0 +1,550 @@
+//! Scheduled applies let a [`ChangeSet`] be approved now and applied later, inside a maintenance
+//! window.
+//!
+//! A schedule has a start time and an optional end time. Once the window opens, the scheduler
+//! [claims](ScheduledApply::claim_due) the schedule and [runs](ScheduledApply::run_due) the apply
+//! as the user who scheduled it. Approval rules, policies and dependent values are checked again
+//! at that point, since the change set or HEAD may have moved on since it was scheduled. If any
+//! check fails, the apply conflicts with HEAD or the window has already closed, the schedule is
+//! skipped rather than retried. The rebaser dispatches the resulting actions on HEAD as usual.
+//!
+//! A claim is a lease, which the scheduler renews every [`CLAIM_HEARTBEAT`] while the apply runs.
+//! If the scheduler dies mid-apply, the schedule stays [`Applying`](ScheduledApplyStatus::Applying)
+//! until [`CLAIM_LEASE`] has passed without a renewal and the next scheduler claims it again. Each
+//! claim gets a new claim token, so a scheduler that lost its claim can neither renew it nor record
+//! how the apply finished.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use si_events::audit_log::AuditLogKind;
+use strum::{Display, EnumString};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    revoked_auth_token::{RevokedAuthToken, RevokedAuthTokenError},
+    AccessBuilder, ChangeSet, ChangeSetError, ChangeSetId, ChangeSetStatus, DalContext,
+    DalContextBuilder, HistoryActor, Tenancy, TransactionsError, User, UserError, UserPk,
+    Visibility, WorkspacePk, WsEvent, WsEventError,
+};
+
+/// How long a claimed schedule may stay [`Applying`](ScheduledApplyStatus::Applying) without its
+/// claim being renewed before another scheduler assumes the one that claimed it is gone.
+pub const CLAIM_LEASE: chrono::Duration = chrono::Duration::minutes(5);
+
+/// How often the scheduler renews its claim while an apply runs. This must stay well under
+/// [`CLAIM_LEASE`].
+pub const CLAIM_HEARTBEAT: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ScheduledApplyError {
+    #[error("scheduled apply for change set {0} is already running")]
+    AlreadyApplying(ChangeSetId),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] Box<ChangeSetError>),
+    #[error("scheduled apply for change set {0} was claimed by another scheduler")]
+    ClaimSuperseded(ChangeSetId),
+    #[error("apply window must end after it starts (start: {0}, end: {1})")]
+    InvalidWindow(DateTime<Utc>, DateTime<Utc>),
+    #[error("change set {0} cannot be scheduled for apply with status {1}")]
+    NotSchedulable(ChangeSetId, ChangeSetStatus),
+    #[error("change set {0} has no scheduled apply to cancel")]
+    NotScheduled(ChangeSetId),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("revoked auth token error: {0}")]
+    RevokedAuthToken(#[from] RevokedAuthTokenError),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("user error: {0}")]
+    User(#[from] Box<UserError>),
+    #[error("apply window already closed at {0}")]
+    WindowAlreadyClosed(DateTime<Utc>),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] Box<WsEventError>),
+}
+
+pub type ScheduledApplyResult<T> = Result<T, ScheduledApplyError>;
+
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Display, EnumString, PartialEq, Eq, Copy, Clone)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum ScheduledApplyStatus {
+    /// The change set was applied inside the window
+    Applied,
+    /// The scheduler has claimed the schedule and is applying the change set
+    Applying,
+    /// A user cancelled the schedule before the window opened
+    Cancelled,
+    /// Waiting for the window to open
+    Scheduled,
+    /// The window opened but the change set could not be applied
+    Skipped,
+}
+
+/// When a change set should be applied, and how that went.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledApply {
+    pub change_set_id: ChangeSetId,
+    pub workspace_pk: WorkspacePk,
+    pub window_start: DateTime<Utc>,
+    /// The apply is skipped if the scheduler only gets to it after this time.
+    pub window_end: Option<DateTime<Utc>>,
+    pub scheduled_by_user_pk: UserPk,
+    pub scheduled_at: DateTime<Utc>,
+    /// The automation token the apply was scheduled with, if any. It must still be valid when the
+    /// window opens.
+    #[serde(skip)]
+    pub scheduled_with_token_id: Option<String>,
+    pub status: ScheduledApplyStatus,
+    /// When the scheduler last claimed the schedule or renewed its claim.
+    pub claimed_at: Option<DateTime<Utc>>,
+    /// Incremented by every claim, so that only the latest claimant may finish the schedule.
+    #[serde(skip)]
+    pub claim_token: i64,
+    /// Why the apply was skipped.
+    pub message: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<PgRow> for ScheduledApply {
+    type Error = ScheduledApplyError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let status: String = row.try_get("status")?;
+        Ok(Self {
+            change_set_id: row.try_get("change_set_id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            window_start: row.try_get("window_start")?,
+            window_end: row.try_get("window_end")?,
+            scheduled_by_user_pk: row.try_get("scheduled_by_user_pk")?,
+            scheduled_at: row.try_get("scheduled_at")?,
+            scheduled_with_token_id: row.try_get("scheduled_with_token_id")?,
+            status: status.parse()?,
+            claimed_at: row.try_get("claimed_at")?,
+            claim_token: row.try_get("claim_token")?,
+            message: row.try_get("message")?,
+            completed_at: row.try_get("completed_at")?,
+        })
+    }
+}
+
+impl ScheduledApply {
+    /// Schedules the change set in the [`DalContext`] to be applied by the current user once
+    /// `window_start` has passed, replacing any previous schedule.
+    ///
+    /// If the request was made with an automation token, pass its JWT id so the apply is skipped
+    /// should the token be revoked before the window opens.
+    pub async fn schedule(
+        ctx: &DalContext,
+        window_start: DateTime<Utc>,
+        window_end: Option<DateTime<Utc>>,
+        token_id: Option<String>,
+    ) -> ScheduledApplyResult<Self> {
+        if let Some(window_end) = window_end {
+            if window_end <= window_start {
+                return Err(ScheduledApplyError::InvalidWindow(window_start, window_end));
+            }
+            if window_end <= Utc::now() {
+                return Err(ScheduledApplyError::WindowAlreadyClosed(window_end));
+            }
+        }
+
+        let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+            .await
+            .map_err(Box::new)?;
+        if change_set.base_change_set_id.is_none() || !change_set.status.is_active() {
+            return Err(ScheduledApplyError::NotSchedulable(
+                change_set.id,
+                change_set.status,
+            ));
+        }
+        let user_pk = ChangeSet::extract_userid_from_context_or_error(ctx)
+            .await
+            .map_err(Box::new)?;
+
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "INSERT INTO change_set_scheduled_applies
+                   (change_set_id, workspace_pk, window_start, window_end, scheduled_by_user_pk,
+                    scheduled_with_token_id, status)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7)
+                   ON CONFLICT (change_set_id) DO UPDATE SET
+                     window_start = EXCLUDED.window_start,
+                     window_end = EXCLUDED.window_end,
+                     scheduled_by_user_pk = EXCLUDED.scheduled_by_user_pk,
+                     scheduled_with_token_id = EXCLUDED.scheduled_with_token_id,
+                     scheduled_at = clock_timestamp(),
+                     status = EXCLUDED.status,
+                     message = NULL,
+                     claimed_at = NULL,
+                     completed_at = NULL
+                   WHERE change_set_scheduled_applies.status != $8
+                   RETURNING *",
+                &[
+                    &change_set.id,
+                    &ctx.workspace_pk()?,
+                    &window_start,
+                    &window_end,
+                    &user_pk,
+                    &token_id,
+                    &ScheduledApplyStatus::Scheduled.to_string(),
+                    &ScheduledApplyStatus::Applying.to_string(),
+                ],
+            )
+            .await?;
+        let Some(row) = maybe_row else {
+            return Err(ScheduledApplyError::AlreadyApplying(change_set.id));
+        };
+
+        let scheduled = Self::try_from(row)?;
+        ctx.write_audit_log(
+            AuditLogKind::ScheduleChangeSetApply {
+                window_start: scheduled.window_start,
+                window_end: scheduled.window_end,
+            },
+            change_set.name,
+        )
+        .await?;
+        scheduled.publish_on_commit(ctx).await?;
+
+        Ok(scheduled)
+    }
+
+    /// Cancels the scheduled apply for the change set in the [`DalContext`], as long as the
+    /// scheduler has not picked it up yet.
+    pub async fn cancel(ctx: &DalContext) -> ScheduledApplyResult<Self> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE change_set_scheduled_applies
+                   SET status = $3, completed_at = clock_timestamp()
+                   WHERE change_set_id = $1 AND workspace_pk = $2 AND status = $4
+                   RETURNING *",
+                &[
+                    &ctx.change_set_id(),
+                    &ctx.workspace_pk()?,
+                    &ScheduledApplyStatus::Cancelled.to_string(),
+                    &ScheduledApplyStatus::Scheduled.to_string(),
+                ],
+            )
+            .await?;
+        let Some(row) = maybe_row else {
+            return Err(ScheduledApplyError::NotScheduled(ctx.change_set_id()));
+        };
+
+        let cancelled = Self::try_from(row)?;
+        let change_set = ChangeSet::get_by_id(ctx, cancelled.change_set_id)
+            .await
+            .map_err(Box::new)?;
+        ctx.write_audit_log(
+            AuditLogKind::CancelScheduledChangeSetApply {
+                window_start: cancelled.window_start,
+                window_end: cancelled.window_end,
+            },
+            change_set.name,
+        )
+        .await?;
+        cancelled.publish_on_commit(ctx).await?;
+
+        Ok(cancelled)
+    }
+
+    /// The latest schedule for the change set in the [`DalContext`], whatever its outcome.
+    pub async fn get(ctx: &DalContext) -> ScheduledApplyResult<Option<Self>> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "SELECT * FROM change_set_scheduled_applies WHERE change_set_id = $1 AND workspace_pk = $2",
+                &[&ctx.change_set_id(), &ctx.workspace_pk()?],
+            )
+            .await?;
+
+        maybe_row.map(Self::try_from).transpose()
+    }
+
+    /// Marks every schedule whose window has opened, across all workspaces, as
+    /// [`ScheduledApplyStatus::Applying`] and returns them. Rows locked by another scheduler are
+    /// skipped, so each schedule is only claimed once.
+    ///
+    /// Schedules that are still applying but whose claim has not been renewed for `lease` are
+    /// claimed again, since the scheduler that claimed them is gone.
+    pub async fn claim_due(
+        ctx: &DalContext,
+        lease: chrono::Duration,
+    ) -> ScheduledApplyResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "UPDATE change_set_scheduled_applies
+                   SET status = $1, claimed_at = clock_timestamp(), claim_token = claim_token + 1
+                   WHERE change_set_id IN (
+                     SELECT change_set_id FROM change_set_scheduled_applies
+                       WHERE (status = $2 AND window_start <= clock_timestamp())
+                          OR (status = $1 AND claimed_at <= clock_timestamp() - make_interval(secs => $3))
+                       ORDER BY window_start
+                       FOR UPDATE SKIP LOCKED
+                   )
+                   RETURNING *",
+                &[
+                    &ScheduledApplyStatus::Applying.to_string(),
+                    &ScheduledApplyStatus::Scheduled.to_string(),
+                    &(lease.num_seconds() as f64),
+                ],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+
+    /// Claims and runs every due schedule, returning how each one finished.
+    #[instrument(name = "change_set.scheduled_apply.run_due", level = "info", skip_all)]
+    pub async fn run_due(builder: &DalContextBuilder) -> ScheduledApplyResult<Vec<Self>> {
+        let ctx = builder.build_default(None).await?;
+        let due = Self::claim_due(&ctx, CLAIM_LEASE).await?;
+        ctx.commit_no_rebase().await?;
+
+        let mut finished = Vec::with_capacity(due.len());
+        for scheduled in due {
+            // Keep the claim alive for as long as the apply takes, so that no other scheduler
+            // starts the same apply again
+            let result = tokio::select! {
+                result = scheduled.run(builder) => result,
+                () = scheduled.heartbeat(builder) => unreachable!("the heartbeat never finishes"),
+            };
+            match result {
+                Ok(outcome) => finished.push(outcome),
+                Err(err) => {
+                    error!(
+                        si.error.message = ?err,
+                        si.change_set.id = %scheduled.change_set_id,
+                        "scheduled apply failed",
+                    );
+                    // Don't leave the schedule stuck in "applying"
+                    match scheduled.skip_as_system(builder, err.to_string()).await {
+                        Ok(outcome) => finished.push(outcome),
+                        Err(err) => error!(
+                            si.error.message = ?err,
+                            si.change_set.id = %scheduled.change_set_id,
+                            "could not mark scheduled apply as skipped",
+                        ),
+                    }
+                }
+            }
+        }
+
+        Ok(finished)
+    }
+
+    async fn run(&self, builder: &DalContextBuilder) -> ScheduledApplyResult<Self> {
+        let access_builder = AccessBuilder::new(
+            Tenancy::new(self.workspace_pk),
+            HistoryActor::User(self.scheduled_by_user_pk),
+            None,
+        );
+        let visibility = Visibility::new(self.change_set_id);
+
+        if let Some(window_end) = self.window_end {
+            if window_end <= Utc::now() {
+                let ctx = builder.build(access_builder.build(visibility)).await?;
+                return self
+                    .finish(
+                        &ctx,
+                        ScheduledApplyStatus::Skipped,
+                        Some(format!("apply window closed at {window_end}")),
+                    )
+                    .await;
+            }
+        }
+
+        // The user may have left the workspace, or revoked the token they scheduled with, since.
+        let ctx = builder.build(access_builder.build(visibility)).await?;
+        if let Some(reason) = self.no_longer_permitted(&ctx).await? {
+            return self
+                .finish(&ctx, ScheduledApplyStatus::Skipped, Some(reason))
+                .await;
+        }
+
+        // Approvals, policies and dependent values may all have changed since the apply was
+        // scheduled, so this goes through the same checks as applying by hand.
+        let mut ctx = ctx;
+        if let Err(err) = ChangeSet::prepare_for_apply(&ctx).await {
+            ctx.rollback().await?;
+            let ctx = builder.build(access_builder.build(visibility)).await?;
+            return self
+                .finish(&ctx, ScheduledApplyStatus::Skipped, Some(err.to_string()))
+                .await;
+        }
+        ctx.blocking_commit().await?;
+
+        if let Err(err) = ChangeSet::apply_to_base_change_set(&mut ctx).await {
+            let ctx = builder.build(access_builder.build(visibility)).await?;
+            return self
+                .finish(&ctx, ScheduledApplyStatus::Skipped, Some(err.to_string()))
+                .await;
+        }
+
+        let change_set = ChangeSet::get_by_id(&ctx, self.change_set_id)
+            .await
+            .map_err(Box::new)?;
+        ctx.write_audit_log(AuditLogKind::ApplyChangeSet, change_set.name)
+            .await?;
+
+        self.finish(&ctx, ScheduledApplyStatus::Applied, None).await
+    }
+
+    /// Renews the claim on the schedule, returning whether it is still ours to renew.
+    pub async fn renew_claim(&self, ctx: &DalContext) -> ScheduledApplyResult<bool> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE change_set_scheduled_applies SET claimed_at = clock_timestamp()
+                   WHERE change_set_id = $1 AND status = $2 AND claim_token = $3
+                   RETURNING change_set_id",
+                &[
+                    &self.change_set_id,
+                    &ScheduledApplyStatus::Applying.to_string(),
+                    &self.claim_token,
+                ],
+            )
+            .await?;
+
+        Ok(maybe_row.is_some())
+    }
+
+    /// Renews the claim every [`CLAIM_HEARTBEAT`], until dropped.
+    async fn heartbeat(&self, builder: &DalContextBuilder) {
+        loop {
+            tokio::time::sleep(CLAIM_HEARTBEAT).await;
+
+            let result = async {
+                let ctx = builder.build_default(None).await?;
+                let renewed = self.renew_claim(&ctx).await?;
+                ctx.commit_no_rebase().await?;
+                Ok::<_, ScheduledApplyError>(renewed)
+            }
+            .await;
+            match result {
+                Ok(true) => {}
+                Ok(false) => warn!(
+                    si.change_set.id = %self.change_set_id,
+                    "scheduled apply claim was superseded while applying",
+                ),
+                Err(err) => error!(
+                    si.error.message = ?err,
+                    si.change_set.id = %self.change_set_id,
+                    "could not renew scheduled apply claim",
+                ),
+            }
+        }
+    }
+
+    /// Why the user who scheduled the apply may no longer apply the change set, if they may not.
+    async fn no_longer_permitted(&self, ctx: &DalContext) -> ScheduledApplyResult<Option<String>> {
+        let members = User::list_members_for_workspace(ctx, self.workspace_pk.to_string())
+            .await
+            .map_err(Box::new)?;
+        if !members.iter().any(|m| m.pk() == self.scheduled_by_user_pk) {
+            return Ok(Some(format!(
+                "user {} is no longer a member of the workspace",
+                self.scheduled_by_user_pk
+            )));
+        }
+
+        if let Some(token_id) = &self.scheduled_with_token_id {
+            if RevokedAuthToken::is_revoked(ctx, token_id).await? {
+                return Ok(Some(format!(
+                    "token {token_id} used to schedule the apply has been revoked"
+                )));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn skip_as_system(
+        &self,
+        builder: &DalContextBuilder,
+        reason: String,
+    ) -> ScheduledApplyResult<Self> {
+        let ctx = builder
+            .build_for_change_set_as_system(self.workspace_pk, self.change_set_id, None)
+            .await?;
+        self.finish(&ctx, ScheduledApplyStatus::Skipped, Some(reason))
+            .await
+    }
+
+    async fn finish(
+        &self,
+        ctx: &DalContext,
+        status: ScheduledApplyStatus,
+        message: Option<String>,
+    ) -> ScheduledApplyResult<Self> {
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(
+                "UPDATE change_set_scheduled_applies
+                   SET status = $2, message = $3, completed_at = clock_timestamp()
+                   WHERE change_set_id = $1 AND claim_token = $4
+                   RETURNING *",
+                &[
+                    &self.change_set_id,
+                    &status.to_string(),
+                    &message,
+                    &self.claim_token,
+                ],
+            )
+            .await?;
+        let Some(row) = maybe_row else {
+            return Err(ScheduledApplyError::ClaimSuperseded(self.change_set_id));
+        };
+        let finished = Self::try_from(row)?;
+
+        if status == ScheduledApplyStatus::Skipped {
+            let change_set = ChangeSet::get_by_id(ctx, self.change_set_id)
+                .await
+                .map_err(Box::new)?;
+            ctx.write_audit_log(
+                AuditLogKind::SkipScheduledChangeSetApply {
+                    reason: message.unwrap_or_default(),
+                },
+                change_set.name,
+            )
+            .await?;
+        }
+        finished.publish_on_commit(ctx).await?;
+        ctx.commit_no_rebase().await?;
+
+        Ok(finished)
+    }
+
+    async fn publish_on_commit(&self, ctx: &DalContext) -> ScheduledApplyResult<()> {
+        WsEvent::change_set_scheduled_apply_updated(ctx, self.clone())
+            .await
+            .map_err(Box::new)?
+            .publish_on_commit(ctx)
+            .await
+            .map_err(Box::new)?;
+
+        Ok(())
+    }
+}
@@ -13,7 +13,7 @@ use telemetry::prelude::*;
 use thiserror::Error;
 use tokio::sync::TryLockError;
 
-use si_events::{ulid::Ulid, ContentHash};
+use si_events::{audit_log::AuditLogKind, ulid::Ulid, ContentHash};
 
 use crate::action::prototype::{ActionKind, ActionPrototype, ActionPrototypeError};
 use crate::action::{Action, ActionError, ActionState};
@@ -34,7 +34,7 @@ use crate::diagram::{
 };
 use crate::func::argument::FuncArgumentError;
 use crate::history_event::HistoryEventMetadata;
-use crate::layer_db_types::{ComponentContent, ComponentContentV2};
+use crate::layer_db_types::{ComponentContent, ComponentContentV3};
 use crate::module::{Module, ModuleError};
 use crate::prop::{PropError, PropPath};
 use crate::qualification::QualificationError;
@@ -58,6 +58,7 @@ use si_frontend_types::{
 };
 
 use self::inferred_connection_graph::InferredConnectionGraphError;
+use self::tag::{ComponentTag, ComponentTagError};
 use crate::diagram::geometry::Geometry;
 use crate::diagram::view::{View, ViewId};
 use crate::{
@@ -72,12 +73,15 @@ pub mod code;
 pub mod debug;
 pub mod delete;
 pub mod diff;
+pub mod fragment;
 pub mod frame;
 pub mod inferred_connection_graph;
 pub mod properties;
 pub mod qualification;
+pub mod query;
 pub mod resource;
 pub mod socket;
+pub mod tag;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -120,6 +124,8 @@ pub enum ComponentError {
     ComponentMissingValue(ComponentId, PropId),
     #[error("component {0} is based on a schema {1} that is not managed by {2}")]
     ComponentNotManagedSchema(ComponentId, SchemaId, ComponentId),
+    #[error("component {0} is protected and must be unprotected before it can be deleted")]
+    ComponentProtected(ComponentId),
     #[error("connection destination component {0} has no attribute value for input socket {1}")]
     DestinationComponentMissingAttributeValueForInputSocket(ComponentId, InputSocketId),
     #[error("diagram error: {0}")]
@@ -200,6 +206,8 @@ pub enum ComponentError {
     Serde(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
     StandardModel(#[from] StandardModelError),
+    #[error("component tag error: {0}")]
+    Tag(#[from] Box<ComponentTagError>),
     #[error("too many explicit connection sources ({0:?}) for component ({1}) and input socket ({2}) with an arity of one")]
     TooManyExplicitConnectionSources(Vec<ComponentId>, ComponentId, InputSocketId),
     #[error(
@@ -270,12 +278,14 @@ pub struct Component {
     #[serde(flatten)]
     timestamp: Timestamp,
     to_delete: bool,
+    protected: bool,
 }
 
-impl From<Component> for ComponentContentV2 {
+impl From<Component> for ComponentContentV3 {
     fn from(value: Component) -> Self {
         Self {
             timestamp: value.timestamp,
+            protected: value.protected,
         }
     }
 }
@@ -288,11 +298,12 @@ pub struct ControllingFuncData {
 }
 
 impl Component {
-    pub fn assemble(node_weight: &ComponentNodeWeight, content: ComponentContentV2) -> Self {
+    pub fn assemble(node_weight: &ComponentNodeWeight, content: ComponentContentV3) -> Self {
         Self {
             id: node_weight.id().into(),
             timestamp: content.timestamp,
             to_delete: node_weight.to_delete(),
+            protected: content.protected,
         }
     }
 
@@ -308,6 +319,12 @@ impl Component {
         self.to_delete
     }
 
+    /// Protected components cannot be deleted, erased or have destroy [`Actions`](Action)
+    /// enqueued until they are unprotected.
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
+
     pub async fn change_status(&self, ctx: &DalContext) -> ComponentResult<ChangeStatus> {
         let status = if self.exists_in_head(ctx).await? {
             if self.to_delete() {
@@ -408,12 +425,14 @@ impl Component {
         schema_variant_id: SchemaVariantId,
         view_id: ViewId,
     ) -> ComponentResult<Self> {
-        let content = ComponentContentV2 {
+        let schema = SchemaVariant::schema_for_schema_variant_id(ctx, schema_variant_id).await?;
+        let content = ComponentContentV3 {
             timestamp: Timestamp::now(),
+            protected: schema.protect_components(),
         };
 
         let (hash, _) = ctx.layer_db().cas().write(
-            Arc::new(ComponentContent::V2(content.clone()).into()),
+            Arc::new(ComponentContent::V3(content.clone()).into()),
             None,
             ctx.events_tenancy(),
             ctx.events_actor(),
@@ -1274,7 +1293,7 @@ impl Component {
     async fn try_get_node_weight_and_content(
         ctx: &DalContext,
         component_id: ComponentId,
-    ) -> ComponentResult<Option<(ComponentNodeWeight, ComponentContentV2)>> {
+    ) -> ComponentResult<Option<(ComponentNodeWeight, ComponentContentV3)>> {
         if let Some((component_node_weight, content_hash)) =
             Self::try_get_node_weight_and_content_hash(ctx, component_id).await?
         {
@@ -1296,7 +1315,7 @@ impl Component {
     async fn get_node_weight_and_content(
         ctx: &DalContext,
         component_id: ComponentId,
-    ) -> ComponentResult<(ComponentNodeWeight, ComponentContentV2)> {
+    ) -> ComponentResult<(ComponentNodeWeight, ComponentContentV3)> {
         Self::try_get_node_weight_and_content(ctx, component_id)
             .await?
             .ok_or(ComponentError::NotFound(component_id))
@@ -2437,7 +2456,7 @@ impl Component {
         let original_component = self.clone();
         let mut component = self;
 
-        let before = ComponentContentV2::from(component.clone());
+        let before = ComponentContentV3::from(component.clone());
         lambda(&mut component)?;
 
         // The `to_delete` lives on the node itself, not in the content, so we need to be a little
@@ -2459,10 +2478,10 @@ impl Component {
                 .await?;
         }
 
-        let updated = ComponentContentV2::from(component.clone());
+        let updated = ComponentContentV3::from(component.clone());
         if updated != before {
             let (hash, _) = ctx.layer_db().cas().write(
-                Arc::new(ComponentContent::V2(updated.clone()).into()),
+                Arc::new(ComponentContent::V3(updated.clone()).into()),
                 None,
                 ctx.events_tenancy(),
                 ctx.events_actor(),
@@ -2608,7 +2627,52 @@ impl Component {
         Ok(true)
     }
 
+    /// Protects the [`Component`] from deletion, or lifts that protection.
+    pub async fn set_protected(self, ctx: &DalContext, protected: bool) -> ComponentResult<Self> {
+        if self.protected == protected {
+            return Ok(self);
+        }
+
+        let component_id = self.id;
+        let component_name = self.name(ctx).await?;
+        let component = self
+            .modify(ctx, |component| {
+                component.protected = protected;
+                Ok(())
+            })
+            .await?;
+
+        let kind = if protected {
+            AuditLogKind::ProtectComponent {
+                component_id,
+                component_name: component_name.clone(),
+            }
+        } else {
+            AuditLogKind::UnprotectComponent {
+                component_id,
+                component_name: component_name.clone(),
+            }
+        };
+        ctx.write_audit_log(kind, component_name).await?;
+
+        Ok(component)
+    }
+
+    pub async fn is_protected(ctx: &DalContext, id: ComponentId) -> ComponentResult<bool> {
+        let (_, content) = Self::get_node_weight_and_content(ctx, id).await?;
+        Ok(content.protected)
+    }
+
+    /// Errors if the [`Component`] is [protected](Self::protected).
+    pub fn ensure_not_protected(&self) -> ComponentResult<()> {
+        if self.protected {
+            return Err(ComponentError::ComponentProtected(self.id));
+        }
+        Ok(())
+    }
+
     pub async fn delete(self, ctx: &DalContext) -> ComponentResult<Option<Self>> {
+        self.ensure_not_protected()?;
         if self.allowed_to_be_removed(ctx).await? {
             Self::remove(ctx, self.id).await?;
             Ok(None)
@@ -3588,6 +3652,10 @@ impl Component {
 
         let maybe_parent = self.parent(ctx).await?;
 
+        let tags = ComponentTag::list_for_component(ctx, self.id())
+            .await
+            .map_err(Box::new)?;
+
         let geometry = if let Some(geometry) = maybe_geometry {
             let view_id = Geometry::get_view_id_by_id(ctx, geometry.id())
                 .await
@@ -3624,6 +3692,8 @@ impl Component {
             can_be_upgraded,
             from_base_change_set: false,
             view_data: geometry,
+            tags,
+            protected: self.protected,
         })
     }
 
@@ -3673,6 +3743,12 @@ pub struct ComponentDeletedPayload {
     change_set_id: ChangeSetId,
 }
 
+impl ComponentDeletedPayload {
+    pub fn component_id(&self) -> ComponentId {
+        self.component_id
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum ConnectionDeletedPayload {
@@ -5,7 +5,7 @@ use si_id::ComponentId;
 
 use crate::{change_status::ChangeStatus, diagram::SummaryDiagramEdge, DalContext, WsEvent};
 
-use super::{Component, ComponentResult};
+use super::{Component, ComponentError, ComponentResult};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ComponentDeletionStatus {
@@ -24,6 +24,13 @@ pub async fn delete_components(
     component_ids: &[ComponentId],
     force_erase: bool,
 ) -> ComponentResult<HashMap<ComponentId, ComponentDeletionStatus>> {
+    // Refuse the whole request, rather than deleting part of it, if anything is protected
+    for &component_id in component_ids {
+        if Component::is_protected(ctx, component_id).await? {
+            return Err(ComponentError::ComponentProtected(component_id));
+        }
+    }
+
     let head_components: HashSet<ComponentId> =
         Component::exists_on_head(ctx, component_ids).await?;
     let mut result = HashMap::new();
@@ -0,0 +1,487 @@
+//! Copying a selection of [`Components`](Component) from one workspace or change set to another.
+//!
+//! [`export`](ComponentFragment::export) captures the selected components as a portable
+//! [`ComponentFragmentSpec`]: their attribute trees and tags, the connections among them, their
+//! frame parentage within the selection and where they sit in each view. Nothing outside the selection is
+//! included, so connections and parents that leave the selection are dropped.
+//!
+//! [`import`](ComponentFragment::import) re-creates the fragment in the change set of the
+//! [`DalContext`]. Each component is matched to a [`Schema`] by name and to the variant with the
+//! same version, falling back to the schema's default variant when that version isn't installed.
+//! Every component gets a new id. Views are matched by name and created when missing. Components
+//! whose schema can't be found, and connections whose sockets don't exist on the mapped variants,
+//! are skipped and reported rather than failing the import.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use si_events::audit_log::AuditLogKind;
+use si_pkg::{
+    ComponentFragmentSpec, EdgeSpec, EdgeSpecKind, FragmentComponentSpec, FragmentGeometrySpec,
+    FragmentViewSpec, PositionSpec, SpecError,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    component::{
+        frame::{Frame, FrameError},
+        tag::{ComponentTag, ComponentTagError},
+    },
+    diagram::{
+        geometry::{Geometry, RawGeometry},
+        view::{View, ViewId},
+        DiagramError,
+    },
+    management::{update_component, ManagementError},
+    socket::{input::InputSocketError, output::OutputSocketError},
+    Component, ComponentError, ComponentId, DalContext, InputSocket, OutputSocket, Schema,
+    SchemaError, SchemaVariant, SchemaVariantError, SchemaVariantId, TransactionsError,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ComponentFragmentError {
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("component tag error: {0}")]
+    ComponentTag(#[from] Box<ComponentTagError>),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] Box<DiagramError>),
+    #[error("frame error: {0}")]
+    Frame(#[from] Box<FrameError>),
+    #[error("input socket error: {0}")]
+    InputSocket(#[from] Box<InputSocketError>),
+    #[error("invalid position for component {0}: {1}")]
+    InvalidPosition(String, String),
+    #[error("management error: {0}")]
+    Management(#[from] Box<ManagementError>),
+    #[error("nothing to export")]
+    NothingToExport,
+    #[error("output socket error: {0}")]
+    OutputSocket(#[from] Box<OutputSocketError>),
+    #[error("schema error: {0}")]
+    Schema(#[from] Box<SchemaError>),
+    #[error("schema variant error: {0}")]
+    SchemaVariant(#[from] Box<SchemaVariantError>),
+    #[error("spec error: {0}")]
+    Spec(#[from] SpecError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type ComponentFragmentResult<T> = Result<T, ComponentFragmentError>;
+
+/// A component in the fragment that could not be imported.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappedComponent {
+    pub unique_id: String,
+    pub name: String,
+    pub schema_name: String,
+}
+
+/// A component imported with a different variant than the one it was exported with.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubstitutedVariant {
+    pub unique_id: String,
+    pub schema_name: String,
+    pub exported_version: String,
+    pub imported_version: String,
+}
+
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnmappedSocketDirection {
+    Input,
+    Output,
+}
+
+/// A socket named by a connection in the fragment that the mapped variant doesn't have. The
+/// connection is skipped.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappedSocket {
+    pub component_unique_id: String,
+    pub socket_name: String,
+    pub direction: UnmappedSocketDirection,
+}
+
+/// What [`ComponentFragment::import`] did.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentFragmentImport {
+    /// The new id of each imported component, keyed by its unique id in the fragment.
+    pub component_ids: HashMap<String, ComponentId>,
+    pub created_view_ids: Vec<ViewId>,
+    pub unmapped_components: Vec<UnmappedComponent>,
+    pub substituted_variants: Vec<SubstitutedVariant>,
+    pub unmapped_sockets: Vec<UnmappedSocket>,
+}
+
+/// Logic for exporting and importing [`ComponentFragmentSpecs`](ComponentFragmentSpec).
+pub struct ComponentFragment;
+
+impl ComponentFragment {
+    /// Exports the given components from the change set in the [`DalContext`].
+    #[instrument(name = "component.fragment.export", level = "info", skip_all)]
+    pub async fn export(
+        ctx: &DalContext,
+        component_ids: &[ComponentId],
+        created_by: impl Into<String>,
+    ) -> ComponentFragmentResult<ComponentFragmentSpec> {
+        let selection: BTreeSet<ComponentId> = component_ids.iter().copied().collect();
+        if selection.is_empty() {
+            return Err(ComponentFragmentError::NothingToExport);
+        }
+
+        let mut builder = ComponentFragmentSpec::builder();
+        builder
+            .created_by(created_by)
+            .source_workspace_pk(ctx.workspace_pk()?.to_string())
+            .source_change_set_id(ctx.change_set_id().to_string());
+
+        let mut view_ids = BTreeSet::new();
+        for &component_id in &selection {
+            let component = Component::get_by_id(ctx, component_id)
+                .await
+                .map_err(Box::new)?;
+            let schema_variant = component.schema_variant(ctx).await.map_err(Box::new)?;
+            let schema = component.schema(ctx).await.map_err(Box::new)?;
+
+            let mut component_builder = FragmentComponentSpec::builder();
+            component_builder
+                .unique_id(component_id.to_string())
+                .name(component.name(ctx).await.map_err(Box::new)?)
+                .schema_name(schema.name())
+                .variant_version(schema_variant.version())
+                .properties(
+                    component
+                        .view(ctx)
+                        .await
+                        .map_err(Box::new)?
+                        .unwrap_or(serde_json::Value::Null),
+                )
+                .tags(
+                    ComponentTag::list_for_component(ctx, component_id)
+                        .await
+                        .map_err(Box::new)?,
+                );
+
+            if let Some(parent_id) = Component::get_parent_by_id(ctx, component_id)
+                .await
+                .map_err(Box::new)?
+            {
+                if selection.contains(&parent_id) {
+                    component_builder.parent_unique_id(parent_id.to_string());
+                }
+            }
+
+            let mut geometries: Vec<(ViewId, Geometry)> =
+                Geometry::by_view_for_component_id(ctx, component_id)
+                    .await
+                    .map_err(Box::new)?
+                    .into_iter()
+                    .collect();
+            geometries.sort_by_key(|(view_id, _)| *view_id);
+            for (view_id, geometry) in geometries {
+                view_ids.insert(view_id);
+                component_builder.geometry(
+                    FragmentGeometrySpec::builder()
+                        .view_unique_id(view_id.to_string())
+                        .position(
+                            PositionSpec::builder()
+                                .x(geometry.x().to_string())
+                                .y(geometry.y().to_string())
+                                .width(geometry.width().map(|width| width.to_string()))
+                                .height(geometry.height().map(|height| height.to_string()))
+                                .build()?,
+                        )
+                        .build()?,
+                );
+            }
+
+            builder.component(component_builder.build()?);
+
+            for connection in component
+                .incoming_connections(ctx)
+                .await
+                .map_err(Box::new)?
+            {
+                if !selection.contains(&connection.from_component_id) {
+                    continue;
+                }
+                let from_socket = OutputSocket::get_by_id(ctx, connection.from_output_socket_id)
+                    .await
+                    .map_err(Box::new)?;
+                let to_socket = InputSocket::get_by_id(ctx, connection.to_input_socket_id)
+                    .await
+                    .map_err(Box::new)?;
+                builder.edge(
+                    EdgeSpec::builder()
+                        .edge_kind(EdgeSpecKind::Configuration)
+                        .from_component_unique_id(connection.from_component_id.to_string())
+                        .from_socket_name(from_socket.name())
+                        .to_component_unique_id(component_id.to_string())
+                        .to_socket_name(to_socket.name())
+                        .creation_user_pk(None::<String>)
+                        .deletion_user_pk(None::<String>)
+                        .deleted_implicitly(false)
+                        .build()?,
+                );
+            }
+        }
+
+        for view_id in view_ids {
+            let view = View::get_by_id(ctx, view_id).await.map_err(Box::new)?;
+            builder.view(
+                FragmentViewSpec::builder()
+                    .unique_id(view_id.to_string())
+                    .name(view.name())
+                    .build()?,
+            );
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Re-creates the fragment in the change set of the [`DalContext`].
+    #[instrument(name = "component.fragment.import", level = "info", skip_all)]
+    pub async fn import(
+        ctx: &DalContext,
+        fragment: &ComponentFragmentSpec,
+    ) -> ComponentFragmentResult<ComponentFragmentImport> {
+        let mut report = ComponentFragmentImport::default();
+
+        let mut view_ids: HashMap<&str, ViewId> = HashMap::new();
+        for view_spec in &fragment.views {
+            let view_id = match View::find_by_name(ctx, &view_spec.name)
+                .await
+                .map_err(Box::new)?
+            {
+                Some(view) => view.id(),
+                None => {
+                    let view_id = View::new(ctx, &view_spec.name)
+                        .await
+                        .map_err(Box::new)?
+                        .id();
+                    report.created_view_ids.push(view_id);
+                    view_id
+                }
+            };
+            view_ids.insert(view_spec.unique_id.as_str(), view_id);
+        }
+        let default_view_id = View::get_id_for_default(ctx).await.map_err(Box::new)?;
+
+        let mut variant_ids: HashMap<ComponentId, SchemaVariantId> = HashMap::new();
+        for component_spec in &fragment.components {
+            let Some(schema_variant) = Self::map_variant(ctx, component_spec, &mut report).await?
+            else {
+                report.unmapped_components.push(UnmappedComponent {
+                    unique_id: component_spec.unique_id.clone(),
+                    name: component_spec.name.clone(),
+                    schema_name: component_spec.schema_name.clone(),
+                });
+                continue;
+            };
+
+            let mut geometries = Vec::with_capacity(component_spec.geometries.len());
+            for geometry_spec in &component_spec.geometries {
+                if let Some(view_id) = view_ids.get(geometry_spec.view_unique_id.as_str()) {
+                    geometries.push((
+                        *view_id,
+                        raw_geometry(&component_spec.unique_id, &geometry_spec.position)?,
+                    ));
+                }
+            }
+            let first_view_id = geometries
+                .first()
+                .map(|(view_id, _)| *view_id)
+                .unwrap_or(default_view_id);
+
+            let mut component = Component::new(
+                ctx,
+                &component_spec.name,
+                schema_variant.id(),
+                first_view_id,
+            )
+            .await
+            .map_err(Box::new)?;
+            let component_id = component.id();
+            for (view_id, geometry) in geometries {
+                if view_id == first_view_id {
+                    component
+                        .set_raw_geometry(ctx, geometry, view_id)
+                        .await
+                        .map_err(Box::new)?;
+                } else {
+                    Component::add_to_view(ctx, component_id, view_id, geometry)
+                        .await
+                        .map_err(Box::new)?;
+                }
+            }
+
+            update_component(ctx, component_id, &component_spec.properties, &[])
+                .await
+                .map_err(Box::new)?;
+            for (key, value) in &component_spec.tags {
+                ComponentTag::set(ctx, component_id, key, value)
+                    .await
+                    .map_err(Box::new)?;
+            }
+
+            ctx.write_audit_log(
+                AuditLogKind::CreateComponent {
+                    name: component_spec.name.clone(),
+                    component_id,
+                    schema_variant_id: schema_variant.id(),
+                    schema_variant_name: schema_variant.display_name().to_owned(),
+                },
+                component_spec.name.clone(),
+            )
+            .await?;
+
+            variant_ids.insert(component_id, schema_variant.id());
+            report
+                .component_ids
+                .insert(component_spec.unique_id.clone(), component_id);
+        }
+
+        // Parents go in once every component exists, since frames may come after their children
+        for component_spec in &fragment.components {
+            let (Some(component_id), Some(parent_id)) = (
+                report.component_ids.get(&component_spec.unique_id),
+                component_spec
+                    .parent_unique_id
+                    .as_ref()
+                    .and_then(|parent_unique_id| report.component_ids.get(parent_unique_id)),
+            ) else {
+                continue;
+            };
+            Frame::upsert_parent_no_events(ctx, *component_id, *parent_id)
+                .await
+                .map_err(Box::new)?;
+        }
+
+        for edge in &fragment.edges {
+            let (Some(&from_component_id), Some(&to_component_id)) = (
+                report.component_ids.get(&edge.from_component_unique_id),
+                report.component_ids.get(&edge.to_component_unique_id),
+            ) else {
+                continue;
+            };
+
+            let from_socket = OutputSocket::find_with_name(
+                ctx,
+                &edge.from_socket_name,
+                variant_ids[&from_component_id],
+            )
+            .await
+            .map_err(Box::new)?;
+            let to_socket = InputSocket::find_with_name(
+                ctx,
+                &edge.to_socket_name,
+                variant_ids[&to_component_id],
+            )
+            .await
+            .map_err(Box::new)?;
+
+            if from_socket.is_none() {
+                report.unmapped_sockets.push(UnmappedSocket {
+                    component_unique_id: edge.from_component_unique_id.clone(),
+                    socket_name: edge.from_socket_name.clone(),
+                    direction: UnmappedSocketDirection::Output,
+                });
+            }
+            if to_socket.is_none() {
+                report.unmapped_sockets.push(UnmappedSocket {
+                    component_unique_id: edge.to_component_unique_id.clone(),
+                    socket_name: edge.to_socket_name.clone(),
+                    direction: UnmappedSocketDirection::Input,
+                });
+            }
+            let (Some(from_socket), Some(to_socket)) = (from_socket, to_socket) else {
+                continue;
+            };
+
+            Component::connect(
+                ctx,
+                from_component_id,
+                from_socket.id(),
+                to_component_id,
+                to_socket.id(),
+            )
+            .await
+            .map_err(Box::new)?;
+        }
+
+        Ok(report)
+    }
+
+    /// Finds the variant of the component's schema with the exported version, or the schema's
+    /// default variant if that version isn't installed.
+    async fn map_variant(
+        ctx: &DalContext,
+        component_spec: &FragmentComponentSpec,
+        report: &mut ComponentFragmentImport,
+    ) -> ComponentFragmentResult<Option<SchemaVariant>> {
+        let Some(schema) = Schema::find_by_name(ctx, &component_spec.schema_name)
+            .await
+            .map_err(Box::new)?
+        else {
+            return Ok(None);
+        };
+
+        let variants = SchemaVariant::list_for_schema(ctx, schema.id())
+            .await
+            .map_err(Box::new)?;
+        if let Some(variant) = variants
+            .iter()
+            .find(|variant| variant.version() == component_spec.variant_version)
+        {
+            return Ok(Some(variant.clone()));
+        }
+
+        let Some(default_variant_id) = schema
+            .get_default_schema_variant_id(ctx)
+            .await
+            .map_err(Box::new)?
+        else {
+            return Ok(None);
+        };
+        let variant = SchemaVariant::get_by_id_or_error(ctx, default_variant_id)
+            .await
+            .map_err(Box::new)?;
+        report.substituted_variants.push(SubstitutedVariant {
+            unique_id: component_spec.unique_id.clone(),
+            schema_name: component_spec.schema_name.clone(),
+            exported_version: component_spec.variant_version.clone(),
+            imported_version: variant.version().to_owned(),
+        });
+
+        Ok(Some(variant))
+    }
+}
+
+fn raw_geometry(
+    component_unique_id: &str,
+    position: &PositionSpec,
+) -> ComponentFragmentResult<RawGeometry> {
+    let parse = |value: &str| {
+        value.parse::<isize>().map_err(|_| {
+            ComponentFragmentError::InvalidPosition(
+                component_unique_id.to_owned(),
+                value.to_owned(),
+            )
+        })
+    };
+
+    Ok(RawGeometry {
+        x: parse(&position.x)?,
+        y: parse(&position.y)?,
+        width: position.width.as_deref().map(parse).transpose()?,
+        height: position.height.as_deref().map(parse).transpose()?,
+    })
+}
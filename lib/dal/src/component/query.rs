@@ -0,0 +1,389 @@
+//! A small query language for finding [`Components`](Component) in a change set.
+//!
+//! A query is a boolean expression over comparisons and predicates:
+//!
+//! ```text
+//! schema = "EC2 Instance" and /domain/region = "us-east-1" and not incoming("Security Group ID")
+//! ```
+//!
+//! Fields:
+//! - `id`, `name`, `schema`, `variant` and `type` (the component type, such as `component`)
+//! - `parent`: the name of the frame the component is in, or `null`
+//! - `qualification`: the worst qualification status, one of `failure`, `warning`, `unknown` or
+//!   `success`
+//! - `tag.<key>`: the value of a tag, or `null`
+//! - a path from the root prop, such as `/domain/region` or `/resource/status`. Segments that
+//!   contain spaces or operators can be quoted: `/domain/"Security Groups"/0`
+//!
+//! Operators are `=`, `!=`, `<`, `<=`, `>`, `>=` and `~` (the string contains the value, the array
+//! contains the value, or the object has the value as a key). Values are strings, numbers, `true`,
+//! `false` or `null`; a missing path compares equal to `null`.
+//!
+//! Predicates are `connected("socket")`, `incoming("socket")` and `outgoing("socket")` (the socket
+//! name is optional), `in_frame("name")` for any ancestor frame, and `exists(field)`. Combine with
+//! `and`, `or`, `not` and parentheses. An empty query matches every component.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    component::tag::{ComponentTag, ComponentTagError},
+    qualification::QualificationSubCheckStatus,
+    socket::{input::InputSocketError, output::OutputSocketError},
+    Component, ComponentError, ComponentId, DalContext, InputSocket, InputSocketId, OutputSocket,
+    OutputSocketId,
+};
+
+mod parser;
+
+use parser::{Direction, Expr, Field, Op};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ComponentQueryError {
+    #[error("component error: {0}")]
+    Component(#[from] ComponentError),
+    #[error("component tag error: {0}")]
+    ComponentTag(#[from] ComponentTagError),
+    #[error("input socket error: {0}")]
+    InputSocket(#[from] InputSocketError),
+    #[error("output socket error: {0}")]
+    OutputSocket(#[from] OutputSocketError),
+    #[error("invalid query at position {position}: {message}")]
+    Parse { position: usize, message: String },
+}
+
+pub type ComponentQueryResult<T> = Result<T, ComponentQueryError>;
+
+/// A component matched by a [`ComponentQuery`], with the values of the selected fields keyed by
+/// the field as it was written.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentQueryMatch {
+    pub component_id: ComponentId,
+    pub values: BTreeMap<String, Value>,
+}
+
+impl From<ComponentQueryMatch> for si_frontend_types::ComponentQueryMatch {
+    fn from(value: ComponentQueryMatch) -> Self {
+        Self {
+            component_id: value.component_id,
+            values: value.values,
+        }
+    }
+}
+
+/// A parsed component query. See the [module documentation](self) for the syntax.
+#[derive(Debug, Clone)]
+pub struct ComponentQuery {
+    expr: Expr,
+    select: Vec<(String, Field)>,
+}
+
+impl ComponentQuery {
+    /// Parses a query and the fields whose values should be returned for each match.
+    pub fn parse(query: &str, select: &[String]) -> ComponentQueryResult<Self> {
+        Ok(Self {
+            expr: parser::parse(query)?,
+            select: select
+                .iter()
+                .map(|field| Ok((field.to_owned(), parser::parse_field(field)?)))
+                .collect::<ComponentQueryResult<_>>()?,
+        })
+    }
+
+    /// Evaluates the query against every component in the change set.
+    pub async fn run(&self, ctx: &DalContext) -> ComponentQueryResult<Vec<ComponentQueryMatch>> {
+        let mut needs = Needs::default();
+        needs.expr(&self.expr);
+        for (_, field) in &self.select {
+            needs.field(field);
+        }
+
+        let mut socket_names = SocketNames::default();
+        let mut matches = Vec::new();
+        for component_id in Component::list_ids(ctx).await? {
+            let facts = Facts::gather(ctx, component_id, &needs, &mut socket_names).await?;
+            if !facts.eval(&self.expr) {
+                continue;
+            }
+
+            matches.push(ComponentQueryMatch {
+                component_id,
+                values: self
+                    .select
+                    .iter()
+                    .map(|(name, field)| (name.to_owned(), facts.field(field)))
+                    .collect(),
+            });
+        }
+
+        Ok(matches)
+    }
+}
+
+/// Which of the more expensive facts a query needs, so that we only gather those.
+#[derive(Debug, Default)]
+struct Needs {
+    ancestors: bool,
+    connections: bool,
+    qualification: bool,
+    tags: bool,
+    view: bool,
+}
+
+impl Needs {
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::All => {}
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::Not(inner) => self.expr(inner),
+            Expr::Compare(field, _, _) | Expr::Exists(field) => self.field(field),
+            Expr::Connected(_, _) => self.connections = true,
+            Expr::InFrame(_) => self.ancestors = true,
+        }
+    }
+
+    fn field(&mut self, field: &Field) {
+        match field {
+            Field::Parent => self.ancestors = true,
+            Field::Path(_) => self.view = true,
+            Field::Qualification => self.qualification = true,
+            Field::Tag(_) => self.tags = true,
+            Field::Id | Field::Name | Field::Schema | Field::Type | Field::Variant => {}
+        }
+    }
+}
+
+/// Socket names, cached across components since most components share schema variants.
+#[derive(Debug, Default)]
+struct SocketNames {
+    input: HashMap<InputSocketId, String>,
+    output: HashMap<OutputSocketId, String>,
+}
+
+impl SocketNames {
+    async fn input(&mut self, ctx: &DalContext, id: InputSocketId) -> ComponentQueryResult<String> {
+        if let Some(name) = self.input.get(&id) {
+            return Ok(name.to_owned());
+        }
+        let name = InputSocket::get_by_id(ctx, id).await?.name().to_owned();
+        self.input.insert(id, name.to_owned());
+        Ok(name)
+    }
+
+    async fn output(
+        &mut self,
+        ctx: &DalContext,
+        id: OutputSocketId,
+    ) -> ComponentQueryResult<String> {
+        if let Some(name) = self.output.get(&id) {
+            return Ok(name.to_owned());
+        }
+        let name = OutputSocket::get_by_id(ctx, id).await?.name().to_owned();
+        self.output.insert(id, name.to_owned());
+        Ok(name)
+    }
+}
+
+/// Everything a query can ask about one component.
+#[derive(Debug)]
+struct Facts {
+    id: ComponentId,
+    name: String,
+    schema: String,
+    variant: String,
+    component_type: String,
+    /// Frame names from the immediate parent outwards.
+    ancestors: Vec<String>,
+    incoming_sockets: HashSet<String>,
+    outgoing_sockets: HashSet<String>,
+    qualification: &'static str,
+    tags: BTreeMap<String, String>,
+    view: Value,
+}
+
+impl Facts {
+    async fn gather(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        needs: &Needs,
+        socket_names: &mut SocketNames,
+    ) -> ComponentQueryResult<Self> {
+        let component = Component::get_by_id(ctx, component_id).await?;
+        let mut facts = Self {
+            id: component_id,
+            name: component.name(ctx).await?,
+            schema: component.schema(ctx).await?.name().to_owned(),
+            variant: component
+                .schema_variant(ctx)
+                .await?
+                .display_name()
+                .to_owned(),
+            component_type: component.get_type(ctx).await?.to_string(),
+            ancestors: Vec::new(),
+            incoming_sockets: HashSet::new(),
+            outgoing_sockets: HashSet::new(),
+            qualification: "unknown",
+            tags: BTreeMap::new(),
+            view: Value::Null,
+        };
+
+        if needs.ancestors {
+            let mut parent = component.parent(ctx).await?;
+            while let Some(parent_id) = parent {
+                facts
+                    .ancestors
+                    .push(Component::name_by_id(ctx, parent_id).await?);
+                parent = Component::get_parent_by_id(ctx, parent_id).await?;
+            }
+        }
+
+        if needs.connections {
+            for connection in component.incoming_connections(ctx).await? {
+                facts.incoming_sockets.insert(
+                    socket_names
+                        .input(ctx, connection.to_input_socket_id)
+                        .await?,
+                );
+            }
+            for connection in component.outgoing_connections(ctx).await? {
+                facts.outgoing_sockets.insert(
+                    socket_names
+                        .output(ctx, connection.from_output_socket_id)
+                        .await?,
+                );
+            }
+        }
+
+        if needs.qualification {
+            let statuses = Component::list_qualification_statuses(ctx, component_id).await?;
+            facts.qualification = if statuses
+                .iter()
+                .any(|status| *status == Some(QualificationSubCheckStatus::Failure))
+            {
+                "failure"
+            } else if statuses
+                .iter()
+                .any(|status| *status == Some(QualificationSubCheckStatus::Warning))
+            {
+                "warning"
+            } else if statuses
+                .iter()
+                .any(|status| !matches!(status, Some(QualificationSubCheckStatus::Success)))
+            {
+                "unknown"
+            } else {
+                "success"
+            };
+        }
+
+        if needs.tags {
+            facts.tags = ComponentTag::list_for_component(ctx, component_id).await?;
+        }
+
+        if needs.view {
+            facts.view = component.view(ctx).await?.unwrap_or(Value::Null);
+        }
+
+        Ok(facts)
+    }
+
+    fn field(&self, field: &Field) -> Value {
+        match field {
+            Field::Id => Value::String(self.id.to_string()),
+            Field::Name => Value::String(self.name.to_owned()),
+            Field::Parent => self
+                .ancestors
+                .first()
+                .map(|name| Value::String(name.to_owned()))
+                .unwrap_or(Value::Null),
+            Field::Path(segments) => {
+                let mut value = &self.view;
+                for segment in segments {
+                    let next = match value {
+                        Value::Object(object) => object.get(segment),
+                        Value::Array(array) => segment
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| array.get(index)),
+                        _ => None,
+                    };
+                    match next {
+                        Some(next) => value = next,
+                        None => return Value::Null,
+                    }
+                }
+                value.to_owned()
+            }
+            Field::Qualification => Value::String(self.qualification.to_owned()),
+            Field::Schema => Value::String(self.schema.to_owned()),
+            Field::Tag(key) => self
+                .tags
+                .get(key)
+                .map(|value| Value::String(value.to_owned()))
+                .unwrap_or(Value::Null),
+            Field::Type => Value::String(self.component_type.to_owned()),
+            Field::Variant => Value::String(self.variant.to_owned()),
+        }
+    }
+
+    fn eval(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::All => true,
+            Expr::And(lhs, rhs) => self.eval(lhs) && self.eval(rhs),
+            Expr::Or(lhs, rhs) => self.eval(lhs) || self.eval(rhs),
+            Expr::Not(inner) => !self.eval(inner),
+            Expr::Compare(field, op, value) => compare(&self.field(field), *op, value),
+            Expr::Exists(field) => !self.field(field).is_null(),
+            Expr::Connected(direction, socket) => {
+                let has = |sockets: &HashSet<String>| match socket {
+                    Some(socket) => sockets.contains(socket),
+                    None => !sockets.is_empty(),
+                };
+                match direction {
+                    Direction::Any => has(&self.incoming_sockets) || has(&self.outgoing_sockets),
+                    Direction::Incoming => has(&self.incoming_sockets),
+                    Direction::Outgoing => has(&self.outgoing_sockets),
+                }
+            }
+            Expr::InFrame(name) => self.ancestors.contains(name),
+        }
+    }
+}
+
+fn compare(lhs: &Value, op: Op, rhs: &Value) -> bool {
+    let ordering = match (lhs, rhs) {
+        (Value::Number(lhs), Value::Number(rhs)) => lhs
+            .as_f64()
+            .zip(rhs.as_f64())
+            .and_then(|(lhs, rhs)| lhs.partial_cmp(&rhs)),
+        (Value::String(lhs), Value::String(rhs)) => Some(lhs.cmp(rhs)),
+        _ => None,
+    };
+
+    match op {
+        Op::Eq => ordering
+            .map(|ordering| ordering.is_eq())
+            .unwrap_or(lhs == rhs),
+        Op::Ne => !ordering
+            .map(|ordering| ordering.is_eq())
+            .unwrap_or(lhs == rhs),
+        Op::Lt => ordering.is_some_and(|ordering| ordering.is_lt()),
+        Op::Lte => ordering.is_some_and(|ordering| ordering.is_le()),
+        Op::Gt => ordering.is_some_and(|ordering| ordering.is_gt()),
+        Op::Gte => ordering.is_some_and(|ordering| ordering.is_ge()),
+        Op::Contains => match (lhs, rhs) {
+            (Value::String(lhs), Value::String(rhs)) => lhs.contains(rhs.as_str()),
+            (Value::Array(items), rhs) => items.contains(rhs),
+            (Value::Object(object), Value::String(key)) => object.contains_key(key),
+            _ => false,
+        },
+    }
+}
@@ -0,0 +1,497 @@
+//! Tokenizer and recursive descent parser for [`ComponentQuery`](super::ComponentQuery) source.
+
+use serde_json::Value;
+
+use super::{ComponentQueryError, ComponentQueryResult};
+
+/// How deeply parentheses and `not`s may nest, so that a query can't exhaust the stack of the
+/// recursive descent parser.
+pub const MAX_NESTING_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Matches every component. Produced by an empty query.
+    All,
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Op, Value),
+    Exists(Field),
+    Connected(Direction, Option<String>),
+    InFrame(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Field {
+    Id,
+    Name,
+    Parent,
+    /// A path from the root prop, such as `["domain", "region"]`.
+    Path(Vec<String>),
+    Qualification,
+    Schema,
+    Tag(String),
+    Type,
+    Variant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Contains,
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Ne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Any,
+    Incoming,
+    Outgoing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Path(Vec<String>),
+    Str(String),
+    Literal(Value),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+fn parse_error(position: usize, message: impl Into<String>) -> ComponentQueryError {
+    ComponentQueryError::Parse {
+        position,
+        message: message.into(),
+    }
+}
+
+fn is_path_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, '/' | '"' | '=' | '!' | '<' | '>' | '~' | '(' | ')')
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+fn tokenize(source: &str) -> ComponentQueryResult<Vec<(usize, Token)>> {
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let read_string = |i: &mut usize| -> ComponentQueryResult<String> {
+        let start = chars[*i].0;
+        let mut value = String::new();
+        *i += 1;
+        while let Some(&(_, c)) = chars.get(*i) {
+            *i += 1;
+            match c {
+                '"' => return Ok(value),
+                '\\' => match chars.get(*i) {
+                    Some(&(_, escaped)) => {
+                        value.push(escaped);
+                        *i += 1;
+                    }
+                    None => break,
+                },
+                c => value.push(c),
+            }
+        }
+        Err(parse_error(start, "unterminated string"))
+    };
+
+    while let Some(&(position, c)) = chars.get(i) {
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                i += 1;
+                Token::LParen
+            }
+            ')' => {
+                i += 1;
+                Token::RParen
+            }
+            '"' => Token::Str(read_string(&mut i)?),
+            '/' => {
+                let mut segments = Vec::new();
+                while chars.get(i).map(|&(_, c)| c) == Some('/') {
+                    i += 1;
+                    let segment = if chars.get(i).map(|&(_, c)| c) == Some('"') {
+                        read_string(&mut i)?
+                    } else {
+                        let mut segment = String::new();
+                        while let Some(&(_, c)) = chars.get(i).filter(|&&(_, c)| is_path_char(c)) {
+                            segment.push(c);
+                            i += 1;
+                        }
+                        segment
+                    };
+                    if segment.is_empty() {
+                        return Err(parse_error(position, "empty path segment"));
+                    }
+                    segments.push(segment);
+                }
+                Token::Path(segments)
+            }
+            '=' => {
+                i += 1;
+                Token::Op(Op::Eq)
+            }
+            '~' => {
+                i += 1;
+                Token::Op(Op::Contains)
+            }
+            '!' | '<' | '>' => {
+                let followed_by_eq = chars.get(i + 1).map(|&(_, c)| c) == Some('=');
+                i += if followed_by_eq { 2 } else { 1 };
+                Token::Op(match (c, followed_by_eq) {
+                    ('!', true) => Op::Ne,
+                    ('<', true) => Op::Lte,
+                    ('<', false) => Op::Lt,
+                    ('>', true) => Op::Gte,
+                    ('>', false) => Op::Gt,
+                    _ => return Err(parse_error(position, "expected '!='")),
+                })
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let start = i;
+                while chars.get(i).is_some_and(|&(_, c)| {
+                    c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')
+                }) {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().map(|&(_, c)| c).collect();
+                let number: serde_json::Number = text
+                    .parse()
+                    .map_err(|_| parse_error(position, format!("invalid number {text:?}")))?;
+                Token::Literal(Value::Number(number))
+            }
+            c if is_ident_char(c) => {
+                let start = i;
+                while chars.get(i).is_some_and(|&(_, c)| is_ident_char(c)) {
+                    i += 1;
+                }
+                let text: String = chars[start..i].iter().map(|&(_, c)| c).collect();
+                match text.as_str() {
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    "null" => Token::Literal(Value::Null),
+                    _ => Token::Ident(text),
+                }
+            }
+            c => return Err(parse_error(position, format!("unexpected character {c:?}"))),
+        };
+        tokens.push((position, token));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map(|(position, _)| *position)
+            .unwrap_or(self.end)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).map(|(_, token)| token.clone());
+        self.index += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> ComponentQueryResult<()> {
+        let position = self.position();
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(parse_error(position, format!("expected {description}"))),
+        }
+    }
+
+    /// Parses `parse` one level of nesting deeper, failing once [`MAX_NESTING_DEPTH`] is reached.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> ComponentQueryResult<T>,
+    ) -> ComponentQueryResult<T> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(parse_error(
+                self.position(),
+                format!("query nests deeper than {MAX_NESTING_DEPTH} levels"),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn or(&mut self) -> ComponentQueryResult<Expr> {
+        let mut expr = self.and()?;
+        while self.peek_keyword("or") {
+            self.index += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> ComponentQueryResult<Expr> {
+        let mut expr = self.not()?;
+        while self.peek_keyword("and") {
+            self.index += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> ComponentQueryResult<Expr> {
+        if self.peek_keyword("not") {
+            self.index += 1;
+            return Ok(Expr::Not(Box::new(self.nested(Self::not)?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> ComponentQueryResult<Expr> {
+        let position = self.position();
+        match self.peek().cloned() {
+            Some(Token::LParen) => {
+                self.index += 1;
+                let expr = self.nested(Self::or)?;
+                self.expect(Token::RParen, "')'")?;
+                Ok(expr)
+            }
+            Some(Token::Ident(ident))
+                if self.tokens.get(self.index + 1).map(|(_, token)| token)
+                    == Some(&Token::LParen) =>
+            {
+                self.index += 2;
+                let expr = match ident.to_ascii_lowercase().as_str() {
+                    "connected" | "incoming" | "outgoing" => {
+                        let direction = match ident.to_ascii_lowercase().as_str() {
+                            "incoming" => Direction::Incoming,
+                            "outgoing" => Direction::Outgoing,
+                            _ => Direction::Any,
+                        };
+                        let socket = match self.peek() {
+                            Some(Token::Str(socket)) => {
+                                let socket = socket.to_owned();
+                                self.index += 1;
+                                Some(socket)
+                            }
+                            _ => None,
+                        };
+                        Expr::Connected(direction, socket)
+                    }
+                    "in_frame" => match self.next() {
+                        Some(Token::Str(name)) => Expr::InFrame(name),
+                        _ => return Err(parse_error(position, "in_frame expects a frame name")),
+                    },
+                    "exists" => Expr::Exists(self.field()?),
+                    _ => return Err(parse_error(position, format!("unknown function {ident:?}"))),
+                };
+                self.expect(Token::RParen, "')'")?;
+                Ok(expr)
+            }
+            _ => {
+                let field = self.field()?;
+                let op_position = self.position();
+                let Some(Token::Op(op)) = self.next() else {
+                    return Err(parse_error(op_position, "expected a comparison operator"));
+                };
+                let literal_position = self.position();
+                let literal = match self.next() {
+                    Some(Token::Str(value)) => Value::String(value),
+                    Some(Token::Literal(value)) => value,
+                    _ => return Err(parse_error(literal_position, "expected a value")),
+                };
+                Ok(Expr::Compare(field, op, literal))
+            }
+        }
+    }
+
+    fn field(&mut self) -> ComponentQueryResult<Field> {
+        let position = self.position();
+        match self.next() {
+            Some(Token::Path(segments)) => Ok(Field::Path(segments)),
+            Some(Token::Ident(ident)) => parse_field_name(&ident)
+                .ok_or_else(|| parse_error(position, format!("unknown field {ident:?}"))),
+            _ => Err(parse_error(position, "expected a field")),
+        }
+    }
+}
+
+fn parse_field_name(ident: &str) -> Option<Field> {
+    if let Some(key) = ident.strip_prefix("tag.") {
+        return (!key.is_empty()).then(|| Field::Tag(key.to_owned()));
+    }
+    Some(match ident.to_ascii_lowercase().as_str() {
+        "id" => Field::Id,
+        "name" => Field::Name,
+        "parent" => Field::Parent,
+        "qualification" => Field::Qualification,
+        "schema" => Field::Schema,
+        "type" => Field::Type,
+        "variant" => Field::Variant,
+        _ => return None,
+    })
+}
+
+pub fn parse(source: &str) -> ComponentQueryResult<Expr> {
+    let tokens = tokenize(source)?;
+    if tokens.is_empty() {
+        return Ok(Expr::All);
+    }
+
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        end: source.len(),
+        depth: 0,
+    };
+    let expr = parser.or()?;
+    if parser.peek().is_some() {
+        return Err(parse_error(parser.position(), "unexpected trailing input"));
+    }
+
+    Ok(expr)
+}
+
+/// Parses a single field, as used in the `select` list of a query.
+pub fn parse_field(source: &str) -> ComponentQueryResult<Field> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        index: 0,
+        end: source.len(),
+        depth: 0,
+    };
+    let field = parser.field()?;
+    if parser.peek().is_some() {
+        return Err(parse_error(parser.position(), "unexpected trailing input"));
+    }
+
+    Ok(field)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn path(segments: &[&str]) -> Field {
+        Field::Path(segments.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn parses_precedence() {
+        let expr = parse(
+            r#"schema = "EC2 Instance" and /domain/region = "us-east-1" or not incoming("Security Group ID")"#,
+        )
+        .expect("parse");
+
+        assert_eq!(
+            Expr::Or(
+                Box::new(Expr::And(
+                    Box::new(Expr::Compare(Field::Schema, Op::Eq, json!("EC2 Instance"))),
+                    Box::new(Expr::Compare(
+                        path(&["domain", "region"]),
+                        Op::Eq,
+                        json!("us-east-1")
+                    )),
+                )),
+                Box::new(Expr::Not(Box::new(Expr::Connected(
+                    Direction::Incoming,
+                    Some("Security Group ID".to_owned())
+                )))),
+            ),
+            expr
+        );
+    }
+
+    #[test]
+    fn parses_fields_and_literals() {
+        assert_eq!(
+            Expr::Compare(
+                path(&["domain", "Security Groups", "0"]),
+                Op::Ne,
+                json!(null)
+            ),
+            parse(r#"/domain/"Security Groups"/0 != null"#).expect("parse"),
+        );
+        assert_eq!(
+            Expr::Compare(Field::Tag("env".to_owned()), Op::Contains, json!("prod")),
+            parse(r#"tag.env ~ "prod""#).expect("parse"),
+        );
+        assert_eq!(
+            Expr::Compare(path(&["domain", "Count"]), Op::Gte, json!(-2.5)),
+            parse("/domain/Count >= -2.5").expect("parse"),
+        );
+        assert_eq!(
+            Expr::And(
+                Box::new(Expr::InFrame("VPC".to_owned())),
+                Box::new(Expr::Exists(path(&["resource", "payload"]))),
+            ),
+            parse(r#"in_frame("VPC") AND exists(/resource/payload)"#).expect("parse"),
+        );
+        assert_eq!(Expr::All, parse("  ").expect("parse"));
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        let Err(ComponentQueryError::Parse { position, .. }) = parse(r#"schema "EC2""#) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(7, position);
+
+        assert!(parse("frobnicate = 1").is_err());
+        assert!(parse(r#"name = "unterminated"#).is_err());
+        assert!(parse(r#"(name = "a""#).is_err());
+        assert!(parse(r#"name = "a" name"#).is_err());
+    }
+
+    #[test]
+    fn limits_nesting_depth() {
+        let nested =
+            |depth: usize| format!("{}name = \"a\"{}", "(not ".repeat(depth), ")".repeat(depth));
+
+        assert!(parse(&nested(MAX_NESTING_DEPTH / 2)).is_ok());
+
+        for source in [
+            nested(100_000),
+            format!("{}name = \"a\"", "not ".repeat(100_000)),
+            format!("{}name = \"a\"{}", "(".repeat(100_000), ")".repeat(100_000)),
+        ] {
+            let Err(ComponentQueryError::Parse { message, .. }) = parse(&source) else {
+                panic!("expected a parse error");
+            };
+            assert!(
+                message.contains("nests deeper"),
+                "unexpected error: {message}"
+            );
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! User-defined key/value tags on [`Components`](Component).
+//!
+//! Each tag is a [`Tag`](NodeWeight::Tag) node hanging off its component by a
+//! [`Tag`](EdgeWeightKind::Tag) edge, so tags are rebased and merged like any other part of the
+//! graph. A component has at most one value per key: setting an existing key replaces the value
+//! on the existing node rather than adding another one.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use si_events::ulid::Ulid;
+use thiserror::Error;
+
+use crate::{
+    workspace_snapshot::{
+        edge_weight::{EdgeWeightKind, EdgeWeightKindDiscriminants},
+        node_weight::{traits::SiVersionedNodeWeight, NodeWeight, NodeWeightError},
+        WorkspaceSnapshotError,
+    },
+    Component, ComponentError, ComponentId, DalContext, EdgeWeight,
+};
+
+/// The longest tag key we accept, in bytes.
+const MAX_KEY_LEN: usize = 128;
+/// The longest tag value we accept, in bytes.
+const MAX_VALUE_LEN: usize = 256;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ComponentTagError {
+    #[error("component error: {0}")]
+    Component(#[from] Box<ComponentError>),
+    #[error("invalid tag key {0:?}: keys must be 1 to 128 bytes and cannot contain '=' or ','")]
+    InvalidKey(String),
+    #[error(
+        "invalid tag value for key {0}: values must be at most 256 bytes and cannot contain ','"
+    )]
+    InvalidValue(String),
+    #[error("node weight error: {0}")]
+    NodeWeight(#[from] NodeWeightError),
+    #[error("workspace snapshot error: {0}")]
+    WorkspaceSnapshot(#[from] WorkspaceSnapshotError),
+}
+
+pub type ComponentTagResult<T> = Result<T, ComponentTagError>;
+
+/// Logic for reading and writing the tags on a [`Component`].
+pub struct ComponentTag;
+
+impl ComponentTag {
+    /// Returns the tags on a component, keyed by tag key.
+    pub async fn list_for_component(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentTagResult<BTreeMap<String, String>> {
+        Ok(Self::tag_nodes(ctx, component_id)
+            .await?
+            .into_iter()
+            .map(|(_, key, value)| (key, value))
+            .collect())
+    }
+
+    /// Sets a tag on a component, replacing the value if the key is already set.
+    pub async fn set(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> ComponentTagResult<()> {
+        let key = validate_key(key.into())?;
+        let value = value.into();
+        if value.len() > MAX_VALUE_LEN || value.contains(',') {
+            return Err(ComponentTagError::InvalidValue(key));
+        }
+
+        let snap = ctx.workspace_snapshot()?;
+        match Self::find_by_key(ctx, component_id, &key).await? {
+            Some((_, existing_value)) if existing_value == value => {}
+            Some((tag_id, _)) => {
+                let lineage_id = snap.get_node_weight_by_id(tag_id).await?.lineage_id();
+                snap.add_or_replace_node(NodeWeight::new_tag(tag_id, lineage_id, key, value))
+                    .await?;
+            }
+            None => {
+                let id = snap.generate_ulid().await?;
+                let lineage_id = snap.generate_ulid().await?;
+                snap.add_or_replace_node(NodeWeight::new_tag(id, lineage_id, key, value))
+                    .await?;
+                snap.add_edge(component_id, EdgeWeight::new(EdgeWeightKind::Tag), id)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes a tag from a component. Returns `false` if the component had no tag with the key.
+    pub async fn remove(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        key: impl AsRef<str>,
+    ) -> ComponentTagResult<bool> {
+        let snap = ctx.workspace_snapshot()?;
+
+        // Remove every node with the key, including any duplicates left by concurrent edits.
+        let mut removed = false;
+        for tag_idx in snap
+            .outgoing_targets_for_edge_weight_kind(component_id, EdgeWeightKindDiscriminants::Tag)
+            .await?
+        {
+            let tag = snap.get_node_weight(tag_idx).await?.get_tag_node_weight()?;
+            if tag.key() == key.as_ref() {
+                snap.remove_node_by_id(tag.id()).await?;
+                removed = true;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Lists the components whose tags match every one of the selectors.
+    pub async fn list_component_ids_matching(
+        ctx: &DalContext,
+        selectors: &[TagSelector],
+    ) -> ComponentTagResult<Vec<ComponentId>> {
+        let mut matching = Vec::new();
+        for component_id in Component::list_ids(ctx).await.map_err(Box::new)? {
+            let tags = Self::list_for_component(ctx, component_id).await?;
+            if TagSelector::all_match(selectors, &tags) {
+                matching.push(component_id);
+            }
+        }
+
+        Ok(matching)
+    }
+
+    async fn find_by_key(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        key: &str,
+    ) -> ComponentTagResult<Option<(Ulid, String)>> {
+        Ok(Self::tag_nodes(ctx, component_id)
+            .await?
+            .into_iter()
+            .find(|(_, tag_key, _)| tag_key == key)
+            .map(|(id, _, value)| (id, value)))
+    }
+
+    /// Returns `(id, key, value)` for each tag node on the component. If two change sets added the
+    /// same key concurrently, both nodes survive the rebase; the one with the newest id wins.
+    async fn tag_nodes(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentTagResult<Vec<(Ulid, String, String)>> {
+        let snap = ctx.workspace_snapshot()?;
+
+        let mut by_key: BTreeMap<String, (Ulid, String)> = BTreeMap::new();
+        for tag_idx in snap
+            .outgoing_targets_for_edge_weight_kind(component_id, EdgeWeightKindDiscriminants::Tag)
+            .await?
+        {
+            let tag = snap.get_node_weight(tag_idx).await?.get_tag_node_weight()?;
+            let id = tag.id();
+            match by_key.get(tag.key()) {
+                Some((existing_id, _)) if *existing_id > id => {}
+                _ => {
+                    by_key.insert(tag.key().to_owned(), (id, tag.value().to_owned()));
+                }
+            }
+        }
+
+        Ok(by_key
+            .into_iter()
+            .map(|(key, (id, value))| (id, key, value))
+            .collect())
+    }
+}
+
+fn validate_key(key: String) -> ComponentTagResult<String> {
+    let key = key.trim().to_owned();
+    if key.is_empty() || key.len() > MAX_KEY_LEN || key.contains('=') || key.contains(',') {
+        return Err(ComponentTagError::InvalidKey(key));
+    }
+
+    Ok(key)
+}
+
+/// Selects components by tag: either by key alone (`env`), matching any value, or by key and value
+/// (`env=prod`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSelector {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl TagSelector {
+    pub fn new(key: impl Into<String>, value: Option<String>) -> Self {
+        Self {
+            key: key.into(),
+            value,
+        }
+    }
+
+    pub fn matches(&self, tags: &BTreeMap<String, String>) -> bool {
+        match (tags.get(&self.key), &self.value) {
+            (Some(_), None) => true,
+            (Some(tag_value), Some(value)) => tag_value == value,
+            (None, _) => false,
+        }
+    }
+
+    /// Returns `true` if every selector matches. An empty set of selectors matches everything.
+    pub fn all_match(selectors: &[Self], tags: &BTreeMap<String, String>) -> bool {
+        selectors.iter().all(|selector| selector.matches(tags))
+    }
+
+    /// Parses a comma separated list of selectors, such as `env=prod,team`.
+    pub fn parse_list(selectors: &str) -> ComponentTagResult<Vec<Self>> {
+        selectors
+            .split(',')
+            .filter(|selector| !selector.trim().is_empty())
+            .map(Self::from_str)
+            .collect()
+    }
+}
+
+impl FromStr for TagSelector {
+    type Err = ComponentTagError;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        Ok(match selector.split_once('=') {
+            Some((key, value)) => Self::new(validate_key(key.to_owned())?, Some(value.to_owned())),
+            None => Self::new(validate_key(selector.to_owned())?, None),
+        })
+    }
+}
+
+impl fmt::Display for TagSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}={}", self.key, value),
+            None => write!(f, "{}", self.key),
+        }
+    }
+}
@@ -40,17 +40,18 @@ use crate::{audit_logging, slow_rt, ChangeSetError, EncryptedSecret, Workspace,
 use crate::{
     change_set::{ChangeSet, ChangeSetId},
     job::{
-        definition::ActionJob,
+        definition::{ActionJob, ReencryptSecrets},
         processor::{JobQueueProcessor, JobQueueProcessorError},
         producer::{BlockingJobError, BlockingJobResult, JobProducer},
         queue::JobQueue,
     },
-    workspace_snapshot::WorkspaceSnapshotError,
+    workspace_snapshot::{chunked::SnapshotChunk, WorkspaceSnapshotError},
     AttributeValueId, HistoryActor, StandardModel, Tenancy, TenancyError, Visibility, WorkspacePk,
     WorkspaceSnapshot,
 };
 
-pub type DalLayerDb = LayerDb<ContentTypes, EncryptedSecret, WorkspaceSnapshotGraph, RebaseBatch>;
+pub type DalLayerDb =
+    LayerDb<ContentTypes, EncryptedSecret, WorkspaceSnapshotGraph, RebaseBatch, SnapshotChunk>;
 
 /// A context type which contains handles to common core service dependencies.
 ///
@@ -812,6 +813,16 @@ impl DalContext {
         Ok(())
     }
 
+    /// Enqueues a [`ReencryptSecrets`] job which re-encrypts the secrets in the current change set
+    /// with the workspace's current key pair.
+    pub async fn enqueue_reencrypt_secrets(
+        &self,
+        job: Box<ReencryptSecrets>,
+    ) -> TransactionsResult<()> {
+        self.txns().await?.job_queue.enqueue_job(job).await;
+        Ok(())
+    }
+
     /// Add the node ids to the workspace snapshot graph and enqueue a dependent values update.
     /// This update will only be run on commit if blocking_commit is used. If commit is used, the
     /// DVU debouncer will run the job. Note that the DVU debouncer might still pick up the job
@@ -21,8 +21,8 @@ use crate::{
     },
     change_status::ChangeStatus,
     component::{
-        inferred_connection_graph::InferredConnectionGraphError, ComponentError, ComponentResult,
-        IncomingConnection, InferredConnection, OutgoingConnection,
+        inferred_connection_graph::InferredConnectionGraphError, tag::TagSelector, ComponentError,
+        ComponentResult, IncomingConnection, InferredConnection, OutgoingConnection,
     },
     diagram::{
         geometry::{Geometry, GeometryId, GeometryRepresents},
@@ -756,4 +756,29 @@ impl Diagram {
 
         Self::assemble(ctx, Some(default_view_id)).await
     }
+
+    /// Drops every component whose tags do not match all of the selectors, along with any edges
+    /// to or from those components.
+    pub fn retain_tagged(&mut self, selectors: &[TagSelector]) {
+        if selectors.is_empty() {
+            return;
+        }
+
+        self.components
+            .retain(|component| TagSelector::all_match(selectors, &component.tags));
+        let kept: HashSet<ComponentId> = self
+            .components
+            .iter()
+            .map(|component| component.component_id)
+            .collect();
+        let keep_edge =
+            |from: &ComponentId, to: &ComponentId| kept.contains(from) && kept.contains(to);
+
+        self.edges
+            .retain(|edge| keep_edge(&edge.from_component_id, &edge.to_component_id));
+        self.inferred_edges
+            .retain(|edge| keep_edge(&edge.from_component_id, &edge.to_component_id));
+        self.management_edges
+            .retain(|edge| keep_edge(&edge.from_component_id, &edge.to_component_id));
+    }
 }
@@ -220,6 +220,7 @@ impl Geometry {
             | NodeWeight::SchemaVariant(_)
             | NodeWeight::ManagementPrototype(_)
             | NodeWeight::Geometry(_)
+            | NodeWeight::Tag(_)
             | NodeWeight::View(_) => {
                 return Err(DiagramError::GeometryCannotRepresentNodeWeight(
                     node_weight.into(),
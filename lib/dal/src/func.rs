@@ -37,7 +37,10 @@ pub mod backend;
 pub mod binding;
 pub mod intrinsics;
 mod kind;
+pub mod replay;
 pub mod resource_payload_to_value;
+pub mod retention;
+pub mod run_log;
 pub mod runner;
 pub use kind::FuncKind;
 
@@ -403,7 +406,19 @@ impl Func {
                 | IntrinsicFunc::SetObject
                 | IntrinsicFunc::SetString
                 | IntrinsicFunc::Unset => false,
-                IntrinsicFunc::Identity | IntrinsicFunc::Validation => true,
+                IntrinsicFunc::Base64Decode
+                | IntrinsicFunc::Base64Encode
+                | IntrinsicFunc::BuildArray
+                | IntrinsicFunc::BuildMap
+                | IntrinsicFunc::Concat
+                | IntrinsicFunc::DefaultIfEmpty
+                | IntrinsicFunc::Identity
+                | IntrinsicFunc::JsonPointer
+                | IntrinsicFunc::Lowercase
+                | IntrinsicFunc::RegexExtract
+                | IntrinsicFunc::Template
+                | IntrinsicFunc::Uppercase
+                | IntrinsicFunc::Validation => true,
             },
             None => true,
         }
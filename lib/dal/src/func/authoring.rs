@@ -198,6 +198,21 @@ impl FuncAuthoringClient {
         Ok(func)
     }
 
+    /// Creates a new workspace policy Func and returns it. Policies are not bound to anything; they
+    /// run against every change set before it is applied.
+    #[instrument(
+        name = "func.authoring.create_new_policy_func",
+        level = "info",
+        skip(ctx)
+    )]
+    pub async fn create_new_policy_func(
+        ctx: &DalContext,
+        name: Option<String>,
+    ) -> FuncAuthoringResult<Func> {
+        let func = create::create_policy_func(ctx, name).await?;
+        Ok(func)
+    }
+
     /// Creates a new Code Gen or Qualification Func and returns it
     #[instrument(
         name = "func.authoring.create_new_leaf_func",
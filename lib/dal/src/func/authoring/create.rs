@@ -23,6 +23,7 @@ static DEFAULT_QUALIFICATION_CODE: &str = include_str!("data/defaults/qualificat
 static DEFAULT_ACTION_CODE: &str = include_str!("data/defaults/action.ts");
 static DEFAULT_AUTHENTICATION_CODE: &str = include_str!("data/defaults/authentication.ts");
 static DEFAULT_MGMT_CODE: &str = include_str!("data/defaults/management.ts");
+static DEFAULT_POLICY_CODE: &str = include_str!("data/defaults/policy.ts");
 
 #[allow(dead_code)]
 static DEFAULT_VALIDATION_CODE: &str = include_str!("data/defaults/validation.ts");
@@ -52,6 +53,26 @@ pub(crate) async fn create_management_func(
     Ok(func)
 }
 
+#[instrument(
+    name = "func.authoring.create_func.create.policy",
+    level = "debug",
+    skip(ctx)
+)]
+pub(crate) async fn create_policy_func(
+    ctx: &DalContext,
+    name: Option<String>,
+) -> FuncAuthoringResult<Func> {
+    create_func_stub(
+        ctx,
+        name,
+        FuncBackendKind::JsPolicy,
+        FuncBackendResponseType::Json,
+        DEFAULT_POLICY_CODE,
+        DEFAULT_CODE_HANDLER,
+    )
+    .await
+}
+
 #[instrument(
     name = "func.authoring.create_func.create.action",
     level = "debug",
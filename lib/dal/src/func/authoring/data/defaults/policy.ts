@@ -0,0 +1,21 @@
+async function main(input: Input): Promise<Output> {
+  const violations = input.components
+    .filter((component) => component.changeStatus !== "deleted")
+    .filter((component) => !component.name)
+    .map((component) => ({
+      componentId: component.id,
+      message: "Components must have a name",
+    }));
+
+  if (violations.length > 0) {
+    return {
+      result: "failure",
+      message: `${violations.length} component(s) violate this policy`,
+      violations,
+    };
+  }
+
+  return {
+    result: "success",
+  };
+}
@@ -29,21 +29,35 @@ pub mod management;
 pub mod map;
 pub mod object;
 pub mod string;
+pub mod transform;
 pub mod validation;
+pub mod wasm;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum FuncBackendError {
+    #[error("base64 decode error: {0}")]
+    Base64Decode(#[from] base64::DecodeError),
+    #[error("cannot build a map from {0} keys and {1} values")]
+    BuildMapLengthMismatch(usize, usize),
     #[error("expected same array entry prop kinds - expected {0}, found: {1}")]
     DifferingArrayEntryPropKinds(PropKind, PropKind),
     #[error("dispatch func missing code_base64 {0}")]
     DispatchMissingBase64(FuncId),
     #[error("dispatch func missing handler {0}")]
     DispatchMissingHandler(FuncId),
+    #[error("decoded value is not valid utf-8: {0}")]
+    FromUtf8(#[from] std::string::FromUtf8Error),
     #[error("function result action run error: {0:?}")]
     FunctionResultActionRun(FunctionResult<Box<ActionRunResultSuccess>>),
     #[error("invalid data - expected a valid array entry value, got: {0}")]
     InvalidArrayEntryData(serde_json::Value),
+    #[error("invalid json pointer, must be empty or start with '/': {0}")]
+    InvalidJsonPointer(String),
+    #[error("regex error: {0}")]
+    Regex(#[from] regex::Error),
+    #[error("regex compiles to more than the {0} byte limit")]
+    RegexTooLarge(usize),
     #[error("result failure: kind={kind}, message={message}, backend={backend}")]
     ResultFailure {
         kind: FunctionResultFailureErrorKind,
@@ -58,6 +72,8 @@ pub enum FuncBackendError {
     Ulid(#[from] ulid::DecodeError),
     #[error("veritech client error: {0}")]
     VeritechClient(#[from] veritech_client::ClientError),
+    #[error("wasm execution task join error: {0}")]
+    WasmJoin(#[from] tokio::task::JoinError),
 }
 
 pub type FuncBackendResult<T> = Result<T, FuncBackendError>;
@@ -99,6 +115,22 @@ pub enum FuncBackendKind {
     Unset,
     Validation,
     Management,
+    /// A workspace policy, run against the components in a change set before apply.
+    JsPolicy,
+    /// A WebAssembly module executed in-process (see [`wasm`]).
+    Wasm,
+    // The pure Rust transform intrinsics (see [`transform`]).
+    Base64Decode,
+    Base64Encode,
+    BuildArray,
+    BuildMap,
+    Concat,
+    DefaultIfEmpty,
+    JsonPointer,
+    Lowercase,
+    RegexExtract,
+    Template,
+    Uppercase,
 }
 
 impl From<FuncBackendKind> for si_events::FuncBackendKind {
@@ -124,6 +156,19 @@ impl From<FuncBackendKind> for si_events::FuncBackendKind {
             FuncBackendKind::Unset => si_events::FuncBackendKind::Unset,
             FuncBackendKind::Validation => si_events::FuncBackendKind::Validation,
             FuncBackendKind::Management => si_events::FuncBackendKind::Management,
+            FuncBackendKind::JsPolicy => si_events::FuncBackendKind::JsPolicy,
+            FuncBackendKind::Wasm => si_events::FuncBackendKind::Wasm,
+            FuncBackendKind::Base64Decode => si_events::FuncBackendKind::Base64Decode,
+            FuncBackendKind::Base64Encode => si_events::FuncBackendKind::Base64Encode,
+            FuncBackendKind::BuildArray => si_events::FuncBackendKind::BuildArray,
+            FuncBackendKind::BuildMap => si_events::FuncBackendKind::BuildMap,
+            FuncBackendKind::Concat => si_events::FuncBackendKind::Concat,
+            FuncBackendKind::DefaultIfEmpty => si_events::FuncBackendKind::DefaultIfEmpty,
+            FuncBackendKind::JsonPointer => si_events::FuncBackendKind::JsonPointer,
+            FuncBackendKind::Lowercase => si_events::FuncBackendKind::Lowercase,
+            FuncBackendKind::RegexExtract => si_events::FuncBackendKind::RegexExtract,
+            FuncBackendKind::Template => si_events::FuncBackendKind::Template,
+            FuncBackendKind::Uppercase => si_events::FuncBackendKind::Uppercase,
         }
     }
 }
@@ -151,6 +196,19 @@ impl From<si_events::FuncBackendKind> for FuncBackendKind {
             si_events::FuncBackendKind::Unset => FuncBackendKind::Unset,
             si_events::FuncBackendKind::Validation => FuncBackendKind::Validation,
             si_events::FuncBackendKind::Management => FuncBackendKind::Management,
+            si_events::FuncBackendKind::JsPolicy => FuncBackendKind::JsPolicy,
+            si_events::FuncBackendKind::Wasm => FuncBackendKind::Wasm,
+            si_events::FuncBackendKind::Base64Decode => FuncBackendKind::Base64Decode,
+            si_events::FuncBackendKind::Base64Encode => FuncBackendKind::Base64Encode,
+            si_events::FuncBackendKind::BuildArray => FuncBackendKind::BuildArray,
+            si_events::FuncBackendKind::BuildMap => FuncBackendKind::BuildMap,
+            si_events::FuncBackendKind::Concat => FuncBackendKind::Concat,
+            si_events::FuncBackendKind::DefaultIfEmpty => FuncBackendKind::DefaultIfEmpty,
+            si_events::FuncBackendKind::JsonPointer => FuncBackendKind::JsonPointer,
+            si_events::FuncBackendKind::Lowercase => FuncBackendKind::Lowercase,
+            si_events::FuncBackendKind::RegexExtract => FuncBackendKind::RegexExtract,
+            si_events::FuncBackendKind::Template => FuncBackendKind::Template,
+            si_events::FuncBackendKind::Uppercase => FuncBackendKind::Uppercase,
         }
     }
 }
@@ -54,49 +54,57 @@ impl FuncDispatch for FuncBackendJsAttribute {
                 &change_set_id.to_string(),
             )
             .await?;
-        let value = match value {
-            FunctionResult::Failure(failure) => match &self.request.response_type {
-                ResolverFunctionResponseType::Action
-                | ResolverFunctionResponseType::Array
-                | ResolverFunctionResponseType::Boolean
-                | ResolverFunctionResponseType::Integer
-                | ResolverFunctionResponseType::Identity
-                | ResolverFunctionResponseType::Map
-                | ResolverFunctionResponseType::Object
-                | ResolverFunctionResponseType::String
-                | ResolverFunctionResponseType::Unset
-                | ResolverFunctionResponseType::Void
-                | ResolverFunctionResponseType::Management
-                | ResolverFunctionResponseType::Json => FunctionResult::Failure(failure),
-                ResolverFunctionResponseType::Qualification => {
-                    FunctionResult::Success(Self::Output {
-                        execution_id: failure.execution_id().to_owned(),
-                        data: serde_json::json!({
-                            "result": "failure",
-                            "message": format!("Function execution failed: {}", failure.error().message),
-                        }),
-                        unset: false,
-                        timestamp: u64::try_from(std::cmp::max(Utc::now().timestamp(), 0))
-                            .expect("timestamp not be negative"),
-                    })
-                }
-                ResolverFunctionResponseType::CodeGeneration => {
-                    FunctionResult::Success(Self::Output {
-                        execution_id: failure.execution_id().to_owned(),
-                        data: serde_json::json!({
-                            "format": "json",
-                            "code": "null",
-                            "message": format!("Function execution failed: {}", failure.error().message),
-                        }),
-                        unset: false,
-                        timestamp: u64::try_from(std::cmp::max(Utc::now().timestamp(), 0))
-                            .expect("timestamp not be negative"),
-                    })
-                }
-            },
-            FunctionResult::Success(value) => FunctionResult::Success(value),
-        };
-        Ok(value)
+        Ok(handle_failure(&self.request.response_type, value))
+    }
+}
+
+/// Qualification and code generation funcs that fail still produce a result, so that the failure
+/// is shown to the user instead of the prior result silently remaining in place.
+pub(crate) fn handle_failure(
+    response_type: &ResolverFunctionResponseType,
+    value: FunctionResult<ResolverFunctionResultSuccess>,
+) -> FunctionResult<ResolverFunctionResultSuccess> {
+    match value {
+        FunctionResult::Failure(failure) => match response_type {
+            ResolverFunctionResponseType::Action
+            | ResolverFunctionResponseType::Array
+            | ResolverFunctionResponseType::Boolean
+            | ResolverFunctionResponseType::Integer
+            | ResolverFunctionResponseType::Identity
+            | ResolverFunctionResponseType::Map
+            | ResolverFunctionResponseType::Object
+            | ResolverFunctionResponseType::String
+            | ResolverFunctionResponseType::Unset
+            | ResolverFunctionResponseType::Void
+            | ResolverFunctionResponseType::Management
+            | ResolverFunctionResponseType::Json => FunctionResult::Failure(failure),
+            ResolverFunctionResponseType::Qualification => {
+                FunctionResult::Success(ResolverFunctionResultSuccess {
+                    execution_id: failure.execution_id().to_owned(),
+                    data: serde_json::json!({
+                        "result": "failure",
+                        "message": format!("Function execution failed: {}", failure.error().message),
+                    }),
+                    unset: false,
+                    timestamp: u64::try_from(std::cmp::max(Utc::now().timestamp(), 0))
+                        .expect("timestamp not be negative"),
+                })
+            }
+            ResolverFunctionResponseType::CodeGeneration => {
+                FunctionResult::Success(ResolverFunctionResultSuccess {
+                    execution_id: failure.execution_id().to_owned(),
+                    data: serde_json::json!({
+                        "format": "json",
+                        "code": "null",
+                        "message": format!("Function execution failed: {}", failure.error().message),
+                    }),
+                    unset: false,
+                    timestamp: u64::try_from(std::cmp::max(Utc::now().timestamp(), 0))
+                        .expect("timestamp not be negative"),
+                })
+            }
+        },
+        FunctionResult::Success(value) => FunctionResult::Success(value),
     }
 }
 
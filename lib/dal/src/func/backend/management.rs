@@ -13,9 +13,11 @@ use super::ExtractPayload;
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct FuncBackendManagementArgs {
-    this_component: ComponentViewWithGeometry,
-    components: HashMap<String, ComponentViewWithGeometry>,
-    current_view: String,
+    pub this_component: ComponentViewWithGeometry,
+    pub components: HashMap<String, ComponentViewWithGeometry>,
+    #[serde(default)]
+    pub selected_components: HashMap<String, ComponentViewWithGeometry>,
+    pub current_view: String,
 }
 
 #[derive(Debug)]
@@ -42,6 +44,7 @@ impl FuncDispatch for FuncBackendManagement {
             code_base64: code_base64.into(),
             this_component: args.this_component,
             components: args.components,
+            selected_components: args.selected_components,
             current_view: args.current_view,
             before,
         };
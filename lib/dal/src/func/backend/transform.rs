@@ -0,0 +1,511 @@
+//! Pure Rust backends for the transform [intrinsics](crate::func::intrinsics::IntrinsicFunc).
+//!
+//! These cover the small attribute functions that would otherwise need a JavaScript round-trip
+//! through veritech, like joining strings or picking a field out of an object. A `null` input
+//! generally produces a `null` output, so that a transform whose inputs have not been set yet
+//! leaves its destination unset instead of failing.
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::func::backend::{FuncBackend, FuncBackendError, FuncBackendResult};
+
+type FuncBackendValues = (Option<Value>, Option<Value>);
+
+/// Caps the memory a user supplied pattern may compile to, for both the compiled program and the
+/// lazy DFA cache, so that a pathological pattern fails the function instead of the server.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+fn values(value: Value) -> FuncBackendResult<FuncBackendValues> {
+    Ok((Some(value.clone()), Some(value)))
+}
+
+/// Multiple inputs for one argument arrive as an array, but a single input arrives bare.
+fn into_list(value: Option<Value>) -> Vec<Value> {
+    match value {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(values)) => values,
+        Some(value) => vec![value],
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(string) => string.is_empty(),
+        Value::Array(array) => array.is_empty(),
+        Value::Object(object) => object.is_empty(),
+        Value::Bool(_) | Value::Number(_) => false,
+    }
+}
+
+/// Renders a scalar the way it would appear when interpolated into a string.
+fn to_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(string) => Some(string.to_owned()),
+        other => Some(other.to_string()),
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendStringTransformArgs {
+    pub value: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendBase64Decode {
+    args: FuncBackendStringTransformArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendBase64Decode {
+    type Args = FuncBackendStringTransformArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        let Some(value) = self.args.value else {
+            return values(Value::Null);
+        };
+        let decoded = String::from_utf8(general_purpose::STANDARD.decode(value.trim())?)?;
+        values(Value::String(decoded))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendBase64Encode {
+    args: FuncBackendStringTransformArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendBase64Encode {
+    type Args = FuncBackendStringTransformArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        values(match self.args.value {
+            Some(value) => Value::String(general_purpose::STANDARD.encode(value)),
+            None => Value::Null,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendLowercase {
+    args: FuncBackendStringTransformArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendLowercase {
+    type Args = FuncBackendStringTransformArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        values(match self.args.value {
+            Some(value) => Value::String(value.to_lowercase()),
+            None => Value::Null,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendUppercase {
+    args: FuncBackendStringTransformArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendUppercase {
+    type Args = FuncBackendStringTransformArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        values(match self.args.value {
+            Some(value) => Value::String(value.to_uppercase()),
+            None => Value::Null,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendBuildArrayArgs {
+    pub values: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendBuildArray {
+    args: FuncBackendBuildArrayArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendBuildArray {
+    type Args = FuncBackendBuildArrayArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        values(Value::Array(into_list(self.args.values)))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendBuildMapArgs {
+    pub keys: Option<Value>,
+    pub values: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendBuildMap {
+    args: FuncBackendBuildMapArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendBuildMap {
+    type Args = FuncBackendBuildMapArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        let keys = into_list(self.args.keys);
+        let map_values = into_list(self.args.values);
+        if keys.len() != map_values.len() {
+            return Err(FuncBackendError::BuildMapLengthMismatch(
+                keys.len(),
+                map_values.len(),
+            ));
+        }
+
+        let mut map = serde_json::Map::with_capacity(keys.len());
+        for (key, value) in keys.into_iter().zip(map_values) {
+            let Some(key) = to_text(&key) else {
+                continue;
+            };
+            map.insert(key, value);
+        }
+
+        values(Value::Object(map))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendConcatArgs {
+    pub values: Option<Value>,
+    pub separator: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendConcat {
+    args: FuncBackendConcatArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendConcat {
+    type Args = FuncBackendConcatArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        let parts: Vec<String> = into_list(self.args.values)
+            .iter()
+            .filter_map(to_text)
+            .collect();
+        let separator = self.args.separator.unwrap_or_default();
+
+        values(Value::String(parts.join(&separator)))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendDefaultIfEmptyArgs {
+    pub value: Option<Value>,
+    pub default: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendDefaultIfEmpty {
+    args: FuncBackendDefaultIfEmptyArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendDefaultIfEmpty {
+    type Args = FuncBackendDefaultIfEmptyArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        let value = self.args.value.unwrap_or(Value::Null);
+        if is_empty(&value) {
+            values(self.args.default.unwrap_or(Value::Null))
+        } else {
+            values(value)
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendJsonPointerArgs {
+    pub value: Option<Value>,
+    pub pointer: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendJsonPointer {
+    args: FuncBackendJsonPointerArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendJsonPointer {
+    type Args = FuncBackendJsonPointerArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        let (Some(value), Some(pointer)) = (self.args.value, self.args.pointer) else {
+            return values(Value::Null);
+        };
+        if !pointer.is_empty() && !pointer.starts_with('/') {
+            return Err(FuncBackendError::InvalidJsonPointer(pointer));
+        }
+
+        values(value.pointer(&pointer).cloned().unwrap_or(Value::Null))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendRegexExtractArgs {
+    pub value: Option<String>,
+    pub pattern: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendRegexExtract {
+    args: FuncBackendRegexExtractArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendRegexExtract {
+    type Args = FuncBackendRegexExtractArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    /// Returns the first capture group of the first match, or the whole match if the pattern
+    /// has no groups.
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        let (Some(value), Some(pattern)) = (self.args.value, self.args.pattern) else {
+            return values(Value::Null);
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .size_limit(REGEX_SIZE_LIMIT)
+            .dfa_size_limit(REGEX_SIZE_LIMIT)
+            .build()
+            .map_err(|err| match err {
+                regex::Error::CompiledTooBig(_) => {
+                    FuncBackendError::RegexTooLarge(REGEX_SIZE_LIMIT)
+                }
+                err => FuncBackendError::Regex(err),
+            })?;
+
+        let extracted = regex.captures(&value).and_then(|captures| {
+            captures
+                .get(1)
+                .or_else(|| captures.get(0))
+                .map(|found| Value::String(found.as_str().to_owned()))
+        });
+
+        values(extracted.unwrap_or(Value::Null))
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendTemplateArgs {
+    pub template: Option<String>,
+    pub values: Option<serde_json::Map<String, Value>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FuncBackendTemplate {
+    args: FuncBackendTemplateArgs,
+}
+
+#[async_trait]
+impl FuncBackend for FuncBackendTemplate {
+    type Args = FuncBackendTemplateArgs;
+
+    fn new(args: Self::Args) -> Box<Self> {
+        Box::new(Self { args })
+    }
+
+    /// Replaces every `{{name}}` in the template with the value of the same name, for example
+    /// `arn:aws:iam::{{accountId}}:role/{{roleName}}`. If any referenced value is missing or
+    /// `null`, the result is `null`.
+    async fn inline(self: Box<Self>) -> FuncBackendResult<FuncBackendValues> {
+        let Some(template) = self.args.template else {
+            return values(Value::Null);
+        };
+        let template_values = self.args.values.unwrap_or_default();
+
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let name = rest[start + 2..start + 2 + len].trim();
+            let Some(text) = template_values.get(name).and_then(to_text) else {
+                return values(Value::Null);
+            };
+
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(&text);
+            rest = &rest[start + 2 + len + 2..];
+        }
+        rendered.push_str(rest);
+
+        values(Value::String(rendered))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    async fn run<B: FuncBackend>(args: Value) -> Value {
+        B::create_and_execute(&args)
+            .await
+            .expect("could not execute transform")
+            .1
+            .expect("transform should produce a value")
+    }
+
+    #[tokio::test]
+    async fn string_transforms() {
+        assert_eq!(
+            json!("aGVsbG8="),
+            run::<FuncBackendBase64Encode>(json!({ "value": "hello" })).await
+        );
+        assert_eq!(
+            json!("hello"),
+            run::<FuncBackendBase64Decode>(json!({ "value": "aGVsbG8=" })).await
+        );
+        assert_eq!(
+            json!("hello"),
+            run::<FuncBackendLowercase>(json!({ "value": "HeLLo" })).await
+        );
+        assert_eq!(
+            json!("HELLO"),
+            run::<FuncBackendUppercase>(json!({ "value": "HeLLo" })).await
+        );
+        assert_eq!(
+            Value::Null,
+            run::<FuncBackendUppercase>(json!({ "value": null })).await
+        );
+    }
+
+    #[tokio::test]
+    async fn concat_and_template() {
+        assert_eq!(
+            json!("us-east-1a"),
+            run::<FuncBackendConcat>(json!({ "values": ["us-east-1", "a"] })).await
+        );
+        assert_eq!(
+            json!("a-1-true"),
+            run::<FuncBackendConcat>(json!({ "values": ["a", 1, null, true], "separator": "-" }))
+                .await
+        );
+        assert_eq!(
+            json!("arn:aws:iam::123:role/admin"),
+            run::<FuncBackendTemplate>(json!({
+                "template": "arn:aws:iam::{{ accountId }}:role/{{roleName}}",
+                "values": { "accountId": 123, "roleName": "admin" },
+            }))
+            .await
+        );
+        assert_eq!(
+            Value::Null,
+            run::<FuncBackendTemplate>(json!({
+                "template": "arn:aws:iam::{{accountId}}:role/{{roleName}}",
+                "values": { "accountId": 123, "roleName": null },
+            }))
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn structural_transforms() {
+        assert_eq!(
+            json!(["a"]),
+            run::<FuncBackendBuildArray>(json!({ "values": "a" })).await
+        );
+        assert_eq!(
+            json!({ "a": 1, "b": 2 }),
+            run::<FuncBackendBuildMap>(json!({ "keys": ["a", "b"], "values": [1, 2] })).await
+        );
+        assert_eq!(
+            json!("vpc-1"),
+            run::<FuncBackendJsonPointer>(json!({
+                "value": { "vpc": { "id": "vpc-1" } },
+                "pointer": "/vpc/id",
+            }))
+            .await
+        );
+        assert_eq!(
+            json!("fallback"),
+            run::<FuncBackendDefaultIfEmpty>(json!({ "value": "", "default": "fallback" })).await
+        );
+        assert_eq!(
+            json!("set"),
+            run::<FuncBackendDefaultIfEmpty>(json!({ "value": "set", "default": "fallback" }))
+                .await
+        );
+        assert_eq!(
+            json!("123456789012"),
+            run::<FuncBackendRegexExtract>(json!({
+                "value": "arn:aws:iam::123456789012:role/admin",
+                "pattern": r"::(\d{12}):",
+            }))
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn regex_extract_rejects_oversized_patterns() {
+        let result = FuncBackendRegexExtract::create_and_execute(&json!({
+            "value": "anything",
+            "pattern": r"\w{1000}{1000}",
+        }))
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(FuncBackendError::RegexTooLarge(REGEX_SIZE_LIMIT))
+        ));
+    }
+}
@@ -0,0 +1,549 @@
+//! Executes [`Funcs`](crate::Func) whose code is a WebAssembly module in-process, instead of
+//! dispatching them to veritech.
+//!
+//! The module is the base64 encoded `code_base64` of the func and the `handler` names the export
+//! to call. Modules do not get WASI; everything crosses the boundary as JSON through linear
+//! memory:
+//!
+//! - the module exports its `memory` and an `alloc(len: i32) -> i32` function, which the host
+//!   calls to reserve room for the input
+//! - the handler has the signature `(ptr: i32, len: i32) -> i64`. It receives the input document
+//!   `{"args": ..., "responseType": ...}` and returns the location of its output document packed
+//!   as `(ptr << 32) | len`
+//! - the output document is either `{"data": ...}` or `{"error": "message"}`
+//! - the module may import `si.log(level: i32, ptr: i32, len: i32)` to write a UTF-8 line to the
+//!   func run logs, where the level is 0 for debug, 1 for info, 2 for warn and 3 for error
+//!
+//! Every execution is bounded by [`FUEL_LIMIT`] and [`MEMORY_LIMIT_BYTES`]; growing memory past
+//! the limit traps instead of failing silently. Pointers handed back by the module are checked
+//! against its memory before anything is read, and at most [`LOG_LIMIT_BYTES`] of log lines are
+//! kept per execution. Traps, running out of fuel and malformed output
+//! are reported as failed [`FunctionResults`](FunctionResult), just like exceptions thrown by
+//! JavaScript funcs.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use async_trait::async_trait;
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+use veritech_client::{
+    BeforeFunction, FunctionResult, FunctionResultFailure, FunctionResultFailureError,
+    FunctionResultFailureErrorKind, OutputStream, ResolverFunctionResponseType,
+    ResolverFunctionResultSuccess,
+};
+use wasmtime::{Caller, Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::func::backend::{
+    js_attribute::handle_failure, FuncBackendResult, FuncDispatch, FuncDispatchContext,
+};
+
+/// Units of fuel a single execution may consume before it is aborted. Fuel roughly tracks the
+/// number of instructions executed.
+pub const FUEL_LIMIT: u64 = 2_000_000_000;
+/// The most linear memory a single execution may grow to.
+pub const MEMORY_LIMIT_BYTES: usize = 64 * 1024 * 1024;
+/// The most log output a single execution may write. Lines past the limit are dropped.
+pub const LOG_LIMIT_BYTES: usize = 1024 * 1024;
+/// Compiled modules are kept around so that a func is only compiled on its first execution. Once
+/// full, the least recently used module is evicted.
+const MODULE_CACHE_SIZE: usize = 256;
+
+const CODE_ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+static RUNTIME: OnceLock<WasmRuntime> = OnceLock::new();
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncBackendWasmArgs {
+    pub args: serde_json::Value,
+    pub response_type: ResolverFunctionResponseType,
+}
+
+#[derive(Debug)]
+pub struct FuncBackendWasm {
+    context: FuncDispatchContext,
+    code_base64: String,
+    handler: String,
+    args: FuncBackendWasmArgs,
+}
+
+#[async_trait]
+impl FuncDispatch for FuncBackendWasm {
+    type Args = FuncBackendWasmArgs;
+    type Output = ResolverFunctionResultSuccess;
+
+    fn new(
+        context: FuncDispatchContext,
+        code_base64: &str,
+        handler: &str,
+        args: Self::Args,
+        _before: Vec<BeforeFunction>,
+    ) -> Box<Self> {
+        Box::new(Self {
+            context,
+            code_base64: code_base64.into(),
+            handler: handler.into(),
+            args,
+        })
+    }
+
+    async fn dispatch(self: Box<Self>) -> FuncBackendResult<FunctionResult<Self::Output>> {
+        let execution_id = self.context.func_run_id.to_string();
+        let response_type = self.args.response_type.clone();
+        let output_tx = self.context.output_tx.clone();
+
+        let input = serde_json::to_vec(&self.args)?;
+        let (code_base64, handler) = (self.code_base64, self.handler);
+        let (result, output) =
+            tokio::task::spawn_blocking(move || execute(&code_base64, &handler, &input)).await?;
+
+        for (level, message) in output {
+            let output_stream = OutputStream {
+                stream: "output".to_owned(),
+                execution_id: execution_id.clone(),
+                level: level.to_owned(),
+                group: None,
+                message,
+                timestamp: timestamp(),
+            };
+            if output_tx.send(output_stream).await.is_err() {
+                debug!("func run log receiver dropped before wasm output was sent");
+                break;
+            }
+        }
+
+        let value = match result {
+            Ok(WasmOutput::Data(data)) => FunctionResult::Success(ResolverFunctionResultSuccess {
+                execution_id,
+                unset: data.is_null(),
+                data,
+                timestamp: timestamp(),
+            }),
+            Ok(WasmOutput::Error(message)) => FunctionResult::Failure(FunctionResultFailure::new(
+                execution_id,
+                FunctionResultFailureError {
+                    kind: FunctionResultFailureErrorKind::UserCodeException("WasmError".into()),
+                    message,
+                },
+                timestamp(),
+            )),
+            Err(err) => FunctionResult::Failure(FunctionResultFailure::new(
+                execution_id,
+                FunctionResultFailureError {
+                    kind: FunctionResultFailureErrorKind::UserCodeException("WasmTrap".into()),
+                    message: format!("{err:#}"),
+                },
+                timestamp(),
+            )),
+        };
+
+        Ok(handle_failure(&response_type, value))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum WasmOutput {
+    Data(serde_json::Value),
+    Error(String),
+}
+
+struct WasmState {
+    limits: StoreLimits,
+    output: Vec<(&'static str, String)>,
+    log_bytes: usize,
+}
+
+struct WasmRuntime {
+    engine: Engine,
+    modules: Mutex<ModuleCache>,
+}
+
+impl WasmRuntime {
+    fn get() -> wasmtime::Result<&'static Self> {
+        if let Some(runtime) = RUNTIME.get() {
+            return Ok(runtime);
+        }
+
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let runtime = Self {
+            engine: Engine::new(&config)?,
+            modules: Mutex::new(ModuleCache::new(MODULE_CACHE_SIZE)),
+        };
+
+        Ok(RUNTIME.get_or_init(|| runtime))
+    }
+
+    fn module(&self, code_base64: &str) -> wasmtime::Result<Module> {
+        let hash = blake3::hash(code_base64.as_bytes());
+        if let Some(module) = self.lock_modules().get(&hash) {
+            return Ok(module);
+        }
+
+        let bytes = CODE_ENGINE.decode(code_base64)?;
+        let module = Module::new(&self.engine, bytes)?;
+        self.lock_modules().insert(hash, module.clone());
+
+        Ok(module)
+    }
+
+    fn lock_modules(&self) -> std::sync::MutexGuard<'_, ModuleCache> {
+        // A panic while holding the lock cannot leave the cache inconsistent, only incomplete.
+        self.modules
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A bounded cache of compiled modules, keyed by the hash of their code, that evicts the least
+/// recently used module when it is full.
+struct ModuleCache {
+    capacity: usize,
+    clock: u64,
+    entries: HashMap<blake3::Hash, (Module, u64)>,
+}
+
+impl ModuleCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            clock: 0,
+            entries: HashMap::with_capacity(capacity),
+        }
+    }
+
+    fn get(&mut self, hash: &blake3::Hash) -> Option<Module> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(hash).map(|(module, last_used)| {
+            *last_used = clock;
+            module.clone()
+        })
+    }
+
+    fn insert(&mut self, hash: blake3::Hash, module: Module) {
+        self.clock += 1;
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&hash) {
+            let least_recently_used = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(hash, _)| *hash);
+            if let Some(least_recently_used) = least_recently_used {
+                self.entries.remove(&least_recently_used);
+            }
+        }
+        self.entries.insert(hash, (module, self.clock));
+    }
+}
+
+/// Runs the handler, returning its output along with every line it logged, including the ones
+/// logged before a trap.
+fn execute(
+    code_base64: &str,
+    handler: &str,
+    input: &[u8],
+) -> (wasmtime::Result<WasmOutput>, Vec<(&'static str, String)>) {
+    let runtime = match WasmRuntime::get() {
+        Ok(runtime) => runtime,
+        Err(err) => return (Err(err), Vec::new()),
+    };
+
+    let mut store = Store::new(
+        &runtime.engine,
+        WasmState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(MEMORY_LIMIT_BYTES)
+                .instances(1)
+                .trap_on_grow_failure(true)
+                .build(),
+            output: Vec::new(),
+            log_bytes: 0,
+        },
+    );
+    store.limiter(|state| &mut state.limits);
+
+    let result = instantiate_and_call(runtime, &mut store, code_base64, handler, input);
+    let output = std::mem::take(&mut store.data_mut().output);
+
+    (result, output)
+}
+
+fn instantiate_and_call(
+    runtime: &WasmRuntime,
+    store: &mut Store<WasmState>,
+    code_base64: &str,
+    handler: &str,
+    input: &[u8],
+) -> wasmtime::Result<WasmOutput> {
+    store.set_fuel(FUEL_LIMIT)?;
+
+    let module = runtime.module(code_base64)?;
+
+    let mut linker = Linker::new(&runtime.engine);
+    linker.func_wrap(
+        "si",
+        "log",
+        |mut caller: Caller<'_, WasmState>, level: i32, ptr: i32, len: i32| {
+            let memory = caller
+                .get_export("memory")
+                .and_then(|export| export.into_memory())
+                .ok_or_else(|| wasmtime::Error::msg("module does not export its memory"))?;
+            let (data, state) = memory.data_and_store_mut(&mut caller);
+            let bytes = guest_bytes(data, ptr as u32 as usize, len as u32 as usize)?;
+
+            if state.log_bytes > LOG_LIMIT_BYTES {
+                return Ok(());
+            }
+            state.log_bytes += bytes.len();
+            if state.log_bytes > LOG_LIMIT_BYTES {
+                state.output.push((
+                    "warn",
+                    format!("log output exceeded {LOG_LIMIT_BYTES} bytes, dropping further lines"),
+                ));
+                return Ok(());
+            }
+
+            let level = match level {
+                0 => "debug",
+                1 => "info",
+                2 => "warn",
+                _ => "error",
+            };
+            state
+                .output
+                .push((level, String::from_utf8_lossy(bytes).into_owned()));
+
+            wasmtime::Result::<()>::Ok(())
+        },
+    )?;
+
+    let instance = linker.instantiate(&mut *store, &module)?;
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or_else(|| wasmtime::Error::msg("module does not export its memory"))?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut *store, "alloc")?;
+    let handler = instance.get_typed_func::<(i32, i32), i64>(&mut *store, handler)?;
+
+    let input_len = i32::try_from(input.len())?;
+    let input_ptr = alloc.call(&mut *store, input_len)?;
+    memory.write(&mut *store, input_ptr as u32 as usize, input)?;
+
+    let packed = handler.call(&mut *store, (input_ptr, input_len))?;
+    let output_ptr = (packed as u64 >> 32) as usize;
+    let output_len = (packed as u64 & u64::from(u32::MAX)) as usize;
+    let output = guest_bytes(memory.data(&*store), output_ptr, output_len)?;
+
+    Ok(serde_json::from_slice(output)?)
+}
+
+/// Borrows `len` bytes at `ptr` out of the module's memory, failing when the range reaches past
+/// its end rather than trusting the module.
+fn guest_bytes(data: &[u8], ptr: usize, len: usize) -> wasmtime::Result<&[u8]> {
+    ptr.checked_add(len)
+        .and_then(|end| data.get(ptr..end))
+        .ok_or_else(|| {
+            wasmtime::Error::msg(format!(
+                "range of {len} bytes at {ptr} is outside of the module's {} byte memory",
+                data.len()
+            ))
+        })
+}
+
+fn timestamp() -> u64 {
+    u64::try_from(std::cmp::max(Utc::now().timestamp(), 0)).expect("timestamp not be negative")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wasmtime::Trap;
+
+    use super::*;
+
+    const INPUT: &[u8] = br#"{"args":{},"responseType":"object"}"#;
+
+    /// Builds a module whose `handler` runs `body` and then returns `output`, encoded the way a
+    /// func stores its code.
+    fn fixture(output: &str, body: &str) -> String {
+        let wat = format!(
+            r#"(module
+                (import "si" "log" (func $log (param i32 i32 i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 1024) "{data}")
+                (data (i32.const 2048) "hello from wasm")
+                (func (export "alloc") (param i32) (result i32)
+                    i32.const 4096)
+                (func (export "handler") (param i32 i32) (result i64)
+                    {body}
+                    i64.const 1024
+                    i64.const 32
+                    i64.shl
+                    i64.const {len}
+                    i64.or))"#,
+            data = output.replace('"', "\\\""),
+            len = output.len(),
+        );
+        CODE_ENGINE.encode(wat::parse_str(wat).expect("fixture should be valid wat"))
+    }
+
+    fn trap(result: wasmtime::Result<WasmOutput>) -> Trap {
+        *result
+            .expect_err("execution should fail")
+            .downcast_ref::<Trap>()
+            .expect("execution should trap")
+    }
+
+    #[test]
+    fn returns_data() {
+        let code = fixture(r#"{"data":{"greeting":"hi"}}"#, "");
+
+        let (result, output) = execute(&code, "handler", INPUT);
+
+        match result.expect("execution should succeed") {
+            WasmOutput::Data(data) => assert_eq!(json!({ "greeting": "hi" }), data),
+            other => panic!("expected data, got {other:?}"),
+        }
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn returns_error_document() {
+        let code = fixture(r#"{"error":"no good"}"#, "");
+
+        let (result, _) = execute(&code, "handler", INPUT);
+
+        match result.expect("execution should succeed") {
+            WasmOutput::Error(message) => assert_eq!("no good", message),
+            other => panic!("expected an error document, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn runs_out_of_fuel() {
+        let code = fixture(r#"{"data":null}"#, "(loop $spin br $spin)");
+
+        let (result, _) = execute(&code, "handler", INPUT);
+
+        assert_eq!(Trap::OutOfFuel, trap(result));
+    }
+
+    #[test]
+    fn traps_when_growing_past_memory_limit() {
+        let pages = MEMORY_LIMIT_BYTES / (64 * 1024);
+        let code = fixture(
+            r#"{"data":null}"#,
+            &format!("(drop (memory.grow (i32.const {pages})))"),
+        );
+
+        let (result, _) = execute(&code, "handler", INPUT);
+
+        let err = result.expect_err("growing past the memory limit should fail");
+        assert!(
+            format!("{err:#}").contains("forcing trap when growing memory"),
+            "unexpected error: {err:#}"
+        );
+    }
+
+    #[test]
+    fn captures_logs_before_a_trap() {
+        let code = fixture(
+            r#"{"data":null}"#,
+            "(call $log (i32.const 1) (i32.const 2048) (i32.const 15))
+             (call $log (i32.const 3) (i32.const 2048) (i32.const 5))
+             unreachable",
+        );
+
+        let (result, output) = execute(&code, "handler", INPUT);
+
+        assert_eq!(Trap::UnreachableCodeReached, trap(result));
+        assert_eq!(
+            vec![
+                ("info", "hello from wasm".to_owned()),
+                ("error", "hello".to_owned()),
+            ],
+            output
+        );
+    }
+
+    #[test]
+    fn rejects_output_outside_of_memory() {
+        let code = fixture(r#"{"data":null}"#, "(return (i64.const -1))");
+
+        let (result, _) = execute(&code, "handler", INPUT);
+
+        let err = result.expect_err("reading past the end of memory should fail");
+        assert!(
+            format!("{err:#}").contains("outside of the module's"),
+            "unexpected error: {err:#}"
+        );
+    }
+
+    #[test]
+    fn rejects_log_outside_of_memory() {
+        let code = fixture(
+            r#"{"data":null}"#,
+            "(call $log (i32.const 1) (i32.const 2048) (i32.const -1))",
+        );
+
+        let (result, output) = execute(&code, "handler", INPUT);
+
+        let err = result.expect_err("logging past the end of memory should fail");
+        assert!(
+            format!("{err:#}").contains("outside of the module's"),
+            "unexpected error: {err:#}"
+        );
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn drops_logs_past_the_limit() {
+        let code = fixture(
+            r#"{"data":null}"#,
+            "(local.set 0 (i32.const 100000))
+             (loop $more
+                 (call $log (i32.const 1) (i32.const 2048) (i32.const 15))
+                 (local.set 0 (i32.sub (local.get 0) (i32.const 1)))
+                 (br_if $more (local.get 0)))",
+        );
+
+        let (result, output) = execute(&code, "handler", INPUT);
+
+        assert!(result.is_ok(), "execution should succeed: {result:?}");
+        let (last, lines) = output.split_last().expect("output should not be empty");
+        assert_eq!("warn", last.0);
+        assert_eq!(LOG_LIMIT_BYTES / 15, lines.len());
+        assert!(lines.iter().map(|(_, line)| line.len()).sum::<usize>() <= LOG_LIMIT_BYTES);
+    }
+
+    #[test]
+    fn module_cache_evicts_least_recently_used() {
+        let engine = Engine::default();
+        let module = Module::new(&engine, wat::parse_str("(module)").expect("valid wat"))
+            .expect("module should compile");
+        let [first, second, third] = [b"first".as_slice(), b"second", b"third"].map(blake3::hash);
+
+        let mut cache = ModuleCache::new(2);
+        cache.insert(first, module.clone());
+        cache.insert(second, module.clone());
+        assert!(cache.get(&first).is_some());
+        cache.insert(third, module);
+
+        assert!(cache.entries.contains_key(&first));
+        assert!(!cache.entries.contains_key(&second));
+        assert!(cache.entries.contains_key(&third));
+    }
+}
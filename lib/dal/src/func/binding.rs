@@ -18,6 +18,8 @@ use crate::attribute::prototype::argument::{
 };
 use crate::attribute::prototype::AttributePrototypeError;
 use crate::attribute::value::AttributeValueError;
+use crate::change_set::policy::POLICY_FUNC_TYPES;
+use crate::component::tag::ComponentTagError;
 use crate::func::argument::FuncArgumentError;
 use crate::func::argument::FuncArgumentId;
 use crate::func::binding::attribute::AttributeBindingMalformedInput;
@@ -68,6 +70,8 @@ pub enum FuncBindingError {
     CannotSetIntrinsicForComponent(ComponentId),
     #[error("component error: {0}")]
     ComponentError(#[from] ComponentError),
+    #[error("component tag error: {0}")]
+    ComponentTag(#[from] ComponentTagError),
     #[error("func error: {0}")]
     Func(#[from] FuncError),
     #[error("func argument error: {0}")]
@@ -252,6 +256,9 @@ impl From<FuncBinding> for si_frontend_types::FuncBinding {
                 managed_schemas: mgmt
                     .managed_schemas
                     .map(|s| s.into_iter().map(Into::into).collect()),
+                tag_selectors: mgmt
+                    .tag_selectors
+                    .map(|selectors| selectors.iter().map(ToString::to_string).collect()),
             },
             FuncBinding::CodeGeneration(code_gen) => {
                 si_frontend_types::FuncBinding::CodeGeneration {
@@ -415,7 +422,7 @@ impl FuncBinding {
             FuncKind::Intrinsic => {
                 AttributeBinding::assemble_intrinsic_bindings(ctx, func_id).await?
             }
-            FuncKind::SchemaVariantDefinition | FuncKind::Unknown => vec![],
+            FuncKind::Policy | FuncKind::SchemaVariantDefinition | FuncKind::Unknown => vec![],
             FuncKind::Management => {
                 ManagementBinding::assemble_management_bindings(ctx, func_id).await?
             }
@@ -605,6 +612,7 @@ impl FuncBinding {
             FuncKind::Management => {
                 ManagementBinding::compile_management_types(ctx, func_id).await?
             }
+            FuncKind::Policy => POLICY_FUNC_TYPES.to_owned(),
             FuncKind::Authentication
             | FuncKind::Intrinsic
             | FuncKind::SchemaVariantDefinition
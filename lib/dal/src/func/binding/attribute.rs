@@ -902,6 +902,24 @@ async fn validate_intrinsic_inputs(
                 return Err(FuncBindingError::InvalidIntrinsicBinding);
             }
         }
+        IntrinsicFunc::Base64Decode
+        | IntrinsicFunc::Base64Encode
+        | IntrinsicFunc::BuildArray
+        | IntrinsicFunc::BuildMap
+        | IntrinsicFunc::Concat
+        | IntrinsicFunc::DefaultIfEmpty
+        | IntrinsicFunc::JsonPointer
+        | IntrinsicFunc::Lowercase
+        | IntrinsicFunc::RegexExtract
+        | IntrinsicFunc::Template
+        | IntrinsicFunc::Uppercase => {
+            // transforms can take any inputs, but input sockets can't take input this way
+            if let AttributeFuncDestination::InputSocket(_) = output_location {
+                return Err(FuncBindingError::InvalidAttributePrototypeDestination(
+                    output_location,
+                ));
+            }
+        }
         IntrinsicFunc::Validation => return Err(FuncBindingError::InvalidIntrinsicBinding),
     };
     Ok(())
@@ -1,9 +1,10 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
 
 use crate::{
+    component::tag::TagSelector,
     management::prototype::{ManagementPrototype, ManagementPrototypeId},
     DalContext, Func, FuncId, Prop, Schema, SchemaId, SchemaVariant, SchemaVariantId,
 };
@@ -17,6 +18,7 @@ pub struct ManagementBinding {
     pub management_prototype_id: ManagementPrototypeId,
     pub func_id: FuncId,
     pub managed_schemas: Option<Vec<SchemaId>>,
+    pub tag_selectors: Option<Vec<TagSelector>>,
 }
 
 impl ManagementBinding {
@@ -96,6 +98,7 @@ impl ManagementBinding {
                                     kind: "{name}",
                                     properties?: {sv_type},
                                     geometry?: {{ [key: string]: Geometry }},
+                                    tags?: {{ [key: string]: string }},
                                     connect?: {{
                                         from: string,
                                         to: {{
@@ -181,8 +184,15 @@ type Input = {{
     thisComponent: {{
         properties: {this_component_iface},
         geometry: {{ [key: string]: Geometry }},
+        tags: {{ [key: string]: string }},
     }},
-    components: {{ [key: string]: {component_input_type} }}
+    components: {{ [key: string]: {component_input_type} }},
+    selectedComponents: {{ [key: string]: {{
+        kind: string,
+        properties?: {{ [key: string]: unknown }},
+        geometry?: {{ [key: string]: Geometry }},
+        tags?: {{ [key: string]: string }},
+    }} }}
 }};"#
         ))
     }
@@ -205,6 +215,7 @@ type Input = {{
             let managed_schemas = prototype
                 .managed_schemas()
                 .map(|schemas| schemas.iter().map(ToOwned::to_owned).collect());
+            let tag_selectors = prototype.tag_selectors().map(ToOwned::to_owned);
             let schema_variant_id =
                 ManagementPrototype::get_schema_variant_id(ctx, management_prototype_id).await;
             match schema_variant_id {
@@ -214,6 +225,7 @@ type Input = {{
                         func_id,
                         management_prototype_id,
                         managed_schemas,
+                        tag_selectors,
                     }));
                 }
                 Err(err) => {
@@ -232,14 +244,32 @@ type Input = {{
     ) -> FuncBindingResult<Vec<FuncBinding>> {
         let schema_variant_id = self.schema_variant_id;
 
-        let managed_schemas = ManagementPrototype::get_by_id(ctx, self.management_prototype_id)
-            .await?
+        let prototype = ManagementPrototype::get_by_id(ctx, self.management_prototype_id).await?;
+        let managed_schemas = prototype
+            .as_ref()
             .and_then(|prototype| prototype.managed_schemas().map(ToOwned::to_owned));
+        let tag_selectors = prototype
+            .as_ref()
+            .and_then(|prototype| prototype.tag_selectors().map(ToOwned::to_owned));
 
         ManagementPrototype::remove(ctx, self.management_prototype_id).await?;
 
         Self::create_management_binding(ctx, new_func_id, schema_variant_id, managed_schemas)
             .await?;
+        if let Some(tag_selectors) = tag_selectors {
+            for prototype_id in ManagementPrototype::list_ids_for_func_id(ctx, new_func_id).await? {
+                if ManagementPrototype::get_schema_variant_id(ctx, prototype_id).await?
+                    != schema_variant_id
+                {
+                    continue;
+                }
+                if let Some(prototype) = ManagementPrototype::get_by_id(ctx, prototype_id).await? {
+                    prototype
+                        .set_tag_selectors(ctx, Some(tag_selectors.clone()))
+                        .await?;
+                }
+            }
+        }
 
         FuncBinding::for_func_id(ctx, new_func_id).await
     }
@@ -267,6 +297,7 @@ type Input = {{
         ctx: &DalContext,
         management_prototype_id: ManagementPrototypeId,
         managed_schemas: Option<Vec<SchemaId>>,
+        tag_selectors: Option<Vec<String>>,
     ) -> FuncBindingResult<Vec<FuncBinding>> {
         let func_id = ManagementPrototype::func_id(ctx, management_prototype_id).await?;
         let Some(management_prototype) =
@@ -281,6 +312,15 @@ type Input = {{
         eventual_parent.error_if_locked(ctx).await?;
         let manager_schema_id =
             SchemaVariant::schema_id_for_schema_variant_id(ctx, schema_variant_id).await?;
+        let tag_selectors = match tag_selectors {
+            Some(selectors) => Some(
+                selectors
+                    .iter()
+                    .map(|selector| TagSelector::from_str(selector))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            None => None,
+        };
 
         management_prototype
             .modify(ctx, |proto| {
@@ -290,6 +330,7 @@ type Input = {{
                         .filter(|schema_id| schema_id != &manager_schema_id)
                         .collect()
                 });
+                proto.tag_selectors = tag_selectors.filter(|selectors| !selectors.is_empty());
                 Ok(())
             })
             .await?;
@@ -11,7 +11,16 @@ use crate::PropKind;
 #[remain::sorted]
 #[derive(AsRefStr, Display, EnumIter, EnumString, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IntrinsicFunc {
+    Base64Decode,
+    Base64Encode,
+    BuildArray,
+    BuildMap,
+    Concat,
+    DefaultIfEmpty,
     Identity,
+    JsonPointer,
+    Lowercase,
+    RegexExtract,
     SetArray,
     SetBoolean,
     SetInteger,
@@ -19,7 +28,9 @@ pub enum IntrinsicFunc {
     SetMap,
     SetObject,
     SetString,
+    Template,
     Unset,
+    Uppercase,
     Validation,
 }
 
@@ -54,6 +65,125 @@ impl IntrinsicFunc {
         // These magic unique ids are here to keep them consistent with the intrinsic ids in the
         // existing builtin packages (chicken/egg problem here a bit)
         match self {
+            Self::Base64Decode => {
+                builder
+                    .unique_id("9dfa86c62b5aa2617c5cdc80242713b2fc8b21d729fdef17b5d63aaf88fa4bfd");
+                data_builder.backend_kind(FuncSpecBackendKind::Base64Decode);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(transform_argument("value", FuncArgumentKind::String, None)?);
+            }
+            Self::Base64Encode => {
+                builder
+                    .unique_id("347904a627b99d5adbb003f517d97df4498218fe81ece5b1cbef1110db24efb7");
+                data_builder.backend_kind(FuncSpecBackendKind::Base64Encode);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(transform_argument("value", FuncArgumentKind::String, None)?);
+            }
+            Self::BuildArray => {
+                builder
+                    .unique_id("446115210b27206a95236ce5066664ae582f0f37b9c943f16baa99a1f55e22d6");
+                data_builder.backend_kind(FuncSpecBackendKind::BuildArray);
+                data_builder.response_type(FuncSpecBackendResponseType::Array);
+                builder.argument(transform_argument(
+                    "values",
+                    FuncArgumentKind::Array,
+                    Some(FuncArgumentKind::Any),
+                )?);
+            }
+            Self::BuildMap => {
+                builder
+                    .unique_id("4b9cd141b4fda5016c70b60a39cc9c1e95a328df47d180459aacb2faeb8bed30");
+                data_builder.backend_kind(FuncSpecBackendKind::BuildMap);
+                data_builder.response_type(FuncSpecBackendResponseType::Map);
+                builder.argument(transform_argument(
+                    "keys",
+                    FuncArgumentKind::Array,
+                    Some(FuncArgumentKind::String),
+                )?);
+                builder.argument(transform_argument(
+                    "values",
+                    FuncArgumentKind::Array,
+                    Some(FuncArgumentKind::Any),
+                )?);
+            }
+            Self::Concat => {
+                builder
+                    .unique_id("80fe6dab226fbe174f7d4305c5585b515508f3502bceb53d1ede10ce0d852d63");
+                data_builder.backend_kind(FuncSpecBackendKind::Concat);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(transform_argument(
+                    "values",
+                    FuncArgumentKind::Array,
+                    Some(FuncArgumentKind::Any),
+                )?);
+                builder.argument(transform_argument(
+                    "separator",
+                    FuncArgumentKind::String,
+                    None,
+                )?);
+            }
+            Self::DefaultIfEmpty => {
+                builder
+                    .unique_id("d4944c780e46334b080944f7f31ef4bde820314cb6ce255191ba37f3cd1ed6e6");
+                data_builder.backend_kind(FuncSpecBackendKind::DefaultIfEmpty);
+                data_builder.response_type(FuncSpecBackendResponseType::Identity);
+                builder.argument(transform_argument("value", FuncArgumentKind::Any, None)?);
+                builder.argument(transform_argument("default", FuncArgumentKind::Any, None)?);
+            }
+            Self::JsonPointer => {
+                builder
+                    .unique_id("277f27fc870cc2c7cf76fc2781c975afe2cb8322fb1d1b3c36dd822a8e463f04");
+                data_builder.backend_kind(FuncSpecBackendKind::JsonPointer);
+                data_builder.response_type(FuncSpecBackendResponseType::Identity);
+                builder.argument(transform_argument("value", FuncArgumentKind::Any, None)?);
+                builder.argument(transform_argument(
+                    "pointer",
+                    FuncArgumentKind::String,
+                    None,
+                )?);
+            }
+            Self::Lowercase => {
+                builder
+                    .unique_id("eed978c18bb99b914279dfe811e8c32ed4c05b82a7779b865be46d1133e805c0");
+                data_builder.backend_kind(FuncSpecBackendKind::Lowercase);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(transform_argument("value", FuncArgumentKind::String, None)?);
+            }
+            Self::RegexExtract => {
+                builder
+                    .unique_id("523ef70507c80092d2d9a83c115ae90b838b37326eb35c071d2c805d3bae7f16");
+                data_builder.backend_kind(FuncSpecBackendKind::RegexExtract);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(transform_argument("value", FuncArgumentKind::String, None)?);
+                builder.argument(transform_argument(
+                    "pattern",
+                    FuncArgumentKind::String,
+                    None,
+                )?);
+            }
+            Self::Template => {
+                builder
+                    .unique_id("322abd0d59d59169069690b50d05bd46fdd00906271cdb69314a99e284540963");
+                data_builder.backend_kind(FuncSpecBackendKind::Template);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(transform_argument(
+                    "template",
+                    FuncArgumentKind::String,
+                    None,
+                )?);
+                builder.argument(transform_argument(
+                    "values",
+                    FuncArgumentKind::Map,
+                    Some(FuncArgumentKind::Any),
+                )?);
+            }
+            Self::Uppercase => {
+                builder
+                    .unique_id("b9748bcc2c3ac6ba408c52e60288f8298624692c883060740d0415309495baec");
+                data_builder.backend_kind(FuncSpecBackendKind::Uppercase);
+                data_builder.response_type(FuncSpecBackendResponseType::String);
+                builder.argument(transform_argument("value", FuncArgumentKind::String, None)?);
+            }
             Self::Identity => {
                 builder
                     .unique_id("c6938e12287ab65f8ba8234559178413f2e2c02c44ea08384ed6687a36ec4f50");
@@ -193,6 +323,17 @@ impl IntrinsicFunc {
 
     pub fn name(&self) -> &str {
         match self {
+            Self::Base64Decode => "si:base64Decode",
+            Self::Base64Encode => "si:base64Encode",
+            Self::BuildArray => "si:buildArray",
+            Self::BuildMap => "si:buildMap",
+            Self::Concat => "si:concat",
+            Self::DefaultIfEmpty => "si:defaultIfEmpty",
+            Self::JsonPointer => "si:jsonPointer",
+            Self::Lowercase => "si:lowercase",
+            Self::RegexExtract => "si:regexExtract",
+            Self::Template => "si:template",
+            Self::Uppercase => "si:uppercase",
             Self::Identity => "si:identity",
             Self::SetArray => "si:setArray",
             Self::SetBoolean => "si:setBoolean",
@@ -206,8 +347,48 @@ impl IntrinsicFunc {
         }
     }
 
+    /// Whether this is one of the pure Rust transforms, which take their inputs from attribute
+    /// bindings like [`Identity`](Self::Identity) does. Workspaces created before the transforms
+    /// existed may not have them.
+    pub fn is_transform(&self) -> bool {
+        match self {
+            Self::Base64Decode
+            | Self::Base64Encode
+            | Self::BuildArray
+            | Self::BuildMap
+            | Self::Concat
+            | Self::DefaultIfEmpty
+            | Self::JsonPointer
+            | Self::Lowercase
+            | Self::RegexExtract
+            | Self::Template
+            | Self::Uppercase => true,
+            Self::Identity
+            | Self::SetArray
+            | Self::SetBoolean
+            | Self::SetInteger
+            | Self::SetJson
+            | Self::SetMap
+            | Self::SetObject
+            | Self::SetString
+            | Self::Unset
+            | Self::Validation => false,
+        }
+    }
+
     pub fn maybe_from_str(s: impl AsRef<str>) -> Option<Self> {
         Some(match s.as_ref() {
+            "si:base64Decode" => Self::Base64Decode,
+            "si:base64Encode" => Self::Base64Encode,
+            "si:buildArray" => Self::BuildArray,
+            "si:buildMap" => Self::BuildMap,
+            "si:concat" => Self::Concat,
+            "si:defaultIfEmpty" => Self::DefaultIfEmpty,
+            "si:jsonPointer" => Self::JsonPointer,
+            "si:lowercase" => Self::Lowercase,
+            "si:regexExtract" => Self::RegexExtract,
+            "si:template" => Self::Template,
+            "si:uppercase" => Self::Uppercase,
             "si:identity" => Self::Identity,
             "si:setArray" => Self::SetArray,
             "si:setBoolean" => Self::SetBoolean,
@@ -225,6 +406,19 @@ impl IntrinsicFunc {
     }
 }
 
+fn transform_argument(
+    name: &str,
+    kind: FuncArgumentKind,
+    element_kind: Option<FuncArgumentKind>,
+) -> FuncResult<FuncArgumentSpec> {
+    FuncArgumentSpec::builder()
+        .name(name)
+        .kind(kind)
+        .element_kind(element_kind)
+        .build()
+        .map_err(|e| FuncError::IntrinsicSpecCreation(e.to_string()))
+}
+
 impl From<PropKind> for IntrinsicFunc {
     fn from(value: PropKind) -> Self {
         match value {
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use si_events::FuncKind as EventFuncKind;
-use strum::{AsRefStr, Display};
+use strum::{AsRefStr, Display, EnumString};
 use telemetry::prelude::warn;
 
 use crate::func::FuncResult;
@@ -9,7 +9,9 @@ use crate::{FuncBackendKind, FuncBackendResponseType};
 /// Describes the kind of [`Func`](crate::Func).
 /// This type is postcard serialized, so cannot be "remain::sorted". New enum
 /// variants must go at the end
-#[derive(AsRefStr, Deserialize, Display, Serialize, Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[derive(
+    AsRefStr, Deserialize, Display, EnumString, Serialize, Debug, Eq, PartialEq, Clone, Copy, Hash,
+)]
 pub enum FuncKind {
     Action,
     Attribute,
@@ -20,6 +22,7 @@ pub enum FuncKind {
     SchemaVariantDefinition,
     Unknown,
     Management,
+    Policy,
 }
 
 impl From<EventFuncKind> for FuncKind {
@@ -34,6 +37,7 @@ impl From<EventFuncKind> for FuncKind {
             EventFuncKind::SchemaVariantDefinition => FuncKind::SchemaVariantDefinition,
             EventFuncKind::Unknown => FuncKind::Unknown,
             EventFuncKind::Management => FuncKind::Management,
+            EventFuncKind::Policy => FuncKind::Policy,
         }
     }
 }
@@ -50,6 +54,7 @@ impl From<FuncKind> for si_events::FuncKind {
             FuncKind::SchemaVariantDefinition => si_events::FuncKind::SchemaVariantDefinition,
             FuncKind::Unknown => si_events::FuncKind::Unknown,
             FuncKind::Management => si_events::FuncKind::Management,
+            FuncKind::Policy => si_events::FuncKind::Policy,
         }
     }
 }
@@ -60,16 +65,30 @@ impl FuncKind {
         func_backend_response_type: FuncBackendResponseType,
     ) -> FuncResult<FuncKind> {
         Ok(match func_backend_kind {
-            FuncBackendKind::JsAttribute => match func_backend_response_type {
-                FuncBackendResponseType::CodeGeneration => FuncKind::CodeGeneration,
-                FuncBackendResponseType::Qualification => FuncKind::Qualification,
-                _ => FuncKind::Attribute,
-            },
+            FuncBackendKind::JsAttribute | FuncBackendKind::Wasm => {
+                match func_backend_response_type {
+                    FuncBackendResponseType::CodeGeneration => FuncKind::CodeGeneration,
+                    FuncBackendResponseType::Qualification => FuncKind::Qualification,
+                    _ => FuncKind::Attribute,
+                }
+            }
             FuncBackendKind::JsAction => FuncKind::Action,
             FuncBackendKind::JsAuthentication => FuncKind::Authentication,
             FuncBackendKind::JsSchemaVariantDefinition => FuncKind::SchemaVariantDefinition,
             FuncBackendKind::Management => FuncKind::Management,
+            FuncBackendKind::JsPolicy => FuncKind::Policy,
             FuncBackendKind::Array
+            | FuncBackendKind::Base64Decode
+            | FuncBackendKind::Base64Encode
+            | FuncBackendKind::BuildArray
+            | FuncBackendKind::BuildMap
+            | FuncBackendKind::Concat
+            | FuncBackendKind::DefaultIfEmpty
+            | FuncBackendKind::JsonPointer
+            | FuncBackendKind::Lowercase
+            | FuncBackendKind::RegexExtract
+            | FuncBackendKind::Template
+            | FuncBackendKind::Uppercase
             | FuncBackendKind::Json
             | FuncBackendKind::Boolean
             | FuncBackendKind::Diff
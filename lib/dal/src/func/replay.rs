@@ -0,0 +1,332 @@
+//! Reconstructing the request behind a recorded [`FuncRun`] so that it can be executed again
+//! against a local cyclone.
+//!
+//! A [`FuncRun`] records where its arguments and code were written to the CAS, along with its
+//! backend kind. [`FuncRunReplay::load`] reads those back and assembles the request that veritech
+//! would have forwarded to cyclone. The recorded code and arguments are replayed as they were.
+//!
+//! A [`FuncRun`] does not record its handler or the before functions that ran ahead of it, so
+//! both are taken from the current state of the func run's change set: the handler from the
+//! [`Func`] of the same name, and the before functions from the component's current secrets.
+//! [`FuncRunReplay::handler`] reports which handler was used.
+//!
+//! Secrets are never decrypted. Each before function gets the value supplied for its secret by
+//! name, or [`REDACTED_SECRET_PLACEHOLDER`] when none was supplied.
+
+use std::{collections::HashMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use si_crypto::SensitiveStrings;
+use si_events::{CasValue, FuncRun, FuncRunId, OutputLine};
+use si_layer_cache::LayerDbError;
+use telemetry::prelude::*;
+use thiserror::Error;
+use veritech_client::{
+    ActionRunRequest, BeforeFunction, ManagementRequest, ResolverFunctionComponent,
+    ResolverFunctionRequest, ResolverFunctionResponseType, SchemaVariantDefinitionRequest,
+    ValidationRequest,
+};
+
+use crate::func::backend::{
+    management::FuncBackendManagementArgs, validation::FuncBackendJsAttributeArgs, FuncBackendKind,
+    FuncBackendResponseType, InvalidResolverFunctionTypeError,
+};
+use crate::func::runner::{FuncRunner, FuncRunnerError};
+use crate::{ComponentId, DalContext, Func, FuncError, FuncId, Secret, SecretError};
+
+/// The argument given to a before function whose secret value was not supplied.
+pub const REDACTED_SECRET_PLACEHOLDER: &str = "[redacted]";
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum FuncRunReplayError {
+    #[error("func error: {0}")]
+    Func(#[from] Box<FuncError>),
+    #[error("func {0} has no code to replay")]
+    FuncMissingCode(FuncId),
+    #[error("func {0} has no handler")]
+    FuncMissingHandler(FuncId),
+    #[error("no func named {0} in the func run's change set")]
+    FuncNotFound(String),
+    #[error("func runner error: {0}")]
+    FuncRunner(#[from] Box<FuncRunnerError>),
+    #[error("func run not found: {0}")]
+    FuncRunNotFound(FuncRunId),
+    #[error("invalid resolver function type: {0}")]
+    InvalidResolverFunctionType(#[from] InvalidResolverFunctionTypeError),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("secret error: {0}")]
+    Secret(#[from] Box<SecretError>),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("func runs with the {0} backend do not execute in cyclone")]
+    UnsupportedBackend(FuncBackendKind),
+}
+
+pub type FuncRunReplayResult<T> = Result<T, FuncRunReplayError>;
+
+/// The request that a recorded [`FuncRun`] sent to cyclone.
+#[remain::sorted]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "request", rename_all = "camelCase")]
+pub enum FuncRunReplayRequest {
+    Action(ActionRunRequest),
+    Management(ManagementRequest),
+    Resolver(ResolverFunctionRequest),
+    SchemaVariantDefinition(SchemaVariantDefinitionRequest),
+    Validation(ValidationRequest),
+}
+
+/// A recorded [`FuncRun`] alongside everything needed to run it again and compare the outcome.
+#[derive(Debug, Clone)]
+pub struct FuncRunReplay {
+    pub func_run: FuncRun,
+    pub request: FuncRunReplayRequest,
+    /// The handler the replay invoked. Func runs don't record their handler, so this is the
+    /// [`Func`]'s current handler rather than the one the original run invoked.
+    pub handler: String,
+    /// Strings from the supplied secret values, to be redacted from the replay's output.
+    pub sensitive_strings: SensitiveStrings,
+    /// Secrets that were replaced with [`REDACTED_SECRET_PLACEHOLDER`].
+    pub redacted_secrets: Vec<String>,
+    /// Whether the recorded code was missing from the CAS, so the [`Func`]'s current code is
+    /// replayed instead.
+    pub code_from_current_func: bool,
+    pub original_logs: Vec<OutputLine>,
+    pub original_result: Option<serde_json::Value>,
+}
+
+impl FuncRunReplay {
+    /// Loads the [`FuncRun`] and reconstructs its request. The [`DalContext`] must be in the func
+    /// run's workspace and change set.
+    ///
+    /// `secrets` maps a [`Secret`]'s name to the value its before functions receive.
+    #[instrument(name = "func.replay.load", level = "info", skip(ctx, secrets))]
+    pub async fn load(
+        ctx: &DalContext,
+        func_run_id: FuncRunId,
+        secrets: &HashMap<String, serde_json::Value>,
+    ) -> FuncRunReplayResult<Self> {
+        let func_run = ctx
+            .layer_db()
+            .func_run()
+            .read(func_run_id)
+            .await?
+            .ok_or(FuncRunReplayError::FuncRunNotFound(func_run_id))?;
+        let func_run = Arc::unwrap_or_clone(func_run);
+
+        let backend_kind: FuncBackendKind = func_run.backend_kind().into();
+        match backend_kind {
+            FuncBackendKind::JsAction
+            | FuncBackendKind::JsAttribute
+            | FuncBackendKind::JsPolicy
+            | FuncBackendKind::JsSchemaVariantDefinition
+            | FuncBackendKind::Management
+            | FuncBackendKind::Validation => {}
+            other => return Err(FuncRunReplayError::UnsupportedBackend(other)),
+        }
+
+        let func_id = Func::find_id_by_name(ctx, func_run.function_name())
+            .await
+            .map_err(Box::new)?
+            .ok_or_else(|| FuncRunReplayError::FuncNotFound(func_run.function_name().into()))?;
+        let func = Func::get_by_id_or_error(ctx, func_id)
+            .await
+            .map_err(Box::new)?;
+
+        let args: serde_json::Value = read_cas(ctx, &func_run.function_args_cas_address())
+            .await?
+            .unwrap_or(serde_json::Value::Null);
+        let (code_base64, code_from_current_func) =
+            match read_cas(ctx, &func_run.function_code_cas_address()).await? {
+                Some(serde_json::Value::String(code_base64)) => (code_base64, false),
+                _ => (
+                    func.code_base64
+                        .clone()
+                        .ok_or(FuncRunReplayError::FuncMissingCode(func.id))?,
+                    true,
+                ),
+            };
+        let handler = func
+            .handler
+            .clone()
+            .ok_or(FuncRunReplayError::FuncMissingHandler(func.id))?;
+
+        let mut sensitive_strings = SensitiveStrings::default();
+        let mut redacted_secrets = Vec::new();
+        let before = match func_run.component_id() {
+            Some(component_id) => {
+                before_funcs(
+                    ctx,
+                    component_id,
+                    secrets,
+                    &mut sensitive_strings,
+                    &mut redacted_secrets,
+                )
+                .await?
+            }
+            None => Vec::new(),
+        };
+
+        let execution_id = func_run.id().to_string();
+        let request = match backend_kind {
+            FuncBackendKind::JsAction => FuncRunReplayRequest::Action(ActionRunRequest {
+                execution_id,
+                handler: handler.clone(),
+                code_base64,
+                args,
+                before,
+            }),
+            FuncBackendKind::Management => {
+                let args: FuncBackendManagementArgs = serde_json::from_value(args)?;
+                FuncRunReplayRequest::Management(ManagementRequest {
+                    execution_id,
+                    handler: handler.clone(),
+                    code_base64,
+                    this_component: args.this_component,
+                    components: args.components,
+                    current_view: args.current_view,
+                    before,
+                })
+            }
+            FuncBackendKind::JsSchemaVariantDefinition => {
+                FuncRunReplayRequest::SchemaVariantDefinition(SchemaVariantDefinitionRequest {
+                    execution_id,
+                    handler: handler.clone(),
+                    code_base64,
+                })
+            }
+            FuncBackendKind::Validation => {
+                let args: FuncBackendJsAttributeArgs = serde_json::from_value(args)?;
+                FuncRunReplayRequest::Validation(ValidationRequest {
+                    execution_id,
+                    value: args.value,
+                    validation_format: args.validation_format,
+                    handler: "".to_string(),
+                    code_base64: "".to_string(),
+                    before: vec![],
+                })
+            }
+            // Only the JS attribute and policy backends remain.
+            _ => {
+                let response_type = if backend_kind == FuncBackendKind::JsPolicy {
+                    ResolverFunctionResponseType::Json
+                } else {
+                    FuncBackendResponseType::from(func_run.backend_response_type()).try_into()?
+                };
+                FuncRunReplayRequest::Resolver(ResolverFunctionRequest {
+                    execution_id,
+                    handler: handler.clone(),
+                    component: ResolverFunctionComponent {
+                        data: veritech_client::ComponentView {
+                            properties: args,
+                            ..Default::default()
+                        },
+                        parents: Vec::new(),
+                    },
+                    response_type,
+                    code_base64,
+                    before,
+                })
+            }
+        };
+
+        let original_logs = ctx
+            .layer_db()
+            .func_run_log()
+            .get_for_func_run_id(func_run.id())
+            .await?
+            .map(|func_run_log| func_run_log.logs().to_vec())
+            .unwrap_or_default();
+        let original_result = match func_run.result_value_cas_address() {
+            Some(address) => read_cas(ctx, &address).await?,
+            None => None,
+        };
+
+        Ok(Self {
+            func_run,
+            request,
+            handler,
+            sensitive_strings,
+            redacted_secrets,
+            code_from_current_func,
+            original_logs,
+            original_result,
+        })
+    }
+}
+
+async fn read_cas(
+    ctx: &DalContext,
+    address: &si_events::ContentHash,
+) -> FuncRunReplayResult<Option<serde_json::Value>> {
+    let value: Option<CasValue> = ctx.layer_db().cas().try_read_as(address).await?;
+    Ok(value.map(Into::into))
+}
+
+/// Mirrors [`FuncRunner`]'s before functions without decrypting any secret.
+async fn before_funcs(
+    ctx: &DalContext,
+    component_id: ComponentId,
+    secrets: &HashMap<String, serde_json::Value>,
+    sensitive_strings: &mut SensitiveStrings,
+    redacted_secrets: &mut Vec<String>,
+) -> FuncRunReplayResult<Vec<BeforeFunction>> {
+    let secret_ids_by_key = Secret::list_ids_by_key(ctx).await.map_err(Box::new)?;
+
+    let mut before = Vec::new();
+    for (key, funcs) in FuncRunner::ordered_before_funcs_with_secret_keys(ctx, component_id)
+        .await
+        .map_err(Box::new)?
+    {
+        let name = match secret_ids_by_key.get(&key) {
+            Some(secret_id) => Secret::get_by_id_or_error(ctx, *secret_id)
+                .await
+                .map_err(Box::new)?
+                .name()
+                .to_owned(),
+            None => key.to_string(),
+        };
+
+        let arg = match secrets.get(&name) {
+            Some(value) => {
+                collect_sensitive_strings(value, sensitive_strings);
+                value.clone()
+            }
+            None => {
+                if !redacted_secrets.contains(&name) {
+                    redacted_secrets.push(name);
+                }
+                serde_json::Value::String(REDACTED_SECRET_PLACEHOLDER.to_owned())
+            }
+        };
+
+        for func in funcs {
+            before.push(BeforeFunction {
+                handler: func
+                    .handler
+                    .ok_or(FuncRunReplayError::FuncMissingHandler(func.id))?,
+                code_base64: func
+                    .code_base64
+                    .ok_or(FuncRunReplayError::FuncMissingCode(func.id))?,
+                arg: arg.clone(),
+            });
+        }
+    }
+
+    Ok(before)
+}
+
+fn collect_sensitive_strings(value: &serde_json::Value, sensitive_strings: &mut SensitiveStrings) {
+    match value {
+        serde_json::Value::String(s) => sensitive_strings.insert(s.as_str()),
+        serde_json::Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_sensitive_strings(value, sensitive_strings)),
+        serde_json::Value::Object(map) => map
+            .values()
+            .for_each(|value| collect_sensitive_strings(value, sensitive_strings)),
+        _ => {}
+    }
+}
@@ -0,0 +1,310 @@
+//! Retention policies for [`FuncRuns`](si_events::FuncRun) and their logs.
+//!
+//! Every workspace keeps its func runs for a number of days that depends on the [`FuncKind`].
+//! Workspaces start out with the [defaults](FuncRunRetentionPolicy::default_for) and can
+//! override them per kind. When a policy keeps the latest runs, the most recent run for each
+//! attribute value and action survives pruning however old it is, so that the last qualification
+//! or action result can always be shown.
+//!
+//! [`FuncRunRetention::prune`] deletes expired runs and their logs from durable storage and
+//! evicts them from every cache, in bounded batches so that no single workspace holds up the rest.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgRow};
+use si_layer_cache::LayerDbError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{DalContext, DalContextBuilder, FuncKind, TransactionsError, WorkspacePk};
+
+/// Every [`FuncKind`] that func runs can be recorded for.
+const FUNC_KINDS: [FuncKind; 10] = [
+    FuncKind::Action,
+    FuncKind::Attribute,
+    FuncKind::Authentication,
+    FuncKind::CodeGeneration,
+    FuncKind::Intrinsic,
+    FuncKind::Management,
+    FuncKind::Policy,
+    FuncKind::Qualification,
+    FuncKind::SchemaVariantDefinition,
+    FuncKind::Unknown,
+];
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum FuncRunRetentionError {
+    #[error("retention must be at least one day, got {0}")]
+    InvalidRetentionDays(i32),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
+    #[error("strum parse error: {0}")]
+    StrumParse(#[from] strum::ParseError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type FuncRunRetentionResult<T> = Result<T, FuncRunRetentionError>;
+
+/// How long a workspace keeps func runs of one [`FuncKind`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunRetentionPolicy {
+    pub workspace_pk: WorkspacePk,
+    pub function_kind: FuncKind,
+    pub retention_days: i32,
+    /// Whether the latest run for each attribute value and action is kept regardless of age.
+    pub keep_latest: bool,
+    /// Whether the workspace uses the default for this kind rather than its own override.
+    pub is_default: bool,
+}
+
+impl TryFrom<PgRow> for FuncRunRetentionPolicy {
+    type Error = FuncRunRetentionError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        let function_kind: String = row.try_get("function_kind")?;
+        Ok(Self {
+            workspace_pk: row.try_get("workspace_pk")?,
+            function_kind: function_kind.parse()?,
+            retention_days: row.try_get("retention_days")?,
+            keep_latest: row.try_get("keep_latest")?,
+            is_default: false,
+        })
+    }
+}
+
+impl FuncRunRetentionPolicy {
+    /// The policy for a workspace that has not overridden it. Action and management runs record
+    /// changes to real resources, so they are kept for a year. Attribute-like runs are
+    /// re-executed constantly and only matter while they are recent.
+    pub fn default_for(workspace_pk: WorkspacePk, function_kind: FuncKind) -> Self {
+        let retention_days = match function_kind {
+            FuncKind::Action | FuncKind::Management => 365,
+            FuncKind::Attribute
+            | FuncKind::CodeGeneration
+            | FuncKind::Intrinsic
+            | FuncKind::Qualification => 7,
+            FuncKind::Authentication
+            | FuncKind::Policy
+            | FuncKind::SchemaVariantDefinition
+            | FuncKind::Unknown => 30,
+        };
+
+        Self {
+            workspace_pk,
+            function_kind,
+            retention_days,
+            keep_latest: true,
+            is_default: true,
+        }
+    }
+
+    /// Func runs last updated before this are expired.
+    pub fn expires_before(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.retention_days.into())
+    }
+
+    /// The policy for every [`FuncKind`] in the workspace of the [`DalContext`].
+    pub async fn list(ctx: &DalContext) -> FuncRunRetentionResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM func_run_retention_policies WHERE workspace_pk = $1",
+                &[&workspace_pk],
+            )
+            .await?;
+        let overrides = rows
+            .into_iter()
+            .map(Self::try_from)
+            .collect::<FuncRunRetentionResult<Vec<_>>>()?;
+
+        Ok(FUNC_KINDS
+            .into_iter()
+            .map(|function_kind| {
+                overrides
+                    .iter()
+                    .find(|policy| policy.function_kind == function_kind)
+                    .cloned()
+                    .unwrap_or_else(|| Self::default_for(workspace_pk, function_kind))
+            })
+            .collect())
+    }
+
+    /// Overrides the policy for a [`FuncKind`] in the workspace of the [`DalContext`].
+    pub async fn set(
+        ctx: &DalContext,
+        function_kind: FuncKind,
+        retention_days: i32,
+        keep_latest: bool,
+    ) -> FuncRunRetentionResult<Self> {
+        if retention_days < 1 {
+            return Err(FuncRunRetentionError::InvalidRetentionDays(retention_days));
+        }
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO func_run_retention_policies
+                   (workspace_pk, function_kind, retention_days, keep_latest)
+                   VALUES ($1, $2, $3, $4)
+                   ON CONFLICT (workspace_pk, function_kind) DO UPDATE SET
+                     retention_days = EXCLUDED.retention_days,
+                     keep_latest = EXCLUDED.keep_latest,
+                     updated_at = clock_timestamp()
+                   RETURNING *",
+                &[
+                    &ctx.workspace_pk()?,
+                    &function_kind.to_string(),
+                    &retention_days,
+                    &keep_latest,
+                ],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Removes the override for a [`FuncKind`] in the workspace of the [`DalContext`], returning
+    /// the default policy that applies again.
+    pub async fn reset(ctx: &DalContext, function_kind: FuncKind) -> FuncRunRetentionResult<Self> {
+        let workspace_pk = ctx.workspace_pk()?;
+        ctx.txns()
+            .await?
+            .pg()
+            .execute(
+                "DELETE FROM func_run_retention_policies WHERE workspace_pk = $1 AND function_kind = $2",
+                &[&workspace_pk, &function_kind.to_string()],
+            )
+            .await?;
+
+        Ok(Self::default_for(workspace_pk, function_kind))
+    }
+}
+
+/// How much a call to [`FuncRunRetention::prune`] deleted.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunPruneReport {
+    pub func_runs: usize,
+    pub func_run_logs: usize,
+}
+
+pub struct FuncRunRetention;
+
+impl FuncRunRetention {
+    /// Prunes expired func runs and their logs in every workspace.
+    ///
+    /// Runs are deleted `batch_size` at a time. At most `max_batches` are deleted per workspace
+    /// and [`FuncKind`] on each call, so a workspace with a large backlog is worked through over
+    /// several calls instead of holding up the others.
+    #[instrument(name = "func.retention.prune", level = "info", skip(builder))]
+    pub async fn prune(
+        builder: &DalContextBuilder,
+        batch_size: i64,
+        max_batches: usize,
+    ) -> FuncRunRetentionResult<FuncRunPruneReport> {
+        let ctx = builder.build_default(None).await?;
+        let workspace_pks: Vec<WorkspacePk> = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT pk FROM workspaces WHERE pk != $1",
+                &[&WorkspacePk::NONE],
+            )
+            .await?
+            .into_iter()
+            .map(|row| row.try_get("pk"))
+            .collect::<Result<_, _>>()?;
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query("SELECT * FROM func_run_retention_policies", &[])
+            .await?;
+        let overrides = rows
+            .into_iter()
+            .map(FuncRunRetentionPolicy::try_from)
+            .collect::<FuncRunRetentionResult<Vec<_>>>()?;
+        ctx.commit_no_rebase().await?;
+
+        let now = Utc::now();
+        let mut report = FuncRunPruneReport::default();
+        for workspace_pk in workspace_pks {
+            for function_kind in FUNC_KINDS {
+                let policy = overrides
+                    .iter()
+                    .find(|policy| {
+                        policy.workspace_pk == workspace_pk && policy.function_kind == function_kind
+                    })
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        FuncRunRetentionPolicy::default_for(workspace_pk, function_kind)
+                    });
+
+                let pruned =
+                    Self::prune_policy(&ctx, &policy, now, batch_size, max_batches).await?;
+                report.func_runs += pruned.func_runs;
+                report.func_run_logs += pruned.func_run_logs;
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn prune_policy(
+        ctx: &DalContext,
+        policy: &FuncRunRetentionPolicy,
+        now: DateTime<Utc>,
+        batch_size: i64,
+        max_batches: usize,
+    ) -> FuncRunRetentionResult<FuncRunPruneReport> {
+        let older_than = policy.expires_before(now);
+
+        let mut report = FuncRunPruneReport::default();
+        for _ in 0..max_batches {
+            let pruned = ctx
+                .layer_db()
+                .func_run()
+                .prune(
+                    policy.workspace_pk,
+                    policy.function_kind.into(),
+                    older_than,
+                    policy.keep_latest,
+                    batch_size,
+                )
+                .await?;
+            report.func_run_logs += ctx
+                .layer_db()
+                .func_run_log()
+                .prune_for_func_runs(policy.workspace_pk, &pruned)
+                .await?;
+            report.func_runs += pruned.len();
+
+            if (pruned.len() as i64) < batch_size {
+                break;
+            }
+        }
+
+        if report.func_runs > 0 {
+            info!(
+                si.workspace.id = %policy.workspace_pk,
+                function_kind = %policy.function_kind,
+                func_runs = report.func_runs,
+                func_run_logs = report.func_run_logs,
+                "pruned expired func runs",
+            );
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,406 @@
+//! Searching and tailing the logs of [`FuncRuns`](si_events::FuncRun).
+//!
+//! Logs are stored as one blob per func run, so a search narrows down the func runs in SQL and
+//! matches individual lines here. Tailing replays what has been stored for a func run and then
+//! follows the lines the func runner publishes on [`func_run_log_subject`] as cyclone emits them.
+
+use std::{collections::VecDeque, time::Duration};
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream, StreamExt};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use si_data_nats::{NatsError, Subject, Subscriber};
+use si_events::{FuncRun, FuncRunId, FuncRunState, OutputLine};
+use si_layer_cache::{db::func_run_log::FuncRunLogFilter, LayerDbError};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    context::DalLayerDb, ChangeSetId, ComponentId, DalContext, Func, FuncError, FuncId,
+    TransactionsError, WorkspacePk,
+};
+
+/// The number of matching lines returned when a search does not ask for a limit.
+pub const DEFAULT_SEARCH_LIMIT: usize = 100;
+/// The most matching lines a single search can return.
+pub const MAX_SEARCH_LIMIT: usize = 1000;
+/// The most func run logs a single search looks through, newest first.
+const MAX_FUNC_RUN_LOGS_SEARCHED: i64 = 500;
+/// Bounds the memory a compiled search pattern may use.
+const REGEX_SIZE_LIMIT: usize = 1024 * 1024;
+/// How long a tail waits for the next line before checking whether the func run has finished.
+const LIVE_LINE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum FuncRunLogError {
+    #[error("func error: {0}")]
+    Func(#[from] Box<FuncError>),
+    #[error("func run not found: {0}")]
+    FuncRunNotFound(FuncRunId),
+    #[error("invalid search pattern: {0}")]
+    InvalidPattern(#[from] regex::Error),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("nats error: {0}")]
+    Nats(#[from] NatsError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type FuncRunLogResult<T> = Result<T, FuncRunLogError>;
+
+/// A single line of a func run's log.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunLogLine {
+    pub stream: String,
+    pub level: String,
+    pub group: Option<String>,
+    pub message: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+impl From<OutputLine> for FuncRunLogLine {
+    fn from(value: OutputLine) -> Self {
+        Self {
+            stream: value.stream,
+            level: value.level,
+            group: value.group,
+            message: value.message,
+            timestamp: value.timestamp,
+        }
+    }
+}
+
+/// Searches the func run logs of a workspace. Every filter that is set must match.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunLogSearch {
+    pub change_set_id: Option<ChangeSetId>,
+    pub component_id: Option<ComponentId>,
+    pub func_id: Option<FuncId>,
+    /// Only lines logged at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only lines logged at or before this time.
+    pub until: Option<DateTime<Utc>>,
+    /// Only lines written to this stream, such as `output`.
+    pub stream: Option<String>,
+    /// Only lines whose message contains this text.
+    pub contains: Option<String>,
+    /// Only lines whose message matches this regular expression.
+    pub pattern: Option<String>,
+    /// The most matching lines to return, [`DEFAULT_SEARCH_LIMIT`] if unset.
+    pub limit: Option<usize>,
+}
+
+/// A line that matched a [`FuncRunLogSearch`], along with the func run that logged it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunLogMatch {
+    pub func_run_id: FuncRunId,
+    pub change_set_id: ChangeSetId,
+    pub component_id: Option<ComponentId>,
+    pub component_name: Option<String>,
+    pub function_name: String,
+    /// The position of the line in the func run's log, starting at zero.
+    pub line_number: usize,
+    pub line: FuncRunLogLine,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunLogSearchResults {
+    /// Matching lines from the most recently written logs first, in log order within a func run.
+    pub matches: Vec<FuncRunLogMatch>,
+    /// Whether the search stopped early, either at its limit or after looking through the most
+    /// func run logs a single search may. Narrowing down the search finds the rest.
+    pub truncated: bool,
+}
+
+impl FuncRunLogSearch {
+    #[instrument(name = "func_run_log.search", level = "info", skip_all)]
+    pub async fn run(&self, ctx: &DalContext) -> FuncRunLogResult<FuncRunLogSearchResults> {
+        let limit = self
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+        let pattern = self
+            .pattern
+            .as_deref()
+            .map(|pattern| {
+                RegexBuilder::new(pattern)
+                    .size_limit(REGEX_SIZE_LIMIT)
+                    .build()
+            })
+            .transpose()?;
+
+        // Func runs are matched to the func by name, which covers attribute and action runs too
+        let function_name = match self.func_id {
+            Some(func_id) => Some(
+                Func::get_by_id_or_error(ctx, func_id)
+                    .await
+                    .map_err(Box::new)?
+                    .name,
+            ),
+            None => None,
+        };
+        let filter = FuncRunLogFilter {
+            change_set_id: self.change_set_id,
+            component_id: self.component_id,
+            function_name,
+            since: self.since,
+            until: self.until,
+        };
+        let func_run_logs = ctx
+            .layer_db()
+            .func_run_log()
+            .list_for_search(ctx.workspace_pk()?, &filter, MAX_FUNC_RUN_LOGS_SEARCHED)
+            .await?;
+
+        let mut truncated = func_run_logs.len() as i64 >= MAX_FUNC_RUN_LOGS_SEARCHED;
+        let mut matches = Vec::new();
+        'logs: for (func_run, func_run_log) in func_run_logs {
+            for (line_number, line) in func_run_log.logs().iter().enumerate() {
+                if !self.matches(line, pattern.as_ref()) {
+                    continue;
+                }
+                if matches.len() == limit {
+                    truncated = true;
+                    break 'logs;
+                }
+
+                matches.push(FuncRunLogMatch {
+                    func_run_id: func_run.id(),
+                    change_set_id: func_run.tenancy().change_set_id,
+                    component_id: func_run.component_id(),
+                    component_name: func_run.component_name().map(ToOwned::to_owned),
+                    function_name: func_run.function_name().to_owned(),
+                    line_number,
+                    line: line.clone().into(),
+                });
+            }
+        }
+
+        Ok(FuncRunLogSearchResults { matches, truncated })
+    }
+
+    fn matches(&self, line: &OutputLine, pattern: Option<&Regex>) -> bool {
+        let timestamp = line.timestamp as i64;
+        self.stream
+            .as_ref()
+            .is_none_or(|stream| &line.stream == stream)
+            && self
+                .since
+                .is_none_or(|since| timestamp >= since.timestamp())
+            && self
+                .until
+                .is_none_or(|until| timestamp <= until.timestamp())
+            && self
+                .contains
+                .as_deref()
+                .is_none_or(|contains| line.message.contains(contains))
+            && pattern.is_none_or(|pattern| pattern.is_match(&line.message))
+    }
+}
+
+/// Published by the func runner on [`func_run_log_subject`] as it stores a func run's log.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FuncRunLogMessage {
+    /// A line was appended to the log at `index`.
+    Line { index: usize, line: OutputLine },
+    /// The log is complete and no more lines will follow.
+    Finalized,
+}
+
+/// The subject the lines of a func run's log are published on while it runs.
+pub fn func_run_log_subject(
+    prefix: Option<&str>,
+    workspace_pk: WorkspacePk,
+    func_run_id: FuncRunId,
+) -> Subject {
+    let subject = format!("func_run_log.{workspace_pk}.{func_run_id}");
+    match prefix {
+        Some(prefix) => format!("{prefix}.{subject}").into(),
+        None => subject.into(),
+    }
+}
+
+/// Publishes a [`FuncRunLogMessage`] for anyone tailing the func run's log.
+///
+/// Lines are stored before they are published, so a failed publish only holds up tailing clients
+/// until the next line and is logged rather than returned.
+pub(crate) async fn publish(
+    ctx: &DalContext,
+    workspace_pk: WorkspacePk,
+    func_run_id: FuncRunId,
+    message: &FuncRunLogMessage,
+) {
+    let payload = match serde_json::to_vec(message) {
+        Ok(payload) => payload,
+        Err(err) => {
+            warn!(
+                si.error.message = ?err,
+                si.func_run.id = %func_run_id,
+                "failed to serialize func run log message",
+            );
+            return;
+        }
+    };
+
+    let nats = ctx.nats_conn();
+    let subject = func_run_log_subject(nats.metadata().subject_prefix(), workspace_pk, func_run_id);
+    if let Err(err) = nats.publish(subject, payload.into()).await {
+        warn!(
+            si.error.message = ?err,
+            si.func_run.id = %func_run_id,
+            "failed to publish func run log message",
+        );
+    }
+}
+
+/// Streams the log of a func run in the current workspace: every line stored so far, followed by
+/// new lines as they are logged. The stream ends once the log is complete.
+#[instrument(name = "func_run_log.tail", level = "info", skip(ctx))]
+pub async fn tail(
+    ctx: &DalContext,
+    func_run_id: FuncRunId,
+) -> FuncRunLogResult<impl Stream<Item = FuncRunLogLine> + Send + 'static> {
+    let workspace_pk = ctx.workspace_pk()?;
+
+    // Subscribe before reading the stored log, so that no line falls between the two. Lines are
+    // stored before they are published, so any line both stored and received is skipped by index.
+    let nats = ctx.nats_conn();
+    let subscriber = nats
+        .subscribe(func_run_log_subject(
+            nats.metadata().subject_prefix(),
+            workspace_pk,
+            func_run_id,
+        ))
+        .await?;
+
+    let func_run = ctx
+        .layer_db()
+        .func_run()
+        .read(func_run_id)
+        .await?
+        .filter(|func_run| func_run.tenancy().workspace_pk == workspace_pk)
+        .ok_or(FuncRunLogError::FuncRunNotFound(func_run_id))?;
+
+    let (stored, complete) = stored_log(ctx.layer_db(), &func_run).await?;
+
+    let live = stream::unfold(
+        (!complete).then(|| LiveLog {
+            subscriber,
+            next_index: stored.len(),
+            missed: None,
+            layer_db: ctx.layer_db().clone(),
+            func_run_id,
+        }),
+        live_line,
+    );
+
+    Ok(stream::iter(stored.into_iter().map(Into::into)).chain(live))
+}
+
+/// The lines stored for a func run so far, and whether no more will follow.
+async fn stored_log(
+    layer_db: &DalLayerDb,
+    func_run: &FuncRun,
+) -> FuncRunLogResult<(Vec<OutputLine>, bool)> {
+    Ok(
+        match layer_db
+            .func_run_log()
+            .get_for_func_run_id(func_run.id())
+            .await?
+        {
+            Some(func_run_log) => (func_run_log.logs().to_vec(), func_run_log.is_finalized()),
+            // Either nothing has been logged yet, or the func run finished without a stored log
+            // (for example, because it has been pruned) and nothing ever will be
+            None => (Vec::new(), has_finished(func_run)),
+        },
+    )
+}
+
+fn has_finished(func_run: &FuncRun) -> bool {
+    matches!(
+        func_run.state(),
+        FuncRunState::Failure | FuncRunState::Killed | FuncRunState::Success
+    )
+}
+
+/// Where a tail is up to in following a func run's live log.
+struct LiveLog {
+    subscriber: Subscriber,
+    next_index: usize,
+    /// Once the log is known to be complete, the stored lines that never arrived on the subject.
+    /// The tail ends after streaming them.
+    missed: Option<VecDeque<OutputLine>>,
+    layer_db: DalLayerDb,
+    func_run_id: FuncRunId,
+}
+
+async fn live_line(state: Option<LiveLog>) -> Option<(FuncRunLogLine, Option<LiveLog>)> {
+    let mut state = state?;
+
+    loop {
+        if let Some(missed) = &mut state.missed {
+            let line = missed.pop_front()?;
+            return Some((line.into(), Some(state)));
+        }
+
+        let message = match tokio::time::timeout(LIVE_LINE_TIMEOUT, state.subscriber.next()).await {
+            Ok(Some(message)) => message,
+            Ok(None) => return None,
+            // The finalized message may have been lost, or the func runner may have died, so
+            // check with the layer db whether the func run is still going
+            Err(_) => {
+                if let Err(err) = state.recheck().await {
+                    warn!(
+                        si.error.message = ?err,
+                        si.func_run.id = %state.func_run_id,
+                        "failed to check whether func run log is complete",
+                    );
+                }
+                continue;
+            }
+        };
+
+        match serde_json::from_slice(message.payload()) {
+            Ok(FuncRunLogMessage::Line { index, line }) if index >= state.next_index => {
+                state.next_index = index + 1;
+                return Some((line.into(), Some(state)));
+            }
+            // Already streamed from the stored log
+            Ok(FuncRunLogMessage::Line { .. }) => {}
+            Ok(FuncRunLogMessage::Finalized) => return None,
+            Err(err) => warn!(
+                si.error.message = ?err,
+                si.func_run.id = %state.func_run_id,
+                "failed to deserialize func run log message",
+            ),
+        }
+    }
+}
+
+impl LiveLog {
+    /// Reads the func run and its log back from the layer db. If the log is complete, or the func
+    /// run has finished without completing it, queues up the lines that were never received.
+    async fn recheck(&mut self) -> FuncRunLogResult<()> {
+        let func_run = self
+            .layer_db
+            .func_run()
+            .read(self.func_run_id)
+            .await?
+            .ok_or(FuncRunLogError::FuncRunNotFound(self.func_run_id))?;
+        let (stored, complete) = stored_log(&self.layer_db, &func_run).await?;
+        if complete || has_finished(&func_run) {
+            self.missed = Some(stored.into_iter().skip(self.next_index).collect());
+        }
+
+        Ok(())
+    }
+}
@@ -16,7 +16,7 @@ use tokio::sync::{mpsc, oneshot};
 use veritech_client::{
     encrypt_value_tree, BeforeFunction, FunctionResult, FunctionResultFailure,
     FunctionResultFailureErrorKind, KillExecutionRequest, OutputStream, ResolverFunctionComponent,
-    VeritechValueEncryptError,
+    ResolverFunctionResponseType, VeritechValueEncryptError,
 };
 
 use crate::attribute::prototype::argument::value_source::ValueSource;
@@ -56,9 +56,16 @@ use super::backend::{
     map::FuncBackendMap,
     object::FuncBackendObject,
     string::FuncBackendString,
+    transform::{
+        FuncBackendBase64Decode, FuncBackendBase64Encode, FuncBackendBuildArray,
+        FuncBackendBuildMap, FuncBackendConcat, FuncBackendDefaultIfEmpty, FuncBackendJsonPointer,
+        FuncBackendLowercase, FuncBackendRegexExtract, FuncBackendTemplate, FuncBackendUppercase,
+    },
     validation::FuncBackendValidation,
+    wasm::{FuncBackendWasm, FuncBackendWasmArgs},
     FuncBackend, FuncDispatch, FuncDispatchContext, InvalidResolverFunctionTypeError,
 };
+use super::run_log::{self, FuncRunLogMessage};
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -293,6 +300,126 @@ impl FuncRunner {
         Ok((func_run_id, result_channel))
     }
 
+    /// Runs a [policy](crate::func::FuncKind::Policy) against a JSON view of the change set.
+    /// Policies are not tied to a component, so no authentication functions run before them.
+    #[instrument(
+        name = "func_runner.run_policy",
+        level = "debug",
+        skip_all,
+        fields(
+            job.id = Empty,
+            job.invoked_args = Empty,
+            job.invoked_name = func.name.as_str(),
+            otel.kind = SpanKind::Producer.as_str(),
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+            si.change_set.id = Empty,
+            si.func_run.func.args = Empty,
+            si.func_run.func.backend_kind = func.backend_kind.as_ref(),
+            si.func_run.func.backend_response_type = func.backend_response_type.as_ref(),
+            si.func_run.func.id = Empty,
+            si.func_run.func.kind = func.kind.as_ref(),
+            si.func_run.func.name = func.name.as_str(),
+            si.func_run.id = Empty,
+            si.workspace.id = Empty,
+        )
+    )]
+    pub async fn run_policy(
+        ctx: &DalContext,
+        func: Func,
+        args: serde_json::Value,
+    ) -> FuncRunnerResult<(FuncRunId, FuncRunnerValueChannel)> {
+        let span = current_span_for_instrument_at!("debug");
+
+        #[instrument(
+            name = "func_runner.run_policy.prepare",
+            level = "debug",
+            skip_all,
+            fields()
+        )]
+        #[inline]
+        async fn prepare(
+            ctx: &DalContext,
+            func: Func,
+            args: serde_json::Value,
+            span: &Span,
+        ) -> FuncRunnerResult<FuncRunner> {
+            let function_args: CasValue = args.clone().into();
+            let (function_args_cas_address, _) = ctx.layer_db().cas().write(
+                Arc::new(function_args.into()),
+                None,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )?;
+
+            let func_run_create_time = Utc::now();
+            let func_run_inner = FuncRunBuilder::default()
+                .actor(ctx.events_actor())
+                .tenancy(ctx.events_tenancy())
+                .backend_kind(func.backend_kind.into())
+                .backend_response_type(func.backend_response_type.into())
+                .function_name(func.name.clone())
+                .function_kind(func.kind.into())
+                .function_display_name(func.display_name.clone())
+                .function_description(func.description.clone())
+                .function_link(func.link.clone())
+                .function_args_cas_address(function_args_cas_address)
+                .function_code_cas_address(func.code_blake3)
+                .attribute_value_id(None)
+                .component_id(None)
+                .created_at(func_run_create_time)
+                .updated_at(func_run_create_time)
+                .build()?;
+
+            if !span.is_disabled() {
+                let mut id_buf = FuncRunId::array_to_str_buf();
+
+                let id = func_run_inner.id().array_to_str(&mut id_buf);
+                span.record("job.id", &id);
+                span.record("si.func_run.id", &id);
+
+                span.record("si.func_run.func.id", func.id.array_to_str(&mut id_buf));
+
+                span.record(
+                    "si.change_set.id",
+                    func_run_inner.change_set_id().array_to_str(&mut id_buf),
+                );
+                span.record(
+                    "si.workspace.id",
+                    func_run_inner.workspace_pk().array_to_str(&mut id_buf),
+                );
+            }
+
+            let func_run = Arc::new(func_run_inner);
+
+            ctx.layer_db()
+                .func_run()
+                .write(
+                    func_run.clone(),
+                    None,
+                    ctx.events_tenancy(),
+                    ctx.events_actor(),
+                )
+                .await?;
+
+            Ok(FuncRunner {
+                func_run,
+                func,
+                args,
+                before: Vec::new(),
+            })
+        }
+
+        let runner = prepare(ctx, func, args, &span)
+            .await
+            .map_err(|err| span.record_err(err))?;
+
+        let func_run_id = runner.id();
+        let result_channel = runner.execute(ctx.clone(), span).await;
+
+        Ok((func_run_id, result_channel))
+    }
+
     #[instrument(
         name = "func_runner.run_asset_definition_func",
         level = "debug",
@@ -1321,14 +1448,14 @@ impl FuncRunner {
         Ok(before_functions)
     }
 
-    /// This _private_ method generates a flattened graph of before [`Funcs`](Func) with corresponding
+    /// Generates a flattened graph of before [`Funcs`](Func) with corresponding
     /// [`keys`](EncryptedSecretKey).
     #[instrument(
         name = "func_runner.before_funcs.ordered_before_funcs_with_secret_keys",
         level = "debug",
         skip_all
     )]
-    async fn ordered_before_funcs_with_secret_keys(
+    pub(crate) async fn ordered_before_funcs_with_secret_keys(
         ctx: &DalContext,
         component_id: ComponentId,
     ) -> FuncRunnerResult<Vec<(EncryptedSecretKey, Vec<Func>)>> {
@@ -1569,6 +1696,21 @@ impl FuncRunnerLogsTask {
                     self.ctx.events_actor(),
                 )
                 .await?;
+
+            // Only published once stored, so that clients tailing the log can tell which lines
+            // they have already read from the stored log
+            if let Some(line) = func_run_log.logs().last() {
+                run_log::publish(
+                    &self.ctx,
+                    self.ctx.events_tenancy().workspace_pk,
+                    self.func_run_id,
+                    &FuncRunLogMessage::Line {
+                        index: func_run_log.logs().len() - 1,
+                        line: line.clone(),
+                    },
+                )
+                .await;
+            }
         }
 
         // Now that all `OutputStream` messages have been received, we will never
@@ -1589,6 +1731,13 @@ impl FuncRunnerLogsTask {
                 self.ctx.events_actor(),
             )
             .await?;
+        run_log::publish(
+            &self.ctx,
+            self.ctx.events_tenancy().workspace_pk,
+            self.func_run_id,
+            &FuncRunLogMessage::Finalized,
+        )
+        .await;
 
         Ok(())
     }
@@ -1687,6 +1836,38 @@ impl FuncRunnerExecutionTask {
                 )
                 .await
             }
+            FuncBackendKind::JsPolicy => {
+                let args = FuncBackendJsAttributeArgs {
+                    component: ResolverFunctionComponent {
+                        data: veritech_client::ComponentView {
+                            properties: self.args.to_owned(),
+                            ..Default::default()
+                        },
+                        parents: Vec::new(),
+                    },
+                    response_type: ResolverFunctionResponseType::Json,
+                };
+                FuncBackendJsAttribute::create_and_execute(
+                    self.func_dispatch_context,
+                    &self.func,
+                    &serde_json::to_value(args)?,
+                    self.before,
+                )
+                .await
+            }
+            FuncBackendKind::Wasm => {
+                let args = FuncBackendWasmArgs {
+                    args: self.args.to_owned(),
+                    response_type: self.func.backend_response_type.try_into()?,
+                };
+                FuncBackendWasm::create_and_execute(
+                    self.func_dispatch_context,
+                    &self.func,
+                    &serde_json::to_value(args)?,
+                    self.before,
+                )
+                .await
+            }
             FuncBackendKind::JsSchemaVariantDefinition => {
                 FuncBackendJsSchemaVariantDefinition::create_and_execute(
                     self.func_dispatch_context,
@@ -1705,6 +1886,33 @@ impl FuncRunnerExecutionTask {
             FuncBackendKind::Map => FuncBackendMap::create_and_execute(&self.args).await,
             FuncBackendKind::Object => FuncBackendObject::create_and_execute(&self.args).await,
             FuncBackendKind::String => FuncBackendString::create_and_execute(&self.args).await,
+            FuncBackendKind::Base64Decode => {
+                FuncBackendBase64Decode::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::Base64Encode => {
+                FuncBackendBase64Encode::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::BuildArray => {
+                FuncBackendBuildArray::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::BuildMap => FuncBackendBuildMap::create_and_execute(&self.args).await,
+            FuncBackendKind::Concat => FuncBackendConcat::create_and_execute(&self.args).await,
+            FuncBackendKind::DefaultIfEmpty => {
+                FuncBackendDefaultIfEmpty::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::JsonPointer => {
+                FuncBackendJsonPointer::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::Lowercase => {
+                FuncBackendLowercase::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::RegexExtract => {
+                FuncBackendRegexExtract::create_and_execute(&self.args).await
+            }
+            FuncBackendKind::Template => FuncBackendTemplate::create_and_execute(&self.args).await,
+            FuncBackendKind::Uppercase => {
+                FuncBackendUppercase::create_and_execute(&self.args).await
+            }
             FuncBackendKind::Unset => Ok((None, None)),
             FuncBackendKind::Validation => {
                 FuncBackendValidation::create_and_execute(
@@ -1741,6 +1949,8 @@ impl FuncRunnerExecutionTask {
             }
         };
 
+        let succeeded = execution_result.is_ok();
+
         match execution_result {
             Ok((mut unprocessed_value, mut value)) => {
                 // We so sorry - this is the way that the old code
@@ -1839,6 +2049,29 @@ impl FuncRunnerExecutionTask {
             }
         }
 
+        if !self.func.is_intrinsic() {
+            // The func run has already been recorded, so failing to notify clients shouldn't fail the task
+            let publish_result = async {
+                WsEvent::func_run_finished(
+                    &self.ctx,
+                    self.func_run.id(),
+                    self.func_run.action_id(),
+                    succeeded,
+                )
+                .await?
+                .publish_immediately(&self.ctx)
+                .await
+            }
+            .await;
+            if let Err(err) = publish_result {
+                error!(
+                    si.error.message = ?err,
+                    task = Self::NAME,
+                    "error while publishing func run finished event"
+                );
+            }
+        }
+
         Ok(())
     }
 }
@@ -1851,6 +2084,30 @@ pub struct FuncRunLogUpdatedPayload {
     action_id: Option<ActionId>,
 }
 
+/// Sent once a function has finished executing. Callers may still be post-processing the result of a successful
+/// execution when this is published.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunFinishedPayload {
+    func_run_id: FuncRunId,
+    action_id: Option<ActionId>,
+    succeeded: bool,
+}
+
+impl FuncRunFinishedPayload {
+    pub fn func_run_id(&self) -> FuncRunId {
+        self.func_run_id
+    }
+
+    pub fn action_id(&self) -> Option<ActionId> {
+        self.action_id
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.succeeded
+    }
+}
+
 impl WsEvent {
     pub async fn func_run_log_updated(
         ctx: &DalContext,
@@ -1868,4 +2125,21 @@ impl WsEvent {
         )
         .await
     }
+
+    pub async fn func_run_finished(
+        ctx: &DalContext,
+        func_run_id: FuncRunId,
+        action_id: Option<ActionId>,
+        succeeded: bool,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::FuncRunFinished(FuncRunFinishedPayload {
+                func_run_id,
+                action_id,
+                succeeded,
+            }),
+        )
+        .await
+    }
 }
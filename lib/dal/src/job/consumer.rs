@@ -14,6 +14,7 @@ use tokio::task::JoinError;
 use crate::billing_publish::BillingPublishError;
 use crate::diagram::DiagramError;
 use crate::prop::PropError;
+use crate::secret::SecretError;
 use crate::validation::ValidationError;
 use crate::FuncError;
 use crate::{
@@ -71,6 +72,8 @@ pub enum JobConsumerError {
     Prop(#[from] PropError),
     #[error("execution of job {0} failed after {1} retry attempts")]
     RetriesFailed(String, u32),
+    #[error("secret error: {0}")]
+    Secret(#[from] SecretError),
     #[error("serde json error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
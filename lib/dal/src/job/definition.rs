@@ -1,9 +1,11 @@
 mod action;
 pub mod compute_validation;
 pub mod dependent_values_update;
+mod reencrypt_secrets;
 
 pub use action::ActionJob;
 pub use dependent_values_update::DependentValuesUpdate;
+pub use reencrypt_secrets::ReencryptSecrets;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum AttributeValueBasedJobIdentifier {
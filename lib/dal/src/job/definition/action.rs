@@ -221,13 +221,26 @@ async fn process_execution(
         .ok_or(ActionError::ComponentNotFoundForAction(action_id))?;
     let component = Component::get_by_id(ctx, component_id).await?;
     let mut success = false;
+    let mut drifted_component_name = None;
     if let Some(run_result) = action_run_result {
         // Set the resource if we have a payload, regardless of status *and* assemble a
         // summary
         if run_result.payload.is_some() {
-            // Send the create resource event if we're not updating an existing resource
-            if component.resource(ctx).await?.is_none() {
-                billing_publish::for_resource_create(ctx, component_id, func_run_id).await?;
+            match component.resource(ctx).await? {
+                // Send the create resource event if we're not updating an existing resource
+                None => {
+                    billing_publish::for_resource_create(ctx, component_id, func_run_id).await?;
+                }
+                // A successful refresh that returns a different payload than the one we last
+                // recorded means the resource was changed outside of System Initiative
+                Some(previous_resource) => {
+                    if prototype.kind == ActionKind::Refresh
+                        && run_result.status == ResourceStatus::Ok
+                        && previous_resource.payload != run_result.payload
+                    {
+                        drifted_component_name = Some(component.name(ctx).await?);
+                    }
+                }
             }
 
             component.set_resource(ctx, run_result.into()).await?;
@@ -305,6 +318,21 @@ async fn process_execution(
         .publish_on_commit(ctx)
         .await?;
 
+    if let Some(component_name) = drifted_component_name {
+        ctx.write_audit_log(
+            AuditLogKind::DetectResourceDrift {
+                component_id,
+                component_name: component_name.clone(),
+                prototype_id,
+                func_id: func.id,
+                func_display_name: func.display_name.clone(),
+                func_name: func.name.clone(),
+            },
+            component_name,
+        )
+        .await?;
+    }
+
     ctx.write_audit_log(
         AuditLogKind::RunAction {
             prototype_id,
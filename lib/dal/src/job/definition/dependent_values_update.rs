@@ -3,6 +3,7 @@ use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     convert::TryFrom,
     sync::Arc,
+    time::Duration,
 };
 use telemetry_utils::metric;
 
@@ -19,6 +20,7 @@ use ulid::Ulid;
 
 use crate::{
     attribute::value::{dependent_value_graph::DependentValueGraph, AttributeValueError},
+    func::FuncKind,
     job::{
         consumer::{
             JobCompletionState, JobConsumer, JobConsumerError, JobConsumerMetadata,
@@ -117,6 +119,10 @@ impl JobConsumer for DependentValuesUpdate {
         skip_all,
         fields(
             si.change_set.id = Empty,
+            si.dvu.values_computed = Empty,
+            si.dvu.values_deferred = Empty,
+            si.dvu.values_skipped_by_cutoff = Empty,
+            si.dvu.wall_time_ms = Empty,
             si.workspace.id = Empty,
         ),
     )]
@@ -158,10 +164,8 @@ impl StatusUpdateTracker {
             tracker
                 .values_by_component
                 .entry(component_id)
-                .and_modify(|values: &mut HashSet<AttributeValueId>| {
-                    values.insert(value_id);
-                })
-                .or_default();
+                .or_default()
+                .insert(value_id);
             tracker.components_by_value.insert(value_id, component_id);
         }
 
@@ -188,19 +192,41 @@ impl StatusUpdateTracker {
             })
     }
 
+    /// Finishing the last value of a component frees up its slot for another component.
     fn finish_value(&mut self, value_id: AttributeValueId) -> Option<ComponentId> {
-        self.components_by_value
+        let finished_component_id =
+            self.components_by_value
+                .get(&value_id)
+                .and_then(
+                    |component_id| match self.values_by_component.entry(*component_id) {
+                        Entry::Occupied(mut values_entry) => {
+                            let values = values_entry.get_mut();
+                            values.remove(&value_id);
+                            values.is_empty().then_some(*component_id)
+                        }
+                        Entry::Vacant(_) => None,
+                    },
+                );
+        if let Some(component_id) = finished_component_id {
+            self.active_components.remove(&component_id);
+        }
+
+        finished_component_id
+    }
+
+    /// Finishes a value that was never started because of the early cutoff. A finished update is
+    /// only needed if the component had been started for another value.
+    fn skip_value(&mut self, value_id: AttributeValueId) -> Option<StatusUpdate> {
+        let was_active = self
+            .components_by_value
             .get(&value_id)
-            .and_then(
-                |component_id| match self.values_by_component.entry(*component_id) {
-                    Entry::Occupied(mut values_entry) => {
-                        let values = values_entry.get_mut();
-                        values.remove(&value_id);
-                        values.is_empty().then_some(*component_id)
-                    }
-                    Entry::Vacant(_) => None,
-                },
-            )
+            .is_some_and(|component_id| self.active_components.contains(component_id));
+
+        self.finish_value(value_id)
+            .filter(|_| was_active)
+            .map(|component_id| {
+                StatusUpdate::new_dvu(StatusMessageState::StatusFinished, component_id)
+            })
     }
 
     fn finish_remaining(&self) -> Vec<StatusUpdate> {
@@ -226,6 +252,43 @@ impl StatusUpdateTracker {
     }
 }
 
+/// Per run statistics, to measure how much work the early cutoff saves on large workspaces.
+#[derive(Debug, Default)]
+struct DependentValuesUpdateStats {
+    /// Values whose functions were executed.
+    values_computed: usize,
+    /// Values left for the next run because too many components were already in flight.
+    values_deferred: usize,
+    /// Values that were not executed because nothing they depend on changed.
+    values_skipped_by_cutoff: usize,
+}
+
+impl DependentValuesUpdateStats {
+    fn record(&self, span: &Span, wall_time: Duration) {
+        let wall_time_ms = wall_time.as_millis() as u64;
+
+        span.record("si.dvu.values_computed", self.values_computed);
+        span.record("si.dvu.values_deferred", self.values_deferred);
+        span.record(
+            "si.dvu.values_skipped_by_cutoff",
+            self.values_skipped_by_cutoff,
+        );
+        span.record("si.dvu.wall_time_ms", wall_time_ms);
+
+        metric!(monotonic_counter.dvu.values_computed = self.values_computed);
+        metric!(monotonic_counter.dvu.values_skipped_by_cutoff = self.values_skipped_by_cutoff);
+        metric!(histogram.dvu.wall_time_ms = wall_time_ms);
+
+        debug!(
+            values_computed = self.values_computed,
+            values_deferred = self.values_deferred,
+            values_skipped_by_cutoff = self.values_skipped_by_cutoff,
+            wall_time_ms,
+            "DependentValuesUpdate finished",
+        );
+    }
+}
+
 impl DependentValuesUpdate {
     async fn inner_run(
         &self,
@@ -258,6 +321,13 @@ impl DependentValuesUpdate {
 
         let concurrency_limit = ctx.get_workspace().await?.component_concurrency_limit() as usize;
 
+        // The values the run starts from have changed by definition, so they are never cut off
+        let root_value_ids: HashSet<AttributeValueId> = unfinished_values
+            .iter()
+            .chain(finished_values.iter())
+            .map(|ulid| (*ulid).into())
+            .collect();
+
         let mut dependency_graph = DependentValueGraph::new(ctx, roots).await?;
 
         debug!(
@@ -265,6 +335,19 @@ impl DependentValuesUpdate {
             start.elapsed()
         );
 
+        // The graph loses its edges as values are processed, so remember what every value depends
+        // on for the early cutoff
+        let dependencies_by_value: HashMap<AttributeValueId, Vec<AttributeValueId>> =
+            dependency_graph
+                .all_value_ids()
+                .into_iter()
+                .map(|value_id| (value_id, dependency_graph.direct_dependencies_of(value_id)))
+                .collect();
+        let values_with_dependents: HashSet<AttributeValueId> =
+            dependencies_by_value.values().flatten().copied().collect();
+        let mut changed_values = root_value_ids.clone();
+        changed_values.extend(dependency_graph.independent_values());
+
         // Remove the first set of independent_values since they should already have had their functions executed
         for value_id in dependency_graph.independent_values() {
             if !dependency_graph.values_needs_to_execute_from_prototype_function(value_id)
@@ -279,68 +362,90 @@ impl DependentValuesUpdate {
 
         let mut tracker = StatusUpdateTracker::new_for_values(ctx, all_value_ids).await?;
 
+        let mut stats = DependentValuesUpdateStats::default();
         let mut spawned_ids = HashSet::new();
         let mut task_id_to_av_id = HashMap::new();
+        let mut before_views = HashMap::new();
         let mut update_join_set = JoinSet::new();
         let mut independent_value_ids: HashSet<AttributeValueId> =
             dependency_graph.independent_values().into_iter().collect();
-        let mut would_start_ids = HashSet::new();
 
         loop {
-            if independent_value_ids.is_empty() && task_id_to_av_id.is_empty() {
-                break;
-            }
+            let mut cut_off_values = false;
 
-            if independent_value_ids
-                .difference(&would_start_ids)
-                .next()
-                .is_none()
-            {
-                if task_id_to_av_id.is_empty() {
-                    break;
+            for attribute_value_id in &independent_value_ids {
+                let attribute_value_id = *attribute_value_id;
+                if spawned_ids.contains(&attribute_value_id) {
+                    continue;
                 }
-            } else {
-                for attribute_value_id in &independent_value_ids {
-                    let attribute_value_id = *attribute_value_id;
-                    let parent_span = span.clone();
-                    if !spawned_ids.contains(&attribute_value_id)
-                        && !would_start_ids.contains(&attribute_value_id)
-                    {
-                        let id = Ulid::new();
 
-                        if tracker.would_start_component(attribute_value_id)
-                            && tracker.active_components_count() >= concurrency_limit
-                        {
-                            would_start_ids.insert(attribute_value_id);
-                            continue;
+                // Early cutoff: if nothing this value depends on has changed, executing its
+                // function cannot change it either, so neither it nor anything downstream of it
+                // needs to run.
+                let inputs_unchanged = !root_value_ids.contains(&attribute_value_id)
+                    && !dependency_graph
+                        .values_needs_to_execute_from_prototype_function(attribute_value_id)
+                    && dependencies_by_value
+                        .get(&attribute_value_id)
+                        .is_some_and(|dependencies| {
+                            !dependencies.is_empty()
+                                && dependencies
+                                    .iter()
+                                    .all(|dependency| !changed_values.contains(dependency))
+                        });
+                if inputs_unchanged {
+                    dependency_graph.remove_value(attribute_value_id);
+                    stats.values_skipped_by_cutoff += 1;
+                    cut_off_values = true;
+                    metric!(counter.dvu.values_to_run = -1);
+
+                    if let Some(status_update) = tracker.skip_value(attribute_value_id) {
+                        if let Err(err) = send_status_update(ctx, status_update).await {
+                            error!(si.error.message = ?err, "status update finished event send failed for AttributeValue {attribute_value_id}");
                         }
-
-                        let status_update = tracker.get_status_update(
-                            StatusMessageState::StatusStarted,
-                            attribute_value_id,
-                        );
-
-                        let before_value = AttributeValue::get_by_id(ctx, attribute_value_id)
-                            .await?
-                            .value(ctx)
-                            .await?;
-
-                        update_join_set.spawn(values_from_prototype_function_execution(
-                            id,
-                            parent_span,
-                            ctx.clone(),
-                            attribute_value_id,
-                            before_value,
-                            self.set_value_lock.clone(),
-                            status_update,
-                        ));
-                        task_id_to_av_id.insert(id, attribute_value_id);
-                        spawned_ids.insert(attribute_value_id);
                     }
+                    continue;
+                }
+
+                // Values for components over the limit are picked up again once a running
+                // component finishes
+                if tracker.would_start_component(attribute_value_id)
+                    && tracker.active_components_count() >= concurrency_limit
+                {
+                    continue;
+                }
+
+                let status_update = tracker
+                    .get_status_update(StatusMessageState::StatusStarted, attribute_value_id);
+
+                let attribute_value = AttributeValue::get_by_id(ctx, attribute_value_id).await?;
+                let before_value = attribute_value.value(ctx).await?;
+                if values_with_dependents.contains(&attribute_value_id) {
+                    before_views.insert(attribute_value_id, attribute_value.view(ctx).await?);
                 }
+
+                let id = Ulid::new();
+                update_join_set.spawn(values_from_prototype_function_execution(
+                    id,
+                    span.clone(),
+                    ctx.clone(),
+                    attribute_value_id,
+                    before_value,
+                    self.set_value_lock.clone(),
+                    status_update,
+                ));
+                task_id_to_av_id.insert(id, attribute_value_id);
+                spawned_ids.insert(attribute_value_id);
+                stats.values_computed += 1;
+            }
+
+            // Cutting values off can make their dependents independent
+            if cut_off_values {
+                independent_value_ids = dependency_graph.independent_values().into_iter().collect();
+                continue;
             }
 
-            // Wait for a task to finish
+            // Wait for a task to finish, or stop if nothing is left running
             if let Some(join_result) = update_join_set.join_next().await {
                 let (task_id, execution_result, before_value) = join_result?;
 
@@ -355,6 +460,22 @@ impl DependentValuesUpdate {
                             // at the end of the scope.
                             let write_guard = self.set_value_lock.write().await;
 
+                            // Qualification failures are only audited when a qualification
+                            // starts failing, so its result from before this run is needed. Not
+                            // knowing it must not fail the update, so we'd rather audit the
+                            // failure again than miss it.
+                            let was_failing_qualification = func.kind == FuncKind::Qualification
+                                && audit_log::qualification_failed(ctx, finished_value_id)
+                                    .await
+                                    .unwrap_or_else(|err| {
+                                        error!(
+                                            si.error.message = ?err,
+                                            %finished_value_id,
+                                            "failed to determine whether qualification was failing",
+                                        );
+                                        false
+                                    });
+
                             // Only set values if their functions are actually
                             // "dependent". Other values may have been
                             // introduced to the attribute value graph because
@@ -380,6 +501,19 @@ impl DependentValuesUpdate {
                                 .await
                                 {
                                     Ok(_) => {
+                                        if let Some(before_view) =
+                                            before_views.remove(&finished_value_id)
+                                        {
+                                            let after_view =
+                                                AttributeValue::get_by_id(ctx, finished_value_id)
+                                                    .await?
+                                                    .view(ctx)
+                                                    .await?;
+                                            if after_view != before_view {
+                                                changed_values.insert(finished_value_id);
+                                            }
+                                        }
+
                                         // Remove the value, so that any values that depend on it will
                                         // become independent values (once all other dependencies are removed)
                                         dependency_graph.remove_value(finished_value_id);
@@ -392,6 +526,7 @@ impl DependentValuesUpdate {
                                             input_attribute_value_ids,
                                             func,
                                             before_value,
+                                            was_failing_qualification,
                                         )
                                         .await?;
                                     }
@@ -402,6 +537,9 @@ impl DependentValuesUpdate {
                                     }
                                 },
                                 Ok(false) => {
+                                    // Containers are only executed when something they depend
+                                    // on changed, so their views changed too
+                                    changed_values.insert(finished_value_id);
                                     dependency_graph.remove_value(finished_value_id);
                                 }
                                 Err(err) => {
@@ -431,6 +569,8 @@ impl DependentValuesUpdate {
                         }
                     }
                 }
+            } else {
+                break;
             }
 
             independent_value_ids = dependency_graph.independent_values().into_iter().collect();
@@ -465,7 +605,8 @@ impl DependentValuesUpdate {
             snap.take_dependent_values().await?;
         }
 
-        debug!("DependentValuesUpdate took: {:?}", start.elapsed());
+        stats.values_deferred = independent_value_ids.difference(&spawned_ids).count();
+        stats.record(&span, start.elapsed());
 
         ctx.commit().await?;
         metric!(counter.dvu_concurrency_count = -1);
@@ -579,7 +720,10 @@ pub mod audit_log {
 
     use crate::{
         attribute::value::{AttributeValueError, ValueIsFor},
+        component::qualification::QualificationEntry,
+        func::FuncKind,
         prop::PropError,
+        qualification::QualificationSubCheckStatus,
         socket::{input::InputSocketError, output::OutputSocketError},
         AttributeValue, AttributeValueId, Component, ComponentError, DalContext, Func, InputSocket,
         OutputSocket, Prop, TransactionsError,
@@ -598,6 +742,8 @@ pub mod audit_log {
         OutputSocket(#[from] OutputSocketError),
         #[error("prop error: {0}")]
         Prop(#[from] PropError),
+        #[error("serde json error: {0}")]
+        SerdeJson(#[from] serde_json::Error),
         #[error("write audit log error: {0}")]
         WriteAuditLog(#[source] TransactionsError),
     }
@@ -613,6 +759,7 @@ pub mod audit_log {
         input_attribute_value_ids: Vec<AttributeValueId>,
         func: Func,
         before_value: Option<serde_json::Value>,
+        was_failing_qualification: bool,
     ) -> Result<(), DependentValueUpdateAuditLogError> {
         // Metadata for who "owns" the attribute value.
         let component_id = AttributeValue::component_id(ctx, finished_value_id).await?;
@@ -627,6 +774,29 @@ pub mod audit_log {
             .await?;
         let is_for = AttributeValue::is_for(ctx, finished_value_id).await?;
 
+        // Qualifications that start failing get an audit log of their own so that they can be
+        // acted upon (e.g. by outbound webhooks) without inspecting every dependent value
+        // update. Qualifications that keep failing are not audited again.
+        if func.kind == FuncKind::Qualification && !was_failing_qualification {
+            if let Some(entry) = qualification_entry(ctx, finished_value_id).await? {
+                if entry.result == Some(QualificationSubCheckStatus::Failure) {
+                    ctx.write_audit_log(
+                        AuditLogKind::FailQualification {
+                            component_id,
+                            component_name: component_name.clone(),
+                            func_id: func.id,
+                            func_display_name: func.display_name.clone(),
+                            func_name: func.name.clone(),
+                            message: entry.message,
+                        },
+                        component_name.clone(),
+                    )
+                    .await
+                    .map_err(DependentValueUpdateAuditLogError::WriteAuditLog)?;
+                }
+            }
+        }
+
         // Write an audit log based on what the attribute value is for.
         match is_for {
             ValueIsFor::InputSocket(input_socket_id) => {
@@ -708,4 +878,28 @@ pub mod audit_log {
 
         Ok(())
     }
+
+    /// Returns whether the qualification set by the attribute value is currently failing.
+    pub async fn qualification_failed(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+    ) -> Result<bool, DependentValueUpdateAuditLogError> {
+        Ok(qualification_entry(ctx, attribute_value_id)
+            .await?
+            .is_some_and(|entry| entry.result == Some(QualificationSubCheckStatus::Failure)))
+    }
+
+    async fn qualification_entry(
+        ctx: &DalContext,
+        attribute_value_id: AttributeValueId,
+    ) -> Result<Option<QualificationEntry>, DependentValueUpdateAuditLogError> {
+        match AttributeValue::get_by_id(ctx, attribute_value_id)
+            .await?
+            .view(ctx)
+            .await?
+        {
+            Some(entry) => Ok(Some(serde_json::from_value(entry)?)),
+            None => Ok(None),
+        }
+    }
 }
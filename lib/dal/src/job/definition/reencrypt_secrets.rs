@@ -0,0 +1,115 @@
+use std::convert::TryFrom;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    job::{
+        consumer::{
+            JobCompletionState, JobConsumer, JobConsumerError, JobConsumerMetadata,
+            JobConsumerResult, JobInfo,
+        },
+        producer::{JobProducer, JobProducerResult},
+    },
+    AccessBuilder, DalContext, Secret, Visibility,
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ReencryptSecretsArgs;
+
+impl From<ReencryptSecrets> for ReencryptSecretsArgs {
+    fn from(_value: ReencryptSecrets) -> Self {
+        Self
+    }
+}
+
+/// Re-encrypts every [`Secret`] in a change set whose encrypted contents were encrypted with a
+/// [`KeyPair`](crate::KeyPair) other than the workspace's current one (or with a symmetric key
+/// other than the active one). Once this has run in every open change set, old keys can be
+/// retired.
+#[derive(Clone, Debug, Serialize)]
+pub struct ReencryptSecrets {
+    access_builder: AccessBuilder,
+    visibility: Visibility,
+    job: Option<JobInfo>,
+}
+
+impl ReencryptSecrets {
+    pub fn new(ctx: &DalContext) -> Box<Self> {
+        let access_builder = ctx.access_builder();
+        let visibility = *ctx.visibility();
+
+        Box::new(Self {
+            access_builder,
+            visibility,
+            job: None,
+        })
+    }
+}
+
+impl JobProducer for ReencryptSecrets {
+    fn arg(&self) -> JobProducerResult<serde_json::Value> {
+        Ok(serde_json::to_value(ReencryptSecretsArgs::from(
+            self.clone(),
+        ))?)
+    }
+}
+
+impl JobConsumerMetadata for ReencryptSecrets {
+    fn type_name(&self) -> String {
+        "ReencryptSecrets".to_string()
+    }
+
+    fn access_builder(&self) -> AccessBuilder {
+        self.access_builder
+    }
+
+    fn visibility(&self) -> Visibility {
+        self.visibility
+    }
+}
+
+#[async_trait]
+impl JobConsumer for ReencryptSecrets {
+    #[instrument(
+        name = "reencrypt_secrets.run",
+        skip_all,
+        level = "info",
+        fields(
+            si.secret.reencrypted_count = Empty,
+        )
+    )]
+    async fn run(&self, ctx: &mut DalContext) -> JobConsumerResult<JobCompletionState> {
+        let span = current_span_for_instrument_at!("info");
+
+        let mut reencrypted_count: usize = 0;
+        for secret in Secret::list(ctx).await? {
+            let secret_id = secret.id();
+            if secret.reencrypt_with_current_keys(ctx).await?.is_some() {
+                debug!(si.secret.id = %secret_id, "re-encrypted secret with current keys");
+                reencrypted_count += 1;
+            }
+        }
+
+        span.record("si.secret.reencrypted_count", reencrypted_count);
+
+        if reencrypted_count > 0 {
+            ctx.commit().await?;
+        }
+
+        Ok(JobCompletionState::Done)
+    }
+}
+
+impl TryFrom<JobInfo> for ReencryptSecrets {
+    type Error = JobConsumerError;
+
+    fn try_from(job: JobInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            access_builder: job.access_builder,
+            visibility: job.visibility,
+            job: Some(job),
+        })
+    }
+}
@@ -222,15 +222,21 @@ pub struct AttributePrototypeContentV1 {
 pub enum ComponentContent {
     V1(ComponentContentV1),
     V2(ComponentContentV2),
+    V3(ComponentContentV3),
 }
 
 impl ComponentContent {
-    pub fn extract(self) -> ComponentContentV2 {
+    pub fn extract(self) -> ComponentContentV3 {
         match self {
-            ComponentContent::V1(v1) => ComponentContentV2 {
+            ComponentContent::V1(v1) => ComponentContentV3 {
                 timestamp: v1.timestamp,
+                protected: false,
             },
-            ComponentContent::V2(v2) => v2,
+            ComponentContent::V2(v2) => ComponentContentV3 {
+                timestamp: v2.timestamp,
+                protected: false,
+            },
+            ComponentContent::V3(v3) => v3,
         }
     }
 }
@@ -249,6 +255,13 @@ pub struct ComponentContentV2 {
     pub timestamp: Timestamp,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ComponentContentV3 {
+    pub timestamp: Timestamp,
+    /// Protected components cannot be deleted, erased or have destroy actions enqueued.
+    pub protected: bool,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum ViewContent {
     V1(ViewContentV1),
@@ -503,6 +516,22 @@ pub struct PropContentV1 {
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum SchemaContent {
     V1(SchemaContentV1),
+    V2(SchemaContentV2),
+}
+
+impl SchemaContent {
+    pub fn extract(self) -> SchemaContentV2 {
+        match self {
+            SchemaContent::V1(v1) => SchemaContentV2 {
+                timestamp: v1.timestamp,
+                name: v1.name,
+                ui_hidden: v1.ui_hidden,
+                is_builtin: v1.is_builtin,
+                protect_components: false,
+            },
+            SchemaContent::V2(v2) => v2,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -513,6 +542,16 @@ pub struct SchemaContentV1 {
     pub is_builtin: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct SchemaContentV2 {
+    pub timestamp: Timestamp,
+    pub name: String,
+    pub ui_hidden: bool,
+    pub is_builtin: bool,
+    /// Whether new components of this schema start out protected.
+    pub protect_components: bool,
+}
+
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum SchemaVariantContent {
     V1(SchemaVariantContentV1),
@@ -675,6 +714,21 @@ pub struct ValidationContentV1 {
 #[derive(Debug, Clone, EnumDiscriminants, Serialize, Deserialize, PartialEq)]
 pub enum ManagementPrototypeContent {
     V1(ManagementPrototypeContentV1),
+    V2(ManagementPrototypeContentV2),
+}
+
+impl ManagementPrototypeContent {
+    pub fn extract(self) -> ManagementPrototypeContentV2 {
+        match self {
+            ManagementPrototypeContent::V1(v1) => ManagementPrototypeContentV2 {
+                name: v1.name,
+                managed_schemas: v1.managed_schemas,
+                description: v1.description,
+                tag_selectors: None,
+            },
+            ManagementPrototypeContent::V2(v2) => v2,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -683,3 +737,13 @@ pub struct ManagementPrototypeContentV1 {
     pub managed_schemas: Option<HashSet<SchemaId>>,
     pub description: Option<String>,
 }
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct ManagementPrototypeContentV2 {
+    pub name: String,
+    pub managed_schemas: Option<HashSet<SchemaId>>,
+    pub description: Option<String>,
+    /// Tag selectors, such as `env=prod`. Components matching every selector are passed to the
+    /// management function alongside the components it manages.
+    pub tag_selectors: Option<Vec<String>>,
+}
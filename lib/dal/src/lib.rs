@@ -51,6 +51,7 @@ pub mod prop;
 pub mod property_editor;
 pub mod qualification;
 pub mod resource_metadata;
+pub mod revoked_auth_token;
 pub mod schema;
 pub mod secret;
 pub mod serde_impls;
@@ -1633,7 +1633,7 @@ const IGNORE_PATHS: [&[&str]; 6] = [
 
 const ROOT_SI_TYPE_PATH: &[&str] = &["root", "si", "type"];
 
-async fn update_component(
+pub(crate) async fn update_component(
     ctx: &DalContext,
     component_id: ComponentId,
     properties: &serde_json::Value,
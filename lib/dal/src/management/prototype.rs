@@ -1,7 +1,8 @@
 //! A [`ManagementPrototype`] points to a Management [`Func`] for a schema variant
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
+    str::FromStr,
     sync::Arc,
 };
 
@@ -12,6 +13,7 @@ use veritech_client::{ManagementFuncStatus, ManagementResultSuccess};
 
 use crate::{
     cached_module::{CachedModule, CachedModuleError},
+    component::tag::{ComponentTag, ComponentTagError, TagSelector},
     diagram::{
         geometry::Geometry,
         view::{View, ViewId},
@@ -19,7 +21,7 @@ use crate::{
     },
     func::runner::{FuncRunner, FuncRunnerError},
     implement_add_edge_to,
-    layer_db_types::{ManagementPrototypeContent, ManagementPrototypeContentV1},
+    layer_db_types::{ManagementPrototypeContent, ManagementPrototypeContentV2},
     workspace_snapshot::node_weight::{traits::SiVersionedNodeWeight, NodeWeight},
     Component, ComponentError, ComponentId, DalContext, EdgeWeightKind,
     EdgeWeightKindDiscriminants, FuncId, HelperError, NodeWeightDiscriminants, Schema, SchemaError,
@@ -36,6 +38,8 @@ pub enum ManagementPrototypeError {
     CachedModule(#[from] CachedModuleError),
     #[error("component error: {0}")]
     Component(#[from] ComponentError),
+    #[error("component tag error: {0}")]
+    ComponentTag(#[from] ComponentTagError),
     #[error("diagram error: {0}")]
     Diagram(#[from] DiagramError),
     #[error("func runner error: {0}")]
@@ -79,14 +83,46 @@ pub struct ManagementPrototype {
     pub managed_schemas: Option<HashSet<SchemaId>>,
     pub name: String,
     pub description: Option<String>,
+    /// Components whose tags match every selector are passed to the function as read-only
+    /// `selectedComponents`, alongside the components it manages.
+    pub tag_selectors: Option<Vec<TagSelector>>,
 }
 
 impl From<ManagementPrototype> for ManagementPrototypeContent {
     fn from(value: ManagementPrototype) -> Self {
-        Self::V1(ManagementPrototypeContentV1 {
+        Self::V2(ManagementPrototypeContentV2 {
             name: value.name,
             managed_schemas: value.managed_schemas,
             description: value.description,
+            tag_selectors: value
+                .tag_selectors
+                .map(|selectors| selectors.iter().map(ToString::to_string).collect()),
+        })
+    }
+}
+
+impl TryFrom<(ManagementPrototypeId, ManagementPrototypeContentV2)> for ManagementPrototype {
+    type Error = ManagementPrototypeError;
+
+    fn try_from(
+        (id, content): (ManagementPrototypeId, ManagementPrototypeContentV2),
+    ) -> ManagementPrototypeResult<Self> {
+        let tag_selectors = match content.tag_selectors {
+            Some(selectors) => Some(
+                selectors
+                    .iter()
+                    .map(|selector| TagSelector::from_str(selector))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            id,
+            managed_schemas: content.managed_schemas,
+            name: content.name,
+            description: content.description,
+            tag_selectors,
         })
     }
 }
@@ -106,6 +142,7 @@ pub struct ManagedComponent {
     kind: String,
     properties: Option<serde_json::Value>,
     geometry: HashMap<String, ManagementGeometry>,
+    tags: BTreeMap<String, String>,
 }
 
 async fn build_management_geometry_map(
@@ -137,11 +174,13 @@ impl ManagedComponent {
         let component = Component::get_by_id(ctx, component_id).await?;
         let properties = component.view(ctx).await?;
         let geometry = build_management_geometry_map(ctx, component_id, views).await?;
+        let tags = ComponentTag::list_for_component(ctx, component_id).await?;
 
         Ok(Self {
             kind: kind.to_owned(),
             properties,
             geometry,
+            tags,
         })
     }
 }
@@ -171,6 +210,10 @@ impl ManagementPrototype {
         self.description.as_deref()
     }
 
+    pub fn tag_selectors(&self) -> Option<&[TagSelector]> {
+        self.tag_selectors.as_deref()
+    }
+
     pub async fn schema_id(&self, ctx: &DalContext) -> ManagementPrototypeResult<Option<SchemaId>> {
         let snapshot = ctx.workspace_snapshot()?;
 
@@ -238,16 +281,17 @@ impl ManagementPrototype {
         managed_schemas: Option<HashSet<SchemaId>>,
         schema_variant_id: SchemaVariantId,
     ) -> ManagementPrototypeResult<Self> {
-        let content = ManagementPrototypeContentV1 {
+        let content = ManagementPrototypeContentV2 {
             name: name.clone(),
             managed_schemas: managed_schemas
                 .clone()
                 .map(|schemas| schemas.into_iter().map(Into::into).collect()),
             description: description.clone(),
+            tag_selectors: None,
         };
 
         let (hash, _) = ctx.layer_db().cas().write(
-            Arc::new(ManagementPrototypeContent::V1(content).into()),
+            Arc::new(ManagementPrototypeContent::V2(content).into()),
             None,
             ctx.events_tenancy(),
             ctx.events_actor(),
@@ -274,6 +318,7 @@ impl ManagementPrototype {
             name,
             managed_schemas,
             description,
+            tag_selectors: None,
         })
     }
 
@@ -302,14 +347,7 @@ impl ManagementPrototype {
                 .await?;
         }
 
-        let ManagementPrototypeContent::V1(inner) = updated;
-
-        Ok(Self {
-            id: proto_id,
-            managed_schemas: inner.managed_schemas,
-            name: inner.name,
-            description: inner.description,
-        })
+        (proto_id, updated.extract()).try_into()
     }
 
     pub async fn get_by_id(
@@ -336,16 +374,7 @@ impl ManagementPrototype {
             .await?
             .ok_or(WorkspaceSnapshotError::MissingContentFromStore(id.into()))?;
 
-        let ManagementPrototypeContent::V1(content_inner) = content;
-
-        Ok(Some(Self {
-            id,
-            managed_schemas: content_inner
-                .managed_schemas
-                .map(|schemas| schemas.into_iter().map(Into::into).collect()),
-            name: content_inner.name,
-            description: content_inner.description,
-        }))
+        Ok(Some((id, content.extract()).try_into()?))
     }
 
     pub async fn func_id(
@@ -420,6 +449,26 @@ impl ManagementPrototype {
             }
         }
 
+        // Components picked out by tag are inputs only. They get no placeholders, so the
+        // function cannot update, delete or connect to them unless it also manages them.
+        let mut selected_components = HashMap::new();
+        if let Some(selectors) = prototype.tag_selectors() {
+            for component_id in ComponentTag::list_component_ids_matching(ctx, selectors).await? {
+                if component_id == manager_component_id {
+                    continue;
+                }
+
+                let schema_name = Component::schema_for_component_id(ctx, component_id)
+                    .await?
+                    .name()
+                    .to_owned();
+                let selected_component =
+                    ManagedComponent::new(ctx, component_id, &schema_name, &views).await?;
+
+                selected_components.insert(component_id, selected_component);
+            }
+        }
+
         let this_schema = Component::schema_for_component_id(ctx, manager_component_id)
             .await?
             .name()
@@ -433,6 +482,7 @@ impl ManagementPrototype {
             "current_view": current_view,
             "this_component": manager_component,
             "components": managed_components,
+            "selected_components": selected_components,
         });
 
         let result_channel =
@@ -635,6 +685,20 @@ impl ManagementPrototype {
         Ok(None)
     }
 
+    pub async fn set_tag_selectors(
+        self,
+        ctx: &DalContext,
+        tag_selectors: Option<Vec<TagSelector>>,
+    ) -> ManagementPrototypeResult<()> {
+        self.modify(ctx, |proto| {
+            proto.tag_selectors = tag_selectors.filter(|selectors| !selectors.is_empty());
+            Ok(())
+        })
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn set_managed_schemas(
         self,
         ctx: &DalContext,
@@ -0,0 +1,8 @@
+CREATE TABLE revoked_auth_tokens
+(
+    token_id                    text NOT NULL,
+    workspace_pk                ident NOT NULL,
+    revoked_by_user_pk          ident NULL,
+    revoked_at                  timestamp with time zone NOT NULL DEFAULT clock_timestamp(),
+    PRIMARY KEY (workspace_pk, token_id)
+);
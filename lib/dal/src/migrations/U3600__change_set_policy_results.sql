@@ -0,0 +1,16 @@
+CREATE TABLE change_set_policy_results
+(
+    change_set_id               ident NOT NULL,
+    workspace_pk                ident NOT NULL,
+    func_id                     ident NOT NULL,
+    func_name                   text NOT NULL,
+    status                      text NOT NULL,
+    message                     text NULL,
+    violations                  jsonb NOT NULL DEFAULT '[]'::jsonb,
+    func_run_id                 ident NULL,
+    evaluated_at                timestamp with time zone NOT NULL DEFAULT clock_timestamp(),
+    overridden_by_user_pk       ident NULL,
+    overridden_at               timestamp with time zone NULL,
+    PRIMARY KEY (change_set_id, func_id)
+);
+CREATE INDEX ON change_set_policy_results (workspace_pk);
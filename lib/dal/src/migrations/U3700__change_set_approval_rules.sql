@@ -0,0 +1,24 @@
+CREATE TABLE change_set_approval_rules
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    name                        text NOT NULL,
+    required_approvals          integer NOT NULL DEFAULT 1,
+    schema_names                text[] NOT NULL DEFAULT '{}',
+    view_ids                    text[] NOT NULL DEFAULT '{}',
+    match_funcs                 boolean NOT NULL DEFAULT false,
+    approver_user_pks           text[] NOT NULL DEFAULT '{}',
+    allow_self_approval         boolean NOT NULL DEFAULT true,
+    created_at                  timestamp with time zone NOT NULL DEFAULT clock_timestamp()
+);
+CREATE INDEX ON change_set_approval_rules (workspace_pk);
+
+CREATE TABLE change_set_approvals
+(
+    change_set_id               ident NOT NULL,
+    workspace_pk                ident NOT NULL,
+    user_pk                     ident NOT NULL,
+    approved_at                 timestamp with time zone NOT NULL DEFAULT clock_timestamp(),
+    PRIMARY KEY (change_set_id, user_pk)
+);
+CREATE INDEX ON change_set_approvals (workspace_pk);
@@ -0,0 +1,14 @@
+CREATE TABLE change_set_scheduled_applies
+(
+    change_set_id               ident primary key,
+    workspace_pk                ident NOT NULL,
+    window_start                timestamp with time zone NOT NULL,
+    window_end                  timestamp with time zone,
+    scheduled_by_user_pk        ident NOT NULL,
+    scheduled_at                timestamp with time zone NOT NULL DEFAULT clock_timestamp(),
+    status                      text NOT NULL,
+    message                     text,
+    completed_at                timestamp with time zone
+);
+CREATE INDEX ON change_set_scheduled_applies (workspace_pk);
+CREATE INDEX ON change_set_scheduled_applies (status, window_start);
@@ -0,0 +1,28 @@
+CREATE TABLE change_set_comment_threads
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    change_set_id               ident NOT NULL,
+    component_id                ident,
+    attribute_path              text,
+    created_by_user_pk          ident NOT NULL,
+    created_at                  timestamp with time zone NOT NULL DEFAULT clock_timestamp(),
+    resolved_by_user_pk         ident,
+    resolved_at                 timestamp with time zone
+);
+CREATE INDEX ON change_set_comment_threads (change_set_id);
+CREATE INDEX ON change_set_comment_threads (workspace_pk);
+
+CREATE TABLE change_set_comments
+(
+    id                          ident primary key default ident_create_v1(),
+    thread_id                   ident NOT NULL REFERENCES change_set_comment_threads (id) ON DELETE CASCADE,
+    workspace_pk                ident NOT NULL,
+    change_set_id               ident NOT NULL,
+    author_user_pk              ident NOT NULL,
+    body                        text NOT NULL,
+    mentioned_user_pks          text[] NOT NULL DEFAULT '{}',
+    created_at                  timestamp with time zone NOT NULL DEFAULT clock_timestamp(),
+    edited_at                   timestamp with time zone
+);
+CREATE INDEX ON change_set_comments (thread_id);
@@ -0,0 +1,9 @@
+CREATE TABLE func_run_retention_policies
+(
+    workspace_pk                ident NOT NULL,
+    function_kind               text NOT NULL,
+    retention_days              integer NOT NULL,
+    keep_latest                 boolean NOT NULL DEFAULT true,
+    updated_at                  timestamp with time zone NOT NULL DEFAULT clock_timestamp(),
+    PRIMARY KEY (workspace_pk, function_kind)
+);
@@ -0,0 +1,5 @@
+ALTER TABLE change_set_scheduled_applies
+    ADD COLUMN claimed_at              timestamp with time zone,
+    ADD COLUMN claim_token             bigint NOT NULL DEFAULT 0,
+    ADD COLUMN scheduled_with_token_id text;
+CREATE INDEX ON change_set_scheduled_applies (status, claimed_at);
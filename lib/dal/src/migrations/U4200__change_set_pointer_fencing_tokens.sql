@@ -0,0 +1 @@
+ALTER TABLE change_set_pointers ADD COLUMN fencing_token bigint NOT NULL DEFAULT 0;
@@ -0,0 +1,15 @@
+CREATE TABLE change_set_approver_groups
+(
+    id                          ident primary key default ident_create_v1(),
+    workspace_pk                ident NOT NULL,
+    name                        text NOT NULL,
+    member_user_pks             text[] NOT NULL DEFAULT '{}',
+    created_at                  timestamp with time zone NOT NULL DEFAULT clock_timestamp()
+);
+CREATE INDEX ON change_set_approver_groups (workspace_pk);
+
+ALTER TABLE change_set_approval_rules ADD COLUMN approver_group_ids text[] NOT NULL DEFAULT '{}';
+
+-- Approvals only count for the snapshot they were given against. Existing approvals predate this
+-- and so have to be given again.
+ALTER TABLE change_set_approvals ADD COLUMN approved_snapshot_address text;
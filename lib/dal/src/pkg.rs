@@ -153,6 +153,19 @@ impl From<FuncBackendKind> for FuncSpecBackendKind {
             FuncBackendKind::Validation => Self::Validation,
             FuncBackendKind::JsAuthentication => Self::JsAuthentication,
             FuncBackendKind::Management => Self::Management,
+            FuncBackendKind::JsPolicy => Self::JsPolicy,
+            FuncBackendKind::Wasm => Self::Wasm,
+            FuncBackendKind::Base64Decode => Self::Base64Decode,
+            FuncBackendKind::Base64Encode => Self::Base64Encode,
+            FuncBackendKind::BuildArray => Self::BuildArray,
+            FuncBackendKind::BuildMap => Self::BuildMap,
+            FuncBackendKind::Concat => Self::Concat,
+            FuncBackendKind::DefaultIfEmpty => Self::DefaultIfEmpty,
+            FuncBackendKind::JsonPointer => Self::JsonPointer,
+            FuncBackendKind::Lowercase => Self::Lowercase,
+            FuncBackendKind::RegexExtract => Self::RegexExtract,
+            FuncBackendKind::Template => Self::Template,
+            FuncBackendKind::Uppercase => Self::Uppercase,
         }
     }
 }
@@ -178,6 +191,19 @@ impl From<FuncSpecBackendKind> for FuncBackendKind {
             FuncSpecBackendKind::Validation => Self::Validation,
             FuncSpecBackendKind::JsAuthentication => Self::JsAuthentication,
             FuncSpecBackendKind::Management => Self::Management,
+            FuncSpecBackendKind::JsPolicy => Self::JsPolicy,
+            FuncSpecBackendKind::Wasm => Self::Wasm,
+            FuncSpecBackendKind::Base64Decode => Self::Base64Decode,
+            FuncSpecBackendKind::Base64Encode => Self::Base64Encode,
+            FuncSpecBackendKind::BuildArray => Self::BuildArray,
+            FuncSpecBackendKind::BuildMap => Self::BuildMap,
+            FuncSpecBackendKind::Concat => Self::Concat,
+            FuncSpecBackendKind::DefaultIfEmpty => Self::DefaultIfEmpty,
+            FuncSpecBackendKind::JsonPointer => Self::JsonPointer,
+            FuncSpecBackendKind::Lowercase => Self::Lowercase,
+            FuncSpecBackendKind::RegexExtract => Self::RegexExtract,
+            FuncSpecBackendKind::Template => Self::Template,
+            FuncSpecBackendKind::Uppercase => Self::Uppercase,
         }
     }
 }
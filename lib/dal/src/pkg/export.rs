@@ -982,9 +982,13 @@ impl PkgExporter {
             let intrinsic_name = intrinsic.name();
             // We need a unique id for intrinsic funcs to refer to them in custom bindings (for example
             // mapping one prop to another via si:identity)
-            let intrinsic_func_id = Func::find_id_by_name(ctx, intrinsic_name)
-                .await?
-                .ok_or(PkgError::MissingIntrinsicFunc(intrinsic_name.to_string()))?;
+            let intrinsic_func_id = match Func::find_id_by_name(ctx, intrinsic_name).await? {
+                Some(func_id) => func_id,
+                // Workspaces that predate the transforms only get them once the startup
+                // intrinsics migration has run, so a missing transform is not an error
+                None if intrinsic.is_transform() => continue,
+                None => return Err(PkgError::MissingIntrinsicFunc(intrinsic_name.to_string())),
+            };
 
             let intrinsic_func = Func::get_by_id_or_error(ctx, intrinsic_func_id).await?;
 
@@ -1063,9 +1067,16 @@ impl PkgExporter {
     async fn export_intrinsics(&mut self, ctx: &DalContext) -> PkgResult<Vec<FuncSpec>> {
         let mut funcs = vec![];
         for instrinsic in IntrinsicFunc::iter() {
-            let intrinsic_func_id = Func::find_id_by_name(ctx, instrinsic.name()).await?.ok_or(
-                PkgError::MissingIntrinsicFunc(instrinsic.name().to_string()),
-            )?;
+            let intrinsic_func_id = match Func::find_id_by_name(ctx, instrinsic.name()).await? {
+                Some(func_id) => func_id,
+                // Older workspaces lack transforms until the startup intrinsics migration runs
+                None if instrinsic.is_transform() => continue,
+                None => {
+                    return Err(PkgError::MissingIntrinsicFunc(
+                        instrinsic.name().to_string(),
+                    ))
+                }
+            };
 
             let spec = instrinsic.to_spec()?;
             funcs.push(spec.clone());
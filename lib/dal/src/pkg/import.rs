@@ -1071,6 +1071,36 @@ pub async fn import_only_new_funcs(
     Ok(thing_map)
 }
 
+/// Imports (locked) every func spec whose name is not already taken by a func in the workspace,
+/// along with its arguments. Funcs that already exist are left alone, so this is safe to run
+/// repeatedly.
+pub async fn import_funcs_missing_by_name(
+    ctx: &DalContext,
+    funcs: Vec<SiPkgFunc<'_>>,
+) -> PkgResult<Vec<Func>> {
+    let mut thing_map = ThingMap::new();
+    let mut imported = vec![];
+
+    for func_spec in funcs {
+        if Func::find_id_by_name(ctx, func_spec.name())
+            .await?
+            .is_some()
+        {
+            continue;
+        }
+
+        let func = import_func(ctx, &func_spec, None, &mut thing_map, false).await?;
+        let args = func_spec.arguments()?;
+        if !args.is_empty() {
+            import_func_arguments(ctx, func.id, &args, &mut thing_map).await?;
+        }
+
+        imported.push(func);
+    }
+
+    Ok(imported)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn import_schema_variant(
     ctx: &DalContext,
@@ -0,0 +1,140 @@
+//! A denylist of revoked automation tokens, checked whenever sdf validates a token.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, PgPool, PgPoolError, PgRow};
+use thiserror::Error;
+
+use crate::{DalContext, HistoryActor, TransactionsError, UserPk, WorkspacePk};
+
+#[remain::sorted]
+#[derive(Error, Debug)]
+pub enum RevokedAuthTokenError {
+    #[error(transparent)]
+    Pg(#[from] PgError),
+    #[error(transparent)]
+    PgPool(#[from] PgPoolError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+pub type RevokedAuthTokenResult<T> = Result<T, RevokedAuthTokenError>;
+
+const IS_REVOKED_QUERY: &str =
+    "SELECT token_id FROM revoked_auth_tokens WHERE workspace_pk = $1 AND token_id = $2";
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RevokedAuthToken {
+    token_id: String,
+    workspace_pk: WorkspacePk,
+    revoked_by_user_pk: Option<UserPk>,
+    revoked_at: DateTime<Utc>,
+}
+
+impl TryFrom<PgRow> for RevokedAuthToken {
+    type Error = RevokedAuthTokenError;
+
+    fn try_from(row: PgRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            token_id: row.try_get("token_id")?,
+            workspace_pk: row.try_get("workspace_pk")?,
+            revoked_by_user_pk: row.try_get("revoked_by_user_pk")?,
+            revoked_at: row.try_get("revoked_at")?,
+        })
+    }
+}
+
+impl RevokedAuthToken {
+    pub fn token_id(&self) -> &str {
+        &self.token_id
+    }
+
+    pub fn workspace_pk(&self) -> WorkspacePk {
+        self.workspace_pk
+    }
+
+    pub fn revoked_by_user_pk(&self) -> Option<UserPk> {
+        self.revoked_by_user_pk
+    }
+
+    pub fn revoked_at(&self) -> DateTime<Utc> {
+        self.revoked_at
+    }
+
+    /// Adds a token, identified by its JWT id, to the denylist for the current workspace. Revoking a token twice
+    /// keeps the original record.
+    pub async fn revoke(
+        ctx: &DalContext,
+        token_id: impl AsRef<str>,
+    ) -> RevokedAuthTokenResult<Self> {
+        let workspace_pk = ctx.workspace_pk()?;
+        let revoked_by_user_pk = match ctx.history_actor() {
+            HistoryActor::User(user_pk) => Some(*user_pk),
+            HistoryActor::SystemInit => None,
+        };
+
+        let row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_one(
+                "INSERT INTO revoked_auth_tokens (token_id, workspace_pk, revoked_by_user_pk)
+                   VALUES ($1, $2, $3)
+                   ON CONFLICT (workspace_pk, token_id) DO UPDATE SET token_id = EXCLUDED.token_id
+                   RETURNING *",
+                &[&token_id.as_ref(), &workspace_pk, &revoked_by_user_pk],
+            )
+            .await?;
+
+        Self::try_from(row)
+    }
+
+    /// Whether the token has been revoked in the current workspace.
+    pub async fn is_revoked(
+        ctx: &DalContext,
+        token_id: impl AsRef<str>,
+    ) -> RevokedAuthTokenResult<bool> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let maybe_row = ctx
+            .txns()
+            .await?
+            .pg()
+            .query_opt(IS_REVOKED_QUERY, &[&workspace_pk, &token_id.as_ref()])
+            .await?;
+
+        Ok(maybe_row.is_some())
+    }
+
+    /// Checks the denylist straight from the pool, for when a token is validated on every request and building a
+    /// [`DalContext`] for it would be wasteful.
+    pub async fn is_revoked_in_pool(
+        pg_pool: &PgPool,
+        workspace_pk: WorkspacePk,
+        token_id: impl AsRef<str>,
+    ) -> RevokedAuthTokenResult<bool> {
+        let maybe_row = pg_pool
+            .get()
+            .await?
+            .query_opt(IS_REVOKED_QUERY, &[&workspace_pk, &token_id.as_ref()])
+            .await?;
+
+        Ok(maybe_row.is_some())
+    }
+
+    pub async fn list_for_workspace(ctx: &DalContext) -> RevokedAuthTokenResult<Vec<Self>> {
+        let workspace_pk = ctx.workspace_pk()?;
+
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM revoked_auth_tokens WHERE workspace_pk = $1 ORDER BY revoked_at DESC",
+                &[&workspace_pk],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
+}
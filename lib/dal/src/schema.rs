@@ -11,7 +11,7 @@ use tokio::sync::TryLockError;
 
 use crate::cached_module::{CachedModule, CachedModuleError};
 use crate::change_set::ChangeSetError;
-use crate::layer_db_types::{SchemaContent, SchemaContentDiscriminants, SchemaContentV1};
+use crate::layer_db_types::{SchemaContent, SchemaContentDiscriminants, SchemaContentV2};
 use crate::pkg::{import_pkg_from_pkg, ImportOptions, PkgError};
 use crate::workspace_snapshot::content_address::{ContentAddress, ContentAddressDiscriminants};
 use crate::workspace_snapshot::edge_weight::{
@@ -30,7 +30,7 @@ pub use variant::{SchemaVariant, SchemaVariantId};
 pub mod variant;
 pub mod view;
 
-pub const SCHEMA_VERSION: SchemaContentDiscriminants = SchemaContentDiscriminants::V1;
+pub const SCHEMA_VERSION: SchemaContentDiscriminants = SchemaContentDiscriminants::V2;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -77,27 +77,32 @@ pub struct Schema {
     pub name: String,
     pub ui_hidden: bool,
     pub is_builtin: bool,
+    /// Whether new [`Components`](crate::Component) of this schema start out
+    /// [protected](crate::Component::protected).
+    pub protect_components: bool,
 }
 
-impl From<Schema> for SchemaContentV1 {
+impl From<Schema> for SchemaContentV2 {
     fn from(value: Schema) -> Self {
         Self {
             timestamp: value.timestamp,
             name: value.name,
             ui_hidden: value.ui_hidden,
             is_builtin: value.is_builtin,
+            protect_components: value.protect_components,
         }
     }
 }
 
 impl Schema {
-    pub fn assemble(id: SchemaId, inner: SchemaContentV1) -> Self {
+    pub fn assemble(id: SchemaId, inner: SchemaContentV2) -> Self {
         Self {
             id,
             timestamp: inner.timestamp,
             name: inner.name,
             ui_hidden: inner.ui_hidden,
             is_builtin: inner.is_builtin,
+            protect_components: inner.protect_components,
         }
     }
 
@@ -114,6 +119,9 @@ impl Schema {
     pub fn ui_hidden(&self) -> bool {
         self.ui_hidden
     }
+    pub fn protect_components(&self) -> bool {
+        self.protect_components
+    }
 
     implement_add_edge_to!(
         source_id: SchemaId,
@@ -133,15 +141,16 @@ impl Schema {
         id: SchemaId,
         name: impl Into<String>,
     ) -> SchemaResult<Self> {
-        let content = SchemaContentV1 {
+        let content = SchemaContentV2 {
             timestamp: Timestamp::now(),
             name: name.into(),
             ui_hidden: false,
             is_builtin: false,
+            protect_components: false,
         };
 
         let (hash, _) = ctx.layer_db().cas().write(
-            Arc::new(SchemaContent::V1(content.clone()).into()),
+            Arc::new(SchemaContent::V2(content.clone()).into()),
             None,
             ctx.events_tenancy(),
             ctx.events_actor(),
@@ -345,10 +354,7 @@ impl Schema {
             .await?
             .ok_or(WorkspaceSnapshotError::MissingContentFromStore(id.into()))?;
 
-        // NOTE(nick,jacob,zack): if we had a v2, then there would be migration logic here.
-        let SchemaContent::V1(inner) = content;
-
-        Ok(Some(Self::assemble(id, inner)))
+        Ok(Some(Self::assemble(id, content.extract())))
     }
 
     pub async fn get_by_id_or_error(ctx: &DalContext, id: SchemaId) -> SchemaResult<Self> {
@@ -365,10 +371,7 @@ impl Schema {
             .await?
             .ok_or(WorkspaceSnapshotError::MissingContentFromStore(id.into()))?;
 
-        // NOTE(nick,jacob,zack): if we had a v2, then there would be migration logic here.
-        let SchemaContent::V1(inner) = content;
-
-        Ok(Self::assemble(id, inner))
+        Ok(Self::assemble(id, content.extract()))
     }
 
     pub async fn modify<L>(self, ctx: &DalContext, lambda: L) -> SchemaResult<Self>
@@ -377,13 +380,13 @@ impl Schema {
     {
         let mut schema = self;
 
-        let before = SchemaContentV1::from(schema.clone());
+        let before = SchemaContentV2::from(schema.clone());
         lambda(&mut schema)?;
-        let updated = SchemaContentV1::from(schema.clone());
+        let updated = SchemaContentV2::from(schema.clone());
 
         if updated != before {
             let (hash, _) = ctx.layer_db().cas().write(
-                Arc::new(SchemaContent::V1(updated.clone()).into()),
+                Arc::new(SchemaContent::V2(updated.clone()).into()),
                 None,
                 ctx.events_tenancy(),
                 ctx.events_actor(),
@@ -432,10 +435,10 @@ impl Schema {
         for node_weight in schema_node_weights {
             match schema_contents.get(&node_weight.content_hash()) {
                 Some(content) => {
-                    // NOTE(nick,jacob,zack): if we had a v2, then there would be migration logic here.
-                    let SchemaContent::V1(inner) = content;
-
-                    schemas.push(Self::assemble(node_weight.id().into(), inner.to_owned()));
+                    schemas.push(Self::assemble(
+                        node_weight.id().into(),
+                        content.to_owned().extract(),
+                    ));
                 }
                 None => Err(WorkspaceSnapshotError::MissingContentFromStore(
                     node_weight.id(),
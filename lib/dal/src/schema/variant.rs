@@ -119,7 +119,7 @@ pub enum SchemaVariantError {
     LayerDb(#[from] LayerDbError),
     #[error("Func {0} of response type {1} cannot set leaf {2:?}")]
     LeafFunctionMismatch(FuncId, FuncBackendResponseType, LeafKind),
-    #[error("func {0} not a JsAttribute func, required for leaf functions")]
+    #[error("func {0} not a JsAttribute or Wasm func, required for leaf functions")]
     LeafFunctionMustBeJsAttribute(FuncId),
     #[error("Leaf map prop not found for item prop {0}")]
     LeafMapPropNotFound(PropId),
@@ -1883,9 +1883,19 @@ impl SchemaVariant {
             match IntrinsicFunc::maybe_from_str(&func.name) {
                 None => filtered_funcs.push(func.to_owned()),
                 Some(intrinsic) => match intrinsic {
-                    IntrinsicFunc::Identity | IntrinsicFunc::Unset => {
-                        filtered_funcs.push(func.to_owned())
-                    }
+                    IntrinsicFunc::Base64Decode
+                    | IntrinsicFunc::Base64Encode
+                    | IntrinsicFunc::BuildArray
+                    | IntrinsicFunc::BuildMap
+                    | IntrinsicFunc::Concat
+                    | IntrinsicFunc::DefaultIfEmpty
+                    | IntrinsicFunc::Identity
+                    | IntrinsicFunc::JsonPointer
+                    | IntrinsicFunc::Lowercase
+                    | IntrinsicFunc::RegexExtract
+                    | IntrinsicFunc::Template
+                    | IntrinsicFunc::Unset
+                    | IntrinsicFunc::Uppercase => filtered_funcs.push(func.to_owned()),
                     IntrinsicFunc::SetArray
                     | IntrinsicFunc::SetBoolean
                     | IntrinsicFunc::SetInteger
@@ -2296,7 +2306,8 @@ impl SchemaVariant {
                 | EdgeWeightKindDiscriminants::SocketValue
                 | EdgeWeightKindDiscriminants::ValidationOutput
                 | EdgeWeightKindDiscriminants::Manages
-                | EdgeWeightKindDiscriminants::DiagramObject => {}
+                | EdgeWeightKindDiscriminants::DiagramObject
+                | EdgeWeightKindDiscriminants::Tag => {}
             }
         }
 
@@ -50,7 +50,7 @@ use crate::attribute::prototype::AttributePrototypeError;
 use crate::attribute::value::AttributeValueError;
 use crate::func::argument::{FuncArgument, FuncArgumentError};
 use crate::func::intrinsics::IntrinsicFunc;
-use crate::key_pair::KeyPairPk;
+use crate::key_pair::{KeyPairPk, PublicKey as KeyPairPublicKey};
 use crate::layer_db_types::{SecretContent, SecretContentV1};
 use crate::prop::PropError;
 use crate::schema::variant::root_prop::RootPropChild;
@@ -646,6 +646,45 @@ impl Secret {
         .await
     }
 
+    /// Re-encrypts the underlying encrypted contents with the workspace's current [`KeyPair`] and
+    /// the active symmetric key if they were encrypted with any other keys. This allows old keys
+    /// to be retired after a rotation.
+    ///
+    /// Returns `None` if the contents were already encrypted with the current keys.
+    pub async fn reencrypt_with_current_keys(self, ctx: &DalContext) -> SecretResult<Option<Self>> {
+        let encrypted_secret = EncryptedSecret::get_by_key(ctx, self.encrypted_secret_key)
+            .await?
+            .ok_or(SecretError::EncryptedSecretNotFound(
+                self.encrypted_secret_key,
+            ))?;
+        let current_public_key = KeyPairPublicKey::get_current(ctx).await?;
+
+        if encrypted_secret.key_pair_pk == *current_public_key.pk()
+            && &encrypted_secret.key_hash == ctx.symmetric_crypto_service().active_key_hash()
+        {
+            return Ok(None);
+        }
+
+        let version = encrypted_secret.version;
+        let algorithm = encrypted_secret.algorithm;
+        let decrypted_secret = encrypted_secret.decrypt(ctx).await?;
+
+        // Explicitly match on (version, algorithm) tuple to ensure that any new
+        // versions/algorithms will trigger a compilation failure
+        let crypted = match (version, algorithm) {
+            (SecretVersion::V1, SecretAlgorithm::Sealedbox) => sealedbox::seal(
+                &serde_json::to_vec(&decrypted_secret.message)?,
+                current_public_key.public_key(),
+            ),
+        };
+
+        let secret = self
+            .update_encrypted_contents(ctx, &crypted, *current_public_key.pk(), version, algorithm)
+            .await?;
+
+        Ok(Some(secret))
+    }
+
     /// Finds all the connected component Ids for the [`Secret`]
     pub async fn find_connected_components(
         self,
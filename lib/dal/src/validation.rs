@@ -1,3 +1,5 @@
+pub mod joi;
+
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
@@ -12,6 +14,7 @@ use crate::func::backend::validation::ValidationRunResult;
 use crate::func::runner::{FuncRunner, FuncRunnerError};
 use crate::layer_db_types::{ValidationContent, ValidationContentV1};
 use crate::prop::PropError;
+use crate::validation::joi::JoiOutcome;
 use crate::workspace_snapshot::content_address::{ContentAddress, ContentAddressDiscriminants};
 use crate::workspace_snapshot::edge_weight::{
     EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants,
@@ -270,6 +273,27 @@ impl ValidationOutput {
             return Ok(None);
         };
 
+        // Most formats only use a small subset of Joi, which does not need a func run
+        match joi::evaluate(&validation_format, value.as_ref()) {
+            JoiOutcome::Valid => {
+                metric!(monotonic_counter.validation.native = 1);
+                return Ok(Some(ValidationOutput {
+                    status: ValidationStatus::Success,
+                    message: None,
+                }));
+            }
+            JoiOutcome::Invalid(message) => {
+                metric!(monotonic_counter.validation.native = 1);
+                return Ok(Some(ValidationOutput {
+                    status: ValidationStatus::Failure,
+                    message: Some(message),
+                }));
+            }
+            JoiOutcome::Unsupported => {
+                metric!(monotonic_counter.validation.veritech_fallback = 1);
+            }
+        }
+
         let result_channel =
             FuncRunner::run_validation_format(ctx, attribute_value_id, value, validation_format)
                 .await
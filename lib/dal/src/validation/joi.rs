@@ -0,0 +1,887 @@
+//! A native evaluator for the commonly used subset of Joi validation formats.
+//!
+//! A validation format is the JSON produced by Joi's `describe()`, which lang-js turns back into a
+//! schema with `Joi.build()`. Running it through veritech is a function execution per value
+//! change, so the formats that only use types, limits, patterns and allowed values are evaluated
+//! here instead, producing the same messages Joi would.
+//!
+//! Evaluation is deliberately conservative: anything outside the supported subset, including
+//! values Joi would coerce (like the string `"5"` for a number) and well-known formats we cannot
+//! judge exactly (like emails with uncommon top level domains), is [`JoiOutcome::Unsupported`]
+//! and must be sent to veritech.
+
+use std::net::IpAddr;
+
+use regex::{Regex, RegexBuilder};
+use serde_json::{Map, Value};
+
+/// The largest integer JavaScript can represent exactly. Joi rejects larger numbers as unsafe.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// Top level domains that are certain to be in the IANA list Joi checks emails against.
+const COMMON_TLDS: &[&str] = &[
+    "ai", "app", "au", "biz", "br", "ca", "cloud", "co", "com", "de", "dev", "edu", "es", "eu",
+    "fr", "gov", "in", "info", "io", "it", "jp", "me", "net", "nl", "org", "ru", "tech", "uk",
+    "us",
+];
+
+/// The result of evaluating a value against a validation format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoiOutcome {
+    Valid,
+    /// The value is invalid, with the message Joi would give.
+    Invalid(String),
+    /// The format or the value cannot be evaluated natively.
+    Unsupported,
+}
+
+/// Evaluates a value against a validation format. As in lang-js, `null` is treated as a missing
+/// value.
+pub fn evaluate(validation_format: &str, value: Option<&Value>) -> JoiOutcome {
+    match JoiSchema::parse(validation_format) {
+        Some(schema) => schema.validate(value),
+        None => JoiOutcome::Unsupported,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JoiType {
+    Any,
+    Array,
+    Boolean,
+    Number,
+    Object,
+    String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cidr {
+    Forbidden,
+    Optional,
+    Required,
+}
+
+impl Cidr {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Forbidden => "forbidden",
+            Self::Optional => "optional",
+            Self::Required => "required",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum JoiRule {
+    Email,
+    Greater(f64),
+    Hostname,
+    Integer,
+    Ip(Cidr),
+    Length(f64),
+    Less(f64),
+    Max(f64),
+    Min(f64),
+    Pattern {
+        regex: Regex,
+        source: String,
+        name: Option<String>,
+    },
+    Uri,
+}
+
+/// A validation format that only uses the supported subset of Joi.
+#[derive(Debug, Clone)]
+pub struct JoiSchema {
+    kind: JoiType,
+    label: String,
+    required: bool,
+    only: bool,
+    allow: Vec<Value>,
+    rules: Vec<JoiRule>,
+}
+
+impl JoiSchema {
+    /// Parses a validation format, returning `None` if it is not valid JSON or uses anything
+    /// outside the supported subset. Malformed formats are left to veritech so that their errors
+    /// are reported exactly as before.
+    pub fn parse(validation_format: &str) -> Option<Self> {
+        let description: Map<String, Value> = serde_json::from_str(validation_format).ok()?;
+
+        let mut schema = Self {
+            kind: JoiType::Any,
+            label: "value".to_owned(),
+            required: false,
+            only: false,
+            allow: Vec::new(),
+            rules: Vec::new(),
+        };
+
+        for (key, value) in &description {
+            match key.as_str() {
+                "type" => {
+                    schema.kind = match value.as_str()? {
+                        "any" => JoiType::Any,
+                        "array" => JoiType::Array,
+                        "boolean" => JoiType::Boolean,
+                        "number" => JoiType::Number,
+                        "object" => JoiType::Object,
+                        "string" => JoiType::String,
+                        _ => return None,
+                    }
+                }
+                "flags" => schema.parse_flags(value.as_object()?)?,
+                "allow" => schema.allow = value.as_array()?.clone(),
+                "rules" => {
+                    for rule in value.as_array()? {
+                        let rule = Self::parse_rule(rule.as_object()?)?;
+                        schema.rules.push(rule);
+                    }
+                }
+                _ => return None,
+            }
+        }
+
+        // Every rule has to apply to the type, otherwise Joi would have refused to build it.
+        for rule in &schema.rules {
+            let applies = match rule {
+                JoiRule::Length(_) | JoiRule::Max(_) | JoiRule::Min(_) => {
+                    matches!(
+                        schema.kind,
+                        JoiType::Array | JoiType::Number | JoiType::Object | JoiType::String
+                    ) && !(matches!(rule, JoiRule::Length(_)) && schema.kind == JoiType::Number)
+                }
+                JoiRule::Greater(_) | JoiRule::Integer | JoiRule::Less(_) => {
+                    schema.kind == JoiType::Number
+                }
+                JoiRule::Email
+                | JoiRule::Hostname
+                | JoiRule::Ip(_)
+                | JoiRule::Pattern { .. }
+                | JoiRule::Uri => schema.kind == JoiType::String,
+            };
+            if !applies {
+                return None;
+            }
+        }
+
+        Some(schema)
+    }
+
+    fn parse_flags(&mut self, flags: &Map<String, Value>) -> Option<()> {
+        for (flag, value) in flags {
+            match flag.as_str() {
+                "presence" => match value.as_str()? {
+                    "required" => self.required = true,
+                    "optional" => self.required = false,
+                    _ => return None,
+                },
+                "only" => self.only = value.as_bool()?,
+                "label" => self.label = value.as_str()?.to_owned(),
+                "description" => {}
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    fn parse_rule(rule: &Map<String, Value>) -> Option<JoiRule> {
+        let name = rule.get("name")?.as_str()?;
+        let args = match rule.get("args") {
+            Some(args) => args.as_object()?.clone(),
+            None => Map::new(),
+        };
+        if rule.keys().any(|key| key != "name" && key != "args") {
+            return None;
+        }
+
+        let limit = || -> Option<f64> {
+            if args.len() != 1 {
+                return None;
+            }
+            args.get("limit")?.as_f64()
+        };
+        let no_options = || -> Option<()> {
+            match args.get("options") {
+                None if args.is_empty() => Some(()),
+                Some(Value::Object(options)) if options.is_empty() && args.len() == 1 => Some(()),
+                _ => None,
+            }
+        };
+
+        Some(match name {
+            "email" => {
+                no_options()?;
+                JoiRule::Email
+            }
+            "greater" => JoiRule::Greater(limit()?),
+            "hostname" => {
+                no_options()?;
+                JoiRule::Hostname
+            }
+            "integer" => {
+                if !args.is_empty() {
+                    return None;
+                }
+                JoiRule::Integer
+            }
+            "ip" => {
+                let mut cidr = Cidr::Optional;
+                if let Some(options) = args.get("options") {
+                    for (option, value) in options.as_object()? {
+                        match (option.as_str(), value.as_str()?) {
+                            ("cidr", "forbidden") => cidr = Cidr::Forbidden,
+                            ("cidr", "optional") => cidr = Cidr::Optional,
+                            ("cidr", "required") => cidr = Cidr::Required,
+                            _ => return None,
+                        }
+                    }
+                }
+                if args.keys().any(|key| key != "options") {
+                    return None;
+                }
+                JoiRule::Ip(cidr)
+            }
+            "length" => JoiRule::Length(limit()?),
+            "less" => JoiRule::Less(limit()?),
+            "max" => JoiRule::Max(limit()?),
+            "min" => JoiRule::Min(limit()?),
+            "pattern" => {
+                let source = args.get("regex")?.as_str()?.to_owned();
+                let mut name = None;
+                if let Some(options) = args.get("options") {
+                    for (option, value) in options.as_object()? {
+                        match option.as_str() {
+                            "name" => name = Some(value.as_str()?.to_owned()),
+                            "invert" if value.as_bool()? => return None,
+                            "invert" => {}
+                            _ => return None,
+                        }
+                    }
+                }
+                if args.keys().any(|key| key != "regex" && key != "options") {
+                    return None;
+                }
+                JoiRule::Pattern {
+                    regex: compile_js_regex(&source)?,
+                    source,
+                    name,
+                }
+            }
+            "uri" => {
+                no_options()?;
+                JoiRule::Uri
+            }
+            _ => return None,
+        })
+    }
+
+    /// Validates a value, stopping at the first error as Joi does by default.
+    pub fn validate(&self, value: Option<&Value>) -> JoiOutcome {
+        let label = format!("\"{}\"", self.label);
+
+        let value = match value {
+            None | Some(Value::Null) => {
+                return if self.required {
+                    JoiOutcome::Invalid(format!("{label} is required"))
+                } else {
+                    JoiOutcome::Valid
+                };
+            }
+            Some(value) => value,
+        };
+
+        if self.allow.iter().any(|allowed| allowed == value) {
+            return JoiOutcome::Valid;
+        }
+        if self.only {
+            let valids: Vec<String> = self.allow.iter().map(render).collect();
+            let one_of = if valids.len() == 1 { "" } else { "one of " };
+            return JoiOutcome::Invalid(format!("{label} must be {one_of}[{}]", valids.join(", ")));
+        }
+
+        match self.kind {
+            JoiType::Any => JoiOutcome::Valid,
+            JoiType::Array => match value {
+                Value::Array(items) => self.validate_size(&label, items.len(), "array"),
+                // Joi parses strings that look like JSON arrays
+                Value::String(_) => JoiOutcome::Unsupported,
+                _ => JoiOutcome::Invalid(format!("{label} must be an array")),
+            },
+            JoiType::Boolean => match value {
+                Value::Bool(_) => JoiOutcome::Valid,
+                // Joi converts "true" and "false"
+                Value::String(_) => JoiOutcome::Unsupported,
+                _ => JoiOutcome::Invalid(format!("{label} must be a boolean")),
+            },
+            JoiType::Number => match value {
+                Value::Number(number) => match number.as_f64() {
+                    Some(number) => self.validate_number(&label, number),
+                    None => JoiOutcome::Unsupported,
+                },
+                // Joi converts numeric strings
+                Value::String(_) => JoiOutcome::Unsupported,
+                _ => JoiOutcome::Invalid(format!("{label} must be a number")),
+            },
+            JoiType::Object => match value {
+                Value::Object(object) => self.validate_size(&label, object.len(), "object"),
+                // Joi parses strings that look like JSON objects
+                Value::String(_) => JoiOutcome::Unsupported,
+                _ => JoiOutcome::Invalid(format!("{label} must be of type object")),
+            },
+            JoiType::String => match value {
+                Value::String(string) => self.validate_string(&label, string),
+                _ => JoiOutcome::Invalid(format!("{label} must be a string")),
+            },
+        }
+    }
+
+    fn validate_size(&self, label: &str, size: usize, kind: &str) -> JoiOutcome {
+        let size = size as f64;
+        for rule in &self.rules {
+            let (failed, limit) = match rule {
+                JoiRule::Length(limit) => (size != *limit, *limit),
+                JoiRule::Max(limit) => (size > *limit, *limit),
+                JoiRule::Min(limit) => (size < *limit, *limit),
+                _ => return JoiOutcome::Unsupported,
+            };
+            if !failed {
+                continue;
+            }
+
+            let limit_text = render_number(limit);
+            let message = if kind == "array" {
+                match rule {
+                    JoiRule::Length(_) => format!("{label} must contain {limit_text} items"),
+                    JoiRule::Max(_) => {
+                        format!("{label} must contain less than or equal to {limit_text} items")
+                    }
+                    _ => format!("{label} must contain at least {limit_text} items"),
+                }
+            } else {
+                let keys = if limit == 1.0 { "key" } else { "keys" };
+                match rule {
+                    JoiRule::Length(_) => format!("{label} must have {limit_text} {keys}"),
+                    JoiRule::Max(_) => {
+                        format!("{label} must have less than or equal to {limit_text} {keys}")
+                    }
+                    _ => format!("{label} must have at least {limit_text} {keys}"),
+                }
+            };
+            return JoiOutcome::Invalid(message);
+        }
+
+        JoiOutcome::Valid
+    }
+
+    fn validate_number(&self, label: &str, number: f64) -> JoiOutcome {
+        if number.abs() > MAX_SAFE_INTEGER {
+            return JoiOutcome::Unsupported;
+        }
+
+        for rule in &self.rules {
+            let message = match rule {
+                JoiRule::Greater(limit) if number <= *limit => {
+                    format!("{label} must be greater than {}", render_number(*limit))
+                }
+                JoiRule::Integer if number.fract() != 0.0 => {
+                    format!("{label} must be an integer")
+                }
+                JoiRule::Less(limit) if number >= *limit => {
+                    format!("{label} must be less than {}", render_number(*limit))
+                }
+                JoiRule::Max(limit) if number > *limit => format!(
+                    "{label} must be less than or equal to {}",
+                    render_number(*limit)
+                ),
+                JoiRule::Min(limit) if number < *limit => format!(
+                    "{label} must be greater than or equal to {}",
+                    render_number(*limit)
+                ),
+                _ => continue,
+            };
+            return JoiOutcome::Invalid(message);
+        }
+
+        JoiOutcome::Valid
+    }
+
+    fn validate_string(&self, label: &str, string: &str) -> JoiOutcome {
+        if string.is_empty() {
+            let allows_empty = self
+                .rules
+                .iter()
+                .any(|rule| matches!(rule, JoiRule::Min(limit) if *limit == 0.0));
+            if !allows_empty {
+                return JoiOutcome::Invalid(format!("{label} is not allowed to be empty"));
+            }
+        }
+
+        // JavaScript measures strings in UTF-16 code units
+        let length = string.encode_utf16().count() as f64;
+
+        for rule in &self.rules {
+            let message = match rule {
+                JoiRule::Email => match is_email(string) {
+                    Some(true) => continue,
+                    Some(false) => format!("{label} must be a valid email"),
+                    None => return JoiOutcome::Unsupported,
+                },
+                JoiRule::Hostname => match is_hostname(string) {
+                    Some(true) => continue,
+                    Some(false) => format!("{label} must be a valid hostname"),
+                    None => return JoiOutcome::Unsupported,
+                },
+                JoiRule::Ip(cidr) => match is_ip(string, *cidr) {
+                    Some(true) => continue,
+                    Some(false) => format!(
+                        "{label} must be a valid ip address with a {} CIDR",
+                        cidr.as_str()
+                    ),
+                    None => return JoiOutcome::Unsupported,
+                },
+                JoiRule::Length(limit) if length != *limit => format!(
+                    "{label} length must be {} characters long",
+                    render_number(*limit)
+                ),
+                JoiRule::Max(limit) if length > *limit => format!(
+                    "{label} length must be less than or equal to {} characters long",
+                    render_number(*limit)
+                ),
+                JoiRule::Min(limit) if length < *limit => format!(
+                    "{label} length must be at least {} characters long",
+                    render_number(*limit)
+                ),
+                JoiRule::Pattern {
+                    regex,
+                    source,
+                    name,
+                } if !regex.is_match(string) => match name {
+                    Some(name) => {
+                        format!("{label} with value \"{string}\" fails to match the {name} pattern")
+                    }
+                    None => format!(
+                        "{label} with value \"{string}\" fails to match the required pattern: {source}"
+                    ),
+                },
+                JoiRule::Uri => match is_uri(string) {
+                    Some(true) => continue,
+                    Some(false) => format!("{label} must be a valid uri"),
+                    None => return JoiOutcome::Unsupported,
+                },
+                _ => continue,
+            };
+            return JoiOutcome::Invalid(message);
+        }
+
+        JoiOutcome::Valid
+    }
+}
+
+/// Renders a number the way JavaScript prints it.
+fn render_number(number: f64) -> String {
+    if number.fract() == 0.0 && number.abs() < 1e21 {
+        format!("{}", number as i64)
+    } else {
+        format!("{number}")
+    }
+}
+
+/// Renders an allowed value the way Joi prints it in an error message.
+fn render(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.to_owned(),
+        Value::Number(number) => number
+            .as_f64()
+            .map(render_number)
+            .unwrap_or_else(|| number.to_string()),
+        other => other.to_string(),
+    }
+}
+
+/// Compiles a JavaScript regex literal, such as `/^[a-z]+$/i`, into an equivalent Rust regex.
+/// Returns `None` for flags or syntax that would not behave the same.
+fn compile_js_regex(literal: &str) -> Option<Regex> {
+    let body = literal.strip_prefix('/')?;
+    let end = body.rfind('/')?;
+    let (source, flags) = (&body[..end], &body[end + 1..]);
+
+    let mut builder_flags = (false, false, false);
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder_flags.0 = true,
+            'm' => builder_flags.1 = true,
+            's' => builder_flags.2 = true,
+            'u' => {}
+            _ => return None,
+        }
+    }
+
+    // JavaScript's shorthand classes and word boundaries are ASCII only, whereas Rust's are
+    // Unicode aware.
+    let mut translated = String::with_capacity(source.len());
+    let mut in_class = false;
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let escaped = chars.next()?;
+                match escaped {
+                    'd' => translated.push_str("[0-9]"),
+                    'D' => translated.push_str("[^0-9]"),
+                    'w' => translated.push_str("[0-9A-Za-z_]"),
+                    'W' => translated.push_str("[^0-9A-Za-z_]"),
+                    'b' | 'B' if in_class => return None,
+                    'b' => translated.push_str("(?-u:\\b)"),
+                    'B' => translated.push_str("(?-u:\\B)"),
+                    'c' | '0'..='9' => return None,
+                    other => {
+                        translated.push('\\');
+                        translated.push(other);
+                    }
+                }
+            }
+            '[' if !in_class => {
+                in_class = true;
+                translated.push(c);
+            }
+            // Rust treats a nested `[` as a nested class, JavaScript as a literal
+            '[' => translated.push_str("\\["),
+            ']' if in_class => {
+                in_class = false;
+                translated.push(c);
+            }
+            _ => translated.push(c),
+        }
+    }
+
+    RegexBuilder::new(&translated)
+        .case_insensitive(builder_flags.0)
+        .multi_line(builder_flags.1)
+        .dot_matches_new_line(builder_flags.2)
+        .build()
+        .ok()
+}
+
+fn is_domain_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Whether a string is an email with an ASCII address and a common top level domain. `None` if
+/// only Joi can tell.
+fn is_email(string: &str) -> Option<bool> {
+    if !string.is_ascii() {
+        return None;
+    }
+    let Some((local, domain)) = string.split_once('@') else {
+        return Some(false);
+    };
+
+    const ATEXT: &str = "!#$%&'*+-/=?^_`{|}~";
+    let local_ok = !local.is_empty()
+        && local.len() <= 64
+        && local.split('.').all(|atom| {
+            !atom.is_empty()
+                && atom
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || ATEXT.contains(c))
+        });
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok =
+        domain.len() <= 255 && labels.len() >= 2 && labels.iter().all(|l| is_domain_label(l));
+    if !local_ok || !domain_ok {
+        return Some(false);
+    }
+
+    let tld = labels.last()?.to_ascii_lowercase();
+    if COMMON_TLDS.contains(&tld.as_str()) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Whether a string is an ASCII hostname or an ip address. `None` if only Joi can tell.
+fn is_hostname(string: &str) -> Option<bool> {
+    if string.parse::<IpAddr>().is_ok() {
+        return Some(true);
+    }
+    if !string.is_ascii() {
+        return None;
+    }
+    if string
+        .chars()
+        .any(|c| c.is_whitespace() || "/@?#".contains(c))
+    {
+        return Some(false);
+    }
+
+    let domain = string.strip_suffix('.').unwrap_or(string);
+    if domain.len() <= 255 && domain.split('.').all(is_domain_label) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Whether a string is an ip address with or without a CIDR prefix as required. `None` if only
+/// Joi can tell, for example for zero padded addresses.
+fn is_ip(string: &str, cidr: Cidr) -> Option<bool> {
+    let (address, prefix) = match string.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (string, None),
+    };
+
+    let parsed = match address.parse::<IpAddr>() {
+        Ok(parsed) => parsed,
+        Err(_) => {
+            return if string
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || ".:/".contains(c))
+            {
+                None
+            } else {
+                Some(false)
+            };
+        }
+    };
+
+    let prefix_ok = match prefix {
+        None => cidr != Cidr::Required,
+        Some(_) if cidr == Cidr::Forbidden => false,
+        Some(prefix) => {
+            let max = if parsed.is_ipv4() { 32 } else { 128 };
+            match prefix.parse::<u8>() {
+                Ok(prefix) => prefix <= max,
+                Err(_) => false,
+            }
+        }
+    };
+
+    Some(prefix_ok)
+}
+
+/// Whether a string is an absolute uri. `None` if only Joi can tell.
+fn is_uri(string: &str) -> Option<bool> {
+    if string.chars().any(char::is_whitespace) {
+        return Some(false);
+    }
+    let Some((scheme, rest)) = string.split_once(':') else {
+        return Some(false);
+    };
+    let scheme_ok = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c));
+    if !scheme_ok {
+        return Some(false);
+    }
+
+    let Some(rest) = rest.strip_prefix("//") else {
+        return None;
+    };
+    let (authority, path) = match rest.find(['/', '?', '#']) {
+        Some(index) => rest.split_at(index),
+        None => (rest, ""),
+    };
+    if authority.contains(['[', '@']) {
+        return None;
+    }
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+
+    let host_ok = !host.is_empty() && host.split('.').all(is_domain_label);
+    let port_ok = port.map_or(true, |port| port.chars().all(|c| c.is_ascii_digit()));
+    let path_ok = path_is_plain(path);
+
+    if host_ok && port_ok && path_ok {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Whether a path, query and fragment only use unreserved characters and valid percent
+/// encodings.
+fn path_is_plain(path: &str) -> bool {
+    const ALLOWED: &str = "-._~!$&'()*+,;=:@/?#";
+    let bytes = path.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let c = bytes[index] as char;
+        if c == '%' {
+            let valid = bytes
+                .get(index + 1..index + 3)
+                .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+            if !valid {
+                return false;
+            }
+            index += 3;
+            continue;
+        }
+        if !(c.is_ascii_alphanumeric() || ALLOWED.contains(c)) {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const PIRATE_EYES: &str = r#"{"type":"number","flags":{"presence":"required"},"rules":[{"name":"integer"},{"name":"min","args":{"limit":0}},{"name":"max","args":{"limit":2}}]}"#;
+
+    fn invalid(message: &str) -> JoiOutcome {
+        JoiOutcome::Invalid(message.to_owned())
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(
+            invalid("\"value\" is required"),
+            evaluate(PIRATE_EYES, None)
+        );
+        assert_eq!(
+            invalid("\"value\" is required"),
+            evaluate(PIRATE_EYES, Some(&Value::Null))
+        );
+        assert_eq!(JoiOutcome::Valid, evaluate(PIRATE_EYES, Some(&json!(1))));
+        assert_eq!(
+            invalid("\"value\" must be less than or equal to 2"),
+            evaluate(PIRATE_EYES, Some(&json!(3)))
+        );
+        assert_eq!(
+            invalid("\"value\" must be an integer"),
+            evaluate(PIRATE_EYES, Some(&json!(1.5)))
+        );
+        assert_eq!(
+            invalid("\"value\" must be a number"),
+            evaluate(PIRATE_EYES, Some(&json!(true)))
+        );
+        assert_eq!(
+            JoiOutcome::Unsupported,
+            evaluate(PIRATE_EYES, Some(&json!("1")))
+        );
+    }
+
+    #[test]
+    fn strings() {
+        let format = r#"{"type":"string","rules":[{"name":"min","args":{"limit":3}},{"name":"pattern","args":{"regex":"/^\\w+$/"}}]}"#;
+        assert_eq!(JoiOutcome::Valid, evaluate(format, Some(&json!("abc_1"))));
+        assert_eq!(
+            invalid("\"value\" is not allowed to be empty"),
+            evaluate(format, Some(&json!("")))
+        );
+        assert_eq!(
+            invalid("\"value\" length must be at least 3 characters long"),
+            evaluate(format, Some(&json!("ab")))
+        );
+        assert_eq!(
+            invalid("\"value\" with value \"ab c\" fails to match the required pattern: /^\\w+$/"),
+            evaluate(format, Some(&json!("ab c")))
+        );
+        assert_eq!(
+            invalid("\"value\" with value \"abcé\" fails to match the required pattern: /^\\w+$/"),
+            evaluate(format, Some(&json!("abcé")))
+        );
+        assert_eq!(
+            invalid("\"value\" must be a string"),
+            evaluate(format, Some(&json!(5)))
+        );
+    }
+
+    #[test]
+    fn allowed_values() {
+        let format = r#"{"type":"string","flags":{"only":true},"allow":["a","b"]}"#;
+        assert_eq!(JoiOutcome::Valid, evaluate(format, Some(&json!("a"))));
+        assert_eq!(
+            invalid("\"value\" must be one of [a, b]"),
+            evaluate(format, Some(&json!("c")))
+        );
+
+        let format = r#"{"type":"number","flags":{"only":true},"allow":[1]}"#;
+        assert_eq!(
+            invalid("\"value\" must be [1]"),
+            evaluate(format, Some(&json!(2)))
+        );
+    }
+
+    #[test]
+    fn string_formats() {
+        let email = r#"{"type":"string","rules":[{"name":"email"}]}"#;
+        assert_eq!(
+            JoiOutcome::Valid,
+            evaluate(email, Some(&json!("pirate@example.com")))
+        );
+        assert_eq!(
+            invalid("\"value\" must be a valid email"),
+            evaluate(email, Some(&json!("pirate")))
+        );
+        assert_eq!(
+            JoiOutcome::Unsupported,
+            evaluate(email, Some(&json!("pirate@example.unknowntld")))
+        );
+
+        let ip =
+            r#"{"type":"string","rules":[{"name":"ip","args":{"options":{"cidr":"forbidden"}}}]}"#;
+        assert_eq!(JoiOutcome::Valid, evaluate(ip, Some(&json!("10.0.0.1"))));
+        assert_eq!(
+            invalid("\"value\" must be a valid ip address with a forbidden CIDR"),
+            evaluate(ip, Some(&json!("10.0.0.0/8")))
+        );
+
+        let hostname = r#"{"type":"string","rules":[{"name":"hostname"}]}"#;
+        assert_eq!(
+            JoiOutcome::Valid,
+            evaluate(hostname, Some(&json!("db-1.internal")))
+        );
+        assert_eq!(
+            invalid("\"value\" must be a valid hostname"),
+            evaluate(hostname, Some(&json!("not a host")))
+        );
+
+        let uri = r#"{"type":"string","rules":[{"name":"uri"}]}"#;
+        assert_eq!(
+            JoiOutcome::Valid,
+            evaluate(uri, Some(&json!("https://example.com/a?b=c")))
+        );
+        assert_eq!(
+            invalid("\"value\" must be a valid uri"),
+            evaluate(uri, Some(&json!("example.com")))
+        );
+    }
+
+    #[test]
+    fn unsupported_formats() {
+        assert_eq!(JoiOutcome::Unsupported, evaluate("'{}'", None));
+        assert_eq!(JoiOutcome::Unsupported, evaluate("5", None));
+        assert_eq!(
+            JoiOutcome::Unsupported,
+            evaluate(r#"{"type":"string","rules":[{"name":"guid"}]}"#, None)
+        );
+        assert_eq!(
+            JoiOutcome::Unsupported,
+            evaluate(
+                r#"{"type":"object","keys":{"a":{"type":"string"}}}"#,
+                Some(&json!({}))
+            )
+        );
+        assert_eq!(
+            JoiOutcome::Unsupported,
+            evaluate(
+                r#"{"type":"string","rules":[{"name":"pattern","args":{"regex":"/(?<=a)b/"}}]}"#,
+                Some(&json!("ab"))
+            )
+        );
+    }
+}
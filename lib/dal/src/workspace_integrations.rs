@@ -40,6 +40,10 @@ impl WorkspaceIntegration {
         &self.pk
     }
 
+    pub fn workspace_pk(&self) -> WorkspaceId {
+        self.workspace_pk
+    }
+
     pub fn slack_webhook_url(&self) -> Option<String> {
         self.slack_webhook_url.clone()
     }
@@ -123,4 +127,21 @@ impl WorkspaceIntegration {
         };
         Ok(maybe_workspace_integration)
     }
+
+    /// Lists the integrations of every workspace that have a Slack webhook URL set.
+    pub async fn list_with_slack_webhook_url(
+        ctx: &DalContext,
+    ) -> WorkspaceIntegrationsResult<Vec<Self>> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                "SELECT * FROM workspace_integrations WHERE slack_webhook_url IS NOT NULL AND slack_webhook_url != ''",
+                &[],
+            )
+            .await?;
+
+        rows.into_iter().map(Self::try_from).collect()
+    }
 }
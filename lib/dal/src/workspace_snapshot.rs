@@ -24,6 +24,7 @@
 //     clippy::missing_panics_doc
 // )]
 
+pub mod chunked;
 pub mod content_address;
 pub mod edge_weight;
 pub mod graph;
@@ -69,7 +70,10 @@ use crate::socket::input::InputSocketError;
 use crate::workspace_snapshot::{
     content_address::ContentAddressDiscriminants,
     edge_weight::{EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants},
-    graph::{LineageId, WorkspaceSnapshotGraphDiscriminants},
+    graph::{
+        v4::fsck::{SnapshotViolation, SnapshotViolationKind},
+        LineageId, WorkspaceSnapshotGraphDiscriminants,
+    },
     node_weight::{category_node_weight::CategoryNodeKind, NodeWeight},
 };
 use crate::{
@@ -85,6 +89,9 @@ use self::node_weight::{NodeWeightDiscriminants, OrderingNodeWeight};
 
 pub use si_id::WorkspaceSnapshotNodeId as NodeId;
 
+/// How many content hashes [`WorkspaceSnapshot::fsck`] looks up in the CAS at a time.
+const FSCK_CAS_BATCH_SIZE: usize = 1000;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NodeInformation {
     pub node_weight_kind: NodeWeightDiscriminants,
@@ -115,6 +122,8 @@ pub enum WorkspaceSnapshotError {
     ChangeSet(#[from] ChangeSetError),
     #[error("change set {0} has no workspace snapshot address")]
     ChangeSetMissingWorkspaceSnapshotAddress(ChangeSetId),
+    #[error("chunked snapshot error: {0}")]
+    ChunkedSnapshot(#[from] chunked::ChunkedSnapshotError),
     #[error("Component error: {0}")]
     Component(#[from] Box<ComponentError>),
     #[error("ConnectionAnnotation error: {0}")]
@@ -510,12 +519,13 @@ impl WorkspaceSnapshot {
                 let mut working_copy = self_clone.working_copy_mut().await;
                 working_copy.cleanup_and_merkle_tree_hash()?;
 
-                let (new_address, _) = layer_db.workspace_snapshot().write(
+                let new_address =
+                    chunked::write(&layer_db, &working_copy, events_tenancy, events_actor)?;
+                layer_db.workspace_snapshot().insert_into_cache(
+                    &new_address,
                     Arc::new(WorkspaceSnapshotGraph::V4(working_copy.clone())),
-                    None,
-                    events_tenancy,
-                    events_actor,
-                )?;
+                    chunked::estimated_size(&working_copy),
+                );
 
                 Ok::<WorkspaceSnapshotAddress, WorkspaceSnapshotError>(new_address)
             })?
@@ -595,6 +605,50 @@ impl WorkspaceSnapshot {
         }
     }
 
+    /// Checks the structural invariants of the snapshot (see
+    /// [`WorkspaceSnapshotGraphVCurrent::fsck`]), and that all the content it refers to is in the
+    /// CAS.
+    #[instrument(name = "workspace_snapshot.fsck", level = "info", skip_all)]
+    pub async fn fsck(&self, ctx: &DalContext) -> WorkspaceSnapshotResult<Vec<SnapshotViolation>> {
+        let working_copy = self.working_copy().await;
+        let mut violations = working_copy.fsck();
+
+        let mut node_indexes_by_hash: HashMap<ContentHash, Vec<NodeIndex>> = HashMap::new();
+        for (node_weight, node_index) in working_copy.nodes() {
+            for hash in node_weight.content_store_hashes() {
+                node_indexes_by_hash
+                    .entry(hash)
+                    .or_default()
+                    .push(node_index);
+            }
+        }
+
+        let hashes: Vec<ContentHash> = node_indexes_by_hash.keys().copied().collect();
+        for batch in hashes.chunks(FSCK_CAS_BATCH_SIZE) {
+            let found = ctx.layer_db().cas().read_many(batch).await?;
+            for hash in batch.iter().filter(|hash| !found.contains_key(*hash)) {
+                for node_index in node_indexes_by_hash.get(hash).into_iter().flatten() {
+                    violations.push(working_copy.violation(
+                        SnapshotViolationKind::MissingContent,
+                        *node_index,
+                        format!("content {hash} is not in the CAS"),
+                    ));
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+
+    /// Repairs the repairable violations found by [`Self::fsck`] in the working copy, returning
+    /// the ones that were repaired. [Write](Self::write) the snapshot to persist the repairs.
+    pub async fn repair(
+        &self,
+        violations: &[SnapshotViolation],
+    ) -> WorkspaceSnapshotResult<Vec<SnapshotViolation>> {
+        Ok(self.working_copy_mut().await.repair(violations)?)
+    }
+
     pub async fn serialized(&self) -> WorkspaceSnapshotResult<Vec<u8>> {
         let graph = self.working_copy().await.clone();
         Ok(si_layer_cache::db::serialize::to_vec(&WorkspaceSnapshotGraph::V4(graph))?.0)
@@ -897,15 +951,36 @@ impl WorkspaceSnapshot {
         ctx: &DalContext,
         workspace_snapshot_addr: WorkspaceSnapshotAddress,
     ) -> WorkspaceSnapshotResult<Self> {
+        // Snapshots are written as chunks, but older ones are stored whole until migrated. The
+        // snapshot cache holds both whole snapshots and chunked ones that were already assembled,
+        // so look there first. Failing that, assemble it from its chunks before falling back to
+        // waiting on a whole one to show up in memory, which is slow.
         let snapshot = match ctx
             .layer_db()
             .workspace_snapshot()
-            .read_wait_for_memory(&workspace_snapshot_addr)
+            .read(&workspace_snapshot_addr)
             .await
         {
-            Ok(snapshot) => snapshot.ok_or(
-                WorkspaceSnapshotError::WorkspaceSnapshotGraphMissing(workspace_snapshot_addr),
-            )?,
+            Ok(None) => match Self::find_chunked(ctx, workspace_snapshot_addr).await? {
+                Some(snapshot) => Ok(Some(snapshot)),
+                None => {
+                    ctx.layer_db()
+                        .workspace_snapshot()
+                        .read_wait_for_memory(&workspace_snapshot_addr)
+                        .await
+                }
+            },
+            result => result,
+        };
+
+        let snapshot = match snapshot {
+            Ok(Some(snapshot)) => snapshot,
+            // The chunks may have landed while we were waiting on a whole snapshot
+            Ok(None) => Self::find_chunked(ctx, workspace_snapshot_addr)
+                .await?
+                .ok_or(WorkspaceSnapshotError::WorkspaceSnapshotGraphMissing(
+                    workspace_snapshot_addr,
+                ))?,
             Err(err) => match err {
                 LayerDbError::Postcard(_) => {
                     return Err(WorkspaceSnapshotError::WorkspaceSnapshotNotMigrated(
@@ -926,6 +1001,26 @@ impl WorkspaceSnapshot {
         })
     }
 
+    /// Reassembles a chunked snapshot and caches the result, so it is only reassembled once.
+    async fn find_chunked(
+        ctx: &DalContext,
+        workspace_snapshot_addr: WorkspaceSnapshotAddress,
+    ) -> WorkspaceSnapshotResult<Option<Arc<WorkspaceSnapshotGraph>>> {
+        let Some(graph) = chunked::read(ctx.layer_db(), workspace_snapshot_addr).await? else {
+            return Ok(None);
+        };
+
+        let size_hint = chunked::estimated_size(&graph);
+        let snapshot = Arc::new(WorkspaceSnapshotGraph::V4(graph));
+        ctx.layer_db().workspace_snapshot().insert_into_cache(
+            &workspace_snapshot_addr,
+            snapshot.clone(),
+            size_hint,
+        );
+
+        Ok(Some(snapshot))
+    }
+
     pub async fn find_for_change_set(
         ctx: &DalContext,
         change_set_id: ChangeSetId,
@@ -1642,7 +1737,8 @@ impl WorkspaceSnapshot {
             | NodeWeight::Prop(_)
             | NodeWeight::SchemaVariant(_)
             | NodeWeight::ManagementPrototype(_)
-            | NodeWeight::Secret(_) => None,
+            | NodeWeight::Secret(_)
+            | NodeWeight::Tag(_) => None,
         } {
             let next_node_idxs = self
                 .incoming_sources_for_edge_weight_kind(this_node_weight.id(), edge_kind)
@@ -0,0 +1,474 @@
+//! Chunked storage for workspace snapshot graphs.
+//!
+//! Rather than persisting a [`WorkspaceSnapshotGraphV4`] as a single blob, the graph is split into
+//! [`SnapshotChunks`](SnapshotChunk) along the boundaries of the things users edit: categories,
+//! components, schemas, schema variants, funcs, secrets and views. Each chunk holds the nodes it
+//! owns, their outgoing edges, and the keys of the chunks it points into, and is stored under a
+//! [`MerkleTreeHash`] of its contents. Since a chunk's key covers the keys of its children, the
+//! chunks form a merkle tree: editing one attribute rewrites only the chunk that owns it and the
+//! chunks on its path up to the root, and every other chunk is shared with the snapshot it was
+//! derived from (and with every change set forked from it).
+//!
+//! Reading walks the tree from the root chunk, fetching only the chunks that are not already in
+//! the layer cache, a level at a time, and assembles the whole graph.
+//!
+//! Loading chunks on demand is deliberately left out of this format and will follow separately:
+//! graph operations, rebasing and the snapshot cache all expect a complete
+//! [`WorkspaceSnapshotGraphV4`] in memory, and teaching them to fault chunks in is its own change.
+//! Until then, the savings are in what is written and stored, and in chunks a reader already has
+//! cached, rather than in what a reader holds in memory. The `snapshot_storage` benchmark compares
+//! writing and reading chunks against snapshots stored whole.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use petgraph::prelude::*;
+use serde::{Deserialize, Serialize};
+use si_events::{
+    merkle_tree_hash::MerkleTreeHash, ulid::Ulid, Actor, Tenancy, WorkspaceSnapshotAddress,
+};
+use si_layer_cache::LayerDbError;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use crate::{
+    workspace_snapshot::{
+        content_address::ContentAddress,
+        graph::{WorkspaceSnapshotGraphError, WorkspaceSnapshotGraphV4},
+        node_weight::NodeWeight,
+    },
+    DalLayerDb, EdgeWeight,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ChunkedSnapshotError {
+    #[error("chunk missing from store: {0}")]
+    ChunkMissing(MerkleTreeHash),
+    #[error("edge references a node that is not in the snapshot: {0}")]
+    EdgeNodeMissing(Ulid),
+    #[error("chunk has no nodes")]
+    EmptyChunk,
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] LayerDbError),
+    #[error("workspace snapshot graph error: {0}")]
+    WorkspaceSnapshotGraph(#[from] WorkspaceSnapshotGraphError),
+}
+
+pub type ChunkedSnapshotResult<T> = Result<T, ChunkedSnapshotError>;
+
+/// A rough in-memory size of a node and its edges, used to weigh assembled graphs in the snapshot
+/// cache since they never pass through a serializer.
+const ESTIMATED_BYTES_PER_NODE: usize = 200;
+
+/// A subtree of a workspace snapshot graph. The first node is the node the chunk is rooted at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotChunk {
+    nodes: Vec<NodeWeight>,
+    edges: Vec<SnapshotChunkEdge>,
+    children: Vec<MerkleTreeHash>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotChunkEdge {
+    source: Ulid,
+    target: Ulid,
+    weight: EdgeWeight,
+}
+
+impl SnapshotChunk {
+    pub fn nodes(&self) -> &[NodeWeight] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[SnapshotChunkEdge] {
+        &self.edges
+    }
+
+    pub fn children(&self) -> &[MerkleTreeHash] {
+        &self.children
+    }
+
+    /// The id of the node this chunk is rooted at.
+    pub fn root_id(&self) -> ChunkedSnapshotResult<Ulid> {
+        self.nodes
+            .first()
+            .map(|node| node.id())
+            .ok_or(ChunkedSnapshotError::EmptyChunk)
+    }
+}
+
+impl SnapshotChunkEdge {
+    pub fn source(&self) -> Ulid {
+        self.source
+    }
+
+    pub fn target(&self) -> Ulid {
+        self.target
+    }
+
+    pub fn weight(&self) -> &EdgeWeight {
+        &self.weight
+    }
+}
+
+/// Nodes that get a chunk of their own.
+fn is_chunk_root(weight: &NodeWeight) -> bool {
+    match weight {
+        NodeWeight::Category(_)
+        | NodeWeight::Component(_)
+        | NodeWeight::Func(_)
+        | NodeWeight::SchemaVariant(_)
+        | NodeWeight::Secret(_)
+        | NodeWeight::View(_) => true,
+        NodeWeight::Content(content) => matches!(
+            content.content_address(),
+            ContentAddress::Module(_) | ContentAddress::Schema(_)
+        ),
+        _ => false,
+    }
+}
+
+/// Nodes that belong to a schema variant, even when something else (like an attribute value
+/// pointing at its prop) reaches them first. Keeping them out of component chunks means every
+/// component of a variant shares the variant's chunk rather than carrying its own copy.
+fn is_owned_by_schema_variant(weight: &NodeWeight) -> bool {
+    match weight {
+        NodeWeight::InputSocket(_) | NodeWeight::Prop(_) => true,
+        NodeWeight::Content(content) => {
+            matches!(content.content_address(), ContentAddress::OutputSocket(_))
+        }
+        _ => false,
+    }
+}
+
+/// The nodes that make up a chunk, before it is keyed.
+struct PlannedChunk {
+    nodes: Vec<NodeIndex>,
+    children: Vec<usize>,
+}
+
+/// Decides which chunk owns each node. Chunks are discovered breadth first from the root of the
+/// graph, and each claims the nodes reachable from it that are not chunk roots themselves and not
+/// already owned by another chunk. The first chunk is always the one rooted at the graph root.
+fn plan_chunks(graph: &WorkspaceSnapshotGraphV4) -> ChunkedSnapshotResult<Vec<PlannedChunk>> {
+    let inner = graph.graph();
+    let mut chunk_by_root: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut owned: HashSet<NodeIndex> = HashSet::new();
+    let mut planned: Vec<PlannedChunk> = Vec::new();
+    let mut queue: VecDeque<(NodeIndex, bool)> = VecDeque::new();
+
+    let mut enqueue = |root: NodeIndex,
+                       claims_variant_nodes: bool,
+                       planned: &mut Vec<PlannedChunk>,
+                       queue: &mut VecDeque<(NodeIndex, bool)>| {
+        *chunk_by_root.entry(root).or_insert_with(|| {
+            planned.push(PlannedChunk {
+                nodes: vec![root],
+                children: Vec::new(),
+            });
+            queue.push_back((root, claims_variant_nodes));
+            planned.len() - 1
+        })
+    };
+
+    owned.insert(graph.root());
+    enqueue(graph.root(), false, &mut planned, &mut queue);
+
+    // Anything left unclaimed once the tree has been walked gets a chunk hung off the root chunk,
+    // so every node is stored somewhere regardless of how it is reached.
+    let mut sweep_done = false;
+    loop {
+        while let Some((chunk_root, claims_variant_nodes)) = queue.pop_front() {
+            let chunk_index = enqueue(chunk_root, false, &mut planned, &mut queue);
+            let claims_variant_nodes = claims_variant_nodes
+                || matches!(
+                    graph.get_node_weight(chunk_root)?,
+                    NodeWeight::SchemaVariant(_)
+                );
+
+            let mut stack = vec![chunk_root];
+            while let Some(node_index) = stack.pop() {
+                let mut targets: Vec<(Ulid, NodeIndex)> = inner
+                    .neighbors_directed(node_index, Outgoing)
+                    .map(|target| Ok((graph.get_node_weight(target)?.id(), target)))
+                    .collect::<ChunkedSnapshotResult<_>>()?;
+                targets.sort();
+                targets.dedup();
+
+                for (_, target) in targets {
+                    let weight = graph.get_node_weight(target)?;
+                    if is_chunk_root(weight) {
+                        owned.insert(target);
+                        let child = enqueue(target, false, &mut planned, &mut queue);
+                        if child != chunk_index && !planned[chunk_index].children.contains(&child) {
+                            planned[chunk_index].children.push(child);
+                        }
+                    } else if owned.contains(&target)
+                        || (!claims_variant_nodes && is_owned_by_schema_variant(weight))
+                    {
+                        continue;
+                    } else {
+                        owned.insert(target);
+                        planned[chunk_index].nodes.push(target);
+                        stack.push(target);
+                    }
+                }
+            }
+        }
+
+        if sweep_done {
+            break;
+        }
+        sweep_done = true;
+
+        let mut unowned: Vec<(Ulid, NodeIndex)> = graph
+            .nodes()
+            .filter(|(_, index)| !owned.contains(index))
+            .map(|(weight, index)| (weight.id(), index))
+            .collect();
+        unowned.sort();
+        for (_, index) in unowned {
+            if owned.insert(index) {
+                let child = enqueue(index, true, &mut planned, &mut queue);
+                planned[0].children.push(child);
+            }
+        }
+        if queue.is_empty() {
+            break;
+        }
+    }
+
+    Ok(planned)
+}
+
+fn build_chunk(
+    graph: &WorkspaceSnapshotGraphV4,
+    planned: &PlannedChunk,
+    child_keys: &[MerkleTreeHash],
+) -> ChunkedSnapshotResult<SnapshotChunk> {
+    let inner = graph.graph();
+    let mut nodes = Vec::with_capacity(planned.nodes.len());
+    let mut edges = Vec::new();
+
+    for &node_index in &planned.nodes {
+        let weight = graph.get_node_weight(node_index)?;
+        let source = weight.id();
+        nodes.push(weight.clone());
+
+        let mut outgoing: Vec<SnapshotChunkEdge> = inner
+            .edges_directed(node_index, Outgoing)
+            .map(|edge_ref| {
+                Ok(SnapshotChunkEdge {
+                    source,
+                    target: graph.get_node_weight(edge_ref.target())?.id(),
+                    weight: edge_ref.weight().clone(),
+                })
+            })
+            .collect::<ChunkedSnapshotResult<_>>()?;
+        outgoing.sort_by_key(|edge| edge.target);
+        edges.extend(outgoing);
+    }
+
+    Ok(SnapshotChunk {
+        nodes,
+        edges,
+        children: child_keys.to_vec(),
+    })
+}
+
+/// Splits a graph into chunks, handing every chunk except the root one to `write_chunk` (children
+/// before their parents) to be stored and keyed. The root chunk is returned, since it is stored
+/// under the snapshot address rather than its own key.
+///
+/// The graph's merkle tree hashes must be up to date, since they are carried in the chunks.
+pub fn split_graph(
+    graph: &WorkspaceSnapshotGraphV4,
+    mut write_chunk: impl FnMut(SnapshotChunk) -> ChunkedSnapshotResult<MerkleTreeHash>,
+) -> ChunkedSnapshotResult<SnapshotChunk> {
+    let planned = plan_chunks(graph)?;
+    let mut keys: Vec<Option<MerkleTreeHash>> = vec![None; planned.len()];
+
+    // Iterative post-order over the chunk tree, so children are keyed before their parents.
+    let mut stack = vec![(0, false)];
+    let mut root_chunk = None;
+    while let Some((chunk_index, children_done)) = stack.pop() {
+        if keys[chunk_index].is_some() {
+            continue;
+        }
+        if !children_done {
+            stack.push((chunk_index, true));
+            for &child in &planned[chunk_index].children {
+                if keys[child].is_none() {
+                    stack.push((child, false));
+                }
+            }
+            continue;
+        }
+
+        let child_keys: Vec<MerkleTreeHash> = planned[chunk_index]
+            .children
+            .iter()
+            .filter_map(|&child| keys[child])
+            .collect();
+        let chunk = build_chunk(graph, &planned[chunk_index], &child_keys)?;
+
+        if chunk_index == 0 {
+            root_chunk = Some(chunk);
+            break;
+        }
+        keys[chunk_index] = Some(write_chunk(chunk)?);
+    }
+
+    root_chunk.ok_or(ChunkedSnapshotError::EmptyChunk)
+}
+
+/// Rebuilds a graph from its root chunk and every chunk beneath it.
+pub fn assemble_graph<'a>(
+    root_chunk: &'a SnapshotChunk,
+    chunks: impl IntoIterator<Item = &'a SnapshotChunk>,
+) -> ChunkedSnapshotResult<WorkspaceSnapshotGraphV4> {
+    let chunks: Vec<&SnapshotChunk> = std::iter::once(root_chunk).chain(chunks).collect();
+
+    let mut graph: StableDiGraph<NodeWeight, EdgeWeight> = StableDiGraph::with_capacity(
+        chunks.iter().map(|chunk| chunk.nodes.len()).sum(),
+        chunks.iter().map(|chunk| chunk.edges.len()).sum(),
+    );
+    let mut node_index_by_id = HashMap::new();
+    let mut node_indices_by_lineage_id: HashMap<Ulid, HashSet<NodeIndex>> = HashMap::new();
+
+    for chunk in &chunks {
+        for node in &chunk.nodes {
+            if node_index_by_id.contains_key(&node.id()) {
+                continue;
+            }
+            let node_index = graph.add_node(node.clone());
+            node_index_by_id.insert(node.id(), node_index);
+            node_indices_by_lineage_id
+                .entry(node.lineage_id())
+                .or_default()
+                .insert(node_index);
+        }
+    }
+
+    for chunk in &chunks {
+        for edge in &chunk.edges {
+            let source = *node_index_by_id
+                .get(&edge.source)
+                .ok_or(ChunkedSnapshotError::EdgeNodeMissing(edge.source))?;
+            let target = *node_index_by_id
+                .get(&edge.target)
+                .ok_or(ChunkedSnapshotError::EdgeNodeMissing(edge.target))?;
+            graph.add_edge(source, target, edge.weight.clone());
+        }
+    }
+
+    let root_id = root_chunk.root_id()?;
+    let root_index = *node_index_by_id
+        .get(&root_id)
+        .ok_or(ChunkedSnapshotError::EdgeNodeMissing(root_id))?;
+
+    Ok(WorkspaceSnapshotGraphV4::new_from_parts(
+        graph,
+        node_index_by_id,
+        node_indices_by_lineage_id,
+        root_index,
+    ))
+}
+
+/// Writes a graph as chunks, returning the address of the snapshot. Chunks this instance has
+/// already seen are not persisted again.
+#[instrument(
+    name = "workspace_snapshot.chunked.write",
+    level = "debug",
+    skip_all,
+    fields(
+        si.workspace_snapshot.chunks.total = Empty,
+        si.workspace_snapshot.chunks.written = Empty,
+    )
+)]
+pub fn write(
+    layer_db: &DalLayerDb,
+    graph: &WorkspaceSnapshotGraphV4,
+    tenancy: Tenancy,
+    actor: Actor,
+) -> ChunkedSnapshotResult<WorkspaceSnapshotAddress> {
+    let span = current_span_for_instrument_at!("debug");
+    let chunk_db = layer_db.workspace_snapshot_chunk();
+
+    let mut total = 0;
+    let mut written = 0;
+    let root_chunk = split_graph(graph, |chunk| {
+        let (key, reader) = chunk_db.write(Arc::new(chunk), tenancy, actor)?;
+        total += 1;
+        if reader.is_some() {
+            written += 1;
+        }
+        Ok(key)
+    })?;
+    let (address, _) = chunk_db.write_root(Arc::new(root_chunk), None, tenancy, actor)?;
+
+    span.record("si.workspace_snapshot.chunks.total", total);
+    span.record("si.workspace_snapshot.chunks.written", written);
+
+    Ok(address)
+}
+
+/// Estimates the memory used by an assembled graph, for the cache to weigh it by.
+pub fn estimated_size(graph: &WorkspaceSnapshotGraphV4) -> usize {
+    graph.node_count() * ESTIMATED_BYTES_PER_NODE
+}
+
+/// Reads a chunked snapshot and assembles every chunk into a graph. Returns `None` if there is no
+/// chunked snapshot at the address, which means it was stored whole.
+#[instrument(
+    name = "workspace_snapshot.chunked.read",
+    level = "debug",
+    skip_all,
+    fields(
+        si.workspace_snapshot.address = %address,
+        si.workspace_snapshot.chunks.total = Empty,
+    )
+)]
+pub async fn read(
+    layer_db: &DalLayerDb,
+    address: WorkspaceSnapshotAddress,
+) -> ChunkedSnapshotResult<Option<WorkspaceSnapshotGraphV4>> {
+    let span = current_span_for_instrument_at!("debug");
+    let chunk_db = layer_db.workspace_snapshot_chunk();
+
+    let Some(root_chunk) = chunk_db.read_root(&address, false).await? else {
+        return Ok(None);
+    };
+
+    let mut seen: HashSet<MerkleTreeHash> = HashSet::new();
+    let mut chunks: Vec<Arc<SnapshotChunk>> = Vec::new();
+    let mut frontier: Vec<MerkleTreeHash> = root_chunk.children.clone();
+    while !frontier.is_empty() {
+        frontier.retain(|key| seen.insert(*key));
+        let mut found = chunk_db.read_many(&frontier).await?;
+
+        let mut next = Vec::new();
+        for key in frontier {
+            let chunk = found
+                .remove(&key)
+                .ok_or(ChunkedSnapshotError::ChunkMissing(key))?;
+            next.extend(
+                chunk
+                    .children
+                    .iter()
+                    .filter(|child| !seen.contains(child))
+                    .copied(),
+            );
+            chunks.push(chunk);
+        }
+        frontier = next;
+    }
+
+    span.record("si.workspace_snapshot.chunks.total", chunks.len() + 1);
+
+    Ok(Some(assemble_graph(
+        &root_chunk,
+        chunks.iter().map(AsRef::as_ref),
+    )?))
+}
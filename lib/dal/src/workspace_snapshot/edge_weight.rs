@@ -69,6 +69,8 @@ pub enum EdgeWeightKind {
     Manages,
     /// From a view node to a diagram object node, to which geometries can be connected.
     DiagramObject,
+    /// From a [`Component`](crate::Component) to one of its tags.
+    Tag,
 }
 
 impl EdgeWeightKind {
@@ -7,6 +7,7 @@ use crate::{
     EdgeWeight, EdgeWeightKind, PropKind,
 };
 
+mod chunked;
 mod detect_updates;
 mod exclusive_outgoing_edges;
 mod rebase;
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
+
+    use si_events::{merkle_tree_hash::MerkleTreeHash, ulid::Ulid, ContentHash};
+    use si_layer_cache::db::serialize;
+
+    use crate::{
+        workspace_snapshot::{
+            chunked::{self, SnapshotChunk},
+            graph::WorkspaceSnapshotGraphVCurrent,
+            node_weight::NodeWeight,
+        },
+        PropKind,
+    };
+
+    use super::super::{add_edges, add_prop_nodes_to_graph};
+
+    /// Splits a graph the way [`chunked::write`] does, keeping the chunks in memory.
+    fn split(
+        graph: &WorkspaceSnapshotGraphVCurrent,
+    ) -> (SnapshotChunk, HashMap<MerkleTreeHash, SnapshotChunk>) {
+        let mut chunks = HashMap::new();
+        let root_chunk = chunked::split_graph(graph, |chunk| {
+            let (_, uncompressed) = serialize::to_vec_with_uncompressed(&chunk)?;
+            let key = MerkleTreeHash::new(&uncompressed);
+            chunks.insert(key, chunk);
+            Ok(key)
+        })
+        .expect("split graph");
+
+        (root_chunk, chunks)
+    }
+
+    fn build_graph(
+        names: &[&'static str],
+    ) -> (WorkspaceSnapshotGraphVCurrent, HashMap<&'static str, Ulid>) {
+        let mut graph = WorkspaceSnapshotGraphVCurrent::new_for_unit_tests().expect("create graph");
+        let node_id_map = add_prop_nodes_to_graph(&mut graph, names, false);
+
+        let mut edges = Vec::new();
+        for pair in names.chunks(2) {
+            edges.push((None, pair[0]));
+            if let Some(child) = pair.get(1) {
+                edges.push((Some(pair[0]), *child));
+            }
+        }
+        add_edges(&mut graph, &node_id_map, &edges);
+        graph.cleanup_and_merkle_tree_hash().expect("merkle hash");
+
+        (graph, node_id_map)
+    }
+
+    fn replace_prop_content(
+        graph: &mut WorkspaceSnapshotGraphVCurrent,
+        node_id_map: &HashMap<&str, Ulid>,
+        name: &str,
+        new_content: &str,
+    ) {
+        let id = node_id_map.get(name).copied().expect("prop id");
+        let lineage_id = graph
+            .get_node_weight(graph.get_node_index_by_id(id).expect("node index"))
+            .expect("node weight")
+            .lineage_id();
+        graph
+            .add_or_replace_node(NodeWeight::new_prop(
+                id,
+                lineage_id,
+                PropKind::Object,
+                name,
+                ContentHash::new(new_content.as_bytes()),
+            ))
+            .expect("replace prop");
+        graph.cleanup_and_merkle_tree_hash().expect("merkle hash");
+    }
+
+    #[test]
+    fn round_trip() {
+        let (graph, node_id_map) = build_graph(&["a", "b", "c", "d", "e"]);
+
+        let (root_chunk, chunks) = split(&graph);
+        let assembled =
+            chunked::assemble_graph(&root_chunk, chunks.values()).expect("assemble graph");
+
+        assert_eq!(graph.node_count(), assembled.node_count());
+        assert_eq!(graph.graph().edge_count(), assembled.graph().edge_count());
+        assert_eq!(
+            graph
+                .get_node_weight(graph.root())
+                .expect("root weight")
+                .merkle_tree_hash(),
+            assembled
+                .get_node_weight(assembled.root())
+                .expect("root weight")
+                .merkle_tree_hash(),
+        );
+        for id in node_id_map.values() {
+            assembled
+                .get_node_index_by_id(*id)
+                .expect("node is in the assembled graph");
+        }
+    }
+
+    #[test]
+    fn unchanged_chunks_are_shared() {
+        let (mut graph, node_id_map) = build_graph(&["a", "b", "c", "d", "e", "f"]);
+        let (before_root, before) = split(&graph);
+
+        replace_prop_content(&mut graph, &node_id_map, "d", "an edit");
+        let (after_root, after) = split(&graph);
+
+        let before_keys: HashSet<_> = before.keys().copied().collect();
+        let after_keys: HashSet<_> = after.keys().copied().collect();
+        let rewritten = after_keys.difference(&before_keys).count();
+
+        assert_eq!(before_keys.len(), after_keys.len());
+        assert!(rewritten > 0);
+        assert!(rewritten < after_keys.len());
+        assert_ne!(before_root.children(), after_root.children());
+    }
+
+    #[test]
+    fn splitting_is_deterministic() {
+        let (graph, _) = build_graph(&["a", "b", "c", "d"]);
+
+        let (first_root, first) = split(&graph);
+        let (second_root, second) = split(&graph);
+
+        let first_keys: HashSet<_> = first.keys().copied().collect();
+        let second_keys: HashSet<_> = second.keys().copied().collect();
+        assert_eq!(first_keys, second_keys);
+        assert_eq!(first_root.children(), second_root.children());
+    }
+}
@@ -751,6 +751,7 @@ impl WorkspaceSnapshotGraphV3 {
                     EdgeWeightKindDiscriminants::ManagementPrototype => "pink",
                     EdgeWeightKindDiscriminants::Manages => "pink",
                     EdgeWeightKindDiscriminants::DiagramObject => "black",
+                    EdgeWeightKindDiscriminants::Tag => "gold",
                 };
 
                 match edgeref.weight().kind() {
@@ -876,6 +877,7 @@ impl WorkspaceSnapshotGraphV3 {
                         ("ManagementPrototype".to_string(), "black")
                     }
                     NodeWeight::DiagramObject(_) => ("DiagramObject".to_string(), "black"),
+                    NodeWeight::Tag(tag) => (format!("Tag\n{}={}", tag.key(), tag.value()), "gold"),
                 };
                 let color = color.to_string();
                 let id = node_weight.id();
@@ -1414,7 +1416,8 @@ impl WorkspaceSnapshotGraphV3 {
                     | EdgeWeightKind::ValidationOutput
                     | EdgeWeightKind::ManagementPrototype
                     | EdgeWeightKind::Manages
-                    | EdgeWeightKind::DiagramObject => {}
+                    | EdgeWeightKind::DiagramObject
+                    | EdgeWeightKind::Tag => {}
                 }
             }
         }
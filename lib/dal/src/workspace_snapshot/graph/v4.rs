@@ -35,6 +35,7 @@ use crate::{
 
 pub mod component;
 pub mod diagram;
+pub mod fsck;
 pub mod schema;
 pub mod socket;
 
@@ -875,6 +876,7 @@ impl WorkspaceSnapshotGraphV4 {
                     EdgeWeightKindDiscriminants::ManagementPrototype => "pink",
                     EdgeWeightKindDiscriminants::Manages => "pink",
                     EdgeWeightKindDiscriminants::DiagramObject => "black",
+                    EdgeWeightKindDiscriminants::Tag => "gold",
                 };
 
                 match edgeref.weight().kind() {
@@ -1000,6 +1002,7 @@ impl WorkspaceSnapshotGraphV4 {
                         ("ManagementPrototype".to_string(), "black")
                     }
                     NodeWeight::DiagramObject(_) => ("DiagramObject".to_string(), "black"),
+                    NodeWeight::Tag(tag) => (format!("Tag\n{}={}", tag.key(), tag.value()), "gold"),
                 };
                 let color = color.to_string();
                 let id = node_weight.id();
@@ -1560,7 +1563,8 @@ impl WorkspaceSnapshotGraphV4 {
                     | EdgeWeightKind::ValidationOutput
                     | EdgeWeightKind::ManagementPrototype
                     | EdgeWeightKind::Manages
-                    | EdgeWeightKind::DiagramObject => {}
+                    | EdgeWeightKind::DiagramObject
+                    | EdgeWeightKind::Tag => {}
                 }
             }
         }
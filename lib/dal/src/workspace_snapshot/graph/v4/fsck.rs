@@ -0,0 +1,729 @@
+//! Consistency checks over the structure of a [`WorkspaceSnapshotGraphV4`].
+//!
+//! [`WorkspaceSnapshotGraphV4::fsck`] walks the whole graph and reports every node that breaks
+//! one of the invariants the rest of the dal relies on. Violations that can be fixed without
+//! guessing at user intent are marked [repairable](SnapshotViolationKind::is_repairable), and
+//! [`WorkspaceSnapshotGraphV4::repair`] applies those fixes.
+
+use std::collections::{HashMap, HashSet};
+
+use petgraph::{algo, prelude::*};
+use serde::{Deserialize, Serialize};
+use si_events::ulid::Ulid;
+use strum::{Display, IntoEnumIterator};
+
+use crate::{
+    workspace_snapshot::{
+        content_address::ContentAddressDiscriminants,
+        graph::{WorkspaceSnapshotGraphResult, WorkspaceSnapshotGraphV4},
+        node_weight::{
+            category_node_weight::CategoryNodeKind, NodeWeight, NodeWeightDiscriminants,
+        },
+    },
+    EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants,
+};
+
+#[remain::sorted]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapshotViolationKind {
+    /// A component has no incoming edge from the component category.
+    ComponentMissingCategory,
+    /// A component has no [`Root`](EdgeWeightKind::Root) edge to its root attribute value.
+    ComponentMissingRoot,
+    /// The node is part of a cycle.
+    Cycle,
+    /// An attribute prototype argument has no prototype.
+    DanglingPrototypeArgument,
+    /// More than one category node of the same kind.
+    DuplicateCategory,
+    /// More than one node with the same id.
+    DuplicateNodeId,
+    /// More than one outgoing edge of a kind the node only allows one of.
+    ExclusiveEdgeViolated,
+    /// The id and lineage indexes don't match the nodes in the graph.
+    IndexOutOfSync,
+    /// An attribute prototype argument takes its value from a node that can't be a value source.
+    InvalidPrototypeArgumentValue,
+    /// A category node that every snapshot has is missing.
+    MissingCategory,
+    /// The node refers to content that is not in the CAS.
+    MissingContent,
+    /// An ordering node's order doesn't match the children of its container.
+    OrderingOutOfSync,
+    /// An attribute value has no parent value, component or socket.
+    OrphanedAttributeValue,
+    /// A container has more than one ordering node.
+    TooManyOrderingNodes,
+    /// The node can't be reached from the root of the graph.
+    Unreachable,
+}
+
+impl SnapshotViolationKind {
+    /// Whether [`WorkspaceSnapshotGraphV4::repair`] can fix this kind of violation. Repairs only
+    /// remove nodes that nothing can reach or use, rebuild derived data, or add what every
+    /// snapshot is expected to have.
+    pub fn is_repairable(&self) -> bool {
+        match self {
+            Self::DanglingPrototypeArgument
+            | Self::IndexOutOfSync
+            | Self::MissingCategory
+            | Self::OrderingOutOfSync
+            | Self::OrphanedAttributeValue
+            | Self::Unreachable => true,
+            Self::ComponentMissingCategory
+            | Self::ComponentMissingRoot
+            | Self::Cycle
+            | Self::DuplicateCategory
+            | Self::DuplicateNodeId
+            | Self::ExclusiveEdgeViolated
+            | Self::InvalidPrototypeArgumentValue
+            | Self::MissingContent
+            | Self::TooManyOrderingNodes => false,
+        }
+    }
+}
+
+/// A broken invariant, found by [`WorkspaceSnapshotGraphV4::fsck`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotViolation {
+    pub kind: SnapshotViolationKind,
+    /// The offending node. For a [`SnapshotViolationKind::MissingCategory`], the root node.
+    pub node_id: Ulid,
+    /// The nodes leading from the root to the offending node, or from the furthest ancestor that
+    /// could be found when the node is not reachable from the root.
+    pub path: Vec<String>,
+    pub message: String,
+    pub repairable: bool,
+}
+
+impl WorkspaceSnapshotGraphV4 {
+    /// Checks the structural invariants of the graph and returns every violation found.
+    pub fn fsck(&self) -> Vec<SnapshotViolation> {
+        let mut violations = Vec::new();
+
+        self.fsck_indexes(&mut violations);
+        self.fsck_reachability(&mut violations);
+        self.fsck_categories(&mut violations);
+
+        for (node_weight, node_index) in self.nodes() {
+            self.fsck_exclusive_edges(node_weight, node_index, &mut violations);
+            match node_weight {
+                NodeWeight::AttributePrototypeArgument(_) => {
+                    self.fsck_prototype_argument(node_index, &mut violations)
+                }
+                NodeWeight::AttributeValue(_) => {
+                    if self
+                        .incoming_edge_kinds(node_index)
+                        .iter()
+                        .all(|kind| !Self::is_attribute_value_parent_edge(*kind))
+                    {
+                        violations.push(
+                            self.violation(
+                                SnapshotViolationKind::OrphanedAttributeValue,
+                                node_index,
+                                "attribute value has no incoming Root, Contain or SocketValue edge"
+                                    .to_string(),
+                            ),
+                        );
+                    }
+                }
+                NodeWeight::Component(_) => self.fsck_component(node_index, &mut violations),
+                NodeWeight::Ordering(_) => self.fsck_ordering(node_index, &mut violations),
+                _ => {}
+            }
+        }
+
+        if let Err(cycle) = algo::toposort(&self.graph, None) {
+            violations.push(self.violation(
+                SnapshotViolationKind::Cycle,
+                cycle.node_id(),
+                "node is part of a cycle".to_string(),
+            ));
+        }
+
+        violations
+    }
+
+    /// Repairs every [repairable](SnapshotViolationKind::is_repairable) violation in the list,
+    /// returning the ones that were repaired. The violations should come from calling
+    /// [`Self::fsck`] on this graph.
+    pub fn repair(
+        &mut self,
+        violations: &[SnapshotViolation],
+    ) -> WorkspaceSnapshotGraphResult<Vec<SnapshotViolation>> {
+        let mut repaired = Vec::new();
+
+        // The indexes are used to find everything else, so they go first.
+        if violations
+            .iter()
+            .any(|violation| violation.kind == SnapshotViolationKind::IndexOutOfSync)
+        {
+            self.rebuild_indexes();
+            repaired.extend(
+                violations
+                    .iter()
+                    .filter(|violation| violation.kind == SnapshotViolationKind::IndexOutOfSync)
+                    .cloned(),
+            );
+        }
+
+        if violations
+            .iter()
+            .any(|violation| violation.kind == SnapshotViolationKind::MissingCategory)
+        {
+            self.add_missing_categories()?;
+            repaired.extend(
+                violations
+                    .iter()
+                    .filter(|violation| violation.kind == SnapshotViolationKind::MissingCategory)
+                    .cloned(),
+            );
+        }
+
+        for violation in violations {
+            let node_index = self.get_node_index_by_id_opt(violation.node_id);
+            match (violation.kind, node_index) {
+                (SnapshotViolationKind::OrderingOutOfSync, Some(node_index)) => {
+                    self.resync_ordering(node_index)?;
+                }
+                (
+                    SnapshotViolationKind::DanglingPrototypeArgument
+                    | SnapshotViolationKind::OrphanedAttributeValue
+                    | SnapshotViolationKind::Unreachable,
+                    Some(node_index),
+                ) => {
+                    self.remove_node(node_index);
+                    self.remove_node_id(violation.node_id);
+                }
+                _ => continue,
+            }
+            repaired.push(violation.clone());
+        }
+
+        // Anything that was only reachable through a removed node goes too.
+        self.cleanup_and_merkle_tree_hash()?;
+
+        Ok(repaired)
+    }
+
+    /// The nodes leading to the node, following incoming edges back towards the root. Ordinal
+    /// edges are only followed when there is no other way up, so that elements of a container are
+    /// shown under the container rather than its ordering node.
+    pub fn path_to_node(&self, node_index: NodeIndex) -> Vec<String> {
+        let mut path = vec![self.node_label(node_index)];
+        let mut visited = HashSet::from([node_index]);
+        let mut current = node_index;
+
+        while current != self.root() {
+            let mut parents: Vec<_> = self
+                .graph
+                .edges_directed(current, Incoming)
+                .map(|edge_ref| {
+                    (
+                        EdgeWeightKindDiscriminants::from(edge_ref.weight().kind()),
+                        edge_ref.source(),
+                    )
+                })
+                .filter(|(_, source)| !visited.contains(source))
+                .collect();
+            parents.sort_by_key(|(kind, _)| *kind == EdgeWeightKindDiscriminants::Ordinal);
+            let Some((_, parent)) = parents.first().copied() else {
+                break;
+            };
+
+            visited.insert(parent);
+            path.push(self.node_label(parent));
+            current = parent;
+        }
+
+        path.reverse();
+        path
+    }
+
+    pub(crate) fn violation(
+        &self,
+        kind: SnapshotViolationKind,
+        node_index: NodeIndex,
+        message: String,
+    ) -> SnapshotViolation {
+        SnapshotViolation {
+            kind,
+            node_id: self.node_index_to_id(node_index).unwrap_or_default(),
+            path: self.path_to_node(node_index),
+            message,
+            repairable: kind.is_repairable(),
+        }
+    }
+
+    fn node_label(&self, node_index: NodeIndex) -> String {
+        match self.get_node_weight_opt(node_index) {
+            Some(NodeWeight::Category(category)) => format!("Category({})", category.kind()),
+            Some(NodeWeight::Content(content)) => format!(
+                "{}({})",
+                content.content_address_discriminants(),
+                content.id()
+            ),
+            Some(node_weight) => format!(
+                "{}({})",
+                NodeWeightDiscriminants::from(node_weight),
+                node_weight.id()
+            ),
+            None => format!("missing({node_index:?})"),
+        }
+    }
+
+    fn incoming_edge_kinds(&self, node_index: NodeIndex) -> Vec<EdgeWeightKindDiscriminants> {
+        self.graph
+            .edges_directed(node_index, Incoming)
+            .map(|edge_ref| EdgeWeightKindDiscriminants::from(edge_ref.weight().kind()))
+            .collect()
+    }
+
+    fn is_attribute_value_parent_edge(kind: EdgeWeightKindDiscriminants) -> bool {
+        matches!(
+            kind,
+            EdgeWeightKindDiscriminants::Root
+                | EdgeWeightKindDiscriminants::Contain
+                | EdgeWeightKindDiscriminants::SocketValue
+        )
+    }
+
+    fn fsck_indexes(&self, violations: &mut Vec<SnapshotViolation>) {
+        let mut id_counts: HashMap<Ulid, usize> = HashMap::new();
+        for (node_weight, _) in self.nodes() {
+            *id_counts.entry(node_weight.id()).or_default() += 1;
+        }
+
+        let mut seen_ids = HashSet::new();
+        for (node_weight, node_index) in self.nodes() {
+            let id = node_weight.id();
+            if !seen_ids.insert(id) {
+                violations.push(self.violation(
+                    SnapshotViolationKind::DuplicateNodeId,
+                    node_index,
+                    format!("another node has the id {id}"),
+                ));
+            } else if id_counts.get(&id).is_some_and(|count| *count > 1) {
+                // The id index can only point at one of the nodes sharing an id, so which one it
+                // picked is not a problem of its own.
+                continue;
+            } else if self.node_index_by_id.get(&id) != Some(&node_index) {
+                violations.push(self.violation(
+                    SnapshotViolationKind::IndexOutOfSync,
+                    node_index,
+                    "node is missing from the id index".to_string(),
+                ));
+            } else if !self
+                .node_indices_by_lineage_id
+                .get(&node_weight.lineage_id())
+                .is_some_and(|indices| indices.contains(&node_index))
+            {
+                violations.push(self.violation(
+                    SnapshotViolationKind::IndexOutOfSync,
+                    node_index,
+                    "node is missing from the lineage index".to_string(),
+                ));
+            }
+        }
+
+        for (id, node_index) in &self.node_index_by_id {
+            if self.get_node_weight_opt(*node_index).map(NodeWeight::id) != Some(*id) {
+                violations.push(SnapshotViolation {
+                    kind: SnapshotViolationKind::IndexOutOfSync,
+                    node_id: *id,
+                    path: Vec::new(),
+                    message: format!("id index points at {node_index:?}, which is another node"),
+                    repairable: SnapshotViolationKind::IndexOutOfSync.is_repairable(),
+                });
+            }
+        }
+    }
+
+    fn fsck_reachability(&self, violations: &mut Vec<SnapshotViolation>) {
+        let mut reachable = HashSet::new();
+        let mut bfs = Bfs::new(&self.graph, self.root());
+        while let Some(node_index) = bfs.next(&self.graph) {
+            reachable.insert(node_index);
+        }
+
+        for node_index in self.graph.node_indices() {
+            if !reachable.contains(&node_index) {
+                violations.push(self.violation(
+                    SnapshotViolationKind::Unreachable,
+                    node_index,
+                    "node cannot be reached from the root".to_string(),
+                ));
+            }
+        }
+    }
+
+    fn fsck_categories(&self, violations: &mut Vec<SnapshotViolation>) {
+        let mut categories: HashMap<CategoryNodeKind, Vec<NodeIndex>> = HashMap::new();
+        for (node_weight, node_index) in self.nodes() {
+            if let NodeWeight::Category(category) = node_weight {
+                categories
+                    .entry(category.kind())
+                    .or_default()
+                    .push(node_index);
+            }
+        }
+
+        for kind in CategoryNodeKind::iter() {
+            match categories.get(&kind).map(Vec::as_slice) {
+                None | Some([]) => violations.push(self.violation(
+                    SnapshotViolationKind::MissingCategory,
+                    self.root(),
+                    format!("no {kind} category node"),
+                )),
+                Some([_]) => {}
+                Some(duplicates) => {
+                    for node_index in duplicates {
+                        violations.push(self.violation(
+                            SnapshotViolationKind::DuplicateCategory,
+                            *node_index,
+                            format!("{} category nodes of kind {kind}", duplicates.len()),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    fn fsck_exclusive_edges(
+        &self,
+        node_weight: &NodeWeight,
+        node_index: NodeIndex,
+        violations: &mut Vec<SnapshotViolation>,
+    ) {
+        for exclusive_kind in node_weight.exclusive_outgoing_edges() {
+            let count = self
+                .graph
+                .edges_directed(node_index, Outgoing)
+                .filter(|edge_ref| {
+                    EdgeWeightKindDiscriminants::from(edge_ref.weight().kind()) == *exclusive_kind
+                })
+                .count();
+            if count > 1 {
+                violations.push(self.violation(
+                    SnapshotViolationKind::ExclusiveEdgeViolated,
+                    node_index,
+                    format!("{count} outgoing {exclusive_kind} edges, at most one is allowed"),
+                ));
+            }
+        }
+    }
+
+    fn fsck_prototype_argument(
+        &self,
+        node_index: NodeIndex,
+        violations: &mut Vec<SnapshotViolation>,
+    ) {
+        if !self
+            .incoming_edge_kinds(node_index)
+            .contains(&EdgeWeightKindDiscriminants::PrototypeArgument)
+        {
+            violations.push(self.violation(
+                SnapshotViolationKind::DanglingPrototypeArgument,
+                node_index,
+                "attribute prototype argument has no incoming PrototypeArgument edge".to_string(),
+            ));
+        }
+
+        for (_, _, target_index) in self.edges_directed_for_edge_weight_kind(
+            node_index,
+            Outgoing,
+            EdgeWeightKindDiscriminants::PrototypeArgumentValue,
+        ) {
+            let is_value_source = match self.get_node_weight_opt(target_index) {
+                Some(NodeWeight::Prop(_) | NodeWeight::Secret(_) | NodeWeight::InputSocket(_)) => {
+                    true
+                }
+                Some(NodeWeight::Content(content)) => matches!(
+                    content.content_address_discriminants(),
+                    ContentAddressDiscriminants::InputSocket
+                        | ContentAddressDiscriminants::OutputSocket
+                        | ContentAddressDiscriminants::StaticArgumentValue
+                ),
+                _ => false,
+            };
+            if !is_value_source {
+                violations.push(self.violation(
+                    SnapshotViolationKind::InvalidPrototypeArgumentValue,
+                    node_index,
+                    format!(
+                        "PrototypeArgumentValue edge points at {}, which is not a value source",
+                        self.node_label(target_index)
+                    ),
+                ));
+            }
+        }
+    }
+
+    fn fsck_component(&self, node_index: NodeIndex, violations: &mut Vec<SnapshotViolation>) {
+        if self
+            .edges_directed_for_edge_weight_kind(
+                node_index,
+                Outgoing,
+                EdgeWeightKindDiscriminants::Root,
+            )
+            .is_empty()
+        {
+            violations.push(self.violation(
+                SnapshotViolationKind::ComponentMissingRoot,
+                node_index,
+                "component has no Root edge to an attribute value".to_string(),
+            ));
+        }
+
+        let has_category = self
+            .graph
+            .neighbors_directed(node_index, Incoming)
+            .any(|source| {
+                matches!(
+                    self.get_node_weight_opt(source),
+                    Some(NodeWeight::Category(category))
+                        if category.kind() == CategoryNodeKind::Component
+                )
+            });
+        if !has_category {
+            violations.push(self.violation(
+                SnapshotViolationKind::ComponentMissingCategory,
+                node_index,
+                "component has no incoming edge from the component category".to_string(),
+            ));
+        }
+    }
+
+    fn fsck_ordering(&self, node_index: NodeIndex, violations: &mut Vec<SnapshotViolation>) {
+        let containers: Vec<_> = self
+            .edges_directed_for_edge_weight_kind(
+                node_index,
+                Incoming,
+                EdgeWeightKindDiscriminants::Ordering,
+            )
+            .into_iter()
+            .map(|(_, source, _)| source)
+            .collect();
+        for container_index in &containers {
+            let ordering_count = self
+                .edges_directed_for_edge_weight_kind(
+                    *container_index,
+                    Outgoing,
+                    EdgeWeightKindDiscriminants::Ordering,
+                )
+                .len();
+            // Only report this once, from the first of the container's ordering nodes.
+            if ordering_count > 1
+                && self
+                    .ordering_node_indexes(*container_index)
+                    .first()
+                    .is_some_and(|first| *first == node_index)
+            {
+                violations.push(self.violation(
+                    SnapshotViolationKind::TooManyOrderingNodes,
+                    *container_index,
+                    format!("container has {ordering_count} ordering nodes"),
+                ));
+            }
+        }
+
+        if let Some(problem) = self.ordering_problem(node_index) {
+            violations.push(self.violation(
+                SnapshotViolationKind::OrderingOutOfSync,
+                node_index,
+                problem,
+            ));
+        }
+    }
+
+    fn ordering_node_indexes(&self, container_index: NodeIndex) -> Vec<NodeIndex> {
+        let mut indexes: Vec<_> = self
+            .edges_directed_for_edge_weight_kind(
+                container_index,
+                Outgoing,
+                EdgeWeightKindDiscriminants::Ordering,
+            )
+            .into_iter()
+            .map(|(_, _, target)| target)
+            .collect();
+        indexes.sort();
+        indexes
+    }
+
+    /// The ids that the ordering node should order: the elements of its container when the
+    /// container is an attribute value, or whatever the ordering node points at otherwise.
+    fn expected_order_ids(&self, ordering_index: NodeIndex) -> HashSet<Ulid> {
+        let container_index = self
+            .edges_directed_for_edge_weight_kind(
+                ordering_index,
+                Incoming,
+                EdgeWeightKindDiscriminants::Ordering,
+            )
+            .into_iter()
+            .map(|(_, source, _)| source)
+            .next();
+
+        let (source_index, edge_kind) = match container_index {
+            Some(container_index)
+                if matches!(
+                    self.get_node_weight_opt(container_index),
+                    Some(NodeWeight::AttributeValue(_))
+                ) =>
+            {
+                (container_index, EdgeWeightKindDiscriminants::Contain)
+            }
+            _ => (ordering_index, EdgeWeightKindDiscriminants::Ordinal),
+        };
+
+        self.edges_directed_for_edge_weight_kind(source_index, Outgoing, edge_kind)
+            .into_iter()
+            .filter_map(|(_, _, target)| self.node_index_to_id(target))
+            .collect()
+    }
+
+    fn ordinal_ids(&self, ordering_index: NodeIndex) -> HashSet<Ulid> {
+        self.edges_directed_for_edge_weight_kind(
+            ordering_index,
+            Outgoing,
+            EdgeWeightKindDiscriminants::Ordinal,
+        )
+        .into_iter()
+        .filter_map(|(_, _, target)| self.node_index_to_id(target))
+        .collect()
+    }
+
+    fn ordering_problem(&self, ordering_index: NodeIndex) -> Option<String> {
+        let Some(NodeWeight::Ordering(ordering)) = self.get_node_weight_opt(ordering_index) else {
+            return None;
+        };
+
+        let order: HashSet<Ulid> = ordering.order().iter().copied().collect();
+        if order.len() != ordering.order().len() {
+            return Some("order lists the same node more than once".to_string());
+        }
+        if let Some(missing) = order
+            .iter()
+            .find(|id| self.get_node_index_by_id_opt(**id).is_none())
+        {
+            return Some(format!("order lists {missing}, which is not in the graph"));
+        }
+
+        let expected = self.expected_order_ids(ordering_index);
+        if let Some(extra) = order.difference(&expected).next() {
+            return Some(format!(
+                "order lists {extra}, which is not in the container"
+            ));
+        }
+        if let Some(missing) = expected.difference(&order).next() {
+            return Some(format!(
+                "container has {missing}, which is not in the order"
+            ));
+        }
+        if self.ordinal_ids(ordering_index) != order {
+            return Some("Ordinal edges don't match the order".to_string());
+        }
+
+        None
+    }
+
+    /// Keeps the elements of the order that belong to the container, in the same order, appends
+    /// the container's missing elements and points the Ordinal edges at exactly those elements.
+    fn resync_ordering(&mut self, ordering_index: NodeIndex) -> WorkspaceSnapshotGraphResult<()> {
+        let Some(NodeWeight::Ordering(ordering)) = self.get_node_weight_opt(ordering_index) else {
+            return Ok(());
+        };
+
+        let expected = self.expected_order_ids(ordering_index);
+        let mut seen = HashSet::new();
+        let mut new_order: Vec<Ulid> = ordering
+            .order()
+            .iter()
+            .copied()
+            .filter(|id| expected.contains(id) && seen.insert(*id))
+            .collect();
+        let mut missing: Vec<Ulid> = expected.difference(&seen).copied().collect();
+        missing.sort();
+        new_order.extend(missing);
+
+        let ordinal_ids = self.ordinal_ids(ordering_index);
+        let new_ids: HashSet<Ulid> = new_order.iter().copied().collect();
+        for stale_id in ordinal_ids.difference(&new_ids) {
+            if let Some(target_index) = self.get_node_index_by_id_opt(*stale_id) {
+                let stale_edges: Vec<_> = self
+                    .graph
+                    .edges_connecting(ordering_index, target_index)
+                    .filter(|edge_ref| {
+                        EdgeWeightKindDiscriminants::from(edge_ref.weight().kind())
+                            == EdgeWeightKindDiscriminants::Ordinal
+                    })
+                    .map(|edge_ref| edge_ref.id())
+                    .collect();
+                for edge in stale_edges {
+                    self.graph.remove_edge(edge);
+                }
+            }
+        }
+        for new_id in new_ids.difference(&ordinal_ids) {
+            let target_index = self.get_node_index_by_id(*new_id)?;
+            self.add_edge(
+                ordering_index,
+                EdgeWeight::new(EdgeWeightKind::Ordinal),
+                target_index,
+            )?;
+        }
+
+        self.get_node_weight_mut(ordering_index)?
+            .set_order(new_order)?;
+        self.touch_node(ordering_index);
+
+        Ok(())
+    }
+
+    fn add_missing_categories(&mut self) -> WorkspaceSnapshotGraphResult<()> {
+        let present: HashSet<CategoryNodeKind> = self
+            .nodes()
+            .filter_map(|(node_weight, _)| match node_weight {
+                NodeWeight::Category(category) => Some(category.kind()),
+                _ => None,
+            })
+            .collect();
+
+        for kind in CategoryNodeKind::iter().filter(|kind| !present.contains(kind)) {
+            let id = self.generate_ulid()?;
+            let lineage_id = self.generate_ulid()?;
+            let category_node_index = self.add_category_node(id, lineage_id, kind)?;
+            self.add_edge(
+                self.root(),
+                EdgeWeight::new(EdgeWeightKind::new_use()),
+                category_node_index,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn rebuild_indexes(&mut self) {
+        self.node_index_by_id.clear();
+        self.node_indices_by_lineage_id.clear();
+
+        let entries: Vec<_> = self
+            .graph
+            .node_indices()
+            .filter_map(|node_index| {
+                self.graph
+                    .node_weight(node_index)
+                    .map(|node_weight| (node_weight.id(), node_weight.lineage_id(), node_index))
+            })
+            .collect();
+        for (id, lineage_id, node_index) in entries {
+            self.node_index_by_id.insert(id, node_index);
+            self.node_indices_by_lineage_id
+                .entry(lineage_id)
+                .or_default()
+                .insert(node_index);
+            self.touch_node(node_index);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::{
+    chunked::{self, ChunkedSnapshotError},
     graph::{
         WorkspaceSnapshotGraph, WorkspaceSnapshotGraphDiscriminants, WorkspaceSnapshotGraphError,
     },
@@ -30,6 +31,8 @@ pub mod v4;
 pub enum SnapshotGraphMigratorError {
     #[error("change set error: {0}")]
     ChangeSet(#[from] ChangeSetError),
+    #[error("chunked snapshot error: {0}")]
+    ChunkedSnapshot(#[from] ChunkedSnapshotError),
     #[error("ulid decode error: {0}")]
     Decode(#[from] DecodeError),
     #[error("InputSocketNodeWeight error: {0}")]
@@ -157,6 +160,77 @@ impl SnapshotGraphMigrator {
         Ok(())
     }
 
+    /// Rewrites the snapshot of every open change set that is still stored whole as chunks, so that
+    /// it shares storage with the snapshots derived from it. Snapshots already stored as chunks
+    /// are left alone, so this is safe to run on every startup.
+    #[instrument(skip(self, ctx))]
+    pub async fn migrate_all_to_chunked_storage(
+        &mut self,
+        ctx: &DalContext,
+    ) -> SnapshotGraphMigratorResult<()> {
+        let open_change_sets = ChangeSet::list_open_for_all_workspaces(ctx).await?;
+        let chunk_db = ctx.layer_db().workspace_snapshot_chunk();
+
+        let mut migrated = 0;
+        for change_set in open_change_sets {
+            let mut change_set = ChangeSet::get_by_id_across_workspaces(ctx, change_set.id).await?;
+            if change_set.workspace_id.is_none() || change_set.status == ChangeSetStatus::Failed {
+                continue;
+            }
+
+            let snapshot_address = change_set.workspace_snapshot_address;
+            if chunk_db
+                .read_root(&snapshot_address, false)
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+
+            let mut change_set_ctx = ctx.clone_with_new_visibility(Visibility::from(change_set.id));
+            change_set_ctx.set_change_set(change_set.clone())?;
+
+            let graph = match ctx
+                .layer_db()
+                .workspace_snapshot()
+                .read(&snapshot_address)
+                .await?
+                .as_deref()
+            {
+                Some(WorkspaceSnapshotGraph::V4(graph)) => graph.clone(),
+                Some(other) => {
+                    return Err(SnapshotGraphMigratorError::UnexpectedGraphVersion(
+                        snapshot_address,
+                        other.into(),
+                    ));
+                }
+                None => {
+                    error!(
+                        "Snapshot {} for change set {} is missing, not moving it to chunked storage",
+                        snapshot_address, change_set.id
+                    );
+                    continue;
+                }
+            };
+
+            let new_snapshot_address = chunked::write(
+                ctx.layer_db(),
+                &graph,
+                ctx.events_tenancy(),
+                ctx.events_actor(),
+            )?;
+
+            change_set
+                .update_pointer(&change_set_ctx, new_snapshot_address)
+                .await?;
+            migrated += 1;
+        }
+
+        info!("Moved {migrated} snapshot(s) to chunked storage");
+
+        Ok(())
+    }
+
     #[instrument(skip(self, ctx))]
     pub async fn migrate_snapshot(
         &mut self,
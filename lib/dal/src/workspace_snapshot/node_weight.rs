@@ -33,6 +33,7 @@ use crate::workspace_snapshot::node_weight::diagram_object_node_weight::{
     DiagramObjectKind, DiagramObjectNodeWeight,
 };
 use crate::workspace_snapshot::node_weight::geometry_node_weight::GeometryNodeWeight;
+use crate::workspace_snapshot::node_weight::tag_node_weight::TagNodeWeight;
 use crate::workspace_snapshot::node_weight::traits::SiVersionedNodeWeight;
 use crate::workspace_snapshot::node_weight::view_node_weight::ViewNodeWeight;
 pub use action_node_weight::ActionNodeWeight;
@@ -72,6 +73,7 @@ pub mod ordering_node_weight;
 pub mod prop_node_weight;
 pub mod schema_variant_node_weight;
 pub mod secret_node_weight;
+pub mod tag_node_weight;
 pub mod view_node_weight;
 
 pub mod traits;
@@ -146,6 +148,7 @@ pub enum NodeWeight {
     Geometry(GeometryNodeWeight),
     View(ViewNodeWeight),
     DiagramObject(DiagramObjectNodeWeight),
+    Tag(TagNodeWeight),
 }
 
 impl NodeWeight {
@@ -171,6 +174,7 @@ impl NodeWeight {
             NodeWeight::Geometry(w) => w.content_hash(),
             NodeWeight::View(w) => w.content_hash(),
             NodeWeight::DiagramObject(w) => w.content_hash(),
+            NodeWeight::Tag(w) => w.content_hash(),
         }
     }
 
@@ -196,6 +200,7 @@ impl NodeWeight {
             NodeWeight::Geometry(weight) => weight.content_store_hashes(),
             NodeWeight::View(weight) => weight.content_store_hashes(),
             NodeWeight::DiagramObject(w) => w.content_store_hashes(),
+            NodeWeight::Tag(_) => vec![],
         }
     }
 
@@ -220,7 +225,8 @@ impl NodeWeight {
             | NodeWeight::ManagementPrototype(_)
             | NodeWeight::View(_)
             | NodeWeight::SchemaVariant(_)
-            | NodeWeight::DiagramObject(_) => None,
+            | NodeWeight::DiagramObject(_)
+            | NodeWeight::Tag(_) => None,
         }
     }
 
@@ -246,6 +252,7 @@ impl NodeWeight {
             NodeWeight::Geometry(weight) => weight.id(),
             NodeWeight::View(weight) => weight.id(),
             NodeWeight::DiagramObject(weight) => weight.id(),
+            NodeWeight::Tag(weight) => weight.id(),
         }
     }
 
@@ -271,6 +278,7 @@ impl NodeWeight {
             NodeWeight::Geometry(weight) => weight.lineage_id(),
             NodeWeight::View(weight) => weight.lineage_id(),
             NodeWeight::DiagramObject(weight) => weight.lineage_id(),
+            NodeWeight::Tag(weight) => weight.lineage_id(),
         }
     }
 
@@ -356,6 +364,10 @@ impl NodeWeight {
                 weight.set_id(id.into());
                 weight.set_lineage_id(lineage_id);
             }
+            NodeWeight::Tag(weight) => {
+                weight.set_id(id.into());
+                weight.set_lineage_id(lineage_id);
+            }
         }
     }
 
@@ -381,6 +393,7 @@ impl NodeWeight {
             NodeWeight::Geometry(w) => w.merkle_tree_hash(),
             NodeWeight::View(w) => w.merkle_tree_hash(),
             NodeWeight::DiagramObject(w) => w.merkle_tree_hash(),
+            NodeWeight::Tag(w) => w.merkle_tree_hash(),
         }
     }
 
@@ -412,7 +425,8 @@ impl NodeWeight {
             | NodeWeight::DependentValueRoot(_)
             | NodeWeight::FinishedDependentValueRoot(_)
             | NodeWeight::Ordering(_)
-            | NodeWeight::DiagramObject(_) => Err(NodeWeightError::CannotSetContentHashOnKind),
+            | NodeWeight::DiagramObject(_)
+            | NodeWeight::Tag(_) => Err(NodeWeightError::CannotSetContentHashOnKind),
             NodeWeight::Geometry(w) => {
                 traits::SiVersionedNodeWeight::inner_mut(w).new_content_hash(content_hash);
                 Ok(())
@@ -449,6 +463,7 @@ impl NodeWeight {
             NodeWeight::Geometry(weight) => weight.node_hash(),
             NodeWeight::View(weight) => weight.node_hash(),
             NodeWeight::DiagramObject(weight) => weight.node_hash(),
+            NodeWeight::Tag(weight) => weight.node_hash(),
         }
     }
 
@@ -474,6 +489,7 @@ impl NodeWeight {
             NodeWeight::Geometry(weight) => weight.set_merkle_tree_hash(new_hash),
             NodeWeight::View(weight) => weight.set_merkle_tree_hash(new_hash),
             NodeWeight::DiagramObject(weight) => weight.set_merkle_tree_hash(new_hash),
+            NodeWeight::Tag(weight) => weight.set_merkle_tree_hash(new_hash),
         }
     }
 
@@ -501,6 +517,7 @@ impl NodeWeight {
             | NodeWeight::InputSocket(_)
             | NodeWeight::ManagementPrototype(_)
             | NodeWeight::DiagramObject(_)
+            | NodeWeight::Tag(_)
             | NodeWeight::SchemaVariant(_) => Err(NodeWeightError::CannotSetOrderOnKind),
         }
     }
@@ -537,6 +554,7 @@ impl NodeWeight {
             NodeWeight::SchemaVariant(weight) => weight.exclusive_outgoing_edges(),
             NodeWeight::ManagementPrototype(weight) => weight.exclusive_outgoing_edges(),
             NodeWeight::DiagramObject(weight) => weight.exclusive_outgoing_edges(),
+            NodeWeight::Tag(weight) => weight.exclusive_outgoing_edges(),
         }
     }
 
@@ -638,6 +656,16 @@ impl NodeWeight {
         }
     }
 
+    pub fn get_tag_node_weight(&self) -> NodeWeightResult<TagNodeWeight> {
+        match self {
+            NodeWeight::Tag(inner) => Ok(inner.to_owned()),
+            other => Err(NodeWeightError::UnexpectedNodeWeightVariant(
+                NodeWeightDiscriminants::Tag,
+                other.into(),
+            )),
+        }
+    }
+
     pub fn get_prop_node_weight(&self) -> NodeWeightResult<PropNodeWeight> {
         match self {
             NodeWeight::Prop(inner) => Ok(inner.to_owned()),
@@ -850,6 +878,10 @@ impl NodeWeight {
         NodeWeight::DiagramObject(DiagramObjectNodeWeight::new(id, lineage_id, object_kind))
     }
 
+    pub fn new_tag(id: Ulid, lineage_id: Ulid, key: String, value: String) -> Self {
+        NodeWeight::Tag(TagNodeWeight::new(id, lineage_id, key, value))
+    }
+
     pub fn new_prop(
         prop_id: Ulid,
         lineage_id: Ulid,
@@ -1083,6 +1115,11 @@ impl CorrectTransforms for NodeWeight {
                 updates,
                 from_different_change_set,
             ),
+            NodeWeight::Tag(weight) => weight.correct_transforms(
+                workspace_snapshot_graph,
+                updates,
+                from_different_change_set,
+            ),
         }?;
 
         Ok(self.correct_exclusive_outgoing_edges(workspace_snapshot_graph, updates))
@@ -1112,6 +1149,7 @@ impl CorrectExclusiveOutgoingEdge for NodeWeight {
             NodeWeight::Geometry(weight) => weight.exclusive_outgoing_edges(),
             NodeWeight::View(weight) => weight.exclusive_outgoing_edges(),
             NodeWeight::DiagramObject(weight) => weight.exclusive_outgoing_edges(),
+            NodeWeight::Tag(weight) => weight.exclusive_outgoing_edges(),
         }
     }
 }
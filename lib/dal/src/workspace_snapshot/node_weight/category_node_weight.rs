@@ -13,7 +13,7 @@ use crate::EdgeWeightKindDiscriminants;
 /// the given context). Note that a race to create the category will result in a broken graph(since
 /// having two of the same category would leave the graph in an inconsistent state), so you should
 /// implement the ability to merge your category nodes together.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display, EnumIter)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Display, EnumIter)]
 pub enum CategoryNodeKind {
     Action,
     Component,
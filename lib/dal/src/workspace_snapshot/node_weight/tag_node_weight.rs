@@ -0,0 +1,31 @@
+use dal_macros::SiVersionedNodeWeight;
+use serde::{Deserialize, Serialize};
+use si_events::ulid::Ulid;
+
+use crate::workspace_snapshot::node_weight::traits::SiVersionedNodeWeight;
+
+pub mod v1;
+use v1::TagNodeWeightV1;
+
+/// A key/value label attached to a [`Component`](crate::Component). The key and value are stored
+/// on the node itself rather than in the content store, since they are small and are read every
+/// time components are filtered by tag.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SiVersionedNodeWeight)]
+pub enum TagNodeWeight {
+    #[si_versioned_node_weight(current)]
+    V1(TagNodeWeightV1),
+}
+
+impl TagNodeWeight {
+    pub fn new(id: Ulid, lineage_id: Ulid, key: String, value: String) -> Self {
+        Self::V1(TagNodeWeightV1::new(id, lineage_id, key, value))
+    }
+
+    pub fn key(&self) -> &str {
+        self.inner().key()
+    }
+
+    pub fn value(&self) -> &str {
+        self.inner().value()
+    }
+}
@@ -0,0 +1,55 @@
+use dal_macros::SiNodeWeight;
+use serde::{Deserialize, Serialize};
+use si_events::{merkle_tree_hash::MerkleTreeHash, ulid::Ulid, ContentHash};
+
+use crate::{
+    workspace_snapshot::{
+        graph::LineageId,
+        node_weight::{
+            traits::{CorrectExclusiveOutgoingEdge, CorrectTransforms, SiNodeWeight},
+            NodeWeightDiscriminants,
+        },
+    },
+    EdgeWeightKindDiscriminants,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SiNodeWeight)]
+#[si_node_weight(discriminant = NodeWeightDiscriminants::Tag)]
+pub struct TagNodeWeightV1 {
+    pub id: Ulid,
+    pub lineage_id: LineageId,
+    merkle_tree_hash: MerkleTreeHash,
+    // Keys can't contain "=", so hashing the separator keeps "a=bc" and "ab=c" apart.
+    #[si_node_weight(node_hash = "format!(\"{}=\", self.key).as_bytes()")]
+    key: String,
+    #[si_node_weight(node_hash = "self.value.as_bytes()")]
+    value: String,
+}
+
+impl TagNodeWeightV1 {
+    pub fn new(id: Ulid, lineage_id: Ulid, key: String, value: String) -> Self {
+        Self {
+            id,
+            lineage_id,
+            merkle_tree_hash: MerkleTreeHash::default(),
+            key,
+            value,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl CorrectTransforms for TagNodeWeightV1 {}
+
+impl CorrectExclusiveOutgoingEdge for TagNodeWeightV1 {
+    fn exclusive_outgoing_edges(&self) -> &[EdgeWeightKindDiscriminants] {
+        &[]
+    }
+}
@@ -8,11 +8,14 @@ use si_frontend_types as frontend_types;
 use thiserror::Error;
 use ulid::Ulid;
 
+use crate::action::ActionStateUpdatedPayload;
 use crate::audit_logging::AuditLogsPublishedPayload;
+use crate::change_set::comment::{CommentThread, CommentThreadId};
 use crate::change_set::event::{
     ChangeSetActorPayload, ChangeSetAppliedPayload, ChangeSetMergeVotePayload,
     ChangeSetRenamePayload, ChangeSetStateChangePayload,
 };
+use crate::change_set::schedule::ScheduledApply;
 use crate::component::{
     ComponentCreatedPayload, ComponentDeletedPayload, ComponentSetPositionPayload,
     ComponentUpdatedPayload, ComponentUpgradedPayload, ConnectionDeletedPayload,
@@ -22,7 +25,7 @@ use crate::diagram::view::{
     ViewComponentsUpdatePayload, ViewDeletedPayload, ViewObjectCreatedPayload,
     ViewObjectRemovedPayload, ViewWsPayload,
 };
-use crate::func::runner::FuncRunLogUpdatedPayload;
+use crate::func::runner::{FuncRunFinishedPayload, FuncRunLogUpdatedPayload};
 use crate::func::{
     FuncWsEventCodeSaved, FuncWsEventFuncSummary, FuncWsEventGenerating, FuncWsEventPayload,
 };
@@ -80,6 +83,7 @@ pub type WsEventResult<T> = Result<T, WsEventError>;
 #[allow(clippy::large_enum_variant)]
 pub enum WsPayload {
     ActionsListUpdated(ChangeSetId),
+    ActionStateUpdated(ActionStateUpdatedPayload),
     AsyncError(ErrorPayload),
     AsyncFinish(FinishPayload),
     AuditLogsPublished(AuditLogsPublishedPayload),
@@ -91,9 +95,12 @@ pub enum WsPayload {
     ChangeSetCancelAbandonProcess(ChangeSetActorPayload),
     ChangeSetCancelApprovalProcess(ChangeSetActorPayload),
     ChangeSetCanceled(ChangeSetId),
+    ChangeSetCommentThreadRemoved(CommentThreadId),
+    ChangeSetCommentThreadUpdated(CommentThread),
     ChangeSetCreated(ChangeSetId),
     ChangeSetMergeVote(ChangeSetMergeVotePayload),
     ChangeSetRename(ChangeSetRenamePayload),
+    ChangeSetScheduledApplyUpdated(ScheduledApply),
     ChangeSetStatusChanged(ChangeSetStateChangePayload),
     ChangeSetWritten(ChangeSetId),
     CheckedQualifications(QualificationCheckPayload),
@@ -109,6 +116,7 @@ pub enum WsPayload {
     FuncCreated(FuncWsEventFuncSummary),
     FuncDeleted(FuncWsEventPayload),
     FuncGenerating(FuncWsEventGenerating),
+    FuncRunFinished(FuncRunFinishedPayload),
     FuncRunLogUpdated(FuncRunLogUpdatedPayload),
     FuncSaved(FuncWsEventPayload),
     FuncUpdated(FuncWsEventFuncSummary),
@@ -232,6 +240,10 @@ impl WsEvent {
         self.change_set_id
     }
 
+    pub fn payload(&self) -> &WsPayload {
+        &self.payload
+    }
+
     fn workspace_subject(&self) -> String {
         format!("si.workspace_pk.{}.event", self.workspace_pk)
     }
@@ -1,4 +1,5 @@
 use dal::action::dependency_graph::ActionDependencyGraph;
+use dal::action::plan::{ActionPlan, ActionPlanDependency, HeldAction};
 use dal::component::frame::Frame;
 use dal::{
     action::prototype::ActionKind, action::prototype::ActionPrototype, action::Action,
@@ -529,3 +530,106 @@ async fn actions_are_ordered_correctly(ctx: &mut DalContext) {
         vec![first_component_action]
     );
 }
+
+#[test]
+async fn apply_plan(ctx: &mut DalContext) {
+    let first_component = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small odd lego",
+        "first component",
+        dal::ComponentType::ConfigurationFrameDown,
+    )
+    .await
+    .expect("could not create component");
+    let second_component = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small even lego",
+        "second component",
+        dal::ComponentType::ConfigurationFrameDown,
+    )
+    .await
+    .expect("could not create component");
+    connect_components_with_socket_names(
+        ctx,
+        first_component.id(),
+        "two",
+        second_component.id(),
+        "two",
+    )
+    .await
+    .expect("could not create connection");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let first_action = Action::find_for_component_id(ctx, first_component.id())
+        .await
+        .expect("could not get actions")
+        .pop()
+        .expect("doesn't have one");
+    let second_action = Action::find_for_component_id(ctx, second_component.id())
+        .await
+        .expect("could not get actions")
+        .pop()
+        .expect("doesn't have one");
+
+    // Nothing is queued on HEAD yet, so the plan is just this change set's actions, in order.
+    let plan = ActionPlan::for_apply(ctx)
+        .await
+        .expect("could not build plan");
+    assert_eq!(
+        vec![first_action, second_action],
+        plan.actions
+            .iter()
+            .map(|entry| entry.id)
+            .collect::<Vec<_>>()
+    );
+    assert!(plan.actions.iter().all(|entry| entry.from_change_set));
+    assert_eq!(
+        vec![
+            Some("first component".to_string()),
+            Some("second component".to_string())
+        ],
+        plan.actions
+            .iter()
+            .map(|entry| entry.component_name.clone())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(vec![vec![first_action], vec![second_action]], plan.batches);
+    assert_eq!(
+        vec![ActionPlanDependency {
+            action_id: second_action,
+            depends_on: first_action,
+        }],
+        plan.dependencies
+    );
+    assert!(plan.held.is_empty());
+    assert!(plan
+        .to_dot()
+        .contains(&format!("\"{first_action}\" -> \"{second_action}\";")));
+
+    // Putting the first action on hold holds up the second too.
+    Action::set_state(ctx, first_action, ActionState::OnHold)
+        .await
+        .expect("could not set state");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let plan = ActionPlan::for_apply(ctx)
+        .await
+        .expect("could not build plan");
+    assert!(plan.batches.is_empty());
+    assert!(plan.cyclic_action_ids.is_empty());
+    let mut affected_component_ids = vec![first_component.id(), second_component.id()];
+    affected_component_ids.sort();
+    assert_eq!(
+        vec![HeldAction {
+            action_id: first_action,
+            state: ActionState::OnHold,
+            blocked_action_ids: vec![second_action],
+            affected_component_ids,
+        }],
+        plan.held
+    );
+}
@@ -1,9 +1,23 @@
+use chrono::{Duration, Utc};
+use dal::change_set::approval::{
+    ApprovalRule, ApprovalRuleSpec, ApproverGroup, ApproverGroupId, ApproverGroupSpec,
+    ChangeSetApprovalError, ChangeSetApprovals,
+};
+use dal::change_set::comment::{
+    ChangeSetComment, ChangeSetCommentError, CommentTarget, CommentThread,
+};
+use dal::change_set::policy::{ChangeSetPolicy, PolicyStatus};
+use dal::change_set::schedule::{
+    ScheduledApply, ScheduledApplyError, ScheduledApplyStatus, CLAIM_LEASE,
+};
 use dal::change_set::view::OpenChangeSetsView;
+use dal::func::authoring::FuncAuthoringClient;
+use dal::revoked_auth_token::RevokedAuthToken;
 use dal::{
     context::TransactionsErrorDiscriminants, DalContext, DalContextBuilder, HistoryActor,
     RequestContext, Workspace, WorkspacePk,
 };
-use dal::{AccessBuilder, ChangeSet, ChangeSetStatus, Component};
+use dal::{AccessBuilder, ChangeSet, ChangeSetError, ChangeSetStatus, Component};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view, create_user, ChangeSetTestHelpers,
 };
@@ -543,3 +557,731 @@ async fn change_set_approval_flow(ctx: &mut DalContext) {
         .collect_vec();
     assert_eq!(components.len(), 2);
 }
+
+#[test]
+async fn policies_gate_apply_until_overridden(ctx: &mut DalContext) {
+    let func = FuncAuthoringClient::create_new_policy_func(ctx, Some("no small legos".to_string()))
+        .await
+        .expect("could not create policy func");
+    FuncAuthoringClient::save_code(
+        ctx,
+        func.id,
+        r#"async function main(input: Input): Promise<Output> {
+  const violations = input.components
+    .filter((component) => component.name === "small")
+    .map((component) => ({ componentId: component.id, message: "too small" }));
+  if (violations.length > 0) {
+    return { result: "failure", message: "small legos found", violations };
+  }
+  return { result: "success" };
+}"#
+        .to_string(),
+    )
+    .await
+    .expect("could not save code");
+
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "small odd lego", "small")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    let results = ChangeSetPolicy::evaluate(ctx)
+        .await
+        .expect("could not evaluate policies");
+    assert_eq!(1, results.len());
+    let result = results.first().expect("has a result");
+    assert_eq!(PolicyStatus::Failure, result.status);
+    assert_eq!(Some("small legos found".to_string()), result.message);
+    assert_eq!(
+        vec![Some(component.id())],
+        result
+            .violations
+            .iter()
+            .map(|violation| violation.component_id)
+            .collect_vec()
+    );
+    assert!(result.is_blocking());
+
+    let err = ChangeSetTestHelpers::force_apply_change_set_to_base_approvals(ctx)
+        .await
+        .expect_err("failing policy should block apply");
+    assert!(err.to_string().contains("no small legos"));
+
+    let overridden = ChangeSetPolicy::override_failures(ctx)
+        .await
+        .expect("could not override failures");
+    assert_eq!(1, overridden.len());
+    assert!(overridden.iter().all(|result| !result.is_blocking()));
+    ChangeSetPolicy::override_failures(ctx)
+        .await
+        .expect_err("nothing left to override");
+
+    ChangeSetTestHelpers::force_apply_change_set_to_base_approvals(ctx)
+        .await
+        .expect("overridden policy should not block apply");
+}
+
+#[test]
+async fn approval_rules_gate_apply(ctx: &mut DalContext) {
+    let requester = ChangeSet::extract_userid_from_context_or_error(ctx)
+        .await
+        .expect("could not get current user");
+    let second_approver = create_user(ctx).await.expect("could not create user");
+
+    let rule = ApprovalRule::new(
+        ctx,
+        ApprovalRuleSpec {
+            name: "starfield needs a second pair of eyes".to_string(),
+            required_approvals: 1,
+            schema_names: vec!["starfield".to_string()],
+            view_ids: vec![],
+            match_funcs: false,
+            approver_user_pks: vec![],
+            approver_group_ids: vec![],
+            allow_self_approval: false,
+        },
+    )
+    .await
+    .expect("could not create rule");
+    ApprovalRule::new(
+        ctx,
+        ApprovalRuleSpec {
+            name: "swifty changes".to_string(),
+            required_approvals: 3,
+            schema_names: vec!["swifty".to_string()],
+            view_ids: vec![],
+            match_funcs: false,
+            approver_user_pks: vec![],
+            approver_group_ids: vec![],
+            allow_self_approval: true,
+        },
+    )
+    .await
+    .expect("could not create rule");
+
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    // Approving your own change set doesn't count towards the rule.
+    let mut change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set");
+    change_set
+        .request_change_set_approval(ctx)
+        .await
+        .expect("could not request approval");
+    change_set
+        .approve_change_set_for_apply(ctx)
+        .await
+        .expect("could not approve");
+    assert_eq!(ChangeSetStatus::NeedsApproval, change_set.status);
+
+    let status = ChangeSetApprovals::evaluate(ctx)
+        .await
+        .expect("could not evaluate approvals");
+    assert!(!status.satisfied);
+    assert!(status.touched.component_ids.contains(&component.id()));
+    assert_eq!(
+        vec![(rule.id, vec![requester])],
+        status
+            .rules
+            .iter()
+            .map(|evaluation| (evaluation.rule.id, evaluation.ignored_approvals.clone()))
+            .collect_vec()
+    );
+
+    let err = ChangeSet::prepare_for_apply(ctx)
+        .await
+        .expect_err("unsatisfied rule should block apply");
+    assert!(err.to_string().contains(&rule.name));
+
+    ctx.update_history_actor(HistoryActor::User(second_approver.pk()));
+    change_set
+        .approve_change_set_for_apply(ctx)
+        .await
+        .expect("could not approve");
+    assert_eq!(ChangeSetStatus::Approved, change_set.status);
+    assert!(
+        ChangeSetApprovals::evaluate(ctx)
+            .await
+            .expect("could not evaluate approvals")
+            .satisfied
+    );
+
+    ChangeSetTestHelpers::apply_change_set_to_base_approvals(ctx)
+        .await
+        .expect("satisfied rules should not block apply");
+}
+
+#[test]
+async fn approver_groups_gate_apply(ctx: &mut DalContext) {
+    let member = create_user(ctx).await.expect("could not create user");
+    let outsider = create_user(ctx).await.expect("could not create user");
+
+    let err = ApprovalRule::new(
+        ctx,
+        ApprovalRuleSpec {
+            name: "unknown group".to_string(),
+            required_approvals: 1,
+            schema_names: vec![],
+            view_ids: vec![],
+            match_funcs: false,
+            approver_user_pks: vec![],
+            approver_group_ids: vec![ApproverGroupId::generate()],
+            allow_self_approval: true,
+        },
+    )
+    .await
+    .expect_err("rules should only name existing groups");
+    assert!(matches!(
+        err,
+        ChangeSetApprovalError::ApproverGroupNotFound(_)
+    ));
+
+    let group = ApproverGroup::new(
+        ctx,
+        ApproverGroupSpec {
+            name: "platform".to_string(),
+            member_user_pks: vec![member.pk()],
+        },
+    )
+    .await
+    .expect("could not create group");
+    let rule = ApprovalRule::new(
+        ctx,
+        ApprovalRuleSpec {
+            name: "platform signs off".to_string(),
+            required_approvals: 1,
+            schema_names: vec![],
+            view_ids: vec![],
+            match_funcs: false,
+            approver_user_pks: vec![],
+            approver_group_ids: vec![group.id],
+            allow_self_approval: true,
+        },
+    )
+    .await
+    .expect("could not create rule");
+
+    create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    let mut change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set");
+    change_set
+        .request_change_set_approval(ctx)
+        .await
+        .expect("could not request approval");
+
+    // Approvals from outside the group don't count towards the rule.
+    ctx.update_history_actor(HistoryActor::User(outsider.pk()));
+    change_set
+        .approve_change_set_for_apply(ctx)
+        .await
+        .expect("could not approve");
+    assert_eq!(ChangeSetStatus::NeedsApproval, change_set.status);
+    let status = ChangeSetApprovals::evaluate(ctx)
+        .await
+        .expect("could not evaluate approvals");
+    assert_eq!(
+        vec![(rule.id, vec![outsider.pk()])],
+        status
+            .rules
+            .iter()
+            .map(|evaluation| (evaluation.rule.id, evaluation.ignored_approvals.clone()))
+            .collect_vec()
+    );
+
+    // Adding the user to the group makes their approval count.
+    ApproverGroup::set_members(ctx, group.id, vec![member.pk(), outsider.pk()])
+        .await
+        .expect("could not set members");
+    assert!(
+        ChangeSetApprovals::evaluate(ctx)
+            .await
+            .expect("could not evaluate approvals")
+            .satisfied
+    );
+
+    // Removing the group leaves the rule without approvers, rather than open to anyone.
+    ApproverGroup::remove(ctx, group.id)
+        .await
+        .expect("could not remove group");
+    assert!(
+        !ChangeSetApprovals::evaluate(ctx)
+            .await
+            .expect("could not evaluate approvals")
+            .satisfied
+    );
+    ApproverGroup::new(
+        ctx,
+        ApproverGroupSpec {
+            name: "platform".to_string(),
+            member_user_pks: vec![member.pk()],
+        },
+    )
+    .await
+    .expect("could not create group");
+    assert!(
+        !ChangeSetApprovals::evaluate(ctx)
+            .await
+            .expect("could not evaluate approvals")
+            .satisfied
+    );
+}
+
+#[test]
+async fn edits_invalidate_approvals(ctx: &mut DalContext) {
+    let approver = ChangeSet::extract_userid_from_context_or_error(ctx)
+        .await
+        .expect("could not get current user");
+
+    create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    let mut change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set");
+    change_set
+        .request_change_set_approval(ctx)
+        .await
+        .expect("could not request approval");
+    change_set
+        .approve_change_set_for_apply(ctx)
+        .await
+        .expect("could not approve");
+    assert_eq!(ChangeSetStatus::Approved, change_set.status);
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    // Editing the change set after it was approved voids the approval.
+    create_component_for_default_schema_name_in_default_view(ctx, "swifty", "swifty")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    let status = ChangeSetApprovals::evaluate(ctx)
+        .await
+        .expect("could not evaluate approvals");
+    assert!(!status.satisfied);
+    assert!(status.approvals.is_empty());
+    assert_eq!(
+        vec![approver],
+        status
+            .stale_approvals
+            .iter()
+            .map(|approval| approval.user_pk)
+            .collect_vec()
+    );
+
+    let err = ChangeSet::prepare_for_apply(ctx)
+        .await
+        .expect_err("stale approval should block apply");
+    assert!(matches!(
+        err,
+        ChangeSetError::EditedSinceApproval(change_set_id) if change_set_id == ctx.change_set_id()
+    ));
+
+    // Approving again counts for the change set as it is now.
+    change_set
+        .approve_change_set_for_apply(ctx)
+        .await
+        .expect("could not approve");
+    let status = ChangeSetApprovals::evaluate(ctx)
+        .await
+        .expect("could not evaluate approvals");
+    assert!(status.satisfied);
+    assert!(status.stale_approvals.is_empty());
+
+    ChangeSetTestHelpers::apply_change_set_to_base_approvals(ctx)
+        .await
+        .expect("current approval should not block apply");
+}
+
+#[test]
+async fn fenced_pointer_updates_reject_stale_tokens(ctx: &DalContext) {
+    let mut change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set");
+    let address = change_set.workspace_snapshot_address;
+
+    // The holder of a lease may move the pointer as often as it likes
+    change_set
+        .update_pointer_fenced(ctx, address, 5)
+        .await
+        .expect("first write with a token should succeed");
+    change_set
+        .update_pointer_fenced(ctx, address, 5)
+        .await
+        .expect("writing again with the same token should succeed");
+
+    // but once a newer holder has moved it, the previous holder can't
+    change_set
+        .update_pointer_fenced(ctx, address, 7)
+        .await
+        .expect("a newer token should succeed");
+    let err = change_set
+        .update_pointer_fenced(ctx, address, 5)
+        .await
+        .expect_err("an older token should be rejected");
+    assert!(matches!(
+        err,
+        dal::ChangeSetError::FencingTokenSuperseded(id, 5) if id == change_set.id
+    ));
+}
+
+#[test]
+async fn scheduled_apply(ctx: &mut DalContext) {
+    let now = Utc::now();
+    let err = ScheduledApply::schedule(ctx, now, Some(now - Duration::minutes(5)), None)
+        .await
+        .expect_err("window ending before it starts should be rejected");
+    assert!(matches!(err, ScheduledApplyError::InvalidWindow(_, _)));
+
+    create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    // Cancelling works until the scheduler picks it up, and only once.
+    let window_start = now - Duration::minutes(1);
+    ScheduledApply::schedule(ctx, window_start, None, None)
+        .await
+        .expect("could not schedule apply");
+    let cancelled = ScheduledApply::cancel(ctx)
+        .await
+        .expect("could not cancel scheduled apply");
+    assert_eq!(ScheduledApplyStatus::Cancelled, cancelled.status);
+    let err = ScheduledApply::cancel(ctx)
+        .await
+        .expect_err("cancelling twice should fail");
+    assert!(matches!(err, ScheduledApplyError::NotScheduled(_)));
+
+    // Without an approval, the scheduler skips the apply.
+    ScheduledApply::schedule(ctx, window_start, None, None)
+        .await
+        .expect("could not schedule apply");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+    let finished = ScheduledApply::run_due(&ctx.to_builder())
+        .await
+        .expect("could not run scheduled applies");
+    assert_eq!(
+        vec![(ctx.change_set_id(), ScheduledApplyStatus::Skipped)],
+        finished
+            .iter()
+            .map(|scheduled| (scheduled.change_set_id, scheduled.status))
+            .collect_vec()
+    );
+    assert!(finished[0].message.is_some());
+
+    // Once approved, the scheduler applies it.
+    let mut change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set");
+    change_set
+        .request_change_set_approval(ctx)
+        .await
+        .expect("could not request approval");
+    change_set
+        .approve_change_set_for_apply(ctx)
+        .await
+        .expect("could not approve");
+    ScheduledApply::schedule(ctx, window_start, Some(now + Duration::hours(1)), None)
+        .await
+        .expect("could not schedule apply");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+    let finished = ScheduledApply::run_due(&ctx.to_builder())
+        .await
+        .expect("could not run scheduled applies");
+    assert_eq!(
+        vec![(ctx.change_set_id(), ScheduledApplyStatus::Applied)],
+        finished
+            .iter()
+            .map(|scheduled| (scheduled.change_set_id, scheduled.status))
+            .collect_vec()
+    );
+
+    let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set");
+    assert_eq!(ChangeSetStatus::Applied, change_set.status);
+}
+
+#[test]
+async fn scheduled_apply_reclaims_stale_claims(ctx: &mut DalContext) {
+    create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+        .await
+        .expect("could not create component");
+    ScheduledApply::schedule(ctx, Utc::now() - Duration::minutes(1), None, None)
+        .await
+        .expect("could not schedule apply");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    // A scheduler claims the schedule and then dies without finishing it.
+    let claimed = ScheduledApply::claim_due(ctx, CLAIM_LEASE)
+        .await
+        .expect("could not claim due schedules");
+    assert_eq!(
+        vec![(ctx.change_set_id(), ScheduledApplyStatus::Applying)],
+        claimed
+            .iter()
+            .map(|scheduled| (scheduled.change_set_id, scheduled.status))
+            .collect_vec()
+    );
+    assert!(claimed[0].claimed_at.is_some());
+    let claimed_first = claimed[0].clone();
+    ctx.commit_no_rebase().await.expect("could not commit");
+
+    // While the lease holds, nobody else picks it up.
+    let claimed = ScheduledApply::claim_due(ctx, CLAIM_LEASE)
+        .await
+        .expect("could not claim due schedules");
+    assert!(claimed.is_empty());
+
+    // Once it has expired, the schedule is claimed again rather than left applying forever.
+    let reclaimed = ScheduledApply::claim_due(ctx, Duration::zero())
+        .await
+        .expect("could not claim due schedules");
+    assert_eq!(
+        vec![ctx.change_set_id()],
+        reclaimed
+            .iter()
+            .map(|scheduled| scheduled.change_set_id)
+            .collect_vec()
+    );
+    assert!(reclaimed[0].claim_token > claimed_first.claim_token);
+
+    // The scheduler that lost the claim can no longer keep it alive, while the new one can.
+    assert!(!claimed_first
+        .renew_claim(ctx)
+        .await
+        .expect("could not renew claim"));
+    assert!(reclaimed[0]
+        .renew_claim(ctx)
+        .await
+        .expect("could not renew claim"));
+    ctx.commit_no_rebase().await.expect("could not commit");
+}
+
+#[test]
+async fn revoked_auth_tokens_are_scoped_to_their_workspace(ctx: &mut DalContext) {
+    RevokedAuthToken::revoke(ctx, "shared-token-id")
+        .await
+        .expect("could not revoke token");
+    ctx.commit_no_rebase().await.expect("could not commit");
+
+    assert!(RevokedAuthToken::is_revoked(ctx, "shared-token-id")
+        .await
+        .expect("could not check token"));
+    assert!(RevokedAuthToken::is_revoked_in_pool(
+        ctx.pg_pool(),
+        ctx.workspace_pk().expect("no workspace"),
+        "shared-token-id"
+    )
+    .await
+    .expect("could not check token"));
+    assert!(!RevokedAuthToken::is_revoked_in_pool(
+        ctx.pg_pool(),
+        WorkspacePk::generate(),
+        "shared-token-id"
+    )
+    .await
+    .expect("could not check token"));
+}
+
+#[test]
+async fn scheduled_apply_skips_revoked_token(ctx: &mut DalContext) {
+    create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+        .await
+        .expect("could not create component");
+    let mut change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set");
+    change_set
+        .request_change_set_approval(ctx)
+        .await
+        .expect("could not request approval");
+    change_set
+        .approve_change_set_for_apply(ctx)
+        .await
+        .expect("could not approve");
+    ScheduledApply::schedule(
+        ctx,
+        Utc::now() - Duration::minutes(1),
+        None,
+        Some("scheduling-token".to_owned()),
+    )
+    .await
+    .expect("could not schedule apply");
+    RevokedAuthToken::revoke(ctx, "scheduling-token")
+        .await
+        .expect("could not revoke token");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    let finished = ScheduledApply::run_due(&ctx.to_builder())
+        .await
+        .expect("could not run scheduled applies");
+    assert_eq!(
+        vec![(ctx.change_set_id(), ScheduledApplyStatus::Skipped)],
+        finished
+            .iter()
+            .map(|scheduled| (scheduled.change_set_id, scheduled.status))
+            .collect_vec()
+    );
+    assert!(finished[0]
+        .message
+        .as_deref()
+        .is_some_and(|message| message.contains("revoked")));
+
+    let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id())
+        .await
+        .expect("could not find change set");
+    assert_ne!(ChangeSetStatus::Applied, change_set.status);
+}
+
+#[test]
+async fn comment_threads(ctx: &mut DalContext) {
+    let author = ChangeSet::extract_userid_from_context_or_error(ctx)
+        .await
+        .expect("could not get current user");
+    let reviewer = create_user(ctx).await.expect("could not create user");
+    reviewer
+        .associate_workspace(ctx, ctx.workspace_pk().expect("could not get workspace pk"))
+        .await
+        .expect("could not add reviewer to workspace");
+    let outsider = create_user(ctx).await.expect("could not create user");
+
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update");
+
+    let err = CommentThread::new(
+        ctx,
+        CommentTarget {
+            component_id: None,
+            attribute_path: Some("/domain/name".to_string()),
+        },
+        "which name?",
+        vec![],
+    )
+    .await
+    .expect_err("attribute path without a component should be rejected");
+    assert!(matches!(
+        err,
+        ChangeSetCommentError::AttributePathWithoutComponent(_)
+    ));
+    let err = CommentThread::new(ctx, CommentTarget::default(), "hi", vec![outsider.pk()])
+        .await
+        .expect_err("mentioning someone outside the workspace should be rejected");
+    assert!(matches!(err, ChangeSetCommentError::UnknownMention(pk) if pk == outsider.pk()));
+
+    let thread = CommentThread::new(
+        ctx,
+        CommentTarget {
+            component_id: Some(component.id()),
+            attribute_path: Some("/domain/name".to_string()),
+        },
+        "is this the right name?",
+        vec![reviewer.pk()],
+    )
+    .await
+    .expect("could not start thread");
+    assert_eq!(1, thread.comments.len());
+    assert_eq!(vec![reviewer.pk()], thread.comments[0].mentioned_user_pks);
+
+    ctx.update_history_actor(HistoryActor::User(reviewer.pk()));
+    let thread = CommentThread::reply(ctx, thread.id, "looks right to me", vec![author])
+        .await
+        .expect("could not reply");
+    assert_eq!(2, thread.comments.len());
+    let reply_id = thread.comments[1].id;
+
+    let err = ChangeSetComment::edit(ctx, thread.comments[0].id, "hijacked", vec![])
+        .await
+        .expect_err("only the author can edit a comment");
+    assert!(matches!(err, ChangeSetCommentError::NotAuthor(_)));
+
+    // Approvers see open threads
+    let open_thread_ids = ChangeSetApprovals::evaluate(ctx)
+        .await
+        .expect("could not evaluate approvals")
+        .open_comment_threads
+        .iter()
+        .map(|thread| thread.id)
+        .collect_vec();
+    assert_eq!(vec![thread.id], open_thread_ids);
+
+    let thread = CommentThread::resolve(ctx, thread.id)
+        .await
+        .expect("could not resolve thread");
+    assert_eq!(Some(reviewer.pk()), thread.resolved_by_user_pk);
+    assert!(ChangeSetApprovals::evaluate(ctx)
+        .await
+        .expect("could not evaluate approvals")
+        .open_comment_threads
+        .is_empty());
+    let thread = CommentThread::unresolve(ctx, thread.id)
+        .await
+        .expect("could not unresolve thread");
+    assert!(!thread.is_resolved());
+
+    assert_eq!(
+        vec![thread.id],
+        CommentThread::list_for_component(ctx, component.id())
+            .await
+            .expect("could not list threads")
+            .iter()
+            .map(|thread| thread.id)
+            .collect_vec()
+    );
+
+    let thread = ChangeSetComment::remove(ctx, reply_id)
+        .await
+        .expect("could not delete reply")
+        .expect("thread should still have a comment");
+    assert_eq!(1, thread.comments.len());
+
+    ctx.update_history_actor(HistoryActor::User(author));
+    assert!(ChangeSetComment::remove(ctx, thread.comments[0].id)
+        .await
+        .expect("could not delete comment")
+        .is_none());
+    assert!(CommentThread::list(ctx)
+        .await
+        .expect("could not list threads")
+        .is_empty());
+}
@@ -16,10 +16,13 @@ use serde_json::json;
 
 mod debug;
 mod delete;
+mod fragment;
 mod get_code;
 mod get_diff;
 mod property_order;
+mod query;
 mod set_type;
+mod tag;
 mod upgrade;
 
 #[test]
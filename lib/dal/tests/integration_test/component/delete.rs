@@ -7,7 +7,7 @@ use dal::component::frame::Frame;
 use dal::component::resource::ResourceData;
 use dal::func::intrinsics::IntrinsicFunc;
 use dal::{AttributeValue, ComponentType, Func, InputSocket, OutputSocket};
-use dal::{Component, DalContext, Schema, SchemaVariant};
+use dal::{Component, ComponentError, DalContext, Schema, SchemaVariant};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view,
     create_component_for_schema_name_with_type_on_default_view,
@@ -1113,3 +1113,87 @@ async fn delete_multiple_components(ctx: &mut DalContext) {
         "component with resource should be marked as to delete"
     );
 }
+
+#[test]
+async fn protected_components_cannot_be_deleted(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "dummy-secret", "component")
+            .await
+            .expect("could not create component");
+    component
+        .set_resource(
+            ctx,
+            ResourceData::new(
+                ResourceStatus::Ok,
+                Some(serde_json::json![{"resource": "something"}]),
+            ),
+        )
+        .await
+        .expect("Unable to set resource");
+    let schema_variant_id = Component::schema_variant_id(ctx, component.id())
+        .await
+        .expect("Unable to get schema variant id");
+    let destroy_prototype = ActionPrototype::new(
+        ctx,
+        ActionKind::Destroy,
+        "Destroy action".to_string(),
+        None,
+        schema_variant_id,
+        Func::find_intrinsic(ctx, IntrinsicFunc::Identity)
+            .await
+            .expect("Unable to find identity func"),
+    )
+    .await
+    .expect("Unable to create destroy action");
+
+    let component = component
+        .set_protected(ctx, true)
+        .await
+        .expect("could not protect component");
+    assert!(component.protected());
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    for force_erase in [false, true] {
+        let result = delete_components(ctx, &[component.id()], force_erase).await;
+        assert!(
+            matches!(result, Err(ComponentError::ComponentProtected(id)) if id == component.id()),
+            "protected component should not be deleted (force erase: {force_erase})"
+        );
+    }
+    assert!(
+        Action::new(ctx, destroy_prototype.id(), Some(component.id()))
+            .await
+            .is_err(),
+        "destroy action should not be enqueued for a protected component"
+    );
+    assert!(Action::all_ids(ctx)
+        .await
+        .expect("Unable to list enqueued actions")
+        .is_empty());
+
+    let component = Component::get_by_id(ctx, component.id())
+        .await
+        .expect("component should still exist");
+    assert!(!component.to_delete());
+
+    let component = component
+        .set_protected(ctx, false)
+        .await
+        .expect("could not unprotect component");
+    let deletion_statuses = delete_components(ctx, &[component.id()], false)
+        .await
+        .expect("should be able to delete");
+    assert_eq!(
+        Some(&ComponentDeletionStatus::MarkedForDeletion),
+        deletion_statuses.get(&component.id())
+    );
+    assert_eq!(
+        1,
+        Action::all_ids(ctx)
+            .await
+            .expect("Unable to list enqueued actions")
+            .len()
+    );
+}
@@ -0,0 +1,167 @@
+use dal::component::fragment::ComponentFragment;
+use dal::component::frame::Frame;
+use dal::component::tag::ComponentTag;
+use dal::{Component, ComponentType, DalContext};
+use dal_test::helpers::{
+    connect_components_with_socket_names,
+    create_component_for_schema_name_with_type_on_default_view, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn export_and_import(ctx: &mut DalContext) {
+    let frame = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "swifty",
+        "frame",
+        ComponentType::ConfigurationFrameDown,
+    )
+    .await
+    .expect("could not create component");
+    let first = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small odd lego",
+        "first",
+        ComponentType::Component,
+    )
+    .await
+    .expect("could not create component");
+    let second = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small even lego",
+        "second",
+        ComponentType::Component,
+    )
+    .await
+    .expect("could not create component");
+    let left_out = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small even lego",
+        "left out",
+        ComponentType::Component,
+    )
+    .await
+    .expect("could not create component");
+
+    Frame::upsert_parent(ctx, second.id(), frame.id())
+        .await
+        .expect("could not upsert parent");
+    connect_components_with_socket_names(ctx, first.id(), "two", second.id(), "two")
+        .await
+        .expect("could not connect components");
+    connect_components_with_socket_names(ctx, first.id(), "two", left_out.id(), "two")
+        .await
+        .expect("could not connect components");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let mut fragment =
+        ComponentFragment::export(ctx, &[frame.id(), first.id(), second.id()], "tester")
+            .await
+            .expect("could not export components");
+    assert_eq!(3, fragment.components.len());
+    // The connection to the component outside the selection is dropped.
+    assert_eq!(1, fragment.edges.len());
+
+    let mut missing = fragment.components[0].clone();
+    missing.unique_id = "missing".to_string();
+    missing.schema_name = "not a schema".to_string();
+    missing.parent_unique_id = None;
+    fragment.components.push(missing);
+
+    let report = ComponentFragment::import(ctx, &fragment)
+        .await
+        .expect("could not import fragment");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert_eq!(3, report.component_ids.len());
+    assert_eq!(
+        vec!["missing".to_string()],
+        report
+            .unmapped_components
+            .iter()
+            .map(|unmapped| unmapped.unique_id.clone())
+            .collect::<Vec<_>>()
+    );
+    assert!(report.substituted_variants.is_empty());
+    assert!(report.unmapped_sockets.is_empty());
+
+    let new_frame_id = report.component_ids[&frame.id().to_string()];
+    let new_first_id = report.component_ids[&first.id().to_string()];
+    let new_second_id = report.component_ids[&second.id().to_string()];
+    assert_ne!(frame.id(), new_frame_id);
+    assert_ne!(first.id(), new_first_id);
+    assert_ne!(second.id(), new_second_id);
+
+    assert_eq!(
+        "second",
+        Component::name_by_id(ctx, new_second_id)
+            .await
+            .expect("could not get name")
+    );
+    assert_eq!(
+        Some(new_frame_id),
+        Component::get_parent_by_id(ctx, new_second_id)
+            .await
+            .expect("could not get parent")
+    );
+
+    let incoming = Component::incoming_connections_for_id(ctx, new_second_id)
+        .await
+        .expect("could not list incoming connections");
+    assert_eq!(
+        vec![new_first_id],
+        incoming
+            .iter()
+            .map(|connection| connection.from_component_id)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+async fn export_and_import_keeps_tags(ctx: &mut DalContext) {
+    let component = create_component_for_schema_name_with_type_on_default_view(
+        ctx,
+        "small odd lego",
+        "tagged",
+        ComponentType::Component,
+    )
+    .await
+    .expect("could not create component");
+    ComponentTag::set(ctx, component.id(), "env", "prod")
+        .await
+        .expect("could not set tag");
+    ComponentTag::set(ctx, component.id(), "team", "platform")
+        .await
+        .expect("could not set tag");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let fragment = ComponentFragment::export(ctx, &[component.id()], "tester")
+        .await
+        .expect("could not export components");
+    let tags = ComponentTag::list_for_component(ctx, component.id())
+        .await
+        .expect("could not list tags");
+    assert_eq!(tags, fragment.components[0].tags);
+
+    let report = ComponentFragment::import(ctx, &fragment)
+        .await
+        .expect("could not import fragment");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let new_id = report.component_ids[&component.id().to_string()];
+    assert_eq!(
+        tags,
+        ComponentTag::list_for_component(ctx, new_id)
+            .await
+            .expect("could not list tags")
+    );
+}
@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use dal::component::query::{ComponentQuery, ComponentQueryError};
+use dal::component::tag::ComponentTag;
+use dal::{ComponentId, DalContext};
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use serde_json::json;
+
+async fn query_ids(ctx: &DalContext, query: &str) -> Vec<ComponentId> {
+    let mut ids: Vec<ComponentId> = ComponentQuery::parse(query, &[])
+        .expect("could not parse query")
+        .run(ctx)
+        .await
+        .expect("could not run query")
+        .into_iter()
+        .map(|component| component.component_id)
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+async fn filter_by_schema_name_and_tag(ctx: &mut DalContext) {
+    let prod_star =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "prod star")
+            .await
+            .expect("could not create component");
+    let dev_star =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "dev star")
+            .await
+            .expect("could not create component");
+    let swifty =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "shake it off")
+            .await
+            .expect("could not create component");
+
+    ComponentTag::set(ctx, prod_star.id(), "env", "prod")
+        .await
+        .expect("could not set tag");
+    ComponentTag::set(ctx, swifty.id(), "env", "prod")
+        .await
+        .expect("could not set tag");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let mut all_stars = vec![prod_star.id(), dev_star.id()];
+    all_stars.sort();
+    assert_eq!(all_stars, query_ids(ctx, r#"schema = "starfield""#).await);
+
+    assert_eq!(
+        vec![prod_star.id()],
+        query_ids(ctx, r#"schema = "starfield" and tag.env = "prod""#).await,
+    );
+    assert_eq!(
+        vec![dev_star.id()],
+        query_ids(ctx, r#"schema = "starfield" and not exists(tag.env)"#).await,
+    );
+    assert_eq!(
+        vec![swifty.id()],
+        query_ids(
+            ctx,
+            r#"name ~ "shake" or (name = "nope" and tag.env = "prod")"#
+        )
+        .await,
+    );
+
+    let mut everything = vec![prod_star.id(), dev_star.id(), swifty.id()];
+    everything.sort();
+    assert_eq!(everything, query_ids(ctx, "").await);
+}
+
+#[test]
+async fn select_values(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "swifty", "shake it off")
+            .await
+            .expect("could not create component");
+    ComponentTag::set(ctx, component.id(), "team", "platform")
+        .await
+        .expect("could not set tag");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let matches = ComponentQuery::parse(
+        r#"name = "shake it off""#,
+        &[
+            "name".to_string(),
+            "schema".to_string(),
+            "tag.team".to_string(),
+            "tag.missing".to_string(),
+            "/domain/not-a-prop".to_string(),
+        ],
+    )
+    .expect("could not parse query")
+    .run(ctx)
+    .await
+    .expect("could not run query");
+
+    assert_eq!(1, matches.len());
+    assert_eq!(component.id(), matches[0].component_id);
+    assert_eq!(
+        BTreeMap::from([
+            ("name".to_string(), json!("shake it off")),
+            ("schema".to_string(), json!("swifty")),
+            ("tag.team".to_string(), json!("platform")),
+            ("tag.missing".to_string(), json!(null)),
+            ("/domain/not-a-prop".to_string(), json!(null)),
+        ]),
+        matches[0].values,
+    );
+
+    assert!(matches!(
+        ComponentQuery::parse(r#"name = "shake it off" and"#, &[]),
+        Err(ComponentQueryError::Parse { .. })
+    ));
+}
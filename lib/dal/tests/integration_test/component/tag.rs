@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use dal::component::tag::{ComponentTag, TagSelector};
+use dal::diagram::Diagram;
+use dal::DalContext;
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn set_replace_and_remove(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "black star")
+            .await
+            .expect("could not create component");
+
+    ComponentTag::set(ctx, component.id(), "env", "staging")
+        .await
+        .expect("could not set tag");
+    ComponentTag::set(ctx, component.id(), "team", "platform")
+        .await
+        .expect("could not set tag");
+    ComponentTag::set(ctx, component.id(), "env", "prod")
+        .await
+        .expect("could not replace tag");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert_eq!(
+        BTreeMap::from([
+            ("env".to_string(), "prod".to_string()),
+            ("team".to_string(), "platform".to_string()),
+        ]),
+        ComponentTag::list_for_component(ctx, component.id())
+            .await
+            .expect("could not list tags"),
+    );
+
+    assert!(ComponentTag::remove(ctx, component.id(), "team")
+        .await
+        .expect("could not remove tag"));
+    assert!(!ComponentTag::remove(ctx, component.id(), "team")
+        .await
+        .expect("could not remove tag"));
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert_eq!(
+        BTreeMap::from([("env".to_string(), "prod".to_string())]),
+        ComponentTag::list_for_component(ctx, component.id())
+            .await
+            .expect("could not list tags"),
+    );
+
+    ComponentTag::set(ctx, component.id(), "env=prod", "nope")
+        .await
+        .expect_err("keys cannot contain '='");
+}
+
+#[test]
+async fn select_by_tag(ctx: &mut DalContext) {
+    let prod =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "prod star")
+            .await
+            .expect("could not create component");
+    let staging =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "staging star")
+            .await
+            .expect("could not create component");
+    let untagged =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "untagged star")
+            .await
+            .expect("could not create component");
+
+    ComponentTag::set(ctx, prod.id(), "env", "prod")
+        .await
+        .expect("could not set tag");
+    ComponentTag::set(ctx, prod.id(), "team", "platform")
+        .await
+        .expect("could not set tag");
+    ComponentTag::set(ctx, staging.id(), "env", "staging")
+        .await
+        .expect("could not set tag");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let mut with_env = ComponentTag::list_component_ids_matching(
+        ctx,
+        &TagSelector::parse_list("env").expect("could not parse selectors"),
+    )
+    .await
+    .expect("could not select components");
+    with_env.sort();
+    let mut expected = vec![prod.id(), staging.id()];
+    expected.sort();
+    assert_eq!(expected, with_env);
+
+    let selectors = TagSelector::parse_list("env=prod,team").expect("could not parse selectors");
+    assert_eq!(
+        vec![prod.id()],
+        ComponentTag::list_component_ids_matching(ctx, &selectors)
+            .await
+            .expect("could not select components"),
+    );
+
+    let mut diagram = Diagram::assemble_for_default_view(ctx)
+        .await
+        .expect("could not assemble diagram");
+    assert_eq!(3, diagram.components.len());
+    diagram.retain_tagged(&selectors);
+    assert_eq!(
+        vec![prod.id()],
+        diagram
+            .components
+            .iter()
+            .map(|component| component.component_id)
+            .collect::<Vec<_>>(),
+    );
+    assert!(!diagram
+        .components
+        .iter()
+        .any(|component| component.component_id == untagged.id()));
+}
+
+#[test]
+async fn tags_merge_across_change_sets(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "black star")
+            .await
+            .expect("could not create component");
+    ComponentTag::set(ctx, component.id(), "env", "staging")
+        .await
+        .expect("could not set tag");
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set");
+
+    ChangeSetTestHelpers::fork_from_head_change_set(ctx)
+        .await
+        .expect("could not fork change set");
+    ComponentTag::set(ctx, component.id(), "env", "prod")
+        .await
+        .expect("could not replace tag");
+    ComponentTag::set(ctx, component.id(), "team", "platform")
+        .await
+        .expect("could not set tag");
+    ChangeSetTestHelpers::apply_change_set_to_base(ctx)
+        .await
+        .expect("could not apply change set");
+
+    assert_eq!(
+        BTreeMap::from([
+            ("env".to_string(), "prod".to_string()),
+            ("team".to_string(), "platform".to_string()),
+        ]),
+        ComponentTag::list_for_component(ctx, component.id())
+            .await
+            .expect("could not list tags"),
+    );
+}
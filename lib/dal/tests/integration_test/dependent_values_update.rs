@@ -519,3 +519,62 @@ async fn component_concurrency_limit(ctx: &mut DalContext) {
         )
     }
 }
+
+/// Values downstream of a recomputed value that did not change are cut off, which must not leave
+/// them stale once the upstream value does change again.
+#[test]
+async fn early_cutoff_keeps_downstream_values_correct(ctx: &mut DalContext) {
+    let etoiles = ExpectComponent::create(ctx, "etoiles").await;
+    let morningstar = ExpectComponent::create(ctx, "morningstar").await;
+    etoiles
+        .connect(
+            ctx,
+            "naming_and_necessity",
+            morningstar,
+            "naming_and_necessity",
+        )
+        .await;
+    expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+    let rigid_designator = etoiles
+        .prop(
+            ctx,
+            [
+                "root",
+                "domain",
+                "possible_world_a",
+                "wormhole_1",
+                "wormhole_2",
+                "wormhole_3",
+                "rigid_designator",
+            ],
+        )
+        .await;
+    let stars = morningstar.prop(ctx, ["root", "domain", "stars"]).await;
+
+    // Every designator other than "hesperus" produces the same world, so changing between them
+    // is cut off before reaching the morningstar
+    for (designator, expected_stars) in [
+        ("hesperus", "phosphorus"),
+        ("venus", "not hesperus"),
+        ("mars", "not hesperus"),
+        ("hesperus", "phosphorus"),
+    ] {
+        rigid_designator.set(ctx, designator).await;
+        expected::commit_and_update_snapshot_to_visibility(ctx).await;
+
+        assert!(
+            !ctx.workspace_snapshot()
+                .expect("workspace_snapshot")
+                .has_dependent_value_roots()
+                .await
+                .expect("has dependent value roots"),
+            "all dvu roots should be processed and removed"
+        );
+        assert_eq!(
+            json!(expected_stars),
+            stars.get(ctx).await,
+            "morningstar should see the current world after setting {designator}"
+        );
+    }
+}
@@ -147,6 +147,12 @@ fn make_me_one_with_everything(graph: &mut WorkspaceSnapshotGraphVCurrent) {
                 Ulid::new(),
                 DiagramObjectKind::View(Ulid::new().into()),
             ),
+            NodeWeightDiscriminants::Tag => NodeWeight::new_tag(
+                Ulid::new(),
+                Ulid::new(),
+                "team".to_string(),
+                "platform".to_string(),
+            ),
         };
 
         let idx = graph.add_or_replace_node(weight).expect("add node");
@@ -197,6 +203,7 @@ fn make_me_one_with_everything(graph: &mut WorkspaceSnapshotGraphVCurrent) {
             EdgeWeightKindDiscriminants::Represents => EdgeWeightKind::Represents,
             EdgeWeightKindDiscriminants::Manages => EdgeWeightKind::Manages,
             EdgeWeightKindDiscriminants::DiagramObject => EdgeWeightKind::DiagramObject,
+            EdgeWeightKindDiscriminants::Tag => EdgeWeightKind::Tag,
         };
 
         let edge_weight = EdgeWeight::new(edge_weight_kind);
@@ -290,6 +297,7 @@ async fn graph_can_be_deserialized(_ctx: &DalContext) {
             NodeWeight::Geometry(_) => {}
             NodeWeight::View(_) => {}
             NodeWeight::DiagramObject(_) => {}
+            NodeWeight::Tag(_) => {}
         }
     }
 }
@@ -7,6 +7,9 @@ use pretty_assertions_sorted::assert_eq;
 mod argument;
 mod authoring;
 mod kill_execution;
+mod replay;
+mod retention;
+mod run_log;
 
 #[test]
 async fn summary(ctx: &mut DalContext) {
@@ -905,6 +905,18 @@ async fn return_the_right_bindings(ctx: &mut DalContext, nw: &WorkspaceSignup) {
                                     | dal::func::intrinsics::IntrinsicFunc::Validation => {
                                         assert_eq!(attribute_binding.argument_bindings.len(), 1);
                                     }
+                                    // transforms take any inputs
+                                    dal::func::intrinsics::IntrinsicFunc::Base64Decode
+                                    | dal::func::intrinsics::IntrinsicFunc::Base64Encode
+                                    | dal::func::intrinsics::IntrinsicFunc::BuildArray
+                                    | dal::func::intrinsics::IntrinsicFunc::BuildMap
+                                    | dal::func::intrinsics::IntrinsicFunc::Concat
+                                    | dal::func::intrinsics::IntrinsicFunc::DefaultIfEmpty
+                                    | dal::func::intrinsics::IntrinsicFunc::JsonPointer
+                                    | dal::func::intrinsics::IntrinsicFunc::Lowercase
+                                    | dal::func::intrinsics::IntrinsicFunc::RegexExtract
+                                    | dal::func::intrinsics::IntrinsicFunc::Template
+                                    | dal::func::intrinsics::IntrinsicFunc::Uppercase => {}
                                 }
                             }
                         }
@@ -964,6 +976,18 @@ async fn return_the_right_bindings(ctx: &mut DalContext, nw: &WorkspaceSignup) {
                                     | dal::func::intrinsics::IntrinsicFunc::Validation => {
                                         assert_eq!(attribute_binding.argument_bindings.len(), 1);
                                     }
+                                    // transforms take any inputs
+                                    dal::func::intrinsics::IntrinsicFunc::Base64Decode
+                                    | dal::func::intrinsics::IntrinsicFunc::Base64Encode
+                                    | dal::func::intrinsics::IntrinsicFunc::BuildArray
+                                    | dal::func::intrinsics::IntrinsicFunc::BuildMap
+                                    | dal::func::intrinsics::IntrinsicFunc::Concat
+                                    | dal::func::intrinsics::IntrinsicFunc::DefaultIfEmpty
+                                    | dal::func::intrinsics::IntrinsicFunc::JsonPointer
+                                    | dal::func::intrinsics::IntrinsicFunc::Lowercase
+                                    | dal::func::intrinsics::IntrinsicFunc::RegexExtract
+                                    | dal::func::intrinsics::IntrinsicFunc::Template
+                                    | dal::func::intrinsics::IntrinsicFunc::Uppercase => {}
                                 }
                             }
                         }
@@ -1,6 +1,7 @@
 use crate::integration_test::func::authoring::save_func::save_func_setup;
 use dal::attribute::prototype::argument::value_source::ValueSource;
 use dal::attribute::prototype::argument::AttributePrototypeArgument;
+use dal::builtins::func::migrate_missing_intrinsics_no_commit;
 use dal::func::argument::{FuncArgument, FuncArgumentKind};
 use dal::func::binding::attribute::AttributeBinding;
 use dal::func::binding::{
@@ -16,8 +17,9 @@ use dal::{
 };
 use dal_test::helpers::{
     connect_components_with_socket_names, create_component_for_default_schema_name_in_default_view,
-    get_attribute_value_for_component, get_component_output_socket_value,
-    update_attribute_value_for_component, ChangeSetTestHelpers,
+    create_named_component_for_schema_variant_on_default_view,
+    create_unlocked_variant_copy_for_schema_name, get_attribute_value_for_component,
+    get_component_output_socket_value, update_attribute_value_for_component, ChangeSetTestHelpers,
 };
 use dal_test::test;
 use itertools::Itertools;
@@ -879,3 +881,91 @@ async fn detach_attribute_func(ctx: &mut DalContext) {
     );
     assert!(!funcs.iter().any(|summary| summary.id == func_id));
 }
+
+#[test]
+async fn bind_transform_in_workspace_predating_transforms(ctx: &mut DalContext) {
+    // Workspaces created before the transforms existed don't have them, so remove one to get
+    // there.
+    let uppercase_func_id = Func::find_intrinsic(ctx, IntrinsicFunc::Uppercase)
+        .await
+        .expect("could not find uppercase func");
+    Func::delete_by_id(ctx, uppercase_func_id)
+        .await
+        .expect("could not delete uppercase func");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    assert!(Func::find_intrinsic(ctx, IntrinsicFunc::Uppercase)
+        .await
+        .is_err());
+
+    let imported = migrate_missing_intrinsics_no_commit(ctx)
+        .await
+        .expect("could not migrate missing intrinsics");
+    assert_eq!(1, imported);
+    assert_eq!(
+        0,
+        migrate_missing_intrinsics_no_commit(ctx)
+            .await
+            .expect("could not migrate missing intrinsics")
+    );
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // Bind the migrated transform and make sure it runs.
+    let uppercase_func_id = Func::find_intrinsic(ctx, IntrinsicFunc::Uppercase)
+        .await
+        .expect("could not find migrated uppercase func");
+    let value_arg = FuncArgument::find_by_name_for_func(ctx, "value", uppercase_func_id)
+        .await
+        .expect("could not perform find by name for func")
+        .expect("value argument not found");
+
+    let schema_variant_id = create_unlocked_variant_copy_for_schema_name(ctx, "starfield")
+        .await
+        .expect("could not create unlocked variant copy");
+    let name_prop_id = Prop::find_prop_id_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "si", "name"]),
+    )
+    .await
+    .expect("could not find prop id by path");
+    let freestar_prop_id = Prop::find_prop_id_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "domain", "freestar"]),
+    )
+    .await
+    .expect("could not find prop id by path");
+
+    AttributeBinding::upsert_attribute_binding(
+        ctx,
+        uppercase_func_id,
+        Some(EventualParent::SchemaVariant(schema_variant_id)),
+        AttributeFuncDestination::Prop(freestar_prop_id),
+        vec![AttributeArgumentBinding {
+            func_argument_id: value_arg.id,
+            attribute_prototype_argument_id: None,
+            attribute_func_input_location: AttributeFuncArgumentSource::Prop(name_prop_id),
+        }],
+    )
+    .await
+    .expect("could not bind uppercase func");
+
+    let component =
+        create_named_component_for_schema_variant_on_default_view(ctx, "hello", schema_variant_id)
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    assert_eq!(
+        Some(serde_json::json!("HELLO")),
+        get_attribute_value_for_component(ctx, component.id(), &["root", "domain", "freestar"])
+            .await
+            .expect("could not get freestar value")
+    );
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dal::func::authoring::FuncAuthoringClient;
+use dal::func::replay::{FuncRunReplay, FuncRunReplayRequest};
+use dal::{DalContext, Func};
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use si_events::{FuncRun, FuncRunId, FuncRunState};
+
+#[test]
+async fn load_action_run(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "replayed")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let func_id = Func::find_id_by_name(ctx, "test:createActionStarfield")
+        .await
+        .expect("could not perform find func by name")
+        .expect("no func found");
+    let func = Func::get_by_id_or_error(ctx, func_id)
+        .await
+        .expect("could not get func by id");
+
+    let func_run_id = FuncAuthoringClient::test_execute_func(
+        ctx,
+        func_id,
+        serde_json::Value::Null,
+        None,
+        component.id(),
+    )
+    .await
+    .expect("could not perform test execution for func");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    let func_run = wait_for_func_run_with_success_state(ctx, func_run_id).await;
+
+    let replay = FuncRunReplay::load(ctx, func_run_id, &HashMap::new())
+        .await
+        .expect("could not load replay");
+
+    assert_eq!(func_run.id(), replay.func_run.id());
+    assert!(replay.redacted_secrets.is_empty());
+    assert!(!replay.code_from_current_func);
+    assert_eq!(func.handler.as_deref(), Some(replay.handler.as_str()));
+    match replay.request {
+        FuncRunReplayRequest::Action(request) => {
+            assert_eq!(func_run_id.to_string(), request.execution_id);
+            assert_eq!(func.handler, Some(request.handler));
+            assert_eq!(func.code_base64, Some(request.code_base64));
+            assert_eq!(serde_json::Value::Null, request.args);
+        }
+        other => panic!("expected an action request, found: {other:?}"),
+    }
+}
+
+async fn wait_for_func_run_with_success_state(ctx: &DalContext, func_run_id: FuncRunId) -> FuncRun {
+    let seconds = 15;
+
+    for _ in 0..(seconds * 10) {
+        let func_run = ctx
+            .layer_db()
+            .func_run()
+            .read(func_run_id)
+            .await
+            .expect("could not read func run")
+            .expect("func run not found");
+
+        if func_run.state() == FuncRunState::Success {
+            return Arc::unwrap_or_clone(func_run);
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("timed out waiting for func run");
+}
@@ -0,0 +1,63 @@
+use dal::func::retention::{FuncRunRetentionError, FuncRunRetentionPolicy};
+use dal::{DalContext, FuncKind};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+
+#[test]
+async fn set_and_reset_policy(ctx: &DalContext) {
+    let workspace_pk = ctx.workspace_pk().expect("could not get workspace pk");
+
+    let policies = FuncRunRetentionPolicy::list(ctx)
+        .await
+        .expect("could not list policies");
+    assert!(policies.iter().all(|policy| policy.is_default));
+    let action = policies
+        .iter()
+        .find(|policy| policy.function_kind == FuncKind::Action)
+        .expect("no action policy");
+    assert_eq!(
+        FuncRunRetentionPolicy::default_for(workspace_pk, FuncKind::Action),
+        action.to_owned()
+    );
+
+    let set = FuncRunRetentionPolicy::set(ctx, FuncKind::Attribute, 2, false)
+        .await
+        .expect("could not set policy");
+    assert_eq!(
+        FuncRunRetentionPolicy {
+            workspace_pk,
+            function_kind: FuncKind::Attribute,
+            retention_days: 2,
+            keep_latest: false,
+            is_default: false,
+        },
+        set
+    );
+    let policies = FuncRunRetentionPolicy::list(ctx)
+        .await
+        .expect("could not list policies");
+    assert_eq!(
+        Some(&set),
+        policies
+            .iter()
+            .find(|policy| policy.function_kind == FuncKind::Attribute)
+    );
+
+    let invalid = FuncRunRetentionPolicy::set(ctx, FuncKind::Attribute, 0, true).await;
+    assert!(matches!(
+        invalid,
+        Err(FuncRunRetentionError::InvalidRetentionDays(0))
+    ));
+
+    let reset = FuncRunRetentionPolicy::reset(ctx, FuncKind::Attribute)
+        .await
+        .expect("could not reset policy");
+    assert_eq!(
+        FuncRunRetentionPolicy::default_for(workspace_pk, FuncKind::Attribute),
+        reset
+    );
+    let policies = FuncRunRetentionPolicy::list(ctx)
+        .await
+        .expect("could not list policies");
+    assert!(policies.iter().all(|policy| policy.is_default));
+}
@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use dal::func::run_log::{self, FuncRunLogError, FuncRunLogLine, FuncRunLogSearch};
+use dal::{ActionId, AttributeValueId, ComponentId, DalContext, Func};
+use dal_test::test;
+use futures::StreamExt;
+use pretty_assertions_sorted::assert_eq;
+use si_events::{
+    ContentHash, FuncBackendKind, FuncBackendResponseType, FuncKind, FuncRun, FuncRunBuilder,
+    FuncRunId, FuncRunLog, OutputLine,
+};
+
+#[test]
+async fn search_and_tail(ctx: &DalContext) {
+    let component_id = ComponentId::new();
+    let create = write_func_run(
+        ctx,
+        Some(component_id),
+        "create",
+        &["creating bucket", "error: bucket exists", "retrying"],
+    )
+    .await;
+    let refresh = write_func_run(ctx, None, "refresh", &["refreshing", "error: timeout"]).await;
+
+    let search = |search: FuncRunLogSearch| async move {
+        search
+            .run(ctx)
+            .await
+            .expect("could not search func run logs")
+    };
+
+    let results = search(FuncRunLogSearch {
+        contains: Some("error".to_owned()),
+        ..Default::default()
+    })
+    .await;
+    // Only look at our own func runs, in case the workspace has others
+    let mut found: Vec<_> = results
+        .matches
+        .iter()
+        .filter(|found| [create.id(), refresh.id()].contains(&found.func_run_id))
+        .map(|found| (found.func_run_id, found.line_number))
+        .collect();
+    found.sort();
+    let mut expected = vec![(create.id(), 1), (refresh.id(), 1)];
+    expected.sort();
+    assert_eq!(expected, found);
+
+    let results = search(FuncRunLogSearch {
+        component_id: Some(component_id),
+        pattern: Some("^(creat|retry)ing".to_owned()),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(
+        vec!["creating bucket".to_owned(), "retrying".to_owned()],
+        results
+            .matches
+            .iter()
+            .map(|found| found.line.message.clone())
+            .collect::<Vec<_>>()
+    );
+
+    let results = search(FuncRunLogSearch {
+        stream: Some("output".to_owned()),
+        limit: Some(1),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(1, results.matches.len());
+    assert!(results.truncated);
+
+    let invalid = FuncRunLogSearch {
+        pattern: Some("(".to_owned()),
+        ..Default::default()
+    }
+    .run(ctx)
+    .await;
+    assert!(matches!(invalid, Err(FuncRunLogError::InvalidPattern(_))));
+
+    // The log is complete, so tailing it replays the stored lines and ends
+    let lines: Vec<FuncRunLogLine> = run_log::tail(ctx, refresh.id())
+        .await
+        .expect("could not tail func run log")
+        .collect()
+        .await;
+    assert_eq!(
+        vec!["refreshing".to_owned(), "error: timeout".to_owned()],
+        lines
+            .into_iter()
+            .map(|line| line.message)
+            .collect::<Vec<_>>()
+    );
+
+    let missing = run_log::tail(ctx, FuncRunId::new()).await;
+    assert!(matches!(missing, Err(FuncRunLogError::FuncRunNotFound(_))));
+}
+
+#[test]
+async fn search_by_func(ctx: &DalContext) {
+    let func = Func::list_all(ctx)
+        .await
+        .expect("could not list funcs")
+        .into_iter()
+        .next()
+        .expect("workspace has no funcs");
+
+    // Neither attribute nor action runs store the func's id, so they must be found by its name
+    let mut attribute = func_run_builder(ctx, &func.name);
+    attribute
+        .attribute_value_id(Some(AttributeValueId::new()))
+        .backend_kind(FuncBackendKind::JsAttribute)
+        .backend_response_type(FuncBackendResponseType::Object)
+        .function_kind(FuncKind::Attribute);
+    let attribute = write_func_run_with(ctx, attribute, &["computing"]).await;
+    let mut action = func_run_builder(ctx, &func.name);
+    action.action_or_func_id(Some(ActionId::new().into()));
+    let action = write_func_run_with(ctx, action, &["creating"]).await;
+    let other = write_func_run(ctx, None, "some other func", &["ignored"]).await;
+
+    let results = FuncRunLogSearch {
+        func_id: Some(func.id),
+        ..Default::default()
+    }
+    .run(ctx)
+    .await
+    .expect("could not search func run logs");
+    let mut found: Vec<_> = results
+        .matches
+        .iter()
+        .filter(|found| [attribute.id(), action.id(), other.id()].contains(&found.func_run_id))
+        .map(|found| found.func_run_id)
+        .collect();
+    found.sort();
+    let mut expected = vec![attribute.id(), action.id()];
+    expected.sort();
+    assert_eq!(expected, found);
+}
+
+async fn write_func_run(
+    ctx: &DalContext,
+    component_id: Option<ComponentId>,
+    function_name: &str,
+    messages: &[&str],
+) -> Arc<FuncRun> {
+    let mut builder = func_run_builder(ctx, function_name);
+    builder.component_id(component_id);
+    write_func_run_with(ctx, builder, messages).await
+}
+
+fn func_run_builder(ctx: &DalContext, function_name: &str) -> FuncRunBuilder {
+    let now = Utc::now();
+    let mut builder = FuncRunBuilder::default();
+    builder
+        .actor(ctx.events_actor())
+        .tenancy(ctx.events_tenancy())
+        .component_id(None)
+        .attribute_value_id(None)
+        .backend_kind(FuncBackendKind::JsAction)
+        .backend_response_type(FuncBackendResponseType::Action)
+        .function_name(function_name.to_owned())
+        .function_kind(FuncKind::Action)
+        .function_args_cas_address(ContentHash::default())
+        .function_code_cas_address(ContentHash::default())
+        .created_at(now)
+        .updated_at(now);
+    builder
+}
+
+async fn write_func_run_with(
+    ctx: &DalContext,
+    builder: FuncRunBuilder,
+    messages: &[&str],
+) -> Arc<FuncRun> {
+    let now = Utc::now();
+    let func_run = Arc::new(builder.build().expect("could not build func run"));
+    ctx.layer_db()
+        .func_run()
+        .write(
+            func_run.clone(),
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )
+        .await
+        .expect("could not write func run");
+
+    let mut func_run_log = FuncRunLog::new(func_run.id(), ctx.events_tenancy());
+    for message in messages {
+        func_run_log.push_log(OutputLine {
+            stream: "output".to_owned(),
+            execution_id: func_run.id().to_string(),
+            level: "info".to_owned(),
+            group: None,
+            message: (*message).to_owned(),
+            timestamp: now.timestamp() as u64,
+        });
+    }
+    func_run_log.set_finalized();
+    ctx.layer_db()
+        .func_run_log()
+        .write(
+            Arc::new(func_run_log),
+            None,
+            ctx.events_tenancy(),
+            ctx.events_actor(),
+        )
+        .await
+        .expect("could not write func run log");
+
+    func_run
+}
@@ -1,9 +1,13 @@
 use dal::diagram::geometry::RawGeometry;
+use dal::job::consumer::JobConsumer;
+use dal::job::definition::ReencryptSecrets;
 use dal::prop::PropPath;
 use dal::property_editor::values::PropertyEditorValues;
 use dal::qualification::QualificationSubCheckStatus;
 use dal::secret::DecryptedSecret;
-use dal::{Component, DalContext, EncryptedSecret, Prop, Secret, SecretAlgorithm, SecretVersion};
+use dal::{
+    Component, DalContext, EncryptedSecret, KeyPair, Prop, Secret, SecretAlgorithm, SecretVersion,
+};
 use dal_test::expected::{self, ExpectComponent, ExpectView};
 use dal_test::helpers::{
     create_component_for_default_schema_name_in_default_view, encrypt_message, ChangeSetTestHelpers,
@@ -783,6 +787,95 @@ async fn secret_definition_works_with_dummy_qualification(
     }
 }
 
+#[test]
+async fn reencrypt_secrets_after_key_pair_rotation(ctx: &mut DalContext, nw: &WorkspaceSignup) {
+    let old_key_pair_pk = nw.key_pair.pk();
+    let name = generate_fake_name().expect("could not generate fake name");
+
+    // Create a secret encrypted with the workspace's original key pair.
+    let message = serde_json::json!({"song": "Redbone", "artist": "Childish Gambino"});
+    let crypted = encrypt_message(ctx, old_key_pair_pk, &message)
+        .await
+        .expect("could not encrypt message");
+    let secret = Secret::new(
+        ctx,
+        &name,
+        "imasecret".to_owned(),
+        None,
+        &crypted,
+        old_key_pair_pk,
+        SecretVersion::default(),
+        SecretAlgorithm::default(),
+    )
+    .await
+    .expect("failed to create encrypted secret");
+    let original_key = secret.encrypted_secret_key();
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    // Rotate the key pair and run the job that moves secrets over to it.
+    let new_key_pair = KeyPair::new(ctx, "rotated")
+        .await
+        .expect("could not create key pair");
+    ctx.commit_no_rebase().await.expect("could not commit");
+    assert_ne!(old_key_pair_pk, new_key_pair.pk());
+
+    ReencryptSecrets::new(ctx)
+        .run(ctx)
+        .await
+        .expect("could not run reencrypt secrets job");
+    ctx.update_snapshot_to_visibility()
+        .await
+        .expect("could not update snapshot to visibility");
+
+    // The secret now points at contents encrypted with the new key pair, which decrypt to the
+    // original message.
+    let reencrypted_secret = Secret::get_by_id_or_error(ctx, secret.id())
+        .await
+        .expect("could not perform get by id or secret not found");
+    assert_ne!(original_key, reencrypted_secret.encrypted_secret_key());
+    let encrypted_secret =
+        EncryptedSecret::get_by_key(ctx, reencrypted_secret.encrypted_secret_key())
+            .await
+            .expect("failed to perform get by key for encrypted secret")
+            .expect("no encrypted secret found");
+    assert_eq!(
+        new_key_pair.pk(),
+        encrypted_secret
+            .key_pair(ctx)
+            .await
+            .expect("failed to fetch key pair")
+            .pk()
+    );
+    let decrypted = encrypted_secret
+        .decrypt(ctx)
+        .await
+        .expect("failed to decrypt encrypted secret");
+    assert_eq!(message, prepare_decrypted_secret_for_assertions(&decrypted));
+
+    // Nothing is left on the old key pair, so a second pass has nothing to do.
+    for secret in Secret::list(ctx).await.expect("could not list secrets") {
+        let encrypted_secret = EncryptedSecret::get_by_key(ctx, secret.encrypted_secret_key())
+            .await
+            .expect("failed to perform get by key for encrypted secret")
+            .expect("no encrypted secret found");
+        assert_ne!(
+            old_key_pair_pk,
+            encrypted_secret
+                .key_pair(ctx)
+                .await
+                .expect("failed to fetch key pair")
+                .pk()
+        );
+    }
+    assert!(reencrypted_secret
+        .reencrypt_with_current_keys(ctx)
+        .await
+        .expect("could not reencrypt secret")
+        .is_none());
+}
+
 fn prepare_decrypted_secret_for_assertions(decrypted_secret: &DecryptedSecret) -> Value {
     // We don't provide a direct getter for the raw decrypted message (higher effort should mean
     // less chance of developer error when handling `DecryptedSecret` types), so we'll serialize to
@@ -0,0 +1,599 @@
+use std::collections::HashSet;
+
+use dal::prop::PropPath;
+use dal::workspace_snapshot::graph::v4::fsck::{SnapshotViolation, SnapshotViolationKind};
+use dal::workspace_snapshot::graph::NodeIndex;
+use dal::workspace_snapshot::node_weight::category_node_weight::CategoryNodeKind;
+use dal::workspace_snapshot::node_weight::{CategoryNodeWeight, NodeWeight, OrderingNodeWeight};
+use dal::workspace_snapshot::Direction;
+use dal::{
+    Component, ComponentId, DalContext, EdgeWeight, EdgeWeightKind, EdgeWeightKindDiscriminants,
+    Prop,
+};
+use dal_test::helpers::{
+    create_component_for_default_schema_name_in_default_view, ChangeSetTestHelpers,
+};
+use dal_test::test;
+use pretty_assertions_sorted::assert_eq;
+use si_events::{ulid::Ulid, ContentHash};
+
+#[test]
+async fn fsck_is_clean_after_creating_components(ctx: &mut DalContext) {
+    create_component_for_default_schema_name_in_default_view(ctx, "swifty", "swift")
+        .await
+        .expect("could not create component");
+    create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+        .await
+        .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let violations = snapshot.fsck(ctx).await.expect("could not fsck snapshot");
+    assert_eq!(
+        Vec::<String>::new(),
+        violations
+            .iter()
+            .map(|v| v.message.clone())
+            .collect::<Vec<_>>()
+    );
+
+    let repaired = snapshot
+        .repair(&violations)
+        .await
+        .expect("could not repair snapshot");
+    assert!(repaired.is_empty());
+}
+
+/// Creates a component and checks that the snapshot starts out clean, so that every violation
+/// found afterwards comes from the corruption under test.
+async fn setup(ctx: &mut DalContext) -> ComponentId {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "starfield", "starfield")
+            .await
+            .expect("could not create component");
+    ChangeSetTestHelpers::commit_and_update_snapshot_to_visibility(ctx)
+        .await
+        .expect("could not commit and update snapshot to visibility");
+    assert!(fsck(ctx).await.is_empty());
+
+    component.id()
+}
+
+async fn fsck(ctx: &DalContext) -> Vec<SnapshotViolation> {
+    ctx.workspace_snapshot()
+        .expect("get snap")
+        .fsck(ctx)
+        .await
+        .expect("could not fsck snapshot")
+}
+
+fn kinds(violations: &[SnapshotViolation]) -> HashSet<SnapshotViolationKind> {
+    violations.iter().map(|violation| violation.kind).collect()
+}
+
+/// Runs fsck, checks that it found the violation, repairs the snapshot and returns the
+/// violations that are left afterwards. A repairable violation must be gone after the repair,
+/// and any other must have been left alone.
+async fn detect_and_repair(
+    ctx: &DalContext,
+    kind: SnapshotViolationKind,
+) -> Vec<SnapshotViolation> {
+    let violations = fsck(ctx).await;
+    let detected: Vec<_> = violations.iter().filter(|v| v.kind == kind).collect();
+    assert!(
+        !detected.is_empty(),
+        "{kind} not detected in {violations:?}"
+    );
+    assert!(detected
+        .iter()
+        .all(|v| v.repairable == kind.is_repairable()));
+
+    let repaired = ctx
+        .workspace_snapshot()
+        .expect("get snap")
+        .repair(&violations)
+        .await
+        .expect("could not repair snapshot");
+    assert!(repaired.iter().all(|violation| violation.repairable));
+
+    let remaining = fsck(ctx).await;
+    assert_eq!(
+        kind.is_repairable(),
+        !kinds(&remaining).contains(&kind),
+        "{kind} after repair: {remaining:?}"
+    );
+    remaining
+}
+
+async fn root_attribute_value_index(ctx: &DalContext, component_id: ComponentId) -> NodeIndex {
+    let root_attribute_value_id = Component::root_attribute_value_id(ctx, component_id)
+        .await
+        .expect("could not get root attribute value");
+    ctx.workspace_snapshot()
+        .expect("get snap")
+        .get_node_index_by_id(root_attribute_value_id)
+        .await
+        .expect("could not get node index")
+}
+
+async fn category_id(ctx: &DalContext, kind: CategoryNodeKind) -> Ulid {
+    ctx.workspace_snapshot()
+        .expect("get snap")
+        .get_category_node_or_err(None, kind)
+        .await
+        .expect("could not get category node")
+}
+
+/// An attribute prototype argument that takes its value from a prop, with the index of the
+/// argument and of the prop.
+async fn prop_prototype_argument(ctx: &DalContext) -> (Ulid, NodeIndex, NodeIndex) {
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    for (node_weight, node_index) in snapshot.nodes().await.expect("could not list nodes") {
+        if !matches!(node_weight, NodeWeight::AttributePrototypeArgument(_)) {
+            continue;
+        }
+        for (_, _, target_index) in snapshot
+            .edges_directed_for_edge_weight_kind(
+                node_weight.id(),
+                Direction::Outgoing,
+                EdgeWeightKindDiscriminants::PrototypeArgumentValue,
+            )
+            .await
+            .expect("could not get edges")
+        {
+            if let Some(NodeWeight::Prop(_)) = snapshot.get_node_weight_opt(target_index).await {
+                return (node_weight.id(), node_index, target_index);
+            }
+        }
+    }
+    panic!("no attribute prototype argument takes its value from a prop");
+}
+
+#[test]
+async fn fsck_component_missing_category(ctx: &mut DalContext) {
+    let component_id = setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let category_index = snapshot
+        .get_node_index_by_id(category_id(ctx, CategoryNodeKind::Component).await)
+        .await
+        .expect("could not get node index");
+    let component_index = snapshot
+        .get_node_index_by_id(component_id)
+        .await
+        .expect("could not get node index");
+    snapshot
+        .remove_edge(
+            category_index,
+            component_index,
+            EdgeWeightKindDiscriminants::Use,
+        )
+        .await
+        .expect("could not remove edge");
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::ComponentMissingCategory).await;
+    assert_eq!(
+        HashSet::from([SnapshotViolationKind::ComponentMissingCategory]),
+        kinds(&remaining)
+    );
+}
+
+#[test]
+async fn fsck_component_missing_root(ctx: &mut DalContext) {
+    let component_id = setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let component_index = snapshot
+        .get_node_index_by_id(component_id)
+        .await
+        .expect("could not get node index");
+    let root_index = root_attribute_value_index(ctx, component_id).await;
+    snapshot
+        .remove_edge(
+            component_index,
+            root_index,
+            EdgeWeightKindDiscriminants::Root,
+        )
+        .await
+        .expect("could not remove edge");
+
+    // The root attribute value is orphaned and removed, but the component still has no root.
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::ComponentMissingRoot).await;
+    assert_eq!(
+        HashSet::from([SnapshotViolationKind::ComponentMissingRoot]),
+        kinds(&remaining)
+    );
+}
+
+#[test]
+async fn fsck_cycle(ctx: &mut DalContext) {
+    let component_id = setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let component_index = snapshot
+        .get_node_index_by_id(component_id)
+        .await
+        .expect("could not get node index");
+    let root_index = root_attribute_value_index(ctx, component_id).await;
+    snapshot
+        .add_edge_unchecked(
+            root_index,
+            EdgeWeight::new(EdgeWeightKind::new_use()),
+            component_index,
+        )
+        .await
+        .expect("could not add edge");
+
+    // Repairing rehashes the graph, which can't be done with a cycle in it, so only check that
+    // the cycle is found and left for a person to fix.
+    let violations = fsck(ctx).await;
+    let cycle = violations
+        .iter()
+        .find(|violation| violation.kind == SnapshotViolationKind::Cycle)
+        .expect("cycle not detected");
+    assert!(!cycle.repairable);
+}
+
+#[test]
+async fn fsck_dangling_prototype_argument(ctx: &mut DalContext) {
+    setup(ctx).await;
+    let (argument_id, argument_index, _) = prop_prototype_argument(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    for (_, source_index, target_index) in snapshot
+        .edges_directed_for_edge_weight_kind(
+            argument_id,
+            Direction::Incoming,
+            EdgeWeightKindDiscriminants::PrototypeArgument,
+        )
+        .await
+        .expect("could not get edges")
+    {
+        snapshot
+            .remove_edge(
+                source_index,
+                target_index,
+                EdgeWeightKindDiscriminants::PrototypeArgument,
+            )
+            .await
+            .expect("could not remove edge");
+    }
+    assert_eq!(
+        Some(argument_index),
+        snapshot.get_node_index_by_id_opt(argument_id).await
+    );
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::DanglingPrototypeArgument).await;
+    assert!(remaining.is_empty(), "{remaining:?}");
+    assert_eq!(None, snapshot.get_node_index_by_id_opt(argument_id).await);
+}
+
+#[test]
+async fn fsck_duplicate_category(ctx: &mut DalContext) {
+    setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let id = snapshot
+        .generate_ulid()
+        .await
+        .expect("could not generate ulid");
+    let lineage_id = snapshot
+        .generate_ulid()
+        .await
+        .expect("could not generate ulid");
+    let category_index = snapshot
+        .add_or_replace_node(NodeWeight::Category(CategoryNodeWeight::new(
+            id,
+            lineage_id,
+            CategoryNodeKind::Component,
+        )))
+        .await
+        .expect("could not add node");
+    snapshot
+        .add_edge_unchecked(
+            snapshot.root().await.expect("could not get root"),
+            EdgeWeight::new(EdgeWeightKind::new_use()),
+            category_index,
+        )
+        .await
+        .expect("could not add edge");
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::DuplicateCategory).await;
+    assert_eq!(
+        HashSet::from([SnapshotViolationKind::DuplicateCategory]),
+        kinds(&remaining)
+    );
+    assert_eq!(2, remaining.len());
+}
+
+#[test]
+async fn fsck_duplicate_node_id(ctx: &mut DalContext) {
+    setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let batch_category_id = category_id(ctx, CategoryNodeKind::DeprecatedActionBatch).await;
+    let roots_category_id = category_id(ctx, CategoryNodeKind::DependentValueRoots).await;
+    let lineage_id = snapshot
+        .get_node_weight_by_id(batch_category_id)
+        .await
+        .expect("could not get node weight")
+        .lineage_id();
+    snapshot
+        .update_node_id(batch_category_id, roots_category_id, lineage_id)
+        .await
+        .expect("could not update node id");
+
+    // The stale index entry is rebuilt, but both nodes still have the same id.
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::DuplicateNodeId).await;
+    assert_eq!(
+        HashSet::from([SnapshotViolationKind::DuplicateNodeId]),
+        kinds(&remaining)
+    );
+}
+
+#[test]
+async fn fsck_exclusive_edge_violated(ctx: &mut DalContext) {
+    let component_id = setup(ctx).await;
+    let schema_variant_id = Component::schema_variant_id(ctx, component_id)
+        .await
+        .expect("could not get schema variant id");
+    let domain_prop_id =
+        Prop::find_prop_id_by_path(ctx, schema_variant_id, &PropPath::new(["root", "domain"]))
+            .await
+            .expect("could not find prop id by path");
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let domain_prop_index = snapshot
+        .get_node_index_by_id(domain_prop_id)
+        .await
+        .expect("could not get node index");
+    let root_index = root_attribute_value_index(ctx, component_id).await;
+    snapshot
+        .add_edge_unchecked(
+            root_index,
+            EdgeWeight::new(EdgeWeightKind::Prop),
+            domain_prop_index,
+        )
+        .await
+        .expect("could not add edge");
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::ExclusiveEdgeViolated).await;
+    assert_eq!(
+        HashSet::from([SnapshotViolationKind::ExclusiveEdgeViolated]),
+        kinds(&remaining)
+    );
+}
+
+#[test]
+async fn fsck_index_out_of_sync(ctx: &mut DalContext) {
+    setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let old_id = category_id(ctx, CategoryNodeKind::DeprecatedActionBatch).await;
+    let node_weight = snapshot
+        .get_node_weight_by_id(old_id)
+        .await
+        .expect("could not get node weight");
+    let new_id = snapshot
+        .generate_ulid()
+        .await
+        .expect("could not generate ulid");
+    // Changing the id leaves the old id in the index, pointing at a node that no longer has it.
+    snapshot
+        .update_node_id(old_id, new_id, node_weight.lineage_id())
+        .await
+        .expect("could not update node id");
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::IndexOutOfSync).await;
+    assert!(remaining.is_empty(), "{remaining:?}");
+    assert_eq!(None, snapshot.get_node_index_by_id_opt(old_id).await);
+    assert!(snapshot.get_node_index_by_id_opt(new_id).await.is_some());
+}
+
+#[test]
+async fn fsck_invalid_prototype_argument_value(ctx: &mut DalContext) {
+    setup(ctx).await;
+    let (_, argument_index, prop_index) = prop_prototype_argument(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    // Point the argument at a category with nothing under it, since pointing it at anything
+    // that can reach the argument's prototype would also make a cycle.
+    let category_index = snapshot
+        .get_node_index_by_id(category_id(ctx, CategoryNodeKind::DeprecatedActionBatch).await)
+        .await
+        .expect("could not get node index");
+    snapshot
+        .remove_edge(
+            argument_index,
+            prop_index,
+            EdgeWeightKindDiscriminants::PrototypeArgumentValue,
+        )
+        .await
+        .expect("could not remove edge");
+    snapshot
+        .add_edge_unchecked(
+            argument_index,
+            EdgeWeight::new(EdgeWeightKind::PrototypeArgumentValue),
+            category_index,
+        )
+        .await
+        .expect("could not add edge");
+
+    let remaining =
+        detect_and_repair(ctx, SnapshotViolationKind::InvalidPrototypeArgumentValue).await;
+    assert_eq!(
+        HashSet::from([SnapshotViolationKind::InvalidPrototypeArgumentValue]),
+        kinds(&remaining)
+    );
+}
+
+#[test]
+async fn fsck_missing_category(ctx: &mut DalContext) {
+    setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    snapshot
+        .remove_node_by_id(category_id(ctx, CategoryNodeKind::DeprecatedActionBatch).await)
+        .await
+        .expect("could not remove node");
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::MissingCategory).await;
+    assert!(remaining.is_empty(), "{remaining:?}");
+    assert!(snapshot
+        .get_category_node(None, CategoryNodeKind::DeprecatedActionBatch)
+        .await
+        .expect("could not get category node")
+        .is_some());
+}
+
+#[test]
+async fn fsck_missing_content(ctx: &mut DalContext) {
+    let component_id = setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    snapshot
+        .update_content(
+            Ulid::from(component_id),
+            ContentHash::new(b"not in the cas"),
+        )
+        .await
+        .expect("could not update content");
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::MissingContent).await;
+    assert_eq!(
+        HashSet::from([SnapshotViolationKind::MissingContent]),
+        kinds(&remaining)
+    );
+    assert!(remaining
+        .iter()
+        .all(|violation| violation.node_id == Ulid::from(component_id)));
+}
+
+#[test]
+async fn fsck_ordering_out_of_sync(ctx: &mut DalContext) {
+    let component_id = setup(ctx).await;
+    let root_attribute_value_id = Component::root_attribute_value_id(ctx, component_id)
+        .await
+        .expect("could not get root attribute value");
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let ordering = snapshot
+        .ordering_node_for_container(root_attribute_value_id)
+        .await
+        .expect("could not get ordering node")
+        .expect("root attribute value has no ordering node");
+    let ordering_index = snapshot
+        .get_node_index_by_id(ordering.id())
+        .await
+        .expect("could not get node index");
+    let element_index = snapshot
+        .get_node_index_by_id(*ordering.order().first().expect("order is empty"))
+        .await
+        .expect("could not get node index");
+    snapshot
+        .remove_edge(
+            ordering_index,
+            element_index,
+            EdgeWeightKindDiscriminants::Ordinal,
+        )
+        .await
+        .expect("could not remove edge");
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::OrderingOutOfSync).await;
+    assert!(remaining.is_empty(), "{remaining:?}");
+    let repaired_ordering = snapshot
+        .ordering_node_for_container(root_attribute_value_id)
+        .await
+        .expect("could not get ordering node")
+        .expect("root attribute value has no ordering node");
+    assert_eq!(ordering.order(), repaired_ordering.order());
+}
+
+#[test]
+async fn fsck_orphaned_attribute_value(ctx: &mut DalContext) {
+    let component_id = setup(ctx).await;
+    let component = Component::get_by_id(ctx, component_id)
+        .await
+        .expect("could not get component");
+    let attribute_value_id = component
+        .attribute_values_for_prop(ctx, &["root", "domain", "freestar"])
+        .await
+        .expect("could not get attribute values")
+        .pop()
+        .expect("no attribute value for freestar");
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    for (_, source_index, target_index) in snapshot
+        .edges_directed_for_edge_weight_kind(
+            attribute_value_id,
+            Direction::Incoming,
+            EdgeWeightKindDiscriminants::Contain,
+        )
+        .await
+        .expect("could not get edges")
+    {
+        snapshot
+            .remove_edge(
+                source_index,
+                target_index,
+                EdgeWeightKindDiscriminants::Contain,
+            )
+            .await
+            .expect("could not remove edge");
+    }
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::OrphanedAttributeValue).await;
+    assert!(remaining.is_empty(), "{remaining:?}");
+    assert_eq!(
+        None,
+        snapshot.get_node_index_by_id_opt(attribute_value_id).await
+    );
+}
+
+#[test]
+async fn fsck_too_many_ordering_nodes(ctx: &mut DalContext) {
+    let component_id = setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let id = snapshot
+        .generate_ulid()
+        .await
+        .expect("could not generate ulid");
+    let lineage_id = snapshot
+        .generate_ulid()
+        .await
+        .expect("could not generate ulid");
+    let ordering_index = snapshot
+        .add_or_replace_node(NodeWeight::Ordering(OrderingNodeWeight::new(
+            id, lineage_id,
+        )))
+        .await
+        .expect("could not add node");
+    snapshot
+        .add_edge_unchecked(
+            root_attribute_value_index(ctx, component_id).await,
+            EdgeWeight::new(EdgeWeightKind::Ordering),
+            ordering_index,
+        )
+        .await
+        .expect("could not add edge");
+
+    // The extra ordering node's order is brought in line with the container, but it stays.
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::TooManyOrderingNodes).await;
+    assert_eq!(
+        HashSet::from([SnapshotViolationKind::TooManyOrderingNodes]),
+        kinds(&remaining)
+    );
+}
+
+#[test]
+async fn fsck_unreachable(ctx: &mut DalContext) {
+    setup(ctx).await;
+    let snapshot = ctx.workspace_snapshot().expect("get snap");
+    let id = snapshot
+        .generate_ulid()
+        .await
+        .expect("could not generate ulid");
+    let lineage_id = snapshot
+        .generate_ulid()
+        .await
+        .expect("could not generate ulid");
+    snapshot
+        .add_or_replace_node(NodeWeight::Ordering(OrderingNodeWeight::new(
+            id, lineage_id,
+        )))
+        .await
+        .expect("could not add node");
+
+    let remaining = detect_and_repair(ctx, SnapshotViolationKind::Unreachable).await;
+    assert!(remaining.is_empty(), "{remaining:?}");
+    assert_eq!(None, snapshot.get_node_index_by_id_opt(id).await);
+}
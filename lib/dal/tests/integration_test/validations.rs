@@ -1,3 +1,5 @@
+use dal::func::runner::FuncRunner;
+use dal::validation::joi::{self, JoiOutcome};
 use dal::workspace_snapshot::content_address::ContentAddressDiscriminants;
 use dal::workspace_snapshot::edge_weight::EdgeWeightKindDiscriminants;
 use dal::{AttributeValue, Component, DalContext};
@@ -397,3 +399,158 @@ async fn validation_qualification(ctx: &mut DalContext) {
         serde_json::to_value(validation_qualification).expect("serialise qualification")
     );
 }
+
+#[test]
+async fn native_validation_matches_veritech(ctx: &mut DalContext) {
+    let component =
+        create_component_for_default_schema_name_in_default_view(ctx, "pirate", "Robinson Crusoe")
+            .await
+            .expect("could not create component");
+    let av_id = component
+        .attribute_values_for_prop(ctx, &["root", "domain", "working_eyes"])
+        .await
+        .expect("find value ids for the prop")
+        .pop()
+        .expect("there should only be one value id");
+
+    let cases = [
+        (
+            json!({"type": "number", "flags": {"presence": "required"}, "rules": [{"name": "integer"}, {"name": "min", "args": {"limit": 0}}, {"name": "max", "args": {"limit": 2}}]}),
+            vec![
+                json!(null),
+                json!(0),
+                json!(2),
+                json!(3),
+                json!(-1),
+                json!(1.5),
+                json!(true),
+            ],
+        ),
+        (
+            json!({"type": "number", "rules": [{"name": "greater", "args": {"limit": 0.5}}, {"name": "less", "args": {"limit": 10}}]}),
+            vec![json!(0.5), json!(1), json!(10), json!("nope")],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "min", "args": {"limit": 2}}, {"name": "max", "args": {"limit": 4}}]}),
+            vec![json!(""), json!("a"), json!("ab"), json!("abcde"), json!(5)],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "length", "args": {"limit": 3}}]}),
+            vec![json!("abc"), json!("ab"), json!("🏴‍☠️")],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "pattern", "args": {"regex": "/^[a-z]+\\d?$/i"}}]}),
+            vec![json!("Arr"), json!("arr1"), json!("arr 1"), json!("ärr")],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "pattern", "args": {"regex": "/^\\w+$/", "options": {"name": "word"}}}]}),
+            vec![json!("parrot"), json!("par rot")],
+        ),
+        (
+            json!({"type": "string", "flags": {"only": true}, "allow": ["sloop", "brig"]}),
+            vec![json!("sloop"), json!("galleon"), json!(1)],
+        ),
+        (
+            json!({"type": "string", "flags": {"only": true}, "allow": ["sloop"]}),
+            vec![json!("galleon")],
+        ),
+        (
+            json!({"type": "string", "allow": [""]}),
+            vec![json!(""), json!("x")],
+        ),
+        (
+            json!({"type": "boolean", "flags": {"presence": "required", "label": "flag"}}),
+            vec![json!(true), json!(null), json!(1)],
+        ),
+        (
+            json!({"type": "array", "rules": [{"name": "min", "args": {"limit": 1}}, {"name": "max", "args": {"limit": 2}}]}),
+            vec![json!([]), json!([1]), json!([1, 2, 3]), json!({})],
+        ),
+        (
+            json!({"type": "array", "rules": [{"name": "length", "args": {"limit": 2}}]}),
+            vec![json!([1])],
+        ),
+        (
+            json!({"type": "object", "rules": [{"name": "min", "args": {"limit": 1}}, {"name": "max", "args": {"limit": 2}}]}),
+            vec![
+                json!({}),
+                json!({"a": 1}),
+                json!({"a": 1, "b": 2, "c": 3}),
+                json!([]),
+            ],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "email"}]}),
+            vec![
+                json!("captain@example.com"),
+                json!("captain"),
+                json!("captain@example"),
+                json!("cap..tain@example.com"),
+            ],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "uri"}]}),
+            vec![
+                json!("https://example.com/map?x=1#treasure"),
+                json!("example.com"),
+                json!("http://exa mple.com"),
+            ],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "ip", "args": {"options": {"cidr": "optional"}}}]}),
+            vec![
+                json!("10.0.0.1"),
+                json!("10.0.0.0/8"),
+                json!("::1"),
+                json!("10.0.0.0/33"),
+                json!("galleon"),
+            ],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "ip", "args": {"options": {"cidr": "required"}}}]}),
+            vec![json!("10.0.0.1"), json!("10.0.0.0/8")],
+        ),
+        (
+            json!({"type": "string", "rules": [{"name": "hostname"}]}),
+            vec![
+                json!("ship-1.example.com"),
+                json!("-ship"),
+                json!("sh ip"),
+                json!("10.0.0.1"),
+            ],
+        ),
+    ];
+
+    let mut natively_evaluated = 0;
+    for (format, values) in cases {
+        let format = format.to_string();
+        for value in values {
+            let native = joi::evaluate(&format, Some(&value));
+            let expected = match native {
+                JoiOutcome::Valid => None,
+                JoiOutcome::Invalid(message) => Some(message),
+                JoiOutcome::Unsupported => continue,
+            };
+            natively_evaluated += 1;
+
+            let result =
+                FuncRunner::run_validation_format(ctx, av_id, Some(value.clone()), format.clone())
+                    .await
+                    .expect("run validation format")
+                    .await
+                    .expect("func run channel")
+                    .expect("validation func run");
+            let veritech = result
+                .value()
+                .and_then(|value| value["error"].as_str())
+                .map(ToOwned::to_owned);
+
+            assert_eq!(
+                veritech, expected,
+                "native validation disagrees with veritech for {value} against {format}"
+            );
+        }
+    }
+
+    assert!(natively_evaluated > 40);
+}
@@ -4,6 +4,7 @@ use audit_database::AuditDatabaseConfig;
 use buck2_resources::Buck2Resources;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
+use si_crypto::{SymmetricCryptoServiceConfig, SymmetricCryptoServiceConfigFile};
 use si_data_nats::NatsConfig;
 use si_std::CanonicalFileError;
 use telemetry::prelude::*;
@@ -15,6 +16,7 @@ pub(crate) use si_settings::StandardConfig;
 pub use si_settings::StandardConfigFile;
 
 const DEFAULT_CONCURRENCY_LIMIT: usize = 1000;
+const DEVELOPMENT_WEB_URL: &str = "http://localhost:8080";
 
 #[allow(missing_docs)]
 #[remain::sorted]
@@ -56,8 +58,17 @@ pub struct Config {
     #[builder(default = "default_enable_audit_logs_app()")]
     enable_audit_logs_app: bool,
 
+    #[builder(default = "default_enable_webhooks_app()")]
+    enable_webhooks_app: bool,
+
+    #[builder(default)]
+    web_url: Option<String>,
+
     #[builder(default)]
     audit: AuditDatabaseConfig,
+
+    #[builder(default = "SymmetricCryptoServiceConfig::default()")]
+    symmetric_crypto_service: SymmetricCryptoServiceConfig,
 }
 
 impl StandardConfig for Config {
@@ -96,10 +107,27 @@ impl Config {
         self.enable_audit_logs_app
     }
 
+    /// Indicates whether or not the webhooks app will be enabled.
+    pub fn enable_webhooks_app(&self) -> bool {
+        self.enable_webhooks_app
+    }
+
+    /// Gets a reference to the base URL of the web app, which the webhooks app links change sets
+    /// to in the messages it posts to Slack.
+    pub fn web_url(&self) -> Option<&str> {
+        self.web_url.as_deref()
+    }
+
     /// Gets a reference to the audit database config.
     pub fn audit(&self) -> &AuditDatabaseConfig {
         &self.audit
     }
+
+    /// Gets a reference to the symmetric crypto service config, which the webhooks app uses to
+    /// decrypt the secrets that deliveries are signed with.
+    pub fn symmetric_crypto_service(&self) -> &SymmetricCryptoServiceConfig {
+        &self.symmetric_crypto_service
+    }
 }
 
 #[allow(missing_docs)]
@@ -115,8 +143,14 @@ pub struct ConfigFile {
     pub data_warehouse_stream_name: Option<String>,
     #[serde(default = "default_enable_audit_logs_app")]
     pub enable_audit_logs_app: bool,
+    #[serde(default = "default_enable_webhooks_app")]
+    pub enable_webhooks_app: bool,
+    #[serde(default)]
+    pub web_url: Option<String>,
     #[serde(default)]
     pub audit: AuditDatabaseConfig,
+    #[serde(default = "default_symmetric_crypto_config")]
+    pub symmetric_crypto_service: SymmetricCryptoServiceConfigFile,
 }
 
 impl Default for ConfigFile {
@@ -127,7 +161,10 @@ impl Default for ConfigFile {
             nats: Default::default(),
             data_warehouse_stream_name: default_data_warehouse_stream_name(),
             enable_audit_logs_app: default_enable_audit_logs_app(),
+            enable_webhooks_app: default_enable_webhooks_app(),
+            web_url: None,
             audit: Default::default(),
+            symmetric_crypto_service: default_symmetric_crypto_config(),
         }
     }
 }
@@ -148,7 +185,10 @@ impl TryFrom<ConfigFile> for Config {
             nats: value.nats,
             data_warehouse_stream_name: value.data_warehouse_stream_name,
             enable_audit_logs_app: value.enable_audit_logs_app,
+            enable_webhooks_app: value.enable_webhooks_app,
+            web_url: value.web_url,
             audit: value.audit,
+            symmetric_crypto_service: value.symmetric_crypto_service.try_into()?,
         })
     }
 }
@@ -169,6 +209,18 @@ fn default_enable_audit_logs_app() -> bool {
     false
 }
 
+fn default_enable_webhooks_app() -> bool {
+    false
+}
+
+fn default_symmetric_crypto_config() -> SymmetricCryptoServiceConfigFile {
+    SymmetricCryptoServiceConfigFile {
+        active_key: None,
+        active_key_base64: None,
+        extra_keys: vec![],
+    }
+}
+
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
 fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
@@ -188,15 +240,28 @@ fn buck2_development(config: &mut ConfigFile) -> Result<()> {
         .map_err(ConfigError::development)?
         .to_string_lossy()
         .to_string();
+    let symmetric_crypto_service_key = resources
+        .get_ends_with("dev.donkey.key")
+        .map_err(ConfigError::development)?
+        .to_string_lossy()
+        .to_string();
 
     warn!(
         postgres_cert = postgres_cert.as_str(),
+        symmetric_crypto_service_key = symmetric_crypto_service_key.as_str(),
         "detected development run",
     );
 
     config.audit.pg.certificate_path = Some(postgres_cert.clone().try_into()?);
     config.audit.pg.dbname = audit_database::DBNAME.to_string();
     config.enable_audit_logs_app = true;
+    config.enable_webhooks_app = true;
+    config.web_url = Some(DEVELOPMENT_WEB_URL.to_owned());
+    config.symmetric_crypto_service = SymmetricCryptoServiceConfigFile {
+        active_key: Some(symmetric_crypto_service_key),
+        active_key_base64: None,
+        extra_keys: vec![],
+    };
 
     Ok(())
 }
@@ -206,15 +271,27 @@ fn cargo_development(dir: String, config: &mut ConfigFile) -> Result<()> {
         .join("../../config/keys/dev.postgres.root.crt")
         .to_string_lossy()
         .to_string();
+    let symmetric_crypto_service_key = Path::new(&dir)
+        .join("../../lib/dal/dev.donkey.key")
+        .to_string_lossy()
+        .to_string();
 
     warn!(
         postgres_cert = postgres_cert.as_str(),
+        symmetric_crypto_service_key = symmetric_crypto_service_key.as_str(),
         "detected development run",
     );
 
     config.audit.pg.certificate_path = Some(postgres_cert.clone().try_into()?);
     config.audit.pg.dbname = audit_database::DBNAME.to_string();
     config.enable_audit_logs_app = true;
+    config.enable_webhooks_app = true;
+    config.web_url = Some(DEVELOPMENT_WEB_URL.to_owned());
+    config.symmetric_crypto_service = SymmetricCryptoServiceConfigFile {
+        active_key: Some(symmetric_crypto_service_key),
+        active_key_base64: None,
+        extra_keys: vec![],
+    };
 
     Ok(())
 }
@@ -1,6 +1,7 @@
 use std::{fmt, future::Future, io, sync::Arc};
 
 use audit_database::{AuditDatabaseContext, AuditDatabaseContextError};
+use si_crypto::{SymmetricCryptoError, SymmetricCryptoService};
 use si_data_nats::{jetstream, ConnectionMetadata, NatsClient};
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -14,6 +15,7 @@ mod app;
 pub(crate) use app::AppSetupError;
 
 const DURABLE_CONSUMER_NAME: &str = "forklift-server";
+const WEBHOOKS_DURABLE_CONSUMER_NAME: &str = "forklift-server-webhooks";
 
 #[derive(Debug, Error)]
 pub enum ServerError {
@@ -27,6 +29,8 @@ pub enum ServerError {
     Naxum(#[source] io::Error),
     #[error("si data nats error: {0}")]
     SiDataNats(#[from] si_data_nats::Error),
+    #[error("symmetric crypto error: {0}")]
+    SymmetricCrypto(#[from] SymmetricCryptoError),
 }
 
 type Result<T> = std::result::Result<T, ServerError>;
@@ -61,6 +65,7 @@ pub struct Server {
     // TODO(nick): remove option once this is working.
     inner_audit_logs: Option<Box<dyn Future<Output = io::Result<()>> + Unpin + Send>>,
     inner_billing_events: Box<dyn Future<Output = io::Result<()>> + Unpin + Send>,
+    inner_webhooks: Option<Box<dyn Future<Output = io::Result<()>> + Unpin + Send>>,
 }
 
 impl fmt::Debug for Server {
@@ -87,6 +92,18 @@ impl Server {
         } else {
             None
         };
+        let webhooks_bag = if config.enable_webhooks_app() {
+            let audit_database_context = AuditDatabaseContext::from_config(config.audit()).await?;
+            let symmetric_crypto_service =
+                SymmetricCryptoService::from_config(config.symmetric_crypto_service()).await?;
+            Some((
+                audit_database_context,
+                symmetric_crypto_service,
+                config.web_url().map(ToOwned::to_owned),
+            ))
+        } else {
+            None
+        };
 
         Self::from_services(
             connection_metadata,
@@ -94,6 +111,7 @@ impl Server {
             config.instance_id(),
             config.concurrency_limit(),
             audit_bag,
+            webhooks_bag,
             config.data_warehouse_stream_name(),
             token,
         )
@@ -108,6 +126,7 @@ impl Server {
         instance_id: &str,
         concurrency_limit: usize,
         audit_bag: Option<(AuditDatabaseContext, usize)>,
+        webhooks_bag: Option<(AuditDatabaseContext, SymmetricCryptoService, Option<String>)>,
         data_warehouse_stream_name: Option<&str>,
         token: CancellationToken,
     ) -> Result<Self> {
@@ -132,6 +151,25 @@ impl Server {
             } else {
                 None
             };
+        let inner_webhooks =
+            if let Some((audit_database_context, symmetric_crypto_service, web_url)) = webhooks_bag
+            {
+                Some(
+                    app::webhooks(
+                        jetstream_context.clone(),
+                        WEBHOOKS_DURABLE_CONSUMER_NAME.to_string(),
+                        connection_metadata.clone(),
+                        audit_database_context,
+                        symmetric_crypto_service,
+                        web_url,
+                        concurrency_limit,
+                        token.clone(),
+                    )
+                    .await?,
+                )
+            } else {
+                None
+            };
         let inner_billing_events = app::billing_events(
             jetstream_context,
             DURABLE_CONSUMER_NAME.to_string(),
@@ -146,6 +184,7 @@ impl Server {
             metadata,
             inner_audit_logs,
             inner_billing_events,
+            inner_webhooks,
             shutdown_token: token,
         })
     }
@@ -160,23 +199,25 @@ impl Server {
 
     /// Fallibly awaits the inner naxum task(s).
     pub async fn try_run(self) -> Result<()> {
-        match self.inner_audit_logs {
-            Some(inner_audit_logs) => {
-                info!("running two apps: audit logs and billing events");
-                let (inner_audit_logs_result, inner_billing_events_result) = futures::join!(
-                    tokio::spawn(inner_audit_logs),
-                    tokio::spawn(self.inner_billing_events)
-                );
-                inner_audit_logs_result?.map_err(ServerError::Naxum)?;
-                inner_billing_events_result?.map_err(ServerError::Naxum)?;
-            }
-            None => {
-                info!("running one app: billing events");
-                self.inner_billing_events
-                    .await
-                    .map_err(ServerError::Naxum)?;
-            }
+        let mut apps = vec![("billing events", self.inner_billing_events)];
+        if let Some(inner_audit_logs) = self.inner_audit_logs {
+            apps.push(("audit logs", inner_audit_logs));
         }
+        if let Some(inner_webhooks) = self.inner_webhooks {
+            apps.push(("webhooks", inner_webhooks));
+        }
+
+        info!(
+            apps = ?apps.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            "running {} app(s)",
+            apps.len(),
+        );
+        let results =
+            futures::future::join_all(apps.into_iter().map(|(_, inner)| tokio::spawn(inner))).await;
+        for result in results {
+            result?.map_err(ServerError::Naxum)?;
+        }
+
         info!("forklift main loop shutdown complete");
         Ok(())
     }
@@ -1,6 +1,7 @@
 use std::{future::Future, io, sync::Arc};
 
 use audit_database::AuditDatabaseContext;
+use si_crypto::SymmetricCryptoService;
 use si_data_nats::{jetstream::Context, ConnectionMetadata};
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -8,9 +9,11 @@ use tokio_util::sync::CancellationToken;
 
 mod audit_logs;
 mod billing_events;
+mod webhooks;
 
 pub(crate) use audit_logs::AuditLogsAppSetupError;
 pub(crate) use billing_events::BillingEventsAppSetupError;
+pub(crate) use webhooks::WebhooksAppSetupError;
 
 #[derive(Debug, Error)]
 pub enum AppSetupError {
@@ -18,6 +21,8 @@ pub enum AppSetupError {
     AuditLogsAppSetup(#[from] AuditLogsAppSetupError),
     #[error("billing events app setup: {0}")]
     BillingEventsAppSetup(#[from] BillingEventsAppSetupError),
+    #[error("webhooks app setup: {0}")]
+    WebhooksAppSetup(#[from] WebhooksAppSetupError),
 }
 
 type Result<T> = std::result::Result<T, AppSetupError>;
@@ -71,3 +76,33 @@ pub(crate) async fn billing_events(
     )
     .await?)
 }
+
+#[instrument(
+    name = "forklift.init.app.webhooks",
+    level = "info",
+    skip_all,
+    fields(durable_consumer_name)
+)]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn webhooks(
+    jetstream_context: Context,
+    durable_consumer_name: String,
+    connection_metadata: Arc<ConnectionMetadata>,
+    audit_database_context: AuditDatabaseContext,
+    symmetric_crypto_service: SymmetricCryptoService,
+    web_url: Option<String>,
+    concurrency_limit: usize,
+    token: CancellationToken,
+) -> Result<Box<dyn Future<Output = io::Result<()>> + Unpin + Send>> {
+    Ok(webhooks::build_and_run(
+        jetstream_context,
+        durable_consumer_name,
+        connection_metadata,
+        audit_database_context,
+        symmetric_crypto_service,
+        web_url,
+        concurrency_limit,
+        token,
+    )
+    .await?)
+}
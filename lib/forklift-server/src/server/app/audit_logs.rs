@@ -36,6 +36,8 @@ use tokio_util::sync::CancellationToken;
 mod app_state;
 mod handlers;
 
+pub(crate) use handlers::{find_workspace_id, HandlerError as AuditLogsHandlerError};
+
 #[derive(Debug, Error)]
 pub enum AuditLogsAppSetupError {
     #[error("async nats consumer error: {0}")]
@@ -121,12 +123,12 @@ pub(crate) async fn build_and_run(
 }
 
 #[derive(Clone, Debug)]
-struct ForkliftAuditLogsForSubject {
+pub(crate) struct ForkliftAuditLogsForSubject {
     prefix: Option<()>,
 }
 
 impl ForkliftAuditLogsForSubject {
-    fn with_prefix(prefix: Option<&str>) -> Self {
+    pub(crate) fn with_prefix(prefix: Option<&str>) -> Self {
         Self {
             prefix: prefix.map(|_p| ()),
         }
@@ -63,7 +63,7 @@ pub(crate) async fn default(
 
 // NOTE(nick,fletcher): we may be able to remove this if we store the workspace id on the audit log object itself, and
 // we have a plan for old messages.
-fn find_workspace_id(subject: Subject, using_prefix: bool) -> Result<WorkspacePk> {
+pub(crate) fn find_workspace_id(subject: Subject, using_prefix: bool) -> Result<WorkspacePk> {
     let mut parts = subject.split('.');
     if using_prefix {
         if let (Some(_prefix), Some(_p1), Some(_p2), Some(workspace_id)) =
@@ -0,0 +1,141 @@
+use std::{
+    future::{Future, IntoFuture as _},
+    io,
+    sync::Arc,
+    time::Duration,
+};
+
+use app_state::AppState;
+use audit_database::AuditDatabaseContext;
+use audit_logs_stream::{AuditLogsStream, AuditLogsStreamError};
+use naxum::{
+    handler::Handler as _,
+    middleware::{ack::AckLayer, matched_subject::MatchedSubjectLayer, trace::TraceLayer},
+    response::{IntoResponse, Response},
+    ServiceBuilder, ServiceExt as _, TowerServiceExt as _,
+};
+use si_crypto::SymmetricCryptoService;
+use si_data_nats::{
+    async_nats::{
+        self,
+        error::Error as AsyncNatsError,
+        jetstream::{consumer::StreamErrorKind, stream::ConsumerErrorKind},
+    },
+    jetstream::Context,
+    ConnectionMetadata,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+use super::audit_logs::ForkliftAuditLogsForSubject;
+
+mod app_state;
+mod delivery;
+mod handlers;
+mod sweeper;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum WebhooksAppSetupError {
+    #[error("async nats consumer error: {0}")]
+    AsyncNatsConsumer(#[from] AsyncNatsError<ConsumerErrorKind>),
+    #[error("async nats stream error: {0}")]
+    AsyncNatsStream(#[from] AsyncNatsError<StreamErrorKind>),
+    #[error("audit logs stream error: {0}")]
+    AuditLogsStream(#[from] AuditLogsStreamError),
+    #[error("reqwest error: {0}")]
+    Reqwest(#[from] reqwest::Error),
+}
+
+type Result<T> = std::result::Result<T, WebhooksAppSetupError>;
+
+/// Builds a naxum app for delivering outbound webhooks. This app consumes the audit logs stream with its own durable
+/// consumer (alongside the audit logs app) and delivers the audit logs that correspond to a
+/// [`WebhookEventKind`](si_events::WebhookEventKind) to every subscribed endpoint in the workspace.
+///
+/// Alongside the app, a sweeper periodically retries the deliveries whose previous attempt failed.
+#[instrument(
+    name = "forklift.init.app.webhooks.build_and_run",
+    level = "debug",
+    skip_all
+)]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn build_and_run(
+    jetstream_context: Context,
+    durable_consumer_name: String,
+    connection_metadata: Arc<ConnectionMetadata>,
+    audit_database_context: AuditDatabaseContext,
+    symmetric_crypto_service: SymmetricCryptoService,
+    web_url: Option<String>,
+    concurrency_limit: usize,
+    token: CancellationToken,
+) -> Result<Box<dyn Future<Output = io::Result<()>> + Unpin + Send>> {
+    let incoming = {
+        let stream = AuditLogsStream::get_or_create(jetstream_context).await?;
+        let consumer_subject = stream.consuming_subject_for_all_workspaces();
+        stream
+            .stream()
+            .await?
+            .create_consumer(async_nats::jetstream::consumer::pull::Config {
+                durable_name: Some(durable_consumer_name),
+                filter_subject: consumer_subject.into_string(),
+                // Only deliver events published after the consumer is first created, otherwise every existing audit
+                // log would be delivered to endpoints the first time the app is enabled.
+                deliver_policy: async_nats::jetstream::consumer::DeliverPolicy::New,
+                max_deliver: 4,
+                backoff: vec![
+                    Duration::from_secs(5),
+                    Duration::from_secs(10),
+                    Duration::from_secs(15),
+                ],
+                ..Default::default()
+            })
+            .await?
+            .messages()
+            .await?
+    };
+
+    // Redirects aren't followed and hosts only resolve to addresses we're willing to deliver to, so
+    // that endpoints can't be pointed at our own network.
+    let http_client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .dns_resolver(Arc::new(delivery::DeliverableAddressResolver))
+        .build()?;
+
+    let state = AppState::new(
+        audit_database_context,
+        symmetric_crypto_service,
+        http_client,
+        web_url,
+        connection_metadata.subject_prefix().is_some(),
+        token.clone(),
+    );
+
+    // NOTE(nick,fletcher): the "NatsMakeSpan" builder defaults to "info" level logging. Bump it down, if needed.
+    let app = ServiceBuilder::new()
+        .layer(
+            MatchedSubjectLayer::new().for_subject(ForkliftAuditLogsForSubject::with_prefix(
+                connection_metadata.subject_prefix(),
+            )),
+        )
+        .layer(
+            TraceLayer::new()
+                .make_span_with(telemetry_nats::NatsMakeSpan::builder(connection_metadata).build())
+                .on_response(telemetry_nats::NatsOnResponse::new()),
+        )
+        .layer(AckLayer::new())
+        .service(handlers::default.with_state(state.clone()))
+        .map_response(Response::into_response);
+
+    let inner =
+        naxum::serve_with_incoming_limit(incoming, app.into_make_service(), concurrency_limit)
+            .with_graceful_shutdown(naxum::wait_on_cancelled(token));
+
+    Ok(Box::new(Box::pin(async move {
+        let (result, ()) = futures::join!(inner.into_future(), sweeper::run(state));
+        result
+    })))
+}
@@ -0,0 +1,57 @@
+use audit_database::AuditDatabaseContext;
+use si_crypto::SymmetricCryptoService;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone)]
+pub(crate) struct AppState {
+    context: AuditDatabaseContext,
+    symmetric_crypto_service: SymmetricCryptoService,
+    http_client: reqwest::Client,
+    web_url: Option<String>,
+    using_prefix: bool,
+    token: CancellationToken,
+}
+
+impl AppState {
+    pub(crate) fn new(
+        context: AuditDatabaseContext,
+        symmetric_crypto_service: SymmetricCryptoService,
+        http_client: reqwest::Client,
+        web_url: Option<String>,
+        using_prefix: bool,
+        token: CancellationToken,
+    ) -> Self {
+        Self {
+            context,
+            symmetric_crypto_service,
+            http_client,
+            web_url,
+            using_prefix,
+            token,
+        }
+    }
+
+    pub(crate) fn context(&self) -> &AuditDatabaseContext {
+        &self.context
+    }
+
+    pub(crate) fn symmetric_crypto_service(&self) -> &SymmetricCryptoService {
+        &self.symmetric_crypto_service
+    }
+
+    pub(crate) fn http_client(&self) -> &reqwest::Client {
+        &self.http_client
+    }
+
+    pub(crate) fn web_url(&self) -> Option<&str> {
+        self.web_url.as_deref()
+    }
+
+    pub(crate) fn using_prefix(&self) -> bool {
+        self.using_prefix
+    }
+
+    pub(crate) fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
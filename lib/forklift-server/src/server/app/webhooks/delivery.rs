@@ -0,0 +1,269 @@
+use std::{
+    error::Error,
+    net::{IpAddr, SocketAddr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use audit_database::{
+    is_deliverable_address, WebhookDeliveryRow, WebhookDeliveryStatus, WebhookEndpointRow,
+};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use ring::hmac;
+use telemetry::prelude::*;
+
+use super::app_state::AppState;
+
+/// How long a delivery is claimed for while an attempt is made. Deliveries whose attempt was never
+/// recorded (e.g. because forklift shut down mid-attempt) are picked up by the sweeper once it
+/// passes.
+pub(crate) const CLAIM_LEASE: Duration = Duration::from_secs(60);
+
+const MAX_ATTEMPTS: i32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+const HEADER_DELIVERY: &str = "x-si-webhook-delivery";
+const HEADER_EVENT: &str = "x-si-webhook-event";
+const HEADER_SIGNATURE: &str = "x-si-webhook-signature";
+const HEADER_TIMESTAMP: &str = "x-si-webhook-timestamp";
+
+/// Makes a single attempt to deliver a payload to an endpoint and records it on the delivery row.
+///
+/// Failed attempts are retried with exponential backoff by persisting when the next attempt is
+/// due, which the [sweeper](super::sweeper) acts upon, until we run out of attempts.
+#[instrument(
+    name = "forklift.webhooks.deliver",
+    level = "info",
+    skip_all,
+    fields(
+        si.workspace.id = %endpoint.workspace_id,
+        si.webhook.endpoint.id = %endpoint.id,
+        si.webhook.delivery.id = %delivery.id,
+        si.webhook.event_kind = %delivery.event_kind,
+        si.webhook.delivery.attempt = delivery.attempts + 1,
+    ),
+)]
+pub(crate) async fn deliver(
+    state: AppState,
+    endpoint: WebhookEndpointRow,
+    delivery: WebhookDeliveryRow,
+) {
+    let attempt = delivery.attempts + 1;
+    let body = delivery.payload.to_string();
+
+    let (status, response_status, error) = match signing_secret(&state, &endpoint) {
+        // Neither an undecryptable secret nor an address literal will change by retrying
+        Err(err) => (WebhookDeliveryStatus::Failed, None, Some(err.to_string())),
+        Ok(_) if !has_deliverable_host(&endpoint.url) => (
+            WebhookDeliveryStatus::Failed,
+            None,
+            Some(format!("refusing to deliver to {}", endpoint.url)),
+        ),
+        Ok(signing_secret) => {
+            let (succeeded, response_status, error) = match attempt_delivery(
+                state.http_client(),
+                &endpoint,
+                &delivery,
+                signing_secret.as_deref(),
+                &body,
+            )
+            .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    (true, Some(response.status().as_u16()), None)
+                }
+                Ok(response) => (
+                    false,
+                    Some(response.status().as_u16()),
+                    Some(format!("endpoint responded with {}", response.status())),
+                ),
+                Err(err) => (false, None, Some(err.to_string())),
+            };
+
+            let status = if succeeded {
+                WebhookDeliveryStatus::Succeeded
+            } else if attempt >= MAX_ATTEMPTS {
+                WebhookDeliveryStatus::Failed
+            } else {
+                WebhookDeliveryStatus::Pending
+            };
+            (status, response_status, error)
+        }
+    };
+
+    let retry_in = match status {
+        WebhookDeliveryStatus::Pending => Some(retry_delay(attempt)),
+        WebhookDeliveryStatus::Failed | WebhookDeliveryStatus::Succeeded => None,
+    };
+
+    if let Err(err) = WebhookDeliveryRow::record_attempt(
+        state.context(),
+        delivery.id,
+        status,
+        response_status,
+        error.clone(),
+        retry_in,
+    )
+    .await
+    {
+        error!(si.error.message = ?err, "failed to record webhook delivery attempt");
+    }
+
+    match status {
+        WebhookDeliveryStatus::Succeeded => {}
+        WebhookDeliveryStatus::Failed => {
+            warn!(
+                si.error.message = ?error,
+                attempts = attempt,
+                "giving up on webhook delivery",
+            );
+        }
+        WebhookDeliveryStatus::Pending => {
+            debug!(
+                si.error.message = ?error,
+                attempt,
+                ?retry_in,
+                "webhook delivery attempt failed, retrying",
+            );
+        }
+    }
+}
+
+fn signing_secret(
+    state: &AppState,
+    endpoint: &WebhookEndpointRow,
+) -> audit_database::Result<Option<String>> {
+    endpoint
+        .signing_secret
+        .as_ref()
+        .map(|signing_secret| signing_secret.decrypt(state.symmetric_crypto_service()))
+        .transpose()
+}
+
+async fn attempt_delivery(
+    client: &reqwest::Client,
+    endpoint: &WebhookEndpointRow,
+    delivery: &WebhookDeliveryRow,
+    signing_secret: Option<&str>,
+    body: &str,
+) -> reqwest::Result<reqwest::Response> {
+    let mut request = client
+        .post(&endpoint.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(HEADER_DELIVERY, delivery.id.to_string())
+        .header(HEADER_EVENT, delivery.event_kind.as_ref());
+
+    if let Some(signing_secret) = signing_secret {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            .to_string();
+        request = request
+            .header(HEADER_SIGNATURE, sign(signing_secret, &timestamp, body))
+            .header(HEADER_TIMESTAMP, timestamp);
+    }
+
+    request.body(body.to_owned()).send().await
+}
+
+/// Resolves hosts to only the addresses webhooks may be delivered to, which guards against hosts
+/// that started resolving to one of our own addresses after their endpoint was saved.
+pub(crate) struct DeliverableAddressResolver;
+
+impl Resolve for DeliverableAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(resolve_deliverable(name))
+    }
+}
+
+async fn resolve_deliverable(name: Name) -> Result<Addrs, Box<dyn Error + Send + Sync>> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+        .await?
+        .filter(|addr| is_deliverable_address(addr.ip()))
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("{} has no deliverable addresses", name.as_str()).into());
+    }
+    Ok(Box::new(addrs.into_iter()))
+}
+
+/// Checks the host of a URL whose host is an address literal, which is connected to without being
+/// resolved. Hosts that are names are checked by [`DeliverableAddressResolver`].
+fn has_deliverable_host(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    match url
+        .host_str()
+        .map(|host| host.trim_matches(['[', ']']).parse::<IpAddr>())
+    {
+        Some(Ok(ip)) => is_deliverable_address(ip),
+        Some(Err(_)) => true,
+        None => false,
+    }
+}
+
+/// Returns how long to wait after a failed attempt before making the next one, doubling with each
+/// attempt made.
+fn retry_delay(attempts_made: i32) -> Duration {
+    let doublings = attempts_made.saturating_sub(1).clamp(0, MAX_ATTEMPTS) as u32;
+    INITIAL_BACKOFF * 2u32.pow(doublings)
+}
+
+/// Computes the signature sent with a delivery, which is the hex-encoded HMAC-SHA256 of `{timestamp}.{body}` keyed with
+/// the endpoint's signing secret. Including the timestamp allows receivers to reject replayed deliveries.
+fn sign(signing_secret: &str, timestamp: &str, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, signing_secret.as_bytes());
+    let mut context = hmac::Context::with_key(&key);
+    context.update(timestamp.as_bytes());
+    context.update(b".");
+    context.update(body.as_bytes());
+    format!("sha256={}", hex::encode(context.sign().as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_matches_known_signature() {
+        // Computed independently with:
+        //   printf '1700000000.{"hello":"world"}' | openssl dgst -sha256 -hmac whsec_test
+        assert_eq!(
+            "sha256=f592bbf3951cfc94e560eecfb5d9dd4da6b0fff2e626235f8ab4b54860925d0b",
+            sign("whsec_test", "1700000000", r#"{"hello":"world"}"#)
+        );
+    }
+
+    #[test]
+    fn sign_covers_secret_timestamp_and_body() {
+        let signature = sign("whsec_test", "1700000000", "{}");
+
+        assert_eq!(signature, sign("whsec_test", "1700000000", "{}"));
+        assert_ne!(signature, sign("whsec_other", "1700000000", "{}"));
+        assert_ne!(signature, sign("whsec_test", "1700000001", "{}"));
+        assert_ne!(signature, sign("whsec_test", "1700000000", "[]"));
+    }
+
+    #[test]
+    fn refuses_internal_address_literals() {
+        assert!(has_deliverable_host("https://hooks.example.com/events"));
+        assert!(has_deliverable_host("https://93.184.215.14/events"));
+        assert!(!has_deliverable_host("http://127.0.0.1:8080/events"));
+        assert!(!has_deliverable_host("http://10.1.2.3/events"));
+        assert!(!has_deliverable_host(
+            "http://169.254.169.254/latest/meta-data"
+        ));
+        assert!(!has_deliverable_host("http://[::1]/events"));
+        assert!(!has_deliverable_host("http://[::ffff:192.168.0.1]/events"));
+        assert!(!has_deliverable_host("http://[fd00::1]/events"));
+    }
+
+    #[test]
+    fn retry_delay_doubles() {
+        assert_eq!(Duration::from_secs(2), retry_delay(1));
+        assert_eq!(Duration::from_secs(4), retry_delay(2));
+        assert_eq!(Duration::from_secs(8), retry_delay(3));
+        assert_eq!(Duration::from_secs(16), retry_delay(4));
+    }
+}
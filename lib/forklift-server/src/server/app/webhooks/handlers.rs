@@ -0,0 +1,238 @@
+use audit_database::{AuditDatabaseError, WebhookDeliveryRow, WebhookEndpointRow};
+use naxum::{
+    extract::State,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use si_data_nats::Subject;
+use si_events::{
+    audit_log::{AuditLog, AuditLogMetadata},
+    Actor, ChangeSetId, WebhookEventKind, WebhookPayloadFormat, WorkspacePk,
+};
+use telemetry::prelude::*;
+use thiserror::Error;
+
+use super::{app_state::AppState, delivery};
+use crate::server::app::audit_logs::{find_workspace_id, AuditLogsHandlerError};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub(crate) enum HandlerError {
+    #[error("audit database error: {0}")]
+    AuditDatabase(#[from] AuditDatabaseError),
+    #[error("audit logs handler error: {0}")]
+    AuditLogsHandler(#[from] AuditLogsHandlerError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+type Result<T> = std::result::Result<T, HandlerError>;
+
+impl IntoResponse for HandlerError {
+    fn into_response(self) -> Response {
+        error!(si.error.message = ?self, "failed to process message");
+        Response::default_internal_server_error()
+    }
+}
+
+/// The body delivered to endpoints using the [`Json`](WebhookPayloadFormat::Json) payload format.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonPayload<'a> {
+    event_kind: WebhookEventKind,
+    workspace_id: WorkspacePk,
+    change_set_id: Option<ChangeSetId>,
+    actor: Actor,
+    timestamp: &'a str,
+    title: &'a str,
+    entity_type: Option<&'a str>,
+    entity_name: &'a str,
+    metadata: &'a AuditLogMetadata,
+}
+
+/// The body delivered to endpoints using the [`Slack`](WebhookPayloadFormat::Slack) payload format.
+#[derive(Debug, Serialize)]
+struct SlackPayload {
+    text: String,
+}
+
+pub(crate) async fn default(
+    State(state): State<AppState>,
+    subject: Subject,
+    Json(audit_log): Json<AuditLog>,
+) -> Result<()> {
+    let workspace_id = find_workspace_id(subject, state.using_prefix())?;
+
+    let AuditLog::V1(inner) = audit_log;
+    let event_kind = match WebhookEventKind::for_audit_log_kind(&inner.kind) {
+        Some(event_kind) => event_kind,
+        None => return Ok(()),
+    };
+
+    let endpoints =
+        WebhookEndpointRow::list_for_event_kind(state.context(), workspace_id, event_kind).await?;
+    if endpoints.is_empty() {
+        return Ok(());
+    }
+
+    let metadata = AuditLogMetadata::from(inner.kind);
+    let (title, entity_type) = metadata.title_and_entity_type();
+    let json_payload = serde_json::to_value(JsonPayload {
+        event_kind,
+        workspace_id,
+        change_set_id: inner.change_set_id,
+        actor: inner.actor,
+        timestamp: &inner.timestamp,
+        title,
+        entity_type,
+        entity_name: &inner.entity_name,
+        metadata: &metadata,
+    })?;
+    let change_set_url =
+        state
+            .web_url()
+            .zip(inner.change_set_id)
+            .map(|(web_url, change_set_id)| {
+                format!(
+                    "{}/w/{workspace_id}/{change_set_id}",
+                    web_url.trim_end_matches('/')
+                )
+            });
+    let slack_payload = serde_json::to_value(SlackPayload {
+        text: slack_text(
+            event_kind,
+            &inner.entity_name,
+            inner.actor_email.as_deref(),
+            change_set_url.as_deref(),
+        ),
+    })?;
+
+    for endpoint in endpoints {
+        let payload = match endpoint.payload_format {
+            WebhookPayloadFormat::Json => json_payload.clone(),
+            WebhookPayloadFormat::Slack => slack_payload.clone(),
+        };
+        let delivery = WebhookDeliveryRow::insert(
+            state.context(),
+            &endpoint,
+            event_kind,
+            payload,
+            delivery::CLAIM_LEASE,
+        )
+        .await?;
+
+        // The first attempt runs in the background to avoid holding up the stream. Retries are left to the sweeper.
+        tokio::spawn(delivery::deliver(state.clone(), endpoint, delivery));
+    }
+
+    Ok(())
+}
+
+/// Builds the text of a Slack message, naming the actor and linking to the change set when we
+/// know them.
+fn slack_text(
+    event_kind: WebhookEventKind,
+    entity_name: &str,
+    actor_email: Option<&str>,
+    change_set_url: Option<&str>,
+) -> String {
+    let actor = actor_email.unwrap_or("Someone");
+    let text = match event_kind {
+        WebhookEventKind::ActionFailed => format!("Action {entity_name} failed"),
+        WebhookEventKind::ChangeSetApplied => {
+            format!("{actor} applied change set {entity_name} to HEAD")
+        }
+        WebhookEventKind::ChangeSetApprovalRequested => {
+            format!("{actor} requested an approval of change set {entity_name}")
+        }
+        WebhookEventKind::ChangeSetApprovalWithdrawn => {
+            format!("{actor} withdrew approval request of change set {entity_name}")
+        }
+        WebhookEventKind::ChangeSetApproved => {
+            format!("{actor} approved merge of change set {entity_name}")
+        }
+        WebhookEventKind::ChangeSetRejected => {
+            format!("{actor} rejected merge of change set {entity_name}")
+        }
+        WebhookEventKind::QualificationFailed => {
+            format!("A qualification failed for component {entity_name}")
+        }
+        WebhookEventKind::ResourceDrifted => {
+            format!(
+                "The resource for component {entity_name} has drifted from its last known state"
+            )
+        }
+    };
+
+    match change_set_url {
+        Some(change_set_url) => format!("{text}: {change_set_url}"),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slack_text_names_the_entity() {
+        let actor = Some("nick@systeminit.com");
+        let cases = [
+            (WebhookEventKind::ActionFailed, "Action Create VPC failed"),
+            (
+                WebhookEventKind::ChangeSetApplied,
+                "nick@systeminit.com applied change set Create VPC to HEAD",
+            ),
+            (
+                WebhookEventKind::ChangeSetApprovalRequested,
+                "nick@systeminit.com requested an approval of change set Create VPC",
+            ),
+            (
+                WebhookEventKind::ChangeSetApprovalWithdrawn,
+                "nick@systeminit.com withdrew approval request of change set Create VPC",
+            ),
+            (
+                WebhookEventKind::ChangeSetApproved,
+                "nick@systeminit.com approved merge of change set Create VPC",
+            ),
+            (
+                WebhookEventKind::ChangeSetRejected,
+                "nick@systeminit.com rejected merge of change set Create VPC",
+            ),
+            (
+                WebhookEventKind::QualificationFailed,
+                "A qualification failed for component Create VPC",
+            ),
+            (
+                WebhookEventKind::ResourceDrifted,
+                "The resource for component Create VPC has drifted from its last known state",
+            ),
+        ];
+
+        for (event_kind, expected) in cases {
+            assert_eq!(
+                expected,
+                slack_text(event_kind, "Create VPC", actor, None),
+                "{event_kind}"
+            );
+        }
+    }
+
+    #[test]
+    fn slack_text_links_to_the_change_set() {
+        assert_eq!(
+            "nick@systeminit.com applied change set Create VPC to HEAD: https://app.systeminit.com/w/01J/01K",
+            slack_text(
+                WebhookEventKind::ChangeSetApplied,
+                "Create VPC",
+                Some("nick@systeminit.com"),
+                Some("https://app.systeminit.com/w/01J/01K"),
+            )
+        );
+        assert_eq!(
+            "Someone applied change set Create VPC to HEAD",
+            slack_text(WebhookEventKind::ChangeSetApplied, "Create VPC", None, None)
+        );
+    }
+}
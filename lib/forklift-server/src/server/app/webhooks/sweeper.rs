@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use audit_database::{AuditDatabaseError, WebhookDeliveryRow, WebhookEndpointRow};
+use telemetry::prelude::*;
+
+use super::{app_state::AppState, delivery};
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+const SWEEP_BATCH_SIZE: usize = 100;
+
+/// Periodically attempts the pending deliveries whose next attempt is due until shutdown. This covers both retries of
+/// failed attempts and deliveries whose attempt was interrupted, such as by a restart.
+pub(crate) async fn run(state: AppState) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(SWEEP_INTERVAL) => {}
+            _ = state.token().cancelled() => {
+                debug!("shutting down webhook delivery sweeper");
+                return;
+            }
+        }
+
+        if let Err(err) = sweep(&state).await {
+            error!(si.error.message = ?err, "failed to sweep webhook deliveries");
+        }
+    }
+}
+
+#[instrument(name = "forklift.webhooks.sweep", level = "debug", skip_all)]
+async fn sweep(state: &AppState) -> Result<(), AuditDatabaseError> {
+    let deliveries =
+        WebhookDeliveryRow::claim_due(state.context(), SWEEP_BATCH_SIZE, delivery::CLAIM_LEASE)
+            .await?;
+
+    let mut attempts = Vec::with_capacity(deliveries.len());
+    for claimed in deliveries {
+        match WebhookEndpointRow::get(state.context(), claimed.workspace_id, claimed.endpoint_id)
+            .await?
+        {
+            Some(endpoint) if endpoint.enabled => {
+                attempts.push(delivery::deliver(state.clone(), endpoint, claimed));
+            }
+            Some(_) => {
+                WebhookDeliveryRow::abandon(
+                    state.context(),
+                    claimed.id,
+                    "endpoint was disabled".to_owned(),
+                )
+                .await?;
+            }
+            None => {
+                WebhookDeliveryRow::abandon(
+                    state.context(),
+                    claimed.id,
+                    "endpoint was deleted".to_owned(),
+                )
+                .await?;
+            }
+        }
+    }
+
+    futures::future::join_all(attempts).await;
+
+    Ok(())
+}
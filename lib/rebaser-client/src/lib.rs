@@ -26,13 +26,23 @@ use telemetry::prelude::*;
 use telemetry_nats::propagation;
 use thiserror::Error;
 
-pub use rebaser_core::{api_types, api_types::RequestId};
+pub use rebaser_core::{api_types, api_types::RequestId, lease::ChangeSetLease};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ClientError {
     #[error("error creating jetstream stream: {0}")]
     CreateStream(#[source] async_nats::jetstream::context::CreateStreamError),
+    #[error("error creating change set leases bucket: {0}")]
+    LeaseBucket(#[source] async_nats::jetstream::context::CreateKeyValueError),
+    #[error("error deserializing change set lease: {0}")]
+    LeaseDeserialize(#[source] serde_json::Error),
+    #[error("error fetching change set lease: {0}")]
+    LeaseEntry(#[source] async_nats::jetstream::kv::EntryError),
+    #[error("error listing change set leases: {0}")]
+    LeaseKeys(#[source] async_nats::jetstream::kv::HistoryError),
+    #[error("error receiving change set lease key: {0}")]
+    LeaseKeysWatch(#[source] async_nats::jetstream::kv::WatcherError),
     #[error("pending events error: {0}")]
     PendingEvents(#[from] PendingEventsError),
     #[error("request publish error: {0}")]
@@ -207,6 +217,31 @@ impl Client {
         .await
     }
 
+    /// Lists the change sets leased by rebaser instances, which may include expired leases that no
+    /// instance has taken over yet.
+    #[instrument(
+        name = "rebaser_client.list_change_set_leases",
+        level = "info",
+        skip_all
+    )]
+    pub async fn list_change_set_leases(&self) -> Result<Vec<ChangeSetLease>> {
+        let store = nats::rebaser_leases_kv(&self.context)
+            .await
+            .map_err(Error::LeaseBucket)?;
+
+        let mut keys = store.keys().await.map_err(Error::LeaseKeys)?;
+        let mut leases = Vec::new();
+        while let Some(key) = keys.next().await {
+            let key = key.map_err(Error::LeaseKeysWatch)?;
+            // The lease may have expired since its key was listed
+            if let Some(value) = store.get(&key).await.map_err(Error::LeaseEntry)? {
+                leases.push(serde_json::from_slice(&value).map_err(Error::LeaseDeserialize)?);
+            }
+        }
+
+        Ok(leases)
+    }
+
     async fn call_async(
         &self,
         workspace_id: WorkspacePk,
@@ -0,0 +1,52 @@
+//! Leases that give a single rebaser instance ownership of a change set.
+//!
+//! A lease is a key in a NATS KV bucket (see [`rebaser_leases_kv`](crate::nats::rebaser_leases_kv))
+//! whose entries expire after [`LEASE_TTL`] unless the owning instance keeps heartbeating. Every
+//! acquisition is assigned a new fencing token, which only ever increases. The token is stored
+//! with the change set pointer whenever the lease holder moves it, and a pointer write with an
+//! older token is rejected, so an instance that lost its lease (for example while paused) cannot
+//! overwrite the work of the instance that took over.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use si_events::{ChangeSetId, WorkspacePk};
+
+/// How long a lease lives without a heartbeat.
+pub const LEASE_TTL: Duration = Duration::from_secs(15);
+/// How often the owner of a lease renews it.
+pub const LEASE_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The value stored for a held lease.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSetLease {
+    pub workspace_id: WorkspacePk,
+    pub change_set_id: ChangeSetId,
+    /// Id of the rebaser instance holding the lease.
+    pub instance_id: String,
+    /// Increases with every acquisition of the lease.
+    pub fencing_token: u64,
+    /// When the lease was last renewed, in milliseconds since the Unix epoch.
+    pub heartbeat_at_ms: u64,
+}
+
+impl ChangeSetLease {
+    /// Whether the lease has gone without a heartbeat for longer than [`LEASE_TTL`].
+    pub fn is_expired(&self) -> bool {
+        now_ms().saturating_sub(self.heartbeat_at_ms) > LEASE_TTL.as_millis() as u64
+    }
+}
+
+/// The key of the lease for a change set.
+pub fn lease_key(workspace_id: WorkspacePk, change_set_id: ChangeSetId) -> String {
+    format!("{workspace_id}.{change_set_id}")
+}
+
+/// The current time in milliseconds since the Unix epoch.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
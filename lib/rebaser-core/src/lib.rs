@@ -1,4 +1,5 @@
 //! This crate contains common information for the rebaser for clients, servers and interested parties.
 
 pub mod api_types;
+pub mod lease;
 pub mod nats;
@@ -1,5 +1,8 @@
 use si_data_nats::{async_nats, jetstream};
 
+use crate::lease::LEASE_TTL;
+
+const NATS_REBASER_LEASES_BUCKET_NAME: &str = "REBASER_LEASES";
 const NATS_REBASER_REQUESTS_STREAM_NAME: &str = "REBASER_REQUESTS";
 const NATS_REBASER_REQUESTS_STREAM_SUBJECTS: &[&str] = &["rebaser.requests.>"];
 const NATS_REBASER_TASKS_STREAM_NAME: &str = "REBASER_TASKS";
@@ -61,6 +64,24 @@ pub async fn rebaser_requests_jetstream_stream(
     Ok(stream)
 }
 
+pub async fn rebaser_leases_kv(
+    context: &jetstream::Context,
+) -> Result<async_nats::jetstream::kv::Store, async_nats::jetstream::context::CreateKeyValueError> {
+    let prefix = context.metadata().subject_prefix();
+
+    let store = context
+        .create_key_value(async_nats::jetstream::kv::Config {
+            bucket: nats_stream_name(prefix, NATS_REBASER_LEASES_BUCKET_NAME),
+            description: "Rebaser change set leases".to_owned(),
+            history: 1,
+            max_age: LEASE_TTL,
+            ..Default::default()
+        })
+        .await?;
+
+    Ok(store)
+}
+
 fn nats_stream_name(prefix: Option<&str>, suffix: impl AsRef<str>) -> String {
     let suffix = suffix.as_ref();
 
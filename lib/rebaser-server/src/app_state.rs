@@ -12,6 +12,7 @@ pub(crate) struct AppState {
     pub(crate) metadata: Arc<ServerMetadata>,
     pub(crate) nats: NatsClient,
     pub(crate) requests_stream: jetstream::stream::Stream,
+    pub(crate) leases: jetstream::kv::Store,
     pub(crate) ctx_builder: DalContextBuilder,
     pub(crate) quiescent_period: Duration,
     pub(crate) token: CancellationToken,
@@ -24,6 +25,7 @@ impl AppState {
         metadata: Arc<ServerMetadata>,
         nats: NatsClient,
         requests_stream: jetstream::stream::Stream,
+        leases: jetstream::kv::Store,
         ctx_builder: DalContextBuilder,
         quiescent_period: Duration,
         token: CancellationToken,
@@ -33,6 +35,7 @@ impl AppState {
             metadata,
             nats,
             requests_stream,
+            leases,
             ctx_builder,
             quiescent_period,
             token,
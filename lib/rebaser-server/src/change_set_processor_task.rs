@@ -30,7 +30,7 @@ use tokio_stream::StreamExt as _;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 use self::app_state::AppState;
-use crate::ServerMetadata;
+use crate::{lease::HeldLease, ServerMetadata};
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -68,6 +68,7 @@ impl ChangeSetProcessorTask {
         quiescent_period: Duration,
         task_token: CancellationToken,
         server_tracker: TaskTracker,
+        lease: Arc<HeldLease>,
     ) -> Self {
         let connection_metadata = nats.metadata_clone();
 
@@ -80,6 +81,7 @@ impl ChangeSetProcessorTask {
             ctx_builder,
             run_notify,
             server_tracker,
+            lease,
         );
 
         let quiescence_token = CancellationToken::new();
@@ -309,6 +311,7 @@ mod handlers {
 
     use crate::{
         extract::{ApiTypesNegotiate, HeaderReply},
+        lease::LeaseError,
         rebase::{perform_rebase, RebaseError},
     };
 
@@ -325,6 +328,9 @@ mod handlers {
         /// When failing to create a DAL context
         #[error("error creating a dal ctx: {0}")]
         DalTransactions(#[from] dal::TransactionsError),
+        /// When the change set lease was taken over by another instance
+        #[error("change set lease error: {0}")]
+        Lease(#[from] LeaseError),
         #[error("error publishing reply: {0}")]
         PublishReply(#[source] si_data_nats::Error),
         /// Failures related to rebasing/updating a snapshot or change set pointer.
@@ -367,6 +373,7 @@ mod handlers {
             ctx_builder,
             run_notify,
             server_tracker,
+            lease,
         } = state;
         let mut ctx = ctx_builder
             .build_for_change_set_as_system(workspace_id, change_set_id, None)
@@ -376,9 +383,13 @@ mod handlers {
         span.record("si.workspace.id", workspace_id.to_string());
         span.record("si.change_set.id", change_set_id.to_string());
 
-        let rebase_status = perform_rebase(&mut ctx, &request, &server_tracker)
-            .await
-            .unwrap_or_else(|err| {
+        let rebase_status = match perform_rebase(&mut ctx, &request, &server_tracker, &lease).await
+        {
+            Ok(rebase_status) => rebase_status,
+            // Another instance owns the change set now and will process this request, so neither
+            // reply nor let the request be deleted
+            Err(RebaseError::Lease(err)) => return Err(Error::Lease(err)),
+            Err(err) => {
                 error!(
                     si.error.message = ?err,
                     ?request,
@@ -387,7 +398,8 @@ mod handlers {
                 RebaseStatus::Error {
                     message: err.to_string(),
                 }
-            });
+            }
+        };
 
         // Dispatch eligible actions if the change set is the default for the workspace.
         // Actions are **ONLY** ever dispatched from the default change set for a workspace.
@@ -414,7 +426,15 @@ mod handlers {
                             .ok_or(dal::WorkspaceSnapshotError::WorkspaceSnapshotNotWritten)?;
                         // Manually update the pointer to the new address/id that reflects the new
                         // Action states.
-                        change_set.update_pointer(&ctx, new_snapshot_id).await?;
+                        match change_set
+                            .update_pointer_fenced(&ctx, new_snapshot_id, lease.fencing_token())
+                            .await
+                        {
+                            Err(dal::ChangeSetError::FencingTokenSuperseded(change_set_id, _)) => {
+                                return Err(LeaseError::Lost(change_set_id).into());
+                            }
+                            result => result?,
+                        }
                         // No need to send the request over to the rebaser as we are the rebaser.
                         ctx.commit_no_rebase().await?;
                     }
@@ -464,6 +484,8 @@ mod app_state {
     use tokio::sync::Notify;
     use tokio_util::task::TaskTracker;
 
+    use crate::lease::HeldLease;
+
     /// Application state.
     #[derive(Clone, Debug)]
     pub(crate) struct AppState {
@@ -480,6 +502,8 @@ mod app_state {
         /// A task tracker for server-level tasks that can outlive the lifetime of a change set
         /// processor task
         pub(crate) server_tracker: TaskTracker,
+        /// The lease giving this instance ownership of the change set
+        pub(crate) lease: Arc<HeldLease>,
     }
 
     impl AppState {
@@ -491,6 +515,7 @@ mod app_state {
             ctx_builder: DalContextBuilder,
             run_notify: Arc<Notify>,
             server_tracker: TaskTracker,
+            lease: Arc<HeldLease>,
         ) -> Self {
             Self {
                 workspace_id,
@@ -499,6 +524,7 @@ mod app_state {
                 ctx_builder,
                 run_notify,
                 server_tracker,
+                lease,
             }
         }
     }
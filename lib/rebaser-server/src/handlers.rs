@@ -22,6 +22,7 @@ use ulid::Ulid;
 use crate::{
     app_state::AppState,
     change_set_processor_task::{ChangeSetProcessorTask, ChangeSetProcessorTaskError, Shutdown},
+    lease::{HeldLease, LeaseError},
     serial_dvu_task::{SerialDvuTask, SerialDvuTaskError},
 };
 
@@ -38,6 +39,12 @@ pub(crate) enum HandlerError {
     ChangeSetProcessorJoin,
     #[error("error creating per-change set consumer: {0}")]
     ConsumerCreate(#[source] ConsumerError),
+    #[error("change set lease error: {0}")]
+    Lease(#[from] LeaseError),
+    #[error("change set lease heartbeat unexpectedly completed without error")]
+    LeaseHeartbeatCompleted,
+    #[error("change set lease heartbeat error on tokio join")]
+    LeaseHeartbeatJoin,
     #[error("serial dvu error: {0}")]
     SerialDvu(#[from] SerialDvuTaskError),
     #[error("serial dvu unexpectedly completed without error")]
@@ -84,6 +91,7 @@ pub(crate) async fn default(State(state): State<AppState>, subject: Subject) ->
         metadata,
         nats,
         requests_stream,
+        leases,
         ctx_builder,
         quiescent_period,
         token: server_token,
@@ -94,6 +102,22 @@ pub(crate) async fn default(State(state): State<AppState>, subject: Subject) ->
     let subject_str = subject.as_str();
     let (workspace, change_set) = parse_subject(subject_prefix, subject_str)?;
 
+    // Only the instance holding the change set's lease may process it. If another instance holds
+    // it, wait until it is released or expires.
+    let lease = match HeldLease::acquire(
+        leases,
+        metadata.instance_id(),
+        workspace.id,
+        change_set.id,
+        &server_token,
+    )
+    .await?
+    {
+        Some(lease) => Arc::new(lease),
+        // Cancelled while waiting; reply `Err` to nack for task to persist and retry
+        None => return Err(Error::TaskInterrupted(subject_str.to_string())),
+    };
+
     let requests_stream_filter_subject = nats::subject::enqueue_updates_for_change_set(
         subject_prefix,
         workspace.str,
@@ -107,19 +131,29 @@ pub(crate) async fn default(State(state): State<AppState>, subject: Subject) ->
 
     let run_notify = Arc::new(Notify::new());
 
-    let incoming = requests_stream
-        .create_consumer(rebaser_requests_per_change_set_consumer_config(
-            &nats,
-            &requests_stream_filter_subject,
-            metadata.instance_id(),
-            &workspace,
-            &change_set,
-        ))
-        .await
-        .map_err(HandlerError::ConsumerCreate)?
-        .messages()
-        .await
-        .map_err(HandlerError::Subscribe)?;
+    let incoming = async {
+        requests_stream
+            .create_consumer(rebaser_requests_per_change_set_consumer_config(
+                &nats,
+                &requests_stream_filter_subject,
+                metadata.instance_id(),
+                &workspace,
+                &change_set,
+            ))
+            .await
+            .map_err(HandlerError::ConsumerCreate)?
+            .messages()
+            .await
+            .map_err(HandlerError::Subscribe)
+    }
+    .await;
+    let incoming = match incoming {
+        Ok(incoming) => incoming,
+        Err(err) => {
+            release_lease(&lease, &workspace, &change_set).await;
+            return Err(err);
+        }
+    };
 
     let dvu_task = SerialDvuTask::create(
         metadata.clone(),
@@ -142,10 +176,16 @@ pub(crate) async fn default(State(state): State<AppState>, subject: Subject) ->
         quiescent_period,
         tasks_token.clone(),
         server_tracker,
+        lease.clone(),
     );
 
     let dvu_task_result = tracker.spawn(dvu_task.try_run());
     let processor_task_result = tracker.spawn(processor_task.try_run());
+    let lease_heartbeat_result = tracker.spawn({
+        let lease = lease.clone();
+        let token = tasks_token.clone();
+        async move { lease.keep_alive(token).await }
+    });
     tracker.close();
 
     let result = tokio::select! {
@@ -191,11 +231,29 @@ pub(crate) async fn default(State(state): State<AppState>, subject: Subject) ->
                 Err(_join_err) => Err(Error::SerialDvuJoin),
             }
         }
+        // Lease heartbeat completed
+        lease_heartbeat_result_result = lease_heartbeat_result => {
+            match lease_heartbeat_result_result {
+                // Heartbeat exited cleanly, but unexpectedly; reply `Err` to nack for task to
+                // persist and retry
+                Ok(Ok(())) => Err(Error::LeaseHeartbeatCompleted),
+                // Lease was lost to another instance which is now processing the change set; reply
+                // `Err` to nack for task to persist and retry
+                Ok(Err(err)) => Err(Error::Lease(err)),
+                // Tokio join error on heartbeat exit; reply `Err` to nack for task to persist and
+                // retry
+                Err(_join_err) => Err(Error::LeaseHeartbeatJoin),
+            }
+        }
     };
 
     tasks_token.cancel();
     tracker.wait().await;
 
+    // Release the lease so that the next instance to pick up this change set does not need to
+    // wait for it to expire
+    release_lease(&lease, &workspace, &change_set).await;
+
     // If the processor task was ended via a quiesced shutdown, then check one last time if there
     // are messages on the subject. This means that during the quiet period-triggered shutdown,
     // another message was published to our subject (such as during this handler waiting on the
@@ -230,6 +288,21 @@ pub(crate) async fn default(State(state): State<AppState>, subject: Subject) ->
     }
 }
 
+async fn release_lease(
+    lease: &HeldLease,
+    workspace: &ParsedWorkspaceId<'_>,
+    change_set: &ParsedChangeSetId<'_>,
+) {
+    if let Err(err) = lease.release().await {
+        warn!(
+            si.error.message = ?err,
+            si.workspace.id = %workspace.str,
+            si.change_set.id = %change_set.str,
+            "failed to release change set lease; it will expire instead",
+        );
+    }
+}
+
 struct ParsedWorkspaceId<'a> {
     id: WorkspacePk,
     str: &'a str,
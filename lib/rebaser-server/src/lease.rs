@@ -0,0 +1,359 @@
+//! Holding the [`ChangeSetLease`] which makes this instance the only one processing a change set.
+
+use std::{
+    result,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use rebaser_core::lease::{self, ChangeSetLease, LEASE_HEARTBEAT_INTERVAL};
+use si_data_nats::async_nats::jetstream::kv;
+use si_events::{ChangeSetId, WorkspacePk};
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+/// How long to wait between attempts to take over a lease held by another instance.
+const ACQUIRE_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub(crate) enum LeaseError {
+    #[error("error creating lease: {0}")]
+    Create(#[source] kv::CreateError),
+    #[error("error deleting lease: {0}")]
+    Delete(#[source] kv::DeleteError),
+    #[error("error fetching lease: {0}")]
+    Entry(#[source] kv::EntryError),
+    #[error("lease for change set {0} is no longer held by this instance")]
+    Lost(ChangeSetId),
+    #[error("error serializing lease: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("error updating lease: {0}")]
+    Update(#[source] kv::UpdateError),
+}
+
+type Result<T> = result::Result<T, LeaseError>;
+
+/// A change set lease held by this instance.
+#[derive(Debug)]
+pub(crate) struct HeldLease {
+    store: kv::Store,
+    key: String,
+    lease: ChangeSetLease,
+    /// Revision of the last write to the lease, which the next write is conditional on.
+    revision: AtomicU64,
+}
+
+impl HeldLease {
+    /// Acquires the lease for a change set, waiting for another instance to release it or for its
+    /// lease to expire. Returns `None` if cancelled before the lease could be acquired.
+    pub(crate) async fn acquire(
+        store: kv::Store,
+        instance_id: &str,
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+        token: &CancellationToken,
+    ) -> Result<Option<Self>> {
+        let key = lease::lease_key(workspace_id, change_set_id);
+        let mut lease = ChangeSetLease {
+            workspace_id,
+            change_set_id,
+            instance_id: instance_id.to_owned(),
+            fencing_token: 0,
+            heartbeat_at_ms: lease::now_ms(),
+        };
+
+        loop {
+            if let Some(fencing_token) = Self::try_take(&store, &key, &lease).await? {
+                // The revision of the write that took the lease is unique and increases with
+                // every acquisition, so it doubles as the fencing token
+                lease.fencing_token = fencing_token;
+                lease.heartbeat_at_ms = lease::now_ms();
+                let revision = store
+                    .update(&key, serde_json::to_vec(&lease)?.into(), fencing_token)
+                    .await
+                    .map_err(LeaseError::Update)?;
+
+                debug!(
+                    service.instance.id = instance_id,
+                    si.workspace.id = %workspace_id,
+                    si.change_set.id = %change_set_id,
+                    fencing_token,
+                    "acquired change set lease",
+                );
+                return Ok(Some(Self {
+                    store,
+                    key,
+                    lease,
+                    revision: AtomicU64::new(revision),
+                }));
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => return Ok(None),
+                _ = tokio::time::sleep(ACQUIRE_RETRY_INTERVAL) => {}
+            }
+        }
+    }
+
+    /// Writes the lease if nobody holds it or the holder's lease has expired, returning the
+    /// revision of the write.
+    async fn try_take(store: &kv::Store, key: &str, lease: &ChangeSetLease) -> Result<Option<u64>> {
+        let value = serde_json::to_vec(lease)?;
+
+        let previous_revision = match store.entry(key).await.map_err(LeaseError::Entry)? {
+            Some(entry) if entry.operation == kv::Operation::Put => {
+                let holder: ChangeSetLease = serde_json::from_slice(&entry.value)?;
+                if !holder.is_expired() {
+                    return Ok(None);
+                }
+
+                debug!(
+                    service.instance.id = lease.instance_id,
+                    previous_instance_id = holder.instance_id,
+                    si.change_set.id = %lease.change_set_id,
+                    "taking over expired change set lease",
+                );
+                Some(entry.revision)
+            }
+            // Released leases are taken over like expired ones
+            Some(entry) => Some(entry.revision),
+            None => None,
+        };
+
+        // Losing either race means another instance got there first
+        match previous_revision {
+            Some(previous_revision) => {
+                match store.update(key, value.into(), previous_revision).await {
+                    Ok(revision) => Ok(Some(revision)),
+                    Err(err) if err.kind() == kv::UpdateErrorKind::WrongLastRevision => Ok(None),
+                    Err(err) => Err(LeaseError::Update(err)),
+                }
+            }
+            None => match store.create(key, value.into()).await {
+                Ok(revision) => Ok(Some(revision)),
+                Err(err) if err.kind() == kv::CreateErrorKind::AlreadyExists => Ok(None),
+                Err(err) => Err(LeaseError::Create(err)),
+            },
+        }
+    }
+
+    /// Renews the lease until cancelled. Only returns an error once the lease has been lost.
+    pub(crate) async fn keep_alive(&self, token: CancellationToken) -> Result<()> {
+        let mut interval = tokio::time::interval(LEASE_HEARTBEAT_INTERVAL);
+        // The first tick completes immediately and the lease was just written
+        interval.tick().await;
+
+        loop {
+            tokio::select! {
+                biased;
+
+                _ = token.cancelled() => return Ok(()),
+                _ = interval.tick() => {
+                    match self.heartbeat().await {
+                        Ok(()) => {}
+                        Err(err @ LeaseError::Lost(_)) => return Err(err),
+                        // The lease stays valid until it expires, so a failed heartbeat is retried
+                        Err(err) => warn!(
+                            si.error.message = ?err,
+                            si.change_set.id = %self.lease.change_set_id,
+                            "failed to renew change set lease",
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    async fn heartbeat(&self) -> Result<()> {
+        let lease = ChangeSetLease {
+            heartbeat_at_ms: lease::now_ms(),
+            ..self.lease.clone()
+        };
+
+        let revision = self
+            .store
+            .update(
+                &self.key,
+                serde_json::to_vec(&lease)?.into(),
+                self.revision.load(Ordering::Acquire),
+            )
+            .await
+            .map_err(|err| match err.kind() {
+                kv::UpdateErrorKind::WrongLastRevision => {
+                    LeaseError::Lost(self.lease.change_set_id)
+                }
+                _ => LeaseError::Update(err),
+            })?;
+        self.revision.store(revision, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// The fencing token of this acquisition of the lease.
+    ///
+    /// Must be passed along when moving the change set pointer (see
+    /// [`ChangeSet::update_pointer_fenced`](dal::ChangeSet::update_pointer_fenced)), so that an
+    /// instance which stalled for longer than the lease lifetime cannot overwrite the work of the
+    /// instance that took over.
+    pub(crate) fn fencing_token(&self) -> u64 {
+        self.lease.fencing_token
+    }
+
+    /// Releases the lease so that another instance can take over without waiting for it to
+    /// expire.
+    pub(crate) async fn release(&self) -> Result<()> {
+        self.store
+            .delete_expect_revision(&self.key, Some(self.revision.load(Ordering::Acquire)))
+            .await
+            .map_err(LeaseError::Delete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use rebaser_core::{lease::LEASE_TTL, nats};
+    use si_data_nats::{jetstream, NatsClient, NatsConfig};
+    use ulid::Ulid;
+
+    use super::*;
+
+    const ENV_VAR_NATS_URL: &str = "SI_TEST_NATS_URL";
+
+    /// A leases bucket of its own, so that tests don't see each other's leases.
+    async fn leases() -> kv::Store {
+        let mut config = NatsConfig {
+            subject_prefix: Some(format!("lease-test-{}", Ulid::new())),
+            ..Default::default()
+        };
+        #[allow(clippy::disallowed_methods)] // Environment variables are used exclusively in test
+        if let Ok(value) = env::var(ENV_VAR_NATS_URL) {
+            config.url = value;
+        }
+        let client = NatsClient::new(&config)
+            .await
+            .expect("failed to connect to nats");
+
+        nats::rebaser_leases_kv(&jetstream::new(client))
+            .await
+            .expect("failed to create leases bucket")
+    }
+
+    async fn acquire(
+        store: &kv::Store,
+        instance_id: &str,
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+        token: &CancellationToken,
+    ) -> Option<HeldLease> {
+        HeldLease::acquire(
+            store.clone(),
+            instance_id,
+            workspace_id,
+            change_set_id,
+            token,
+        )
+        .await
+        .expect("failed to acquire lease")
+    }
+
+    /// Makes a held lease look as if its holder stopped heartbeating longer than
+    /// [`LEASE_TTL`] ago.
+    async fn expire(store: &kv::Store, held: &HeldLease) {
+        let expired = ChangeSetLease {
+            heartbeat_at_ms: lease::now_ms() - LEASE_TTL.as_millis() as u64 - 1_000,
+            ..held.lease.clone()
+        };
+        store
+            .put(
+                &held.key,
+                serde_json::to_vec(&expired).expect("serialize").into(),
+            )
+            .await
+            .expect("failed to expire lease");
+    }
+
+    #[tokio::test]
+    async fn acquire_waits_for_release() {
+        let store = leases().await;
+        let (workspace_id, change_set_id) = (WorkspacePk::new(), ChangeSetId::new());
+        let token = CancellationToken::new();
+
+        let first = acquire(&store, "first", workspace_id, change_set_id, &token)
+            .await
+            .expect("lease is free");
+
+        // While the lease is held, another instance waits until it gives up
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+        assert!(
+            acquire(&store, "second", workspace_id, change_set_id, &cancelled)
+                .await
+                .is_none()
+        );
+
+        first.release().await.expect("failed to release lease");
+        let second = acquire(&store, "second", workspace_id, change_set_id, &token)
+            .await
+            .expect("lease was released");
+        assert!(second.fencing_token() > first.fencing_token());
+    }
+
+    #[tokio::test]
+    async fn expired_lease_is_taken_over() {
+        let store = leases().await;
+        let (workspace_id, change_set_id) = (WorkspacePk::new(), ChangeSetId::new());
+        let token = CancellationToken::new();
+
+        let stalled = acquire(&store, "stalled", workspace_id, change_set_id, &token)
+            .await
+            .expect("lease is free");
+        expire(&store, &stalled).await;
+
+        let taken_over = acquire(&store, "taken-over", workspace_id, change_set_id, &token)
+            .await
+            .expect("expired lease should be taken over");
+        assert!(taken_over.fencing_token() > stalled.fencing_token());
+        taken_over
+            .heartbeat()
+            .await
+            .expect("new holder should renew the lease");
+    }
+
+    #[tokio::test]
+    async fn lost_lease_cannot_be_renewed_or_released() {
+        let store = leases().await;
+        let (workspace_id, change_set_id) = (WorkspacePk::new(), ChangeSetId::new());
+        let token = CancellationToken::new();
+
+        let stalled = acquire(&store, "stalled", workspace_id, change_set_id, &token)
+            .await
+            .expect("lease is free");
+        expire(&store, &stalled).await;
+        let taken_over = acquire(&store, "taken-over", workspace_id, change_set_id, &token)
+            .await
+            .expect("expired lease should be taken over");
+
+        // The stalled instance learns it lost the lease when it next heartbeats
+        assert!(matches!(
+            stalled.heartbeat().await,
+            Err(LeaseError::Lost(id)) if id == change_set_id
+        ));
+        // and cannot release it out from under the new holder
+        assert!(stalled.release().await.is_err());
+
+        let current: ChangeSetLease = serde_json::from_slice(
+            &store
+                .get(&taken_over.key)
+                .await
+                .expect("failed to get lease")
+                .expect("lease should still be held"),
+        )
+        .expect("deserialize lease");
+        assert_eq!("taken-over", current.instance_id);
+        assert_eq!(taken_over.fencing_token(), current.fencing_token);
+    }
+}
@@ -34,6 +34,7 @@ mod change_set_processor_task;
 mod config;
 pub mod extract;
 mod handlers;
+mod lease;
 mod rebase;
 mod serial_dvu_task;
 mod server;
@@ -69,6 +70,9 @@ pub enum ServerError {
     /// When failing to create a Jetstream consumer `impl Stream` of messages
     #[error("consumer stream error: {0}")]
     JsConsumerStream(#[from] si_data_nats::async_nats::jetstream::consumer::StreamError),
+    /// When failing to create the change set leases key value bucket
+    #[error("key value create error: {0}")]
+    JsCreateKeyValue(#[from] si_data_nats::async_nats::jetstream::context::CreateKeyValueError),
     /// When failing to create a Jetstream stream
     #[error("stream create error: {0}")]
     JsCreateStreamError(#[from] si_data_nats::async_nats::jetstream::context::CreateStreamError),
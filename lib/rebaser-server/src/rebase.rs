@@ -17,6 +17,8 @@ use thiserror::Error;
 use tokio::time::Instant;
 use tokio_util::task::TaskTracker;
 
+use crate::lease::{HeldLease, LeaseError};
+
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub(crate) enum RebaseError {
@@ -26,6 +28,8 @@ pub(crate) enum RebaseError {
     ChangeSet(#[from] ChangeSetError),
     #[error("layerdb error: {0}")]
     LayerDb(#[from] LayerDbError),
+    #[error("change set lease error: {0}")]
+    Lease(#[from] LeaseError),
     #[error("missing rebase batch {0}")]
     MissingRebaseBatch(RebaseBatchAddress),
     #[error("pending events error: {0}")]
@@ -64,6 +68,7 @@ pub async fn perform_rebase(
     ctx: &mut DalContext,
     request: &EnqueueUpdatesRequest,
     server_tracker: &TaskTracker,
+    lease: &HeldLease,
 ) -> RebaseResult<RebaseStatus> {
     let span = current_span_for_instrument_at!("info");
 
@@ -113,9 +118,20 @@ pub async fn perform_rebase(
         // and update the pointer.
         to_rebase_workspace_snapshot.write(ctx).await?;
         debug!("snapshot written: {:?}", start.elapsed());
-        to_rebase_change_set
-            .update_pointer(ctx, to_rebase_workspace_snapshot.id().await)
-            .await?;
+        // Fails if another instance has taken over the change set and moved the pointer since
+        match to_rebase_change_set
+            .update_pointer_fenced(
+                ctx,
+                to_rebase_workspace_snapshot.id().await,
+                lease.fencing_token(),
+            )
+            .await
+        {
+            Err(ChangeSetError::FencingTokenSuperseded(change_set_id, _)) => {
+                return Err(LeaseError::Lost(change_set_id).into());
+            }
+            result => result?,
+        }
 
         debug!("pointer updated: {:?}", start.elapsed());
 
@@ -158,6 +158,8 @@ impl Server {
 
         let requests_stream = nats::rebaser_requests_jetstream_stream(&context).await?;
 
+        let leases = nats::rebaser_leases_kv(&context).await?;
+
         let ctx_builder = DalContext::builder(services_context, false);
 
         let server_tracker = TaskTracker::new();
@@ -165,6 +167,7 @@ impl Server {
             metadata.clone(),
             nats,
             requests_stream,
+            leases,
             ctx_builder,
             quiescent_period,
             shutdown_token.clone(),
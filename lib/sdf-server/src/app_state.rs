@@ -11,7 +11,8 @@ use tokio::sync::{Mutex, RwLock};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    nats_multiplexer::NatsMultiplexerClients, service::ws::crdt::BroadcastGroups,
+    nats_multiplexer::NatsMultiplexerClients,
+    service::{public::events::buffer::PublicEventBuffers, ws::crdt::BroadcastGroups},
     WorkspacePermissions, WorkspacePermissionsMode,
 };
 
@@ -32,6 +33,7 @@ pub struct AppState {
     asset_sprayer: Option<AssetSprayer>,
     for_tests: bool,
     nats_multiplexer_clients: NatsMultiplexerClients,
+    public_event_buffers: PublicEventBuffers,
     create_workspace_permissions: WorkspacePermissionsMode,
     create_workspace_allowlist: Vec<WorkspacePermissions>,
     pub application_runtime_mode: Arc<RwLock<ApplicationRuntimeMode>>,
@@ -72,6 +74,7 @@ impl AppState {
             asset_sprayer,
             for_tests,
             nats_multiplexer_clients,
+            public_event_buffers: Default::default(),
             create_workspace_permissions,
             create_workspace_allowlist,
             application_runtime_mode,
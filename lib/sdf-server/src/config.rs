@@ -14,7 +14,7 @@ use std::{
 use strum::{Display, EnumString, VariantNames};
 use ulid::Ulid;
 
-use buck2_resources::Buck2Resources;
+use buck2_resources::{Buck2Resources, Buck2ResourcesError};
 use dal::feature_flags::FeatureFlag;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
@@ -152,6 +152,9 @@ pub struct Config {
 
     #[builder(default)]
     dev_mode: bool,
+
+    #[builder(default)]
+    prune_func_runs: bool,
 }
 
 impl StandardConfig for Config {
@@ -278,6 +281,12 @@ impl Config {
     pub fn dev_mode(&self) -> bool {
         self.dev_mode
     }
+
+    /// Whether this instance prunes func runs past their retention policy. Only one instance
+    /// should, so that replicas don't race each other over the same rows.
+    pub fn prune_func_runs(&self) -> bool {
+        self.prune_func_runs
+    }
 }
 
 impl ConfigBuilder {
@@ -334,6 +343,8 @@ pub struct ConfigFile {
     spicedb: SpiceDbConfig,
     #[serde(default)]
     audit: AuditDatabaseConfig,
+    #[serde(default)]
+    prune_func_runs: bool,
 }
 
 impl Default for ConfigFile {
@@ -360,6 +371,7 @@ impl Default for ConfigFile {
             spicedb: Default::default(),
             audit: Default::default(),
             dev_mode: false,
+            prune_func_runs: false,
         }
     }
 }
@@ -371,8 +383,21 @@ impl StandardConfigFile for ConfigFile {
 impl TryFrom<ConfigFile> for Config {
     type Error = ConfigError;
 
-    fn try_from(mut value: ConfigFile) -> Result<Self> {
-        detect_and_configure_development(&mut value)?;
+    fn try_from(value: ConfigFile) -> Result<Self> {
+        Self::from_config_file(value, true)
+    }
+}
+
+impl Config {
+    /// Builds a config for tools, such as si-admin, that don't authenticate requests or install
+    /// packages. In development these don't ship the JWT signing keys or the packages, so those
+    /// keep their configured values rather than failing when the resources are missing.
+    pub fn try_from_for_tool(value: ConfigFile) -> Result<Self> {
+        Self::from_config_file(value, false)
+    }
+
+    fn from_config_file(mut value: ConfigFile, server_resources: bool) -> Result<Self> {
+        detect_and_configure_development(&mut value, server_resources)?;
 
         Ok(Config {
             instance_id: value.instance_id,
@@ -397,6 +422,7 @@ impl TryFrom<ConfigFile> for Config {
             spicedb: value.spicedb,
             audit: value.audit,
             dev_mode: value.dev_mode,
+            prune_func_runs: value.prune_func_runs,
         })
     }
 }
@@ -459,9 +485,9 @@ fn default_layer_db_config() -> LayerDbConfig {
 }
 
 #[allow(clippy::disallowed_methods)] // Used to determine if running in development
-fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
+fn detect_and_configure_development(config: &mut ConfigFile, server_resources: bool) -> Result<()> {
     if env::var("BUCK_RUN_BUILD_ID").is_ok() || env::var("BUCK_BUILD_ID").is_ok() {
-        buck2_development(config)
+        buck2_development(config, server_resources)
     } else if let Ok(dir) = env::var("CARGO_MANIFEST_DIR") {
         cargo_development(dir, config)
     } else {
@@ -469,23 +495,23 @@ fn detect_and_configure_development(config: &mut ConfigFile) -> Result<()> {
     }
 }
 
-fn buck2_development(config: &mut ConfigFile) -> Result<()> {
+fn buck2_development(config: &mut ConfigFile, server_resources: bool) -> Result<()> {
     let resources = Buck2Resources::read().map_err(ConfigError::development)?;
 
+    // The JWT signing keys and the packages are only required by binaries that serve requests.
+    // Tools that don't ship them keep their configured values when they're missing.
+    let server_resource = |path: std::result::Result<PathBuf, Buck2ResourcesError>| match path {
+        Ok(path) => Ok(Some(path.to_string_lossy().to_string())),
+        Err(_) if !server_resources => Ok(None),
+        Err(err) => Err(ConfigError::development(err)),
+    };
+
     #[allow(clippy::disallowed_methods)] // Used in development with a local auth services
     // Note(victor): If the user has set a custom auth ip url via env variable we assume dev mode
     let jwt_primary_signing_public_key_path = if env::var("SI_AUTH_API_URL").is_ok() {
-        resources
-            .get_ends_with("dev.jwt_signing_public_key.pem")
-            .map_err(ConfigError::development)?
-            .to_string_lossy()
-            .to_string()
+        server_resource(resources.get_ends_with("dev.jwt_signing_public_key.pem"))?
     } else {
-        resources
-            .get_ends_with("prod.jwt_signing_public_key.pem")
-            .map_err(ConfigError::development)?
-            .to_string_lossy()
-            .to_string()
+        server_resource(resources.get_ends_with("prod.jwt_signing_public_key.pem"))?
     };
     let veritech_encryption_key_path = resources
         .get_ends_with("dev.encryption.key")
@@ -502,26 +528,24 @@ fn buck2_development(config: &mut ConfigFile) -> Result<()> {
         .map_err(ConfigError::development)?
         .to_string_lossy()
         .to_string();
-    let pkgs_path = resources
-        .get_ends_with("pkgs_path")
-        .map_err(ConfigError::development)?
-        .to_string_lossy()
-        .to_string();
+    let pkgs_path = server_resource(resources.get_ends_with("pkgs_path"))?;
 
     warn!(
-        jwt_signing_public_key_path = jwt_primary_signing_public_key_path.as_str(),
+        jwt_signing_public_key_path = ?jwt_primary_signing_public_key_path,
         veritech_encryption_key_path = veritech_encryption_key_path.as_str(),
         symmetric_crypto_service_key = symmetric_crypto_service_key.as_str(),
         postgres_cert = postgres_cert.as_str(),
-        pkgs_path = pkgs_path.as_str(),
+        pkgs_path = ?pkgs_path,
         "detected development run",
     );
 
-    config.jwt_signing_public_key = JwtConfig {
-        key_file: Some(jwt_primary_signing_public_key_path.try_into()?),
-        key_base64: None,
-        algo: JwtAlgo::RS256,
-    };
+    if let Some(jwt_primary_signing_public_key_path) = jwt_primary_signing_public_key_path {
+        config.jwt_signing_public_key = JwtConfig {
+            key_file: Some(jwt_primary_signing_public_key_path.try_into()?),
+            key_base64: None,
+            algo: JwtAlgo::RS256,
+        };
+    }
     config.crypto.encryption_key_file = veritech_encryption_key_path.parse().ok();
     config.symmetric_crypto_service = SymmetricCryptoServiceConfigFile {
         active_key: Some(symmetric_crypto_service_key),
@@ -531,7 +555,9 @@ fn buck2_development(config: &mut ConfigFile) -> Result<()> {
     config.pg.certificate_path = Some(postgres_cert.clone().try_into()?);
     config.layer_db_config.pg_pool_config.certificate_path =
         Some(postgres_cert.clone().try_into()?);
-    config.pkgs_path = pkgs_path;
+    if let Some(pkgs_path) = pkgs_path {
+        config.pkgs_path = pkgs_path;
+    }
     config.layer_db_config.pg_pool_config.dbname = si_layer_cache::pg::DBNAME.to_string();
     config.spicedb.enabled = true;
     config.audit.pg.certificate_path = Some(postgres_cert.clone().try_into()?);
@@ -5,9 +5,10 @@ use axum::{
     http::request::Parts,
     RequestPartsExt as _,
 };
+use dal::revoked_auth_token::RevokedAuthToken;
 use derive_more::{Deref, Into};
 use serde::Deserialize;
-use si_jwt_public_key::{validate_raw_token, SiJwt};
+use si_jwt_public_key::{validate_raw_token, SiJwt, SiJwtClaimRole};
 use ulid::Ulid;
 
 use super::{internal_error, unauthorized_error, ErrorResponse};
@@ -65,11 +66,36 @@ impl FromRequestParts<AppState> for ValidatedToken {
         let token = validate_raw_token(jwt_public_signing_key, raw_token)
             .await
             .map_err(unauthorized_error)?;
+        ensure_not_revoked(state, &token).await?;
         parts.extensions.insert(Self(token.clone()));
         Ok(Self(token))
     }
 }
 
+/// Rejects automation tokens whose JWT id is on the revocation denylist. Web tokens are short-lived and are not
+/// checked.
+async fn ensure_not_revoked(state: &AppState, token: &SiJwt) -> Result<(), ErrorResponse> {
+    let Some(token_id) = token.jwt_id.as_deref() else {
+        return Ok(());
+    };
+    if token.custom.role() != SiJwtClaimRole::Automation {
+        return Ok(());
+    }
+
+    if RevokedAuthToken::is_revoked_in_pool(
+        state.services_context().pg_pool(),
+        token.custom.workspace_id(),
+        token_id,
+    )
+    .await
+    .map_err(internal_error)?
+    {
+        return Err(unauthorized_error("Token has been revoked"));
+    }
+
+    Ok(())
+}
+
 ///
 /// Validated JWT with unverified claims inside.
 ///
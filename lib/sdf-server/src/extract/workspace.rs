@@ -1,14 +1,19 @@
 use axum::{
     async_trait,
-    extract::{FromRequestParts, Path},
+    extract::{FromRequestParts, OriginalUri, Path},
     http::{header::HeaderMap, request::Parts},
     RequestPartsExt as _,
 };
-use dal::{DalContext, User, UserPk, WorkspacePk};
+use dal::{
+    ChangeSetId, DalContext, DalContextBuilder, HistoryActor, Tenancy, User, UserPk, Visibility,
+    WorkspacePk,
+};
 use derive_more::{Deref, Into};
 use serde::Deserialize;
-use si_jwt_public_key::SiJwtClaimRole;
+use si_events::{audit_log::AuditLogKind, ViewId};
+use si_jwt_public_key::{SiJwtClaimRole, SiJwtPermission};
 use std::str::FromStr;
+use telemetry::prelude::*;
 
 use crate::app_state::AppState;
 
@@ -100,6 +105,10 @@ impl FromRequestParts<AppState> for WorkspaceAuthorization {
             .find(|m| m.pk() == user_id)
             .ok_or_else(|| unauthorized_error("User not a member of the workspace"))?;
 
+        if let Some(token_use) = parts.extensions.get::<AutomationTokenUse>() {
+            token_use.audit(builder, workspace_id, user_id, ctx.change_set_id());
+        }
+
         Ok(Self {
             ctx,
             user,
@@ -151,6 +160,45 @@ impl AuthorizedForRole {
             return Err(unauthorized_error("Not authorized for role"));
         }
 
+        // Automation tokens may be scoped down to a set of permissions, change sets and views
+        if token.custom.role() == SiJwtClaimRole::Automation {
+            let path = parts
+                .extensions
+                .get::<OriginalUri>()
+                .map(|OriginalUri(uri)| uri.path().to_owned())
+                .unwrap_or_else(|| parts.uri.path().to_owned());
+            // Routes that change anything declare what they need with a `TokenPermissionLayer`
+            let permission = parts
+                .extensions
+                .get::<RequiredTokenPermission>()
+                .map_or(SiJwtPermission::ReadOnly, |required| required.0);
+
+            if !token.custom.permits(permission) {
+                return Err(unauthorized_error(format!(
+                    "Token is not authorized for {permission}"
+                )));
+            }
+            if let Some(change_set_id) =
+                path_id_after::<ChangeSetId>(&path, &["change-sets", "change_sets"])
+            {
+                if !token.custom.permits_change_set(change_set_id) {
+                    return Err(unauthorized_error("Token is not authorized for change set"));
+                }
+            }
+            if let Some(view_id) = path_id_after::<ViewId>(&path, &["views"]) {
+                if !token.custom.permits_view(view_id) {
+                    return Err(unauthorized_error("Token is not authorized for view"));
+                }
+            }
+
+            parts.extensions.insert(AutomationTokenUse {
+                token_id: token.jwt_id.clone().unwrap_or_default(),
+                method: parts.method.to_string(),
+                path,
+                permission,
+            });
+        }
+
         // Stash the authorization
         let result = AuthorizedForRole {
             user_id: token.custom.user_id(),
@@ -163,6 +211,99 @@ impl AuthorizedForRole {
     }
 }
 
+/// The permission a scoped automation token needs for a route, as declared by its
+/// [`TokenPermissionLayer`](crate::middleware::TokenPermissionLayer).
+#[derive(Clone, Copy, Debug)]
+struct RequiredTokenPermission(SiJwtPermission);
+
+/// Requires the given permission of automation tokens used for this request.
+///
+/// If the request has already been authorized (such as by a route layer), the token is checked and its recorded use is
+/// updated here. Otherwise the permission is stashed for [`AuthorizedForRole`] to check.
+pub(crate) fn require_token_permission(
+    parts: &mut Parts,
+    permission: SiJwtPermission,
+) -> Result<(), ErrorResponse> {
+    parts.extensions.insert(RequiredTokenPermission(permission));
+
+    if parts.extensions.get::<AuthorizedForRole>().is_none() {
+        return Ok(());
+    }
+    if let Some(ValidatedToken(token)) = parts.extensions.get::<ValidatedToken>() {
+        if token.custom.role() == SiJwtClaimRole::Automation && !token.custom.permits(permission) {
+            return Err(unauthorized_error(format!(
+                "Token is not authorized for {permission}"
+            )));
+        }
+    }
+    if let Some(token_use) = parts.extensions.get_mut::<AutomationTokenUse>() {
+        token_use.permission = permission;
+    }
+
+    Ok(())
+}
+
+/// Parses the id in the path segment following any of the given segment names.
+fn path_id_after<T: FromStr>(path: &str, names: &[&str]) -> Option<T> {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    while let Some(segment) = segments.next() {
+        if names.contains(&segment) {
+            return segments.next().and_then(|id| T::from_str(id).ok());
+        }
+    }
+    None
+}
+
+/// A request made with an automation token, recorded in the audit log once the user is known to be a member of the
+/// workspace.
+#[derive(Clone, Debug)]
+struct AutomationTokenUse {
+    token_id: String,
+    method: String,
+    path: String,
+    permission: SiJwtPermission,
+}
+
+impl AutomationTokenUse {
+    /// Writes the audit log on its own context so read-only requests, which never commit, are still recorded.
+    fn audit(
+        &self,
+        builder: DalContextBuilder,
+        workspace_id: WorkspacePk,
+        user_id: UserPk,
+        change_set_id: ChangeSetId,
+    ) {
+        let token_use = self.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let mut ctx = builder.build_default(None).await?;
+                ctx.update_tenancy(Tenancy::new(workspace_id));
+                ctx.update_visibility_deprecated(Visibility::new(change_set_id));
+                ctx.update_history_actor(HistoryActor::User(user_id));
+
+                let entity_name = token_use.token_id.clone();
+                ctx.write_audit_log(
+                    AuditLogKind::UseAuthToken {
+                        token_id: token_use.token_id,
+                        method: token_use.method,
+                        path: token_use.path,
+                        permission: token_use.permission.to_string(),
+                    },
+                    entity_name,
+                )
+                .await?;
+                ctx.write_audit_log_final_message().await?;
+                ctx.publish_pending_audit_logs(None, None).await
+            }
+            .await;
+
+            if let Err(err) = result {
+                error!(si.error.message = ?err, "unable to write audit log for automation token use");
+            }
+        });
+    }
+}
+
 #[async_trait]
 impl FromRequestParts<AppState> for AuthorizedForRole {
     type Rejection = ErrorResponse;
@@ -329,3 +470,54 @@ impl FromRequestParts<AppState> for TargetWorkspaceIdFromToken {
         )?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_id_after_finds_ids() {
+        let change_set_id = ChangeSetId::generate();
+        let view_id = ViewId::generate();
+        let path =
+            format!("/api/v2/workspaces/01J0/change-sets/{change_set_id}/views/{view_id}/erase");
+
+        assert_eq!(
+            Some(change_set_id),
+            path_id_after::<ChangeSetId>(&path, &["change-sets", "change_sets"])
+        );
+        assert_eq!(Some(view_id), path_id_after::<ViewId>(&path, &["views"]));
+        assert_eq!(
+            Some(change_set_id),
+            path_id_after::<ChangeSetId>(
+                &format!("/api/public/v0/workspaces/01J0/change_sets/{change_set_id}/"),
+                &["change-sets", "change_sets"]
+            )
+        );
+    }
+
+    #[test]
+    fn path_id_after_ignores_missing_and_invalid_ids() {
+        let names = &["change-sets", "change_sets"];
+
+        assert_eq!(
+            None,
+            path_id_after::<ChangeSetId>("/api/v2/workspaces/01J0/change-sets", names)
+        );
+        assert_eq!(
+            None,
+            path_id_after::<ChangeSetId>("/api/v2/workspaces/01J0/change-sets/", names)
+        );
+        assert_eq!(
+            None,
+            path_id_after::<ChangeSetId>(
+                "/api/v2/workspaces/01J0/change-sets/not-an-id/apply",
+                names
+            )
+        );
+        assert_eq!(
+            None,
+            path_id_after::<ChangeSetId>("/api/v2/workspaces/01J0/integrations", names)
+        );
+    }
+}
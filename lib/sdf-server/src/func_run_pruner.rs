@@ -0,0 +1,58 @@
+//! Prunes func runs and their logs once they outlive their workspace's
+//! [retention policy](dal::func::retention).
+
+use std::time::Duration;
+
+use dal::{func::retention::FuncRunRetention, DalContextBuilder, ServicesContext};
+use telemetry::prelude::*;
+use tokio_util::sync::CancellationToken;
+
+/// How often to look for expired func runs.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How many func runs to delete per query.
+const BATCH_SIZE: i64 = 500;
+
+/// How many batches to delete per workspace and func kind on each pass, so that one workspace's
+/// backlog is worked through over several passes instead of holding up the others.
+const MAX_BATCHES: usize = 20;
+
+pub(crate) struct FuncRunPruner {
+    builder: DalContextBuilder,
+    token: CancellationToken,
+}
+
+impl FuncRunPruner {
+    pub(crate) fn new(services_context: ServicesContext, token: CancellationToken) -> Self {
+        Self {
+            builder: services_context.into_builder(false),
+            token,
+        }
+    }
+
+    pub(crate) async fn run(self) {
+        let mut prune = tokio::time::interval(PRUNE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = self.token.cancelled() => break,
+                _ = prune.tick() => {
+                    match FuncRunRetention::prune(&self.builder, BATCH_SIZE, MAX_BATCHES).await {
+                        Ok(report) => {
+                            info!(
+                                func_runs = report.func_runs,
+                                func_run_logs = report.func_run_logs,
+                                "pruned expired func runs",
+                            );
+                        }
+                        Err(err) => {
+                            error!(si.error.message = ?err, "failed to prune func runs");
+                        }
+                    }
+                }
+            }
+        }
+
+        debug!("func run pruner shutdown complete");
+    }
+}
@@ -0,0 +1,264 @@
+use std::{collections::HashMap, fmt, path::PathBuf, sync::Arc};
+
+use cyclone_client::{
+    Client, ClientConfig, ClientError, CycloneClient, CycloneRequest, CycloneRequestable,
+    FunctionResult, OutputStream, ProgressMessage, SensitiveStrings, UdsClient,
+};
+use dal::{
+    func::replay::{FuncRunReplay, FuncRunReplayError, FuncRunReplayRequest},
+    ServicesContext, Tenancy, TransactionsError,
+};
+use futures::TryStreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use si_events::FuncRunId;
+use telemetry::prelude::*;
+use thiserror::Error;
+
+const COLUMN_WIDTH: usize = 60;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum FuncRunReplayerError {
+    #[error("cyclone client error: {0}")]
+    CycloneClient(#[from] ClientError),
+    #[error("cyclone execution error: {0}")]
+    Execution(String),
+    #[error("func run not found: {0}")]
+    FuncRunNotFound(FuncRunId),
+    #[error("func run replay error: {0}")]
+    FuncRunReplay(#[from] FuncRunReplayError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] si_layer_cache::LayerDbError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+}
+
+type FuncRunReplayerResult<T> = std::result::Result<T, FuncRunReplayerError>;
+
+/// Re-executes a recorded func run against a local cyclone listening on a unix domain socket.
+#[derive(Clone)]
+pub struct FuncRunReplayer {
+    services_context: ServicesContext,
+}
+
+impl FuncRunReplayer {
+    pub fn from_services(services_context: ServicesContext) -> Self {
+        Self { services_context }
+    }
+
+    /// Replays the func run and returns the original and replayed output and results.
+    ///
+    /// `secrets_path` names a JSON file mapping secret names to the values their before functions
+    /// receive. Secrets that aren't in it are replaced with a redacted placeholder.
+    #[instrument(name = "sdf.func_run_replayer.replay", level = "info", skip(self))]
+    pub async fn replay(
+        self,
+        func_run_id: FuncRunId,
+        cyclone_socket: PathBuf,
+        secrets_path: Option<PathBuf>,
+    ) -> FuncRunReplayerResult<FuncRunReplayReport> {
+        let secrets: HashMap<String, serde_json::Value> = match secrets_path {
+            Some(path) => serde_json::from_slice(&tokio::fs::read(path).await?)?,
+            None => HashMap::new(),
+        };
+
+        let mut ctx = self
+            .services_context
+            .into_builder(false)
+            .build_default(None)
+            .await?;
+        let func_run = ctx
+            .layer_db()
+            .func_run()
+            .read(func_run_id)
+            .await?
+            .ok_or(FuncRunReplayerError::FuncRunNotFound(func_run_id))?;
+        ctx.update_tenancy(Tenancy::new(func_run.workspace_pk()));
+        ctx.update_visibility_and_snapshot_to_visibility(func_run.change_set_id())
+            .await?;
+
+        let replay = FuncRunReplay::load(&ctx, func_run_id, &secrets).await?;
+        let (replay_logs, replay_result) = execute_replay(&replay, cyclone_socket).await?;
+
+        Ok(FuncRunReplayReport {
+            function_name: replay.func_run.function_name().to_owned(),
+            handler: replay.handler,
+            redacted_secrets: replay.redacted_secrets,
+            code_from_current_func: replay.code_from_current_func,
+            original_logs: replay
+                .original_logs
+                .iter()
+                .map(|line| line.message.clone())
+                .collect(),
+            original_result: replay.original_result,
+            replay_logs,
+            replay_result,
+        })
+    }
+}
+
+/// The original and replayed output and results of a func run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunReplayReport {
+    pub function_name: String,
+    /// The func's current handler, since func runs don't record the one they invoked.
+    pub handler: String,
+    pub redacted_secrets: Vec<String>,
+    pub code_from_current_func: bool,
+    pub original_logs: Vec<String>,
+    pub original_result: Option<serde_json::Value>,
+    pub replay_logs: Vec<String>,
+    pub replay_result: serde_json::Value,
+}
+
+impl fmt::Display for FuncRunReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "replayed {}", self.function_name)?;
+        writeln!(
+            f,
+            "handler: {} (the func's current handler, func runs don't record theirs)",
+            self.handler
+        )?;
+        writeln!(
+            f,
+            "before functions: taken from the component's current secrets, not the recording"
+        )?;
+        if self.code_from_current_func {
+            writeln!(
+                f,
+                "warning: recorded code not found, replayed the func's current code"
+            )?;
+        } else {
+            writeln!(f, "code and arguments: replayed as recorded")?;
+        }
+        if !self.redacted_secrets.is_empty() {
+            writeln!(f, "redacted secrets: {}", self.redacted_secrets.join(", "))?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{:<COLUMN_WIDTH$} | replay", "original")?;
+        writeln!(
+            f,
+            "{}-+-{}",
+            "-".repeat(COLUMN_WIDTH),
+            "-".repeat(COLUMN_WIDTH)
+        )?;
+        let rows = self.original_logs.len().max(self.replay_logs.len());
+        for row in 0..rows {
+            let original = self
+                .original_logs
+                .get(row)
+                .map(String::as_str)
+                .unwrap_or("");
+            let replay = self.replay_logs.get(row).map(String::as_str).unwrap_or("");
+            writeln!(f, "{original:<COLUMN_WIDTH$} | {replay}")?;
+        }
+
+        let original_result = match &self.original_result {
+            Some(value) => serde_json::to_string_pretty(value).map_err(|_| fmt::Error)?,
+            None => "(none)".to_owned(),
+        };
+        let replay_result =
+            serde_json::to_string_pretty(&self.replay_result).map_err(|_| fmt::Error)?;
+        writeln!(f)?;
+        writeln!(f, "original result:\n{original_result}")?;
+        writeln!(f, "replay result:\n{replay_result}")?;
+        write!(
+            f,
+            "results {}",
+            if self.original_result.as_ref() == Some(&self.replay_result) {
+                "match"
+            } else {
+                "differ"
+            }
+        )
+    }
+}
+
+async fn execute_replay(
+    replay: &FuncRunReplay,
+    cyclone_socket: PathBuf,
+) -> FuncRunReplayerResult<(Vec<String>, serde_json::Value)> {
+    let mut client = Client::uds(cyclone_socket, Arc::new(ClientConfig::default()))?;
+    let sensitive_strings = replay.sensitive_strings.clone();
+
+    let (output, result) = match replay.request.clone() {
+        FuncRunReplayRequest::Action(request) => {
+            execute(&mut client, request, sensitive_strings).await?
+        }
+        FuncRunReplayRequest::Management(request) => {
+            execute(&mut client, request, sensitive_strings).await?
+        }
+        FuncRunReplayRequest::Resolver(request) => {
+            let (output, result) = execute(&mut client, request, sensitive_strings).await?;
+            // The resolver's data is what gets recorded as the func run's result.
+            let result = match result {
+                serde_json::Value::Object(mut success) if success.contains_key("data") => {
+                    success.remove("data").unwrap_or_default()
+                }
+                other => other,
+            };
+            (output, result)
+        }
+        FuncRunReplayRequest::SchemaVariantDefinition(request) => {
+            execute(&mut client, request, sensitive_strings).await?
+        }
+        FuncRunReplayRequest::Validation(request) => {
+            execute(&mut client, request, sensitive_strings).await?
+        }
+    };
+
+    Ok((
+        output.into_iter().map(|line| line.message).collect(),
+        result,
+    ))
+}
+
+async fn execute<Request>(
+    client: &mut UdsClient,
+    request: Request,
+    sensitive_strings: SensitiveStrings,
+) -> FuncRunReplayerResult<(Vec<OutputStream>, serde_json::Value)>
+where
+    Request: CycloneRequestable + Serialize + Send + Sync,
+    Request::Response: Serialize + DeserializeOwned + std::fmt::Debug + std::marker::Unpin,
+{
+    let mut progress = client
+        .prepare_execution(CycloneRequest::from_parts(request, sensitive_strings))
+        .await?
+        .start()
+        .await
+        .map_err(|err| FuncRunReplayerError::Execution(err.to_string()))?;
+
+    let mut output = Vec::new();
+    while let Some(message) = progress
+        .try_next()
+        .await
+        .map_err(|err| FuncRunReplayerError::Execution(err.to_string()))?
+    {
+        if let ProgressMessage::OutputStream(line) = message {
+            output.push(line);
+        }
+    }
+
+    let result = match progress
+        .finish()
+        .await
+        .map_err(|err| FuncRunReplayerError::Execution(err.to_string()))?
+    {
+        FunctionResult::Success(success) => serde_json::to_value(success)?,
+        FunctionResult::Failure(failure) => serde_json::json!({
+            "error": {
+                "kind": failure.error().kind,
+                "message": failure.error().message,
+            }
+        }),
+    };
+
+    Ok((output, result))
+}
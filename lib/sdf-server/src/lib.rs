@@ -22,12 +22,16 @@ mod app;
 mod app_state;
 mod config;
 mod extract;
+mod func_run_pruner;
+mod func_run_replay;
 mod init;
 pub mod middleware;
 mod migrations;
 mod nats_multiplexer;
+mod operator;
 mod routes;
 mod runnable;
+mod scheduled_apply;
 mod server;
 pub mod service;
 mod tracking;
@@ -41,8 +45,10 @@ pub use self::{
         Config, ConfigBuilder, ConfigError, ConfigFile, IncomingStream, MigrationMode,
         StandardConfig, StandardConfigFile, WorkspacePermissions, WorkspacePermissionsMode,
     },
+    func_run_replay::{FuncRunReplayReport, FuncRunReplayer, FuncRunReplayerError},
     migrations::Migrator,
     nats_multiplexer::CRDT_MULTIPLEXER_SUBJECT,
+    operator::{Operator, OperatorError, WorkspaceInspection},
     server::{Server, ServerMetadata, ServerSocket},
 };
 pub(crate) use self::{
@@ -1,3 +1,5 @@
+mod token_permission;
 mod workspace_permission;
 
+pub use self::token_permission::{TokenPermission, TokenPermissionLayer};
 pub use self::workspace_permission::{WorkspacePermission, WorkspacePermissionLayer};
@@ -0,0 +1,75 @@
+use std::task::{Context, Poll};
+
+use axum::{
+    body::Body,
+    http::Request,
+    response::{IntoResponse, Response},
+};
+use futures::future::BoxFuture;
+use si_jwt_public_key::SiJwtPermission;
+use tower::{Layer, Service};
+
+use crate::extract::workspace::require_token_permission;
+
+/// Declares the permission a scoped automation token needs to use a route.
+///
+/// Routes that don't declare one only need [`SiJwtPermission::ReadOnly`], so every route that accepts automation
+/// tokens and changes anything must declare its permission.
+#[derive(Clone, Copy)]
+pub struct TokenPermissionLayer {
+    permission: SiJwtPermission,
+}
+
+impl TokenPermissionLayer {
+    pub fn new(permission: SiJwtPermission) -> Self {
+        Self { permission }
+    }
+}
+
+impl<S> Layer<S> for TokenPermissionLayer {
+    type Service = TokenPermission<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TokenPermission {
+            inner,
+            permission: self.permission,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TokenPermission<S> {
+    inner: S,
+    permission: SiJwtPermission,
+}
+
+impl<S> Service<Request<Body>> for TokenPermission<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let mut me = self.clone();
+
+        Box::pin(async move {
+            let (mut parts, body) = req.into_parts();
+
+            if let Err(err) = require_token_permission(&mut parts, me.permission) {
+                return Ok(err.into_response());
+            }
+
+            let req = Request::from_parts(parts, body);
+
+            let response = me.inner.call(req).await?;
+            Ok(response)
+        })
+    }
+}
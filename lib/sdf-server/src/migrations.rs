@@ -2,10 +2,12 @@ use std::future::IntoFuture as _;
 
 use audit_database::{
     AuditDatabaseContext, AuditDatabaseContextError, AuditDatabaseMigrationError,
+    WebhookEndpointRow,
 };
 use dal::{
-    cached_module::CachedModule, slow_rt::SlowRuntimeError,
-    workspace_snapshot::migrator::SnapshotGraphMigrator, ServicesContext,
+    builtins::func::migrate_missing_intrinsics_for_all_workspaces, cached_module::CachedModule,
+    slow_rt::SlowRuntimeError, workspace_integrations::WorkspaceIntegration,
+    workspace_snapshot::migrator::SnapshotGraphMigrator, ServicesContext, WorkspacePk,
 };
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -29,10 +31,16 @@ pub enum MigratorError {
     MigrateCachedModules(#[source] Box<dyn std::error::Error + 'static + Sync + Send>),
     #[error("error while migrating dal database: {0}")]
     MigrateDalDatabase(#[source] dal::ModelError),
+    #[error("error while migrating intrinsic funcs: {0}")]
+    MigrateIntrinsics(#[source] Box<dyn std::error::Error + 'static + Sync + Send>),
     #[error("error while migrating layer db database: {0}")]
     MigrateLayerDbDatabase(#[source] si_layer_cache::LayerDbError),
     #[error("error while migrating snapshots: {0}")]
     MigrateSnapshots(#[source] Box<dyn std::error::Error + 'static + Sync + Send>),
+    #[error("error while migrating workspace integration webhooks: {0}")]
+    MigrateWorkspaceIntegrationWebhooks(
+        #[source] Box<dyn std::error::Error + 'static + Sync + Send>,
+    ),
     #[error("module index url not set")]
     ModuleIndexNotSet,
     #[error("slow runtime: {0}")]
@@ -47,6 +55,20 @@ impl MigratorError {
         Self::MigrateSnapshots(Box::new(err))
     }
 
+    fn migrate_intrinsics<E>(err: E) -> Self
+    where
+        E: std::error::Error + 'static + Sync + Send,
+    {
+        Self::MigrateIntrinsics(Box::new(err))
+    }
+
+    fn migrate_workspace_integration_webhooks<E>(err: E) -> Self
+    where
+        E: std::error::Error + 'static + Sync + Send,
+    {
+        Self::MigrateWorkspaceIntegrationWebhooks(Box::new(err))
+    }
+
     fn migrate_cached_modules<E>(err: E) -> Self
     where
         E: std::error::Error + 'static + Sync + Send,
@@ -123,6 +145,14 @@ impl Migrator {
             .await
             .map_err(|err| span.record_err(err))?;
 
+        self.migrate_intrinsics()
+            .await
+            .map_err(|err| span.record_err(err))?;
+
+        self.migrate_workspace_integration_webhooks()
+            .await
+            .map_err(|err| span.record_err(err))?;
+
         if update_module_cache {
             self.migrate_module_cache()
                 .await
@@ -173,12 +203,66 @@ impl Migrator {
             .migrate_all(&ctx)
             .await
             .map_err(MigratorError::migrate_snapshots)?;
+        migrator
+            .migrate_all_to_chunked_storage(&ctx)
+            .await
+            .map_err(MigratorError::migrate_snapshots)?;
         ctx.commit_no_rebase()
             .await
             .map_err(MigratorError::migrate_snapshots)?;
         Ok(())
     }
 
+    #[instrument(name = "sdf.migrator.migrate_intrinsics", level = "info", skip_all)]
+    async fn migrate_intrinsics(&self) -> MigratorResult<()> {
+        let dal_context = self.services_context.clone().into_builder(true);
+        let ctx = dal_context
+            .build_default(None)
+            .await
+            .map_err(MigratorError::migrate_intrinsics)?;
+
+        migrate_missing_intrinsics_for_all_workspaces(&ctx)
+            .await
+            .map_err(MigratorError::migrate_intrinsics)?;
+        ctx.commit_no_rebase()
+            .await
+            .map_err(MigratorError::migrate_intrinsics)?;
+        Ok(())
+    }
+
+    /// Ensures that every workspace integration with a Slack webhook URL has the webhook endpoint
+    /// that forklift delivers its notifications through.
+    #[instrument(
+        name = "sdf.migrator.migrate_workspace_integration_webhooks",
+        level = "info",
+        skip_all
+    )]
+    async fn migrate_workspace_integration_webhooks(&self) -> MigratorResult<()> {
+        let dal_context = self.services_context.clone().into_builder(true);
+        let ctx = dal_context
+            .build_default(None)
+            .await
+            .map_err(MigratorError::migrate_workspace_integration_webhooks)?;
+
+        let integrations = WorkspaceIntegration::list_with_slack_webhook_url(&ctx)
+            .await
+            .map_err(MigratorError::migrate_workspace_integration_webhooks)?;
+        for integration in integrations {
+            let Some(slack_webhook_url) = integration.slack_webhook_url() else {
+                continue;
+            };
+            WebhookEndpointRow::upsert_for_workspace_integration(
+                &self.audit_database_context,
+                WorkspacePk::from_raw_id(integration.workspace_pk().into_raw_id()),
+                slack_webhook_url,
+            )
+            .await
+            .map_err(MigratorError::migrate_workspace_integration_webhooks)?;
+        }
+
+        Ok(())
+    }
+
     #[instrument(name = "sdf.migrator.migrate_module_cache", level = "info", skip_all)]
     async fn migrate_module_cache(&self) -> MigratorResult<()> {
         let dal_context = self.services_context.clone().into_builder(true);
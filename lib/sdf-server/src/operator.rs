@@ -0,0 +1,284 @@
+use std::{future::IntoFuture as _, path::PathBuf, sync::Arc};
+
+use dal::{
+    cached_module::{CachedModule, CachedModuleError},
+    func::runner::{FuncRunner, FuncRunnerError},
+    workspace_snapshot::{chunked, graph::WorkspaceSnapshotGraph},
+    ChangeSet, ChangeSetId, DalContext, ServicesContext, Tenancy, TransactionsError, Workspace,
+    WorkspaceError, WorkspacePk, WorkspaceSnapshotAddress,
+};
+use serde::{Deserialize, Serialize};
+use si_events::FuncRunId;
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+use crate::{
+    func_run_replay::{FuncRunReplayReport, FuncRunReplayer, FuncRunReplayerError},
+    init,
+    service::v2::admin::{AdminChangeSet, AdminWorkspace},
+    Config,
+};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum OperatorError {
+    #[error("cached module error: {0}")]
+    CachedModule(#[from] CachedModuleError),
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] dal::ChangeSetError),
+    #[error("chunked snapshot error: {0}")]
+    ChunkedSnapshot(#[from] chunked::ChunkedSnapshotError),
+    #[error("func runner error: {0}")]
+    FuncRunner(#[from] FuncRunnerError),
+    #[error("func run replayer error: {0}")]
+    FuncRunReplayer(#[from] FuncRunReplayerError),
+    #[error("error while initializing: {0}")]
+    Init(#[from] init::InitError),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] si_layer_cache::LayerDbError),
+    #[error("tokio join error: {0}")]
+    TokioJoin(#[from] tokio::task::JoinError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
+    #[error("workspace error: {0}")]
+    Workspace(#[from] WorkspaceError),
+    #[error("workspace snapshot {0} for change set {1} could not be found in durable storage")]
+    WorkspaceSnapshotNotFound(WorkspaceSnapshotAddress, ChangeSetId),
+}
+
+type OperatorResult<T> = std::result::Result<T, OperatorError>;
+
+/// A workspace along with all of its change sets.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceInspection {
+    pub workspace: AdminWorkspace,
+    pub change_sets: Vec<AdminChangeSet>,
+}
+
+/// Performs the operations of the admin API directly against the databases and NATS, for
+/// operators who need them when sdf is unavailable or from a script.
+///
+/// Every operation runs as the system user and commits without a rebase, just like its admin API
+/// counterpart.
+#[derive(Clone)]
+pub struct Operator {
+    services_context: ServicesContext,
+}
+
+impl Operator {
+    #[instrument(name = "sdf.operator.init.from_config", level = "info", skip_all)]
+    pub async fn from_config(
+        config: Config,
+        helping_tasks_tracker: &TaskTracker,
+        helping_tasks_token: CancellationToken,
+    ) -> OperatorResult<Self> {
+        let (services_context, layer_db_graceful_shutdown) =
+            init::services_context_from_config(&config, helping_tasks_token).await?;
+
+        helping_tasks_tracker.spawn(layer_db_graceful_shutdown.into_future());
+
+        Ok(Self { services_context })
+    }
+
+    /// Finds up to `limit` workspaces matching the query, the most recently created without one.
+    #[instrument(name = "sdf.operator.search_workspaces", level = "info", skip(self))]
+    pub async fn search_workspaces(
+        &self,
+        query: Option<&str>,
+        limit: usize,
+    ) -> OperatorResult<Vec<AdminWorkspace>> {
+        let ctx = self.ctx(None).await?;
+
+        Ok(Workspace::search(&ctx, query, limit)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    #[instrument(name = "sdf.operator.inspect_workspace", level = "info", skip(self))]
+    pub async fn inspect_workspace(
+        &self,
+        workspace_id: WorkspacePk,
+    ) -> OperatorResult<WorkspaceInspection> {
+        let ctx = self.ctx(Some(workspace_id)).await?;
+
+        let workspace = Workspace::get_by_pk(&ctx, &workspace_id)
+            .await?
+            .ok_or(WorkspaceError::WorkspaceNotFound(workspace_id))?;
+        let change_sets = ChangeSet::list_all_for_workspace(&ctx, workspace_id)
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(WorkspaceInspection {
+            workspace: workspace.into(),
+            change_sets,
+        })
+    }
+
+    /// Returns the serialized workspace snapshot of a change set, in the format
+    /// [`import_snapshot`](Self::import_snapshot) accepts.
+    #[instrument(name = "sdf.operator.export_snapshot", level = "info", skip(self))]
+    pub async fn export_snapshot(
+        &self,
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+    ) -> OperatorResult<Vec<u8>> {
+        let ctx = self.ctx(Some(workspace_id)).await?;
+
+        let snap_addr = ChangeSet::get_by_id(&ctx, change_set_id)
+            .await?
+            .workspace_snapshot_address;
+
+        match ctx
+            .layer_db()
+            .workspace_snapshot()
+            .read_bytes_from_durable_storage(&snap_addr)
+            .await?
+        {
+            Some(bytes) => Ok(bytes),
+            None => {
+                // Chunked snapshots are reassembled and exported whole
+                let graph = chunked::read(ctx.layer_db(), snap_addr).await?.ok_or(
+                    OperatorError::WorkspaceSnapshotNotFound(snap_addr, change_set_id),
+                )?;
+                Ok(tokio::task::spawn_blocking(move || {
+                    si_layer_cache::db::serialize::to_vec(&WorkspaceSnapshotGraph::V4(graph))
+                })
+                .await??
+                .0)
+            }
+        }
+    }
+
+    /// Replaces the workspace snapshot of a change set, returning the address it was written to.
+    #[instrument(
+        name = "sdf.operator.import_snapshot",
+        level = "info",
+        skip(self, bytes),
+        fields(si.workspace_snapshot.address = Empty),
+    )]
+    pub async fn import_snapshot(
+        &self,
+        workspace_id: WorkspacePk,
+        change_set_id: ChangeSetId,
+        bytes: Vec<u8>,
+    ) -> OperatorResult<WorkspaceSnapshotAddress> {
+        let span = current_span_for_instrument_at!("info");
+        let ctx = self.ctx(Some(workspace_id)).await?;
+
+        let mut change_set = ChangeSet::get_by_id(&ctx, change_set_id).await?;
+
+        let bytes = Arc::new(bytes);
+        let data_clone = bytes.clone();
+        let workspace_snapshot_address = tokio::task::spawn_blocking(move || {
+            // We do this to make sure the snapshot is valid before pointing a change set at it
+            si_layer_cache::db::serialize::from_bytes::<Arc<WorkspaceSnapshotGraph>>(&data_clone)?;
+            Ok::<_, OperatorError>(WorkspaceSnapshotAddress::new(&data_clone))
+        })
+        .await??;
+
+        span.record(
+            "si.workspace_snapshot.address",
+            workspace_snapshot_address.to_string(),
+        );
+
+        // The bytes are written as given, so that the address matches the one computed from the
+        // exported file
+        ctx.layer_db()
+            .workspace_snapshot()
+            .write_bytes_to_durable_storage(&workspace_snapshot_address, &bytes)
+            .await?;
+
+        change_set
+            .update_pointer(&ctx, workspace_snapshot_address)
+            .await?;
+
+        ctx.commit_no_rebase().await?;
+
+        Ok(workspace_snapshot_address)
+    }
+
+    /// Sets the component concurrency limit of a workspace, or resets it to the default with
+    /// `None`, returning the limit now in effect.
+    #[instrument(
+        name = "sdf.operator.set_concurrency_limit",
+        level = "info",
+        skip(self)
+    )]
+    pub async fn set_concurrency_limit(
+        &self,
+        workspace_id: WorkspacePk,
+        concurrency_limit: Option<i32>,
+    ) -> OperatorResult<Option<i32>> {
+        let ctx = self.ctx(Some(workspace_id)).await?;
+
+        let mut workspace = Workspace::get_by_pk(&ctx, &workspace_id)
+            .await?
+            .ok_or(WorkspaceError::WorkspaceNotFound(workspace_id))?;
+        workspace
+            .set_component_concurrency_limit(&ctx, concurrency_limit)
+            .await?;
+
+        ctx.commit_no_rebase().await?;
+
+        Ok(workspace.raw_component_concurrency_limit())
+    }
+
+    #[instrument(name = "sdf.operator.kill_execution", level = "info", skip(self))]
+    pub async fn kill_execution(&self, func_run_id: FuncRunId) -> OperatorResult<()> {
+        let ctx = self.ctx(None).await?;
+
+        FuncRunner::kill_execution(&ctx, func_run_id).await?;
+
+        // We commit without a rebase here because we need to commit our func run table changes.
+        ctx.commit_no_rebase().await?;
+
+        Ok(())
+    }
+
+    /// Replays a recorded func run against the cyclone listening on `cyclone_socket` and reports
+    /// its output and result beside the original's.
+    ///
+    /// `secrets_path` names a JSON file mapping secret names to the values their before functions
+    /// receive. Secrets that aren't in it are redacted.
+    #[instrument(name = "sdf.operator.replay_func_run", level = "info", skip(self))]
+    pub async fn replay_func_run(
+        &self,
+        func_run_id: FuncRunId,
+        cyclone_socket: PathBuf,
+        secrets_path: Option<PathBuf>,
+    ) -> OperatorResult<FuncRunReplayReport> {
+        Ok(
+            FuncRunReplayer::from_services(self.services_context.clone())
+                .replay(func_run_id, cyclone_socket, secrets_path)
+                .await?,
+        )
+    }
+
+    /// Caches any builtin modules from the module index that are not cached yet, returning them.
+    #[instrument(name = "sdf.operator.update_module_cache", level = "info", skip(self))]
+    pub async fn update_module_cache(&self) -> OperatorResult<Vec<CachedModule>> {
+        let ctx = self.ctx(None).await?;
+
+        Ok(CachedModule::update_cached_modules(&ctx).await?)
+    }
+
+    async fn ctx(&self, workspace_id: Option<WorkspacePk>) -> OperatorResult<DalContext> {
+        let mut ctx = self
+            .services_context
+            .clone()
+            .into_builder(false)
+            .build_default(None)
+            .await?;
+        if let Some(workspace_id) = workspace_id {
+            ctx.update_tenancy(Tenancy::new(workspace_id));
+        }
+
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,54 @@
+//! Applies change sets whose [scheduled apply](dal::change_set::schedule) window has opened.
+
+use std::time::Duration;
+
+use dal::{change_set::schedule::ScheduledApply, DalContextBuilder, ServicesContext};
+use telemetry::prelude::*;
+use tokio_util::sync::CancellationToken;
+
+/// How often to look for schedules whose window has opened.
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub(crate) struct ScheduledApplyRunner {
+    builder: DalContextBuilder,
+    token: CancellationToken,
+}
+
+impl ScheduledApplyRunner {
+    pub(crate) fn new(services_context: ServicesContext, token: CancellationToken) -> Self {
+        Self {
+            builder: services_context.into_builder(false),
+            token,
+        }
+    }
+
+    pub(crate) async fn run(self) {
+        let mut check = tokio::time::interval(CHECK_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = self.token.cancelled() => break,
+                _ = check.tick() => {
+                    match ScheduledApply::run_due(&self.builder).await {
+                        Ok(finished) => {
+                            for scheduled in finished {
+                                info!(
+                                    si.change_set.id = %scheduled.change_set_id,
+                                    si.workspace.id = %scheduled.workspace_pk,
+                                    status = %scheduled.status,
+                                    message = scheduled.message.as_deref().unwrap_or_default(),
+                                    "finished scheduled apply",
+                                );
+                            }
+                        }
+                        Err(err) => {
+                            error!(si.error.message = ?err, "failed to run scheduled applies");
+                        }
+                    }
+                }
+            }
+        }
+
+        debug!("scheduled apply runner shutdown complete");
+    }
+}
@@ -19,9 +19,11 @@ use tokio::{
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 use crate::{
+    func_run_pruner::FuncRunPruner,
     init,
     nats_multiplexer::{CRDT_MULTIPLEXER_SUBJECT, WS_MULTIPLEXER_SUBJECT},
     runnable::Runnable,
+    scheduled_apply::ScheduledApplyRunner,
     uds::UdsIncomingStream,
     ApplicationRuntimeMode, AxumApp, Config, IncomingStream, Migrator, ServerError, ServerResult,
     WorkspacePermissions, WorkspacePermissionsMode,
@@ -101,7 +103,7 @@ impl Server {
         let (crdt_multiplexer, crdt_multiplexer_client) = Multiplexer::new(
             services_context.nats_conn(),
             CRDT_MULTIPLEXER_SUBJECT,
-            helping_tasks_token,
+            helping_tasks_token.clone(),
         )
         .await?;
 
@@ -130,6 +132,13 @@ impl Server {
         helping_tasks_tracker.spawn(posthog_sender.run());
         helping_tasks_tracker.spawn(ws_multiplexer.run());
         helping_tasks_tracker.spawn(crdt_multiplexer.run());
+        helping_tasks_tracker.spawn(
+            ScheduledApplyRunner::new(services_context.clone(), helping_tasks_token.clone()).run(),
+        );
+        if config.prune_func_runs() {
+            helping_tasks_tracker
+                .spawn(FuncRunPruner::new(services_context.clone(), helping_tasks_token).run());
+        }
 
         let audit_database_context = AuditDatabaseContext::from_config(config.audit()).await?;
 
@@ -68,6 +68,9 @@ impl IntoResponse for ActionError {
     fn into_response(self) -> Response {
         let (status_code, error_message) = match self {
             ActionError::InvalidOnHoldTransition(_) => (StatusCode::NOT_MODIFIED, self.to_string()),
+            ActionError::Action(dal::action::ActionError::Component(
+                dal::ComponentError::ComponentProtected(_),
+            )) => (StatusCode::PRECONDITION_FAILED, self.to_string()),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -82,6 +82,9 @@ impl IntoResponse for ChangeSetError {
                 (StatusCode::NOT_FOUND, self.to_string())
             }
             ChangeSetError::DalChangeSetApply(_) => (StatusCode::CONFLICT, self.to_string()),
+            ChangeSetError::Action(ActionError::Component(
+                dal::ComponentError::ComponentProtected(_),
+            )) => (StatusCode::PRECONDITION_FAILED, self.to_string()),
             ChangeSetError::DvuRootsNotEmpty(_) => (
                 StatusCode::PRECONDITION_REQUIRED,
                 "There are dependent values that still need to be calculated. Please retry!"
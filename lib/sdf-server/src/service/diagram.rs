@@ -140,6 +140,9 @@ impl IntoResponse for DiagramError {
             DiagramError::Component(ComponentError::ComponentAlreadyInView(_, _)) => {
                 StatusCode::UNPROCESSABLE_ENTITY
             }
+            DiagramError::Component(ComponentError::ComponentProtected(_)) => {
+                StatusCode::PRECONDITION_FAILED
+            }
             DiagramError::Component(ComponentError::Diagram(e)) => match *e {
                 dal::diagram::DiagramError::DeletingLastGeometryForComponent(_, _) => {
                     StatusCode::UNPROCESSABLE_ENTITY
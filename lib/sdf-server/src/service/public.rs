@@ -1,15 +1,139 @@
-use axum::Router;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use telemetry::prelude::*;
 
 use crate::AppState;
 
+use self::openapi::{object_schema, ApiRouter, ApiSchema, SchemaRegistry};
+
+mod actions;
 mod change_sets;
 mod components;
+pub mod events;
+mod funcs;
 mod management;
+mod openapi;
 mod workspaces;
 
+const SERVER_URL: &str = "/api/public";
+
 pub fn routes(state: AppState) -> Router<AppState> {
+    let (v0, operations) = ApiRouter::new("Workspaces")
+        .nest("/workspaces", workspaces::routes(state))
+        .into_parts();
+
+    // The document only depends on the route definitions, so it is generated once up front.
+    let document = openapi::document(
+        &format!("{SERVER_URL}/v0"),
+        &operations,
+        PublicApiErrorBody::schema,
+    );
+
     Router::new().nest(
         "/v0",
-        Router::new().nest("/workspaces", workspaces::routes(state)),
+        v0.route("/openapi.json", get(move || async move { Json(document) })),
+    )
+}
+
+/// Declares request and response types for the public API. Each type is serialized in camelCase and implements
+/// [`ApiSchema`] from the same field list, so the OpenAPI document can't drift from what the handlers accept.
+macro_rules! api_types {
+    ($(
+        $(#[doc = $doc:literal])*
+        pub struct $name:ident {
+            $($(#[doc = $field_doc:literal])* pub $field:ident: $ty:ty),* $(,)?
+        }
+    )*) => {
+        $(
+            $(#[doc = $doc])*
+            #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+            #[serde(rename_all = "camelCase")]
+            pub struct $name {
+                $($(#[doc = $field_doc])* pub $field: $ty),*
+            }
+
+            impl $crate::service::public::openapi::ApiSchema for $name {
+                fn schema(
+                    registry: &mut $crate::service::public::openapi::SchemaRegistry,
+                ) -> serde_json::Value {
+                    registry.reference(stringify!($name), |_registry| {
+                        $crate::service::public::openapi::object_schema(vec![
+                            $((
+                                stringify!($field),
+                                <$ty as $crate::service::public::openapi::ApiSchema>::schema(_registry),
+                                <$ty as $crate::service::public::openapi::ApiSchema>::REQUIRED,
+                            )),*
+                        ])
+                    })
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use api_types;
+
+/// The body of every error returned by the public API.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicApiErrorBody {
+    error: PublicApiErrorDetail,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicApiErrorDetail {
+    code: &'static str,
+    message: String,
+    status_code: u16,
+}
+
+impl ApiSchema for PublicApiErrorBody {
+    fn schema(registry: &mut SchemaRegistry) -> Value {
+        registry.reference("ErrorResponse", |registry| {
+            let detail = object_schema(vec![
+                ("code", String::schema(registry), true),
+                ("message", String::schema(registry), true),
+                ("status_code", json!({ "type": "integer" }), true),
+            ]);
+            object_schema(vec![("error", detail, true)])
+        })
+    }
+}
+
+/// Builds the response for a public API error, logging server errors.
+fn error_response(status_code: StatusCode, message: impl ToString) -> Response {
+    let message = message.to_string();
+    if status_code.is_server_error() {
+        error!(si.error.message = message, "public api error");
+    } else {
+        debug!(si.error.message = message, "public api client error");
+    }
+
+    let code = match status_code {
+        StatusCode::BAD_REQUEST => "badRequest",
+        StatusCode::FORBIDDEN => "forbidden",
+        StatusCode::NOT_FOUND => "notFound",
+        StatusCode::CONFLICT => "conflict",
+        StatusCode::PRECONDITION_FAILED => "preconditionFailed",
+        _ => "internalError",
+    };
+
+    (
+        status_code,
+        Json(PublicApiErrorBody {
+            error: PublicApiErrorDetail {
+                code,
+                message,
+                status_code: status_code.as_u16(),
+            },
+        }),
     )
+        .into_response()
 }
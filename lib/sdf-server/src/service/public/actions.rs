@@ -0,0 +1,308 @@
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use dal::{
+    action::{
+        prototype::{ActionKind, ActionPrototype},
+        Action, ActionId, ActionState,
+    },
+    ActionPrototypeId, Component, ComponentId, DalContext, Func, WsEvent,
+};
+use serde::Deserialize;
+use serde_json::json;
+use si_events::{audit_log::AuditLogKind, FuncRunId};
+use si_jwt_public_key::SiJwtPermission;
+use thiserror::Error;
+
+use super::{
+    api_types, error_response,
+    openapi::{ApiRouter, OperationDoc},
+};
+use crate::extract::{change_set::ChangeSetDalContext, PosthogEventTracker};
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ActionsError {
+    #[error("action error: {0}")]
+    Action(#[from] dal::action::ActionError),
+    #[error("action already enqueued: {0}")]
+    ActionAlreadyEnqueued(ActionPrototypeId),
+    #[error("action is {0} and can no longer be cancelled: {1}")]
+    ActionInProgress(ActionState, ActionId),
+    #[error("action not found: {0}")]
+    ActionNotFound(ActionId),
+    #[error("action prototype error: {0}")]
+    ActionPrototype(#[from] dal::action::prototype::ActionPrototypeError),
+    #[error("more than one action matches, specify a prototypeName: {0}")]
+    AmbiguousActionPrototype(ComponentId),
+    #[error("component error: {0}")]
+    Component(#[from] dal::ComponentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("func error: {0}")]
+    Func(#[from] dal::FuncError),
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] si_layer_cache::LayerDbError),
+    #[error("no matching action for component: {0}")]
+    NoMatchingActionPrototype(ComponentId),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] dal::TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] dal::WsEventError),
+}
+
+type Result<T> = std::result::Result<T, ActionsError>;
+
+impl IntoResponse for ActionsError {
+    fn into_response(self) -> Response {
+        let status_code = match &self {
+            Self::ActionNotFound(_) | Self::ComponentNotFound(_) => StatusCode::NOT_FOUND,
+            Self::AmbiguousActionPrototype(_)
+            | Self::InvalidRequest(_)
+            | Self::NoMatchingActionPrototype(_) => StatusCode::BAD_REQUEST,
+            Self::ActionAlreadyEnqueued(_) | Self::ActionInProgress(..) => StatusCode::CONFLICT,
+            Self::Action(dal::action::ActionError::Component(
+                dal::ComponentError::ComponentProtected(_),
+            )) => StatusCode::PRECONDITION_FAILED,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        error_response(status_code, self)
+    }
+}
+
+// /api/public/workspaces/:workspace_id/change-sets/:change_set_id/actions
+pub fn routes() -> ApiRouter {
+    ApiRouter::new("Actions")
+        .get(
+            "/",
+            list_actions,
+            OperationDoc::new("listActions", "List the actions queued in a change set")
+                .response::<ListActionsResponse>(),
+        )
+        .post(
+            "/",
+            enqueue_action,
+            OperationDoc::new("enqueueAction", "Enqueue an action for a component")
+                .request::<EnqueueActionRequest>()
+                .response::<ActionResponse>()
+                .permission(SiJwtPermission::ActionExecute),
+        )
+        .delete(
+            "/:action_id",
+            cancel_action,
+            OperationDoc::new(
+                "cancelAction",
+                "Cancel an action that has not started running",
+            )
+            .response::<ActionResponse>()
+            .permission(SiJwtPermission::ActionExecute),
+        )
+}
+
+api_types! {
+    pub struct ListActionsResponse {
+        pub actions: Vec<ActionView>,
+    }
+
+    pub struct ActionResponse {
+        pub action: ActionView,
+    }
+
+    pub struct ActionView {
+        pub id: ActionId,
+        pub prototype_id: ActionPrototypeId,
+        pub component_id: Option<ComponentId>,
+        pub name: String,
+        pub kind: ActionKind,
+        pub state: ActionState,
+        /// The most recent run of this action, if it has been dispatched.
+        pub func_run_id: Option<FuncRunId>,
+    }
+
+    pub struct EnqueueActionRequest {
+        pub component_id: ComponentId,
+        /// Selects the action by kind. Required unless `prototypeName` is given.
+        pub kind: Option<ActionKind>,
+        /// Selects the action by name, which is needed when a component has several manual actions.
+        pub prototype_name: Option<String>,
+    }
+}
+
+#[derive(Deserialize)]
+struct ActionPath {
+    action_id: ActionId,
+}
+
+async fn action_view(ctx: &DalContext, action_id: ActionId) -> Result<ActionView> {
+    let action = Action::get_by_id(ctx, action_id).await?;
+    let prototype_id = Action::prototype_id(ctx, action_id).await?;
+    let prototype = ActionPrototype::get_by_id(ctx, prototype_id).await?;
+    let func_run_id = ctx
+        .layer_db()
+        .func_run()
+        .get_last_run_for_action_id(ctx.events_tenancy().workspace_pk, action_id)
+        .await?
+        .map(|func_run| func_run.id());
+
+    Ok(ActionView {
+        id: action_id,
+        prototype_id,
+        component_id: Action::component_id(ctx, action_id).await?,
+        name: prototype.name().clone(),
+        kind: prototype.kind,
+        state: action.state(),
+        func_run_id,
+    })
+}
+
+async fn list_actions(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+) -> Result<Json<ListActionsResponse>> {
+    let mut actions = Vec::new();
+    for action_id in Action::list_topologically(&ctx).await? {
+        actions.push(action_view(&ctx, action_id).await?);
+    }
+
+    Ok(Json(ListActionsResponse { actions }))
+}
+
+async fn enqueue_action(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Json(payload): Json<EnqueueActionRequest>,
+) -> Result<Json<ActionResponse>> {
+    let component_id = payload.component_id;
+    let component = Component::try_get_by_id(&ctx, component_id)
+        .await?
+        .ok_or(ActionsError::ComponentNotFound(component_id))?;
+
+    if payload.kind.is_none() && payload.prototype_name.is_none() {
+        return Err(ActionsError::InvalidRequest(
+            "one of kind or prototypeName is required".into(),
+        ));
+    }
+
+    let schema_variant_id = component.schema_variant(&ctx).await?.id();
+    let mut prototypes: Vec<ActionPrototype> =
+        ActionPrototype::for_variant(&ctx, schema_variant_id)
+            .await?
+            .into_iter()
+            .filter(|prototype| payload.kind.map_or(true, |kind| prototype.kind == kind))
+            .filter(|prototype| {
+                payload
+                    .prototype_name
+                    .as_ref()
+                    .map_or(true, |name| prototype.name() == name)
+            })
+            .collect();
+    let prototype = match prototypes.len() {
+        0 => return Err(ActionsError::NoMatchingActionPrototype(component_id)),
+        1 => prototypes.remove(0),
+        _ => return Err(ActionsError::AmbiguousActionPrototype(component_id)),
+    };
+
+    match prototype.kind {
+        ActionKind::Create | ActionKind::Destroy | ActionKind::Update | ActionKind::Refresh => {
+            let maybe_duplicate_action =
+                Action::find_for_kind_and_component_id(&ctx, component_id, prototype.kind).await?;
+            if !maybe_duplicate_action.is_empty() {
+                return Err(ActionsError::ActionAlreadyEnqueued(prototype.id()));
+            }
+        }
+        ActionKind::Manual => {}
+    }
+
+    let action = Action::new(&ctx, prototype.id(), Some(component_id)).await?;
+    let func_id = ActionPrototype::func_id(&ctx, prototype.id()).await?;
+    let func = Func::get_by_id_or_error(&ctx, func_id).await?;
+
+    tracker.track(
+        &ctx,
+        "create_action_v2",
+        json!({
+            "how": "/public/action/enqueue",
+            "action_id": action.id(),
+            "action_kind": prototype.kind,
+            "component_id": component_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.write_audit_log(
+        AuditLogKind::AddAction {
+            prototype_id: prototype.id(),
+            action_kind: prototype.kind.into(),
+            func_id,
+            func_display_name: func.display_name,
+            func_name: func.name.clone(),
+        },
+        func.name,
+    )
+    .await?;
+
+    WsEvent::action_list_updated(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    let view = action_view(&ctx, action.id()).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(ActionResponse { action: view }))
+}
+
+async fn cancel_action(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path(ActionPath { action_id }): Path<ActionPath>,
+) -> Result<Json<ActionResponse>> {
+    if !Action::all_ids(&ctx).await?.contains(&action_id) {
+        return Err(ActionsError::ActionNotFound(action_id));
+    }
+
+    let view = action_view(&ctx, action_id).await?;
+    if matches!(view.state, ActionState::Dispatched | ActionState::Running) {
+        return Err(ActionsError::ActionInProgress(view.state, action_id));
+    }
+
+    let func_id = ActionPrototype::func_id(&ctx, view.prototype_id).await?;
+    let func = Func::get_by_id_or_error(&ctx, func_id).await?;
+    ctx.write_audit_log(
+        AuditLogKind::CancelAction {
+            prototype_id: view.prototype_id,
+            action_kind: view.kind.into(),
+            func_id,
+            func_display_name: func.display_name,
+            func_name: func.name.clone(),
+        },
+        func.name,
+    )
+    .await?;
+
+    Action::remove_by_id(&ctx, action_id).await?;
+
+    tracker.track(
+        &ctx,
+        "cancel_action",
+        json!({
+            "how": "/public/action/cancel",
+            "action_id": action_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    WsEvent::action_list_updated(&ctx)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(ActionResponse { action: view }))
+}
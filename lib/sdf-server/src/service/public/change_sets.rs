@@ -1,26 +1,54 @@
 use axum::{
-    http::StatusCode,
+    extract::Query,
+    http::{header, StatusCode},
     middleware,
     response::{IntoResponse, Response},
-    routing::post,
-    Json, Router,
+    Json,
 };
-use dal::{change_set::ChangeSet, WsEvent};
-use serde::{Deserialize, Serialize};
-use serde_json::json;
+use chrono::{DateTime, Utc};
+use dal::{
+    action::{
+        plan::{ActionPlan, ActionPlanDependency, ActionPlanEntry, HeldAction},
+        prototype::ActionKind,
+        ActionId, ActionState,
+    },
+    change_set::ChangeSet,
+    component::fragment::{ComponentFragment, ComponentFragmentError},
+    ActionPrototypeId, ChangeSetId, ChangeSetStatus, ComponentId, DalContext, WsEvent,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
 use si_events::audit_log::AuditLogKind;
+use si_jwt_public_key::SiJwtPermission;
 use thiserror::Error;
 
+use super::{
+    api_types, error_response,
+    openapi::{ApiRouter, OperationDoc},
+};
 use crate::extract::{
-    change_set::TargetChangeSetIdFromPath, workspace::WorkspaceDalContext, PosthogEventTracker,
+    change_set::{ChangeSetDalContext, TargetChangeSetIdFromPath},
+    workspace::WorkspaceDalContext,
+    PosthogEventTracker,
 };
-use crate::AppState;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum ChangeSetsError {
+    #[error("action error: {0}")]
+    Action(#[from] dal::action::ActionError),
+    #[error("cannot abandon the HEAD change set")]
+    CannotAbandonHead,
+    #[error("change set apply error: {0}")]
+    ChangeSetApply(#[from] dal::ChangeSetApplyError),
+    #[error("component fragment error: {0}")]
+    ComponentFragment(#[from] ComponentFragmentError),
     #[error("dal change set error: {0}")]
     DalChangeSet(#[from] dal::ChangeSetError),
+    #[error("invalid component fragment: {0}")]
+    InvalidFragment(serde_json::Error),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("transactions error: {0}")]
     Transactions(#[from] dal::TransactionsError),
     #[error("ws event error: {0}")]
@@ -31,26 +59,268 @@ type Result<T> = std::result::Result<T, ChangeSetsError>;
 
 impl IntoResponse for ChangeSetsError {
     fn into_response(self) -> Response {
-        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+        let status_code = match &self {
+            Self::CannotAbandonHead
+            | Self::InvalidFragment(_)
+            | Self::ComponentFragment(ComponentFragmentError::InvalidPosition(..)) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::ChangeSetApply(_)
+            | Self::DalChangeSet(dal::ChangeSetError::ChangeSetNotApprovedForApply(_)) => {
+                StatusCode::CONFLICT
+            }
+            Self::DalChangeSet(
+                dal::ChangeSetError::ApprovalRulesNotSatisfied(_)
+                | dal::ChangeSetError::DvuRootsNotEmpty(_)
+                | dal::ChangeSetError::EditedSinceApproval(_)
+                | dal::ChangeSetError::PolicyChecksFailed(_),
+            ) => StatusCode::PRECONDITION_FAILED,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        error_response(status_code, self)
     }
 }
 
 // /api/public/workspaces/:workspace_id/change-sets
-pub fn routes() -> Router<AppState> {
-    Router::new().route("/", post(create_change_set)).nest(
-        "/:change_set_id",
-        Router::new()
-            .nest("/components", super::components::routes())
-            .nest("/management", super::management::routes())
-            .route_layer(middleware::from_extractor::<TargetChangeSetIdFromPath>()),
-    )
+pub fn routes() -> ApiRouter {
+    ApiRouter::new("Change Sets")
+        .post(
+            "/",
+            create_change_set,
+            OperationDoc::new("createChangeSet", "Create a change set from HEAD")
+                .request::<CreateChangeSetRequest>()
+                .response::<ChangeSetResponse>(),
+        )
+        .post(
+            "/import-components",
+            import_components,
+            OperationDoc::new(
+                "importComponents",
+                "Import a fragment from exportComponents into a new change set",
+            )
+            .request::<ImportComponentsRequest>()
+            .response::<ImportComponentsResponse>(),
+        )
+        .nest(
+            "/:change_set_id",
+            ApiRouter::new("Change Sets")
+                .get(
+                    "/",
+                    get_change_set,
+                    OperationDoc::new("getChangeSet", "Get a change set")
+                        .response::<ChangeSetResponse>(),
+                )
+                .post(
+                    "/request-approval",
+                    request_approval,
+                    OperationDoc::new(
+                        "requestChangeSetApproval",
+                        "Request approval to apply a change set",
+                    )
+                    .response::<ChangeSetResponse>(),
+                )
+                .get(
+                    "/apply-plan",
+                    apply_plan,
+                    OperationDoc::new(
+                        "getApplyPlan",
+                        "Preview the actions that applying a change set will queue on HEAD",
+                    )
+                    .query_param(
+                        "format",
+                        "`json` (the default) or `dot` for a Graphviz digraph of the plan",
+                    )
+                    .response::<ApplyPlanResponse>(),
+                )
+                .post(
+                    "/apply",
+                    apply,
+                    OperationDoc::new("applyChangeSet", "Apply an approved change set to HEAD")
+                        .response::<ChangeSetResponse>()
+                        .permission(SiJwtPermission::Apply),
+                )
+                .post(
+                    "/abandon",
+                    abandon,
+                    OperationDoc::new("abandonChangeSet", "Abandon a change set")
+                        .response::<ChangeSetResponse>(),
+                )
+                .nest("/actions", super::actions::routes())
+                .nest("/components", super::components::routes())
+                .nest("/events", super::events::routes())
+                .nest("/funcs", super::funcs::func_routes())
+                .nest("/func-runs", super::funcs::func_run_routes())
+                .nest("/management", super::management::routes())
+                .map_router(|router| {
+                    router.route_layer(middleware::from_extractor::<TargetChangeSetIdFromPath>())
+                }),
+        )
+}
+
+api_types! {
+    pub struct CreateChangeSetRequest {
+        pub change_set_name: String,
+    }
+
+    pub struct ChangeSetResponse {
+        pub change_set: ChangeSetView,
+    }
+
+    pub struct ImportComponentsRequest {
+        pub change_set_name: String,
+        /// A fragment returned by `exportComponents`.
+        pub fragment: Value,
+    }
+
+    pub struct ImportComponentsResponse {
+        pub change_set: ChangeSetView,
+        /// The new id of each component keyed by its id in the fragment, along with the views
+        /// created and anything that couldn't be mapped onto this workspace's schemas.
+        pub report: Value,
+    }
+
+    pub struct ChangeSetView {
+        pub id: ChangeSetId,
+        pub name: String,
+        pub status: ChangeSetStatus,
+        pub base_change_set_id: Option<ChangeSetId>,
+        pub created_at: DateTime<Utc>,
+        pub updated_at: DateTime<Utc>,
+        pub merge_requested_at: Option<DateTime<Utc>>,
+        pub reviewed_at: Option<DateTime<Utc>>,
+    }
+}
+
+api_types! {
+    /// The actions that will be queued on HEAD once the change set is applied.
+    pub struct ApplyPlanResponse {
+        pub change_set_id: ChangeSetId,
+        pub base_change_set_id: ChangeSetId,
+        /// Every action, in the order they will be dispatched.
+        pub actions: Vec<ApplyPlanActionView>,
+        pub dependencies: Vec<ApplyPlanDependencyView>,
+        /// Groups of action ids that can be dispatched together, in order.
+        pub batches: Vec<Vec<ActionId>>,
+        pub held: Vec<HeldActionView>,
+        /// Actions that will never be dispatched because they depend on each other.
+        pub cyclic_action_ids: Vec<ActionId>,
+    }
+
+    pub struct ApplyPlanActionView {
+        pub id: ActionId,
+        pub prototype_id: ActionPrototypeId,
+        pub name: String,
+        pub kind: ActionKind,
+        pub state: ActionState,
+        pub component_id: Option<ComponentId>,
+        pub component_name: Option<String>,
+        /// Whether the action comes from this change set, rather than already being queued on HEAD.
+        pub from_change_set: bool,
+    }
+
+    pub struct ApplyPlanDependencyView {
+        pub action_id: ActionId,
+        pub depends_on: ActionId,
+    }
+
+    pub struct HeldActionView {
+        pub action_id: ActionId,
+        pub state: ActionState,
+        pub blocked_action_ids: Vec<ActionId>,
+        pub affected_component_ids: Vec<ComponentId>,
+    }
+}
+
+impl From<ActionPlan> for ApplyPlanResponse {
+    fn from(plan: ActionPlan) -> Self {
+        Self {
+            change_set_id: plan.change_set_id,
+            base_change_set_id: plan.base_change_set_id,
+            actions: plan.actions.into_iter().map(Into::into).collect(),
+            dependencies: plan.dependencies.into_iter().map(Into::into).collect(),
+            batches: plan.batches,
+            held: plan.held.into_iter().map(Into::into).collect(),
+            cyclic_action_ids: plan.cyclic_action_ids,
+        }
+    }
+}
+
+impl From<ActionPlanEntry> for ApplyPlanActionView {
+    fn from(entry: ActionPlanEntry) -> Self {
+        Self {
+            id: entry.id,
+            prototype_id: entry.prototype_id,
+            name: entry.name,
+            kind: entry.kind,
+            state: entry.state,
+            component_id: entry.component_id,
+            component_name: entry.component_name,
+            from_change_set: entry.from_change_set,
+        }
+    }
+}
+
+impl From<ActionPlanDependency> for ApplyPlanDependencyView {
+    fn from(dependency: ActionPlanDependency) -> Self {
+        Self {
+            action_id: dependency.action_id,
+            depends_on: dependency.depends_on,
+        }
+    }
+}
+
+impl From<HeldAction> for HeldActionView {
+    fn from(held: HeldAction) -> Self {
+        Self {
+            action_id: held.action_id,
+            state: held.state,
+            blocked_action_ids: held.blocked_action_ids,
+            affected_component_ids: held.affected_component_ids,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+enum ApplyPlanFormat {
+    #[default]
+    Json,
+    Dot,
+}
+
+#[derive(Deserialize)]
+struct ApplyPlanQuery {
+    #[serde(default)]
+    format: ApplyPlanFormat,
+}
+
+impl From<ChangeSet> for ChangeSetView {
+    fn from(change_set: ChangeSet) -> Self {
+        Self {
+            id: change_set.id,
+            name: change_set.name,
+            status: change_set.status,
+            base_change_set_id: change_set.base_change_set_id,
+            created_at: change_set.created_at,
+            updated_at: change_set.updated_at,
+            merge_requested_at: change_set.merge_requested_at,
+            reviewed_at: change_set.reviewed_at,
+        }
+    }
+}
+
+async fn change_set_response(ctx: &DalContext) -> Result<Json<ChangeSetResponse>> {
+    let change_set = ChangeSet::get_by_id(ctx, ctx.change_set_id()).await?;
+    Ok(Json(ChangeSetResponse {
+        change_set: change_set.into(),
+    }))
 }
 
 async fn create_change_set(
     WorkspaceDalContext(ctx): WorkspaceDalContext,
     tracker: PosthogEventTracker,
     Json(payload): Json<CreateChangeSetRequest>,
-) -> Result<Json<CreateChangeSetResponse>> {
+) -> Result<Json<ChangeSetResponse>> {
     let change_set = ChangeSet::fork_head(&ctx, &payload.change_set_name).await?;
 
     tracker.track(&ctx, "create_change_set", json!(payload));
@@ -65,17 +335,177 @@ async fn create_change_set(
 
     ctx.commit_no_rebase().await?;
 
-    Ok(Json(CreateChangeSetResponse { change_set }))
+    Ok(Json(ChangeSetResponse {
+        change_set: change_set.into(),
+    }))
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct CreateChangeSetRequest {
-    change_set_name: String,
+async fn import_components(
+    WorkspaceDalContext(mut ctx): WorkspaceDalContext,
+    tracker: PosthogEventTracker,
+    Json(payload): Json<ImportComponentsRequest>,
+) -> Result<Json<ImportComponentsResponse>> {
+    let fragment =
+        serde_json::from_value(payload.fragment).map_err(ChangeSetsError::InvalidFragment)?;
+
+    let change_set = ChangeSet::fork_head(&ctx, &payload.change_set_name).await?;
+    ctx.write_audit_log(
+        AuditLogKind::CreateChangeSet,
+        payload.change_set_name.clone(),
+    )
+    .await?;
+    WsEvent::change_set_created(&ctx, change_set.id)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+    ctx.commit_no_rebase().await?;
+
+    ctx.update_visibility_and_snapshot_to_visibility(change_set.id)
+        .await?;
+    let report = ComponentFragment::import(&ctx, &fragment).await?;
+
+    tracker.track(
+        &ctx,
+        "import_components",
+        json!({
+            "how": "/public/change_set/import_components",
+            "change_set_id": change_set.id,
+            "imported_count": report.component_ids.len(),
+            "unmapped_count": report.unmapped_components.len(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(ImportComponentsResponse {
+        change_set: change_set.into(),
+        report: serde_json::to_value(report)?,
+    }))
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct CreateChangeSetResponse {
-    change_set: ChangeSet,
+async fn get_change_set(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+) -> Result<Json<ChangeSetResponse>> {
+    change_set_response(&ctx).await
+}
+
+async fn request_approval(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+) -> Result<Json<ChangeSetResponse>> {
+    let mut change_set = ChangeSet::get_by_id(&ctx, ctx.change_set_id()).await?;
+    let old_status = change_set.status;
+
+    change_set.request_change_set_approval(&ctx).await?;
+
+    tracker.track(
+        &ctx,
+        "request_change_set_approval",
+        json!({ "how": "/public/change_set/request_approval", "change_set": change_set.id }),
+    );
+
+    let change_set_view = ChangeSet::get_by_id(&ctx, ctx.change_set_id())
+        .await?
+        .into_frontend_type(&ctx)
+        .await?;
+
+    ctx.write_audit_log(
+        AuditLogKind::RequestChangeSetApproval {
+            from_status: old_status.into(),
+        },
+        change_set_view.name.clone(),
+    )
+    .await?;
+
+    WsEvent::change_set_status_changed(&ctx, old_status, change_set_view)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    ctx.commit().await?;
+
+    change_set_response(&ctx).await
+}
+
+async fn apply_plan(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    Query(query): Query<ApplyPlanQuery>,
+) -> Result<Response> {
+    let plan = ActionPlan::for_apply(&ctx).await?;
+
+    Ok(match query.format {
+        ApplyPlanFormat::Json => Json(ApplyPlanResponse::from(plan)).into_response(),
+        ApplyPlanFormat::Dot => {
+            ([(header::CONTENT_TYPE, "text/vnd.graphviz")], plan.to_dot()).into_response()
+        }
+    })
+}
+
+async fn apply(
+    ChangeSetDalContext(mut ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+) -> Result<Json<ChangeSetResponse>> {
+    let change_set = ChangeSet::get_by_id(&ctx, ctx.change_set_id()).await?;
+    let change_set_id = change_set.id;
+    if let Err(err) = ChangeSet::prepare_for_apply(&ctx).await {
+        // Keep the policy results that blocked the apply so that they can be inspected.
+        if let dal::ChangeSetError::PolicyChecksFailed(_) = err {
+            ctx.commit().await?;
+        }
+        return Err(err.into());
+    }
+
+    // We need to run a commit before apply so changes get saved
+    ctx.commit().await?;
+
+    ChangeSet::apply_to_base_change_set(&mut ctx).await?;
+
+    tracker.track(
+        &ctx,
+        "apply_change_set",
+        json!({ "how": "/public/change_set/apply", "merged_change_set": change_set_id }),
+    );
+
+    ctx.write_audit_log(AuditLogKind::ApplyChangeSet, change_set.name)
+        .await?;
+
+    // WS Event fires from the dal
+    ctx.commit().await?;
+
+    Ok(Json(ChangeSetResponse {
+        change_set: ChangeSet::get_by_id(&ctx, change_set_id).await?.into(),
+    }))
+}
+
+async fn abandon(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+) -> Result<Json<ChangeSetResponse>> {
+    if ctx.get_workspace_default_change_set_id().await? == ctx.change_set_id() {
+        return Err(ChangeSetsError::CannotAbandonHead);
+    }
+
+    let mut change_set = ChangeSet::get_by_id(&ctx, ctx.change_set_id()).await?;
+    let old_status = change_set.status;
+    change_set.abandon(&ctx).await?;
+
+    tracker.track(
+        &ctx,
+        "abandon_change_set",
+        json!({ "how": "/public/change_set/abandon", "abandoned_change_set": change_set.id }),
+    );
+
+    ctx.write_audit_log(
+        AuditLogKind::AbandonChangeSet {
+            from_status: old_status.into(),
+        },
+        change_set.name.clone(),
+    )
+    .await?;
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(ChangeSetResponse {
+        change_set: ChangeSet::get_by_id(&ctx, change_set.id).await?.into(),
+    }))
 }
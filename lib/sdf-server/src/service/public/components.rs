@@ -1,68 +1,842 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use axum::{
-    extract::Path,
+    extract::{Path, Query},
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::put,
-    Json, Router,
+    Json,
 };
+use chrono::{DateTime, Utc};
 use dal::{
+    change_status::ChangeStatus,
+    component::{
+        delete::{delete_components, ComponentDeletionStatus},
+        fragment::{ComponentFragment, ComponentFragmentError},
+        query::{ComponentQuery, ComponentQueryError},
+        tag::{ComponentTag, ComponentTagError, TagSelector},
+    },
+    diagram::{
+        view::{View, ViewId},
+        SummaryDiagramEdge,
+    },
+    generate_name,
     prop::{PropPath, PropResult, PROP_PATH_SEPARATOR},
-    AttributeValue, Component, ComponentId, Prop, PropId, SchemaVariantId, WsEvent,
+    qualification::QualificationSubCheckStatus,
+    AttributeValue, Component, ComponentId, InputSocket, OutputSocket, Prop, PropId, Schema,
+    SchemaVariantId, WsEvent,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 use si_events::audit_log::AuditLogKind;
+use si_jwt_public_key::SiJwtPermission;
 use thiserror::Error;
+use veritech_client::ResourceStatus;
 
+use super::{
+    api_types, error_response,
+    openapi::{object_schema, ApiRouter, ApiSchema, OperationDoc, SchemaRegistry},
+};
 use crate::extract::{change_set::ChangeSetDalContext, PosthogEventTracker};
-use crate::AppState;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
-pub enum ChangeSetsError {
+pub enum ComponentsError {
     #[error("attribute value error: {0}")]
     AttributeValue(#[from] dal::attribute::value::AttributeValueError),
     #[error("component error: {0}")]
     Component(#[from] dal::ComponentError),
-    #[error("dal change set error: {0}")]
-    DalChangeSet(#[from] dal::ChangeSetError),
+    #[error("component fragment error: {0}")]
+    ComponentFragment(#[from] ComponentFragmentError),
+    #[error("component not found: {0}")]
+    ComponentNotFound(ComponentId),
+    #[error("component query error: {0}")]
+    ComponentQuery(#[from] ComponentQueryError),
+    #[error("component tag error: {0}")]
+    ComponentTag(#[from] ComponentTagError),
+    #[error("diagram error: {0}")]
+    Diagram(#[from] dal::diagram::DiagramError),
+    #[error("connection already exists")]
+    DuplicateConnection,
+    #[error("history event error: {0}")]
+    HistoryEvent(#[from] dal::HistoryEventError),
+    #[error("input socket error: {0}")]
+    InputSocket(#[from] dal::socket::input::InputSocketError),
+    #[error("input socket not found: {0}")]
+    InputSocketNotFound(String),
+    #[error("output socket error: {0}")]
+    OutputSocket(#[from] dal::socket::output::OutputSocketError),
+    #[error("output socket not found: {0}")]
+    OutputSocketNotFound(String),
     #[error("prop error: {0}")]
     Prop(#[from] dal::prop::PropError),
+    #[error("schema error: {0}")]
+    Schema(#[from] dal::SchemaError),
+    #[error("schema not found: {0}")]
+    SchemaNotFound(String),
     #[error("schema variant error: {0}")]
     SchemaVariant(#[from] dal::SchemaVariantError),
+    #[error("serde json error: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("transactions error: {0}")]
     Transactions(#[from] dal::TransactionsError),
     #[error("ws event error: {0}")]
     WsEvent(#[from] dal::WsEventError),
 }
 
-type Result<T> = std::result::Result<T, ChangeSetsError>;
+type Result<T> = std::result::Result<T, ComponentsError>;
 
-impl IntoResponse for ChangeSetsError {
+impl IntoResponse for ComponentsError {
     fn into_response(self) -> Response {
-        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+        let status_code = match &self {
+            Self::ComponentNotFound(_) | Self::Component(dal::ComponentError::NotFound(_)) => {
+                StatusCode::NOT_FOUND
+            }
+            Self::InputSocketNotFound(_)
+            | Self::OutputSocketNotFound(_)
+            | Self::SchemaNotFound(_)
+            | Self::Prop(dal::prop::PropError::ChildPropNotFoundByName(..))
+            | Self::ComponentTag(
+                ComponentTagError::InvalidKey(_) | ComponentTagError::InvalidValue(_),
+            )
+            | Self::ComponentQuery(ComponentQueryError::Parse { .. })
+            | Self::ComponentFragment(ComponentFragmentError::NothingToExport) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::DuplicateConnection => StatusCode::CONFLICT,
+            Self::Component(dal::ComponentError::ComponentProtected(_)) => {
+                StatusCode::PRECONDITION_FAILED
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        error_response(status_code, self)
     }
 }
 
 // /api/public/workspaces/:workspace_id/change-sets/:change_set_id/components
-pub fn routes() -> Router<AppState> {
-    Router::new().nest(
-        "/:component_id",
-        Router::new().route("/properties", put(update_component_properties)),
+pub fn routes() -> ApiRouter {
+    ApiRouter::new("Components")
+        .get(
+            "/",
+            list_components,
+            OperationDoc::new("listComponents", "List the components in a change set")
+                .query_param(
+                    "tag",
+                    "Comma separated tag selectors, such as `env=prod,team`; only components matching every selector are listed",
+                )
+                .response::<ListComponentsResponse>(),
+        )
+        .post(
+            "/",
+            create_component,
+            OperationDoc::new("createComponent", "Create a component from a schema")
+                .request::<CreateComponentRequest>()
+                .response::<ComponentResponse>(),
+        )
+        .post(
+            "/query",
+            query_components,
+            OperationDoc::new(
+                "queryComponents",
+                "Find components with a query, such as `schema = \"EC2 Instance\" and /domain/region = \"us-east-1\"`",
+            )
+            .request::<QueryComponentsRequest>()
+            .response::<QueryComponentsResponse>()
+            .permission(SiJwtPermission::ReadOnly),
+        )
+        .post(
+            "/export",
+            export_components,
+            OperationDoc::new(
+                "exportComponents",
+                "Export components, their connections and frame parentage as a fragment that can be imported into another workspace",
+            )
+            .request::<ExportComponentsRequest>()
+            .response::<ExportComponentsResponse>()
+            .permission(SiJwtPermission::ReadOnly),
+        )
+        .nest(
+            "/:component_id",
+            ApiRouter::new("Components")
+                .get(
+                    "/",
+                    get_component,
+                    OperationDoc::new(
+                        "getComponent",
+                        "Get a component with its domain and resource",
+                    )
+                    .response::<ComponentResponse>(),
+                )
+                .delete(
+                    "/",
+                    delete_component,
+                    OperationDoc::new("deleteComponent", "Delete a component")
+                        .response::<DeleteComponentResponse>(),
+                )
+                .post(
+                    "/protect",
+                    protect_component,
+                    OperationDoc::new(
+                        "protectComponent",
+                        "Protect a component from being deleted, erased or destroyed",
+                    )
+                    .response::<ComponentProtectionResponse>(),
+                )
+                .post(
+                    "/unprotect",
+                    unprotect_component,
+                    OperationDoc::new("unprotectComponent", "Lift a component's protection")
+                        .response::<ComponentProtectionResponse>(),
+                )
+                .put(
+                    "/properties",
+                    update_component_properties,
+                    OperationDoc::new(
+                        "updateComponentProperties",
+                        "Update domain properties by prop id or path",
+                    )
+                    .request::<UpdateComponentPropertiesRequest>()
+                    .response::<UpdateComponentPropertiesResponse>(),
+                )
+                .get(
+                    "/qualifications",
+                    list_qualifications,
+                    OperationDoc::new(
+                        "listComponentQualifications",
+                        "List the qualification results for a component",
+                    )
+                    .response::<ListQualificationsResponse>(),
+                )
+                .post(
+                    "/connections",
+                    create_connection,
+                    OperationDoc::new(
+                        "createComponentConnection",
+                        "Connect an output socket of this component to an input socket of another",
+                    )
+                    .request::<CreateConnectionRequest>()
+                    .response::<CreateConnectionResponse>(),
+                )
+                .put(
+                    "/tags/:key",
+                    set_component_tag,
+                    OperationDoc::new(
+                        "setComponentTag",
+                        "Set a tag on a component, replacing any existing value for the key",
+                    )
+                    .request::<SetComponentTagRequest>()
+                    .response::<ComponentTagsResponse>(),
+                )
+                .delete(
+                    "/tags/:key",
+                    remove_component_tag,
+                    OperationDoc::new("removeComponentTag", "Remove a tag from a component")
+                        .response::<ComponentTagsResponse>(),
+                ),
+        )
+}
+
+api_types! {
+    pub struct ListComponentsResponse {
+        pub components: Vec<ComponentSummary>,
+    }
+
+    pub struct ComponentSummary {
+        pub id: ComponentId,
+        pub name: String,
+        pub schema_name: String,
+        pub resource_id: Option<String>,
+        pub tags: BTreeMap<String, String>,
+    }
+
+    pub struct QueryComponentsRequest {
+        /// Fields: `id`, `name`, `schema`, `variant`, `type`, `parent`, `qualification`,
+        /// `tag.<key>` and root prop paths such as `/domain/region`. Operators: `=`, `!=`, `<`,
+        /// `<=`, `>`, `>=` and `~`. Predicates: `connected(socket)`, `incoming(socket)`,
+        /// `outgoing(socket)`, `in_frame(name)` and `exists(field)`, combined with `and`, `or`
+        /// and `not`.
+        pub query: String,
+        /// Fields whose values are returned for each match.
+        pub select: Option<Vec<String>>,
+    }
+
+    pub struct QueryComponentsResponse {
+        pub components: Vec<QueryComponentsMatch>,
+    }
+
+    pub struct QueryComponentsMatch {
+        pub component_id: ComponentId,
+        /// Selected values keyed by field.
+        pub values: BTreeMap<String, Value>,
+    }
+
+    pub struct ExportComponentsRequest {
+        pub component_ids: Vec<ComponentId>,
+    }
+
+    pub struct ExportComponentsResponse {
+        /// Pass this to `importComponents` unchanged.
+        pub fragment: Value,
+    }
+
+    pub struct CreateComponentRequest {
+        pub schema_name: String,
+        /// Defaults to a generated name.
+        pub name: Option<String>,
+        /// Defaults to the workspace's default view.
+        pub view_id: Option<ViewId>,
+    }
+
+    pub struct ComponentResponse {
+        pub component: ComponentView,
+    }
+
+    pub struct ComponentView {
+        pub id: ComponentId,
+        pub name: String,
+        pub schema_name: String,
+        pub schema_variant_id: SchemaVariantId,
+        pub to_delete: bool,
+        /// Protected components cannot be deleted or have destroy actions enqueued.
+        pub protected: bool,
+        /// The component's properties under `/root/domain`.
+        pub domain: Value,
+        pub resource: Option<ResourceView>,
+        pub tags: BTreeMap<String, String>,
+    }
+
+    pub struct ResourceView {
+        pub status: ResourceStatus,
+        pub payload: Option<Value>,
+        pub last_synced: DateTime<Utc>,
+    }
+
+    pub struct DeleteComponentResponse {
+        /// One of `deleted`, `markedForDeletion` or `stillExistsOnHead`.
+        pub status: String,
+    }
+
+    pub struct ComponentProtectionResponse {
+        pub protected: bool,
+    }
+
+    pub struct UpdateComponentPropertiesResponse {}
+
+    pub struct ListQualificationsResponse {
+        pub qualifications: Vec<QualificationView>,
+    }
+
+    pub struct QualificationView {
+        pub name: String,
+        pub title: String,
+        pub description: Option<String>,
+        pub link: Option<String>,
+        /// Absent until the qualification has run.
+        pub status: Option<QualificationSubCheckStatus>,
+        pub messages: Vec<String>,
+        pub finalized: bool,
+    }
+
+    pub struct CreateConnectionRequest {
+        pub from_socket_name: String,
+        pub to_component_id: ComponentId,
+        pub to_socket_name: String,
+    }
+
+    pub struct CreateConnectionResponse {}
+
+    pub struct SetComponentTagRequest {
+        pub value: String,
+    }
+
+    pub struct ComponentTagsResponse {
+        pub tags: BTreeMap<String, String>,
+    }
+}
+
+async fn get_component_or_not_found(
+    ctx: &dal::DalContext,
+    component_id: ComponentId,
+) -> Result<Component> {
+    Component::try_get_by_id(ctx, component_id)
+        .await?
+        .ok_or(ComponentsError::ComponentNotFound(component_id))
+}
+
+async fn component_view(ctx: &dal::DalContext, component: &Component) -> Result<ComponentView> {
+    let schema_variant = component.schema_variant(ctx).await?;
+    let schema = component.schema(ctx).await?;
+    let domain = component
+        .view(ctx)
+        .await?
+        .and_then(|mut view| view.get_mut("domain").map(Value::take))
+        .unwrap_or(Value::Null);
+    let resource = component.resource(ctx).await?.map(|resource| ResourceView {
+        status: resource.status,
+        payload: resource.payload,
+        last_synced: resource.last_synced,
+    });
+
+    Ok(ComponentView {
+        id: component.id(),
+        name: component.name(ctx).await?,
+        schema_name: schema.name().to_owned(),
+        schema_variant_id: schema_variant.id(),
+        to_delete: component.to_delete(),
+        protected: component.protected(),
+        domain,
+        resource,
+        tags: ComponentTag::list_for_component(ctx, component.id()).await?,
+    })
+}
+
+async fn list_components(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    Query(query): Query<ListComponentsQuery>,
+) -> Result<Json<ListComponentsResponse>> {
+    let selectors = match query.tag {
+        Some(tag) => TagSelector::parse_list(&tag)?,
+        None => vec![],
+    };
+
+    let mut components = Vec::new();
+    for component in Component::list(&ctx).await? {
+        let tags = ComponentTag::list_for_component(&ctx, component.id()).await?;
+        if !TagSelector::all_match(&selectors, &tags) {
+            continue;
+        }
+
+        let resource_id = component.resource_id(&ctx).await?;
+        components.push(ComponentSummary {
+            id: component.id(),
+            name: component.name(&ctx).await?,
+            schema_name: component.schema(&ctx).await?.name().to_owned(),
+            resource_id: Some(resource_id).filter(|id| !id.is_empty()),
+            tags,
+        });
+    }
+
+    Ok(Json(ListComponentsResponse { components }))
+}
+
+async fn query_components(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    Json(payload): Json<QueryComponentsRequest>,
+) -> Result<Json<QueryComponentsResponse>> {
+    let components = ComponentQuery::parse(&payload.query, &payload.select.unwrap_or_default())?
+        .run(&ctx)
+        .await?
+        .into_iter()
+        .map(|component| QueryComponentsMatch {
+            component_id: component.component_id,
+            values: component.values,
+        })
+        .collect();
+
+    Ok(Json(QueryComponentsResponse { components }))
+}
+
+async fn export_components(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Json(payload): Json<ExportComponentsRequest>,
+) -> Result<Json<ExportComponentsResponse>> {
+    let created_by = ctx.history_actor().email(&ctx).await?;
+    let fragment = ComponentFragment::export(&ctx, &payload.component_ids, created_by).await?;
+
+    tracker.track(
+        &ctx,
+        "export_components",
+        json!({
+            "how": "/public/component/export",
+            "component_count": fragment.components.len(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    Ok(Json(ExportComponentsResponse {
+        fragment: serde_json::to_value(fragment)?,
+    }))
+}
+
+async fn create_component(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Json(payload): Json<CreateComponentRequest>,
+) -> Result<Json<ComponentResponse>> {
+    let schema = Schema::find_by_name(&ctx, &payload.schema_name)
+        .await?
+        .ok_or_else(|| ComponentsError::SchemaNotFound(payload.schema_name.clone()))?;
+    let schema_variant_id = schema.get_default_schema_variant_id_or_error(&ctx).await?;
+    let variant = dal::SchemaVariant::get_by_id_or_error(&ctx, schema_variant_id).await?;
+
+    let view_id = match payload.view_id {
+        Some(view_id) => view_id,
+        None => View::get_id_for_default(&ctx).await?,
+    };
+    let name = payload.name.clone().unwrap_or_else(generate_name);
+
+    let component = Component::new(&ctx, &name, schema_variant_id, view_id).await?;
+
+    ctx.write_audit_log(
+        AuditLogKind::CreateComponent {
+            name: name.clone(),
+            component_id: component.id(),
+            schema_variant_id,
+            schema_variant_name: variant.display_name().to_owned(),
+        },
+        name.clone(),
     )
+    .await?;
+
+    tracker.track(
+        &ctx,
+        "component_created",
+        json!({
+            "how": "/public/component/create",
+            "component_id": component.id(),
+            "component_name": name,
+            "schema_name": schema.name(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    let geometry = component.geometry(&ctx, view_id).await?;
+    let mut socket_map = HashMap::new();
+    let frontend_component = component
+        .into_frontend_type(&ctx, Some(&geometry), ChangeStatus::Added, &mut socket_map)
+        .await?;
+    WsEvent::component_created(&ctx, frontend_component)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
+    let view = component_view(&ctx, &component).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(ComponentResponse { component: view }))
+}
+
+async fn get_component(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    Path(ComponentPath { component_id }): Path<ComponentPath>,
+) -> Result<Json<ComponentResponse>> {
+    let component = get_component_or_not_found(&ctx, component_id).await?;
+    Ok(Json(ComponentResponse {
+        component: component_view(&ctx, &component).await?,
+    }))
+}
+
+async fn delete_component(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path(ComponentPath { component_id }): Path<ComponentPath>,
+) -> Result<Json<DeleteComponentResponse>> {
+    get_component_or_not_found(&ctx, component_id).await?;
+
+    // Audit logs and ws events are handled by the deletion itself.
+    let statuses = delete_components(&ctx, &[component_id], false).await?;
+    let status = match statuses.get(&component_id) {
+        Some(ComponentDeletionStatus::MarkedForDeletion) => "markedForDeletion",
+        Some(ComponentDeletionStatus::StillExistsOnHead) => "stillExistsOnHead",
+        Some(ComponentDeletionStatus::Deleted) | None => "deleted",
+    };
+
+    tracker.track(
+        &ctx,
+        "delete_component",
+        json!({
+            "how": "/public/component/delete",
+            "component_id": component_id,
+            "status": status,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(DeleteComponentResponse {
+        status: status.to_owned(),
+    }))
+}
+
+async fn list_qualifications(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    Path(ComponentPath { component_id }): Path<ComponentPath>,
+) -> Result<Json<ListQualificationsResponse>> {
+    get_component_or_not_found(&ctx, component_id).await?;
+
+    let qualifications = Component::list_qualifications(&ctx, component_id)
+        .await?
+        .into_iter()
+        .map(|qualification| {
+            let (status, messages) = match qualification.result {
+                Some(result) => (
+                    Some(result.status),
+                    result
+                        .sub_checks
+                        .into_iter()
+                        .map(|sub_check| sub_check.description)
+                        .collect(),
+                ),
+                None => (None, Vec::new()),
+            };
+            QualificationView {
+                name: qualification.qualification_name,
+                title: qualification.title,
+                description: qualification.description,
+                link: qualification.link,
+                status,
+                messages,
+                finalized: qualification.finalized,
+            }
+        })
+        .collect();
+
+    Ok(Json(ListQualificationsResponse { qualifications }))
+}
+
+async fn create_connection(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path(ComponentPath { component_id }): Path<ComponentPath>,
+    Json(payload): Json<CreateConnectionRequest>,
+) -> Result<Json<CreateConnectionResponse>> {
+    let from_component = get_component_or_not_found(&ctx, component_id).await?;
+    let to_component = get_component_or_not_found(&ctx, payload.to_component_id).await?;
+
+    let from_socket = OutputSocket::find_with_name(
+        &ctx,
+        &payload.from_socket_name,
+        from_component.schema_variant(&ctx).await?.id(),
+    )
+    .await?
+    .ok_or_else(|| ComponentsError::OutputSocketNotFound(payload.from_socket_name.clone()))?;
+    let to_socket = InputSocket::find_with_name(
+        &ctx,
+        &payload.to_socket_name,
+        to_component.schema_variant(&ctx).await?.id(),
+    )
+    .await?
+    .ok_or_else(|| ComponentsError::InputSocketNotFound(payload.to_socket_name.clone()))?;
+
+    Component::connect(
+        &ctx,
+        from_component.id(),
+        from_socket.id(),
+        to_component.id(),
+        to_socket.id(),
+    )
+    .await?
+    .ok_or(ComponentsError::DuplicateConnection)?;
+
+    tracker.track(
+        &ctx,
+        "create_connection",
+        json!({
+            "how": "/public/component/create_connection",
+            "from_component_id": from_component.id(),
+            "from_socket_id": from_socket.id(),
+            "to_component_id": to_component.id(),
+            "to_socket_id": to_socket.id(),
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    for incoming_connection in to_component.incoming_connections(&ctx).await? {
+        if incoming_connection.to_input_socket_id == to_socket.id()
+            && incoming_connection.from_component_id == from_component.id()
+        {
+            let edge = SummaryDiagramEdge::assemble(
+                incoming_connection,
+                &from_component,
+                &to_component,
+                ChangeStatus::Added,
+            )?;
+            WsEvent::connection_upserted(&ctx, edge.into())
+                .await?
+                .publish_on_commit(&ctx)
+                .await?;
+        }
+    }
+
+    let to_component_name = to_component.name(&ctx).await?;
+    ctx.write_audit_log(
+        AuditLogKind::CreateConnection {
+            from_component_id: from_component.id(),
+            from_component_name: from_component.name(&ctx).await?,
+            from_socket_id: from_socket.id(),
+            from_socket_name: payload.from_socket_name,
+            to_component_id: to_component.id(),
+            to_component_name: to_component_name.clone(),
+            to_socket_id: to_socket.id(),
+            to_socket_name: payload.to_socket_name.clone(),
+        },
+        format!("{to_component_name} --- {}", payload.to_socket_name),
+    )
+    .await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(CreateConnectionResponse {}))
+}
+
+async fn set_component_tag(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path(ComponentTagPath { component_id, key }): Path<ComponentTagPath>,
+    Json(payload): Json<SetComponentTagRequest>,
+) -> Result<Json<ComponentTagsResponse>> {
+    let component = get_component_or_not_found(&ctx, component_id).await?;
+
+    ComponentTag::set(&ctx, component_id, &key, payload.value).await?;
+
+    tracker.track(
+        &ctx,
+        "set_component_tag",
+        json!({
+            "how": "/public/component/set_tag",
+            "component_id": component_id,
+            "tag_key": key,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    tags_updated(&ctx, &component).await
+}
+
+async fn remove_component_tag(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path(ComponentTagPath { component_id, key }): Path<ComponentTagPath>,
+) -> Result<Json<ComponentTagsResponse>> {
+    let component = get_component_or_not_found(&ctx, component_id).await?;
+
+    if ComponentTag::remove(&ctx, component_id, &key).await? {
+        tracker.track(
+            &ctx,
+            "remove_component_tag",
+            json!({
+                "how": "/public/component/remove_tag",
+                "component_id": component_id,
+                "tag_key": key,
+                "change_set_id": ctx.change_set_id(),
+            }),
+        );
+    }
+
+    tags_updated(&ctx, &component).await
+}
+
+/// Publishes the component's new tags to the frontend, commits, and returns them.
+async fn tags_updated(
+    ctx: &dal::DalContext,
+    component: &Component,
+) -> Result<Json<ComponentTagsResponse>> {
+    let mut socket_map = HashMap::new();
+    let payload = component
+        .into_frontend_type(
+            ctx,
+            None,
+            component.change_status(ctx).await?,
+            &mut socket_map,
+        )
+        .await?;
+    WsEvent::component_updated(ctx, payload)
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+
+    let tags = ComponentTag::list_for_component(ctx, component.id()).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(ComponentTagsResponse { tags }))
+}
+
+async fn protect_component(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path(ComponentPath { component_id }): Path<ComponentPath>,
+) -> Result<Json<ComponentProtectionResponse>> {
+    let response = set_component_protected(&ctx, component_id, true).await?;
+
+    tracker.track(
+        &ctx,
+        "protect_component",
+        json!({
+            "how": "/public/component/protect",
+            "component_id": component_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(response)
+}
+
+async fn unprotect_component(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    Path(ComponentPath { component_id }): Path<ComponentPath>,
+) -> Result<Json<ComponentProtectionResponse>> {
+    let response = set_component_protected(&ctx, component_id, false).await?;
+
+    tracker.track(
+        &ctx,
+        "unprotect_component",
+        json!({
+            "how": "/public/component/unprotect",
+            "component_id": component_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(response)
+}
+
+/// Sets the component's protection and publishes it to the frontend. Audit logs are written by
+/// the component itself.
+async fn set_component_protected(
+    ctx: &dal::DalContext,
+    component_id: ComponentId,
+    protected: bool,
+) -> Result<Json<ComponentProtectionResponse>> {
+    let component = get_component_or_not_found(ctx, component_id)
+        .await?
+        .set_protected(ctx, protected)
+        .await?;
+
+    let payload = component
+        .into_frontend_type(
+            ctx,
+            None,
+            component.change_status(ctx).await?,
+            &mut HashMap::new(),
+        )
+        .await?;
+    WsEvent::component_updated(ctx, payload)
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+
+    Ok(Json(ComponentProtectionResponse {
+        protected: component.protected(),
+    }))
 }
 
 async fn update_component_properties(
     ChangeSetDalContext(ctx): ChangeSetDalContext,
     tracker: PosthogEventTracker,
-    Path(UpdateComponentPropertiesPath { component_id }): Path<UpdateComponentPropertiesPath>,
+    Path(ComponentPath { component_id }): Path<ComponentPath>,
     Json(payload): Json<UpdateComponentPropertiesRequest>,
 ) -> Result<Json<UpdateComponentPropertiesResponse>> {
     tracker.track(&ctx, "update_component_properties", json!(payload));
 
-    let component = Component::get_by_id(&ctx, component_id).await?;
+    let component = get_component_or_not_found(&ctx, component_id).await?;
     let component_name = component.name(&ctx).await?;
     let schema_variant = component.schema_variant(&ctx).await?;
     let schema_variant_id = schema_variant.id;
@@ -139,19 +913,40 @@ async fn update_component_properties(
 }
 
 #[derive(Deserialize)]
-struct UpdateComponentPropertiesPath {
+struct ComponentPath {
+    component_id: ComponentId,
+}
+
+#[derive(Deserialize)]
+struct ComponentTagPath {
     component_id: ComponentId,
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListComponentsQuery {
+    tag: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct UpdateComponentPropertiesRequest {
-    domain: HashMap<ComponentPropKey, serde_json::Value>,
+    domain: HashMap<ComponentPropKey, Value>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct UpdateComponentPropertiesResponse {}
+// Keys are either prop ids or prop paths, so this is described by hand rather than through `api_types!`.
+impl ApiSchema for UpdateComponentPropertiesRequest {
+    fn schema(registry: &mut SchemaRegistry) -> Value {
+        registry.reference("UpdateComponentPropertiesRequest", |_registry| {
+            let domain = json!({
+                "type": "object",
+                "description": "New values keyed by prop id or by prop path relative to /root/domain, such as `tags/Name`",
+                "additionalProperties": {},
+            });
+            object_schema(vec![("domain", domain, true)])
+        })
+    }
+}
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(untagged)]
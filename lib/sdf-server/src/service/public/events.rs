@@ -0,0 +1,272 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use dal::{
+    action::{ActionId, ActionState},
+    ChangeSetId, ChangeSetStatus, ComponentId, WorkspacePk, WsEvent, WsPayload,
+};
+use futures::{stream, Stream, StreamExt};
+use serde::Serialize;
+use serde_json::json;
+use si_events::FuncRunId;
+use si_frontend_types::DiagramComponentView;
+use strum::AsRefStr;
+use thiserror::Error;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use ulid::Ulid;
+
+use super::{
+    error_response,
+    openapi::{ApiRouter, OperationDoc},
+};
+use crate::{
+    extract::{change_set::ChangeSetDalContext, PosthogEventTracker},
+    nats_multiplexer::NatsMultiplexerClients,
+};
+
+use self::buffer::{BufferError, EventBuffer, PublicEventBuffers, SequencedEvent};
+
+pub mod buffer;
+
+/// The version of the event schema. Bump this when making a breaking change to [`PublicEventPayload`].
+const PUBLIC_EVENT_VERSION: u8 = 1;
+/// Sent in place of replayed events when a client resumes from an event that is no longer retained. Clients should
+/// refetch any state they depend on when they see it.
+const RESET_EVENT_NAME: &str = "reset";
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum EventsError {
+    #[error("event buffer error: {0}")]
+    Buffer(#[from] BufferError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] dal::TransactionsError),
+}
+
+type Result<T> = std::result::Result<T, EventsError>;
+
+impl IntoResponse for EventsError {
+    fn into_response(self) -> Response {
+        error_response(StatusCode::INTERNAL_SERVER_ERROR, self)
+    }
+}
+
+// /api/public/workspaces/:workspace_id/change-sets/:change_set_id/events
+pub fn routes() -> ApiRouter {
+    ApiRouter::new("Events").get(
+        "/",
+        stream_events,
+        OperationDoc::new(
+            "streamChangeSetEvents",
+            "Stream change set events as server-sent events, resuming from the Last-Event-ID header",
+        ),
+    )
+}
+
+/// An event published on the public event stream.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicEvent {
+    version: u8,
+    workspace_id: WorkspacePk,
+    change_set_id: ChangeSetId,
+    #[serde(flatten)]
+    payload: PublicEventPayload,
+}
+
+/// The stable subset of workspace events exposed on the public API. Unlike [`WsPayload`], these are part of the API
+/// contract, so changes here must be additive or come with a new [`PUBLIC_EVENT_VERSION`].
+#[remain::sorted]
+#[derive(AsRefStr, Clone, Debug, Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum PublicEventPayload {
+    #[serde(rename_all = "camelCase")]
+    ActionStateChanged {
+        action_id: ActionId,
+        state: ActionState,
+    },
+    #[serde(rename_all = "camelCase")]
+    ChangeSetApplied {
+        to_change_set_id: ChangeSetId,
+    },
+    #[serde(rename_all = "camelCase")]
+    ChangeSetStatusChanged {
+        from_status: ChangeSetStatus,
+        to_status: ChangeSetStatus,
+    },
+    ComponentCreated(PublicComponent),
+    #[serde(rename_all = "camelCase")]
+    ComponentDeleted {
+        component_id: ComponentId,
+    },
+    ComponentUpdated(PublicComponent),
+    #[serde(rename_all = "camelCase")]
+    FuncRunFinished {
+        func_run_id: FuncRunId,
+        action_id: Option<ActionId>,
+        succeeded: bool,
+    },
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicComponent {
+    component_id: ComponentId,
+    name: String,
+    schema_name: String,
+    parent_id: Option<ComponentId>,
+    resource_id: Option<String>,
+    has_resource: bool,
+    to_delete: bool,
+}
+
+impl From<&DiagramComponentView> for PublicComponent {
+    fn from(view: &DiagramComponentView) -> Self {
+        Self {
+            component_id: view.component_id,
+            name: view.display_name.clone(),
+            schema_name: view.schema_name.clone(),
+            parent_id: view.parent_id,
+            resource_id: Some(view.resource_id.clone()).filter(|id| !id.is_empty()),
+            has_resource: view.has_resource,
+            to_delete: view.to_delete,
+        }
+    }
+}
+
+impl PublicEvent {
+    /// Maps a [`WsEvent`] onto a public event for the given change set, if it is one we expose.
+    pub fn from_ws_event(ws_event: &WsEvent, change_set_id: ChangeSetId) -> Option<Self> {
+        let in_change_set = ws_event.change_set_id() == Some(change_set_id);
+
+        let payload = match ws_event.payload() {
+            WsPayload::ActionStateUpdated(payload) if in_change_set => {
+                PublicEventPayload::ActionStateChanged {
+                    action_id: payload.action_id(),
+                    state: payload.state(),
+                }
+            }
+            // Change set events are published from whichever change set caused them, so match on the subject of the
+            // event instead.
+            WsPayload::ChangeSetApplied(payload) if payload.change_set_id() == change_set_id => {
+                PublicEventPayload::ChangeSetApplied {
+                    to_change_set_id: payload.to_rebase_change_set_id(),
+                }
+            }
+            WsPayload::ChangeSetStatusChanged(payload)
+                if payload.change_set().id == change_set_id =>
+            {
+                PublicEventPayload::ChangeSetStatusChanged {
+                    from_status: payload.from_status(),
+                    to_status: payload.change_set().status,
+                }
+            }
+            WsPayload::ComponentCreated(payload) if in_change_set => {
+                PublicEventPayload::ComponentCreated((&payload.component).into())
+            }
+            WsPayload::ComponentDeleted(payload) if in_change_set => {
+                PublicEventPayload::ComponentDeleted {
+                    component_id: payload.component_id(),
+                }
+            }
+            WsPayload::ComponentUpdated(payload) | WsPayload::ResourceRefreshed(payload)
+                if in_change_set =>
+            {
+                PublicEventPayload::ComponentUpdated((&payload.component).into())
+            }
+            WsPayload::FuncRunFinished(payload) if in_change_set => {
+                PublicEventPayload::FuncRunFinished {
+                    func_run_id: payload.func_run_id(),
+                    action_id: payload.action_id(),
+                    succeeded: payload.succeeded(),
+                }
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            version: PUBLIC_EVENT_VERSION,
+            workspace_id: ws_event.workspace_pk(),
+            change_set_id,
+            payload,
+        })
+    }
+
+    fn to_sse_event(&self, epoch: Ulid, sequence: u64) -> std::result::Result<Event, axum::Error> {
+        Event::default()
+            .id(EventBuffer::event_id(epoch, sequence))
+            .event(self.payload.as_ref())
+            .json_data(self)
+    }
+}
+
+async fn stream_events(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    tracker: PosthogEventTracker,
+    headers: HeaderMap,
+    State(buffers): State<PublicEventBuffers>,
+    State(nats_multiplexer_clients): State<NatsMultiplexerClients>,
+    State(shutdown_token): State<CancellationToken>,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, axum::Error>>>> {
+    let workspace_pk = ctx.workspace_pk()?;
+    let change_set_id = ctx.change_set_id();
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok());
+
+    let subscription = buffer::subscribe(
+        &buffers,
+        &nats_multiplexer_clients.ws,
+        workspace_pk,
+        change_set_id,
+        last_event_id,
+        shutdown_token.clone(),
+    )
+    .await?;
+
+    tracker.track(
+        &ctx,
+        "api_stream_events",
+        json!({ "resumed": last_event_id.is_some() }),
+    );
+
+    let epoch = subscription.epoch;
+    // The reset carries the id of the latest event in the buffer, so a client that reconnects right after it
+    // resumes from here rather than being reset again.
+    let reset = (!subscription.replay_complete).then(|| {
+        Ok(Event::default()
+            .id(EventBuffer::event_id(epoch, subscription.latest_sequence))
+            .event(RESET_EVENT_NAME)
+            .data(json!({ "version": PUBLIC_EVENT_VERSION }).to_string()))
+    });
+    let replay = subscription
+        .replay
+        .into_iter()
+        .map(move |SequencedEvent { sequence, event }| event.to_sse_event(epoch, sequence));
+
+    // When a client falls too far behind or its buffer is retired, we end the stream. Clients reconnect with their
+    // `Last-Event-ID` and are either caught up from the buffer or told to reset.
+    let live = stream::unfold(subscription.live, move |mut live| async move {
+        match live.recv().await {
+            Ok(SequencedEvent { sequence, event }) => {
+                Some((event.to_sse_event(epoch, sequence), live))
+            }
+            Err(broadcast::error::RecvError::Lagged(_) | broadcast::error::RecvError::Closed) => {
+                None
+            }
+        }
+    });
+
+    let events = stream::iter(reset.into_iter().chain(replay))
+        .chain(live)
+        .take_until(shutdown_token.cancelled_owned());
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,427 @@
+//! Bounded, per change set replay buffers for the public event stream.
+//!
+//! Each buffer is fed by a single background task that consumes workspace events from the "ws" multiplexer, keeps the
+//! most recent [`REPLAY_CAPACITY`] public events and fans them out to every connected client. This lets a client that
+//! reconnects with a `Last-Event-ID` pick up where it left off, as long as the events it missed are still retained.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, PoisonError},
+    time::Duration,
+};
+
+use dal::{ChangeSetId, WorkspacePk, WsEvent};
+use nats_multiplexer_client::{MultiplexerClient, MultiplexerClientError};
+use si_data_nats::Subject;
+use telemetry::prelude::*;
+use thiserror::Error;
+use tokio::{
+    sync::{broadcast, Mutex, TryLockError},
+    time::Instant,
+};
+use tokio_util::sync::CancellationToken;
+use ulid::Ulid;
+
+use super::PublicEvent;
+
+/// The number of events retained for replay per change set.
+const REPLAY_CAPACITY: usize = 1000;
+/// The number of live events that a slow client may fall behind by before its stream is ended. Clients resume from the
+/// replay buffer when they reconnect.
+const LIVE_CAPACITY: usize = 256;
+/// How long a buffer outlives its last client, giving clients a window to reconnect and resume.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum BufferError {
+    #[error("event buffer closed before it could be subscribed to")]
+    Closed,
+    #[error("nats multiplexer client error: {0}")]
+    MultiplexerClient(#[from] MultiplexerClientError),
+    #[error("try lock error: {0}")]
+    TryLock(#[from] TryLockError),
+}
+
+pub type PublicEventBuffers = Arc<Mutex<HashMap<(WorkspacePk, ChangeSetId), Arc<EventBuffer>>>>;
+
+/// A public event along with its position in the buffer it was published to.
+#[derive(Clone, Debug)]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    pub event: PublicEvent,
+}
+
+/// What a client receives when subscribing to a buffer.
+#[derive(Debug)]
+pub struct Subscription {
+    pub epoch: Ulid,
+    /// The sequence of the latest event published to the buffer at the time of subscribing, or zero if there are none.
+    pub latest_sequence: u64,
+    /// Retained events the client has not yet seen, based on the `Last-Event-ID` it provided.
+    pub replay: Vec<SequencedEvent>,
+    /// Whether `replay` covers every event the client missed. This is false when the client's last event was from a
+    /// buffer that no longer exists or has since been evicted.
+    pub replay_complete: bool,
+    pub live: broadcast::Receiver<SequencedEvent>,
+}
+
+#[derive(Debug)]
+pub struct EventBuffer {
+    /// Identifies this buffer's sequence numbers, which restart whenever a buffer is recreated.
+    epoch: Ulid,
+    inner: std::sync::Mutex<EventBufferInner>,
+}
+
+#[derive(Debug)]
+struct EventBufferInner {
+    next_sequence: u64,
+    events: VecDeque<SequencedEvent>,
+    live_tx: Option<broadcast::Sender<SequencedEvent>>,
+}
+
+impl EventBuffer {
+    fn new() -> Self {
+        let (live_tx, _) = broadcast::channel(LIVE_CAPACITY);
+        Self {
+            epoch: Ulid::new(),
+            inner: std::sync::Mutex::new(EventBufferInner {
+                next_sequence: 1,
+                events: VecDeque::with_capacity(REPLAY_CAPACITY),
+                live_tx: Some(live_tx),
+            }),
+        }
+    }
+
+    /// Formats the id sent to clients for an event in this buffer.
+    pub fn event_id(epoch: Ulid, sequence: u64) -> String {
+        format!("{epoch}-{sequence}")
+    }
+
+    fn parse_event_id(event_id: &str) -> Option<(Ulid, u64)> {
+        let (epoch, sequence) = event_id.trim().split_once('-')?;
+        Some((epoch.parse().ok()?, sequence.parse().ok()?))
+    }
+
+    fn push(&self, event: PublicEvent) {
+        let mut inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let sequenced = SequencedEvent {
+            sequence: inner.next_sequence,
+            event,
+        };
+        inner.next_sequence += 1;
+
+        if inner.events.len() == REPLAY_CAPACITY {
+            inner.events.pop_front();
+        }
+        inner.events.push_back(sequenced.clone());
+
+        // An error only means that no clients are currently connected.
+        if let Some(live_tx) = &inner.live_tx {
+            let _ = live_tx.send(sequenced);
+        }
+    }
+
+    /// Subscribes to live events and collects the retained events that follow `last_event_id`. Both happen under the
+    /// same lock as [`push`](Self::push), so no event is missed or delivered twice between the two.
+    fn subscribe(&self, last_event_id: Option<&str>) -> Option<Subscription> {
+        let inner = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        let live = inner.live_tx.as_ref()?.subscribe();
+
+        let (replay, replay_complete) = match last_event_id {
+            None => (Vec::new(), true),
+            Some(last_event_id) => match Self::parse_event_id(last_event_id) {
+                Some((epoch, last_sequence))
+                    if epoch == self.epoch && last_sequence < inner.next_sequence =>
+                {
+                    let oldest_sequence = inner
+                        .events
+                        .front()
+                        .map_or(inner.next_sequence, |event| event.sequence);
+                    let replay = inner
+                        .events
+                        .iter()
+                        .filter(|event| event.sequence > last_sequence)
+                        .cloned()
+                        .collect();
+                    (replay, last_sequence + 1 >= oldest_sequence)
+                }
+                _ => (Vec::new(), false),
+            },
+        };
+
+        Some(Subscription {
+            epoch: self.epoch,
+            latest_sequence: inner.next_sequence - 1,
+            replay,
+            replay_complete,
+            live,
+        })
+    }
+
+    fn client_count(&self) -> usize {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .live_tx
+            .as_ref()
+            .map_or(0, |live_tx| live_tx.receiver_count())
+    }
+
+    /// Ends the live stream for every connected client.
+    fn close(&self) {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .live_tx
+            .take();
+    }
+}
+
+/// Subscribes to the buffer for a change set, starting it if no client is currently using it.
+pub async fn subscribe(
+    buffers: &PublicEventBuffers,
+    ws_multiplexer_client: &Arc<Mutex<MultiplexerClient>>,
+    workspace_pk: WorkspacePk,
+    change_set_id: ChangeSetId,
+    last_event_id: Option<&str>,
+    token: CancellationToken,
+) -> Result<Subscription, BufferError> {
+    let key = (workspace_pk, change_set_id);
+
+    // The map stays locked until we hold a live receiver, so the feeding task can't retire the buffer out from under us.
+    let mut buffers_guard = buffers.lock().await;
+    if let Some(subscription) = buffers_guard
+        .get(&key)
+        .and_then(|buffer| buffer.subscribe(last_event_id))
+    {
+        return Ok(subscription);
+    }
+
+    let subject = Subject::from(format!("si.workspace_pk.{workspace_pk}.>"));
+    let receiver = ws_multiplexer_client.try_lock()?.receiver(subject).await?;
+
+    let buffer = Arc::new(EventBuffer::new());
+    let subscription = buffer.subscribe(last_event_id).ok_or(BufferError::Closed)?;
+    buffers_guard.insert(key, buffer.clone());
+    drop(buffers_guard);
+
+    tokio::spawn(feed(
+        buffers.clone(),
+        key,
+        buffer,
+        receiver,
+        change_set_id,
+        token,
+    ));
+
+    Ok(subscription)
+}
+
+#[instrument(
+    name = "sdf.public.events.feed",
+    level = "debug",
+    skip_all,
+    fields(si.workspace.id = %key.0, si.change_set.id = %key.1),
+)]
+async fn feed(
+    buffers: PublicEventBuffers,
+    key: (WorkspacePk, ChangeSetId),
+    buffer: Arc<EventBuffer>,
+    mut receiver: broadcast::Receiver<si_data_nats::Message>,
+    change_set_id: ChangeSetId,
+    token: CancellationToken,
+) {
+    let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+    let mut last_active = Instant::now();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = idle_check.tick() => {
+                if buffer.client_count() > 0 {
+                    last_active = Instant::now();
+                } else if last_active.elapsed() >= IDLE_TIMEOUT {
+                    let mut buffers_guard = buffers.lock().await;
+                    // A client may have subscribed while we waited on the lock.
+                    if buffer.client_count() == 0 {
+                        buffers_guard.remove(&key);
+                        buffer.close();
+                        debug!("retiring idle public event buffer");
+                        return;
+                    }
+                }
+            }
+            recv_result = receiver.recv() => match recv_result {
+                Ok(message) => {
+                    let ws_event: WsEvent = match serde_json::from_slice(message.payload()) {
+                        Ok(ws_event) => ws_event,
+                        Err(err) => {
+                            warn!(si.error.message = ?err, "failed to deserialize ws event");
+                            continue;
+                        }
+                    };
+                    if let Some(event) = PublicEvent::from_ws_event(&ws_event, change_set_id) {
+                        buffer.push(event);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    // The buffer now has a gap, so it can't be used to resume. Retiring it ends every client's stream
+                    // and the next client to connect starts a fresh buffer.
+                    warn!(skipped, "public event buffer fell behind, retiring it");
+                    break;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        }
+    }
+
+    let mut buffers_guard = buffers.lock().await;
+    if buffers_guard
+        .get(&key)
+        .is_some_and(|current| Arc::ptr_eq(current, &buffer))
+    {
+        buffers_guard.remove(&key);
+    }
+    buffer.close();
+}
+
+#[cfg(test)]
+mod tests {
+    use dal::ComponentId;
+
+    use super::*;
+    use crate::service::public::events::{PublicEventPayload, PUBLIC_EVENT_VERSION};
+
+    fn event() -> PublicEvent {
+        PublicEvent {
+            version: PUBLIC_EVENT_VERSION,
+            workspace_id: WorkspacePk::generate(),
+            change_set_id: ChangeSetId::generate(),
+            payload: PublicEventPayload::ComponentDeleted {
+                component_id: ComponentId::generate(),
+            },
+        }
+    }
+
+    fn sequences(events: &[SequencedEvent]) -> Vec<u64> {
+        events.iter().map(|event| event.sequence).collect()
+    }
+
+    #[test]
+    fn replays_missed_events_in_order() {
+        let buffer = EventBuffer::new();
+        for _ in 0..5 {
+            buffer.push(event());
+        }
+
+        let subscription = buffer
+            .subscribe(Some(&EventBuffer::event_id(buffer.epoch, 2)))
+            .expect("buffer is open");
+
+        assert_eq!(5, subscription.latest_sequence);
+        assert_eq!(vec![3, 4, 5], sequences(&subscription.replay));
+        assert!(subscription.replay_complete);
+    }
+
+    #[test]
+    fn live_events_follow_the_replay() {
+        let buffer = EventBuffer::new();
+        buffer.push(event());
+
+        let mut subscription = buffer
+            .subscribe(Some(&EventBuffer::event_id(buffer.epoch, 0)))
+            .expect("buffer is open");
+        buffer.push(event());
+        buffer.push(event());
+
+        assert_eq!(vec![1], sequences(&subscription.replay));
+        let live: Vec<u64> = std::iter::from_fn(|| subscription.live.try_recv().ok())
+            .map(|event| event.sequence)
+            .collect();
+        assert_eq!(vec![2, 3], live);
+    }
+
+    #[test]
+    fn drops_the_oldest_events_beyond_capacity() {
+        let buffer = EventBuffer::new();
+        let overflow = 10;
+        for _ in 0..REPLAY_CAPACITY as u64 + overflow {
+            buffer.push(event());
+        }
+
+        // The events after the first one have been dropped, so the replay has a gap
+        let subscription = buffer
+            .subscribe(Some(&EventBuffer::event_id(buffer.epoch, 1)))
+            .expect("buffer is open");
+        assert_eq!(REPLAY_CAPACITY, subscription.replay.len());
+        assert_eq!(
+            Some(overflow + 1),
+            subscription.replay.first().map(|event| event.sequence)
+        );
+        assert!(!subscription.replay_complete);
+
+        // The event right before the oldest retained one is the last a client can resume from
+        let subscription = buffer
+            .subscribe(Some(&EventBuffer::event_id(buffer.epoch, overflow)))
+            .expect("buffer is open");
+        assert_eq!(REPLAY_CAPACITY, subscription.replay.len());
+        assert!(subscription.replay_complete);
+    }
+
+    #[test]
+    fn cannot_resume_from_another_buffer() {
+        let buffer = EventBuffer::new();
+        buffer.push(event());
+
+        for last_event_id in [
+            EventBuffer::event_id(Ulid::new(), 0),
+            EventBuffer::event_id(buffer.epoch, 5),
+            "not-an-event-id".to_owned(),
+        ] {
+            let subscription = buffer
+                .subscribe(Some(&last_event_id))
+                .expect("buffer is open");
+            assert!(subscription.replay.is_empty(), "{last_event_id}");
+            assert!(!subscription.replay_complete, "{last_event_id}");
+        }
+    }
+
+    #[test]
+    fn slow_clients_lag_behind_live_events() {
+        let buffer = EventBuffer::new();
+        let mut subscription = buffer.subscribe(None).expect("buffer is open");
+
+        for _ in 0..LIVE_CAPACITY + 1 {
+            buffer.push(event());
+        }
+
+        assert!(matches!(
+            subscription.live.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(1))
+        ));
+        // Events past the lag are still delivered in order
+        assert_eq!(
+            Ok(2),
+            subscription.live.try_recv().map(|event| event.sequence)
+        );
+    }
+
+    #[test]
+    fn closing_ends_live_streams() {
+        let buffer = EventBuffer::new();
+        let mut subscription = buffer.subscribe(None).expect("buffer is open");
+        assert_eq!(1, buffer.client_count());
+
+        buffer.close();
+
+        assert!(matches!(
+            subscription.live.try_recv(),
+            Err(broadcast::error::TryRecvError::Closed)
+        ));
+        assert_eq!(0, buffer.client_count());
+        assert!(buffer.subscribe(None).is_none());
+    }
+}
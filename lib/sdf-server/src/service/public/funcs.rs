@@ -0,0 +1,303 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    Json,
+};
+use chrono::{DateTime, Utc};
+use dal::{
+    action::ActionId,
+    func::run_log::{self, FuncRunLogError, FuncRunLogLine, FuncRunLogSearch},
+    ComponentId, Func, FuncId,
+};
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use si_events::FuncRunId;
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    api_types, error_response,
+    openapi::{ApiRouter, OperationDoc},
+};
+use crate::extract::change_set::ChangeSetDalContext;
+
+/// The number of func runs returned when the client doesn't ask for a limit.
+const DEFAULT_FUNC_RUN_LIMIT: u64 = 50;
+const MAX_FUNC_RUN_LIMIT: u64 = 500;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum FuncsError {
+    #[error("func error: {0}")]
+    Func(#[from] dal::FuncError),
+    #[error("func run log error: {0}")]
+    FuncRunLog(#[from] FuncRunLogError),
+    #[error("layer db error: {0}")]
+    LayerDb(#[from] si_layer_cache::LayerDbError),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] dal::TransactionsError),
+}
+
+type Result<T> = std::result::Result<T, FuncsError>;
+
+impl IntoResponse for FuncsError {
+    fn into_response(self) -> Response {
+        let status_code = match &self {
+            Self::FuncRunLog(FuncRunLogError::InvalidPattern(_)) => StatusCode::BAD_REQUEST,
+            Self::FuncRunLog(FuncRunLogError::FuncRunNotFound(_)) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        error_response(status_code, self)
+    }
+}
+
+// /api/public/workspaces/:workspace_id/change-sets/:change_set_id/funcs
+pub fn func_routes() -> ApiRouter {
+    ApiRouter::new("Funcs").get(
+        "/",
+        list_funcs,
+        OperationDoc::new("listFuncs", "List the funcs in a change set")
+            .response::<ListFuncsResponse>(),
+    )
+}
+
+// /api/public/workspaces/:workspace_id/change-sets/:change_set_id/func-runs
+pub fn func_run_routes() -> ApiRouter {
+    ApiRouter::new("Funcs")
+        .get(
+            "/",
+            list_func_runs,
+            OperationDoc::new(
+                "listFuncRuns",
+                "List the most recent func runs in a change set",
+            )
+            .query_param(
+                "limit",
+                "The maximum number of func runs to return (default 50, max 500)",
+            )
+            .response::<ListFuncRunsResponse>(),
+        )
+        .get(
+            "/logs/search",
+            search_func_run_logs,
+            OperationDoc::new(
+                "searchFuncRunLogs",
+                "Search the log lines of the func runs in a change set, most recent first",
+            )
+            .query_param("componentId", "Only func runs for this component")
+            .query_param("funcId", "Only func runs of this func")
+            .query_param("since", "Only lines logged at or after this RFC 3339 time")
+            .query_param("until", "Only lines logged at or before this RFC 3339 time")
+            .query_param("stream", "Only lines written to this stream, such as `output`")
+            .query_param("contains", "Only lines containing this text")
+            .query_param("pattern", "Only lines matching this regular expression")
+            .query_param(
+                "limit",
+                "The maximum number of lines to return (default 100, max 1000)",
+            )
+            .response::<SearchFuncRunLogsResponse>(),
+        )
+        .get(
+            "/:func_run_id/logs",
+            tail_func_run_log,
+            OperationDoc::new(
+                "tailFuncRunLog",
+                "Stream the log lines of a func run as server-sent `line` events until its log is complete",
+            ),
+        )
+}
+
+api_types! {
+    pub struct ListFuncsResponse {
+        pub funcs: Vec<FuncView>,
+    }
+
+    pub struct FuncView {
+        pub id: FuncId,
+        pub name: String,
+        pub display_name: Option<String>,
+        pub kind: String,
+        pub is_locked: bool,
+    }
+
+    pub struct ListFuncRunsResponse {
+        pub func_runs: Vec<FuncRunView>,
+    }
+
+    pub struct FuncRunView {
+        pub id: FuncRunId,
+        pub state: String,
+        pub func_id: Option<FuncId>,
+        pub function_name: String,
+        pub function_kind: String,
+        pub component_id: Option<ComponentId>,
+        pub action_id: Option<ActionId>,
+        pub created_at: DateTime<Utc>,
+        pub updated_at: DateTime<Utc>,
+    }
+
+    pub struct SearchFuncRunLogsResponse {
+        pub matches: Vec<FuncRunLogMatchView>,
+        /// Whether more lines may have matched than were returned
+        pub truncated: bool,
+    }
+
+    pub struct FuncRunLogMatchView {
+        pub func_run_id: FuncRunId,
+        pub component_id: Option<ComponentId>,
+        pub component_name: Option<String>,
+        pub function_name: String,
+        pub line_number: u64,
+        pub line: FuncRunLogLineView,
+    }
+
+    pub struct FuncRunLogLineView {
+        pub stream: String,
+        pub level: String,
+        pub group: Option<String>,
+        pub message: String,
+        pub timestamp: u64,
+    }
+}
+
+impl From<FuncRunLogLine> for FuncRunLogLineView {
+    fn from(line: FuncRunLogLine) -> Self {
+        Self {
+            stream: line.stream,
+            level: line.level,
+            group: line.group,
+            message: line.message,
+            timestamp: line.timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListFuncRunsQuery {
+    limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct FuncRunPath {
+    func_run_id: FuncRunId,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchFuncRunLogsQuery {
+    component_id: Option<ComponentId>,
+    func_id: Option<FuncId>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    stream: Option<String>,
+    contains: Option<String>,
+    pattern: Option<String>,
+    limit: Option<usize>,
+}
+
+async fn list_funcs(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+) -> Result<Json<ListFuncsResponse>> {
+    let funcs = Func::list_all(&ctx)
+        .await?
+        .into_iter()
+        .map(|func| FuncView {
+            id: func.id,
+            name: func.name,
+            display_name: func.display_name,
+            kind: func.kind.to_string(),
+            is_locked: func.is_locked,
+        })
+        .collect();
+
+    Ok(Json(ListFuncsResponse { funcs }))
+}
+
+async fn list_func_runs(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    Query(query): Query<ListFuncRunsQuery>,
+) -> Result<Json<ListFuncRunsResponse>> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_FUNC_RUN_LIMIT)
+        .min(MAX_FUNC_RUN_LIMIT);
+
+    let func_runs = ctx
+        .layer_db()
+        .func_run()
+        .list_for_change_set(ctx.workspace_pk()?, ctx.change_set_id(), limit as i64)
+        .await?
+        .into_iter()
+        .map(|func_run| FuncRunView {
+            id: func_run.id(),
+            state: func_run.state().to_string(),
+            func_id: func_run.func_id(),
+            function_name: func_run.function_name().to_owned(),
+            function_kind: func_run.function_kind().to_string(),
+            component_id: func_run.component_id(),
+            action_id: func_run.action_id(),
+            created_at: func_run.created_at(),
+            updated_at: func_run.updated_at(),
+        })
+        .collect();
+
+    Ok(Json(ListFuncRunsResponse { func_runs }))
+}
+
+async fn search_func_run_logs(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    Query(query): Query<SearchFuncRunLogsQuery>,
+) -> Result<Json<SearchFuncRunLogsResponse>> {
+    let results = FuncRunLogSearch {
+        change_set_id: Some(ctx.change_set_id()),
+        component_id: query.component_id,
+        func_id: query.func_id,
+        since: query.since,
+        until: query.until,
+        stream: query.stream,
+        contains: query.contains,
+        pattern: query.pattern,
+        limit: query.limit,
+    }
+    .run(&ctx)
+    .await?;
+
+    let matches = results
+        .matches
+        .into_iter()
+        .map(|found| FuncRunLogMatchView {
+            func_run_id: found.func_run_id,
+            component_id: found.component_id,
+            component_name: found.component_name,
+            function_name: found.function_name,
+            line_number: found.line_number as u64,
+            line: found.line.into(),
+        })
+        .collect();
+
+    Ok(Json(SearchFuncRunLogsResponse {
+        matches,
+        truncated: results.truncated,
+    }))
+}
+
+async fn tail_func_run_log(
+    ChangeSetDalContext(ctx): ChangeSetDalContext,
+    Path(FuncRunPath { func_run_id }): Path<FuncRunPath>,
+    State(shutdown_token): State<CancellationToken>,
+) -> Result<Sse<impl Stream<Item = std::result::Result<Event, axum::Error>>>> {
+    let lines = run_log::tail(&ctx, func_run_id)
+        .await?
+        .map(|line| {
+            Event::default()
+                .event("line")
+                .json_data(FuncRunLogLineView::from(line))
+        })
+        .take_until(shutdown_token.cancelled_owned());
+
+    Ok(Sse::new(lines).keep_alive(KeepAlive::default()))
+}
@@ -2,16 +2,18 @@ use axum::{
     extract::Path,
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::post,
-    Json, Router,
+    Json,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use si_events::audit_log::AuditLogKind;
 use thiserror::Error;
 use veritech_client::ManagementFuncStatus;
 
+use super::{
+    api_types, error_response,
+    openapi::{ApiRouter, OperationDoc},
+};
 use crate::extract::{change_set::ChangeSetDalContext, PosthogEventTracker};
-use crate::AppState;
 
 use dal::{
     diagram::view::ViewId,
@@ -23,11 +25,17 @@ use dal::{
     ComponentId, Func, FuncError, WsEvent,
 };
 
-// /api/public/workspaces/:workspace_id/change-sets/:change_set_id/components
-pub fn routes() -> Router<AppState> {
-    Router::new().nest(
-        "/prototype/:management_prototype_id",
-        Router::new().route("/:component_id/:view_id", post(run_prototype)),
+// /api/public/workspaces/:workspace_id/change-sets/:change_set_id/management
+pub fn routes() -> ApiRouter {
+    ApiRouter::new("Management").post(
+        "/prototype/:management_prototype_id/:component_id/:view_id",
+        run_prototype,
+        OperationDoc::new(
+            "runManagementPrototype",
+            "Run a management function against a component",
+        )
+        .request::<RunPrototypeRequest>()
+        .response::<RunPrototypeResponse>(),
     )
 }
 
@@ -122,17 +130,15 @@ async fn run_prototype(
     ))
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct RunPrototypeRequest {
-    request_ulid: Option<ulid::Ulid>,
-}
+api_types! {
+    pub struct RunPrototypeRequest {
+        pub request_ulid: Option<ulid::Ulid>,
+    }
 
-#[derive(Debug, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct RunPrototypeResponse {
-    status: ManagementFuncStatus,
-    message: Option<String>,
+    pub struct RunPrototypeResponse {
+        pub status: ManagementFuncStatus,
+        pub message: Option<String>,
+    }
 }
 
 #[derive(Deserialize)]
@@ -179,6 +185,12 @@ type Result<T> = std::result::Result<T, ManagementApiError>;
 
 impl IntoResponse for ManagementApiError {
     fn into_response(self) -> Response {
-        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+        let status_code = match &self {
+            Self::ManagementPrototype(
+                dal::management::prototype::ManagementPrototypeError::NotFound(_),
+            ) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        error_response(status_code, self)
     }
 }
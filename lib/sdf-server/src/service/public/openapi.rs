@@ -0,0 +1,609 @@
+//! Generates the OpenAPI document for the public API from its route definitions.
+//!
+//! Routes are registered through an [`ApiRouter`], which records an [`OperationDoc`] alongside each handler. Request
+//! and response types implement [`ApiSchema`] (usually via [`api_types!`](crate::service::public::api_types)) so that
+//! their schemas are derived from the same definitions that serde uses.
+
+use std::collections::{BTreeMap, HashMap};
+
+use axum::{
+    handler::Handler,
+    routing::{delete, get, post, put},
+    Router,
+};
+use chrono::{DateTime, Utc};
+use convert_case::{Case, Casing};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use si_jwt_public_key::SiJwtPermission;
+
+use crate::{middleware::TokenPermissionLayer, AppState};
+
+/// A type that can describe itself as an OpenAPI schema.
+pub trait ApiSchema {
+    /// Whether a field of this type must be present in its parent object.
+    const REQUIRED: bool = true;
+
+    fn schema(registry: &mut SchemaRegistry) -> Value;
+}
+
+/// Collects named schemas so they can be referenced rather than repeated.
+#[derive(Debug, Default)]
+pub struct SchemaRegistry {
+    schemas: BTreeMap<&'static str, Value>,
+}
+
+impl SchemaRegistry {
+    /// Registers a named schema, building it only the first time it is seen, and returns a reference to it.
+    pub fn reference(
+        &mut self,
+        name: &'static str,
+        build: impl FnOnce(&mut Self) -> Value,
+    ) -> Value {
+        if !self.schemas.contains_key(name) {
+            // Insert a placeholder first so self-referential types terminate.
+            self.schemas.insert(name, Value::Null);
+            let schema = build(self);
+            self.schemas.insert(name, schema);
+        }
+        json!({ "$ref": format!("#/components/schemas/{name}") })
+    }
+}
+
+/// Builds an object schema from its fields, converting field names to the camelCase used on the wire.
+pub fn object_schema(fields: Vec<(&str, Value, bool)>) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for (name, schema, is_required) in fields {
+        let name = name.to_case(Case::Camel);
+        if is_required {
+            required.push(Value::String(name.clone()));
+        }
+        properties.insert(name, schema);
+    }
+
+    let mut schema = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = Value::Array(required);
+    }
+    schema
+}
+
+/// Builds a schema for an enum that serializes as one of a fixed set of strings.
+pub fn string_enum_schema(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+impl ApiSchema for String {
+    fn schema(_registry: &mut SchemaRegistry) -> Value {
+        json!({ "type": "string" })
+    }
+}
+
+impl ApiSchema for bool {
+    fn schema(_registry: &mut SchemaRegistry) -> Value {
+        json!({ "type": "boolean" })
+    }
+}
+
+impl ApiSchema for u64 {
+    fn schema(_registry: &mut SchemaRegistry) -> Value {
+        json!({ "type": "integer", "format": "int64", "minimum": 0 })
+    }
+}
+
+impl ApiSchema for Value {
+    fn schema(_registry: &mut SchemaRegistry) -> Value {
+        json!({ "description": "Any JSON value" })
+    }
+}
+
+impl ApiSchema for DateTime<Utc> {
+    fn schema(_registry: &mut SchemaRegistry) -> Value {
+        json!({ "type": "string", "format": "date-time" })
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Option<T> {
+    const REQUIRED: bool = false;
+
+    fn schema(registry: &mut SchemaRegistry) -> Value {
+        let mut schema = T::schema(registry);
+        if schema.get("$ref").is_some() {
+            schema = json!({ "allOf": [schema] });
+        }
+        schema["nullable"] = Value::Bool(true);
+        schema
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Vec<T> {
+    fn schema(registry: &mut SchemaRegistry) -> Value {
+        json!({ "type": "array", "items": T::schema(registry) })
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for HashMap<String, T> {
+    fn schema(registry: &mut SchemaRegistry) -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema(registry) })
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for BTreeMap<String, T> {
+    fn schema(registry: &mut SchemaRegistry) -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema(registry) })
+    }
+}
+
+macro_rules! impl_id_api_schema {
+    ($($id:ty),* $(,)?) => {
+        $(
+            impl ApiSchema for $id {
+                fn schema(_registry: &mut SchemaRegistry) -> Value {
+                    json!({ "type": "string", "format": "ulid" })
+                }
+            }
+        )*
+    };
+}
+
+impl_id_api_schema!(
+    dal::ActionPrototypeId,
+    dal::ChangeSetId,
+    dal::ComponentId,
+    dal::FuncId,
+    dal::SchemaVariantId,
+    dal::WorkspacePk,
+    dal::action::ActionId,
+    dal::diagram::view::ViewId,
+    si_events::FuncRunId,
+    ulid::Ulid,
+);
+
+/// Returns the string serde writes for a unit variant, so that enum schemas list exactly what goes over the wire.
+fn serialized_variant_name<T: Serialize>(variant: &T) -> String {
+    match serde_json::to_value(variant) {
+        Ok(Value::String(name)) => name,
+        other => panic!("enum variant does not serialize as a string: {other:?}"),
+    }
+}
+
+/// Implements [`ApiSchema`] for enums that serialize as strings. Every variant must be listed, which the compiler
+/// checks, and the strings come from serde rather than being written out by hand.
+macro_rules! impl_string_enum_api_schema {
+    ($($ty:ty => [$($variant:path),* $(,)?]),* $(,)?) => {
+        $(
+            impl ApiSchema for $ty {
+                fn schema(registry: &mut SchemaRegistry) -> Value {
+                    #[allow(dead_code)]
+                    fn all_variants_listed(value: $ty) {
+                        match value {
+                            $($variant)|* => {}
+                        }
+                    }
+
+                    let name = stringify!($ty).rsplit("::").next().unwrap_or(stringify!($ty));
+                    registry.reference(name, |_registry| {
+                        let values = [$(serialized_variant_name(&$variant)),*];
+                        string_enum_schema(&values.iter().map(String::as_str).collect::<Vec<_>>())
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_string_enum_api_schema!(
+    dal::ChangeSetStatus => [
+        dal::ChangeSetStatus::Abandoned,
+        dal::ChangeSetStatus::Applied,
+        dal::ChangeSetStatus::Approved,
+        dal::ChangeSetStatus::Failed,
+        dal::ChangeSetStatus::NeedsAbandonApproval,
+        dal::ChangeSetStatus::NeedsApproval,
+        dal::ChangeSetStatus::Open,
+        dal::ChangeSetStatus::Rejected,
+    ],
+    dal::action::ActionState => [
+        dal::action::ActionState::Dispatched,
+        dal::action::ActionState::Failed,
+        dal::action::ActionState::OnHold,
+        dal::action::ActionState::Queued,
+        dal::action::ActionState::Running,
+    ],
+    dal::action::prototype::ActionKind => [
+        dal::action::prototype::ActionKind::Create,
+        dal::action::prototype::ActionKind::Destroy,
+        dal::action::prototype::ActionKind::Manual,
+        dal::action::prototype::ActionKind::Refresh,
+        dal::action::prototype::ActionKind::Update,
+    ],
+    dal::qualification::QualificationSubCheckStatus => [
+        dal::qualification::QualificationSubCheckStatus::Failure,
+        dal::qualification::QualificationSubCheckStatus::Success,
+        dal::qualification::QualificationSubCheckStatus::Unknown,
+        dal::qualification::QualificationSubCheckStatus::Warning,
+    ],
+    veritech_client::ManagementFuncStatus => [
+        veritech_client::ManagementFuncStatus::Error,
+        veritech_client::ManagementFuncStatus::Ok,
+    ],
+    veritech_client::ResourceStatus => [
+        veritech_client::ResourceStatus::Error,
+        veritech_client::ResourceStatus::Ok,
+        veritech_client::ResourceStatus::Warning,
+    ],
+);
+
+/// Describes a single operation in the OpenAPI document.
+#[derive(Clone, Debug)]
+pub struct OperationDoc {
+    operation_id: &'static str,
+    summary: &'static str,
+    query_params: Vec<(&'static str, &'static str)>,
+    request_body: Option<fn(&mut SchemaRegistry) -> Value>,
+    response_body: Option<fn(&mut SchemaRegistry) -> Value>,
+    permission: Option<SiJwtPermission>,
+}
+
+impl OperationDoc {
+    pub fn new(operation_id: &'static str, summary: &'static str) -> Self {
+        Self {
+            operation_id,
+            summary,
+            query_params: Vec::new(),
+            request_body: None,
+            response_body: None,
+            permission: None,
+        }
+    }
+
+    /// Declares the permission a scoped token needs for this operation, for operations that need more than
+    /// [`SiJwtPermission::ReadOnly`] to read or [`SiJwtPermission::ChangeSetWrite`] to write.
+    pub fn permission(mut self, permission: SiJwtPermission) -> Self {
+        self.permission = Some(permission);
+        self
+    }
+
+    /// Documents an optional query parameter.
+    pub fn query_param(mut self, name: &'static str, description: &'static str) -> Self {
+        self.query_params.push((name, description));
+        self
+    }
+
+    pub fn request<T: ApiSchema>(mut self) -> Self {
+        self.request_body = Some(T::schema);
+        self
+    }
+
+    pub fn response<T: ApiSchema>(mut self) -> Self {
+        self.response_body = Some(T::schema);
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Operation {
+    method: &'static str,
+    path: String,
+    tag: &'static str,
+    doc: OperationDoc,
+}
+
+/// Wraps a [`Router`], recording documentation for every route registered through it.
+pub struct ApiRouter {
+    router: Router<AppState>,
+    operations: Vec<Operation>,
+    tag: &'static str,
+}
+
+impl ApiRouter {
+    /// Creates a router whose operations are grouped under `tag`.
+    pub fn new(tag: &'static str) -> Self {
+        Self {
+            router: Router::new(),
+            operations: Vec::new(),
+            tag,
+        }
+    }
+
+    pub fn get<H, T>(self, path: &str, handler: H, doc: OperationDoc) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        self.route("get", path, get(handler), doc)
+    }
+
+    pub fn post<H, T>(self, path: &str, handler: H, doc: OperationDoc) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        self.route("post", path, post(handler), doc)
+    }
+
+    pub fn put<H, T>(self, path: &str, handler: H, doc: OperationDoc) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        self.route("put", path, put(handler), doc)
+    }
+
+    pub fn delete<H, T>(self, path: &str, handler: H, doc: OperationDoc) -> Self
+    where
+        H: Handler<T, AppState>,
+        T: 'static,
+    {
+        self.route("delete", path, delete(handler), doc)
+    }
+
+    fn route(
+        mut self,
+        method: &'static str,
+        path: &str,
+        method_router: axum::routing::MethodRouter<AppState>,
+        doc: OperationDoc,
+    ) -> Self {
+        let permission = required_permission(method, &doc);
+        self.router = self.router.route(
+            path,
+            method_router.layer(TokenPermissionLayer::new(permission)),
+        );
+        self.operations.push(Operation {
+            method,
+            path: path.to_owned(),
+            tag: self.tag,
+            doc,
+        });
+        self
+    }
+
+    pub fn nest(mut self, path: &str, other: ApiRouter) -> Self {
+        self.router = self.router.nest(path, other.router);
+        self.operations
+            .extend(other.operations.into_iter().map(|mut operation| {
+                operation.path = join_paths(path, &operation.path);
+                operation
+            }));
+        self
+    }
+
+    /// Applies a transformation, such as a route layer, to the underlying router.
+    pub fn map_router(mut self, f: impl FnOnce(Router<AppState>) -> Router<AppState>) -> Self {
+        self.router = f(self.router);
+        self
+    }
+
+    pub fn into_parts(self) -> (Router<AppState>, Vec<Operation>) {
+        (self.router, self.operations)
+    }
+}
+
+/// The permission a scoped token needs for an operation: whatever it declares, otherwise
+/// [`SiJwtPermission::ReadOnly`] to read and [`SiJwtPermission::ChangeSetWrite`] to write.
+fn required_permission(method: &str, doc: &OperationDoc) -> SiJwtPermission {
+    match (doc.permission, method) {
+        (Some(permission), _) => permission,
+        (None, "get") => SiJwtPermission::ReadOnly,
+        (None, _) => SiJwtPermission::ChangeSetWrite,
+    }
+}
+
+fn join_paths(prefix: &str, path: &str) -> String {
+    match path {
+        "/" => prefix.to_owned(),
+        path => format!("{}{}", prefix.trim_end_matches('/'), path),
+    }
+}
+
+/// Converts an axum path (`/:id`) to an OpenAPI path (`/{id}`), returning the names of its parameters.
+fn openapi_path(path: &str) -> (String, Vec<&str>) {
+    let mut params = Vec::new();
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(param) => {
+                params.push(param);
+                format!("{{{param}}}")
+            }
+            None => segment.to_owned(),
+        })
+        .collect();
+    (segments.join("/"), params)
+}
+
+/// Builds the OpenAPI document for a set of operations mounted under `server_url`.
+pub fn document(
+    server_url: &str,
+    operations: &[Operation],
+    error_body: fn(&mut SchemaRegistry) -> Value,
+) -> Value {
+    let mut registry = SchemaRegistry::default();
+    let error_schema = error_body(&mut registry);
+
+    let mut paths = Map::new();
+    for operation in operations {
+        let (path, path_params) = openapi_path(&operation.path);
+
+        let mut parameters: Vec<Value> = path_params
+            .into_iter()
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            })
+            .collect();
+        parameters.extend(
+            operation
+                .doc
+                .query_params
+                .iter()
+                .map(|(name, description)| {
+                    json!({
+                        "name": name,
+                        "in": "query",
+                        "required": false,
+                        "description": description,
+                        "schema": { "type": "string" },
+                    })
+                }),
+        );
+
+        let success = match operation.doc.response_body {
+            Some(response_body) => json!({
+                "description": "Success",
+                "content": { "application/json": { "schema": response_body(&mut registry) } },
+            }),
+            None => json!({ "description": "Success" }),
+        };
+
+        let mut entry = json!({
+            "operationId": operation.doc.operation_id,
+            "summary": operation.doc.summary,
+            "tags": [operation.tag],
+            "parameters": parameters,
+            "responses": {
+                "200": success,
+                "default": {
+                    "description": "Error",
+                    "content": { "application/json": { "schema": error_schema.clone() } },
+                },
+            },
+        });
+        if let Some(request_body) = operation.doc.request_body {
+            entry["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": request_body(&mut registry) } },
+            });
+        }
+
+        if let Value::Object(methods) = paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()))
+        {
+            methods.insert(operation.method.to_owned(), entry);
+        }
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "System Initiative Public API",
+            "version": "v0",
+        },
+        "servers": [{ "url": server_url }],
+        "security": [{ "bearerAuth": [] }],
+        "paths": paths,
+        "components": {
+            "schemas": registry.schemas,
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_permission_defaults_by_method() {
+        let doc = OperationDoc::new("operation", "An operation");
+        assert_eq!(SiJwtPermission::ReadOnly, required_permission("get", &doc));
+        for method in ["post", "put", "delete"] {
+            assert_eq!(
+                SiJwtPermission::ChangeSetWrite,
+                required_permission(method, &doc),
+                "{method}"
+            );
+        }
+
+        let doc = doc.permission(SiJwtPermission::Apply);
+        assert_eq!(SiJwtPermission::Apply, required_permission("get", &doc));
+        assert_eq!(SiJwtPermission::Apply, required_permission("post", &doc));
+    }
+
+    #[test]
+    fn change_set_operations_declare_permissions() {
+        let (_, operations) = super::super::change_sets::routes().into_parts();
+        let permission = |operation_id: &str| {
+            operations
+                .iter()
+                .find(|operation| operation.doc.operation_id == operation_id)
+                .map(|operation| required_permission(operation.method, &operation.doc))
+                .unwrap_or_else(|| panic!("no operation with id {operation_id}"))
+        };
+
+        assert_eq!(SiJwtPermission::Apply, permission("applyChangeSet"));
+        assert_eq!(SiJwtPermission::ActionExecute, permission("enqueueAction"));
+        assert_eq!(SiJwtPermission::ActionExecute, permission("cancelAction"));
+        assert_eq!(SiJwtPermission::ReadOnly, permission("getApplyPlan"));
+        assert_eq!(SiJwtPermission::ReadOnly, permission("queryComponents"));
+        assert_eq!(SiJwtPermission::ReadOnly, permission("exportComponents"));
+        assert_eq!(
+            SiJwtPermission::ChangeSetWrite,
+            permission("requestChangeSetApproval")
+        );
+        assert_eq!(
+            SiJwtPermission::ChangeSetWrite,
+            permission("createComponent")
+        );
+    }
+
+    fn enum_values<T: ApiSchema>(name: &str) -> Vec<String> {
+        let mut registry = SchemaRegistry::default();
+        T::schema(&mut registry);
+        registry.schemas[name]["enum"]
+            .as_array()
+            .expect("schema should list its values")
+            .iter()
+            .map(|value| value.as_str().expect("values are strings").to_owned())
+            .collect()
+    }
+
+    fn assert_round_trips<T: ApiSchema + serde::de::DeserializeOwned + Serialize>(name: &str) {
+        for value in enum_values::<T>(name) {
+            let variant: T = serde_json::from_value(Value::String(value.clone()))
+                .unwrap_or_else(|err| panic!("{name} does not accept {value:?}: {err}"));
+            assert_eq!(value, serialized_variant_name(&variant));
+        }
+    }
+
+    #[test]
+    fn string_enum_schemas_match_serde() {
+        assert_eq!(
+            vec![
+                "Abandoned",
+                "Applied",
+                "Approved",
+                "Failed",
+                "NeedsAbandonApproval",
+                "NeedsApproval",
+                "Open",
+                "Rejected",
+            ],
+            enum_values::<dal::ChangeSetStatus>("ChangeSetStatus")
+        );
+        assert_eq!(
+            vec!["failure", "success", "unknown", "warning"],
+            enum_values::<dal::qualification::QualificationSubCheckStatus>(
+                "QualificationSubCheckStatus"
+            )
+        );
+
+        assert_round_trips::<dal::ChangeSetStatus>("ChangeSetStatus");
+        assert_round_trips::<dal::action::ActionState>("ActionState");
+        assert_round_trips::<dal::action::prototype::ActionKind>("ActionKind");
+        assert_round_trips::<dal::qualification::QualificationSubCheckStatus>(
+            "QualificationSubCheckStatus",
+        );
+        assert_round_trips::<veritech_client::ManagementFuncStatus>("ManagementFuncStatus");
+        assert_round_trips::<veritech_client::ResourceStatus>("ResourceStatus");
+    }
+}
@@ -1,19 +1,23 @@
-use axum::{middleware, Router};
+use axum::middleware;
 
+use super::openapi::ApiRouter;
 use crate::{
     extract::workspace::{AuthorizedForAutomationRole, TargetWorkspaceIdFromPath},
     AppState,
 };
 
-pub fn routes(state: AppState) -> Router<AppState> {
-    Router::new().nest(
+pub fn routes(state: AppState) -> ApiRouter {
+    ApiRouter::new("Workspaces").nest(
         "/:workspace_id",
-        Router::new()
+        ApiRouter::new("Workspaces")
             .nest("/change-sets", super::change_sets::routes())
-            .route_layer(middleware::from_extractor_with_state::<
-                AuthorizedForAutomationRole,
-                AppState,
-            >(state))
-            .route_layer(middleware::from_extractor::<TargetWorkspaceIdFromPath>()),
+            .map_router(|router| {
+                router
+                    .route_layer(middleware::from_extractor_with_state::<
+                        AuthorizedForAutomationRole,
+                        AppState,
+                    >(state))
+                    .route_layer(middleware::from_extractor::<TargetWorkspaceIdFromPath>())
+            }),
     )
 }
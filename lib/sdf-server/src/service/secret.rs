@@ -6,11 +6,12 @@ use dal::{
     ChangeSetError, KeyPairError, SecretId, StandardModelError, TransactionsError, UserError,
     WorkspacePk, WsEventError,
 };
+use si_jwt_public_key::SiJwtPermission;
 use telemetry::prelude::*;
 use thiserror::Error;
 
 use super::impl_default_error_into_response;
-use crate::AppState;
+use crate::{middleware::TokenPermissionLayer, AppState};
 
 pub mod create_secret;
 pub mod delete_secret;
@@ -62,8 +63,24 @@ impl_default_error_into_response!(SecretError);
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/get_public_key", get(get_public_key::get_public_key))
-        .route("/", post(create_secret::create_secret))
-        .route("/", get(list_secrets::list_secrets))
-        .route("/", patch(update_secret::update_secret))
-        .route("/", delete(delete_secret::delete_secret))
+        .route(
+            "/",
+            post(create_secret::create_secret)
+                .layer(TokenPermissionLayer::new(SiJwtPermission::ChangeSetWrite)),
+        )
+        .route(
+            "/",
+            get(list_secrets::list_secrets)
+                .layer(TokenPermissionLayer::new(SiJwtPermission::SecretRead)),
+        )
+        .route(
+            "/",
+            patch(update_secret::update_secret)
+                .layer(TokenPermissionLayer::new(SiJwtPermission::ChangeSetWrite)),
+        )
+        .route(
+            "/",
+            delete(delete_secret::delete_secret)
+                .layer(TokenPermissionLayer::new(SiJwtPermission::ChangeSetWrite)),
+        )
 }
@@ -14,6 +14,7 @@ use crate::{
 pub mod admin;
 pub mod audit_log;
 pub mod change_set;
+pub mod component;
 pub mod fs;
 pub mod func;
 pub mod integrations;
@@ -31,12 +32,13 @@ pub fn routes(state: AppState) -> Router<AppState> {
 
 fn workspace_routes(state: AppState) -> Router<AppState> {
     Router::new()
-        .nest("/", workspace::v2_routes())
+        .nest("/", workspace::v2_routes(state.clone()))
         .nest("/change-sets", change_set::change_sets_routes())
         .nest(
             "/change-sets/:change_set_id",
             change_set::change_set_routes(state.clone())
                 .nest("/audit-logs", audit_log::v2_routes())
+                .nest("/components", component::v2_routes())
                 .nest("/funcs", func::v2_routes())
                 .nest("/modules", module::v2_routes())
                 .nest("/schema-variants", variant::v2_routes())
@@ -9,9 +9,11 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use dal::{
-    cached_module::CachedModuleError, func::runner::FuncRunnerError,
-    workspace_snapshot::graph::WorkspaceSnapshotGraphDiscriminants, ChangeSet, ChangeSetId,
-    ChangeSetStatus, User, UserPk, Workspace, WorkspacePk, WorkspaceSnapshotAddress,
+    cached_module::CachedModuleError,
+    func::{retention::FuncRunRetentionError, runner::FuncRunnerError},
+    workspace_snapshot::graph::WorkspaceSnapshotGraphDiscriminants,
+    ChangeSet, ChangeSetId, ChangeSetStatus, User, UserPk, Workspace, WorkspacePk,
+    WorkspaceSnapshotAddress,
 };
 use serde::{Deserialize, Serialize};
 use telemetry::prelude::*;
@@ -27,11 +29,15 @@ use crate::{
     AppState,
 };
 
+mod fsck_snapshot;
+mod func_run_retention;
 mod get_snapshot;
 mod kill_execution;
 mod list_change_sets;
+mod list_rebaser_leases;
 mod list_workspace_users;
 mod prompts;
+mod rotate_key_pair;
 mod search_workspaces;
 mod set_concurrency_limit;
 mod set_snapshot;
@@ -49,14 +55,22 @@ pub enum AdminAPIError {
     CachedModule(#[from] CachedModuleError),
     #[error("change set error: {0}")]
     ChangeSet(#[from] dal::ChangeSetError),
+    #[error("chunked snapshot error: {0}")]
+    ChunkedSnapshot(#[from] dal::workspace_snapshot::chunked::ChunkedSnapshotError),
     #[error("func runner error: {0}")]
     FuncRunner(#[from] FuncRunnerError),
+    #[error("func run retention error: {0}")]
+    FuncRunRetention(#[from] FuncRunRetentionError),
+    #[error("key pair error: {0}")]
+    KeyPair(#[from] dal::KeyPairError),
     #[error("layer db error: {0}")]
     LayerDb(#[from] si_layer_cache::LayerDbError),
     #[error("multipart error: {0}")]
     Multipart(#[from] axum::extract::multipart::MultipartError),
     #[error("No multipart data found in request")]
     NoMultipartData,
+    #[error("rebaser client error: {0}")]
+    Rebaser(#[from] rebaser_client::ClientError),
     #[error("tokio join error: {0}")]
     TokioJoin(#[from] tokio::task::JoinError),
     #[error("transactions error: {0}")]
@@ -156,6 +170,9 @@ impl IntoResponse for AdminAPIError {
             AdminAPIError::FuncRunner(FuncRunnerError::DoNotHavePermissionToKillExecution) => {
                 StatusCode::UNAUTHORIZED
             }
+            AdminAPIError::FuncRunRetention(FuncRunRetentionError::InvalidRetentionDays(_)) => {
+                StatusCode::BAD_REQUEST
+            }
             _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
         };
 
@@ -175,6 +192,14 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
             "/func/runs/:func_run_id/kill_execution",
             put(kill_execution::kill_execution),
         )
+        .route(
+            "/func_run_storage",
+            get(func_run_retention::func_run_storage),
+        )
+        .route(
+            "/rebaser/leases",
+            get(list_rebaser_leases::list_rebaser_leases),
+        )
         .route("/workspaces", get(search_workspaces::search_workspaces))
         .route(
             "/workspaces/:workspace_id/users",
@@ -184,10 +209,23 @@ pub fn v2_routes(state: AppState) -> Router<AppState> {
             "/workspaces/:workspace_id/set_concurrency_limit",
             post(set_concurrency_limit::set_concurrency_limit),
         )
+        .route(
+            "/workspaces/:workspace_id/func_run_retention",
+            get(func_run_retention::func_run_retention)
+                .post(func_run_retention::set_func_run_retention),
+        )
+        .route(
+            "/workspaces/:workspace_id/rotate_key_pair",
+            post(rotate_key_pair::rotate_key_pair),
+        )
         .route(
             "/workspaces/:workspace_id/change_sets",
             get(list_change_sets::list_change_sets),
         )
+        .route(
+            "/workspaces/:workspace_id/change_sets/:change_set_id/fsck",
+            post(fsck_snapshot::fsck_snapshot),
+        )
         .route(
             "/workspaces/:workspace_id/change_sets/:change_set_id/get_snapshot",
             get(get_snapshot::get_snapshot),
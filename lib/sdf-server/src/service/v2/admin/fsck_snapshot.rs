@@ -0,0 +1,89 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    response::Json,
+};
+use dal::{
+    workspace_snapshot::graph::v4::fsck::SnapshotViolation, ChangeSet, ChangeSetId, Tenancy,
+    WorkspacePk, WorkspaceSnapshot, WorkspaceSnapshotAddress,
+};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    extract::PosthogClient,
+    service::v2::admin::{AdminAPIResult, AdminUserContext},
+    track_no_ctx,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsckSnapshotRequest {
+    /// Repairs what can be repaired and points the change set at the repaired snapshot.
+    #[serde(default)]
+    pub repair: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsckSnapshotResponse {
+    pub violations: Vec<SnapshotViolation>,
+    pub repaired: Vec<SnapshotViolation>,
+    pub workspace_snapshot_address: WorkspaceSnapshotAddress,
+}
+
+#[instrument(
+    name = "admin.fsck_snapshot",
+    level = "info",
+    skip_all,
+    fields(
+        si.change_set.id = %change_set_id,
+        si.workspace.id = %workspace_id,
+    ),
+)]
+pub async fn fsck_snapshot(
+    AdminUserContext(mut ctx): AdminUserContext,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((workspace_id, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Json(request): Json<FsckSnapshotRequest>,
+) -> AdminAPIResult<Json<FsckSnapshotResponse>> {
+    ctx.update_tenancy(Tenancy::new(workspace_id));
+
+    let mut change_set = ChangeSet::get_by_id(&ctx, change_set_id).await?;
+    let snapshot = WorkspaceSnapshot::find(&ctx, change_set.workspace_snapshot_address).await?;
+
+    let violations = snapshot.fsck(&ctx).await?;
+
+    let mut repaired = Vec::new();
+    let mut workspace_snapshot_address = change_set.workspace_snapshot_address;
+    if request.repair && violations.iter().any(|violation| violation.repairable) {
+        repaired = snapshot.repair(&violations).await?;
+        workspace_snapshot_address = snapshot.write(&ctx).await?;
+        change_set
+            .update_pointer(&ctx, workspace_snapshot_address)
+            .await?;
+        ctx.commit_no_rebase().await?;
+    }
+
+    track_no_ctx(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        Some(workspace_id.to_string()),
+        Some(change_set_id.to_string()),
+        "admin.fsck_snapshot",
+        serde_json::json!({
+            "violations": violations.len(),
+            "repaired": repaired.len(),
+            "workspace_snapshot_address": workspace_snapshot_address.to_string(),
+        }),
+    );
+
+    Ok(Json(FsckSnapshotResponse {
+        violations,
+        repaired,
+        workspace_snapshot_address,
+    }))
+}
@@ -0,0 +1,140 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    response::Json,
+};
+use dal::{func::retention::FuncRunRetentionPolicy, FuncKind, Tenancy, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    extract::PosthogClient,
+    service::v2::admin::{AdminAPIResult, AdminUserContext},
+    track_no_ctx,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunStorageResponse {
+    pub workspaces: Vec<WorkspaceFuncRunStorage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceFuncRunStorage {
+    pub workspace_id: String,
+    pub func_run_count: i64,
+    pub func_run_bytes: i64,
+    pub func_run_log_count: i64,
+    pub func_run_log_bytes: i64,
+    pub total_bytes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuncRunRetentionResponse {
+    pub policies: Vec<FuncRunRetentionPolicy>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFuncRunRetentionRequest {
+    pub function_kind: FuncKind,
+    /// Clears the workspace's override for the kind when unset.
+    pub retention_days: Option<i32>,
+    #[serde(default = "default_keep_latest")]
+    pub keep_latest: bool,
+}
+
+fn default_keep_latest() -> bool {
+    true
+}
+
+#[instrument(name = "admin.func_run_storage", level = "info", skip_all)]
+pub async fn func_run_storage(
+    AdminUserContext(ctx): AdminUserContext,
+) -> AdminAPIResult<Json<FuncRunStorageResponse>> {
+    let workspaces = ctx
+        .layer_db()
+        .func_run()
+        .storage_by_workspace()
+        .await?
+        .into_iter()
+        .map(|storage| WorkspaceFuncRunStorage {
+            total_bytes: storage.func_run_bytes + storage.func_run_log_bytes,
+            workspace_id: storage.workspace_id,
+            func_run_count: storage.func_run_count,
+            func_run_bytes: storage.func_run_bytes,
+            func_run_log_count: storage.func_run_log_count,
+            func_run_log_bytes: storage.func_run_log_bytes,
+        })
+        .collect();
+
+    Ok(Json(FuncRunStorageResponse { workspaces }))
+}
+
+#[instrument(
+    name = "admin.func_run_retention",
+    level = "info",
+    skip_all,
+    fields(si.workspace.id = %workspace_id),
+)]
+pub async fn func_run_retention(
+    AdminUserContext(mut ctx): AdminUserContext,
+    Path(workspace_id): Path<WorkspacePk>,
+) -> AdminAPIResult<Json<FuncRunRetentionResponse>> {
+    ctx.update_tenancy(Tenancy::new(workspace_id));
+
+    let policies = FuncRunRetentionPolicy::list(&ctx).await?;
+
+    Ok(Json(FuncRunRetentionResponse { policies }))
+}
+
+#[instrument(
+    name = "admin.set_func_run_retention",
+    level = "info",
+    skip_all,
+    fields(si.workspace.id = %workspace_id),
+)]
+pub async fn set_func_run_retention(
+    AdminUserContext(mut ctx): AdminUserContext,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path(workspace_id): Path<WorkspacePk>,
+    Json(request): Json<SetFuncRunRetentionRequest>,
+) -> AdminAPIResult<Json<FuncRunRetentionPolicy>> {
+    ctx.update_tenancy(Tenancy::new(workspace_id));
+
+    let policy = match request.retention_days {
+        Some(retention_days) => {
+            FuncRunRetentionPolicy::set(
+                &ctx,
+                request.function_kind,
+                retention_days,
+                request.keep_latest,
+            )
+            .await?
+        }
+        None => FuncRunRetentionPolicy::reset(&ctx, request.function_kind).await?,
+    };
+
+    ctx.commit_no_rebase().await?;
+
+    track_no_ctx(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        Some(workspace_id.to_string()),
+        None,
+        "admin.set_func_run_retention",
+        serde_json::json!({
+            "function_kind": policy.function_kind,
+            "retention_days": policy.retention_days,
+            "keep_latest": policy.keep_latest,
+            "is_default": policy.is_default,
+        }),
+    );
+
+    Ok(Json(policy))
+}
@@ -3,7 +3,10 @@ use axum::{
     response::Response,
 };
 use base64::prelude::*;
-use dal::{ChangeSet, ChangeSetId, Tenancy, WorkspacePk};
+use dal::{
+    workspace_snapshot::{chunked, graph::WorkspaceSnapshotGraph},
+    ChangeSet, ChangeSetId, Tenancy, WorkspacePk,
+};
 use hyper::{header, Body};
 
 use crate::{
@@ -25,15 +28,26 @@ pub async fn get_snapshot(
 
     let snap_addr = change_set.workspace_snapshot_address;
 
-    let bytes = ctx
+    let bytes = match ctx
         .layer_db()
         .workspace_snapshot()
         .read_bytes_from_durable_storage(&snap_addr)
         .await?
-        .ok_or(AdminAPIError::WorkspaceSnapshotNotFound(
-            snap_addr,
-            change_set_id,
-        ))?;
+    {
+        Some(bytes) => bytes,
+        None => {
+            // Chunked snapshots are reassembled and handed out whole, in the same format that
+            // set_snapshot accepts.
+            let graph = chunked::read(ctx.layer_db(), snap_addr).await?.ok_or(
+                AdminAPIError::WorkspaceSnapshotNotFound(snap_addr, change_set_id),
+            )?;
+            tokio::task::spawn_blocking(move || {
+                si_layer_cache::db::serialize::to_vec(&WorkspaceSnapshotGraph::V4(graph))
+            })
+            .await??
+            .0
+        }
+    };
 
     let base64 = tokio::task::spawn_blocking(|| BASE64_STANDARD.encode(bytes)).await?;
 
@@ -0,0 +1,53 @@
+use axum::response::Json;
+use dal::{ChangeSetId, WorkspacePk};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::service::v2::admin::{AdminAPIResult, AdminUserContext};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRebaserLeasesResponse {
+    pub leases: Vec<RebaserLease>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaserLease {
+    pub workspace_id: WorkspacePk,
+    pub change_set_id: ChangeSetId,
+    pub instance_id: String,
+    pub fencing_token: u64,
+    pub heartbeat_at_ms: u64,
+    /// Whether the owning instance stopped heartbeating and another instance may take over.
+    pub expired: bool,
+}
+
+#[instrument(name = "admin.list_rebaser_leases", level = "info", skip_all)]
+pub async fn list_rebaser_leases(
+    AdminUserContext(ctx): AdminUserContext,
+) -> AdminAPIResult<Json<ListRebaserLeasesResponse>> {
+    let mut leases: Vec<_> = ctx
+        .services_context()
+        .rebaser()
+        .list_change_set_leases()
+        .await?
+        .into_iter()
+        .map(|lease| RebaserLease {
+            expired: lease.is_expired(),
+            workspace_id: lease.workspace_id,
+            change_set_id: lease.change_set_id,
+            instance_id: lease.instance_id,
+            fencing_token: lease.fencing_token,
+            heartbeat_at_ms: lease.heartbeat_at_ms,
+        })
+        .collect();
+    leases.sort_by(|a, b| {
+        a.instance_id
+            .cmp(&b.instance_id)
+            .then(a.workspace_id.cmp(&b.workspace_id))
+            .then(a.change_set_id.cmp(&b.change_set_id))
+    });
+
+    Ok(Json(ListRebaserLeasesResponse { leases }))
+}
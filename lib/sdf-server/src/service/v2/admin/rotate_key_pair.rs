@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    response::Json,
+};
+use dal::{
+    job::definition::ReencryptSecrets, key_pair::KeyPairPk, ChangeSet, KeyPair, Tenancy,
+    WorkspacePk,
+};
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    extract::PosthogClient,
+    service::v2::admin::{AdminAPIResult, AdminUserContext},
+    track_no_ctx,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotateKeyPairResponse {
+    pub key_pair_pk: KeyPairPk,
+    pub change_set_count: usize,
+}
+
+/// Creates a new [`KeyPair`] for the workspace and enqueues a [`ReencryptSecrets`] job for every
+/// active change set so that secrets encrypted with older key pairs are moved to the new one.
+#[instrument(
+    name = "admin.rotate_key_pair",
+    level = "info",
+    skip_all,
+    fields(
+        si.workspace.id = %workspace_id,
+    ),
+)]
+pub async fn rotate_key_pair(
+    AdminUserContext(mut ctx): AdminUserContext,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path(workspace_id): Path<WorkspacePk>,
+) -> AdminAPIResult<Json<RotateKeyPairResponse>> {
+    ctx.update_tenancy(Tenancy::new(workspace_id));
+
+    let key_pair = KeyPair::new(&ctx, "default").await?;
+    ctx.commit_no_rebase().await?;
+
+    let change_sets = ChangeSet::list_active(&ctx).await?;
+    for change_set in &change_sets {
+        ctx.update_visibility_and_snapshot_to_visibility(change_set.id)
+            .await?;
+        ctx.enqueue_reencrypt_secrets(ReencryptSecrets::new(&ctx))
+            .await?;
+        ctx.commit_no_rebase().await?;
+    }
+
+    track_no_ctx(
+        &posthog_client,
+        &original_uri,
+        &host_name,
+        ctx.history_actor().distinct_id(),
+        Some(workspace_id.to_string()),
+        None,
+        "admin.rotate_key_pair",
+        serde_json::json!({
+            "key_pair_pk": key_pair.pk(),
+            "change_set_count": change_sets.len(),
+        }),
+    );
+
+    Ok(Json(RotateKeyPairResponse {
+        key_pair_pk: key_pair.pk(),
+        change_set_count: change_sets.len(),
+    }))
+}
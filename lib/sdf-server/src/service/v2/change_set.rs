@@ -3,39 +3,53 @@ use std::result;
 use axum::{
     http::StatusCode,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 use dal::{
-    workspace_integrations::WorkspaceIntegration, ChangeSetId, ChangeSetStatus, DalContext,
-    HistoryEventError, WorkspacePk, WsEventError,
+    change_set::{comment::ChangeSetCommentError, schedule::ScheduledApplyError},
+    ChangeSetId, ChangeSetStatus, HistoryEventError, WsEventError,
 };
-use reqwest::Client;
-use serde::Serialize;
 use si_data_spicedb::SpiceDbError;
+use si_jwt_public_key::SiJwtPermission;
 use thiserror::Error;
 
-use crate::{middleware::WorkspacePermissionLayer, service::ApiError, AppState};
+use crate::{
+    middleware::{TokenPermissionLayer, WorkspacePermissionLayer},
+    service::ApiError,
+    AppState,
+};
 
 mod apply;
+mod apply_plan;
+mod approval_status;
 mod approve;
 mod cancel_approval_request;
+mod comments;
 mod force_apply;
 mod list;
+mod policies;
 mod reject;
 mod rename;
 mod reopen;
 mod request_approval;
+mod schedule_apply;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("action error: {0}")]
+    Action(#[from] dal::action::ActionError),
+    #[error("change set approval error: {0}")]
+    Approval(#[from] dal::change_set::approval::ChangeSetApprovalError),
     #[error("change set error: {0}")]
     ChangeSet(#[from] dal::ChangeSetError),
     #[error("change set apply error: {0}")]
     ChangeSetApply(#[from] dal::ChangeSetApplyError),
     #[error("change set not approved for apply. Current state: {0}")]
     ChangeSetNotApprovedForApply(ChangeSetStatus),
+    #[error("comment error: {0}")]
+    Comment(#[from] ChangeSetCommentError),
     #[error("dvu roots are not empty for change set: {0}")]
     DvuRootsNotEmpty(ChangeSetId),
     #[error("func error: {0}")]
@@ -44,8 +58,10 @@ pub enum Error {
     HistoryEvent(#[from] HistoryEventError),
     #[error("permissions error: {0}")]
     Permissions(#[from] permissions::Error),
-    #[error("http error: {0}")]
-    Request(#[from] reqwest::Error),
+    #[error("change set policy error: {0}")]
+    Policy(#[from] dal::change_set::policy::ChangeSetPolicyError),
+    #[error("scheduled apply error: {0}")]
+    ScheduledApply(#[from] ScheduledApplyError),
     #[error("schema error: {0}")]
     Schema(#[from] dal::SchemaError),
     #[error("schema variant error: {0}")]
@@ -58,10 +74,6 @@ pub enum Error {
     Transactions(#[from] dal::TransactionsError),
     #[error("found an unexpected number of open change sets matching default change set (should be one, found {0:?})")]
     UnexpectedNumberOfOpenChangeSetsMatchingDefaultChangeSet(Vec<ChangeSetId>),
-    #[error("Failed to post to webhook: {0}")]
-    Webhook(String),
-    #[error("workspace integration error: {0}")]
-    WorkspaceIntegrations(#[from] dal::workspace_integrations::WorkspaceIntegrationsError),
     #[error("workspace snapshot error: {0}")]
     WorkspaceSnapshot(#[from] dal::WorkspaceSnapshotError),
     #[error("ws event error: {0}")]
@@ -72,7 +84,32 @@ impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
         let status_code = match &self {
             Self::ChangeSetApply(_) => StatusCode::CONFLICT,
-            Self::DvuRootsNotEmpty(_) => StatusCode::PRECONDITION_FAILED,
+            Self::DvuRootsNotEmpty(_)
+            | Self::ChangeSet(dal::ChangeSetError::ApprovalRulesNotSatisfied(_))
+            | Self::ChangeSet(dal::ChangeSetError::EditedSinceApproval(_))
+            | Self::ChangeSet(dal::ChangeSetError::PolicyChecksFailed(_))
+            | Self::Policy(dal::change_set::policy::ChangeSetPolicyError::NothingToOverride(_)) => {
+                StatusCode::PRECONDITION_FAILED
+            }
+            Self::ScheduledApply(
+                ScheduledApplyError::InvalidWindow(_, _)
+                | ScheduledApplyError::WindowAlreadyClosed(_),
+            ) => StatusCode::BAD_REQUEST,
+            Self::ScheduledApply(
+                ScheduledApplyError::AlreadyApplying(_) | ScheduledApplyError::NotSchedulable(_, _),
+            ) => StatusCode::CONFLICT,
+            Self::ScheduledApply(ScheduledApplyError::NotScheduled(_)) => StatusCode::NOT_FOUND,
+            Self::Comment(
+                ChangeSetCommentError::AttributePathWithoutComponent(_)
+                | ChangeSetCommentError::ComponentNotFound(_)
+                | ChangeSetCommentError::EmptyBody
+                | ChangeSetCommentError::UnknownMention(_),
+            ) => StatusCode::BAD_REQUEST,
+            Self::Comment(
+                ChangeSetCommentError::CommentNotFound(_)
+                | ChangeSetCommentError::ThreadNotFound(_),
+            ) => StatusCode::NOT_FOUND,
+            Self::Comment(ChangeSetCommentError::NotAuthor(_)) => StatusCode::FORBIDDEN,
             Self::Transactions(dal::TransactionsError::BadWorkspaceAndChangeSet) => {
                 StatusCode::FORBIDDEN
             }
@@ -87,62 +124,38 @@ pub type ChangeSetAPIError = Error;
 
 type Result<T> = result::Result<T, Error>;
 
-#[derive(Serialize)]
-struct SlackMessage<'a> {
-    text: &'a str,
-}
-
-pub async fn post_to_webhook(
-    ctx: &DalContext,
-    _workspace_id: WorkspacePk,
-    message: &str,
-) -> Result<()> {
-    if let Some(integration) = WorkspaceIntegration::get_integrations_for_workspace_pk(ctx).await? {
-        if let Some(webhook_url) = integration.slack_webhook_url() {
-            let client = Client::new();
-            let slack_message = SlackMessage { text: message };
-
-            let response = client
-                .post(webhook_url.clone())
-                .json(&slack_message)
-                .send()
-                .await?;
-
-            if response.status().is_success() {
-                return Ok(());
-            } else {
-                return Err(Error::Webhook(webhook_url));
-            }
-        }
-    }
-
-    Ok(())
-}
-
 pub fn change_sets_routes() -> Router<AppState> {
     Router::new().route("/", get(list::list_actionable))
 }
 
 pub fn change_set_routes(state: AppState) -> Router<AppState> {
     Router::new()
-        .route("/apply", post(apply::apply))
+        .route(
+            "/apply",
+            post(apply::apply).layer(TokenPermissionLayer::new(SiJwtPermission::Apply)),
+        )
+        .route("/apply_plan", get(apply_plan::apply_plan))
         .route(
             "/request_approval",
             post(request_approval::request_approval),
         )
         .route(
             "/approve",
-            post(approve::approve).layer(WorkspacePermissionLayer::new(
-                state.clone(),
-                permissions::Permission::Approve,
-            )),
+            post(approve::approve)
+                .layer(WorkspacePermissionLayer::new(
+                    state.clone(),
+                    permissions::Permission::Approve,
+                ))
+                .layer(TokenPermissionLayer::new(SiJwtPermission::Apply)),
         )
         .route(
             "/reject",
-            post(reject::reject).layer(WorkspacePermissionLayer::new(
-                state.clone(),
-                permissions::Permission::Approve,
-            )),
+            post(reject::reject)
+                .layer(WorkspacePermissionLayer::new(
+                    state.clone(),
+                    permissions::Permission::Approve,
+                ))
+                .layer(TokenPermissionLayer::new(SiJwtPermission::Apply)),
         )
         .route(
             "/cancel_approval_request",
@@ -152,10 +165,52 @@ pub fn change_set_routes(state: AppState) -> Router<AppState> {
         .route("/reopen", post(reopen::reopen))
         .route(
             "/force_apply",
-            post(force_apply::force_apply).layer(WorkspacePermissionLayer::new(
-                state.clone(),
-                permissions::Permission::Approve,
-            )),
+            post(force_apply::force_apply)
+                .layer(WorkspacePermissionLayer::new(
+                    state.clone(),
+                    permissions::Permission::Approve,
+                ))
+                .layer(TokenPermissionLayer::new(SiJwtPermission::Apply)),
         )
         .route("/rename", post(rename::rename))
+        .route(
+            "/schedule_apply",
+            post(schedule_apply::schedule_apply)
+                .layer(TokenPermissionLayer::new(SiJwtPermission::Apply)),
+        )
+        .route("/scheduled_apply", get(schedule_apply::scheduled_apply))
+        .route(
+            "/cancel_scheduled_apply",
+            post(schedule_apply::cancel_scheduled_apply)
+                .layer(TokenPermissionLayer::new(SiJwtPermission::Apply)),
+        )
+        .route("/approval_status", get(approval_status::approval_status))
+        .route(
+            "/comment_threads",
+            get(comments::list).post(comments::create),
+        )
+        .route("/comment_threads/:thread_id/reply", post(comments::reply))
+        .route(
+            "/comment_threads/:thread_id/resolve",
+            post(comments::resolve),
+        )
+        .route(
+            "/comment_threads/:thread_id/unresolve",
+            post(comments::unresolve),
+        )
+        .route(
+            "/comments/:comment_id",
+            put(comments::edit).delete(comments::remove),
+        )
+        .route("/policies", get(policies::list))
+        .route("/policies/evaluate", post(policies::evaluate))
+        .route(
+            "/policies/override",
+            post(policies::override_failures)
+                .layer(WorkspacePermissionLayer::new(
+                    state.clone(),
+                    permissions::Permission::Approve,
+                ))
+                .layer(TokenPermissionLayer::new(SiJwtPermission::Apply)),
+        )
 }
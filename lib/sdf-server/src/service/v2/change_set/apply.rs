@@ -2,7 +2,7 @@ use axum::extract::{Host, OriginalUri, Path};
 use dal::{ChangeSet, ChangeSetId, WorkspacePk};
 use si_events::audit_log::AuditLogKind;
 
-use super::{post_to_webhook, Result};
+use super::Result;
 use crate::{
     extract::{HandlerContext, PosthogClient},
     service::v2::AccessBuilder,
@@ -15,24 +15,25 @@ pub async fn apply(
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
-    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
 ) -> Result<()> {
     let mut ctx = builder
         .build(request_ctx.build(change_set_id.into()))
         .await?;
     let change_set = ChangeSet::get_by_id(&ctx, change_set_id).await?;
-    ChangeSet::prepare_for_apply(&ctx).await?;
+    if let Err(err) = ChangeSet::prepare_for_apply(&ctx).await {
+        // Keep the policy results that blocked the apply so that they can be inspected.
+        if let dal::ChangeSetError::PolicyChecksFailed(_) = err {
+            ctx.commit().await?;
+        }
+        return Err(err.into());
+    }
 
     // We need to run a commit before apply so changes get saved
     ctx.commit().await?;
 
     ChangeSet::apply_to_base_change_set(&mut ctx).await?;
 
-    let change_set_view = ChangeSet::get_by_id(&ctx, ctx.visibility().change_set_id)
-        .await?
-        .into_frontend_type(&ctx)
-        .await?;
-
     track(
         &posthog_client,
         &ctx,
@@ -47,16 +48,6 @@ pub async fn apply(
     ctx.write_audit_log(AuditLogKind::ApplyChangeSet, change_set.name)
         .await?;
 
-    let actor = ctx.history_actor().email(&ctx).await?;
-    let change_set_url = format!("https://{}/w/{}/{}", host_name, workspace_pk, change_set_id);
-    let message = format!(
-        "{} applied change set {} to HEAD: {}",
-        actor,
-        change_set_view.name.clone(),
-        change_set_url
-    );
-    post_to_webhook(&ctx, workspace_pk, message.as_str()).await?;
-
     // WS Event fires from the dal
     ctx.commit().await?;
 
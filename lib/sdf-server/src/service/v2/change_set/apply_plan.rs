@@ -0,0 +1,47 @@
+use axum::{
+    extract::{Path, Query},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use dal::{action::plan::ActionPlan, ChangeSetId, WorkspacePk};
+use serde::Deserialize;
+
+use super::Result;
+use crate::{extract::HandlerContext, service::v2::AccessBuilder};
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ApplyPlanFormat {
+    #[default]
+    Json,
+    Dot,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyPlanRequest {
+    #[serde(default)]
+    format: ApplyPlanFormat,
+}
+
+/// Previews the actions that will be queued on HEAD, and their order, if the change set is applied.
+pub async fn apply_plan(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(request): Query<ApplyPlanRequest>,
+) -> Result<Response> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let plan = ActionPlan::for_apply(&ctx).await?;
+
+    Ok(match request.format {
+        ApplyPlanFormat::Json => Json(plan).into_response(),
+        ApplyPlanFormat::Dot => {
+            ([(header::CONTENT_TYPE, "text/vnd.graphviz")], plan.to_dot()).into_response()
+        }
+    })
+}
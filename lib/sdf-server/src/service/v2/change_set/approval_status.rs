@@ -0,0 +1,21 @@
+use axum::{extract::Path, Json};
+use dal::{
+    change_set::approval::{ChangeSetApprovalStatus, ChangeSetApprovals},
+    ChangeSetId, WorkspacePk,
+};
+
+use super::Result;
+use crate::{extract::HandlerContext, service::v2::AccessBuilder};
+
+/// Shows approvers which approval rules apply to the change set and whether they are satisfied.
+pub async fn approval_status(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<ChangeSetApprovalStatus>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    Ok(Json(ChangeSetApprovals::evaluate(&ctx).await?))
+}
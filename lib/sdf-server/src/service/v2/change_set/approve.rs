@@ -2,7 +2,7 @@ use axum::extract::{Host, OriginalUri, Path};
 use dal::{ChangeSet, ChangeSetId, WorkspacePk, WsEvent};
 use si_events::audit_log::AuditLogKind;
 
-use super::{post_to_webhook, Error, Result};
+use super::{Error, Result};
 use crate::{
     extract::{HandlerContext, PosthogClient},
     service::v2::AccessBuilder,
@@ -15,7 +15,7 @@ pub async fn approve(
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
     Host(host_name): Host,
-    Path((workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
 ) -> Result<()> {
     let ctx = builder
         .build(request_ctx.build(change_set_id.into()))
@@ -63,16 +63,6 @@ pub async fn approve(
     )
     .await?;
 
-    let actor = ctx.history_actor().email(&ctx).await?;
-    let change_set_url = format!("https://{}/w/{}/{}", host_name, workspace_pk, change_set_id);
-    let message = format!(
-        "{} approved merge of change set {}: {}",
-        actor,
-        change_set_view.name.clone(),
-        change_set_url
-    );
-    post_to_webhook(&ctx, workspace_pk, message.as_str()).await?;
-
     WsEvent::change_set_status_changed(&ctx, old_status, change_set_view)
         .await?
         .publish_on_commit(&ctx)
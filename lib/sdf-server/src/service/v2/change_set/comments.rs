@@ -0,0 +1,211 @@
+use axum::{
+    extract::{Host, OriginalUri, Path, Query},
+    Json,
+};
+use dal::{
+    change_set::comment::{
+        ChangeSetComment, CommentId, CommentTarget, CommentThread, CommentThreadId,
+    },
+    ChangeSetId, ComponentId, UserPk, WorkspacePk,
+};
+use serde::Deserialize;
+
+use super::Result;
+use crate::{
+    extract::{HandlerContext, PosthogClient},
+    service::v2::AccessBuilder,
+    track,
+};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCommentThreadsQuery {
+    component_id: Option<ComponentId>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCommentThreadRequest {
+    #[serde(flatten)]
+    target: CommentTarget,
+    body: String,
+    #[serde(default)]
+    mentioned_user_pks: Vec<UserPk>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentRequest {
+    body: String,
+    #[serde(default)]
+    mentioned_user_pks: Vec<UserPk>,
+}
+
+pub async fn list(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(query): Query<ListCommentThreadsQuery>,
+) -> Result<Json<Vec<CommentThread>>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let threads = match query.component_id {
+        Some(component_id) => CommentThread::list_for_component(&ctx, component_id).await?,
+        None => CommentThread::list(&ctx).await?,
+    };
+
+    Ok(Json(threads))
+}
+
+pub async fn create(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Json(request): Json<CreateCommentThreadRequest>,
+) -> Result<Json<CommentThread>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let thread = CommentThread::new(
+        &ctx,
+        request.target,
+        request.body,
+        request.mentioned_user_pks,
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "create_comment_thread",
+        serde_json::json!({
+            "change_set_id": change_set_id,
+            "thread_id": thread.id,
+            "component_id": thread.target.component_id,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(thread))
+}
+
+pub async fn reply(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id, thread_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        CommentThreadId,
+    )>,
+    Json(request): Json<CommentRequest>,
+) -> Result<Json<CommentThread>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let thread =
+        CommentThread::reply(&ctx, thread_id, request.body, request.mentioned_user_pks).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "reply_to_comment_thread",
+        serde_json::json!({
+            "change_set_id": change_set_id,
+            "thread_id": thread_id,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(thread))
+}
+
+pub async fn resolve(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id, thread_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        CommentThreadId,
+    )>,
+) -> Result<Json<CommentThread>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let thread = CommentThread::resolve(&ctx, thread_id).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(thread))
+}
+
+pub async fn unresolve(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id, thread_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        CommentThreadId,
+    )>,
+) -> Result<Json<CommentThread>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let thread = CommentThread::unresolve(&ctx, thread_id).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(thread))
+}
+
+pub async fn edit(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id, comment_id)): Path<(WorkspacePk, ChangeSetId, CommentId)>,
+    Json(request): Json<CommentRequest>,
+) -> Result<Json<CommentThread>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let thread =
+        ChangeSetComment::edit(&ctx, comment_id, request.body, request.mentioned_user_pks).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(thread))
+}
+
+/// Returns the remaining thread, or `null` if the comment was the last one in it.
+pub async fn remove(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id, comment_id)): Path<(WorkspacePk, ChangeSetId, CommentId)>,
+) -> Result<Json<Option<CommentThread>>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let thread = ChangeSetComment::remove(&ctx, comment_id).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(thread))
+}
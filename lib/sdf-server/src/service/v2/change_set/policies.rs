@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{
+    change_set::policy::{ChangeSetPolicy, PolicyResult},
+    ChangeSetId, WorkspacePk,
+};
+
+use super::Result;
+use crate::{
+    extract::{HandlerContext, PosthogClient},
+    service::v2::AccessBuilder,
+    track,
+};
+
+pub async fn list(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<Vec<PolicyResult>>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    Ok(Json(ChangeSetPolicy::list_results(&ctx).await?))
+}
+
+pub async fn evaluate(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<Vec<PolicyResult>>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let results = ChangeSetPolicy::evaluate(&ctx).await?;
+
+    ctx.commit().await?;
+
+    Ok(Json(results))
+}
+
+pub async fn override_failures(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<Vec<PolicyResult>>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let overridden = ChangeSetPolicy::override_failures(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "override_change_set_policies",
+        serde_json::json!({
+            "change_set_id": change_set_id,
+            "policies": overridden.iter().map(|result| &result.func_name).collect::<Vec<_>>(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(overridden))
+}
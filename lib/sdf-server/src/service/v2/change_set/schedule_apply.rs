@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use dal::{change_set::schedule::ScheduledApply, ChangeSetId, WorkspacePk};
+use serde::Deserialize;
+use si_jwt_public_key::SiJwtClaimRole;
+
+use super::Result;
+use crate::{
+    extract::{request::ValidatedToken, HandlerContext, PosthogClient},
+    service::v2::AccessBuilder,
+    track,
+};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleApplyRequest {
+    window_start: DateTime<Utc>,
+    #[serde(default)]
+    window_end: Option<DateTime<Utc>>,
+}
+
+/// Schedules the change set to be applied once the window opens.
+pub async fn schedule_apply(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    ValidatedToken(token): ValidatedToken,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Json(request): Json<ScheduleApplyRequest>,
+) -> Result<Json<ScheduledApply>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    // Web tokens are short-lived and can't be revoked, so only automation tokens are checked
+    // again when the window opens.
+    let token_id = match token.custom.role() {
+        SiJwtClaimRole::Automation => token.jwt_id,
+        SiJwtClaimRole::Web => None,
+    };
+    let scheduled =
+        ScheduledApply::schedule(&ctx, request.window_start, request.window_end, token_id).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "schedule_change_set_apply",
+        serde_json::json!({
+            "change_set_id": change_set_id,
+            "window_start": scheduled.window_start,
+            "window_end": scheduled.window_end,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(scheduled))
+}
+
+pub async fn scheduled_apply(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<Option<ScheduledApply>>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    Ok(Json(ScheduledApply::get(&ctx).await?))
+}
+
+pub async fn cancel_scheduled_apply(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+) -> Result<Json<ScheduledApply>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    let cancelled = ScheduledApply::cancel(&ctx).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "cancel_scheduled_change_set_apply",
+        serde_json::json!({
+            "change_set_id": change_set_id,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(cancelled))
+}
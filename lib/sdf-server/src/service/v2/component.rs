@@ -0,0 +1,51 @@
+use axum::{
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use dal::component::query::ComponentQueryError;
+use hyper::StatusCode;
+use thiserror::Error;
+
+use crate::{service::ApiError, AppState};
+
+mod protection;
+mod query_components;
+
+#[remain::sorted]
+#[derive(Debug, Error)]
+pub enum ComponentError {
+    #[error("change set error: {0}")]
+    ChangeSet(#[from] dal::ChangeSetError),
+    #[error("component error: {0}")]
+    Component(#[from] dal::ComponentError),
+    #[error("component query error: {0}")]
+    ComponentQuery(#[from] ComponentQueryError),
+    #[error("dal transactions error: {0}")]
+    DalTransactions(#[from] dal::TransactionsError),
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] dal::WsEventError),
+}
+
+pub type ComponentResult<T> = Result<T, ComponentError>;
+
+impl IntoResponse for ComponentError {
+    fn into_response(self) -> Response {
+        let status_code = match self {
+            ComponentError::ComponentQuery(ComponentQueryError::Parse { .. }) => {
+                StatusCode::BAD_REQUEST
+            }
+            ComponentError::Component(dal::ComponentError::NotFound(_)) => StatusCode::NOT_FOUND,
+            _ => ApiError::DEFAULT_ERROR_STATUS_CODE,
+        };
+
+        ApiError::new(status_code, self.to_string()).into_response()
+    }
+}
+
+pub fn v2_routes() -> Router<AppState> {
+    Router::new()
+        .route("/query", post(query_components::query_components))
+        .route("/:component_id/protect", post(protection::protect))
+        .route("/:component_id/unprotect", post(protection::unprotect))
+}
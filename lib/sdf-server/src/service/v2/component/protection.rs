@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use axum::extract::{Host, OriginalUri, Path};
+use dal::{ChangeSet, ChangeSetId, Component, ComponentId, WorkspacePk, WsEvent};
+
+use super::ComponentResult;
+use crate::{
+    extract::{HandlerContext, PosthogClient},
+    service::{force_change_set_response::ForceChangeSetResponse, v2::AccessBuilder},
+    track,
+};
+
+/// Protects a component from being deleted, erased or destroyed.
+pub async fn protect(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id, component_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        ComponentId,
+    )>,
+) -> ComponentResult<ForceChangeSetResponse<()>> {
+    let mut ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    set_protected(&ctx, component_id, true).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "protect_component",
+        serde_json::json!({
+            "component_id": component_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::empty(force_change_set_id))
+}
+
+/// Lifts a component's protection so that it can be deleted again.
+pub async fn unprotect(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id, component_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        ComponentId,
+    )>,
+) -> ComponentResult<ForceChangeSetResponse<()>> {
+    let mut ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    set_protected(&ctx, component_id, false).await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "unprotect_component",
+        serde_json::json!({
+            "component_id": component_id,
+            "change_set_id": ctx.change_set_id(),
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::empty(force_change_set_id))
+}
+
+async fn set_protected(
+    ctx: &dal::DalContext,
+    component_id: ComponentId,
+    protected: bool,
+) -> ComponentResult<()> {
+    // Audit logs are written by the component itself.
+    let component = Component::get_by_id(ctx, component_id)
+        .await?
+        .set_protected(ctx, protected)
+        .await?;
+
+    let payload = component
+        .into_frontend_type(
+            ctx,
+            None,
+            component.change_status(ctx).await?,
+            &mut HashMap::new(),
+        )
+        .await?;
+    WsEvent::component_updated(ctx, payload)
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+
+    Ok(())
+}
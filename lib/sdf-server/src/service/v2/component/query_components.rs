@@ -0,0 +1,26 @@
+use axum::{extract::Path, Json};
+use dal::component::query::ComponentQuery;
+use si_frontend_types::{ComponentQueryRequest, ComponentQueryResponse};
+
+use super::ComponentResult;
+use crate::{extract::HandlerContext, service::v2::AccessBuilder};
+
+pub async fn query_components(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(dal::WorkspacePk, dal::ChangeSetId)>,
+    Json(request): Json<ComponentQueryRequest>,
+) -> ComponentResult<Json<ComponentQueryResponse>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let components = ComponentQuery::parse(&request.query, &request.select)?
+        .run(&ctx)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(ComponentQueryResponse { components }))
+}
@@ -7,12 +7,15 @@ use axum::{
     Json, Router,
 };
 use dal::{
-    cached_module::CachedModule, workspace::WorkspaceId, ChangeSet, ChangeSetId, DalContext,
-    SchemaId, SchemaVariant, WsEvent, WsEventError,
+    cached_module::CachedModule, component::query::ComponentQuery, workspace::WorkspaceId,
+    ChangeSet, ChangeSetId, DalContext, SchemaId, SchemaVariant, WsEvent, WsEventError,
 };
 use hyper::StatusCode;
 use si_events::audit_log::AuditLogKind;
-use si_frontend_types::fs::{ChangeSet as FsChangeSet, ListVariantsResponse, Schema as FsSchema};
+use si_frontend_types::{
+    fs::{ChangeSet as FsChangeSet, ListVariantsResponse, Schema as FsSchema},
+    ComponentQueryRequest, ComponentQueryResponse,
+};
 use thiserror::Error;
 
 use crate::{
@@ -31,6 +34,8 @@ pub enum FsError {
     ChangeSet(#[from] dal::ChangeSetError),
     #[error("ChangeSet {0}:{1} is inactive")]
     ChangeSetInactive(String, ChangeSetId),
+    #[error("component query error: {0}")]
+    ComponentQuery(#[from] dal::component::query::ComponentQueryError),
     #[error("schema error: {0}")]
     Schema(#[from] dal::SchemaError),
     #[error("schema variant error: {0}")]
@@ -47,7 +52,13 @@ pub type FsResult<T> = Result<T, FsError>;
 
 impl IntoResponse for FsError {
     fn into_response(self) -> Response {
-        let (status_code, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let status_code = match self {
+            FsError::ComponentQuery(dal::component::query::ComponentQueryError::Parse {
+                ..
+            }) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let error_message = self.to_string();
 
         ApiError::new(status_code, error_message).into_response()
     }
@@ -208,6 +219,28 @@ pub async fn list_variants(
     }))
 }
 
+pub async fn query_components(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Path((_workspace_id, change_set_id)): Path<(WorkspaceId, ChangeSetId)>,
+    Json(request): Json<ComponentQueryRequest>,
+) -> FsResult<Json<ComponentQueryResponse>> {
+    let ctx = builder
+        .build(request_ctx.build(change_set_id.into()))
+        .await?;
+
+    check_change_set(&ctx)?;
+
+    let components = ComponentQuery::parse(&request.query, &request.select)?
+        .run(&ctx)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(ComponentQueryResponse { components }))
+}
+
 pub fn fs_routes() -> Router<AppState> {
     Router::new()
         .route("/change-sets", get(list_change_sets))
@@ -215,6 +248,7 @@ pub fn fs_routes() -> Router<AppState> {
         .nest(
             "/change-sets/:change_set_id",
             Router::new()
+                .route("/query", post(query_components))
                 .route("/schemas", get(list_schemas))
                 .route("/schemas/:schema_id/variants", get(list_variants)),
         )
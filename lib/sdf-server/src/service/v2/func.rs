@@ -9,7 +9,7 @@ use dal::{
     attribute::{prototype::argument::AttributePrototypeArgumentError, value::AttributeValueError},
     func::{
         argument::FuncArgumentError, authoring::FuncAuthoringError, binding::FuncBindingError,
-        runner::FuncRunnerError,
+        run_log::FuncRunLogError, runner::FuncRunnerError,
     },
     workspace_snapshot::graph::WorkspaceSnapshotGraphError,
     ChangeSetError, ComponentError, DalContext, Func, FuncError, FuncId, SchemaVariantError,
@@ -35,6 +35,8 @@ pub mod get_func_run;
 pub mod list_all_funcs;
 pub mod list_funcs;
 pub mod save_code;
+pub mod search_func_run_logs;
+pub mod tail_func_run_log;
 pub mod test_execute;
 pub mod update_func;
 
@@ -67,6 +69,8 @@ pub enum FuncAPIError {
     FuncNameReserved(String),
     #[error("The function does not exist")]
     FuncNotFound(FuncId),
+    #[error("func run log error: {0}")]
+    FuncRunLog(#[from] FuncRunLogError),
     #[error("hyper error: {0}")]
     Http(#[from] axum::http::Error),
     #[error("layer db error: {0}")]
@@ -147,12 +151,14 @@ impl IntoResponse for FuncAPIError {
             | Self::MissingPrototypeId
             | Self::MissingSchemaVariantAndFunc
             | Self::Func(FuncError::FuncLocked(_))
+            | Self::FuncRunLog(FuncRunLogError::InvalidPattern(_))
             | Self::SchemaVariant(dal::SchemaVariantError::SchemaVariantLocked(_)) => {
                 (StatusCode::BAD_REQUEST, None)
             }
 
             // Return 404 when the func is not found
             Self::FuncNotFound(_) |
+            Self::FuncRunLog(FuncRunLogError::FuncRunNotFound(_)) |
             // When a graph node cannot be found for a schema variant, it is not found
             Self::SchemaVariant(dal::SchemaVariantError::NotFound(_)) => (StatusCode::NOT_FOUND, None),
 
@@ -194,6 +200,14 @@ pub fn v2_routes() -> Router<AppState> {
         .route("/including_pruned", get(list_all_funcs::list_all_funcs))
         .route("/code", get(get_code::get_code)) // accepts a list of func_ids
         .route("/runs/:func_run_id", get(get_func_run::get_func_run)) // accepts a list of func_ids
+        .route(
+            "/runs/:func_run_id/logs/tail",
+            get(tail_func_run_log::tail_func_run_log),
+        )
+        .route(
+            "/runs/logs/search",
+            get(search_func_run_logs::search_func_run_logs),
+        )
         .route("/", post(create_func::create_func))
         .route("/:func_id", put(update_func::update_func)) // only save the func's metadata
         .route("/:func_id/code", put(save_code::save_code)) // only saves func code
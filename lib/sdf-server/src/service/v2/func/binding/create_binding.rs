@@ -383,7 +383,9 @@ pub async fn create_binding(
                 }
             }
         }
-        dal::func::FuncKind::Unknown | dal::func::FuncKind::SchemaVariantDefinition => {
+        dal::func::FuncKind::Policy
+        | dal::func::FuncKind::Unknown
+        | dal::func::FuncKind::SchemaVariantDefinition => {
             return Err(FuncAPIError::WrongFunctionKindForBinding);
         }
     };
@@ -114,6 +114,7 @@ pub async fn delete_binding(
             }
             FuncKind::Attribute
             | FuncKind::Intrinsic
+            | FuncKind::Policy
             | FuncKind::SchemaVariantDefinition
             | FuncKind::Unknown => return Err(FuncAPIError::CannotDeleteBindingForFunc),
         };
@@ -139,6 +139,7 @@ async fn update_mangement_func_bindings(
         let frontend_types::FuncBinding::Management {
             managed_schemas,
             management_prototype_id,
+            tag_selectors,
             ..
         } = binding
         else {
@@ -154,6 +155,7 @@ async fn update_mangement_func_bindings(
             ctx,
             management_prototype_id,
             managed_schemas.map(|schemas| schemas.into_iter().map(Into::into).collect()),
+            tag_selectors,
         )
         .await?;
     }
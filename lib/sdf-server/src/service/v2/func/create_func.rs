@@ -378,6 +378,19 @@ pub async fn create_func(
                 return Err(FuncAPIError::WrongFunctionKindForBinding);
             }
         }
+        // Policies apply to the whole workspace, so any binding in the request is ignored.
+        FuncKind::Policy => {
+            let func = FuncAuthoringClient::create_new_policy_func(&ctx, request.name).await?;
+            ctx.write_audit_log(
+                AuditLogKind::CreateFunc {
+                    func_display_name: func.display_name.clone(),
+                    func_kind: func.kind.into(),
+                },
+                func.name.clone(),
+            )
+            .await?;
+            func
+        }
         FuncKind::Unknown | FuncKind::SchemaVariantDefinition | FuncKind::Intrinsic => {
             return Err(FuncAPIError::WrongFunctionKindForBinding)
         }
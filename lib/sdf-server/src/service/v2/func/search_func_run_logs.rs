@@ -0,0 +1,27 @@
+use axum::{
+    extract::{Path, Query},
+    Json,
+};
+use dal::{
+    func::run_log::{FuncRunLogSearch, FuncRunLogSearchResults},
+    ChangeSetId, WorkspacePk,
+};
+use telemetry::prelude::*;
+
+use super::FuncAPIResult;
+use crate::extract::HandlerContext;
+use crate::service::v2::AccessBuilder;
+
+#[instrument(name = "sdf.v2.func.search_func_run_logs", level = "info", skip_all)]
+pub async fn search_func_run_logs(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(search): Query<FuncRunLogSearch>,
+) -> FuncAPIResult<Json<FuncRunLogSearchResults>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    Ok(Json(search.run(&ctx).await?))
+}
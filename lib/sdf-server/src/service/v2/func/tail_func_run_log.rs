@@ -0,0 +1,32 @@
+use axum::{
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use dal::{func::run_log, ChangeSetId, WorkspacePk};
+use futures::{Stream, StreamExt};
+use si_events::FuncRunId;
+use tokio_util::sync::CancellationToken;
+
+use super::FuncAPIResult;
+use crate::extract::HandlerContext;
+use crate::service::v2::AccessBuilder;
+
+/// Streams the lines of a func run's log as server-sent events, starting with the lines logged so
+/// far. The stream ends once the func run's log is complete.
+pub async fn tail_func_run_log(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path((_workspace_pk, change_set_id, func_run_id)): Path<(WorkspacePk, ChangeSetId, FuncRunId)>,
+    State(shutdown_token): State<CancellationToken>,
+) -> FuncAPIResult<Sse<impl Stream<Item = Result<Event, axum::Error>>>> {
+    let ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+
+    let lines = run_log::tail(&ctx, func_run_id)
+        .await?
+        .map(|line| Event::default().event("line").json_data(line))
+        .take_until(shutdown_token.cancelled_owned());
+
+    Ok(Sse::new(lines).keep_alive(KeepAlive::default()))
+}
@@ -4,20 +4,34 @@ use axum::{
     Router,
 };
 use hyper::StatusCode;
+use si_events::WebhookEndpointId;
 use thiserror::Error;
 
 use crate::{service::ApiError, AppState};
 
+pub mod create_webhook_endpoint;
+pub mod delete_webhook_endpoint;
 pub mod get_integrations;
+pub mod list_webhook_deliveries;
+pub mod list_webhook_endpoints;
 pub mod update_integration;
+pub mod update_webhook_endpoint;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
 pub enum IntegrationsError {
+    #[error("audit database error: {0}")]
+    AuditDatabase(#[from] audit_database::AuditDatabaseError),
     #[error("integration with id {0} not found")]
     IntegrationNotFound(dal::workspace_integrations::WorkspaceIntegrationId),
+    #[error("invalid webhook url: {0}")]
+    InvalidWebhookUrl(String),
+    #[error("webhook endpoints must subscribe to at least one event kind")]
+    NoWebhookEventKinds,
     #[error("transactions error: {0}")]
     Transactions(#[from] dal::TransactionsError),
+    #[error("webhook endpoint with id {0} not found")]
+    WebhookEndpointNotFound(WebhookEndpointId),
     #[error("workspace integration error: {0}")]
     WorkspaceIntegrations(#[from] dal::workspace_integrations::WorkspaceIntegrationsError),
 }
@@ -26,7 +40,14 @@ pub type IntegrationsResult<T> = Result<T, IntegrationsError>;
 
 impl IntoResponse for IntegrationsError {
     fn into_response(self) -> Response {
-        let (status_code, error_message) = (StatusCode::INTERNAL_SERVER_ERROR, self.to_string());
+        let status_code = match self {
+            Self::InvalidWebhookUrl(_) | Self::NoWebhookEventKinds => StatusCode::BAD_REQUEST,
+            Self::IntegrationNotFound(_) | Self::WebhookEndpointNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let error_message = self.to_string();
 
         ApiError::new(status_code, error_message).into_response()
     }
@@ -39,4 +60,57 @@ pub fn v2_routes() -> Router<AppState> {
             post(update_integration::update_integration),
         )
         .route("/", get(get_integrations::get_integration))
+        .route(
+            "/webhooks",
+            get(list_webhook_endpoints::list_webhook_endpoints)
+                .post(create_webhook_endpoint::create_webhook_endpoint),
+        )
+        .route(
+            "/webhooks/:webhook_endpoint_id",
+            post(update_webhook_endpoint::update_webhook_endpoint)
+                .delete(delete_webhook_endpoint::delete_webhook_endpoint),
+        )
+        .route(
+            "/webhooks/:webhook_endpoint_id/deliveries",
+            get(list_webhook_deliveries::list_webhook_deliveries),
+        )
+}
+
+async fn validate_webhook_endpoint(
+    url: &str,
+    event_kinds: &[si_events::WebhookEventKind],
+) -> IntegrationsResult<()> {
+    validate_webhook_url(url).await?;
+    if event_kinds.is_empty() {
+        return Err(IntegrationsError::NoWebhookEventKinds);
+    }
+    Ok(())
+}
+
+/// Ensures that a webhook URL is an http(s) URL whose host only resolves to addresses we're
+/// willing to deliver to. Forklift checks the addresses again when it delivers, since what a host
+/// resolves to can change after the endpoint is saved.
+async fn validate_webhook_url(url: &str) -> IntegrationsResult<()> {
+    let invalid = || IntegrationsError::InvalidWebhookUrl(url.to_owned());
+
+    let parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid());
+    }
+    let host = parsed.host_str().ok_or_else(invalid)?;
+    let port = parsed.port_or_known_default().ok_or_else(invalid)?;
+
+    let addrs: Vec<_> = tokio::net::lookup_host((host.trim_matches(['[', ']']), port))
+        .await
+        .map_err(|_| invalid())?
+        .collect();
+    if addrs.is_empty()
+        || !addrs
+            .iter()
+            .all(|addr| audit_database::is_deliverable_address(addr.ip()))
+    {
+        return Err(invalid());
+    }
+
+    Ok(())
 }
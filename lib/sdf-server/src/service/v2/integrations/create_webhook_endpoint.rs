@@ -0,0 +1,95 @@
+use audit_database::{EncryptedSigningSecret, WebhookEndpointRow};
+use axum::extract::{Host, OriginalUri, Path, State};
+use axum::Json;
+use dal::WorkspacePk;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use si_events::{WebhookEventKind, WebhookPayloadFormat};
+
+use super::{validate_webhook_endpoint, IntegrationsResult};
+use crate::extract::{HandlerContext, PosthogClient};
+use crate::service::v2::AccessBuilder;
+use crate::{track, AppState};
+
+const SIGNING_SECRET_PREFIX: &str = "whsec_";
+const SIGNING_SECRET_LEN: usize = 48;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookEndpointRequest {
+    url: String,
+    #[serde(default)]
+    payload_format: WebhookPayloadFormat,
+    event_kinds: Vec<WebhookEventKind>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookEndpointResponse {
+    pub endpoint: WebhookEndpointRow,
+    /// The secret used to sign deliveries. This is only ever returned when the endpoint is created.
+    pub signing_secret: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn create_webhook_endpoint(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    State(state): State<AppState>,
+    Path(workspace_pk): Path<WorkspacePk>,
+    Json(request): Json<CreateWebhookEndpointRequest>,
+) -> IntegrationsResult<Json<CreateWebhookEndpointResponse>> {
+    validate_webhook_endpoint(&request.url, &request.event_kinds).await?;
+
+    // Slack incoming webhooks authenticate with the URL itself, so only JSON payloads are signed.
+    let signing_secret = match request.payload_format {
+        WebhookPayloadFormat::Json => Some(generate_signing_secret()),
+        WebhookPayloadFormat::Slack => None,
+    };
+
+    let ctx = builder.build_head(access_builder).await?;
+
+    let endpoint = WebhookEndpointRow::insert(
+        state.audit_database_context(),
+        workspace_pk,
+        request.url,
+        request.payload_format,
+        request.event_kinds,
+        signing_secret.as_deref().map(|signing_secret| {
+            EncryptedSigningSecret::encrypt(ctx.symmetric_crypto_service(), signing_secret)
+        }),
+        request.description,
+    )
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "create_webhook_endpoint",
+        serde_json::json!({
+            "webhook_endpoint_id": endpoint.id,
+            "payload_format": endpoint.payload_format,
+            "event_kinds": endpoint.event_kinds,
+        }),
+    );
+
+    Ok(Json(CreateWebhookEndpointResponse {
+        endpoint,
+        signing_secret,
+    }))
+}
+
+fn generate_signing_secret() -> String {
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SIGNING_SECRET_LEN)
+        .map(char::from)
+        .collect();
+    format!("{SIGNING_SECRET_PREFIX}{secret}")
+}
@@ -0,0 +1,28 @@
+use audit_database::WebhookEndpointRow;
+use axum::extract::{Path, State};
+use dal::WorkspacePk;
+use si_events::WebhookEndpointId;
+
+use super::{IntegrationsError, IntegrationsResult};
+use crate::service::v2::AccessBuilder;
+use crate::AppState;
+
+pub async fn delete_webhook_endpoint(
+    AccessBuilder(_access_builder): AccessBuilder,
+    State(state): State<AppState>,
+    Path((workspace_pk, webhook_endpoint_id)): Path<(WorkspacePk, WebhookEndpointId)>,
+) -> IntegrationsResult<()> {
+    if !WebhookEndpointRow::delete(
+        state.audit_database_context(),
+        workspace_pk,
+        webhook_endpoint_id,
+    )
+    .await?
+    {
+        return Err(IntegrationsError::WebhookEndpointNotFound(
+            webhook_endpoint_id,
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use audit_database::{WebhookDeliveryRow, WebhookEndpointRow};
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use dal::WorkspacePk;
+use serde::{Deserialize, Serialize};
+use si_events::WebhookEndpointId;
+
+use super::{IntegrationsError, IntegrationsResult};
+use crate::service::v2::AccessBuilder;
+use crate::AppState;
+
+const DEFAULT_SIZE: usize = 50;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWebhookDeliveriesRequest {
+    size: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWebhookDeliveriesResponse {
+    pub deliveries: Vec<WebhookDeliveryRow>,
+}
+
+pub async fn list_webhook_deliveries(
+    AccessBuilder(_access_builder): AccessBuilder,
+    State(state): State<AppState>,
+    Path((workspace_pk, webhook_endpoint_id)): Path<(WorkspacePk, WebhookEndpointId)>,
+    Query(request): Query<ListWebhookDeliveriesRequest>,
+) -> IntegrationsResult<Json<ListWebhookDeliveriesResponse>> {
+    let context = state.audit_database_context();
+
+    if WebhookEndpointRow::get(context, workspace_pk, webhook_endpoint_id)
+        .await?
+        .is_none()
+    {
+        return Err(IntegrationsError::WebhookEndpointNotFound(
+            webhook_endpoint_id,
+        ));
+    }
+
+    let deliveries = WebhookDeliveryRow::list_for_endpoint(
+        context,
+        workspace_pk,
+        webhook_endpoint_id,
+        request.size.unwrap_or(DEFAULT_SIZE),
+    )
+    .await?;
+
+    Ok(Json(ListWebhookDeliveriesResponse { deliveries }))
+}
@@ -0,0 +1,25 @@
+use audit_database::WebhookEndpointRow;
+use axum::extract::{Path, State};
+use axum::Json;
+use dal::WorkspacePk;
+use serde::{Deserialize, Serialize};
+
+use super::IntegrationsResult;
+use crate::service::v2::AccessBuilder;
+use crate::AppState;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ListWebhookEndpointsResponse {
+    pub endpoints: Vec<WebhookEndpointRow>,
+}
+
+pub async fn list_webhook_endpoints(
+    AccessBuilder(_access_builder): AccessBuilder,
+    State(state): State<AppState>,
+    Path(workspace_pk): Path<WorkspacePk>,
+) -> IntegrationsResult<Json<ListWebhookEndpointsResponse>> {
+    let endpoints = WebhookEndpointRow::list(state.audit_database_context(), workspace_pk).await?;
+
+    Ok(Json(ListWebhookEndpointsResponse { endpoints }))
+}
@@ -1,13 +1,15 @@
 use crate::extract::{HandlerContext, PosthogClient};
 use crate::service::v2::AccessBuilder;
+use crate::AppState;
 
-use axum::extract::{Host, OriginalUri, Path};
+use audit_database::WebhookEndpointRow;
+use axum::extract::{Host, OriginalUri, Path, State};
 use axum::Json;
 use dal::workspace_integrations::{WorkspaceIntegration, WorkspaceIntegrationId};
 use dal::WorkspacePk;
 use serde::{Deserialize, Serialize};
 
-use super::{IntegrationsError, IntegrationsResult};
+use super::{validate_webhook_url, IntegrationsError, IntegrationsResult};
 
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -27,7 +29,8 @@ pub async fn update_integration(
     PosthogClient(_posthog_client): PosthogClient,
     OriginalUri(_original_uri): OriginalUri,
     Host(_host_name): Host,
-    Path((_workspace_pk, workspace_integration_id)): Path<(WorkspacePk, WorkspaceIntegrationId)>,
+    State(state): State<AppState>,
+    Path((workspace_pk, workspace_integration_id)): Path<(WorkspacePk, WorkspaceIntegrationId)>,
     Json(request): Json<UpdateIntegrationRequest>,
 ) -> IntegrationsResult<Json<UpdateIntegrationResponse>> {
     let ctx = builder.build_head(access_builder).await?;
@@ -39,6 +42,22 @@ pub async fn update_integration(
         ))?;
 
     if let Some(webhook_url) = request.slack_webhook_url {
+        // Notifications for the Slack webhook URL are delivered by forklift through an endpoint of its own
+        if webhook_url.is_empty() {
+            WebhookEndpointRow::delete_for_workspace_integration(
+                state.audit_database_context(),
+                workspace_pk,
+            )
+            .await?;
+        } else {
+            validate_webhook_url(&webhook_url).await?;
+            WebhookEndpointRow::upsert_for_workspace_integration(
+                state.audit_database_context(),
+                workspace_pk,
+                webhook_url.clone(),
+            )
+            .await?;
+        }
         integration.update_webhook_url(&ctx, webhook_url).await?;
     }
     ctx.commit().await?;
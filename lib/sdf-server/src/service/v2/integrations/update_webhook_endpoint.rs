@@ -0,0 +1,52 @@
+use audit_database::WebhookEndpointRow;
+use axum::extract::{Path, State};
+use axum::Json;
+use dal::WorkspacePk;
+use serde::{Deserialize, Serialize};
+use si_events::{WebhookEndpointId, WebhookEventKind, WebhookPayloadFormat};
+
+use super::{validate_webhook_endpoint, IntegrationsError, IntegrationsResult};
+use crate::service::v2::AccessBuilder;
+use crate::AppState;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookEndpointRequest {
+    url: String,
+    payload_format: WebhookPayloadFormat,
+    event_kinds: Vec<WebhookEventKind>,
+    enabled: bool,
+    description: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookEndpointResponse {
+    pub endpoint: WebhookEndpointRow,
+}
+
+pub async fn update_webhook_endpoint(
+    AccessBuilder(_access_builder): AccessBuilder,
+    State(state): State<AppState>,
+    Path((workspace_pk, webhook_endpoint_id)): Path<(WorkspacePk, WebhookEndpointId)>,
+    Json(request): Json<UpdateWebhookEndpointRequest>,
+) -> IntegrationsResult<Json<UpdateWebhookEndpointResponse>> {
+    validate_webhook_endpoint(&request.url, &request.event_kinds).await?;
+
+    let endpoint = WebhookEndpointRow::update(
+        state.audit_database_context(),
+        workspace_pk,
+        webhook_endpoint_id,
+        request.url,
+        request.payload_format,
+        request.event_kinds,
+        request.enabled,
+        request.description,
+    )
+    .await?
+    .ok_or(IntegrationsError::WebhookEndpointNotFound(
+        webhook_endpoint_id,
+    ))?;
+
+    Ok(Json(UpdateWebhookEndpointResponse { endpoint }))
+}
@@ -17,6 +17,7 @@ pub mod create_unlocked_copy;
 mod delete_unlocked_variant;
 mod get_variant;
 mod list_variants;
+mod set_protect_components;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
@@ -33,6 +34,8 @@ pub enum SchemaVariantsAPIError {
     Http(#[from] axum::http::Error),
     #[error("Module error: {0}")]
     Module(#[from] ModuleError),
+    #[error("schema error: {0}")]
+    Schema(#[from] dal::SchemaError),
     #[error("schema variant error: {0}")]
     SchemaVariant(#[from] dal::SchemaVariantError),
     #[error("serde json error: {0}")]
@@ -77,4 +80,8 @@ pub fn v2_routes() -> Router<AppState> {
             "/:schema_variant_id",
             delete(delete_unlocked_variant::delete_unlocked_variant),
         )
+        .route(
+            "/:schema_variant_id/protect_components",
+            post(set_protect_components::set_protect_components),
+        )
 }
@@ -0,0 +1,66 @@
+use axum::{
+    extract::{Host, OriginalUri, Path},
+    Json,
+};
+use dal::{ChangeSet, ChangeSetId, SchemaVariant, SchemaVariantId, WorkspacePk};
+use serde::Deserialize;
+
+use super::SchemaVariantsAPIResult;
+use crate::{
+    extract::{HandlerContext, PosthogClient},
+    service::force_change_set_response::ForceChangeSetResponse,
+    service::v2::AccessBuilder,
+    track,
+};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetProtectComponentsRequest {
+    protect_components: bool,
+}
+
+/// Sets whether new components of the variant's schema start out protected from deletion.
+/// Existing components keep their own setting.
+pub async fn set_protect_components(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Host(host_name): Host,
+    Path((_workspace_pk, change_set_id, schema_variant_id)): Path<(
+        WorkspacePk,
+        ChangeSetId,
+        SchemaVariantId,
+    )>,
+    Json(request): Json<SetProtectComponentsRequest>,
+) -> SchemaVariantsAPIResult<ForceChangeSetResponse<()>> {
+    let mut ctx = builder
+        .build(access_builder.build(change_set_id.into()))
+        .await?;
+    let force_change_set_id = ChangeSet::force_new(&mut ctx).await?;
+
+    let schema = SchemaVariant::schema_for_schema_variant_id(&ctx, schema_variant_id).await?;
+    let schema_id = schema.id();
+    schema
+        .modify(&ctx, |schema| {
+            schema.protect_components = request.protect_components;
+            Ok(())
+        })
+        .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        &host_name,
+        "set_schema_protect_components",
+        serde_json::json!({
+            "schema_id": schema_id,
+            "protect_components": request.protect_components,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(ForceChangeSetResponse::empty(force_change_set_id))
+}
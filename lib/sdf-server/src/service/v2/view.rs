@@ -9,7 +9,10 @@ use axum::{
 };
 use dal::{
     cached_module::CachedModuleError,
-    component::{frame::FrameError, inferred_connection_graph::InferredConnectionGraphError},
+    component::{
+        frame::FrameError, inferred_connection_graph::InferredConnectionGraphError,
+        tag::ComponentTagError,
+    },
     pkg::PkgError,
     slow_rt::SlowRuntimeError,
     workspace_snapshot::graph::WorkspaceSnapshotGraphError,
@@ -42,6 +45,8 @@ pub enum ViewError {
     ChangeSet(#[from] ChangeSetError),
     #[error("component error: {0}")]
     Component(#[from] ComponentError),
+    #[error("component tag error: {0}")]
+    ComponentTag(#[from] ComponentTagError),
     #[error("dal diagram error: {0}")]
     DalDiagram(#[from] dal::diagram::DiagramError),
     #[error("frame error: {0}")]
@@ -88,6 +93,9 @@ impl IntoResponse for ViewError {
     fn into_response(self) -> Response {
         let (status_code, error_message) = match self {
             ViewError::NameAlreadyInUse(_) => (StatusCode::CONFLICT, self.to_string()),
+            ViewError::ComponentTag(
+                ComponentTagError::InvalidKey(_) | ComponentTagError::InvalidValue(_),
+            ) => (StatusCode::BAD_REQUEST, self.to_string()),
             ViewError::DalDiagram(
                 dal::diagram::DiagramError::DeletingLastGeometryForComponent(_, _),
             )
@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use crate::extract::HandlerContext;
 use crate::service::v2::view::{ViewError, ViewResult};
 use crate::service::v2::AccessBuilder;
-use axum::extract::{Json, Path};
+use axum::extract::{Json, Path, Query};
+use dal::component::tag::TagSelector;
 use dal::diagram::geometry::{Geometry, GeometryRepresents};
 use dal::diagram::view::{View, ViewId, ViewView};
 use dal::diagram::{Diagram, DiagramError};
@@ -73,6 +74,14 @@ pub async fn get_geometry(
     }))
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagramQuery {
+    /// Comma separated tag selectors, such as `env=prod,team`. Only components matching every
+    /// selector are returned.
+    tag: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Response {
@@ -84,6 +93,7 @@ pub async fn get_diagram(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     Path((_workspace_pk, change_set_id, view_id)): Path<(WorkspacePk, ChangeSetId, ViewId)>,
+    Query(query): Query<DiagramQuery>,
 ) -> ViewResult<Json<Response>> {
     let ctx = builder
         .build(access_builder.build(change_set_id.into()))
@@ -91,13 +101,14 @@ pub async fn get_diagram(
 
     let view = View::get_by_id(&ctx, view_id).await?;
 
-    get_diagram_inner(&ctx, view).await
+    get_diagram_inner(&ctx, view, query).await
 }
 
 pub async fn get_default_diagram(
     HandlerContext(builder): HandlerContext,
     AccessBuilder(access_builder): AccessBuilder,
     Path((_workspace_pk, change_set_id)): Path<(WorkspacePk, ChangeSetId)>,
+    Query(query): Query<DiagramQuery>,
 ) -> ViewResult<Json<Response>> {
     let ctx = builder
         .build(access_builder.build(change_set_id.into()))
@@ -106,17 +117,27 @@ pub async fn get_default_diagram(
     let view_id = View::get_id_for_default(&ctx).await?;
     let view = View::get_by_id(&ctx, view_id).await?;
 
-    get_diagram_inner(&ctx, view).await
+    get_diagram_inner(&ctx, view, query).await
 }
 
-async fn get_diagram_inner(ctx: &DalContext, view: View) -> ViewResult<Json<Response>> {
+async fn get_diagram_inner(
+    ctx: &DalContext,
+    view: View,
+    query: DiagramQuery,
+) -> ViewResult<Json<Response>> {
+    let selectors = match query.tag {
+        Some(tag) => TagSelector::parse_list(&tag)?,
+        None => vec![],
+    };
+
     let ctx_clone = ctx.clone();
     let view_id = view.id();
-    let diagram = slow_rt::spawn(async move {
+    let mut diagram = slow_rt::spawn(async move {
         let ctx = &ctx_clone;
         Ok::<Diagram, ViewError>(Diagram::assemble(ctx, Some(view_id)).await?)
     })?
     .await??;
+    diagram.retain_tagged(&selectors);
 
     Ok(Json(Response {
         view: ViewView::from_view(ctx, view).await?,
@@ -1,19 +1,31 @@
-use crate::{app_state::AppState, service::ApiError};
+use crate::{app_state::AppState, middleware::WorkspacePermissionLayer, service::ApiError};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
-    routing::post,
+    routing::{delete, get, post, put},
     Router,
 };
+use dal::change_set::approval::ChangeSetApprovalError;
 use dal::{TransactionsError, UserError, UserPk, WorkspaceError, WorkspacePk};
 use thiserror::Error;
 
+mod create_approval_rule;
+mod create_approver_group;
+mod delete_approval_rule;
+mod delete_approver_group;
 mod export_workspace;
 mod install_workspace;
+mod list_approval_rules;
+mod list_approver_groups;
+mod list_revoked_auth_tokens;
+mod revoke_auth_token;
+mod update_approver_group_members;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum WorkspaceAPIError {
+    #[error("change set approval error: {0}")]
+    ChangeSetApproval(#[from] ChangeSetApprovalError),
     #[error("Trying to export from/import into root tenancy")]
     ExportingImportingWithRootTenancy,
     #[error("invalid user: {0}")]
@@ -22,6 +34,8 @@ pub enum WorkspaceAPIError {
     ModuleIndex(#[from] module_index_client::ModuleIndexClientError),
     #[error("Module index not configured")]
     ModuleIndexNotConfigured,
+    #[error("revoked auth token error: {0}")]
+    RevokedAuthToken(#[from] dal::revoked_auth_token::RevokedAuthTokenError),
     #[error("transactions error: {0}")]
     Transactions(#[from] TransactionsError),
     #[error("Unable to parse URL: {0}")]
@@ -39,7 +53,14 @@ pub type WorkspaceAPIResult<T> = Result<T, WorkspaceAPIError>;
 impl IntoResponse for WorkspaceAPIError {
     fn into_response(self) -> Response {
         let (status_code, error_message) = match self {
-            WorkspaceAPIError::WorkspaceNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            WorkspaceAPIError::WorkspaceNotFound(_)
+            | WorkspaceAPIError::ChangeSetApproval(
+                ChangeSetApprovalError::ApproverGroupNotFound(_)
+                | ChangeSetApprovalError::RuleNotFound(_),
+            ) => (StatusCode::NOT_FOUND, self.to_string()),
+            WorkspaceAPIError::ChangeSetApproval(
+                ChangeSetApprovalError::InvalidRequiredApprovals(_),
+            ) => (StatusCode::BAD_REQUEST, self.to_string()),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
 
@@ -47,8 +68,50 @@ impl IntoResponse for WorkspaceAPIError {
     }
 }
 
-pub fn v2_routes() -> Router<AppState> {
+pub fn v2_routes(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/install", post(install_workspace::install_workspace))
         .route("/export", post(export_workspace::export_workspace))
+        .route(
+            "/auth-tokens/revoked",
+            get(list_revoked_auth_tokens::list_revoked_auth_tokens),
+        )
+        .route(
+            "/auth-tokens/:token_id/revoke",
+            post(revoke_auth_token::revoke_auth_token).layer(WorkspacePermissionLayer::new(
+                state.clone(),
+                permissions::Permission::Manage,
+            )),
+        )
+        .route(
+            "/approval-rules",
+            get(list_approval_rules::list_approval_rules).merge(
+                post(create_approval_rule::create_approval_rule).layer(
+                    WorkspacePermissionLayer::new(state.clone(), permissions::Permission::Manage),
+                ),
+            ),
+        )
+        .route(
+            "/approval-rules/:rule_id",
+            delete(delete_approval_rule::delete_approval_rule).layer(
+                WorkspacePermissionLayer::new(state.clone(), permissions::Permission::Manage),
+            ),
+        )
+        .route(
+            "/approver-groups",
+            get(list_approver_groups::list_approver_groups).merge(
+                post(create_approver_group::create_approver_group).layer(
+                    WorkspacePermissionLayer::new(state.clone(), permissions::Permission::Manage),
+                ),
+            ),
+        )
+        .route(
+            "/approver-groups/:group_id",
+            put(update_approver_group_members::update_approver_group_members)
+                .merge(delete(delete_approver_group::delete_approver_group))
+                .layer(WorkspacePermissionLayer::new(
+                    state,
+                    permissions::Permission::Manage,
+                )),
+        )
 }
@@ -0,0 +1,38 @@
+use axum::{extract::Path, Json};
+use dal::{
+    change_set::approval::{ApprovalRule, ApprovalRuleSpec},
+    WorkspacePk,
+};
+
+use crate::{
+    extract::{HandlerContext, PosthogEventTracker},
+    service::v2::AccessBuilder,
+};
+
+use super::WorkspaceAPIResult;
+
+pub async fn create_approval_rule(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    tracker: PosthogEventTracker,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Json(spec): Json<ApprovalRuleSpec>,
+) -> WorkspaceAPIResult<Json<ApprovalRule>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let rule = ApprovalRule::new(&ctx, spec).await?;
+
+    tracker.track(
+        &ctx,
+        "workspace_api_approval_rule_created",
+        serde_json::json!({
+            "how": "/workspace/create_approval_rule",
+            "rule_id": rule.id,
+            "required_approvals": rule.required_approvals,
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(rule))
+}
@@ -0,0 +1,38 @@
+use axum::{extract::Path, Json};
+use dal::{
+    change_set::approval::{ApproverGroup, ApproverGroupSpec},
+    WorkspacePk,
+};
+
+use crate::{
+    extract::{HandlerContext, PosthogEventTracker},
+    service::v2::AccessBuilder,
+};
+
+use super::WorkspaceAPIResult;
+
+pub async fn create_approver_group(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    tracker: PosthogEventTracker,
+    Path(_workspace_pk): Path<WorkspacePk>,
+    Json(spec): Json<ApproverGroupSpec>,
+) -> WorkspaceAPIResult<Json<ApproverGroup>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let group = ApproverGroup::new(&ctx, spec).await?;
+
+    tracker.track(
+        &ctx,
+        "workspace_api_approver_group_created",
+        serde_json::json!({
+            "how": "/workspace/create_approver_group",
+            "group_id": group.id,
+            "members": group.member_user_pks.len(),
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(group))
+}
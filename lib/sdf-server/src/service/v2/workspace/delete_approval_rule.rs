@@ -0,0 +1,36 @@
+use axum::extract::Path;
+use dal::{
+    change_set::approval::{ApprovalRule, ApprovalRuleId},
+    WorkspacePk,
+};
+
+use crate::{
+    extract::{HandlerContext, PosthogEventTracker},
+    service::v2::AccessBuilder,
+};
+
+use super::WorkspaceAPIResult;
+
+pub async fn delete_approval_rule(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    tracker: PosthogEventTracker,
+    Path((_workspace_pk, rule_id)): Path<(WorkspacePk, ApprovalRuleId)>,
+) -> WorkspaceAPIResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    ApprovalRule::remove(&ctx, rule_id).await?;
+
+    tracker.track(
+        &ctx,
+        "workspace_api_approval_rule_deleted",
+        serde_json::json!({
+            "how": "/workspace/delete_approval_rule",
+            "rule_id": rule_id,
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use axum::extract::Path;
+use dal::{
+    change_set::approval::{ApproverGroup, ApproverGroupId},
+    WorkspacePk,
+};
+
+use crate::{
+    extract::{HandlerContext, PosthogEventTracker},
+    service::v2::AccessBuilder,
+};
+
+use super::WorkspaceAPIResult;
+
+pub async fn delete_approver_group(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    tracker: PosthogEventTracker,
+    Path((_workspace_pk, group_id)): Path<(WorkspacePk, ApproverGroupId)>,
+) -> WorkspaceAPIResult<()> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    ApproverGroup::remove(&ctx, group_id).await?;
+
+    tracker.track(
+        &ctx,
+        "workspace_api_approver_group_deleted",
+        serde_json::json!({
+            "how": "/workspace/delete_approver_group",
+            "group_id": group_id,
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(())
+}
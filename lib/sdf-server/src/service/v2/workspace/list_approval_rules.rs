@@ -0,0 +1,16 @@
+use axum::{extract::Path, Json};
+use dal::{change_set::approval::ApprovalRule, WorkspacePk};
+
+use crate::{extract::HandlerContext, service::v2::AccessBuilder};
+
+use super::WorkspaceAPIResult;
+
+pub async fn list_approval_rules(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> WorkspaceAPIResult<Json<Vec<ApprovalRule>>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    Ok(Json(ApprovalRule::list(&ctx).await?))
+}
@@ -0,0 +1,16 @@
+use axum::{extract::Path, Json};
+use dal::{change_set::approval::ApproverGroup, WorkspacePk};
+
+use crate::{extract::HandlerContext, service::v2::AccessBuilder};
+
+use super::WorkspaceAPIResult;
+
+pub async fn list_approver_groups(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> WorkspaceAPIResult<Json<Vec<ApproverGroup>>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    Ok(Json(ApproverGroup::list(&ctx).await?))
+}
@@ -0,0 +1,22 @@
+use axum::{extract::Path, Json};
+use dal::{revoked_auth_token::RevokedAuthToken, WorkspacePk};
+
+use crate::{extract::HandlerContext, service::v2::AccessBuilder};
+
+use super::{revoke_auth_token::RevokedAuthTokenView, WorkspaceAPIResult};
+
+pub async fn list_revoked_auth_tokens(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    Path(_workspace_pk): Path<WorkspacePk>,
+) -> WorkspaceAPIResult<Json<Vec<RevokedAuthTokenView>>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let revoked = RevokedAuthToken::list_for_workspace(&ctx)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    Ok(Json(revoked))
+}
@@ -0,0 +1,55 @@
+use axum::{extract::Path, Json};
+use chrono::{DateTime, Utc};
+use dal::{revoked_auth_token::RevokedAuthToken, UserPk, WorkspacePk};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    extract::{HandlerContext, PosthogEventTracker},
+    service::v2::AccessBuilder,
+};
+
+use super::WorkspaceAPIResult;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokedAuthTokenView {
+    pub token_id: String,
+    pub revoked_by_user_id: Option<UserPk>,
+    pub revoked_at: DateTime<Utc>,
+}
+
+impl From<RevokedAuthToken> for RevokedAuthTokenView {
+    fn from(token: RevokedAuthToken) -> Self {
+        Self {
+            token_id: token.token_id().to_owned(),
+            revoked_by_user_id: token.revoked_by_user_pk(),
+            revoked_at: token.revoked_at(),
+        }
+    }
+}
+
+/// Adds an automation token to the workspace's denylist. Its JWT id is used as the token id, which is the same id the
+/// auth api reports for the token.
+pub async fn revoke_auth_token(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    tracker: PosthogEventTracker,
+    Path((_workspace_pk, token_id)): Path<(WorkspacePk, String)>,
+) -> WorkspaceAPIResult<Json<RevokedAuthTokenView>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let revoked = RevokedAuthToken::revoke(&ctx, &token_id).await?;
+
+    tracker.track(
+        &ctx,
+        "workspace_api_token_revoked",
+        serde_json::json!({
+            "how": "/workspace/revoke_auth_token",
+            "token_id": token_id,
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(revoked.into()))
+}
@@ -0,0 +1,45 @@
+use axum::{extract::Path, Json};
+use dal::{
+    change_set::approval::{ApproverGroup, ApproverGroupId},
+    UserPk, WorkspacePk,
+};
+use serde::Deserialize;
+
+use crate::{
+    extract::{HandlerContext, PosthogEventTracker},
+    service::v2::AccessBuilder,
+};
+
+use super::WorkspaceAPIResult;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateApproverGroupMembersRequest {
+    pub member_user_pks: Vec<UserPk>,
+}
+
+pub async fn update_approver_group_members(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(access_builder): AccessBuilder,
+    tracker: PosthogEventTracker,
+    Path((_workspace_pk, group_id)): Path<(WorkspacePk, ApproverGroupId)>,
+    Json(request): Json<UpdateApproverGroupMembersRequest>,
+) -> WorkspaceAPIResult<Json<ApproverGroup>> {
+    let ctx = builder.build_head(access_builder).await?;
+
+    let group = ApproverGroup::set_members(&ctx, group_id, request.member_user_pks).await?;
+
+    tracker.track(
+        &ctx,
+        "workspace_api_approver_group_members_updated",
+        serde_json::json!({
+            "how": "/workspace/update_approver_group_members",
+            "group_id": group_id,
+            "members": group.member_user_pks.len(),
+        }),
+    );
+
+    ctx.commit_no_rebase().await?;
+
+    Ok(Json(group))
+}
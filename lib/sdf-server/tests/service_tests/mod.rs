@@ -7,6 +7,7 @@ use serde::{de::DeserializeOwned, Serialize};
 use tower::ServiceExt;
 
 mod crdt;
+mod public_api;
 mod session;
 
 pub async fn api_request_auth_empty<Res: DeserializeOwned>(
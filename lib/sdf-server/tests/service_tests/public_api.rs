@@ -0,0 +1,166 @@
+use axum::{
+    body::Body,
+    http::{self, Method, Request, StatusCode},
+    Router,
+};
+use dal::{ComponentId, DalContext};
+use dal_test::{sdf_test, AuthTokenRef};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// Sends a request to the public API, returning the status and the JSON body so that error responses can be checked
+/// too.
+async fn public_api_request(
+    app: Router,
+    method: Method,
+    uri: impl AsRef<str>,
+    auth_token: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let api_request = Request::builder()
+        .method(method)
+        .uri(format!("/api/public/v0{}", uri.as_ref()))
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(http::header::AUTHORIZATION, format!("Bearer {auth_token}"))
+        .body(match body {
+            Some(body) => Body::from(serde_json::to_vec(&body).expect("cannot serialize body")),
+            None => Body::empty(),
+        })
+        .expect("cannot create api request");
+    let response = app.oneshot(api_request).await.expect("cannot send request");
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .expect("cannot read body");
+    let body = serde_json::from_slice(&body).expect("response is not valid json");
+    (status, body)
+}
+
+fn change_set_uri(ctx: &DalContext, path: &str) -> String {
+    format!(
+        "/workspaces/{}/change-sets/{}{path}",
+        ctx.workspace_pk().expect("no workspace in ctx"),
+        ctx.change_set_id()
+    )
+}
+
+#[sdf_test]
+async fn openapi_document_describes_routes(
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let (status, document) =
+        public_api_request(app, Method::GET, "/openapi.json", auth_token, None).await;
+    assert_eq!(StatusCode::OK, status);
+
+    let components =
+        &document["paths"]["/workspaces/{workspace_id}/change-sets/{change_set_id}/components"];
+    assert_eq!("listComponents", components["get"]["operationId"]);
+    assert_eq!("createComponent", components["post"]["operationId"]);
+    assert!(document["components"]["schemas"]["ChangeSetStatus"]["enum"]
+        .as_array()
+        .is_some_and(|values| values.contains(&json!("NeedsApproval"))));
+}
+
+#[sdf_test]
+async fn create_list_and_delete_components(
+    ctx: &DalContext,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let (status, created) = public_api_request(
+        app.clone(),
+        Method::POST,
+        change_set_uri(ctx, "/components"),
+        auth_token,
+        Some(json!({ "schemaName": "starfield", "name": "from the public api" })),
+    )
+    .await;
+    assert_eq!(StatusCode::OK, status, "{created}");
+    assert_eq!("starfield", created["component"]["schemaName"]);
+    assert_eq!("from the public api", created["component"]["name"]);
+    let component_id = created["component"]["id"].clone();
+
+    let (status, listed) = public_api_request(
+        app.clone(),
+        Method::GET,
+        change_set_uri(ctx, "/components"),
+        auth_token,
+        None,
+    )
+    .await;
+    assert_eq!(StatusCode::OK, status, "{listed}");
+    assert!(listed["components"]
+        .as_array()
+        .expect("components should be listed")
+        .iter()
+        .any(|component| component["id"] == component_id));
+
+    let component_uri = change_set_uri(
+        ctx,
+        &format!(
+            "/components/{}",
+            component_id.as_str().expect("id is a string")
+        ),
+    );
+    let (status, fetched) =
+        public_api_request(app.clone(), Method::GET, &component_uri, auth_token, None).await;
+    assert_eq!(StatusCode::OK, status, "{fetched}");
+    assert_eq!(component_id, fetched["component"]["id"]);
+
+    let (status, deleted) =
+        public_api_request(app, Method::DELETE, &component_uri, auth_token, None).await;
+    assert_eq!(StatusCode::OK, status, "{deleted}");
+}
+
+#[sdf_test]
+async fn lists_actions_and_funcs(
+    ctx: &DalContext,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let (status, actions) = public_api_request(
+        app.clone(),
+        Method::GET,
+        change_set_uri(ctx, "/actions"),
+        auth_token,
+        None,
+    )
+    .await;
+    assert_eq!(StatusCode::OK, status, "{actions}");
+    assert!(actions["actions"].is_array());
+
+    let (status, funcs) = public_api_request(
+        app,
+        Method::GET,
+        change_set_uri(ctx, "/funcs"),
+        auth_token,
+        None,
+    )
+    .await;
+    assert_eq!(StatusCode::OK, status, "{funcs}");
+    assert!(!funcs["funcs"]
+        .as_array()
+        .expect("funcs should be listed")
+        .is_empty());
+}
+
+#[sdf_test]
+async fn errors_have_a_consistent_body(
+    ctx: &DalContext,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let (status, body) = public_api_request(
+        app,
+        Method::GET,
+        change_set_uri(ctx, &format!("/components/{}", ComponentId::generate())),
+        auth_token,
+        None,
+    )
+    .await;
+    assert_eq!(StatusCode::NOT_FOUND, status, "{body}");
+    assert_eq!("notFound", body["error"]["code"]);
+    assert_eq!(404, body["error"]["statusCode"]);
+    assert!(body["error"]["message"].is_string());
+}
@@ -28,6 +28,7 @@ pub use symmetric::{
 pub use veritech::{
     config::VeritechCryptoConfig,
     decryption_key::{VeritechDecryptionKey, VeritechDecryptionKeyError},
+    decryption_key_ring::VeritechDecryptionKeyRing,
     encryption_key::{VeritechEncryptionKey, VeritechEncryptionKeyError},
     key_pair::{VeritechKeyPair, VeritechKeyPairError},
 };
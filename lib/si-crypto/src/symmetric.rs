@@ -173,6 +173,11 @@ impl SymmetricCryptoService {
         )
     }
 
+    /// Returns a [`Hash`] of the active [`SymmetricKey`], which is used for all encryption.
+    pub fn active_key_hash(&self) -> &Hash {
+        self.active_key_hash.as_ref()
+    }
+
     /// Decrypts a ciphertext provided with a nonce and a [`Hash`] of the encrypting
     /// [`SymmetricKey`] and returns the decrypted message.
     ///
@@ -1,4 +1,5 @@
 pub(crate) mod config;
 pub(crate) mod decryption_key;
+pub(crate) mod decryption_key_ring;
 pub(crate) mod encryption_key;
 pub(crate) mod key_pair;
@@ -2,6 +2,11 @@ use serde::{Deserialize, Serialize};
 use si_std::CanonicalFile;
 
 /// Configuration for how to load the key for [`CryptoConfig`].
+///
+/// A primary decryption key is always required when decrypting. Any number of extra decryption
+/// keys can also be supplied which are selected by the hash of the encryption key that encrypted
+/// a message. In this way, the encryption key can be rotated by first deploying the new
+/// decryption key alongside the old one(s) and then retiring the old ones.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct VeritechCryptoConfig {
     /// Key file encoded as a base64 string
@@ -12,4 +17,10 @@ pub struct VeritechCryptoConfig {
     pub decryption_key_base64: Option<String>,
     /// Key file on disk
     pub decryption_key_file: Option<CanonicalFile>,
+    /// Extra decryption keys encoded as base64 strings
+    #[serde(default)]
+    pub extra_decryption_keys_base64: Vec<String>,
+    /// Extra decryption key files on disk
+    #[serde(default)]
+    pub extra_decryption_key_files: Vec<CanonicalFile>,
 }
@@ -0,0 +1,161 @@
+use std::{collections::HashMap, sync::Arc};
+
+use si_hash::Hash;
+use telemetry::prelude::*;
+
+use crate::{VeritechCryptoConfig, VeritechDecryptionKey, VeritechDecryptionKeyError};
+
+/// A set of [`VeritechDecryptionKeys`](VeritechDecryptionKey) which can decrypt segments of a
+/// Veritech function request message.
+///
+/// Each encrypted segment of a message carries the hash of the encryption key that encrypted it.
+/// That hash is used to select the matching decryption key from the ring. Holding more than one
+/// key allows for rotating the encryption key without having to coordinate a simultaneous
+/// redeploy of all services that encrypt messages.
+#[derive(Clone, Debug)]
+pub struct VeritechDecryptionKeyRing {
+    keys: Arc<HashMap<String, VeritechDecryptionKey>>,
+    primary_key_hash: Hash,
+}
+
+impl VeritechDecryptionKeyRing {
+    /// Creates and returns a new key ring loaded with the given
+    /// [`VeritechDecryptionKeys`](VeritechDecryptionKey).
+    pub fn new(primary_key: VeritechDecryptionKey, extra_keys: Vec<VeritechDecryptionKey>) -> Self {
+        let primary_key_hash = *primary_key.encryption_key_hash();
+
+        let mut keys = HashMap::new();
+        for key in extra_keys {
+            keys.insert(key.encryption_key_hash_str().to_owned(), key);
+        }
+        keys.insert(
+            primary_key.encryption_key_hash_str().to_owned(),
+            primary_key,
+        );
+
+        Self {
+            keys: Arc::new(keys),
+            primary_key_hash,
+        }
+    }
+
+    /// Creates an instance of [`VeritechDecryptionKeyRing`] based on the supplied configuration.
+    ///
+    /// # Errors
+    ///
+    /// Return `Err` if:
+    ///
+    /// - A key file was not readable (i.e. incorrect permission and/or ownership)
+    /// - A key file could not be successfuly parsed
+    /// - A key string could not be successfully parsed
+    /// - An invalid configuration was passed in
+    pub async fn from_config(
+        config: VeritechCryptoConfig,
+    ) -> Result<Self, VeritechDecryptionKeyError> {
+        let extra_key_files = config.extra_decryption_key_files.clone();
+        let extra_keys_base64 = config.extra_decryption_keys_base64.clone();
+
+        let primary_key = VeritechDecryptionKey::from_config(config).await?;
+
+        let mut extra_keys = Vec::with_capacity(extra_key_files.len() + extra_keys_base64.len());
+        for path in extra_key_files {
+            extra_keys.push(VeritechDecryptionKey::load(path).await?);
+        }
+        for b64_string in extra_keys_base64 {
+            extra_keys.push(VeritechDecryptionKey::decode(b64_string).await?);
+        }
+
+        debug!(
+            primary_key_hash = %primary_key.encryption_key_hash(),
+            extra_keys = extra_keys.len(),
+            "loaded veritech decryption key ring",
+        );
+
+        Ok(Self::new(primary_key, extra_keys))
+    }
+
+    /// Returns the [`VeritechDecryptionKey`] which decrypts messages encrypted by the encryption
+    /// key with the given hash, if one is loaded.
+    pub fn get(&self, encryption_key_hash: impl AsRef<str>) -> Option<&VeritechDecryptionKey> {
+        self.keys.get(encryption_key_hash.as_ref())
+    }
+
+    /// Returns a [`Hash`] of the encryption key corresponding to the primary decryption key.
+    pub fn primary_encryption_key_hash(&self) -> &Hash {
+        &self.primary_key_hash
+    }
+
+    /// Returns an iterator over the hashes of all encryption keys this ring can decrypt messages
+    /// for.
+    pub fn encryption_key_hashes(&self) -> impl Iterator<Item = &str> {
+        self.keys.keys().map(String::as_str)
+    }
+
+    /// Returns the number of keys loaded in the ring.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the ring holds no keys.
+    ///
+    /// A constructed ring always holds its primary key, so this is only provided for
+    /// completeness.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl From<VeritechDecryptionKey> for VeritechDecryptionKeyRing {
+    fn from(value: VeritechDecryptionKey) -> Self {
+        Self::new(value, vec![])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::VeritechKeyPair;
+
+    use super::*;
+
+    #[test]
+    fn selects_key_by_encryption_key_hash() {
+        sodiumoxide::init().expect("failed to init sodiumoxide");
+
+        let (old_encryption_key, old_decryption_key) = VeritechKeyPair::create();
+        let (new_encryption_key, new_decryption_key) = VeritechKeyPair::create();
+
+        let ring = VeritechDecryptionKeyRing::new(new_decryption_key, vec![old_decryption_key]);
+
+        assert_eq!(2, ring.len());
+        assert_eq!(
+            new_encryption_key.key_hash(),
+            ring.primary_encryption_key_hash()
+        );
+
+        for encryption_key in [old_encryption_key, new_encryption_key] {
+            let crypted = encryption_key.encrypt_and_encode("Losing My Religion");
+            let decryption_key = ring
+                .get(encryption_key.key_hash().to_string())
+                .expect("no decryption key found for encryption key hash");
+            let decrypted = decryption_key
+                .decode_and_decrypt(crypted)
+                .expect("failed to decrypt");
+
+            assert_eq!(b"Losing My Religion".as_slice(), decrypted.as_slice());
+        }
+    }
+
+    #[test]
+    fn missing_key() {
+        sodiumoxide::init().expect("failed to init sodiumoxide");
+
+        let (_, decryption_key) = VeritechKeyPair::create();
+        let (unknown_encryption_key, _) = VeritechKeyPair::create();
+
+        let ring = VeritechDecryptionKeyRing::from(decryption_key);
+
+        assert!(ring
+            .get(unknown_encryption_key.key_hash().to_string())
+            .is_none());
+    }
+}
@@ -29,10 +29,21 @@ impl AuditLog {
     ) -> Self {
         Self::V1(Box::new(AuditLogV1 {
             actor,
+            actor_email: None,
             kind,
             entity_name,
             timestamp: Utc::now().to_rfc3339(),
             change_set_id: Some(change_set_id),
         }))
     }
+
+    /// Includes the email of the actor.
+    pub fn with_actor_email(self, actor_email: String) -> Self {
+        match self {
+            Self::V1(mut inner) => {
+                inner.actor_email = Some(actor_email);
+                Self::V1(inner)
+            }
+        }
+    }
 }
@@ -1,11 +1,12 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use si_id::ManagementPrototypeId;
+use si_id::{CommentId, CommentThreadId, ManagementPrototypeId};
 use strum::{Display, EnumDiscriminants};
 
 use crate::{
     func_run::FuncArgumentKind, ActionKind, ActionPrototypeId, Actor, AttributeValueId,
     ChangeSetId, ChangeSetStatus, ComponentId, FuncArgumentId, FuncId, FuncKind, FuncRunId,
-    InputSocketId, OutputSocketId, PropId, SchemaId, SchemaVariantId, SecretId, ViewId,
+    InputSocketId, OutputSocketId, PropId, SchemaId, SchemaVariantId, SecretId, UserPk, ViewId,
     WorkspacePk,
 };
 
@@ -16,6 +17,10 @@ type Metadata = AuditLogMetadataV1;
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct AuditLogV1 {
     pub actor: Actor,
+    /// The email of the actor, which is only included on the audit logs that are delivered as
+    /// webhooks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor_email: Option<String>,
     pub kind: AuditLogKindV1,
     pub entity_name: String,
     pub timestamp: String,
@@ -89,6 +94,10 @@ pub enum AuditLogKindV1 {
         func_display_name: Option<String>,
         func_name: String,
     },
+    CancelScheduledChangeSetApply {
+        window_start: DateTime<Utc>,
+        window_end: Option<DateTime<Utc>>,
+    },
     ContributeModule {
         version: String,
         schema_id: Option<SchemaId>,
@@ -96,6 +105,13 @@ pub enum AuditLogKindV1 {
         schema_variant_version: Option<String>,
     },
     CreateChangeSet,
+    CreateComment {
+        thread_id: CommentThreadId,
+        comment_id: CommentId,
+        component_id: Option<ComponentId>,
+        attribute_path: Option<String>,
+        mentioned_user_pks: Vec<UserPk>,
+    },
     CreateComponent {
         name: String,
         component_id: ComponentId,
@@ -134,6 +150,10 @@ pub enum AuditLogKindV1 {
     CreateView {
         view_id: ViewId,
     },
+    DeleteComment {
+        thread_id: CommentThreadId,
+        comment_id: CommentId,
+    },
     DeleteComponent {
         name: String,
         component_id: ComponentId,
@@ -179,6 +199,19 @@ pub enum AuditLogKindV1 {
         component_id: Option<ComponentId>,
         subject_name: String,
     },
+    DetectResourceDrift {
+        component_id: ComponentId,
+        component_name: String,
+        prototype_id: ActionPrototypeId,
+        func_id: FuncId,
+        func_display_name: Option<String>,
+        func_name: String,
+    },
+    EditComment {
+        thread_id: CommentThreadId,
+        comment_id: CommentId,
+        mentioned_user_pks: Vec<UserPk>,
+    },
     ExecuteFunc {
         func_id: FuncId,
         func_display_name: Option<String>,
@@ -189,6 +222,14 @@ pub enum AuditLogKindV1 {
         version: String,
     },
 
+    FailQualification {
+        component_id: ComponentId,
+        component_name: String,
+        func_id: FuncId,
+        func_display_name: Option<String>,
+        func_name: String,
+        message: Option<String>,
+    },
     GenerateTemplate {
         schema_variant_id: SchemaVariantId,
         management_prototype_id: ManagementPrototypeId,
@@ -218,7 +259,13 @@ pub enum AuditLogKindV1 {
         previous_parent_id: ComponentId,
         previous_parent_name: String,
     },
-
+    OverridePolicyChecks {
+        failed_policies: Vec<String>,
+    },
+    ProtectComponent {
+        component_id: ComponentId,
+        component_name: String,
+    },
     PutActionOnHold {
         prototype_id: ActionPrototypeId,
         action_kind: ActionKind,
@@ -239,6 +286,9 @@ pub enum AuditLogKindV1 {
     RequestChangeSetApproval {
         from_status: ChangeSetStatus,
     },
+    ResolveCommentThread {
+        thread_id: CommentThreadId,
+    },
     RetryAction {
         prototype_id: ActionPrototypeId,
         action_kind: ActionKind,
@@ -254,6 +304,13 @@ pub enum AuditLogKindV1 {
         func_name: String,
         run_status: bool,
     },
+    ScheduleChangeSetApply {
+        window_start: DateTime<Utc>,
+        window_end: Option<DateTime<Utc>>,
+    },
+    SkipScheduledChangeSetApply {
+        reason: String,
+    },
     TestFunction {
         func_id: FuncId,
         func_display_name: Option<String>,
@@ -270,6 +327,13 @@ pub enum AuditLogKindV1 {
         schema_variant_id: SchemaVariantId,
         schema_variant_display_name: String,
     },
+    UnprotectComponent {
+        component_id: ComponentId,
+        component_name: String,
+    },
+    UnresolveCommentThread {
+        thread_id: CommentThreadId,
+    },
     UpdateComponentParent {
         component_id: ComponentId,
         old_parent_id: Option<ComponentId>,
@@ -385,6 +449,12 @@ pub enum AuditLogKindV1 {
         old_schema_variant_id: SchemaVariantId,
         old_schema_variant_name: String,
     },
+    UseAuthToken {
+        token_id: String,
+        method: String,
+        path: String,
+        permission: String,
+    },
     WithdrawRequestForChangeSetApply {
         from_status: ChangeSetStatus,
     },
@@ -472,6 +542,11 @@ pub enum AuditLogMetadataV1 {
         func_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    CancelScheduledChangeSetApply {
+        window_start: DateTime<Utc>,
+        window_end: Option<DateTime<Utc>>,
+    },
+    #[serde(rename_all = "camelCase")]
     ContributeModule {
         version: String,
         schema_id: Option<SchemaId>,
@@ -481,6 +556,14 @@ pub enum AuditLogMetadataV1 {
     #[serde(rename_all = "camelCase")]
     CreateChangeSet,
     #[serde(rename_all = "camelCase")]
+    CreateComment {
+        thread_id: CommentThreadId,
+        comment_id: CommentId,
+        component_id: Option<ComponentId>,
+        attribute_path: Option<String>,
+        mentioned_user_pks: Vec<UserPk>,
+    },
+    #[serde(rename_all = "camelCase")]
     CreateComponent {
         name: String,
         component_id: ComponentId,
@@ -521,6 +604,11 @@ pub enum AuditLogMetadataV1 {
     #[serde(rename_all = "camelCase")]
     CreateView { view_id: ViewId },
     #[serde(rename_all = "camelCase")]
+    DeleteComment {
+        thread_id: CommentThreadId,
+        comment_id: CommentId,
+    },
+    #[serde(rename_all = "camelCase")]
     DeleteComponent {
         name: String,
         component_id: ComponentId,
@@ -569,6 +657,21 @@ pub enum AuditLogMetadataV1 {
         subject_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    DetectResourceDrift {
+        component_id: ComponentId,
+        component_name: String,
+        prototype_id: ActionPrototypeId,
+        func_id: FuncId,
+        func_display_name: Option<String>,
+        func_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    EditComment {
+        thread_id: CommentThreadId,
+        comment_id: CommentId,
+        mentioned_user_pks: Vec<UserPk>,
+    },
+    #[serde(rename_all = "camelCase")]
     ExecuteFunc {
         func_id: FuncId,
         func_display_name: Option<String>,
@@ -580,6 +683,15 @@ pub enum AuditLogMetadataV1 {
         version: String,
     },
 
+    #[serde(rename_all = "camelCase")]
+    FailQualification {
+        component_id: ComponentId,
+        component_name: String,
+        func_id: FuncId,
+        func_display_name: Option<String>,
+        func_name: String,
+        message: Option<String>,
+    },
     #[serde(rename_all = "camelCase")]
     GenerateTemplate {
         schema_variant_id: SchemaVariantId,
@@ -616,6 +728,13 @@ pub enum AuditLogMetadataV1 {
         previous_parent_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    OverridePolicyChecks { failed_policies: Vec<String> },
+    #[serde(rename_all = "camelCase")]
+    ProtectComponent {
+        component_id: ComponentId,
+        component_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
     PutActionOnHold {
         prototype_id: ActionPrototypeId,
         action_kind: ActionKind,
@@ -632,6 +751,8 @@ pub enum AuditLogMetadataV1 {
     #[serde(rename_all = "camelCase")]
     RequestChangeSetApproval { from_status: ChangeSetStatus },
     #[serde(rename_all = "camelCase")]
+    ResolveCommentThread { thread_id: CommentThreadId },
+    #[serde(rename_all = "camelCase")]
     RetryAction {
         prototype_id: ActionPrototypeId,
         action_kind: ActionKind,
@@ -649,6 +770,13 @@ pub enum AuditLogMetadataV1 {
         run_status: bool,
     },
     #[serde(rename_all = "camelCase")]
+    ScheduleChangeSetApply {
+        window_start: DateTime<Utc>,
+        window_end: Option<DateTime<Utc>>,
+    },
+    #[serde(rename_all = "camelCase")]
+    SkipScheduledChangeSetApply { reason: String },
+    #[serde(rename_all = "camelCase")]
     TestFunction {
         func_id: FuncId,
         func_display_name: Option<String>,
@@ -668,6 +796,13 @@ pub enum AuditLogMetadataV1 {
         schema_variant_display_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    UnprotectComponent {
+        component_id: ComponentId,
+        component_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    UnresolveCommentThread { thread_id: CommentThreadId },
+    #[serde(rename_all = "camelCase")]
     UpdateComponentParent {
         component_id: ComponentId,
         old_parent_id: Option<ComponentId>,
@@ -788,6 +923,13 @@ pub enum AuditLogMetadataV1 {
         old_schema_variant_name: String,
     },
     #[serde(rename_all = "camelCase")]
+    UseAuthToken {
+        token_id: String,
+        method: String,
+        path: String,
+        permission: String,
+    },
+    #[serde(rename_all = "camelCase")]
     WithdrawRequestForChangeSetApply { from_status: ChangeSetStatus },
 }
 
@@ -811,8 +953,12 @@ impl AuditLogMetadataV1 {
                 ("Attached", Some("Qualification Function"))
             }
             MetadataDiscrim::CancelAction => ("Removed", Some("Action")),
+            MetadataDiscrim::CancelScheduledChangeSetApply => {
+                ("Cancelled Scheduled Apply", Some("Change Set"))
+            }
             MetadataDiscrim::ContributeModule => ("Contributed", Some("Module")),
             MetadataDiscrim::CreateChangeSet => ("Created", Some("Change Set")),
+            MetadataDiscrim::CreateComment => ("Commented", Some("Comment")),
             MetadataDiscrim::CreateComponent => ("Created", Some("Component")),
             MetadataDiscrim::CreateConnection => ("Created", Some("Connection")),
             MetadataDiscrim::CreateFunc => ("Created", Some("Function")),
@@ -820,6 +966,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::CreateSchemaVariant => ("Created", Some("Schema Variant")),
             MetadataDiscrim::CreateSecret => ("Created", Some("Secret")),
             MetadataDiscrim::CreateView => ("Created", Some("View")),
+            MetadataDiscrim::DeleteComment => ("Deleted", Some("Comment")),
             MetadataDiscrim::DeleteComponent => ("Deleted", Some("Component")),
             MetadataDiscrim::DeleteConnection => ("Deleted", Some("Connection")),
             MetadataDiscrim::DeleteFunc => ("Deleted", Some("Function")),
@@ -828,8 +975,11 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::DeleteSecret => ("Deleted", Some("Secret")),
             MetadataDiscrim::DeleteView => ("Deleted", Some("View")),
             MetadataDiscrim::DetachFunc => ("Detached", Some("Function")),
+            MetadataDiscrim::DetectResourceDrift => ("Detected Drift", Some("Resource")),
+            MetadataDiscrim::EditComment => ("Edited", Some("Comment")),
             MetadataDiscrim::ExecuteFunc => ("Executed", Some("Function")),
             MetadataDiscrim::ExportWorkspace => ("Exported", Some("Workspace")),
+            MetadataDiscrim::FailQualification => ("Failed", Some("Qualification")),
             MetadataDiscrim::InstallWorkspace => ("Installed", Some("Workspace")),
             MetadataDiscrim::GenerateTemplate => ("Generated", Some("Template")),
             MetadataDiscrim::Login => ("Authenticated", None),
@@ -837,6 +987,10 @@ impl AuditLogMetadataV1 {
                 ("Executed", Some("Management Operations"))
             }
             MetadataDiscrim::OrphanComponent => ("Orphaned", Some("Component")),
+            MetadataDiscrim::OverridePolicyChecks => {
+                ("Overrode Failing Policies", Some("Change Set"))
+            }
+            MetadataDiscrim::ProtectComponent => ("Protected", Some("Component")),
             MetadataDiscrim::PutActionOnHold => ("Paused", Some("Action")),
             MetadataDiscrim::RegenerateSchemaVariant => ("Regenerated", Some("Schema Variant")),
             MetadataDiscrim::RejectChangeSetApply => {
@@ -844,11 +998,18 @@ impl AuditLogMetadataV1 {
             }
             MetadataDiscrim::ReopenChangeSet => ("Reopened", Some("Change Set")),
             MetadataDiscrim::RequestChangeSetApproval => ("Requested to Apply", Some("Change Set")),
+            MetadataDiscrim::ResolveCommentThread => ("Resolved", Some("Comment Thread")),
             MetadataDiscrim::RetryAction => ("Retried", Some("Action")),
             MetadataDiscrim::RunAction => ("Ran", Some("Action")),
+            MetadataDiscrim::ScheduleChangeSetApply => ("Scheduled Apply", Some("Change Set")),
+            MetadataDiscrim::SkipScheduledChangeSetApply => {
+                ("Skipped Scheduled Apply", Some("Change Set"))
+            }
             MetadataDiscrim::TestFunction => ("Tested", Some("Function")),
             MetadataDiscrim::UnlockFunc => ("Unlocked", Some("Function")),
             MetadataDiscrim::UnlockSchemaVariant => ("Unlocked", Some("Schema Variant")),
+            MetadataDiscrim::UnprotectComponent => ("Unprotected", Some("Component")),
+            MetadataDiscrim::UnresolveCommentThread => ("Unresolved", Some("Comment Thread")),
             MetadataDiscrim::UpdateComponentParent => ("Updated Parent", Some("Component")),
             MetadataDiscrim::UpdateDependentInputSocket => ("Set Dependent", Some("Input Socket")),
             MetadataDiscrim::UpdateDependentOutputSocket => {
@@ -864,6 +1025,7 @@ impl AuditLogMetadataV1 {
             MetadataDiscrim::UpdateSchemaVariant => ("Updated", Some("Schema Variant")),
             MetadataDiscrim::UpdateView => ("Updated", Some("View")),
             MetadataDiscrim::UpgradeComponent => ("Upgraded", Some("Component")),
+            MetadataDiscrim::UseAuthToken => ("Used", Some("API Token")),
             MetadataDiscrim::WithdrawRequestForChangeSetApply => {
                 ("Withdrew Request to Apply", Some("Change Set"))
             }
@@ -987,6 +1149,13 @@ impl From<Kind> for Metadata {
                 func_display_name,
                 func_name,
             },
+            Kind::CancelScheduledChangeSetApply {
+                window_start,
+                window_end,
+            } => Self::CancelScheduledChangeSetApply {
+                window_start,
+                window_end,
+            },
             Kind::ContributeModule {
                 version,
                 schema_id,
@@ -999,6 +1168,19 @@ impl From<Kind> for Metadata {
                 schema_variant_version,
             },
             Kind::CreateChangeSet => Self::CreateChangeSet,
+            Kind::CreateComment {
+                thread_id,
+                comment_id,
+                component_id,
+                attribute_path,
+                mentioned_user_pks,
+            } => Self::CreateComment {
+                thread_id,
+                comment_id,
+                component_id,
+                attribute_path,
+                mentioned_user_pks,
+            },
             Kind::CreateComponent {
                 name,
                 component_id,
@@ -1058,6 +1240,13 @@ impl From<Kind> for Metadata {
             },
             Kind::CreateSecret { name, secret_id } => Self::CreateSecret { name, secret_id },
             Kind::CreateView { view_id } => Self::CreateView { view_id },
+            Kind::DeleteComment {
+                thread_id,
+                comment_id,
+            } => Self::DeleteComment {
+                thread_id,
+                comment_id,
+            },
             Kind::DeleteComponent {
                 name,
                 component_id,
@@ -1130,6 +1319,30 @@ impl From<Kind> for Metadata {
                 component_id,
                 subject_name,
             },
+            Kind::DetectResourceDrift {
+                component_id,
+                component_name,
+                prototype_id,
+                func_id,
+                func_display_name,
+                func_name,
+            } => Self::DetectResourceDrift {
+                component_id,
+                component_name,
+                prototype_id,
+                func_id,
+                func_display_name,
+                func_name,
+            },
+            Kind::EditComment {
+                thread_id,
+                comment_id,
+                mentioned_user_pks,
+            } => Self::EditComment {
+                thread_id,
+                comment_id,
+                mentioned_user_pks,
+            },
             Kind::ExecuteFunc {
                 func_id,
                 func_display_name,
@@ -1140,6 +1353,21 @@ impl From<Kind> for Metadata {
             Kind::ExportWorkspace { id, name, version } => {
                 Self::ExportWorkspace { id, name, version }
             }
+            Kind::FailQualification {
+                component_id,
+                component_name,
+                func_id,
+                func_display_name,
+                func_name,
+                message,
+            } => Self::FailQualification {
+                component_id,
+                component_name,
+                func_id,
+                func_display_name,
+                func_name,
+                message,
+            },
             Kind::GenerateTemplate {
                 schema_variant_id,
                 management_prototype_id,
@@ -1181,6 +1409,16 @@ impl From<Kind> for Metadata {
                 previous_parent_id,
                 previous_parent_name,
             },
+            Kind::OverridePolicyChecks { failed_policies } => {
+                Self::OverridePolicyChecks { failed_policies }
+            }
+            Kind::ProtectComponent {
+                component_id,
+                component_name,
+            } => Self::ProtectComponent {
+                component_id,
+                component_name,
+            },
             Kind::PutActionOnHold {
                 prototype_id,
                 action_kind,
@@ -1204,6 +1442,7 @@ impl From<Kind> for Metadata {
             Kind::RequestChangeSetApproval { from_status } => {
                 Self::RequestChangeSetApproval { from_status }
             }
+            Kind::ResolveCommentThread { thread_id } => Self::ResolveCommentThread { thread_id },
             Kind::RetryAction {
                 prototype_id,
                 action_kind,
@@ -1232,6 +1471,16 @@ impl From<Kind> for Metadata {
                 func_name,
                 run_status,
             },
+            Kind::ScheduleChangeSetApply {
+                window_start,
+                window_end,
+            } => Self::ScheduleChangeSetApply {
+                window_start,
+                window_end,
+            },
+            Kind::SkipScheduledChangeSetApply { reason } => {
+                Self::SkipScheduledChangeSetApply { reason }
+            }
             Kind::TestFunction {
                 func_id,
                 func_display_name,
@@ -1261,6 +1510,16 @@ impl From<Kind> for Metadata {
                 schema_variant_id,
                 schema_variant_display_name,
             },
+            Kind::UnprotectComponent {
+                component_id,
+                component_name,
+            } => Self::UnprotectComponent {
+                component_id,
+                component_name,
+            },
+            Kind::UnresolveCommentThread { thread_id } => {
+                Self::UnresolveCommentThread { thread_id }
+            }
             Kind::UpdateComponentParent {
                 component_id,
                 old_parent_id,
@@ -1466,6 +1725,17 @@ impl From<Kind> for Metadata {
                 old_schema_variant_id,
                 old_schema_variant_name,
             },
+            Kind::UseAuthToken {
+                token_id,
+                method,
+                path,
+                permission,
+            } => Self::UseAuthToken {
+                token_id,
+                method,
+                path,
+                permission,
+            },
             Kind::WithdrawRequestForChangeSetApply { from_status } => {
                 Self::WithdrawRequestForChangeSetApply { from_status }
             }
@@ -40,6 +40,7 @@ pub enum FuncKind {
     SchemaVariantDefinition,
     Unknown,
     Management,
+    Policy,
 }
 
 /// Describes the kind of [`FuncArgument`](crate::FuncArgument).
@@ -92,6 +93,19 @@ pub enum FuncBackendKind {
     Unset,
     Validation,
     Management,
+    JsPolicy,
+    Wasm,
+    Base64Decode,
+    Base64Encode,
+    BuildArray,
+    BuildMap,
+    Concat,
+    DefaultIfEmpty,
+    JsonPointer,
+    Lowercase,
+    RegexExtract,
+    Template,
+    Uppercase,
 }
 
 // NOTE(nick,zack): do not add "remain::sorted" for postcard de/ser. We need the order to be
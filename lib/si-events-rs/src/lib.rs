@@ -25,6 +25,7 @@ mod tenancy;
 mod timestamp;
 mod vector_clock_id;
 mod web_event;
+mod webhook;
 
 pub use crate::{
     actor::Actor,
@@ -54,5 +55,6 @@ pub use crate::{
     timestamp::Timestamp,
     vector_clock_id::{VectorClockActorId, VectorClockChangeSetId, VectorClockId},
     web_event::WebEvent,
+    webhook::{WebhookDeliveryId, WebhookEndpointId, WebhookEventKind, WebhookPayloadFormat},
     workspace_snapshot_address::WorkspaceSnapshotAddress,
 };
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display, EnumIter, EnumString};
+
+pub use si_id::{WebhookDeliveryId, WebhookEndpointId};
+
+use crate::audit_log::AuditLogKind;
+
+/// The kinds of events that can be delivered to a workspace's outbound webhook endpoints.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumString,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WebhookEventKind {
+    ActionFailed,
+    ChangeSetApplied,
+    ChangeSetApprovalRequested,
+    ChangeSetApprovalWithdrawn,
+    ChangeSetApproved,
+    ChangeSetRejected,
+    QualificationFailed,
+    ResourceDrifted,
+}
+
+impl WebhookEventKind {
+    /// Returns the kind of webhook event that an [`AuditLogKind`] should be delivered as, if any.
+    pub fn for_audit_log_kind(kind: &AuditLogKind) -> Option<Self> {
+        match kind {
+            AuditLogKind::ApplyChangeSet => Some(Self::ChangeSetApplied),
+            AuditLogKind::ApproveChangeSetApply { .. } => Some(Self::ChangeSetApproved),
+            AuditLogKind::DetectResourceDrift { .. } => Some(Self::ResourceDrifted),
+            AuditLogKind::FailQualification { .. } => Some(Self::QualificationFailed),
+            AuditLogKind::RejectChangeSetApply { .. } => Some(Self::ChangeSetRejected),
+            AuditLogKind::RequestChangeSetApproval { .. } => Some(Self::ChangeSetApprovalRequested),
+            AuditLogKind::RunAction {
+                run_status: false, ..
+            } => Some(Self::ActionFailed),
+            AuditLogKind::WithdrawRequestForChangeSetApply { .. } => {
+                Some(Self::ChangeSetApprovalWithdrawn)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The shape of the body delivered to a webhook endpoint.
+#[remain::sorted]
+#[derive(
+    AsRefStr,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Display,
+    EnumString,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum WebhookPayloadFormat {
+    /// A signed JSON document describing the event.
+    #[default]
+    Json,
+    /// A Slack incoming webhook message.
+    Slack,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActionKind, ActionPrototypeId, ChangeSetStatus, FuncId};
+
+    fn run_action(run_status: bool) -> AuditLogKind {
+        AuditLogKind::RunAction {
+            prototype_id: ActionPrototypeId::new(),
+            action_kind: ActionKind::Create,
+            func_id: FuncId::new(),
+            func_display_name: None,
+            func_name: "si:createAction".to_owned(),
+            run_status,
+        }
+    }
+
+    #[test]
+    fn for_audit_log_kind() {
+        let from_status = ChangeSetStatus::Open;
+        let cases = [
+            (
+                AuditLogKind::ApplyChangeSet,
+                Some(WebhookEventKind::ChangeSetApplied),
+            ),
+            (
+                AuditLogKind::ApproveChangeSetApply { from_status },
+                Some(WebhookEventKind::ChangeSetApproved),
+            ),
+            (
+                AuditLogKind::RejectChangeSetApply { from_status },
+                Some(WebhookEventKind::ChangeSetRejected),
+            ),
+            (
+                AuditLogKind::RequestChangeSetApproval { from_status },
+                Some(WebhookEventKind::ChangeSetApprovalRequested),
+            ),
+            (
+                AuditLogKind::WithdrawRequestForChangeSetApply { from_status },
+                Some(WebhookEventKind::ChangeSetApprovalWithdrawn),
+            ),
+            (run_action(false), Some(WebhookEventKind::ActionFailed)),
+            (run_action(true), None),
+            (AuditLogKind::CreateChangeSet, None),
+        ];
+
+        for (kind, expected) in cases {
+            assert_eq!(
+                expected,
+                WebhookEventKind::for_audit_log_kind(&kind),
+                "unexpected event kind for {kind:?}"
+            );
+        }
+    }
+}
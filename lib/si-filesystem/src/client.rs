@@ -1,6 +1,9 @@
-use si_frontend_types::fs::{
-    ChangeSet, CreateChangeSetRequest, CreateChangeSetResponse, ListChangeSetsResponse,
-    ListVariantsResponse, Schema,
+use si_frontend_types::{
+    fs::{
+        ChangeSet, CreateChangeSetRequest, CreateChangeSetResponse, ListChangeSetsResponse,
+        ListVariantsResponse, Schema,
+    },
+    ComponentQueryRequest, ComponentQueryResponse,
 };
 use si_id::{ChangeSetId, SchemaId, WorkspaceId};
 use thiserror::Error;
@@ -108,4 +111,24 @@ impl SiFsClient {
 
         Ok(response.json().await?)
     }
+
+    /// Runs a component query in the change set, returning the matching components and the
+    /// selected values for each.
+    pub async fn query_components(
+        &self,
+        change_set_id: ChangeSetId,
+        query: String,
+        select: Vec<String>,
+    ) -> SiFsClientResult<ComponentQueryResponse> {
+        let response = self
+            .client
+            .post(self.fs_api_change_sets("query", change_set_id))
+            .bearer_auth(&self.token)
+            .json(&ComponentQueryRequest { query, select })
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
 }
@@ -58,6 +58,13 @@ pub enum InodeEntryData {
         change_set_id: ChangeSetId,
         locked: bool,
     },
+    QueryDir {
+        change_set_id: ChangeSetId,
+    },
+    Query {
+        change_set_id: ChangeSetId,
+        query: String,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -96,6 +103,17 @@ impl InodeTable {
             .and_then(|path| self.entries_by_path.get(path))
     }
 
+    pub fn children(&self, ino: u64) -> Vec<(String, &InodeEntry)> {
+        self.entries_by_path
+            .iter()
+            .filter(|(_, entry)| entry.parent == Some(ino))
+            .filter_map(|(path, entry)| {
+                path.file_name()
+                    .map(|name| (name.to_string_lossy().into_owned(), entry))
+            })
+            .collect()
+    }
+
     pub fn next_ino(&self) -> u64 {
         self.path_table.len().saturating_add(1) as u64
     }
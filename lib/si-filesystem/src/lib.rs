@@ -5,11 +5,14 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use client::{SiFsClient, SiFsClientError};
-use fuser::{FileType, MountOption, ReplyAttr, ReplyDirectory, ReplyEntry, ReplyOpen};
+use fuser::{
+    consts::FOPEN_DIRECT_IO, FileType, MountOption, ReplyAttr, ReplyCreate, ReplyData,
+    ReplyDirectory, ReplyEntry, ReplyOpen, ReplyWrite,
+};
 use inode_table::{InodeEntryData, InodeTable, InodeTableError};
 use nix::unistd::{Gid, Uid};
 use nix::{
-    libc::{ENODATA, ENOENT, ENOSYS},
+    libc::{EINVAL, ENODATA, ENOENT, ENOSYS, ENOTDIR},
     unistd,
 };
 use thiserror::Error;
@@ -53,6 +56,11 @@ struct SiFileSystem {
 
 const TTL: Duration = Duration::from_secs(0);
 
+/// The directory inside each change set where component queries are written and read.
+const QUERY_DIR_NAME: &str = "query";
+/// A line in a query file starting with this selects values to return for each match.
+const QUERY_SELECT_PREFIX: &str = "select ";
+
 struct DirEntry {
     ino: u64,
     name: String,
@@ -130,8 +138,214 @@ impl SiFileSystem {
         Ok(())
     }
 
-    async fn open(&self, _ino: u64, reply: ReplyOpen, _flags: i32) -> SiFileSystemResult<()> {
-        reply.opened(self.get_file_handle() | FILE_HANDLE_READ_BIT, 0);
+    async fn open(&self, ino: u64, reply: ReplyOpen, _flags: i32) -> SiFileSystemResult<()> {
+        // Query results are generated on read, so their size is unknown to the kernel. Direct
+        // io makes it read until we return nothing rather than stopping at the reported size.
+        let flags = match self
+            .inode_table
+            .read()
+            .await
+            .get(ino)
+            .map(|entry| entry.data())
+        {
+            Some(InodeEntryData::Query { .. }) => FOPEN_DIRECT_IO,
+            _ => 0,
+        };
+
+        reply.opened(self.get_file_handle() | FILE_HANDLE_READ_BIT, flags);
+        Ok(())
+    }
+
+    async fn create(
+        &self,
+        parent: u64,
+        name: OsString,
+        reply: ReplyCreate,
+    ) -> SiFileSystemResult<()> {
+        let name = name.into_string().expect("received non utf8 name");
+
+        let Some(parent_entry) = self.inode_table.read().await.get(parent).cloned() else {
+            reply.error(ENOENT);
+            return Ok(());
+        };
+
+        let InodeEntryData::QueryDir { change_set_id } = parent_entry.data() else {
+            reply.error(ENOSYS);
+            return Ok(());
+        };
+
+        let attrs = {
+            let mut inode_table = self.inode_table.write().await;
+            let ino = inode_table.upsert_with_parent_ino(
+                parent,
+                &name,
+                InodeEntryData::Query {
+                    change_set_id: *change_set_id,
+                    query: String::new(),
+                },
+                FileType::RegularFile,
+            )?;
+            inode_table.make_attrs(ino, FileType::RegularFile, 0o644)
+        };
+
+        reply.created(&TTL, &attrs, 0, self.get_file_handle(), FOPEN_DIRECT_IO);
+
+        Ok(())
+    }
+
+    async fn write(
+        &self,
+        ino: u64,
+        offset: i64,
+        data: Vec<u8>,
+        reply: ReplyWrite,
+    ) -> SiFileSystemResult<()> {
+        let mut inode_table = self.inode_table.write().await;
+        let Some(entry) = inode_table.get(ino).cloned() else {
+            reply.error(ENOENT);
+            return Ok(());
+        };
+
+        let InodeEntryData::Query {
+            change_set_id,
+            query,
+        } = entry.data()
+        else {
+            reply.error(ENOSYS);
+            return Ok(());
+        };
+
+        let Ok(written) = std::str::from_utf8(&data) else {
+            reply.error(EINVAL);
+            return Ok(());
+        };
+
+        let mut query = query.to_owned();
+        let offset = (offset.max(0) as usize).min(query.len());
+        if !query.is_char_boundary(offset) {
+            reply.error(EINVAL);
+            return Ok(());
+        }
+        query.truncate(offset);
+        query.push_str(written);
+
+        let Some(path) = inode_table.path(ino).cloned() else {
+            reply.error(ENOENT);
+            return Ok(());
+        };
+        inode_table.upsert(
+            path,
+            InodeEntryData::Query {
+                change_set_id: *change_set_id,
+                query,
+            },
+            FileType::RegularFile,
+        );
+
+        reply.written(data.len() as u32);
+
+        Ok(())
+    }
+
+    async fn setattr(
+        &self,
+        ino: u64,
+        size: Option<u64>,
+        reply: ReplyAttr,
+    ) -> SiFileSystemResult<()> {
+        let mut inode_table = self.inode_table.write().await;
+        let Some(entry) = inode_table.get(ino).cloned() else {
+            reply.error(ENOENT);
+            return Ok(());
+        };
+
+        // Truncating a query file (`echo ... > query/name`) clears the query text. Other
+        // attribute changes are accepted and ignored.
+        if let (
+            InodeEntryData::Query {
+                change_set_id,
+                query,
+            },
+            Some(size),
+        ) = (entry.data(), size)
+        {
+            let mut query = query.to_owned();
+            let size = (size as usize).min(query.len());
+            if query.is_char_boundary(size) {
+                query.truncate(size);
+            }
+
+            if let Some(path) = inode_table.path(ino).cloned() {
+                inode_table.upsert(
+                    path,
+                    InodeEntryData::Query {
+                        change_set_id: *change_set_id,
+                        query,
+                    },
+                    FileType::RegularFile,
+                );
+            }
+        }
+
+        reply.attr(&TTL, entry.attrs());
+
+        Ok(())
+    }
+
+    async fn read(
+        &self,
+        ino: u64,
+        offset: i64,
+        size: u32,
+        reply: ReplyData,
+    ) -> SiFileSystemResult<()> {
+        let Some(entry) = self.inode_table.read().await.get(ino).cloned() else {
+            reply.error(ENOENT);
+            return Ok(());
+        };
+
+        let InodeEntryData::Query {
+            change_set_id,
+            query,
+        } = entry.data()
+        else {
+            reply.error(ENOSYS);
+            return Ok(());
+        };
+
+        let mut select = Vec::new();
+        let mut query_lines = Vec::new();
+        for line in query.lines() {
+            match line.trim_start().strip_prefix(QUERY_SELECT_PREFIX) {
+                Some(fields) => select.extend(
+                    fields
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|field| !field.is_empty())
+                        .map(ToOwned::to_owned),
+                ),
+                None => query_lines.push(line),
+            }
+        }
+
+        // Errors, such as a query that does not parse, are returned as the file contents so they
+        // can be read like any other result.
+        let mut contents = match self
+            .client
+            .query_components(*change_set_id, query_lines.join("\n"), select)
+            .await
+        {
+            Ok(response) => serde_json::to_string_pretty(&response.components)
+                .unwrap_or_else(|err| err.to_string()),
+            Err(err) => err.to_string(),
+        }
+        .into_bytes();
+        contents.push(b'\n');
+
+        let start = (offset.max(0) as usize).min(contents.len());
+        let end = start.saturating_add(size as usize).min(contents.len());
+        reply.data(&contents[start..end]);
+
         Ok(())
     }
 
@@ -189,6 +403,12 @@ impl SiFileSystem {
             InodeEntryData::SchemaVariant { .. } => {
                 reply.error(ENOSYS);
             }
+            InodeEntryData::QueryDir { .. } => {
+                reply.error(ENOSYS);
+            }
+            InodeEntryData::Query { .. } => {
+                reply.error(ENOTDIR);
+            }
         }
 
         Ok(())
@@ -284,6 +504,17 @@ impl SiFileSystem {
                     dirs.add(ino, schema.name.clone(), FileType::Directory);
                 }
 
+                {
+                    let mut inode_table = self.inode_table.write().await;
+                    let ino = inode_table.upsert_with_parent_ino(
+                        entry.ino,
+                        QUERY_DIR_NAME,
+                        InodeEntryData::QueryDir { change_set_id: *id },
+                        FileType::Directory,
+                    )?;
+                    dirs.add(ino, QUERY_DIR_NAME.into(), FileType::Directory);
+                }
+
                 dirs.send_reply(&mut reply, offset);
                 reply.ok();
             }
@@ -331,6 +562,20 @@ impl SiFileSystem {
             InodeEntryData::SchemaVariant { .. } => {
                 reply.error(ENOSYS);
             }
+            InodeEntryData::QueryDir { .. } => {
+                let inode_table = self.inode_table.read().await;
+                let mut queries = inode_table.children(entry.ino);
+                queries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                for (name, query_entry) in queries {
+                    dirs.add(query_entry.ino, name, FileType::RegularFile);
+                }
+
+                dirs.send_reply(&mut reply, offset);
+                reply.ok();
+            }
+            InodeEntryData::Query { .. } => {
+                reply.error(ENOTDIR);
+            }
         }
 
         Ok(())
@@ -357,6 +602,29 @@ impl SiFileSystem {
                     FilesystemCommand::OpenDir { reply, ino, flags } => {
                         self_clone.opendir(ino, reply, flags).await
                     }
+                    FilesystemCommand::Create {
+                        parent,
+                        name,
+                        reply,
+                        ..
+                    } => self_clone.create(parent, name, reply).await,
+                    FilesystemCommand::Write {
+                        ino,
+                        offset,
+                        data,
+                        reply,
+                        ..
+                    } => self_clone.write(ino, offset, data, reply).await,
+                    FilesystemCommand::Read {
+                        ino,
+                        offset,
+                        size,
+                        reply,
+                        ..
+                    } => self_clone.read(ino, offset, size, reply).await,
+                    FilesystemCommand::SetAttr {
+                        ino, size, reply, ..
+                    } => self_clone.setattr(ino, size, reply).await,
                     FilesystemCommand::Lookup {
                         parent,
                         name,
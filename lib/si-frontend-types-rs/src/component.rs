@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use si_events::{ComponentId, SchemaId, SchemaVariantId, ViewId};
-use std::num::ParseIntError;
+use std::{collections::BTreeMap, num::ParseIntError};
 use strum::{AsRefStr, Display, EnumIter, EnumString};
 
 #[remain::sorted]
@@ -161,4 +161,30 @@ pub struct DiagramComponentView {
     pub can_be_upgraded: bool,
     pub from_base_change_set: bool,
     pub view_data: Option<GeometryAndView>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default)]
+    pub protected: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentQueryRequest {
+    pub query: String,
+    /// Fields to return for each match, such as `name` or `/domain/region`.
+    #[serde(default)]
+    pub select: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentQueryResponse {
+    pub components: Vec<ComponentQueryMatch>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentQueryMatch {
+    pub component_id: ComponentId,
+    pub values: BTreeMap<String, serde_json::Value>,
 }
@@ -124,6 +124,8 @@ pub enum FuncBinding {
         management_prototype_id: Option<ManagementPrototypeId>,
         func_id: Option<FuncId>,
         managed_schemas: Option<Vec<SchemaId>>,
+        #[serde(default)]
+        tag_selectors: Option<Vec<String>>,
     },
     #[serde(rename_all = "camelCase")]
     Qualification {
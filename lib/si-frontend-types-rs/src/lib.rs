@@ -11,9 +11,9 @@ mod workspace;
 pub use crate::audit_log::AuditLog;
 pub use crate::change_set::{ChangeSet, CreateChangeSetRequest, CreateChangeSetResponse};
 pub use crate::component::{
-    ChangeStatus, ConnectionAnnotation, DiagramComponentView, DiagramSocket,
-    DiagramSocketDirection, DiagramSocketNodeSide, GeometryAndView, GridPoint, RawGeometry, Size2D,
-    StringGeometry,
+    ChangeStatus, ComponentQueryMatch, ComponentQueryRequest, ComponentQueryResponse,
+    ConnectionAnnotation, DiagramComponentView, DiagramSocket, DiagramSocketDirection,
+    DiagramSocketNodeSide, GeometryAndView, GridPoint, RawGeometry, Size2D, StringGeometry,
 };
 pub use crate::conflict::ConflictWithHead;
 pub use crate::func::{
@@ -60,12 +60,18 @@ id!(ValidationOutputId);
 id!(VectorClockActorId);
 id!(VectorClockChangeSetId);
 id!(ViewId);
+id!(WebhookDeliveryId);
+id!(WebhookEndpointId);
 id!(WorkspaceSnapshotNodeId);
 
 // Please keep these alphabetically sorted!
 id_with_pg_types!(ActionId);
+id_with_pg_types!(ApprovalRuleId);
+id_with_pg_types!(ApproverGroupId);
 id_with_pg_types!(CachedModuleId);
 id_with_pg_types!(ChangeSetId);
+id_with_pg_types!(CommentId);
+id_with_pg_types!(CommentThreadId);
 id_with_pg_types!(ComponentId);
 id_with_pg_types!(FuncId);
 id_with_pg_types!(FuncRunId);
@@ -1,5 +1,5 @@
 use core::str;
-use si_events::{UserPk, WorkspacePk};
+use si_events::{ChangeSetId, UserPk, ViewId, WorkspacePk};
 use si_std::CanonicalFile;
 use std::sync::Arc;
 
@@ -96,6 +96,42 @@ impl SiJwtClaimRole {
     }
 }
 
+/// A permission granted to a scoped automation token. Tokens without a scope are granted everything their role
+/// allows.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum SiJwtPermission {
+    /// Run and cancel actions.
+    ActionExecute,
+    /// Apply change sets to HEAD.
+    Apply,
+    /// Create, modify and abandon change sets and their contents.
+    ChangeSetWrite,
+    /// Read workspace data. Every other permission implies this one.
+    ReadOnly,
+    /// Read secret definitions and metadata.
+    SecretRead,
+}
+
+impl SiJwtPermission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ActionExecute => "actionExecute",
+            Self::Apply => "apply",
+            Self::ChangeSetWrite => "changeSetWrite",
+            Self::ReadOnly => "readOnly",
+            Self::SecretRead => "secretRead",
+        }
+    }
+}
+
+impl std::fmt::Display for SiJwtPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[serde(untagged)]
 pub enum SiJwtClaims {
@@ -114,6 +150,15 @@ pub struct SiJwtClaimsV2 {
     user_id: UserPk,
     workspace_id: WorkspacePk,
     role: SiJwtClaimRole,
+    /// If present, the token may only do what these permissions allow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    permissions: Option<Vec<SiJwtPermission>>,
+    /// If present, the token may only access these change sets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    change_set_ids: Option<Vec<ChangeSetId>>,
+    /// If present, the token may only access these views.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    view_ids: Option<Vec<ViewId>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
@@ -149,12 +194,63 @@ impl SiJwtClaims {
         self.role().is_superset_of(required_role)
     }
 
+    /// Whether the token carries any permission, change set or view restrictions.
+    pub fn is_scoped(&self) -> bool {
+        match self {
+            Self::V2(SiJwtClaimsV2 {
+                permissions,
+                change_set_ids,
+                view_ids,
+                ..
+            }) => permissions.is_some() || change_set_ids.is_some() || view_ids.is_some(),
+            Self::V1(_) => false,
+        }
+    }
+
+    /// Whether the token grants the given permission. Any granted permission implies
+    /// [`SiJwtPermission::ReadOnly`].
+    pub fn permits(&self, permission: SiJwtPermission) -> bool {
+        match self {
+            Self::V2(SiJwtClaimsV2 {
+                permissions: Some(permissions),
+                ..
+            }) => {
+                permissions.contains(&permission)
+                    || (permission == SiJwtPermission::ReadOnly && !permissions.is_empty())
+            }
+            _ => true,
+        }
+    }
+
+    pub fn permits_change_set(&self, change_set_id: ChangeSetId) -> bool {
+        match self {
+            Self::V2(SiJwtClaimsV2 {
+                change_set_ids: Some(change_set_ids),
+                ..
+            }) => change_set_ids.contains(&change_set_id),
+            _ => true,
+        }
+    }
+
+    pub fn permits_view(&self, view_id: ViewId) -> bool {
+        match self {
+            Self::V2(SiJwtClaimsV2 {
+                view_ids: Some(view_ids),
+                ..
+            }) => view_ids.contains(&view_id),
+            _ => true,
+        }
+    }
+
     pub fn for_web(user_id: UserPk, workspace_id: WorkspacePk) -> Self {
         Self::V2(SiJwtClaimsV2 {
             version: MustBe!("2"),
             user_id,
             workspace_id,
             role: SiJwtClaimRole::Web,
+            permissions: None,
+            change_set_ids: None,
+            view_ids: None,
         })
     }
 
@@ -294,10 +390,57 @@ mod tests {
                 user_id: UserPk::generate(),
                 workspace_id: WorkspacePk::generate(),
                 role: SiJwtClaimRole::Web,
+                permissions: None,
+                change_set_ids: None,
+                view_ids: None,
+            }),
+            SiJwtClaims::V2(SiJwtClaimsV2 {
+                version: MustBe!("2"),
+                user_id: UserPk::generate(),
+                workspace_id: WorkspacePk::generate(),
+                role: SiJwtClaimRole::Automation,
+                permissions: Some(vec![SiJwtPermission::ChangeSetWrite]),
+                change_set_ids: Some(vec![ChangeSetId::generate()]),
+                view_ids: None,
             }),
         ]
     }
 
+    #[test]
+    fn scoped_permissions() {
+        let change_set_id = ChangeSetId::generate();
+        let claims = SiJwtClaims::V2(SiJwtClaimsV2 {
+            version: MustBe!("2"),
+            user_id: UserPk::generate(),
+            workspace_id: WorkspacePk::generate(),
+            role: SiJwtClaimRole::Automation,
+            permissions: Some(vec![SiJwtPermission::ChangeSetWrite]),
+            change_set_ids: Some(vec![change_set_id]),
+            view_ids: None,
+        });
+
+        assert!(claims.is_scoped());
+        assert!(claims.permits(SiJwtPermission::ChangeSetWrite));
+        assert!(claims.permits(SiJwtPermission::ReadOnly));
+        assert!(!claims.permits(SiJwtPermission::Apply));
+        assert!(!claims.permits(SiJwtPermission::SecretRead));
+        assert!(claims.permits_change_set(change_set_id));
+        assert!(!claims.permits_change_set(ChangeSetId::generate()));
+        assert!(claims.permits_view(ViewId::generate()));
+
+        let unscoped = SiJwtClaims::V2(SiJwtClaimsV2 {
+            version: MustBe!("2"),
+            user_id: UserPk::generate(),
+            workspace_id: WorkspacePk::generate(),
+            role: SiJwtClaimRole::Automation,
+            permissions: None,
+            change_set_ids: None,
+            view_ids: None,
+        });
+        assert!(!unscoped.is_scoped());
+        assert!(unscoped.permits(SiJwtPermission::Apply));
+    }
+
     #[tokio::test]
     async fn validate_with_primary_rs256() {
         for si_claim in v1_and_v2_claims() {
@@ -26,7 +26,7 @@ use crate::{
 
 use self::{
     cache_updates::CacheUpdatesTask, cas::CasDb, rebase_batch::RebaseBatchDb,
-    workspace_snapshot::WorkspaceSnapshotDb,
+    workspace_snapshot::WorkspaceSnapshotDb, workspace_snapshot_chunk::WorkspaceSnapshotChunkDb,
 };
 
 mod cache_updates;
@@ -37,14 +37,21 @@ pub mod func_run_log;
 pub mod rebase_batch;
 pub mod serialize;
 pub mod workspace_snapshot;
+pub mod workspace_snapshot_chunk;
 
 #[derive(Debug, Clone)]
-pub struct LayerDb<CasValue, EncryptedSecretValue, WorkspaceSnapshotValue, RebaseBatchValue>
-where
+pub struct LayerDb<
+    CasValue,
+    EncryptedSecretValue,
+    WorkspaceSnapshotValue,
+    RebaseBatchValue,
+    WorkspaceSnapshotChunkValue,
+> where
     CasValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     EncryptedSecretValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     WorkspaceSnapshotValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     RebaseBatchValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    WorkspaceSnapshotChunkValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
 {
     cas: CasDb<CasValue>,
     encrypted_secret: EncryptedSecretDb<EncryptedSecretValue>,
@@ -52,6 +59,7 @@ where
     func_run_log: FuncRunLogDb,
     rebase_batch: RebaseBatchDb<RebaseBatchValue>,
     workspace_snapshot: WorkspaceSnapshotDb<WorkspaceSnapshotValue>,
+    workspace_snapshot_chunk: WorkspaceSnapshotChunkDb<WorkspaceSnapshotChunkValue>,
     pg_pool: PgPool,
     nats_client: NatsClient,
     persister_client: PersisterClient,
@@ -59,13 +67,26 @@ where
     instance_id: Ulid,
 }
 
-impl<CasValue, EncryptedSecretValue, WorkspaceSnapshotValue, RebaseBatchValue>
-    LayerDb<CasValue, EncryptedSecretValue, WorkspaceSnapshotValue, RebaseBatchValue>
+impl<
+        CasValue,
+        EncryptedSecretValue,
+        WorkspaceSnapshotValue,
+        RebaseBatchValue,
+        WorkspaceSnapshotChunkValue,
+    >
+    LayerDb<
+        CasValue,
+        EncryptedSecretValue,
+        WorkspaceSnapshotValue,
+        RebaseBatchValue,
+        WorkspaceSnapshotChunkValue,
+    >
 where
     CasValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     EncryptedSecretValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     WorkspaceSnapshotValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     RebaseBatchValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    WorkspaceSnapshotChunkValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
 {
     #[instrument(name = "layer_db.init.from_config", level = "info", skip_all)]
     pub async fn from_config(
@@ -108,6 +129,7 @@ where
             func_run_log_cache,
             rebase_batch_cache,
             snapshot_cache,
+            snapshot_chunk_cache,
         ) = try_join!(
             create_layer_cache(
                 cas::CACHE_NAME,
@@ -166,8 +188,18 @@ where
                 compute_executor.clone(),
                 tracker.clone(),
                 token.clone(),
-                50,
-                50
+                40,
+                40
+            ),
+            create_layer_cache(
+                workspace_snapshot_chunk::CACHE_NAME,
+                pg_pool.clone(),
+                cache_config.clone(),
+                compute_executor.clone(),
+                tracker.clone(),
+                token.clone(),
+                10,
+                10
             )
         )?;
 
@@ -180,6 +212,7 @@ where
             func_run_log_cache.clone(),
             rebase_batch_cache.clone(),
             snapshot_cache.clone(),
+            snapshot_chunk_cache.clone(),
             token.clone(),
         )
         .await?;
@@ -202,6 +235,8 @@ where
         let func_run_log = FuncRunLogDb::new(func_run_log_cache, persister_client.clone());
         let workspace_snapshot = WorkspaceSnapshotDb::new(snapshot_cache, persister_client.clone());
         let rebase_batch = RebaseBatchDb::new(rebase_batch_cache, persister_client.clone());
+        let workspace_snapshot_chunk =
+            WorkspaceSnapshotChunkDb::new(snapshot_chunk_cache, persister_client.clone());
 
         let activity = ActivityClient::new(instance_id, nats_client.clone(), token.clone());
         let graceful_shutdown = LayerDbGracefulShutdown { tracker, token };
@@ -213,6 +248,7 @@ where
             func_run,
             func_run_log,
             workspace_snapshot,
+            workspace_snapshot_chunk,
             pg_pool,
             persister_client,
             nats_client,
@@ -259,6 +295,12 @@ where
         &self.workspace_snapshot
     }
 
+    pub fn workspace_snapshot_chunk(
+        &self,
+    ) -> &WorkspaceSnapshotChunkDb<WorkspaceSnapshotChunkValue> {
+        &self.workspace_snapshot_chunk
+    }
+
     pub fn instance_id(&self) -> Ulid {
         self.instance_id
     }
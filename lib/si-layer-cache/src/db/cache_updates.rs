@@ -31,11 +31,13 @@ pub struct CacheUpdatesTask<
     EncryptedSecretValue,
     WorkspaceSnapshotValue,
     RebaseBatchValue,
+    WorkspaceSnapshotChunkValue,
 > where
     CasValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     EncryptedSecretValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     WorkspaceSnapshotValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     RebaseBatchValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    WorkspaceSnapshotChunkValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
 {
     cas_cache: Arc<LayerCache<Arc<CasValue>>>,
     encrypted_secret_cache: Arc<LayerCache<Arc<EncryptedSecretValue>>>,
@@ -43,18 +45,32 @@ pub struct CacheUpdatesTask<
     func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
     rebase_batch_cache: Arc<LayerCache<Arc<RebaseBatchValue>>>,
     snapshot_cache: Arc<LayerCache<Arc<WorkspaceSnapshotValue>>>,
+    snapshot_chunk_cache: Arc<LayerCache<Arc<WorkspaceSnapshotChunkValue>>>,
     event_channel: UnboundedReceiver<LayeredEvent>,
     shutdown_token: CancellationToken,
     tracker: TaskTracker,
 }
 
-impl<CasValue, EncryptedSecretValue, WorkspaceSnapshotValue, RebaseBatchValue>
-    CacheUpdatesTask<CasValue, EncryptedSecretValue, WorkspaceSnapshotValue, RebaseBatchValue>
+impl<
+        CasValue,
+        EncryptedSecretValue,
+        WorkspaceSnapshotValue,
+        RebaseBatchValue,
+        WorkspaceSnapshotChunkValue,
+    >
+    CacheUpdatesTask<
+        CasValue,
+        EncryptedSecretValue,
+        WorkspaceSnapshotValue,
+        RebaseBatchValue,
+        WorkspaceSnapshotChunkValue,
+    >
 where
     CasValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     EncryptedSecretValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     WorkspaceSnapshotValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     RebaseBatchValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    WorkspaceSnapshotChunkValue: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
 {
     const NAME: &'static str = "LayerDB::CacheUpdatesTask";
 
@@ -68,6 +84,7 @@ where
         func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
         rebase_batch_cache: Arc<LayerCache<Arc<RebaseBatchValue>>>,
         snapshot_cache: Arc<LayerCache<Arc<WorkspaceSnapshotValue>>>,
+        snapshot_chunk_cache: Arc<LayerCache<Arc<WorkspaceSnapshotChunkValue>>>,
         shutdown_token: CancellationToken,
     ) -> LayerDbResult<Self> {
         let tracker = TaskTracker::new();
@@ -84,6 +101,7 @@ where
             func_run_log_cache,
             rebase_batch_cache,
             snapshot_cache,
+            snapshot_chunk_cache,
             event_channel,
             shutdown_token,
             tracker,
@@ -113,6 +131,7 @@ where
                 self.func_run_log_cache.clone(),
                 self.snapshot_cache.clone(),
                 self.rebase_batch_cache.clone(),
+                self.snapshot_chunk_cache.clone(),
             );
             self.tracker
                 .spawn(async move { cache_update_task.run(event).await });
@@ -120,12 +139,13 @@ where
     }
 }
 
-struct CacheUpdateTask<Q, R, S, T>
+struct CacheUpdateTask<Q, R, S, T, U>
 where
     Q: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     R: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     S: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    U: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
 {
     cas_cache: Arc<LayerCache<Arc<Q>>>,
     encrypted_secret_cache: Arc<LayerCache<Arc<R>>>,
@@ -133,14 +153,16 @@ where
     func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
     snapshot_cache: Arc<LayerCache<Arc<S>>>,
     rebase_batch_cache: Arc<LayerCache<Arc<T>>>,
+    snapshot_chunk_cache: Arc<LayerCache<Arc<U>>>,
 }
 
-impl<Q, R, S, T> CacheUpdateTask<Q, R, S, T>
+impl<Q, R, S, T, U> CacheUpdateTask<Q, R, S, T, U>
 where
     Q: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     R: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     S: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
     T: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    U: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
 {
     fn new(
         cas_cache: Arc<LayerCache<Arc<Q>>>,
@@ -149,7 +171,8 @@ where
         func_run_log_cache: Arc<LayerCache<Arc<FuncRunLog>>>,
         snapshot_cache: Arc<LayerCache<Arc<S>>>,
         rebase_batch_cache: Arc<LayerCache<Arc<T>>>,
-    ) -> CacheUpdateTask<Q, R, S, T> {
+        snapshot_chunk_cache: Arc<LayerCache<Arc<U>>>,
+    ) -> CacheUpdateTask<Q, R, S, T, U> {
         CacheUpdateTask {
             cas_cache,
             encrypted_secret_cache,
@@ -157,6 +180,7 @@ where
             func_run_log_cache,
             snapshot_cache,
             rebase_batch_cache,
+            snapshot_chunk_cache,
         }
     }
 
@@ -194,6 +218,14 @@ where
                 self.rebase_batch_cache.evict_from_cache_updates(event.key);
             }
 
+            crate::event::LayeredEventKind::SnapshotChunkWrite => {
+                if !self.snapshot_chunk_cache.contains(&event.key) {
+                    let serialized_value =
+                        Arc::try_unwrap(event.payload.value).unwrap_or_else(|arc| (*arc).clone());
+                    self.snapshot_chunk_cache
+                        .insert_from_cache_updates(event.key, serialized_value);
+                }
+            }
             crate::event::LayeredEventKind::SnapshotWrite => {
                 if !self.snapshot_cache.contains(&event.key) {
                     let serialized_value =
@@ -211,12 +243,18 @@ where
                 self.func_run_cache
                     .insert_or_update_from_cache_updates(event.key, serialized_value);
             }
+            crate::event::LayeredEventKind::FuncRunEvict => {
+                self.func_run_cache.evict_from_cache_updates(event.key);
+            }
             crate::event::LayeredEventKind::FuncRunLogWrite => {
                 let serialized_value =
                     Arc::try_unwrap(event.payload.value).unwrap_or_else(|arc| (*arc).clone());
                 self.func_run_log_cache
                     .insert_or_update_from_cache_updates(event.key, serialized_value);
             }
+            crate::event::LayeredEventKind::FuncRunLogEvict => {
+                self.func_run_log_cache.evict_from_cache_updates(event.key);
+            }
         }
 
         Ok(())
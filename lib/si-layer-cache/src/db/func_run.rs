@@ -1,9 +1,11 @@
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use si_events::{
     ActionId, ActionResultState, Actor, AttributeValueId, ChangeSetId, ComponentId, ContentHash,
-    FuncId, FuncRun, FuncRunId, Tenancy, WebEvent, WorkspacePk,
+    FuncId, FuncKind, FuncRun, FuncRunId, Tenancy, WebEvent, WorkspacePk,
 };
 use telemetry::prelude::*;
 
@@ -33,6 +35,19 @@ pub struct FuncRunDb {
     get_last_action_by_action_id: String,
     list_management_history: String,
     get_last_management_by_func_and_component_id: String,
+    list_for_change_set: String,
+    prune_query: String,
+    storage_by_workspace_query: String,
+}
+
+/// How much durable storage a workspace's func runs and their logs use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuncRunStorage {
+    pub workspace_id: String,
+    pub func_run_count: i64,
+    pub func_run_bytes: i64,
+    pub func_run_log_count: i64,
+    pub func_run_log_bytes: i64,
 }
 
 impl FuncRunDb {
@@ -76,7 +91,173 @@ impl FuncRunDb {
                 LIMIT 1
             "#
             ),
+            list_for_change_set: format!(
+                "SELECT value FROM {DBNAME}
+                   WHERE workspace_id = $1 AND change_set_id = $2
+                   ORDER BY updated_at DESC
+                   LIMIT $3",
+            ),
+            // A run is the latest for its attribute value or action unless a newer run shares
+            // either id. Runs without either id have nothing to be the latest of.
+            prune_query: format!(
+                "DELETE FROM {DBNAME} WHERE key IN (
+                   SELECT key FROM {DBNAME} AS run
+                     WHERE run.workspace_id = $1
+                       AND run.function_kind = $2
+                       AND run.updated_at < $3
+                       AND (
+                         NOT $4
+                         OR (run.attribute_value_id IS NULL AND run.action_id IS NULL)
+                         OR EXISTS (
+                           SELECT 1 FROM {DBNAME} AS newer
+                             WHERE newer.workspace_id = run.workspace_id
+                               AND newer.change_set_id = run.change_set_id
+                               AND newer.attribute_value_id = run.attribute_value_id
+                               AND newer.updated_at > run.updated_at
+                         )
+                         OR EXISTS (
+                           SELECT 1 FROM {DBNAME} AS newer
+                             WHERE newer.workspace_id = run.workspace_id
+                               AND newer.change_set_id = run.change_set_id
+                               AND newer.action_id = run.action_id
+                               AND newer.updated_at > run.updated_at
+                         )
+                       )
+                     LIMIT $5
+                 )
+                 RETURNING key, change_set_id",
+            ),
+            storage_by_workspace_query: format!(
+                "SELECT
+                   COALESCE(runs.workspace_id, logs.workspace_id) AS workspace_id,
+                   COALESCE(runs.count, 0) AS func_run_count,
+                   COALESCE(runs.bytes, 0) AS func_run_bytes,
+                   COALESCE(logs.count, 0) AS func_run_log_count,
+                   COALESCE(logs.bytes, 0) AS func_run_log_bytes
+                 FROM (
+                   SELECT workspace_id,
+                          COUNT(*) AS count,
+                          SUM(pg_column_size(value) + pg_column_size(json_value))::bigint AS bytes
+                     FROM {DBNAME} GROUP BY workspace_id
+                 ) AS runs
+                 FULL OUTER JOIN (
+                   SELECT workspace_id,
+                          COUNT(*) AS count,
+                          SUM(pg_column_size(value))::bigint AS bytes
+                     FROM {log_dbname} GROUP BY workspace_id
+                 ) AS logs ON runs.workspace_id = logs.workspace_id
+                 ORDER BY COALESCE(runs.bytes, 0) + COALESCE(logs.bytes, 0) DESC",
+                log_dbname = super::func_run_log::DBNAME,
+            ),
+        }
+    }
+
+    /// Deletes up to `limit` of a workspace's func runs of the given kind that were last updated
+    /// before `older_than`, and evicts them from every cache. When `keep_latest` is set, the
+    /// latest run for each attribute value and action in each change set is kept regardless of its
+    /// age.
+    ///
+    /// Returns the ids of the deleted func runs, so that their logs can be pruned too.
+    #[instrument(level = "info", skip_all, fields(si.workspace.id = %workspace_pk, function_kind = %function_kind))]
+    pub async fn prune(
+        &self,
+        workspace_pk: WorkspacePk,
+        function_kind: FuncKind,
+        older_than: DateTime<Utc>,
+        keep_latest: bool,
+        limit: i64,
+    ) -> LayerDbResult<Vec<FuncRunId>> {
+        let rows = self
+            .cache
+            .pg()
+            .query(
+                &self.prune_query,
+                &[
+                    &workspace_pk,
+                    &function_kind.to_string(),
+                    &older_than,
+                    &keep_latest,
+                    &limit,
+                ],
+            )
+            .await?
+            .unwrap_or_default();
+
+        let mut pruned = Vec::with_capacity(rows.len());
+        for row in rows {
+            let key: String = row.get("key");
+            let change_set_id: String = row.get("change_set_id");
+            self.cache.remove_from_memory(&key);
+
+            let event = LayeredEvent::new(
+                LayeredEventKind::FuncRunEvict,
+                Arc::new(DBNAME.to_string()),
+                key.as_str().into(),
+                Arc::new(Vec::new()),
+                Arc::new(workspace_pk.to_string()),
+                None,
+                Tenancy::new(
+                    workspace_pk,
+                    ChangeSetId::from_str(&change_set_id).map_err(|err| {
+                        LayerDbError::CouldNotConvertToKeyFromString(err.to_string())
+                    })?,
+                ),
+                Actor::System,
+            );
+            self.persister_client.evict_event(event)?;
+
+            pruned
+                .push(FuncRunId::from_str(&key).map_err(|err| {
+                    LayerDbError::CouldNotConvertToKeyFromString(err.to_string())
+                })?);
+        }
+
+        Ok(pruned)
+    }
+
+    /// Reports the storage used by func runs and their logs in every workspace, largest first.
+    pub async fn storage_by_workspace(&self) -> LayerDbResult<Vec<FuncRunStorage>> {
+        let rows = self
+            .cache
+            .pg()
+            .query(&self.storage_by_workspace_query, &[])
+            .await?
+            .unwrap_or_default();
+
+        Ok(rows
+            .into_iter()
+            .map(|row| FuncRunStorage {
+                workspace_id: row.get("workspace_id"),
+                func_run_count: row.get("func_run_count"),
+                func_run_bytes: row.get("func_run_bytes"),
+                func_run_log_count: row.get("func_run_log_count"),
+                func_run_log_bytes: row.get("func_run_log_bytes"),
+            })
+            .collect())
+    }
+
+    /// Lists the most recent func runs in a change set, newest first.
+    pub async fn list_for_change_set(
+        &self,
+        workspace_pk: WorkspacePk,
+        change_set_id: ChangeSetId,
+        limit: i64,
+    ) -> LayerDbResult<Vec<FuncRun>> {
+        let maybe_rows = self
+            .cache
+            .pg()
+            .query(
+                &self.list_for_change_set,
+                &[&workspace_pk, &change_set_id, &limit],
+            )
+            .await?;
+
+        let mut func_runs = Vec::new();
+        for row in maybe_rows.unwrap_or_default() {
+            let postcard_bytes: Vec<u8> = row.get("value");
+            func_runs.push(serialize::from_bytes(&postcard_bytes[..])?);
         }
+        Ok(func_runs)
     }
 
     pub async fn list_action_history(
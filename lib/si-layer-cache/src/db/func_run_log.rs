@@ -1,9 +1,13 @@
+use std::str::FromStr;
 use std::sync::Arc;
 
-use si_events::{Actor, FuncRunId, FuncRunLog, Tenancy, WebEvent};
+use chrono::{DateTime, Utc};
+use si_events::{
+    Actor, ChangeSetId, ComponentId, FuncRun, FuncRunId, FuncRunLog, Tenancy, WebEvent, WorkspacePk,
+};
 
 use crate::{
-    error::LayerDbResult,
+    error::{LayerDbError, LayerDbResult},
     event::{LayeredEvent, LayeredEventKind},
     layer_cache::LayerCache,
     persister::PersisterClient,
@@ -20,6 +24,22 @@ pub struct FuncRunLogDb {
     pub cache: Arc<LayerCache<Arc<FuncRunLog>>>,
     persister_client: PersisterClient,
     get_for_func_run_id_query: String,
+    prune_for_func_run_ids_query: String,
+    list_for_search_query: String,
+}
+
+/// Narrows down which func runs [`FuncRunLogDb::list_for_search`] returns the logs of.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FuncRunLogFilter {
+    pub change_set_id: Option<ChangeSetId>,
+    pub component_id: Option<ComponentId>,
+    /// Only func runs of funcs with this name. Func runs don't record the id of the func they ran
+    /// (action runs store the action's id in its place), but they all record its name.
+    pub function_name: Option<String>,
+    /// Only logs written to at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only logs created at or before this time.
+    pub until: Option<DateTime<Utc>>,
 }
 
 impl FuncRunLogDb {
@@ -28,6 +48,25 @@ impl FuncRunLogDb {
             cache,
             persister_client,
             get_for_func_run_id_query: format!("SELECT value FROM {DBNAME} WHERE func_run_id = $1"),
+            prune_for_func_run_ids_query: format!(
+                "DELETE FROM {DBNAME}
+                   WHERE workspace_id = $1 AND func_run_id = ANY($2)
+                   RETURNING key, change_set_id"
+            ),
+            list_for_search_query: format!(
+                "SELECT logs.value AS log_value, runs.value AS run_value
+                   FROM {DBNAME} AS logs
+                   JOIN {func_run_dbname} AS runs ON runs.key = logs.func_run_id
+                   WHERE logs.workspace_id = $1
+                     AND ($2::text IS NULL OR runs.change_set_id = $2)
+                     AND ($3::text IS NULL OR runs.component_id = $3)
+                     AND ($4::text IS NULL OR runs.json_value->>'function_name' = $4)
+                     AND ($5::timestamptz IS NULL OR logs.updated_at >= $5)
+                     AND ($6::timestamptz IS NULL OR logs.created_at <= $6)
+                   ORDER BY logs.updated_at DESC
+                   LIMIT $7",
+                func_run_dbname = super::func_run::DBNAME,
+            ),
         }
     }
 
@@ -80,6 +119,89 @@ impl FuncRunLogDb {
         }
     }
 
+    /// Lists up to `limit` of a workspace's func run logs matching the filter along with their
+    /// func runs, most recently written first.
+    pub async fn list_for_search(
+        &self,
+        workspace_pk: WorkspacePk,
+        filter: &FuncRunLogFilter,
+        limit: i64,
+    ) -> LayerDbResult<Vec<(FuncRun, FuncRunLog)>> {
+        let rows = self
+            .cache
+            .pg()
+            .query(
+                &self.list_for_search_query,
+                &[
+                    &workspace_pk,
+                    &filter.change_set_id.map(|id| id.to_string()),
+                    &filter.component_id.map(|id| id.to_string()),
+                    &filter.function_name,
+                    &filter.since,
+                    &filter.until,
+                    &limit,
+                ],
+            )
+            .await?
+            .unwrap_or_default();
+
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            let func_run: FuncRun = serialize::from_bytes(row.get("run_value"))?;
+            let func_run_log: FuncRunLog = serialize::from_bytes(row.get("log_value"))?;
+            results.push((func_run, func_run_log));
+        }
+        Ok(results)
+    }
+
+    /// Deletes the logs of the given func runs and evicts them from every cache, returning how
+    /// many were deleted.
+    pub async fn prune_for_func_runs(
+        &self,
+        workspace_pk: WorkspacePk,
+        func_run_ids: &[FuncRunId],
+    ) -> LayerDbResult<usize> {
+        if func_run_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let func_run_ids: Vec<String> = func_run_ids.iter().map(ToString::to_string).collect();
+        let rows = self
+            .cache
+            .pg()
+            .query(
+                &self.prune_for_func_run_ids_query,
+                &[&workspace_pk, &func_run_ids],
+            )
+            .await?
+            .unwrap_or_default();
+
+        for row in &rows {
+            let key: String = row.get("key");
+            let change_set_id: String = row.get("change_set_id");
+            self.cache.remove_from_memory(&key);
+
+            let event = LayeredEvent::new(
+                LayeredEventKind::FuncRunLogEvict,
+                Arc::new(DBNAME.to_string()),
+                key.as_str().into(),
+                Arc::new(Vec::new()),
+                Arc::new(workspace_pk.to_string()),
+                None,
+                Tenancy::new(
+                    workspace_pk,
+                    ChangeSetId::from_str(&change_set_id).map_err(|err| {
+                        LayerDbError::CouldNotConvertToKeyFromString(err.to_string())
+                    })?,
+                ),
+                Actor::System,
+            );
+            self.persister_client.evict_event(event)?;
+        }
+
+        Ok(rows.len())
+    }
+
     pub async fn insert_to_pg(&self, func_run_log: Arc<FuncRunLog>) -> LayerDbResult<()> {
         self.cache
             .pg()
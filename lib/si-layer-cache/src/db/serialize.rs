@@ -30,6 +30,33 @@ where
     Ok((compressed, uncompressed_size))
 }
 
+/// Like [`to_vec`], but also hands back the uncompressed bytes. Compression is not guaranteed to be deterministic, so
+/// content addresses must be computed from the uncompressed form.
+#[inline]
+#[instrument(
+    name = "serialize.to_vec_with_uncompressed",
+    level = "debug",
+    skip_all,
+    fields(
+        bytes.size.compressed = Empty,
+        bytes.size.uncompressed = Empty,
+    )
+)]
+pub fn to_vec_with_uncompressed<T>(value: &T) -> LayerDbResult<(Vec<u8>, Vec<u8>)>
+where
+    T: Serialize + ?Sized,
+{
+    let span = current_span_for_instrument_at!("debug");
+
+    let serialized = postcard::to_stdvec(value)?;
+    let compressed = miniz_oxide::deflate::compress_to_vec(&serialized, 1);
+
+    span.record("bytes.size.compressed", compressed.len());
+    span.record("bytes.size.uncompressed", serialized.len());
+
+    Ok((compressed, serialized))
+}
+
 #[inline]
 #[instrument(
     name = "serialize.from_bytes",
@@ -67,6 +67,17 @@ where
         Ok((key, reader))
     }
 
+    /// Caches a snapshot that was persisted some other way (as chunks, for example) so that reads
+    /// of it can be served from memory. Nothing is written to durable storage.
+    pub fn insert_into_cache(
+        &self,
+        key: &WorkspaceSnapshotAddress,
+        value: Arc<V>,
+        size_hint: usize,
+    ) {
+        self.cache.insert(key.to_string().into(), value, size_hint);
+    }
+
     #[instrument(
         name = "workspace_snapshot.read",
         level = "debug",
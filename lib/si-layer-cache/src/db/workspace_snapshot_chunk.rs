@@ -0,0 +1,167 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serde::{de::DeserializeOwned, Serialize};
+use si_events::{
+    merkle_tree_hash::MerkleTreeHash, Actor, Tenancy, WebEvent, WorkspaceSnapshotAddress,
+};
+use telemetry::prelude::*;
+
+use crate::{
+    error::LayerDbResult,
+    event::{LayeredEvent, LayeredEventKind},
+    layer_cache::LayerCache,
+    persister::{PersisterClient, PersisterStatusReader},
+};
+
+use super::serialize;
+
+pub const DBNAME: &str = "workspace_snapshot_chunks";
+pub const CACHE_NAME: &str = "workspace_snapshot_chunks";
+pub const PARTITION_KEY: &str = "workspace_snapshot_chunks";
+
+/// Storage for workspace snapshots that have been split into content addressed chunks.
+///
+/// Chunks are keyed by a [`MerkleTreeHash`] of their uncompressed contents, so a chunk that is unchanged between two
+/// snapshots (or two change sets) is stored, cached and fetched only once. The chunk containing the root of a
+/// snapshot is additionally stored under the snapshot's [`WorkspaceSnapshotAddress`], which is what change sets
+/// point at.
+#[derive(Debug, Clone)]
+pub struct WorkspaceSnapshotChunkDb<V>
+where
+    V: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    pub cache: Arc<LayerCache<Arc<V>>>,
+    persister_client: PersisterClient,
+}
+
+impl<V> WorkspaceSnapshotChunkDb<V>
+where
+    V: Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    pub fn new(cache: Arc<LayerCache<Arc<V>>>, persister_client: PersisterClient) -> Self {
+        Self {
+            cache,
+            persister_client,
+        }
+    }
+
+    /// Writes a chunk, returning its key. Chunks that are already known to this instance are not persisted again,
+    /// in which case no [`PersisterStatusReader`] is returned.
+    pub fn write(
+        &self,
+        value: Arc<V>,
+        tenancy: Tenancy,
+        actor: Actor,
+    ) -> LayerDbResult<(MerkleTreeHash, Option<PersisterStatusReader>)> {
+        let (postcard_value, uncompressed) = serialize::to_vec_with_uncompressed(&value)?;
+
+        let key = MerkleTreeHash::new(&uncompressed);
+        let cache_key: Arc<str> = key.to_string().into();
+        if self.cache.contains(&cache_key) {
+            return Ok((key, None));
+        }
+
+        let reader = self.insert(cache_key, value, postcard_value, None, tenancy, actor)?;
+
+        Ok((key, Some(reader)))
+    }
+
+    /// Writes the chunk containing the root of a snapshot, returning the address of the snapshot.
+    pub fn write_root(
+        &self,
+        value: Arc<V>,
+        web_events: Option<Vec<WebEvent>>,
+        tenancy: Tenancy,
+        actor: Actor,
+    ) -> LayerDbResult<(WorkspaceSnapshotAddress, PersisterStatusReader)> {
+        let (postcard_value, uncompressed) = serialize::to_vec_with_uncompressed(&value)?;
+
+        let key = WorkspaceSnapshotAddress::new(&uncompressed);
+        let reader = self.insert(
+            key.to_string().into(),
+            value,
+            postcard_value,
+            web_events,
+            tenancy,
+            actor,
+        )?;
+
+        Ok((key, reader))
+    }
+
+    fn insert(
+        &self,
+        cache_key: Arc<str>,
+        value: Arc<V>,
+        postcard_value: Vec<u8>,
+        web_events: Option<Vec<WebEvent>>,
+        tenancy: Tenancy,
+        actor: Actor,
+    ) -> LayerDbResult<PersisterStatusReader> {
+        self.cache
+            .insert(cache_key.clone(), value, postcard_value.len());
+
+        let event = LayeredEvent::new(
+            LayeredEventKind::SnapshotChunkWrite,
+            Arc::new(DBNAME.to_string()),
+            cache_key,
+            Arc::new(postcard_value),
+            Arc::new("workspace_snapshot_chunk".to_string()),
+            web_events,
+            tenancy,
+            actor,
+        );
+
+        self.persister_client.write_event(event)
+    }
+
+    #[instrument(
+        name = "workspace_snapshot_chunk.read",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace_snapshot_chunk.key = %key,
+        )
+    )]
+    pub async fn read(&self, key: &MerkleTreeHash) -> LayerDbResult<Option<Arc<V>>> {
+        self.cache.get(key.to_string().into()).await
+    }
+
+    #[instrument(
+        name = "workspace_snapshot_chunk.read_many",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace_snapshot_chunk.count = keys.len(),
+        )
+    )]
+    pub async fn read_many(
+        &self,
+        keys: &[MerkleTreeHash],
+    ) -> LayerDbResult<HashMap<MerkleTreeHash, Arc<V>>> {
+        self.cache.get_bulk(keys).await
+    }
+
+    /// Reads the chunk containing the root of a snapshot. Only memory is checked if `memory_only` is set, which is
+    /// useful for finding out cheaply whether a snapshot is chunked at all.
+    #[instrument(
+        name = "workspace_snapshot_chunk.read_root",
+        level = "debug",
+        skip_all,
+        fields(
+            si.workspace_snapshot.address = %address,
+        )
+    )]
+    pub async fn read_root(
+        &self,
+        address: &WorkspaceSnapshotAddress,
+        memory_only: bool,
+    ) -> LayerDbResult<Option<Arc<V>>> {
+        let key: Arc<str> = address.to_string().into();
+        if memory_only {
+            self.cache.get_from_memory(key).await
+        } else {
+            self.cache.get(key).await
+        }
+    }
+}
@@ -62,10 +62,11 @@ impl LayeredEventMetadata {
 
 pub use si_id::LayeredEventId;
 
-#[remain::sorted]
 #[derive(AsRefStr, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
+/// NOTE: This type is postcard serialized, so cannot be
+/// #[remain::sorted]. New enum variants must come at the end of the enum!
 pub enum LayeredEventKind {
     CasInsertion,
     EncryptedSecretInsertion,
@@ -76,6 +77,9 @@ pub enum LayeredEventKind {
     RebaseBatchWrite,
     SnapshotEvict,
     SnapshotWrite,
+    SnapshotChunkWrite,
+    FuncRunEvict,
+    FuncRunLogEvict,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
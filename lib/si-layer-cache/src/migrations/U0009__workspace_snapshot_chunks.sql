@@ -0,0 +1,8 @@
+CREATE TABLE workspace_snapshot_chunks
+(
+    key               text                      NOT NULL PRIMARY KEY,
+    sort_key          text                      NOT NULL,
+    created_at        timestamp with time zone  NOT NULL DEFAULT CLOCK_TIMESTAMP(),
+    value             bytea                     NOT NULL,
+    serialization_lib text                      NOT NULL DEFAULT 'postcard'
+);
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS func_run_logs_by_workspace_id ON func_run_logs (workspace_id, updated_at DESC);
@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS func_runs_by_change_set_and_action_id ON func_runs (workspace_id, change_set_id, action_id, updated_at DESC) WHERE action_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS func_runs_by_change_set_and_attribute_value_id ON func_runs (workspace_id, change_set_id, attribute_value_id, updated_at DESC) WHERE attribute_value_id IS NOT NULL;
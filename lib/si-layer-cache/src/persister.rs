@@ -325,9 +325,12 @@ impl PersistEventTask {
         match event.event_kind {
             LayeredEventKind::CasInsertion
             | LayeredEventKind::EncryptedSecretInsertion
+            | LayeredEventKind::FuncRunEvict
+            | LayeredEventKind::FuncRunLogEvict
             | LayeredEventKind::Raw
             | LayeredEventKind::RebaseBatchEvict
             | LayeredEventKind::RebaseBatchWrite
+            | LayeredEventKind::SnapshotChunkWrite
             | LayeredEventKind::SnapshotEvict
             | LayeredEventKind::SnapshotWrite => {
                 pg_layer
@@ -1,11 +1,11 @@
-use chrono::Utc;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use si_layer_cache::hybrid_cache::CacheConfig;
 use std::collections::HashSet;
 use std::{sync::Arc, time::Duration};
 
 use si_events::{
-    Actor, ChangeSetId, ContentHash, FuncBackendKind, FuncBackendResponseType, FuncKind, FuncRun,
-    FuncRunBuilder, FuncRunId, Tenancy, UserPk, WorkspacePk,
+    ActionId, Actor, ChangeSetId, ContentHash, FuncBackendKind, FuncBackendResponseType, FuncKind,
+    FuncRun, FuncRunBuilder, FuncRunId, Tenancy, UserPk, WorkspacePk,
 };
 use si_layer_cache::db::serialize;
 use si_layer_cache::LayerDb;
@@ -14,7 +14,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::integration_test::{setup_compute_executor, setup_nats_client, setup_pg_db};
 
-type TestLayerDb = LayerDb<String, String, String, String>;
+type TestLayerDb = LayerDb<String, String, String, String, String>;
 
 #[tokio::test]
 async fn write_to_db() {
@@ -229,6 +229,101 @@ async fn write_and_read_many_for_workspace_id() {
     );
 }
 
+#[tokio::test]
+async fn prune_keeps_latest_per_change_set() {
+    let token = CancellationToken::new();
+
+    let (ldb, _): (TestLayerDb, _) = LayerDb::from_services(
+        setup_pg_db("func_run_prune_keeps_latest_per_change_set").await,
+        setup_nats_client(Some(
+            "func_run_prune_keeps_latest_per_change_set".to_string(),
+        ))
+        .await,
+        setup_compute_executor(),
+        CacheConfig::default(),
+        token,
+    )
+    .await
+    .expect("cannot create layerdb");
+    ldb.pg_migrate().await.expect("migrate ldb");
+
+    let workspace_pk = WorkspacePk::new();
+    let actor = Actor::User(UserPk::new());
+    let change_set = Tenancy::new(workspace_pk, ChangeSetId::new());
+    let other_change_set = Tenancy::new(workspace_pk, ChangeSetId::new());
+    let action_id = ActionId::new();
+    let now = Utc::now();
+
+    // The same action has run twice in one change set, and more recently in another
+    let oldest = action_run(actor, change_set, action_id, now - ChronoDuration::days(20));
+    let latest = action_run(actor, change_set, action_id, now - ChronoDuration::days(10));
+    let elsewhere = action_run(
+        actor,
+        other_change_set,
+        action_id,
+        now - ChronoDuration::days(1),
+    );
+    for func_run in [&oldest, &latest, &elsewhere] {
+        ldb.func_run()
+            .write(func_run.clone(), None, *func_run.tenancy(), actor)
+            .await
+            .expect("failed to write to layerdb");
+    }
+
+    // A newer run in another change set doesn't make the latest one in this change set prunable
+    let pruned = ldb
+        .func_run()
+        .prune(
+            workspace_pk,
+            FuncKind::Action,
+            now - ChronoDuration::days(5),
+            true,
+            100,
+        )
+        .await
+        .expect("could not prune func runs");
+    assert_eq!(vec![oldest.id()], pruned);
+
+    let pruned = ldb
+        .func_run()
+        .prune(
+            workspace_pk,
+            FuncKind::Action,
+            now - ChronoDuration::days(5),
+            false,
+            100,
+        )
+        .await
+        .expect("could not prune func runs");
+    assert_eq!(vec![latest.id()], pruned);
+}
+
+fn action_run(
+    actor: Actor,
+    tenancy: Tenancy,
+    action_id: ActionId,
+    updated_at: DateTime<Utc>,
+) -> Arc<FuncRun> {
+    Arc::new(
+        FuncRunBuilder::default()
+            .actor(actor)
+            .tenancy(tenancy)
+            .component_id(None)
+            .attribute_value_id(None)
+            .action_or_func_id(Some(action_id.into()))
+            .backend_kind(FuncBackendKind::JsAction)
+            .backend_response_type(FuncBackendResponseType::Action)
+            .function_name("create".to_string())
+            .function_kind(FuncKind::Action)
+            .function_args_cas_address(ContentHash::default())
+            .function_code_cas_address(ContentHash::default())
+            .created_at(updated_at)
+            .updated_at(updated_at)
+            .build()
+            .expect("could not build func run"),
+    )
+}
+
 fn create_func_run(actor: Actor, tenancy: Tenancy, function_name: impl Into<String>) -> FuncRun {
     let func_run_create_time = Utc::now();
     FuncRunBuilder::default()
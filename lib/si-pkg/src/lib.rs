@@ -12,6 +12,8 @@ pub use workspace::{
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use petgraph::dot::Dot;
     use tokio::sync::Mutex;
 
@@ -75,6 +77,60 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn pkg_workspace_component_tags_round_trip() {
+        let mut spec: PkgSpec = serde_json::from_str(WORKSPACE_JSON).unwrap();
+        let tags = BTreeMap::from([
+            ("env".to_string(), "prod".to_string()),
+            ("team".to_string(), "platform".to_string()),
+        ]);
+        let component = ComponentSpec::builder()
+            .name("tagged")
+            .position(
+                PositionSpec::builder()
+                    .x("0")
+                    .y("0")
+                    .width(None::<String>)
+                    .height(None::<String>)
+                    .build()
+                    .expect("build position"),
+            )
+            .variant(ComponentSpecVariant::WorkspaceVariant {
+                variant_unique_id: "variant".to_string(),
+            })
+            .needs_destroy(false)
+            .deletion_user_pk(None::<String>)
+            .unique_id("tagged")
+            .deleted(false)
+            .tags(tags.clone())
+            .build()
+            .expect("build component");
+        spec.change_sets
+            .first_mut()
+            .expect("has a change set")
+            .components
+            .push(component);
+
+        let pkg = SiPkg::load_from_spec(spec).expect("failed to load spec");
+        let pkg_data = pkg.write_to_bytes().expect("failed to serialize pkg");
+        let read_pkg = SiPkg::load_from_bytes(&pkg_data).expect("failed to load pkg from bytes");
+
+        let component = read_pkg
+            .change_sets()
+            .expect("able to get change_sets")
+            .first()
+            .expect("has a change set")
+            .components()
+            .expect("able to get components")
+            .into_iter()
+            .find(|component| component.unique_id() == "tagged")
+            .expect("tagged component was written");
+        assert_eq!(&tags, component.tags());
+
+        let component_spec = ComponentSpec::try_from(component).expect("convert to spec");
+        assert_eq!(tags, component_spec.tags);
+    }
+
     #[tokio::test]
     async fn pkg_bytes_round_trip() {
         let spec: PkgSpec = serde_json::from_str(PACKAGE_JSON).unwrap();
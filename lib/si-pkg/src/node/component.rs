@@ -1,11 +1,12 @@
 use std::{
+    collections::BTreeMap,
     io::{BufRead, Write},
     str::FromStr,
 };
 
 use object_tree::{
-    read_key_value_line, write_key_value_line, GraphError, NameStr, NodeChild, NodeKind,
-    NodeWithChildren, ReadBytes, WriteBytes,
+    read_key_value_line, read_key_value_line_opt, write_key_value_line, write_key_value_line_opt,
+    GraphError, NameStr, NodeChild, NodeKind, NodeWithChildren, ReadBytes, WriteBytes,
 };
 
 use super::{component_child::ComponentChild, PkgNode, KEY_DELETED_STR, KEY_UNIQUE_ID_STR};
@@ -15,6 +16,7 @@ const KEY_NAME_STR: &str = "name";
 const KEY_VARIANT_STR: &str = "variant";
 const KEY_NEEDS_DESTROY_STR: &str = "needs_destroy";
 const KEY_DELETION_USER_PK_STR: &str = "deletion_user_pk";
+const KEY_TAGS_STR: &str = "tags";
 
 #[derive(Clone, Debug)]
 pub struct ComponentNode {
@@ -24,6 +26,7 @@ pub struct ComponentNode {
     pub deletion_user_pk: Option<String>,
     pub unique_id: String,
     pub deleted: bool,
+    pub tags: BTreeMap<String, String>,
 }
 
 impl NameStr for ComponentNode {
@@ -51,6 +54,14 @@ impl WriteBytes for ComponentNode {
         write_key_value_line(writer, KEY_UNIQUE_ID_STR, &self.unique_id)?;
         write_key_value_line(writer, KEY_DELETED_STR, self.deleted)?;
 
+        // Written only when there are tags, so untagged components hash as they did before tags
+        let tags_str = if self.tags.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&self.tags).map_err(GraphError::parse)?)
+        };
+        write_key_value_line_opt(writer, KEY_TAGS_STR, tags_str)?;
+
         Ok(())
     }
 }
@@ -76,6 +87,10 @@ impl ReadBytes for ComponentNode {
         let unique_id = read_key_value_line(reader, KEY_UNIQUE_ID_STR)?;
         let deleted = bool::from_str(&read_key_value_line(reader, KEY_DELETED_STR)?)
             .map_err(GraphError::parse)?;
+        let tags = match read_key_value_line_opt(reader, KEY_TAGS_STR)? {
+            Some(tags_str) => serde_json::from_str(&tags_str).map_err(GraphError::parse)?,
+            None => BTreeMap::new(),
+        };
 
         Ok(Some(Self {
             name,
@@ -84,6 +99,7 @@ impl ReadBytes for ComponentNode {
             deletion_user_pk,
             unique_id,
             deleted,
+            tags,
         }))
     }
 }
@@ -101,6 +117,7 @@ impl NodeChild for ComponentSpec {
                 deletion_user_pk: self.deletion_user_pk.to_owned(),
                 unique_id: self.unique_id.to_owned(),
                 deleted: self.deleted,
+                tags: self.tags.to_owned(),
             }),
             vec![
                 Box::new(ComponentChild::Attributes(self.attributes.to_owned()))
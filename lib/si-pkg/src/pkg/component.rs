@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use object_tree::{Hash, HashedNode};
 use petgraph::prelude::*;
 
@@ -16,6 +18,7 @@ pub struct SiPkgComponent<'a> {
     deletion_user_pk: Option<String>,
     unique_id: String,
     deleted: bool,
+    tags: BTreeMap<String, String>,
 
     hash: Hash,
     source: Source<'a>,
@@ -75,6 +78,7 @@ impl<'a> SiPkgComponent<'a> {
             deletion_user_pk: node.deletion_user_pk,
             deleted: node.deleted,
             unique_id: node.unique_id,
+            tags: node.tags,
 
             hash: hashed_node.hash(),
             source: Source::new(graph, node_idx),
@@ -125,6 +129,10 @@ impl<'a> SiPkgComponent<'a> {
         self.deleted
     }
 
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
     pub fn hash(&self) -> Hash {
         self.hash
     }
@@ -152,7 +160,8 @@ impl<'a> TryFrom<SiPkgComponent<'a>> for ComponentSpec {
             .needs_destroy(value.needs_destroy())
             .deletion_user_pk(value.deletion_user_pk().map(ToString::to_string))
             .unique_id(value.unique_id())
-            .deleted(value.deleted());
+            .deleted(value.deleted())
+            .tags(value.tags().to_owned());
 
         for attribute in value.attributes()? {
             builder.attribute(AttributeValueSpec::try_from(attribute)?);
@@ -9,6 +9,7 @@ mod attribute_value;
 mod authentication_func;
 mod change_set;
 mod component;
+mod component_fragment;
 mod edge;
 mod func;
 mod leaf_function;
@@ -24,8 +25,9 @@ mod variant;
 
 pub use {
     action_func::*, attr_func_input::*, attribute_value::*, authentication_func::*, change_set::*,
-    component::*, edge::*, func::*, leaf_function::*, management_func::*, map_key_func::*,
-    position::*, prop::*, root_prop_func::*, schema::*, si_prop_func::*, socket::*, variant::*,
+    component::*, component_fragment::*, edge::*, func::*, leaf_function::*, management_func::*,
+    map_key_func::*, position::*, prop::*, root_prop_func::*, schema::*, si_prop_func::*,
+    socket::*, variant::*,
 };
 
 use super::SiPkgKind;
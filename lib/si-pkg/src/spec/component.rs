@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
@@ -40,6 +42,10 @@ pub struct ComponentSpec {
     pub unique_id: String,
     #[builder(setter(into))]
     pub deleted: bool,
+    /// User-defined key/value tags on the component.
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
 
     #[builder(setter(each(name = "attribute"), into), default)]
     pub attributes: Vec<AttributeValueSpec>,
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+use super::{EdgeSpec, PositionSpec, SpecError};
+
+/// A selection of components exported from one workspace, so that they can be re-created in
+/// another. Components and views refer to each other by unique id, which is the id they had in the
+/// source workspace.
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct ComponentFragmentSpec {
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub source_workspace_pk: Option<String>,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub source_change_set_id: Option<String>,
+    #[builder(try_setter, setter(into), default = "Utc::now()")]
+    pub created_at: DateTime<Utc>,
+    #[builder(setter(into))]
+    pub created_by: String,
+
+    #[builder(setter(each(name = "component", into)), default)]
+    #[serde(default)]
+    pub components: Vec<FragmentComponentSpec>,
+
+    /// Connections between components in the fragment.
+    #[builder(setter(each(name = "edge", into)), default)]
+    #[serde(default)]
+    pub edges: Vec<EdgeSpec>,
+
+    #[builder(setter(each(name = "view", into)), default)]
+    #[serde(default)]
+    pub views: Vec<FragmentViewSpec>,
+}
+
+impl ComponentFragmentSpec {
+    pub fn builder() -> ComponentFragmentSpecBuilder {
+        ComponentFragmentSpecBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct FragmentComponentSpec {
+    #[builder(setter(into))]
+    pub unique_id: String,
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into))]
+    pub schema_name: String,
+    #[builder(setter(into))]
+    pub variant_version: String,
+    /// The frame the component sits in, if that frame is also in the fragment.
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub parent_unique_id: Option<String>,
+    /// The component's attribute tree, rooted at `root`.
+    #[builder(setter(into))]
+    pub properties: serde_json::Value,
+
+    #[builder(setter(each(name = "geometry", into)), default)]
+    #[serde(default)]
+    pub geometries: Vec<FragmentGeometrySpec>,
+
+    #[builder(setter(into), default)]
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+impl FragmentComponentSpec {
+    pub fn builder() -> FragmentComponentSpecBuilder {
+        FragmentComponentSpecBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct FragmentViewSpec {
+    #[builder(setter(into))]
+    pub unique_id: String,
+    #[builder(setter(into))]
+    pub name: String,
+}
+
+impl FragmentViewSpec {
+    pub fn builder() -> FragmentViewSpecBuilder {
+        FragmentViewSpecBuilder::default()
+    }
+}
+
+#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(build_fn(error = "SpecError"))]
+pub struct FragmentGeometrySpec {
+    #[builder(setter(into))]
+    pub view_unique_id: String,
+    #[builder(setter(into))]
+    pub position: PositionSpec,
+}
+
+impl FragmentGeometrySpec {
+    pub fn builder() -> FragmentGeometrySpecBuilder {
+        FragmentGeometrySpecBuilder::default()
+    }
+}
@@ -62,7 +62,13 @@ impl FuncArgumentSpec {
 #[serde(rename_all = "camelCase")]
 pub enum FuncSpecBackendKind {
     Array,
+    Base64Decode,
+    Base64Encode,
     Boolean,
+    BuildArray,
+    BuildMap,
+    Concat,
+    DefaultIfEmpty,
     Diff,
     Identity,
     Integer,
@@ -70,17 +76,24 @@ pub enum FuncSpecBackendKind {
     JsAttribute,
     JsAuthentication,
     Json,
+    JsonPointer,
+    JsPolicy,
     // NOTE(nick): this is deprecated, but keeping it for now in case something from the module
     // index needs it.
     JsReconciliation,
     JsSchemaVariantDefinition,
     JsValidation,
+    Lowercase,
     Management,
     Map,
     Object,
+    RegexExtract,
     String,
+    Template,
     Unset,
+    Uppercase,
     Validation,
+    Wasm,
 }
 
 #[remain::sorted]
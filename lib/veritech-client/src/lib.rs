@@ -15,7 +15,7 @@ use veritech_core::{
 
 pub use cyclone_core::{
     ActionRunRequest, ActionRunResultSuccess, BeforeFunction, ComponentKind, ComponentView,
-    ComponentViewWithGeometry, FunctionResult, FunctionResultFailure,
+    ComponentViewWithGeometry, FunctionResult, FunctionResultFailure, FunctionResultFailureError,
     FunctionResultFailureErrorKind, KillExecutionRequest, ManagementFuncStatus, ManagementRequest,
     ManagementResultSuccess, OutputStream, ResolverFunctionComponent, ResolverFunctionRequest,
     ResolverFunctionResponseType, ResolverFunctionResultSuccess, ResourceStatus,
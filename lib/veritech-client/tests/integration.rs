@@ -110,8 +110,10 @@ async fn executes_simple_management_function() {
             kind: None,
             properties: serde_json::json!({ "foo": "bar", "baz": "quux", "bar": "foo" }),
             geometry: serde_json::json!({"x": "1", "y": "1"}),
+            tags: Default::default(),
         },
         components: HashMap::new(),
+        selected_components: HashMap::new(),
         code_base64: base64_encode(
             "function numberOfInputs({ thisComponent }) { 
                 const number = Object.keys(thisComponent.properties)?.length; 
@@ -1,6 +1,6 @@
 use serde_json::{json, Value};
 use si_crypto::{
-    SensitiveStrings, VeritechDecryptionKey, VeritechDecryptionKeyError, VeritechEncryptionKey,
+    SensitiveStrings, VeritechDecryptionKeyError, VeritechDecryptionKeyRing, VeritechEncryptionKey,
 };
 use thiserror::Error;
 
@@ -32,7 +32,7 @@ pub enum VeritechValueDecryptError {
     KeyHashFieldMissing,
     #[error("object key hash field value was not a string")]
     KeyHashFieldValueNotString,
-    #[error("object key hash field value does not match provided decryption key")]
+    #[error("object key hash field value does not match any provided decryption key")]
     KeyHashNoMatch,
     #[error("object missing marker field")]
     MarkerFieldMissing,
@@ -86,7 +86,7 @@ pub fn encrypt_value_tree(
 pub fn decrypt_value_tree(
     value: &mut Value,
     sensitive_strings: &mut SensitiveStrings,
-    decryption_key: &VeritechDecryptionKey,
+    decryption_keys: &VeritechDecryptionKeyRing,
 ) -> Result<(), VeritechValueDecryptError> {
     let mut json_pointer_stack = vec!["".to_owned()];
 
@@ -102,7 +102,7 @@ pub fn decrypt_value_tree(
                     );
                 }
                 Value::Object(_) if is_value_encrypted(value) => {
-                    let decrypted_value = decrypt_value(value, decryption_key)?;
+                    let decrypted_value = decrypt_value(value, decryption_keys)?;
                     if let Value::String(sensitive_str) = &decrypted_value {
                         sensitive_strings.insert(sensitive_str);
                     }
@@ -155,7 +155,7 @@ fn encrypt_value(
 
 fn decrypt_value(
     value: &Value,
-    decryption_key: &VeritechDecryptionKey,
+    decryption_keys: &VeritechDecryptionKeyRing,
 ) -> Result<Value, VeritechValueDecryptError> {
     // Confirm value is an object
     let value = value
@@ -171,16 +171,16 @@ fn decrypt_value(
     {
         return Err(VeritechValueDecryptError::MarkerFieldValueNotTrue);
     }
-    // Confirm that key hash field value matches hash for provided decryption key
-    if value
-        .get(KEY_HASH_FIELD)
-        .ok_or(VeritechValueDecryptError::KeyHashFieldMissing)?
-        .as_str()
-        .ok_or(VeritechValueDecryptError::KeyHashFieldValueNotString)?
-        != decryption_key.encryption_key_hash_str()
-    {
-        return Err(VeritechValueDecryptError::KeyHashNoMatch);
-    }
+    // Select the decryption key whose hash matches the key hash field value
+    let decryption_key = decryption_keys
+        .get(
+            value
+                .get(KEY_HASH_FIELD)
+                .ok_or(VeritechValueDecryptError::KeyHashFieldMissing)?
+                .as_str()
+                .ok_or(VeritechValueDecryptError::KeyHashFieldValueNotString)?,
+        )
+        .ok_or(VeritechValueDecryptError::KeyHashNoMatch)?;
 
     // Decrypt crypted field and deserialize decrypted contents as a JSON value
     let decrypted = {
@@ -198,8 +198,15 @@ fn decrypt_value(
 
 #[cfg(test)]
 mod tests {
+    use si_crypto::VeritechKeyPair;
+
     use super::*;
 
+    fn key_pair() -> (VeritechEncryptionKey, VeritechDecryptionKeyRing) {
+        let (encryption_key, decryption_key) = VeritechKeyPair::create();
+        (encryption_key, decryption_key.into())
+    }
+
     mod is_value_encrypted {
         use super::*;
 
@@ -268,13 +275,11 @@ mod tests {
     }
 
     mod encrypt_value {
-        use si_crypto::VeritechKeyPair;
-
         use super::*;
 
         #[test]
         fn string_round_trip() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let message = json!("Telling the Bees");
             let encrypted_value =
@@ -287,7 +292,7 @@ mod tests {
 
         #[test]
         fn obj_round_trip() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let message = json!({
                 "artist": "Dream Theater",
@@ -310,8 +315,6 @@ mod tests {
     }
 
     mod decrypt_value {
-        use si_crypto::VeritechKeyPair;
-
         use super::*;
 
         fn encrypted(encryption_key: &VeritechEncryptionKey) -> Value {
@@ -320,7 +323,7 @@ mod tests {
 
         #[test]
         fn value_not_object() {
-            let (_encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (_encryption_key, decryption_key) = key_pair();
 
             assert!(matches!(
                 decrypt_value(&json!("uh oh"), &decryption_key),
@@ -330,7 +333,7 @@ mod tests {
 
         #[test]
         fn marker_field_missing() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let mut encrypted = encrypted(&encryption_key);
             encrypted
@@ -346,7 +349,7 @@ mod tests {
 
         #[test]
         fn marker_field_value_not_bool() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let mut encrypted = encrypted(&encryption_key);
             *encrypted
@@ -363,7 +366,7 @@ mod tests {
 
         #[test]
         fn marker_field_value_not_true() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let mut encrypted = encrypted(&encryption_key);
             *encrypted
@@ -380,7 +383,7 @@ mod tests {
 
         #[test]
         fn key_hash_field_missing() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let mut encrypted = encrypted(&encryption_key);
             encrypted
@@ -396,7 +399,7 @@ mod tests {
 
         #[test]
         fn key_hash_field_value_not_str() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let mut encrypted = encrypted(&encryption_key);
             *encrypted
@@ -413,7 +416,7 @@ mod tests {
 
         #[test]
         fn key_hash_field_value_no_match() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
             let (wrong_encryption_key, _) = VeritechKeyPair::create();
 
             let mut encrypted = encrypted(&encryption_key);
@@ -431,7 +434,7 @@ mod tests {
 
         #[test]
         fn crypted_field_missing() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let mut encrypted = encrypted(&encryption_key);
             encrypted
@@ -447,7 +450,7 @@ mod tests {
 
         #[test]
         fn crypted_field_value_not_str() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let mut encrypted = encrypted(&encryption_key);
             *encrypted
@@ -464,7 +467,7 @@ mod tests {
 
         #[test]
         fn crypted_field_value_not_properly_encoded() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
 
             let mut encrypted = encrypted(&encryption_key);
             *encrypted
@@ -482,7 +485,7 @@ mod tests {
         #[test]
         fn wrong_decryption_key() {
             let (encryption_key, _) = VeritechKeyPair::create();
-            let (_, wrong_decryption_key) = VeritechKeyPair::create();
+            let (_, wrong_decryption_key) = key_pair();
 
             let encrypted = encrypted(&encryption_key);
 
@@ -491,19 +494,34 @@ mod tests {
                 Err(VeritechValueDecryptError::KeyHashNoMatch),
             ));
         }
+
+        #[test]
+        fn rotated_decryption_keys() {
+            let (old_encryption_key, old_decryption_key) = VeritechKeyPair::create();
+            let (new_encryption_key, new_decryption_key) = VeritechKeyPair::create();
+
+            let decryption_keys =
+                VeritechDecryptionKeyRing::new(new_decryption_key, vec![old_decryption_key]);
+
+            for encryption_key in [old_encryption_key, new_encryption_key] {
+                let decrypted = decrypt_value(&encrypted(&encryption_key), &decryption_keys)
+                    .expect("failed to decrypt message");
+
+                assert_eq!(json!("my-secret"), decrypted);
+            }
+        }
     }
 
     mod encrypt_value_tree {
         use std::collections::HashSet;
 
-        use si_crypto::VeritechKeyPair;
         use si_std::SensitiveString;
 
         use super::*;
 
         #[test]
         fn object_with_string_field_values_round_trip() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
             let mut sensitive_strings = SensitiveStrings::default();
 
             let mut secret = json!({
@@ -526,7 +544,7 @@ mod tests {
 
         #[test]
         fn nested_object_round_trip() {
-            let (encryption_key, decryption_key) = VeritechKeyPair::create();
+            let (encryption_key, decryption_key) = key_pair();
             let mut sensitive_strings = SensitiveStrings::default();
 
             let mut secret = json!({
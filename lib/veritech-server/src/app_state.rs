@@ -1,6 +1,6 @@
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use si_crypto::VeritechDecryptionKey;
+use si_crypto::VeritechDecryptionKeyRing;
 use si_data_nats::NatsClient;
 use si_pool_noodle::{
     instance::cyclone::{LocalUdsInstance, LocalUdsInstanceSpec},
@@ -20,7 +20,7 @@ pub struct AppState {
     // NOTE(nick,fletcher,scott): this implements clone and the inner bits are wrapped in an Arc.
     // If that changes, then I hope you read this comment before that happens.
     pub cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-    pub decryption_key: Arc<VeritechDecryptionKey>,
+    pub decryption_keys: VeritechDecryptionKeyRing,
     // TODO(nick,fletcher,scott): make this mutable at runtime.
     pub cyclone_client_execution_timeout: Duration,
     pub nats: NatsClient,
@@ -32,7 +32,7 @@ impl AppState {
     pub fn new(
         metadata: Arc<ServerMetadata>,
         cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-        decryption_key: Arc<VeritechDecryptionKey>,
+        decryption_keys: VeritechDecryptionKeyRing,
         cyclone_client_execution_timeout: Duration,
         nats: NatsClient,
         kill_senders: Arc<Mutex<HashMap<ExecutionId, oneshot::Sender<()>>>>,
@@ -40,7 +40,7 @@ impl AppState {
         Self {
             metadata,
             cyclone_pool,
-            decryption_key,
+            decryption_keys,
             cyclone_client_execution_timeout,
             nats,
             kill_senders,
@@ -180,7 +180,7 @@ where
     let mut sensitive_strings = SensitiveStrings::default();
     // Decrypt the relevant contents of the request and track any resulting sensitive strings
     // to be redacted
-    request.decrypt(&mut sensitive_strings, &state.decryption_key)?;
+    request.decrypt(&mut sensitive_strings, &state.decryption_keys)?;
 
     // NOTE(nick,fletcher): we need to create a owned client here because publisher has its own lifetime. Yeehaw.
     let nats_for_publisher = state.nats.clone();
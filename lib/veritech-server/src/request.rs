@@ -1,4 +1,4 @@
-use si_crypto::VeritechDecryptionKey;
+use si_crypto::VeritechDecryptionKeyRing;
 use si_pool_noodle::{
     ActionRunRequest, BeforeFunction, ManagementRequest, ResolverFunctionRequest,
     SchemaVariantDefinitionRequest, SensitiveStrings, ValidationRequest,
@@ -9,7 +9,7 @@ pub trait DecryptRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_keys: &VeritechDecryptionKeyRing,
     ) -> Result<(), VeritechValueDecryptError>;
 }
 
@@ -17,9 +17,9 @@ impl DecryptRequest for ResolverFunctionRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_keys: &VeritechDecryptionKeyRing,
     ) -> Result<(), VeritechValueDecryptError> {
-        decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
+        decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_keys)
     }
 }
 
@@ -27,9 +27,9 @@ impl DecryptRequest for ActionRunRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_keys: &VeritechDecryptionKeyRing,
     ) -> Result<(), VeritechValueDecryptError> {
-        decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
+        decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_keys)
     }
 }
 
@@ -37,9 +37,9 @@ impl DecryptRequest for ValidationRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_keys: &VeritechDecryptionKeyRing,
     ) -> Result<(), VeritechValueDecryptError> {
-        decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
+        decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_keys)
     }
 }
 
@@ -47,7 +47,7 @@ impl DecryptRequest for SchemaVariantDefinitionRequest {
     fn decrypt(
         &mut self,
         _sensitive_strings: &mut SensitiveStrings,
-        _decryption_key: &VeritechDecryptionKey,
+        _decryption_keys: &VeritechDecryptionKeyRing,
     ) -> Result<(), VeritechValueDecryptError> {
         // No before funcs defined!
         Ok(())
@@ -58,19 +58,19 @@ impl DecryptRequest for ManagementRequest {
     fn decrypt(
         &mut self,
         sensitive_strings: &mut SensitiveStrings,
-        decryption_key: &VeritechDecryptionKey,
+        decryption_keys: &VeritechDecryptionKeyRing,
     ) -> Result<(), VeritechValueDecryptError> {
-        decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_key)
+        decrypt_before_func_args(&mut self.before, sensitive_strings, decryption_keys)
     }
 }
 
 fn decrypt_before_func_args(
     before: &mut Vec<BeforeFunction>,
     sensitive_strings: &mut SensitiveStrings,
-    decryption_key: &VeritechDecryptionKey,
+    decryption_keys: &VeritechDecryptionKeyRing,
 ) -> Result<(), VeritechValueDecryptError> {
     for func in before {
-        decrypt_value_tree(&mut func.arg, sensitive_strings, decryption_key)?;
+        decrypt_value_tree(&mut func.arg, sensitive_strings, decryption_keys)?;
     }
 
     Ok(())
@@ -20,7 +20,7 @@ use naxum::{
     response::{IntoResponse, Response},
     MessageHead, ServiceBuilder, ServiceExt as _, TowerServiceExt as _,
 };
-use si_crypto::VeritechDecryptionKey;
+use si_crypto::VeritechDecryptionKeyRing;
 use si_data_nats::{async_nats, jetstream, NatsClient, Subscriber};
 use si_pool_noodle::{
     instance::cyclone::{LocalUdsInstance, LocalUdsInstanceSpec},
@@ -81,7 +81,8 @@ impl Server {
             instance_id: config.instance_id().into(),
         });
 
-        let decryption_key = VeritechDecryptionKey::from_config(config.crypto().clone()).await?;
+        let decryption_keys =
+            VeritechDecryptionKeyRing::from_config(config.crypto().clone()).await?;
 
         let kill_senders = Arc::new(Mutex::new(HashMap::new()));
 
@@ -140,7 +141,7 @@ impl Server {
                     metadata.clone(),
                     config.concurrency_limit(),
                     cyclone_pool,
-                    Arc::new(decryption_key),
+                    decryption_keys,
                     config.cyclone_client_execution_timeout(),
                     nats.clone(),
                     kill_senders.clone(),
@@ -185,7 +186,7 @@ impl Server {
         metadata: Arc<ServerMetadata>,
         concurrency_limit: usize,
         cyclone_pool: PoolNoodle<LocalUdsInstance, LocalUdsInstanceSpec>,
-        decryption_key: Arc<VeritechDecryptionKey>,
+        decryption_keys: VeritechDecryptionKeyRing,
         cyclone_client_execution_timeout: Duration,
         nats: NatsClient,
         kill_senders: Arc<Mutex<HashMap<ExecutionId, oneshot::Sender<()>>>>,
@@ -209,7 +210,7 @@ impl Server {
         let state = AppState::new(
             metadata,
             cyclone_pool,
-            decryption_key,
+            decryption_keys,
             cyclone_client_execution_timeout,
             nats,
             kill_senders,